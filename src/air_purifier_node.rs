@@ -0,0 +1,561 @@
+use std::collections::BTreeMap;
+
+use homie5::{
+    HOMIE_UNIT_PERCENT, Homie5DeviceProtocol, Homie5Message, HomieID, HomieValue, NodeRef,
+    PropertyRef,
+    device_description::{
+        HomieDeviceDescription, HomieNodeDescription, IntegerRange, NodeDescriptionBuilder,
+        PropertyDescriptionBuilder,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ParseError, ParseErrorKind, ParseOutcome, PropertyMetadata, SMARTHOME_CAP_AIR_PURIFIER,
+    SetCommandParser, SmarthomePublisher, SmarthomeType, property_metadata, readonly_properties,
+    reject_readonly, smarthome_node_config,
+};
+
+pub const AIR_PURIFIER_NODE_DEFAULT_ID: HomieID = HomieID::new_const("air-purifier");
+pub const AIR_PURIFIER_NODE_DEFAULT_NAME: &str = "Air purifier";
+pub const AIR_PURIFIER_NODE_STATE_PROP_ID: HomieID = HomieID::new_const("state");
+pub const AIR_PURIFIER_NODE_SPEED_PROP_ID: HomieID = HomieID::new_const("speed");
+pub const AIR_PURIFIER_NODE_AUTO_PROP_ID: HomieID = HomieID::new_const("auto");
+pub const AIR_PURIFIER_NODE_FILTER_PROP_ID: HomieID = HomieID::new_const("filter-life");
+pub const AIR_PURIFIER_NODE_PM25_PROP_ID: HomieID = HomieID::new_const("pm25");
+
+// ── Node (state) ────────────────────────────────────────────────────────────
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AirPurifierNodeState {
+    pub state: bool,
+    pub speed: Option<i64>,
+    pub auto: Option<bool>,
+    pub filter_life: Option<i64>,
+    pub pm25: Option<i64>,
+}
+
+#[derive(Debug)]
+pub struct AirPurifierNode {
+    pub publisher: AirPurifierNodePublisher,
+    pub state: AirPurifierNodeState,
+}
+
+impl AirPurifierNode {
+    /// Apply a parsed set command and return the `$target` and value publishes
+    /// for the change.
+    pub fn apply_set(&mut self, event: AirPurifierNodeSetEvents) -> Vec<homie5::client::Publish> {
+        match event {
+            AirPurifierNodeSetEvents::State(value) => {
+                self.state.state = value;
+                vec![
+                    self.publisher.state_target(value),
+                    self.publisher.state(value),
+                ]
+            }
+            AirPurifierNodeSetEvents::Speed(value) => {
+                self.state.speed = Some(value);
+                [
+                    self.publisher.speed_target(value),
+                    self.publisher.speed(value),
+                ]
+                .into_iter()
+                .flatten()
+                .collect()
+            }
+            AirPurifierNodeSetEvents::Auto(value) => {
+                self.state.auto = Some(value);
+                [
+                    self.publisher.auto_target(value),
+                    self.publisher.auto(value),
+                ]
+                .into_iter()
+                .flatten()
+                .collect()
+            }
+        }
+    }
+
+    /// Apply a value reported by the device and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Bool(value)) if p == &AIR_PURIFIER_NODE_STATE_PROP_ID => {
+                self.state.state = *value;
+                vec![self.publisher.state(*value)]
+            }
+            (p, HomieValue::Integer(value)) if p == &AIR_PURIFIER_NODE_SPEED_PROP_ID => {
+                self.state.speed = Some(*value);
+                self.publisher.speed(*value).into_iter().collect()
+            }
+            (p, HomieValue::Bool(value)) if p == &AIR_PURIFIER_NODE_AUTO_PROP_ID => {
+                self.state.auto = Some(*value);
+                self.publisher.auto(*value).into_iter().collect()
+            }
+            (p, HomieValue::Integer(value)) if p == &AIR_PURIFIER_NODE_FILTER_PROP_ID => {
+                self.state.filter_life = Some(*value);
+                self.publisher.filter_life(*value).into_iter().collect()
+            }
+            (p, HomieValue::Integer(value)) if p == &AIR_PURIFIER_NODE_PM25_PROP_ID => {
+                self.state.pm25 = Some(*value);
+                self.publisher.pm25(*value).into_iter().collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            Some(self.publisher.state(self.state.state)),
+            self.state
+                .speed
+                .and_then(|value| self.publisher.speed(value)),
+            self.state.auto.and_then(|value| self.publisher.auto(value)),
+            self.state
+                .filter_life
+                .and_then(|value| self.publisher.filter_life(value)),
+            self.state.pm25.and_then(|value| self.publisher.pm25(value)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+// ── Set events ──────────────────────────────────────────────────────────────
+
+#[derive(Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+pub enum AirPurifierNodeSetEvents {
+    State(bool),
+    Speed(i64),
+    Auto(bool),
+}
+
+// ── Config ──────────────────────────────────────────────────────────────────
+
+smarthome_node_config! {
+    #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+    #[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+    #[serde(default)]
+    pub struct AirPurifierNodeConfig for AirPurifierNodePublisher {
+        /// Expose a manual fan `speed` in percent.
+        pub speed: bool,
+        /// Expose the `auto` mode switch (speed follows the measured air quality).
+        pub auto: bool,
+        /// Expose the remaining filter life in percent.
+        pub filter: bool => AIR_PURIFIER_NODE_FILTER_PROP_ID, filter_life(i64), |_config| {
+            PropertyDescriptionBuilder::integer()
+                .name("Filter life")
+                .unit(HOMIE_UNIT_PERCENT)
+                .integer_range(IntegerRange {
+                    min: Some(0),
+                    max: Some(100),
+                    step: None,
+                })
+                .settable(false)
+                .retained(true)
+                .build()
+        },
+        /// Expose the PM2.5 reading of the built-in sensor.
+        pub pm25: bool => AIR_PURIFIER_NODE_PM25_PROP_ID, pm25(i64), |_config| {
+            PropertyDescriptionBuilder::integer()
+                .name("PM2.5")
+                .unit("µg/m³")
+                .integer_range(IntegerRange {
+                    min: Some(0),
+                    max: None,
+                    step: None,
+                })
+                .settable(false)
+                .retained(true)
+                .build()
+        },
+        /// Properties to publish as read-only; sets to them are rejected.
+        #[cfg_attr(
+            feature = "proptest",
+            proptest(strategy = "crate::arbitrary::homie_ids()")
+        )]
+        pub readonly: Vec<HomieID>,
+    }
+}
+
+impl Default for AirPurifierNodeConfig {
+    fn default() -> Self {
+        Self {
+            speed: true,
+            auto: true,
+            filter: true,
+            pm25: false,
+            readonly: Vec::new(),
+        }
+    }
+}
+
+// ── Builder ─────────────────────────────────────────────────────────────────
+
+pub struct AirPurifierNodeBuilder {
+    node_builder: NodeDescriptionBuilder,
+    config: AirPurifierNodeConfig,
+}
+
+impl Default for AirPurifierNodeBuilder {
+    fn default() -> Self {
+        Self::new(&AirPurifierNodeConfig::default())
+    }
+}
+
+impl AirPurifierNodeBuilder {
+    pub fn new(config: &AirPurifierNodeConfig) -> Self {
+        let db = Self::build_node(
+            NodeDescriptionBuilder::new().name(AIR_PURIFIER_NODE_DEFAULT_NAME),
+            config,
+        )
+        .r#type(SMARTHOME_CAP_AIR_PURIFIER);
+        let db = readonly_properties(db, &config.readonly);
+
+        Self {
+            node_builder: db,
+            config: config.clone(),
+        }
+    }
+
+    fn build_node(
+        db: NodeDescriptionBuilder,
+        config: &AirPurifierNodeConfig,
+    ) -> NodeDescriptionBuilder {
+        config.add_optional_properties(
+            db.add_property(
+                AIR_PURIFIER_NODE_STATE_PROP_ID,
+                PropertyDescriptionBuilder::boolean()
+                    .name("Air purifier state")
+                    .boolean_labels("off", "on")
+                    .settable(true)
+                    .retained(true)
+                    .build(),
+            )
+            .add_property_cond(AIR_PURIFIER_NODE_SPEED_PROP_ID, config.speed, || {
+                PropertyDescriptionBuilder::integer()
+                    .name("Fan speed")
+                    .unit(HOMIE_UNIT_PERCENT)
+                    .integer_range(IntegerRange {
+                        min: Some(0),
+                        max: Some(100),
+                        step: None,
+                    })
+                    .settable(true)
+                    .retained(true)
+                    .build()
+            })
+            .add_property_cond(AIR_PURIFIER_NODE_AUTO_PROP_ID, config.auto, || {
+                PropertyDescriptionBuilder::boolean()
+                    .name("Auto mode")
+                    .boolean_labels("manual", "auto")
+                    .settable(true)
+                    .retained(true)
+                    .build()
+            }),
+        )
+    }
+
+    pub fn name<S: Into<String>>(mut self, name: impl Into<Option<S>>) -> Self {
+        self.node_builder = self.node_builder.name(name);
+        self
+    }
+
+    /// Append a subtype suffix to the node type (`air-purifier-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::AirPurifier.with_subtype(subtype));
+        self
+    }
+
+    pub fn build(self) -> HomieNodeDescription {
+        self.node_builder.build()
+    }
+
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &AirPurifierNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
+    pub fn build_with_publisher(
+        self,
+        node_id: HomieID,
+        client: &Homie5DeviceProtocol,
+    ) -> (HomieNodeDescription, AirPurifierNodePublisher) {
+        (
+            self.node_builder.build(),
+            AirPurifierNodePublisher::new(
+                NodeRef::new(
+                    client.homie_domain().to_owned(),
+                    client.id().clone(),
+                    node_id,
+                ),
+                client.clone(),
+                self.config,
+            ),
+        )
+    }
+}
+
+// ── Publisher ────────────────────────────────────────────────────────────────
+
+#[derive(Debug)]
+pub struct AirPurifierNodePublisher {
+    client: Homie5DeviceProtocol,
+    node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
+    config: AirPurifierNodeConfig,
+    state_prop: HomieID,
+    speed_prop: HomieID,
+    auto_prop: HomieID,
+    filter_prop: HomieID,
+    pm25_prop: HomieID,
+}
+
+impl AirPurifierNodePublisher {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: AirPurifierNodeConfig) -> Self {
+        let metadata = property_metadata(&AirPurifierNodeBuilder::new(&config).build());
+        Self {
+            node,
+            metadata,
+            client,
+            config,
+            state_prop: AIR_PURIFIER_NODE_STATE_PROP_ID,
+            speed_prop: AIR_PURIFIER_NODE_SPEED_PROP_ID,
+            auto_prop: AIR_PURIFIER_NODE_AUTO_PROP_ID,
+            filter_prop: AIR_PURIFIER_NODE_FILTER_PROP_ID,
+            pm25_prop: AIR_PURIFIER_NODE_PM25_PROP_ID,
+        }
+    }
+
+    pub fn state(&self, value: bool) -> homie5::client::Publish {
+        self.client.publish_value(
+            self.node.node_id(),
+            &self.state_prop,
+            value.to_string(),
+            true,
+        )
+    }
+
+    pub fn state_target(&self, value: bool) -> homie5::client::Publish {
+        self.client.publish_target(
+            self.node.node_id(),
+            &self.state_prop,
+            value.to_string(),
+            true,
+        )
+    }
+
+    pub fn speed(&self, value: i64) -> Option<homie5::client::Publish> {
+        if !self.config.speed {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.speed_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+
+    pub fn speed_target(&self, value: i64) -> Option<homie5::client::Publish> {
+        if !self.config.speed {
+            return None;
+        }
+        Some(self.client.publish_target(
+            self.node.node_id(),
+            &self.speed_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+
+    pub fn auto(&self, value: bool) -> Option<homie5::client::Publish> {
+        if !self.config.auto {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.auto_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+
+    pub fn auto_target(&self, value: bool) -> Option<homie5::client::Publish> {
+        if !self.config.auto {
+            return None;
+        }
+        Some(self.client.publish_target(
+            self.node.node_id(),
+            &self.auto_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+}
+
+impl SmarthomePublisher for AirPurifierNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::AirPurifier
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![
+            self.state_prop.clone(),
+            self.speed_prop.clone(),
+            self.auto_prop.clone(),
+            self.filter_prop.clone(),
+            self.pm25_prop.clone(),
+        ]
+    }
+}
+
+impl SetCommandParser for AirPurifierNodePublisher {
+    type Event = AirPurifierNodeSetEvents;
+
+    fn parse_set(
+        &self,
+        property: &PropertyRef,
+        desc: &HomieDeviceDescription,
+        set_value: &str,
+    ) -> ParseOutcome<Self::Event> {
+        if let Some(outcome) = reject_readonly(&self.node, property, desc, set_value) {
+            return outcome;
+        }
+
+        let property_id = property.prop_id().to_string();
+
+        if property.match_with_node(&self.node, &self.state_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Bool(value)) => {
+                    ParseOutcome::Parsed(AirPurifierNodeSetEvents::State(value))
+                }
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else if property.match_with_node(&self.node, &self.speed_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Integer(value)) => {
+                    ParseOutcome::Parsed(AirPurifierNodeSetEvents::Speed(value))
+                }
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else if property.match_with_node(&self.node, &self.auto_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Bool(value)) => {
+                    ParseOutcome::Parsed(AirPurifierNodeSetEvents::Auto(value))
+                }
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else {
+            ParseOutcome::NoMatch
+        }
+    }
+
+    fn parse_set_event(
+        &self,
+        desc: &HomieDeviceDescription,
+        event: &Homie5Message,
+    ) -> ParseOutcome<Self::Event> {
+        match event {
+            Homie5Message::PropertySet {
+                property,
+                set_value,
+            } => self.parse_set(property, desc, set_value),
+            _ => ParseOutcome::Invalid(ParseError::new(
+                self.state_prop.to_string(),
+                "",
+                ParseErrorKind::UnexpectedMessageType,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use homie5::HomieDomain;
+
+    use super::*;
+    use crate::StandaloneSetParser;
+
+    #[test]
+    fn speed_and_auto_mode_parse() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let parser: StandaloneSetParser<_> = AirPurifierNodeBuilder::default()
+            .build_with_publisher(AIR_PURIFIER_NODE_DEFAULT_ID, &client)
+            .into();
+        let node = parser.publisher().node_ref().clone();
+        let speed = PropertyRef::from_node(node.clone(), AIR_PURIFIER_NODE_SPEED_PROP_ID);
+        let auto = PropertyRef::from_node(node, AIR_PURIFIER_NODE_AUTO_PROP_ID);
+
+        assert!(matches!(
+            parser.parse_set(&speed, "40"),
+            ParseOutcome::Parsed(AirPurifierNodeSetEvents::Speed(40))
+        ));
+        assert!(matches!(
+            parser.parse_set(&auto, "true"),
+            ParseOutcome::Parsed(AirPurifierNodeSetEvents::Auto(true))
+        ));
+        assert!(parser.into_publisher().pm25(12).is_none());
+    }
+}
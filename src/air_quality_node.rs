@@ -1,12 +1,18 @@
+use std::collections::BTreeMap;
+
 use homie5::{
-    Homie5DeviceProtocol, HomieID, NodeRef,
+    HOMIE_UNIT_DEGREE_CELSIUS, HOMIE_UNIT_PERCENT, Homie5DeviceProtocol, HomieID, HomieValue,
+    NodeRef,
     device_description::{
         HomieNodeDescription, IntegerRange, NodeDescriptionBuilder, PropertyDescriptionBuilder,
     },
 };
 use serde::{Deserialize, Serialize};
 
-use crate::SMARTHOME_CAP_AIR_QUALITY;
+use crate::{
+    PropertyMetadata, SMARTHOME_CAP_AIR_QUALITY, SmarthomePublisher, SmarthomeType,
+    property_metadata,
+};
 
 pub const AIR_QUALITY_NODE_DEFAULT_ID: HomieID = HomieID::new_const("air-quality");
 pub const AIR_QUALITY_NODE_DEFAULT_NAME: &str = "Air quality";
@@ -15,22 +21,95 @@ pub const AIR_QUALITY_NODE_VOC_PROP_ID: HomieID = HomieID::new_const("voc");
 pub const AIR_QUALITY_NODE_PM25_PROP_ID: HomieID = HomieID::new_const("pm25");
 pub const AIR_QUALITY_NODE_PM10_PROP_ID: HomieID = HomieID::new_const("pm10");
 pub const AIR_QUALITY_NODE_AQI_PROP_ID: HomieID = HomieID::new_const("aqi");
+pub const AIR_QUALITY_NODE_TEMP_PROP_ID: HomieID = HomieID::new_const("temperature");
+pub const AIR_QUALITY_NODE_HUM_PROP_ID: HomieID = HomieID::new_const("humidity");
 
 // ── Node (state) ────────────────────────────────────────────────────────────
 
-#[derive(Debug)]
-pub struct AirQualityNode {
-    pub publisher: AirQualityNodePublisher,
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AirQualityNodeState {
     pub co2: Option<i64>,
     pub voc: Option<i64>,
     pub pm25: Option<i64>,
     pub pm10: Option<i64>,
     pub aqi: Option<i64>,
+    pub temperature: Option<f64>,
+    pub humidity: Option<i64>,
+}
+
+#[derive(Debug)]
+pub struct AirQualityNode {
+    pub publisher: AirQualityNodePublisher,
+    pub state: AirQualityNodeState,
+}
+
+impl AirQualityNode {
+    /// Apply a value reported by the device and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Integer(value)) if p == &AIR_QUALITY_NODE_CO2_PROP_ID => {
+                self.state.co2 = Some(*value);
+                vec![self.publisher.co2(*value)]
+            }
+            (p, HomieValue::Integer(value)) if p == &AIR_QUALITY_NODE_VOC_PROP_ID => {
+                self.state.voc = Some(*value);
+                vec![self.publisher.voc(*value)]
+            }
+            (p, HomieValue::Integer(value)) if p == &AIR_QUALITY_NODE_PM25_PROP_ID => {
+                self.state.pm25 = Some(*value);
+                vec![self.publisher.pm25(*value)]
+            }
+            (p, HomieValue::Integer(value)) if p == &AIR_QUALITY_NODE_PM10_PROP_ID => {
+                self.state.pm10 = Some(*value);
+                vec![self.publisher.pm10(*value)]
+            }
+            (p, HomieValue::Integer(value)) if p == &AIR_QUALITY_NODE_AQI_PROP_ID => {
+                self.state.aqi = Some(*value);
+                vec![self.publisher.aqi(*value)]
+            }
+            (p, HomieValue::Float(value)) if p == &AIR_QUALITY_NODE_TEMP_PROP_ID => {
+                self.state.temperature = Some(*value);
+                vec![self.publisher.temperature(*value)]
+            }
+            (p, HomieValue::Integer(value)) if p == &AIR_QUALITY_NODE_HUM_PROP_ID => {
+                self.state.humidity = Some(*value);
+                vec![self.publisher.humidity(*value)]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            self.state.co2.map(|value| self.publisher.co2(value)),
+            self.state.voc.map(|value| self.publisher.voc(value)),
+            self.state.pm25.map(|value| self.publisher.pm25(value)),
+            self.state.pm10.map(|value| self.publisher.pm10(value)),
+            self.state.aqi.map(|value| self.publisher.aqi(value)),
+            self.state
+                .temperature
+                .map(|value| self.publisher.temperature(value)),
+            self.state
+                .humidity
+                .map(|value| self.publisher.humidity(value)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
 }
 
 // ── Config ──────────────────────────────────────────────────────────────────
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 #[serde(default)]
 pub struct AirQualityNodeConfig {
     pub co2: bool,
@@ -38,6 +117,11 @@ pub struct AirQualityNodeConfig {
     pub pm25: bool,
     pub pm10: bool,
     pub aqi: bool,
+    /// Expose the monitor's built-in temperature reading.
+    pub temperature: bool,
+    /// Expose the monitor's built-in relative humidity reading.
+    pub humidity: bool,
+    pub temp_unit: String,
 }
 
 impl Default for AirQualityNodeConfig {
@@ -48,6 +132,9 @@ impl Default for AirQualityNodeConfig {
             pm25: false,
             pm10: false,
             aqi: false,
+            temperature: false,
+            humidity: false,
+            temp_unit: HOMIE_UNIT_DEGREE_CELSIUS.to_owned(),
         }
     }
 }
@@ -56,6 +143,7 @@ impl Default for AirQualityNodeConfig {
 
 pub struct AirQualityNodeBuilder {
     node_builder: NodeDescriptionBuilder,
+    config: AirQualityNodeConfig,
 }
 
 impl AirQualityNodeBuilder {
@@ -66,7 +154,10 @@ impl AirQualityNodeBuilder {
         )
         .r#type(SMARTHOME_CAP_AIR_QUALITY);
 
-        Self { node_builder: db }
+        Self {
+            node_builder: db,
+            config: config.clone(),
+        }
     }
 
     fn build_node(
@@ -137,6 +228,27 @@ impl AirQualityNodeBuilder {
                 .retained(true)
                 .build()
         })
+        .add_property_cond(AIR_QUALITY_NODE_TEMP_PROP_ID, config.temperature, || {
+            PropertyDescriptionBuilder::float()
+                .name("Current temperature")
+                .unit(config.temp_unit.to_owned())
+                .settable(false)
+                .retained(true)
+                .build()
+        })
+        .add_property_cond(AIR_QUALITY_NODE_HUM_PROP_ID, config.humidity, || {
+            PropertyDescriptionBuilder::integer()
+                .name("Current humidity")
+                .unit(HOMIE_UNIT_PERCENT)
+                .integer_range(IntegerRange {
+                    min: Some(0),
+                    max: Some(100),
+                    step: None,
+                })
+                .settable(false)
+                .retained(true)
+                .build()
+        })
     }
 
     pub fn name<S: Into<String>>(mut self, name: impl Into<Option<S>>) -> Self {
@@ -144,10 +256,23 @@ impl AirQualityNodeBuilder {
         self
     }
 
+    /// Append a subtype suffix to the node type (`air-quality-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::AirQuality.with_subtype(subtype));
+        self
+    }
+
     pub fn build(self) -> HomieNodeDescription {
         self.node_builder.build()
     }
 
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &AirQualityNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
     pub fn build_with_publisher(
         self,
         node_id: HomieID,
@@ -162,6 +287,7 @@ impl AirQualityNodeBuilder {
                     node_id,
                 ),
                 client.clone(),
+                &self.config,
             ),
         )
     }
@@ -173,23 +299,30 @@ impl AirQualityNodeBuilder {
 pub struct AirQualityNodePublisher {
     client: Homie5DeviceProtocol,
     node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
     co2_prop: HomieID,
     voc_prop: HomieID,
     pm25_prop: HomieID,
     pm10_prop: HomieID,
     aqi_prop: HomieID,
+    temp_prop: HomieID,
+    hum_prop: HomieID,
 }
 
 impl AirQualityNodePublisher {
-    pub fn new(node: NodeRef, client: Homie5DeviceProtocol) -> Self {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: &AirQualityNodeConfig) -> Self {
+        let metadata = property_metadata(&AirQualityNodeBuilder::new(config).build());
         Self {
             node,
+            metadata,
             client,
             co2_prop: AIR_QUALITY_NODE_CO2_PROP_ID,
             voc_prop: AIR_QUALITY_NODE_VOC_PROP_ID,
             pm25_prop: AIR_QUALITY_NODE_PM25_PROP_ID,
             pm10_prop: AIR_QUALITY_NODE_PM10_PROP_ID,
             aqi_prop: AIR_QUALITY_NODE_AQI_PROP_ID,
+            temp_prop: AIR_QUALITY_NODE_TEMP_PROP_ID,
+            hum_prop: AIR_QUALITY_NODE_HUM_PROP_ID,
         }
     }
 
@@ -225,4 +358,44 @@ impl AirQualityNodePublisher {
         self.client
             .publish_value(self.node.node_id(), &self.aqi_prop, value.to_string(), true)
     }
+
+    pub fn temperature(&self, value: f64) -> homie5::client::Publish {
+        self.client.publish_value(
+            self.node.node_id(),
+            &self.temp_prop,
+            value.to_string(),
+            true,
+        )
+    }
+
+    pub fn humidity(&self, value: i64) -> homie5::client::Publish {
+        self.client
+            .publish_value(self.node.node_id(), &self.hum_prop, value.to_string(), true)
+    }
+}
+
+impl SmarthomePublisher for AirQualityNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::AirQuality
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![
+            self.co2_prop.clone(),
+            self.voc_prop.clone(),
+            self.pm25_prop.clone(),
+            self.pm10_prop.clone(),
+            self.aqi_prop.clone(),
+            self.temp_prop.clone(),
+            self.hum_prop.clone(),
+        ]
+    }
 }
@@ -1,5 +1,8 @@
+use std::collections::BTreeMap;
+
 use homie5::{
-    Homie5DeviceProtocol, Homie5Message, HomieID, HomieValue, NodeRef, PropertyRef,
+    HOMIE_UNIT_PERCENT, Homie5DeviceProtocol, Homie5Message, HomieID, HomieValue, NodeRef,
+    PropertyRef,
     device_description::{
         HomieDeviceDescription, HomieNodeDescription, IntegerRange, NodeDescriptionBuilder,
         PropertyDescriptionBuilder,
@@ -7,41 +10,139 @@ use homie5::{
 };
 use serde::{Deserialize, Serialize};
 
-use crate::{ParseError, ParseErrorKind, ParseOutcome, SMARTHOME_CAP_ALARM, SetCommandParser};
+use crate::{
+    ConfigError, ParseError, ParseErrorKind, ParseOutcome, PropertyMetadata, SMARTHOME_CAP_ALARM,
+    SetCommandParser, SmarthomePublisher, SmarthomeType, property_metadata, readonly_properties,
+    reject_readonly, validate_enum_format,
+};
 
 pub const ALARM_NODE_DEFAULT_ID: HomieID = HomieID::new_const("alarm");
 pub const ALARM_NODE_DEFAULT_NAME: &str = "Alarm";
 pub const ALARM_NODE_STATE_PROP_ID: HomieID = HomieID::new_const("state");
 pub const ALARM_NODE_SOUND_PROP_ID: HomieID = HomieID::new_const("sound");
 pub const ALARM_NODE_DURATION_PROP_ID: HomieID = HomieID::new_const("duration");
+pub const ALARM_NODE_VOLUME_PROP_ID: HomieID = HomieID::new_const("volume");
 
 // ── Node (state) ────────────────────────────────────────────────────────────
 
-#[derive(Debug)]
-pub struct AlarmNode {
-    pub publisher: AlarmNodePublisher,
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AlarmNodeState {
     pub state: bool,
     pub sound: Option<String>,
     pub duration: Option<i64>,
+    pub volume: Option<i64>,
+}
+
+#[derive(Debug)]
+pub struct AlarmNode {
+    pub publisher: AlarmNodePublisher,
+    pub state: AlarmNodeState,
+}
+
+impl AlarmNode {
+    /// Apply a parsed set command and return the value publish for the change.
+    pub fn apply_set(&mut self, event: AlarmNodeSetEvents) -> Vec<homie5::client::Publish> {
+        match event {
+            AlarmNodeSetEvents::State(value) => {
+                self.state.state = value;
+                vec![self.publisher.state(value)]
+            }
+            AlarmNodeSetEvents::Sound(value) => {
+                let publish = self.publisher.sound(&value);
+                self.state.sound = Some(value);
+                vec![publish]
+            }
+            AlarmNodeSetEvents::Duration(value) => {
+                self.state.duration = Some(value);
+                vec![self.publisher.duration(value)]
+            }
+            AlarmNodeSetEvents::Volume(value) => {
+                self.state.volume = Some(value);
+                vec![self.publisher.volume(value)]
+            }
+        }
+    }
+
+    /// Apply a value reported by the device and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Bool(value)) if p == &ALARM_NODE_STATE_PROP_ID => {
+                self.state.state = *value;
+                vec![self.publisher.state(*value)]
+            }
+            (p, HomieValue::Enum(value)) if p == &ALARM_NODE_SOUND_PROP_ID => {
+                self.state.sound = Some(value.clone());
+                vec![self.publisher.sound(value)]
+            }
+            (p, HomieValue::Integer(value)) if p == &ALARM_NODE_DURATION_PROP_ID => {
+                self.state.duration = Some(*value);
+                vec![self.publisher.duration(*value)]
+            }
+            (p, HomieValue::Integer(value)) if p == &ALARM_NODE_VOLUME_PROP_ID => {
+                self.state.volume = Some(*value);
+                vec![self.publisher.volume(*value)]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            Some(self.publisher.state(self.state.state)),
+            self.state
+                .sound
+                .as_deref()
+                .map(|value| self.publisher.sound(value)),
+            self.state
+                .duration
+                .map(|value| self.publisher.duration(value)),
+            self.state.volume.map(|value| self.publisher.volume(value)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
 }
 
 // ── Set events ──────────────────────────────────────────────────────────────
 
 #[derive(Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 pub enum AlarmNodeSetEvents {
     State(bool),
     Sound(String),
     Duration(i64),
+    Volume(i64),
 }
 
 // ── Config ──────────────────────────────────────────────────────────────────
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 #[serde(default)]
 pub struct AlarmNodeConfig {
     pub sound: bool,
+    #[cfg_attr(
+        feature = "proptest",
+        proptest(strategy = "crate::arbitrary::enum_format()")
+    )]
     pub sounds: Vec<String>,
     pub duration: bool,
+    /// Expose a settable siren `volume` in percent.
+    pub volume: bool,
+    /// Properties to publish as read-only; sets to them are rejected.
+    #[cfg_attr(
+        feature = "proptest",
+        proptest(strategy = "crate::arbitrary::homie_ids()")
+    )]
+    pub readonly: Vec<HomieID>,
 }
 
 impl Default for AlarmNodeConfig {
@@ -50,14 +151,27 @@ impl Default for AlarmNodeConfig {
             sound: false,
             sounds: vec!["default".to_owned()],
             duration: false,
+            volume: false,
+            readonly: Vec::new(),
         }
     }
 }
 
+impl AlarmNodeConfig {
+    /// Check the config-provided enum values before building the node.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.sound {
+            validate_enum_format(&ALARM_NODE_SOUND_PROP_ID, &self.sounds)?;
+        }
+        Ok(())
+    }
+}
+
 // ── Builder ─────────────────────────────────────────────────────────────────
 
 pub struct AlarmNodeBuilder {
     node_builder: NodeDescriptionBuilder,
+    config: AlarmNodeConfig,
 }
 
 impl AlarmNodeBuilder {
@@ -67,8 +181,12 @@ impl AlarmNodeBuilder {
             config,
         )
         .r#type(SMARTHOME_CAP_ALARM);
+        let db = readonly_properties(db, &config.readonly);
 
-        Self { node_builder: db }
+        Self {
+            node_builder: db,
+            config: config.clone(),
+        }
     }
 
     fn build_node(db: NodeDescriptionBuilder, config: &AlarmNodeConfig) -> NodeDescriptionBuilder {
@@ -101,6 +219,19 @@ impl AlarmNodeBuilder {
                 .retained(true)
                 .build()
         })
+        .add_property_cond(ALARM_NODE_VOLUME_PROP_ID, config.volume, || {
+            PropertyDescriptionBuilder::integer()
+                .name("Alarm volume")
+                .unit(HOMIE_UNIT_PERCENT)
+                .integer_range(IntegerRange {
+                    min: Some(0),
+                    max: Some(100),
+                    step: None,
+                })
+                .settable(true)
+                .retained(true)
+                .build()
+        })
     }
 
     pub fn name<S: Into<String>>(mut self, name: impl Into<Option<S>>) -> Self {
@@ -108,10 +239,23 @@ impl AlarmNodeBuilder {
         self
     }
 
+    /// Append a subtype suffix to the node type (`alarm-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::Alarm.with_subtype(subtype));
+        self
+    }
+
     pub fn build(self) -> HomieNodeDescription {
         self.node_builder.build()
     }
 
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &AlarmNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
     pub fn build_with_publisher(
         self,
         node_id: HomieID,
@@ -126,6 +270,7 @@ impl AlarmNodeBuilder {
                     node_id,
                 ),
                 client.clone(),
+                &self.config,
             ),
         )
     }
@@ -137,19 +282,24 @@ impl AlarmNodeBuilder {
 pub struct AlarmNodePublisher {
     client: Homie5DeviceProtocol,
     node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
     state_prop: HomieID,
     sound_prop: HomieID,
     duration_prop: HomieID,
+    volume_prop: HomieID,
 }
 
 impl AlarmNodePublisher {
-    pub fn new(node: NodeRef, client: Homie5DeviceProtocol) -> Self {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: &AlarmNodeConfig) -> Self {
+        let metadata = property_metadata(&AlarmNodeBuilder::new(config).build());
         Self {
             node,
+            metadata,
             client,
             state_prop: ALARM_NODE_STATE_PROP_ID,
             sound_prop: ALARM_NODE_SOUND_PROP_ID,
             duration_prop: ALARM_NODE_DURATION_PROP_ID,
+            volume_prop: ALARM_NODE_VOLUME_PROP_ID,
         }
     }
 
@@ -162,11 +312,25 @@ impl AlarmNodePublisher {
         )
     }
 
+    pub fn state_target(&self, value: bool) -> homie5::client::Publish {
+        self.client.publish_target(
+            self.node.node_id(),
+            &self.state_prop,
+            value.to_string(),
+            true,
+        )
+    }
+
     pub fn sound(&self, value: &str) -> homie5::client::Publish {
         self.client
             .publish_value(self.node.node_id(), &self.sound_prop, value, true)
     }
 
+    pub fn sound_target(&self, value: &str) -> homie5::client::Publish {
+        self.client
+            .publish_target(self.node.node_id(), &self.sound_prop, value, true)
+    }
+
     pub fn duration(&self, value: i64) -> homie5::client::Publish {
         self.client.publish_value(
             self.node.node_id(),
@@ -175,6 +339,56 @@ impl AlarmNodePublisher {
             true,
         )
     }
+
+    pub fn duration_target(&self, value: i64) -> homie5::client::Publish {
+        self.client.publish_target(
+            self.node.node_id(),
+            &self.duration_prop,
+            value.to_string(),
+            true,
+        )
+    }
+
+    pub fn volume(&self, value: i64) -> homie5::client::Publish {
+        self.client.publish_value(
+            self.node.node_id(),
+            &self.volume_prop,
+            value.to_string(),
+            true,
+        )
+    }
+
+    pub fn volume_target(&self, value: i64) -> homie5::client::Publish {
+        self.client.publish_target(
+            self.node.node_id(),
+            &self.volume_prop,
+            value.to_string(),
+            true,
+        )
+    }
+}
+
+impl SmarthomePublisher for AlarmNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::Alarm
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![
+            self.state_prop.clone(),
+            self.sound_prop.clone(),
+            self.duration_prop.clone(),
+            self.volume_prop.clone(),
+        ]
+    }
 }
 
 impl SetCommandParser for AlarmNodePublisher {
@@ -186,6 +400,10 @@ impl SetCommandParser for AlarmNodePublisher {
         desc: &HomieDeviceDescription,
         set_value: &str,
     ) -> ParseOutcome<Self::Event> {
+        if let Some(outcome) = reject_readonly(&self.node, property, desc, set_value) {
+            return outcome;
+        }
+
         let property_id = property.prop_id().to_string();
 
         if property.match_with_node(&self.node, &self.state_prop) {
@@ -251,6 +469,27 @@ impl SetCommandParser for AlarmNodePublisher {
                     ParseErrorKind::InvalidHomieValue,
                 )),
             }
+        } else if property.match_with_node(&self.node, &self.volume_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Integer(value)) => {
+                    ParseOutcome::Parsed(AlarmNodeSetEvents::Volume(value))
+                }
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property.prop_id().to_string(),
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
         } else {
             ParseOutcome::NoMatch
         }
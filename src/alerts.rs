@@ -9,6 +9,7 @@
 
 use std::{fmt, str::FromStr};
 
+use homie5::HomieID;
 use serde::{Deserialize, Serialize};
 
 // ── Alert ID constants ──────────────────────────────────────────────────────
@@ -67,6 +68,11 @@ impl SmarthomeAlert {
         }
     }
 
+    /// Return the alert ID as a `HomieID` for use with `$alert` topics.
+    pub const fn homie_id(&self) -> HomieID {
+        HomieID::new_const(self.as_str())
+    }
+
     /// Try to parse an alert ID string into a well-known variant.
     /// Returns `None` for unrecognised (custom) alert IDs.
     pub fn from_id(id: &str) -> Option<Self> {
@@ -0,0 +1,389 @@
+//! Property-based testing support, enabled by the `proptest` crate feature.
+//!
+//! With the feature enabled, node configs, actions and set events implement
+//! [`proptest::arbitrary::Arbitrary`], so `any::<SwitchNodeConfig>()` or
+//! `any::<SmarthomePropertyConfig>()` generate values for fuzzing builders and
+//! parsers. The strategies below cover the homie5, chrono and serde_json types
+//! those structs contain and only generate values the Homie convention
+//! accepts: valid ids, in-range colors, unique enum values and so on.
+//!
+//! [`SmarthomePropertyConfig`]: crate::SmarthomePropertyConfig
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, TimeZone, Utc};
+use homie5::{
+    HomieColorValue, HomieID,
+    device_description::{BooleanFormat, ColorFormat, FloatRange, IntegerRange},
+};
+use proptest::{collection, option, prelude::*, sample, strategy::Union};
+
+use crate::SmarthomePropertyConfig;
+
+const ID_PATTERN: &str = "[a-z0-9]{1,8}(-[a-z0-9]{1,8}){0,2}";
+
+/// A valid Homie id, e.g. `living-room`.
+pub fn homie_id() -> impl Strategy<Value = HomieID> {
+    ID_PATTERN.prop_map(|id| HomieID::try_from(id).expect("pattern yields valid ids"))
+}
+
+/// Up to three Homie ids, e.g. for the `readonly` list of a config.
+pub fn homie_ids() -> impl Strategy<Value = Vec<HomieID>> {
+    collection::vec(homie_id(), 0..4)
+}
+
+/// A single enum value, usable as a property format entry.
+pub fn enum_value() -> impl Strategy<Value = String> {
+    ID_PATTERN.prop_map(String::from)
+}
+
+/// Up to five distinct enum values, e.g. the `fan_speeds` of a config.
+pub fn enum_values() -> impl Strategy<Value = Vec<String>> {
+    collection::btree_set(enum_value(), 0..6).prop_map(Vec::from_iter)
+}
+
+/// Up to five enum values as a config file may list them, possibly repeated.
+/// Unlike [`enum_values`] these are not valid formats, so they exercise
+/// [`validate_config`](crate::node_factory::validate_config).
+pub fn raw_enum_values() -> impl Strategy<Value = Vec<String>> {
+    collection::vec(prop_oneof![Just("on".to_owned()), enum_value()], 0..6)
+}
+
+/// One to five distinct enum values, the format of an enum property.
+pub fn enum_format() -> impl Strategy<Value = Vec<String>> {
+    collection::btree_set(enum_value(), 1..6).prop_map(Vec::from_iter)
+}
+
+/// One to five distinct values of `T`, e.g. the supported modes of a config.
+pub fn distinct<T: Arbitrary + PartialEq>() -> impl Strategy<Value = Vec<T>> {
+    collection::vec(any::<T>(), 1..6).prop_map(|values| {
+        values.into_iter().fold(Vec::new(), |mut distinct, value| {
+            if !distinct.contains(&value) {
+                distinct.push(value);
+            }
+            distinct
+        })
+    })
+}
+
+/// An integer range with `min <= max` and a positive step.
+pub fn integer_range() -> impl Strategy<Value = IntegerRange> {
+    (
+        option::of(-1000i64..1000),
+        option::of(0i64..1000),
+        option::of(1i64..100),
+    )
+        .prop_map(|(min, span, step)| IntegerRange {
+            min,
+            max: span.map(|span| min.unwrap_or(0) + span),
+            step,
+        })
+}
+
+/// A float range with `min <= max` and a positive step.
+pub fn float_range() -> impl Strategy<Value = FloatRange> {
+    (
+        option::of(-1000.0f64..1000.0),
+        option::of(0.0f64..1000.0),
+        option::of(0.01f64..100.0),
+    )
+        .prop_map(|(min, span, step)| FloatRange {
+            min,
+            max: span.map(|span| min.unwrap_or(0.0) + span),
+            step,
+        })
+}
+
+/// Two distinct, non-empty boolean labels.
+pub fn boolean_format() -> impl Strategy<Value = BooleanFormat> {
+    collection::btree_set("[a-z]{1,8}", 2).prop_map(|labels| {
+        let mut labels = labels.into_iter();
+        BooleanFormat {
+            false_val: labels.next().unwrap(),
+            true_val: labels.next().unwrap(),
+        }
+    })
+}
+
+/// A non-empty set of color formats.
+pub fn color_formats() -> impl Strategy<Value = Vec<ColorFormat>> {
+    sample::subsequence(
+        vec![ColorFormat::Rgb, ColorFormat::Hsv, ColorFormat::Xyz],
+        1..=3,
+    )
+}
+
+/// A color value within the bounds of its format.
+pub fn color_value() -> impl Strategy<Value = HomieColorValue> {
+    prop_oneof![
+        (0i64..=255, 0i64..=255, 0i64..=255).prop_map(|(r, g, b)| HomieColorValue::RGB(r, g, b)),
+        (0i64..=360, 0i64..=100, 0i64..=100).prop_map(|(h, s, v)| HomieColorValue::HSV(h, s, v)),
+        (0.0f64..=1.0, 0.0f64..=1.0).prop_map(|(x, y)| {
+            let y = y * (1.0 - x);
+            HomieColorValue::XYZ(x, y, 1.0 - x - y)
+        }),
+    ]
+}
+
+/// Up to eight color values, e.g. the segments of a LED strip.
+pub fn color_values() -> impl Strategy<Value = Vec<HomieColorValue>> {
+    collection::vec(color_value(), 0..8)
+}
+
+/// A timestamp with whole seconds between 1970 and 2100.
+pub fn date_time() -> impl Strategy<Value = DateTime<Utc>> {
+    (0i64..4_102_444_800).prop_map(|secs| Utc.timestamp_opt(secs, 0).unwrap())
+}
+
+/// A duration in seconds of at most a week, as used for hold and window
+/// settings.
+pub fn seconds() -> impl Strategy<Value = i64> {
+    0i64..=7 * 24 * 3600
+}
+
+/// An optional number of decimals for float formatting.
+pub fn precision() -> impl Strategy<Value = Option<usize>> {
+    option::of(0usize..=6)
+}
+
+/// A JSON object or array, the only payloads the Homie `json` datatype
+/// accepts.
+pub fn json_value() -> impl Strategy<Value = serde_json::Value> {
+    let leaf = prop_oneof![
+        any::<bool>().prop_map(serde_json::Value::from),
+        any::<i64>().prop_map(serde_json::Value::from),
+        "[a-z ]{0,12}".prop_map(serde_json::Value::from),
+    ];
+    prop_oneof![
+        collection::vec(leaf.clone(), 0..4).prop_map(serde_json::Value::from),
+        collection::btree_map("[a-z]{1,8}", leaf, 0..4).prop_map(|map: BTreeMap<_, _>| {
+            serde_json::Value::Object(map.into_iter().collect())
+        }),
+    ]
+}
+
+/// One to five scenes with distinct ids.
+#[cfg(feature = "scene")]
+pub fn scenes() -> impl Strategy<Value = Vec<crate::scene_node::SceneNodeScene>> {
+    collection::btree_set(enum_value(), 1..6).prop_flat_map(|ids| {
+        ids.into_iter()
+            .map(|id| {
+                option::of("[A-Za-z ]{1,12}").prop_map(move |label| {
+                    crate::scene_node::SceneNodeScene {
+                        id: id.clone(),
+                        label,
+                    }
+                })
+            })
+            .collect::<Vec<_>>()
+    })
+}
+
+// `SmarthomePropertyConfig` has one variant per node type; a derived strategy
+// nests them so deeply that generating values overflows the test thread's
+// stack, so the variants are combined in a flat union of boxed strategies.
+// Unit variants follow after the `;`.
+macro_rules! property_config_strategy {
+    (
+        $($feature:literal => $variant:ident($module:ident::$config:ident),)*
+        ;
+        $($unit_feature:literal => $unit:ident,)*
+    ) => {
+        impl Arbitrary for SmarthomePropertyConfig {
+            type Parameters = ();
+            type Strategy = BoxedStrategy<Self>;
+
+            fn arbitrary_with((): ()) -> Self::Strategy {
+                Union::new(vec![$(
+                    #[cfg(feature = $feature)]
+                    any::<crate::$module::$config>()
+                        .prop_map(SmarthomePropertyConfig::$variant)
+                        .boxed(),
+                )* $(
+                    #[cfg(feature = $unit_feature)]
+                    Just(SmarthomePropertyConfig::$unit).boxed(),
+                )*])
+                .boxed()
+            }
+        }
+    };
+}
+
+property_config_strategy! {
+    "air-purifier" => AirPurifier(air_purifier_node::AirPurifierNodeConfig),
+    "air-quality" => AirQuality(air_quality_node::AirQualityNodeConfig),
+    "alarm" => Alarm(alarm_node::AlarmNodeConfig),
+    "awning" => Awning(awning_node::AwningNodeConfig),
+    "battery" => Battery(battery_node::BatteryNodeConfig),
+    "button" => Button(button_node::ButtonNodeConfig),
+    "camera" => Camera(camera_node::CameraNodeConfig),
+    "climate" => Climate(climate_node::ClimateNodeConfig),
+    "co" => Co(co_node::CoNodeConfig),
+    "color" => Color(color_node::ColorNodeConfig),
+    "contact" => Contact(contact_node::ContactNodeConfig),
+    "curtain" => Curtain(curtain_node::CurtainNodeConfig),
+    "daylight" => Daylight(daylight_node::DaylightNodeConfig),
+    "dehumidifier" => Dehumidifier(dehumidifier_node::DehumidifierNodeConfig),
+    "door" => Door(door_node::DoorNodeConfig),
+    "doorbell" => Doorbell(doorbell_node::DoorbellNodeConfig),
+    "energy-dashboard" => EnergyDashboard(energy_dashboard_node::EnergyDashboardNodeConfig),
+    "ev-charger" => EvCharger(ev_charger_node::EvChargerNodeConfig),
+    "fan" => Fan(fan_node::FanNodeConfig),
+    "garage-door" => GarageDoor(garage_door_node::GarageDoorNodeConfig),
+    "gas" => Gas(gas_node::GasNodeConfig),
+    "generic-actuator" => GenericActuator(generic_actuator_node::GenericActuatorNodeConfig),
+    "heating-zone" => HeatingZone(heating_zone_node::HeatingZoneNodeConfig),
+    "humidifier" => Humidifier(humidifier_node::HumidifierNodeConfig),
+    "irrigation" => Irrigation(irrigation_node::IrrigationNodeConfig),
+    "json-data" => JsonData(json_data_node::JsonDataNodeConfig),
+    "led-strip" => LedStrip(led_strip_node::LedStripNodeConfig),
+    "level" => Level(level_node::LevelNodeConfig),
+    "link" => Link(link_node::LinkNodeConfig),
+    "lock" => Lock(lock_node::LockNodeConfig),
+    "maintenance" => Maintenance(maintenance_node::MaintenanceNodeConfig),
+    "media-info" => MediaInfo(media_info_node::MediaInfoNodeConfig),
+    "mediaplayer" => Mediaplayer(mediaplayer_node::MediaplayerNodeConfig),
+    "motion" => Motion(motion_node::MotionNodeConfig),
+    "button" => MultiButton(button_node::MultiButtonNodeConfig),
+    "switch" => MultiRelay(switch_node::MultiRelayNodeConfig),
+    "numeric-sensor" => Numeric(numeric_sensor_node::NumericSensorNodeConfig),
+    "orientation" => Orientation(orientation_node::OrientationNodeConfig),
+    "powermeter" => Powermeter(powermeter_node::PowermeterNodeConfig),
+    "scene" => Scene(scene_node::SceneNodeConfig),
+    "shutter" => Shutter(shutter_node::ShutterNodeConfig),
+    "smoke" => Smoke(smoke_node::SmokeNodeConfig),
+    "string-sensor" => StringSensor(string_sensor_node::StringSensorNodeConfig),
+    "switch" => Switch(switch_node::SwitchNodeConfig),
+    "thermostat" => Thermostat(thermostat_node::ThermostatNodeConfig),
+    "tilt" => Tilt(tilt_node::TiltNodeConfig),
+    "timer" => Timer(timer_node::TimerNodeConfig),
+    "vacuum" => Vacuum(vacuum_node::VacuumNodeConfig),
+    "valve" => Valve(valve_node::ValveNodeConfig),
+    "ventilation" => Ventilation(ventilation_node::VentilationNodeConfig),
+    "vibration" => Vibration(vibration_node::VibrationNodeConfig),
+    "volume" => Volume(volume_node::VolumeNodeConfig),
+    "water-sensor" => WaterSensor(water_sensor_node::WaterSensorNodeConfig),
+    "weather" => Weather(weather_node::WeatherNodeConfig),
+    ;
+    "illuminance" => Illuminance,
+    "text" => Text,
+}
+
+#[cfg(test)]
+mod tests {
+    use homie5::{Homie5DeviceProtocol, HomieDomain};
+
+    use super::*;
+    use crate::{
+        SmarthomePublisher,
+        node_factory::{build_node, node_property_ids},
+        property_metadata,
+    };
+
+    fn client() -> Homie5DeviceProtocol {
+        Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default).0
+    }
+
+    proptest! {
+        #[test]
+        fn any_config_builds_a_consistent_node(config in any::<SmarthomePropertyConfig>()) {
+            let (desc, publisher) = build_node(&config, HomieID::new_const("node"), &client())
+                .expect("generated configs must be valid");
+            let mut ids: Vec<_> = desc.properties.keys().cloned().collect();
+            let known = publisher.property_ids();
+            prop_assert_eq!(publisher.property_metadata(), &property_metadata(&desc));
+            for id in &ids {
+                prop_assert!(known.contains(id), "{id} missing from publisher");
+            }
+            let mut expected = node_property_ids(&config).expect("generated configs must be valid");
+            ids.sort();
+            expected.sort();
+            prop_assert_eq!(ids, expected);
+        }
+    }
+
+    #[cfg(feature = "json-data")]
+    proptest! {
+        #[test]
+        fn json_data_sets_round_trip(value in json_value()) {
+            use homie5::PropertyRef;
+
+            use crate::{ParseOutcome, StandaloneSetParser, json_data_node::*};
+
+            let config = JsonDataNodeConfig {
+                settable: true,
+                ..Default::default()
+            };
+            let parser: StandaloneSetParser<_> = JsonDataNodeBuilder::new(&config)
+                .build_with_publisher(JSON_DATA_NODE_DEFAULT_ID, &client())
+                .into();
+            let prop = PropertyRef::from_node(
+                parser.publisher().node_ref().clone(),
+                JSON_DATA_NODE_VALUE_PROP_ID,
+            );
+            let publish = parser.publisher().value_target(&value);
+            let payload = String::from_utf8(publish.payload).unwrap();
+            let ParseOutcome::Parsed(JsonDataNodeSetEvents::Value(parsed)) =
+                parser.parse_set(&prop, &payload)
+            else {
+                panic!("published payload {payload} must parse");
+            };
+            prop_assert_eq!(parsed, value);
+        }
+    }
+
+    #[cfg(feature = "generic-actuator")]
+    proptest! {
+        #[test]
+        fn generic_actuator_commands_round_trip(
+            commands in enum_format(),
+            state in any::<bool>(),
+            index in any::<sample::Index>(),
+        ) {
+            use homie5::PropertyRef;
+
+            use crate::{ParseOutcome, StandaloneSetParser, generic_actuator_node::*};
+
+            let config = GenericActuatorNodeConfig {
+                commands,
+                state,
+                ..Default::default()
+            };
+            let parser: StandaloneSetParser<_> = GenericActuatorNodeBuilder::new(&config)
+                .build_with_publisher(GENERIC_ACTUATOR_NODE_DEFAULT_ID, &client())
+                .into();
+            let prop = PropertyRef::from_node(
+                parser.publisher().node_ref().clone(),
+                GENERIC_ACTUATOR_NODE_COMMAND_PROP_ID,
+            );
+            let command = index.get(&config.commands);
+            let publish = parser.publisher().command(command).unwrap();
+            let payload = String::from_utf8(publish.payload).unwrap();
+            let ParseOutcome::Parsed(GenericActuatorNodeSetEvents::Command(parsed)) =
+                parser.parse_set(&prop, &payload)
+            else {
+                panic!("published command {payload} must parse");
+            };
+            prop_assert_eq!(&parsed, command);
+        }
+    }
+
+    #[cfg(feature = "generic-actuator")]
+    proptest! {
+        #[test]
+        fn generic_actuator_rejects_repeated_commands(commands in raw_enum_values()) {
+            use std::collections::BTreeSet;
+
+            use crate::generic_actuator_node::GenericActuatorNodeConfig;
+
+            let repeated = commands.iter().collect::<BTreeSet<_>>().len() != commands.len();
+            let config = SmarthomePropertyConfig::GenericActuator(GenericActuatorNodeConfig {
+                commands,
+                ..Default::default()
+            });
+            let built = build_node(&config, HomieID::new_const("node"), &client());
+            prop_assert_eq!(built.is_err(), repeated);
+            prop_assert_eq!(node_property_ids(&config).is_err(), repeated);
+        }
+    }
+}
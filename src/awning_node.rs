@@ -0,0 +1,477 @@
+use core::fmt;
+use std::{collections::BTreeMap, str::FromStr};
+
+use homie5::{
+    HOMIE_UNIT_PERCENT, Homie5DeviceProtocol, Homie5Message, Homie5ProtocolError, HomieID,
+    HomieValue, NodeRef, PropertyRef,
+    device_description::{
+        HomieDeviceDescription, HomieNodeDescription, IntegerRange, NodeDescriptionBuilder,
+        PropertyDescriptionBuilder,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ParseError, ParseErrorKind, ParseOutcome, PropertyMetadata, SMARTHOME_CAP_AWNING,
+    SetCommandParser, SmarthomePublisher, SmarthomeType, property_metadata, readonly_properties,
+    reject_readonly, smarthome_node_config,
+};
+
+pub const AWNING_NODE_DEFAULT_ID: HomieID = HomieID::new_const("awning");
+pub const AWNING_NODE_DEFAULT_NAME: &str = "Awning";
+pub const AWNING_NODE_EXTENSION_PROP_ID: HomieID = HomieID::new_const("extension");
+pub const AWNING_NODE_ACTION_PROP_ID: HomieID = HomieID::new_const("action");
+pub const AWNING_NODE_WIND_LOCKOUT_PROP_ID: HomieID = HomieID::new_const("wind-lockout");
+
+// ── Node (state) ────────────────────────────────────────────────────────────
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AwningNodeState {
+    pub extension: i64,
+    pub wind_lockout: bool,
+}
+
+#[derive(Debug)]
+pub struct AwningNode {
+    pub publisher: AwningNodePublisher,
+    pub state: AwningNodeState,
+}
+
+impl AwningNode {
+    /// Apply a parsed set command and return the `$target` and value publishes
+    /// for the change. While the wind lockout is active the awning cannot be
+    /// extended, so only retracting targets are published.
+    pub fn apply_set(&mut self, event: AwningNodeSetEvents) -> Vec<homie5::client::Publish> {
+        let target = match event {
+            AwningNodeSetEvents::Extension(value) => value,
+            AwningNodeSetEvents::Action(AwningNodeActions::Extend) => 100,
+            AwningNodeSetEvents::Action(AwningNodeActions::Retract) => 0,
+            AwningNodeSetEvents::Action(AwningNodeActions::Stop) => return Vec::new(),
+        };
+        if self.state.wind_lockout && target > self.state.extension {
+            return Vec::new();
+        }
+        vec![self.publisher.extension_target(target)]
+    }
+
+    /// Apply a value reported by the motor and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Integer(value)) if p == &AWNING_NODE_EXTENSION_PROP_ID => {
+                self.state.extension = *value;
+                vec![self.publisher.extension(*value)]
+            }
+            (p, HomieValue::Bool(value)) if p == &AWNING_NODE_WIND_LOCKOUT_PROP_ID => {
+                self.state.wind_lockout = *value;
+                self.publisher.wind_lockout(*value).into_iter().collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            Some(self.publisher.extension(self.state.extension)),
+            self.publisher.wind_lockout(self.state.wind_lockout),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+// ── Actions / set events ────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+pub enum AwningNodeActions {
+    Extend,
+    Retract,
+    Stop,
+}
+
+impl AwningNodeActions {
+    pub const ALL: [Self; 3] = [Self::Extend, Self::Retract, Self::Stop];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Extend => "extend",
+            Self::Retract => "retract",
+            Self::Stop => "stop",
+        }
+    }
+}
+
+impl fmt::Display for AwningNodeActions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AwningNodeActions {
+    type Err = Homie5ProtocolError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "extend" => Ok(Self::Extend),
+            "retract" => Ok(Self::Retract),
+            "stop" => Ok(Self::Stop),
+            _ => Err(Homie5ProtocolError::InvalidPayload),
+        }
+    }
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+pub enum AwningNodeSetEvents {
+    Extension(i64),
+    Action(AwningNodeActions),
+}
+
+// ── Config ──────────────────────────────────────────────────────────────────
+
+smarthome_node_config! {
+    #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+    #[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+    #[serde(default)]
+    pub struct AwningNodeConfig for AwningNodePublisher {
+        /// Expose the `wind-lockout` safety interlock state.
+        pub wind_lockout: bool => AWNING_NODE_WIND_LOCKOUT_PROP_ID, wind_lockout(bool), |_config| {
+            PropertyDescriptionBuilder::boolean()
+                .name("Wind lockout")
+                .boolean_labels("released", "locked")
+                .settable(false)
+                .retained(true)
+                .build()
+        },
+        /// Properties to publish as read-only; sets to them are rejected.
+        #[cfg_attr(
+            feature = "proptest",
+            proptest(strategy = "crate::arbitrary::homie_ids()")
+        )]
+        pub readonly: Vec<HomieID>,
+    }
+}
+
+impl Default for AwningNodeConfig {
+    fn default() -> Self {
+        Self {
+            wind_lockout: true,
+            readonly: Vec::new(),
+        }
+    }
+}
+
+// ── Builder ─────────────────────────────────────────────────────────────────
+
+pub struct AwningNodeBuilder {
+    node_builder: NodeDescriptionBuilder,
+    config: AwningNodeConfig,
+}
+
+impl Default for AwningNodeBuilder {
+    fn default() -> Self {
+        Self::new(&AwningNodeConfig::default())
+    }
+}
+
+impl AwningNodeBuilder {
+    pub fn new(config: &AwningNodeConfig) -> Self {
+        let db = Self::build_node(
+            NodeDescriptionBuilder::new().name(AWNING_NODE_DEFAULT_NAME),
+            config,
+        )
+        .r#type(SMARTHOME_CAP_AWNING);
+        let db = readonly_properties(db, &config.readonly);
+
+        Self {
+            node_builder: db,
+            config: config.clone(),
+        }
+    }
+
+    fn build_node(db: NodeDescriptionBuilder, config: &AwningNodeConfig) -> NodeDescriptionBuilder {
+        config.add_optional_properties(
+            db.add_property(
+                AWNING_NODE_EXTENSION_PROP_ID,
+                PropertyDescriptionBuilder::integer()
+                    .name("Awning extension")
+                    .unit(HOMIE_UNIT_PERCENT)
+                    .integer_range(IntegerRange {
+                        min: Some(0),
+                        max: Some(100),
+                        step: None,
+                    })
+                    .settable(true)
+                    .retained(true)
+                    .build(),
+            )
+            .add_property(
+                AWNING_NODE_ACTION_PROP_ID,
+                PropertyDescriptionBuilder::enumeration(
+                    AwningNodeActions::ALL.iter().map(|a| a.as_str()),
+                )
+                .unwrap()
+                .name("Awning action")
+                .settable(true)
+                .retained(false)
+                .build(),
+            ),
+        )
+    }
+
+    pub fn name<S: Into<String>>(mut self, name: impl Into<Option<S>>) -> Self {
+        self.node_builder = self.node_builder.name(name);
+        self
+    }
+
+    /// Append a subtype suffix to the node type (`awning-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::Awning.with_subtype(subtype));
+        self
+    }
+
+    pub fn build(self) -> HomieNodeDescription {
+        self.node_builder.build()
+    }
+
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &AwningNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
+    pub fn build_with_publisher(
+        self,
+        node_id: HomieID,
+        client: &Homie5DeviceProtocol,
+    ) -> (HomieNodeDescription, AwningNodePublisher) {
+        (
+            self.node_builder.build(),
+            AwningNodePublisher::new(
+                NodeRef::new(
+                    client.homie_domain().to_owned(),
+                    client.id().clone(),
+                    node_id,
+                ),
+                client.clone(),
+                self.config,
+            ),
+        )
+    }
+}
+
+// ── Publisher ────────────────────────────────────────────────────────────────
+
+#[derive(Debug)]
+pub struct AwningNodePublisher {
+    client: Homie5DeviceProtocol,
+    node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
+    config: AwningNodeConfig,
+    extension_prop: HomieID,
+    action_prop: HomieID,
+    wind_lockout_prop: HomieID,
+}
+
+impl AwningNodePublisher {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: AwningNodeConfig) -> Self {
+        let metadata = property_metadata(&AwningNodeBuilder::new(&config).build());
+        Self {
+            node,
+            metadata,
+            client,
+            config,
+            extension_prop: AWNING_NODE_EXTENSION_PROP_ID,
+            action_prop: AWNING_NODE_ACTION_PROP_ID,
+            wind_lockout_prop: AWNING_NODE_WIND_LOCKOUT_PROP_ID,
+        }
+    }
+
+    pub fn extension(&self, value: i64) -> homie5::client::Publish {
+        self.client.publish_value(
+            self.node.node_id(),
+            &self.extension_prop,
+            value.to_string(),
+            true,
+        )
+    }
+
+    pub fn extension_target(&self, value: i64) -> homie5::client::Publish {
+        self.client.publish_target(
+            self.node.node_id(),
+            &self.extension_prop,
+            value.to_string(),
+            true,
+        )
+    }
+
+    pub fn action(&self, action: AwningNodeActions) -> homie5::client::Publish {
+        self.client.publish_value(
+            self.node.node_id(),
+            &self.action_prop,
+            action.as_str(),
+            false,
+        )
+    }
+}
+
+impl SmarthomePublisher for AwningNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::Awning
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![
+            self.extension_prop.clone(),
+            self.action_prop.clone(),
+            self.wind_lockout_prop.clone(),
+        ]
+    }
+}
+
+impl SetCommandParser for AwningNodePublisher {
+    type Event = AwningNodeSetEvents;
+
+    fn parse_set(
+        &self,
+        property: &PropertyRef,
+        desc: &HomieDeviceDescription,
+        set_value: &str,
+    ) -> ParseOutcome<Self::Event> {
+        if let Some(outcome) = reject_readonly(&self.node, property, desc, set_value) {
+            return outcome;
+        }
+
+        let property_id = property.prop_id().to_string();
+
+        if property.match_with_node(&self.node, &self.extension_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Integer(value)) => {
+                    ParseOutcome::Parsed(AwningNodeSetEvents::Extension(value))
+                }
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else if property.match_with_node(&self.node, &self.action_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Enum(value)) => match AwningNodeActions::from_str(&value) {
+                    Ok(action) => ParseOutcome::Parsed(AwningNodeSetEvents::Action(action)),
+                    Err(_) => ParseOutcome::Invalid(ParseError::new(
+                        property_id,
+                        set_value,
+                        ParseErrorKind::InvalidVariant,
+                    )),
+                },
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else {
+            ParseOutcome::NoMatch
+        }
+    }
+
+    fn parse_set_event(
+        &self,
+        desc: &HomieDeviceDescription,
+        event: &Homie5Message,
+    ) -> ParseOutcome<Self::Event> {
+        match event {
+            Homie5Message::PropertySet {
+                property,
+                set_value,
+            } => self.parse_set(property, desc, set_value),
+            _ => ParseOutcome::Invalid(ParseError::new(
+                self.extension_prop.to_string(),
+                "",
+                ParseErrorKind::UnexpectedMessageType,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use homie5::HomieDomain;
+
+    use super::*;
+    use crate::StandaloneSetParser;
+
+    #[test]
+    fn wind_lockout_blocks_extending() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let parser: StandaloneSetParser<_> = AwningNodeBuilder::default()
+            .build_with_publisher(AWNING_NODE_DEFAULT_ID, &client)
+            .into();
+        let node = parser.publisher().node_ref().clone();
+        let action = PropertyRef::from_node(node.clone(), AWNING_NODE_ACTION_PROP_ID);
+        let lockout = PropertyRef::from_node(node, AWNING_NODE_WIND_LOCKOUT_PROP_ID);
+
+        let ParseOutcome::Parsed(extend) = parser.parse_set(&action, "extend") else {
+            panic!("extend must parse");
+        };
+        assert!(matches!(
+            parser.parse_set(&lockout, "true"),
+            ParseOutcome::Invalid(ParseError {
+                kind: ParseErrorKind::NotSettable,
+                ..
+            })
+        ));
+
+        let mut node = AwningNode {
+            publisher: parser.into_publisher(),
+            state: AwningNodeState {
+                extension: 40,
+                wind_lockout: true,
+            },
+        };
+        assert!(node.apply_set(extend).is_empty());
+        assert_eq!(
+            node.apply_set(AwningNodeSetEvents::Action(AwningNodeActions::Retract))
+                .len(),
+            1
+        );
+    }
+}
@@ -1,5 +1,7 @@
+use std::collections::BTreeMap;
+
 use homie5::{
-    HOMIE_UNIT_PERCENT, Homie5DeviceProtocol, HomieID, NodeRef,
+    HOMIE_UNIT_PERCENT, Homie5DeviceProtocol, HomieID, HomieValue, NodeRef,
     device_description::{
         HomieNodeDescription, IntegerRange, NodeDescriptionBuilder,
         PropertyDescriptionBuilder,
@@ -7,25 +9,93 @@ use homie5::{
 };
 use serde::{Deserialize, Serialize};
 
-use crate::SMARTHOME_CAP_BATTERY;
+use crate::{
+    PropertyMetadata, SMARTHOME_CAP_BATTERY, SmarthomePublisher, SmarthomeType, property_metadata,
+    smarthome_node_config,
+};
 
 pub const BATTERY_NODE_DEFAULT_ID: HomieID = HomieID::new_const("battery");
 pub const BATTERY_NODE_DEFAULT_NAME: &str = "Battery";
 pub const BATTERY_NODE_LEVEL_PROP_ID: HomieID = HomieID::new_const("level");
 pub const BATTERY_NODE_VOLTAGE_PROP_ID: HomieID = HomieID::new_const("voltage");
 
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BatteryNodeState {
+    pub level: Option<i64>,
+    pub voltage: Option<i64>,
+}
+
 #[derive(Debug)]
 pub struct BatteryNode {
     pub publisher: BatteryNodePublisher,
-    pub level: Option<i64>,
-    pub voltage: Option<i64>,
+    pub state: BatteryNodeState,
 }
 
-#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
-#[serde(default)]
-pub struct BatteryNodeConfig {
-    pub level: bool,
-    pub voltage: bool,
+impl BatteryNode {
+    /// Apply a value reported by the device and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Integer(value)) if p == &BATTERY_NODE_LEVEL_PROP_ID => {
+                self.state.level = Some(*value);
+                self.publisher.level(*value).into_iter().collect()
+            }
+            (p, HomieValue::Integer(value)) if p == &BATTERY_NODE_VOLTAGE_PROP_ID => {
+                self.state.voltage = Some(*value);
+                self.publisher.voltage(*value).into_iter().collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            self.state
+                .level
+                .and_then(|value| self.publisher.level(value)),
+            self.state
+                .voltage
+                .and_then(|value| self.publisher.voltage(value)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+smarthome_node_config! {
+    #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+    #[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+    #[serde(default)]
+    pub struct BatteryNodeConfig for BatteryNodePublisher {
+        pub level: bool => BATTERY_NODE_LEVEL_PROP_ID, level(i64), |_config| {
+            PropertyDescriptionBuilder::integer()
+                .name("Battery level")
+                .integer_range(IntegerRange {
+                    min: Some(0),
+                    max: Some(100),
+                    step: None,
+                })
+                .unit(HOMIE_UNIT_PERCENT)
+                .settable(false)
+                .retained(true)
+                .build()
+        },
+        pub voltage: bool => BATTERY_NODE_VOLTAGE_PROP_ID, voltage(i64), |_config| {
+            PropertyDescriptionBuilder::integer()
+                .name("Battery voltage")
+                .unit("mV")
+                .settable(false)
+                .retained(true)
+                .build()
+        },
+    }
 }
 
 impl Default for BatteryNodeConfig {
@@ -60,27 +130,7 @@ impl BatteryNodeBuilder {
         db: NodeDescriptionBuilder,
         config: &BatteryNodeConfig,
     ) -> NodeDescriptionBuilder {
-        db.add_property_cond(BATTERY_NODE_LEVEL_PROP_ID, config.level, || {
-            PropertyDescriptionBuilder::integer()
-                .name("Battery level")
-                .integer_range(IntegerRange {
-                    min: Some(0),
-                    max: Some(100),
-                    step: None,
-                })
-                .unit(HOMIE_UNIT_PERCENT)
-                .settable(false)
-                .retained(true)
-                .build()
-        })
-        .add_property_cond(BATTERY_NODE_VOLTAGE_PROP_ID, config.voltage, || {
-            PropertyDescriptionBuilder::integer()
-                .name("Battery voltage")
-                .unit("mV")
-                .settable(false)
-                .retained(true)
-                .build()
-        })
+        config.add_optional_properties(db)
     }
 
     pub fn name<S: Into<String>>(mut self, name: impl Into<Option<S>>) -> Self {
@@ -88,10 +138,23 @@ impl BatteryNodeBuilder {
         self
     }
 
+    /// Append a subtype suffix to the node type (`battery-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::Battery.with_subtype(subtype));
+        self
+    }
+
     pub fn build(self) -> HomieNodeDescription {
         self.node_builder.build()
     }
 
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &BatteryNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
     pub fn build_with_publisher(
         self,
         node_id: HomieID,
@@ -117,42 +180,39 @@ pub struct BatteryNodePublisher {
     client: Homie5DeviceProtocol,
     config: BatteryNodeConfig,
     node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
     level_prop: HomieID,
     voltage_prop: HomieID,
 }
 
 impl BatteryNodePublisher {
     pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: BatteryNodeConfig) -> Self {
+        let metadata = property_metadata(&BatteryNodeBuilder::new(&config).build());
         Self {
             node,
+            metadata,
             client,
             config,
             level_prop: BATTERY_NODE_LEVEL_PROP_ID,
             voltage_prop: BATTERY_NODE_VOLTAGE_PROP_ID,
         }
     }
+}
 
-    pub fn level(&self, value: i64) -> Option<homie5::client::Publish> {
-        if !self.config.level {
-            return None;
-        }
-        Some(self.client.publish_value(
-            self.node.node_id(),
-            &self.level_prop,
-            value.to_string(),
-            true,
-        ))
+impl SmarthomePublisher for BatteryNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::Battery
     }
 
-    pub fn voltage(&self, value: i64) -> Option<homie5::client::Publish> {
-        if !self.config.voltage {
-            return None;
-        }
-        Some(self.client.publish_value(
-            self.node.node_id(),
-            &self.voltage_prop,
-            value.to_string(),
-            true,
-        ))
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![self.level_prop.clone(), self.voltage_prop.clone()]
     }
 }
@@ -1,21 +1,126 @@
-use std::{fmt::Display, str::FromStr};
+use std::{collections::BTreeMap, fmt::Display, str::FromStr};
 
+use chrono::prelude::*;
 use homie5::{
-    Homie5DeviceProtocol, Homie5ProtocolError, HomieID, NodeRef,
+    Homie5DeviceProtocol, Homie5Message, Homie5ProtocolError, HomieID, HomieValue, NodeRef,
+    PropertyRef,
     device_description::{
-        HomieNodeDescription, NodeDescriptionBuilder, PropertyDescriptionBuilder,
+        HomieNodeDescription, IntegerRange, NodeDescriptionBuilder, PropertyDescriptionBuilder,
     },
 };
 use serde::{Deserialize, Serialize};
 
-use crate::SMARTHOME_CAP_BUTTON;
+use crate::{
+    ConfigError, ConfigErrorKind, ParseError, ParseErrorKind, ParseOutcome, PropertyMetadata,
+    SMARTHOME_CAP_BUTTON, SmarthomePublisher, SmarthomeType, property_metadata,
+    smarthome_node_config, validate_enum_format,
+};
 
 pub const BUTTON_NODE_DEFAULT_ID: HomieID = HomieID::new_const("button");
 pub const BUTTON_NODE_DEFAULT_NAME: &str = "Pushbutton";
 
 pub const BUTTON_NODE_ACTION_PROP_ID: HomieID = HomieID::new_const("action");
+pub const BUTTON_NODE_HOLD_DURATION_PROP_ID: HomieID = HomieID::new_const("hold-duration");
+pub const BUTTON_NODE_LAST_ACTION_PROP_ID: HomieID = HomieID::new_const("last-action");
+pub const BUTTON_NODE_LAST_ACTION_TIME_PROP_ID: HomieID = HomieID::new_const("last-action-time");
+
+pub const MULTI_BUTTON_NODE_DEFAULT_NAME: &str = "Multi-key remote";
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ButtonNodeState {
+    pub last_action: Option<ButtonNodeActions>,
+    pub last_action_time: Option<DateTime<Utc>>,
+    pub hold_duration: Option<i64>,
+}
+
+#[derive(Debug)]
+pub struct ButtonNode {
+    pub publisher: ButtonNodePublisher,
+    pub state: ButtonNodeState,
+}
+
+impl ButtonNode {
+    /// Apply a value reported by the device and return the matching publishes.
+    /// An `action` is published as the event, followed by `last-action` and
+    /// `last-action-time` at the current system time if enabled.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Enum(value)) if p == &BUTTON_NODE_ACTION_PROP_ID => {
+                let Some(action) = value
+                    .parse::<ButtonNodeActions>()
+                    .ok()
+                    .filter(|action| self.publisher.config.actions.contains(action))
+                else {
+                    return Vec::new();
+                };
+                let now = Utc::now();
+                let publishes = [
+                    Some(self.publisher.action(&action)),
+                    self.publisher.last_action(&action),
+                    self.publisher.last_action_time(now),
+                ]
+                .into_iter()
+                .flatten()
+                .collect();
+                self.state.last_action = Some(action);
+                self.state.last_action_time = Some(now);
+                publishes
+            }
+            (p, HomieValue::Enum(value)) if p == &BUTTON_NODE_LAST_ACTION_PROP_ID => {
+                let Ok(action) = value.parse::<ButtonNodeActions>() else {
+                    return Vec::new();
+                };
+                self.state.last_action = Some(action);
+                self.publisher.last_action(&action).into_iter().collect()
+            }
+            (p, HomieValue::DateTime(value)) if p == &BUTTON_NODE_LAST_ACTION_TIME_PROP_ID => {
+                self.state.last_action_time = Some(*value);
+                self.publisher
+                    .last_action_time(*value)
+                    .into_iter()
+                    .collect()
+            }
+            (p, HomieValue::Integer(value)) if p == &BUTTON_NODE_HOLD_DURATION_PROP_ID => {
+                self.state.hold_duration = Some(*value);
+                self.publisher.hold_duration(*value).into_iter().collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            self.state
+                .last_action
+                .as_ref()
+                .and_then(|value| self.publisher.last_action(value)),
+            self.state
+                .last_action_time
+                .and_then(|value| self.publisher.last_action_time(value)),
+            self.state
+                .hold_duration
+                .and_then(|value| self.publisher.hold_duration(value)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+#[derive(Debug)]
+pub struct MultiButtonNode {
+    pub publisher: MultiButtonNodePublisher,
+}
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 #[serde(rename_all = "kebab-case")]
 pub enum ButtonNodeActions {
     Press,
@@ -31,6 +136,11 @@ impl FromStr for ButtonNodeActions {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             "press" => Ok(ButtonNodeActions::Press),
+            "long-press" => Ok(ButtonNodeActions::LongPress),
+            "double-press" => Ok(ButtonNodeActions::DoublePress),
+            "release" => Ok(ButtonNodeActions::Release),
+            "long-release" => Ok(ButtonNodeActions::LongRelease),
+            "continuous" => Ok(ButtonNodeActions::Continuous),
             _ => Err(Homie5ProtocolError::InvalidPayload),
         }
     }
@@ -58,7 +168,14 @@ impl From<&ButtonNodeActions> for &'static str {
 
 impl ButtonNodeActions {
     pub fn all_variants() -> &'static [Self] {
-        &[ButtonNodeActions::Press]
+        &[
+            ButtonNodeActions::Press,
+            ButtonNodeActions::LongPress,
+            ButtonNodeActions::DoublePress,
+            ButtonNodeActions::Release,
+            ButtonNodeActions::LongRelease,
+            ButtonNodeActions::Continuous,
+        ]
     }
 
     pub fn to_string_vec() -> Vec<String> {
@@ -66,21 +183,69 @@ impl ButtonNodeActions {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
-#[serde(default)]
-pub struct ButtonNodeConfig {
-    pub actions: Vec<ButtonNodeActions>,
+smarthome_node_config! {
+    #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+    #[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+    #[serde(default)]
+    pub struct ButtonNodeConfig for ButtonNodePublisher {
+        #[cfg_attr(
+            feature = "proptest",
+            proptest(strategy = "crate::arbitrary::distinct()")
+        )]
+        pub actions: Vec<ButtonNodeActions>,
+        /// Expose a `hold-duration` (ms) property published alongside
+        /// `long-release` / `continuous` actions.
+        pub hold_duration: bool => BUTTON_NODE_HOLD_DURATION_PROP_ID, hold_duration(i64), |_config| {
+            PropertyDescriptionBuilder::integer()
+                .name("Hold duration")
+                .integer_range(IntegerRange {
+                    min: Some(0),
+                    max: None,
+                    step: None,
+                })
+                .unit("ms")
+                .settable(false)
+                .retained(false)
+                .build()
+        },
+        /// Additionally publish the last action and its timestamp as retained
+        /// properties (`last-action`, `last-action-time`).
+        pub last_action: bool,
+    }
 }
 
 impl Default for ButtonNodeConfig {
     fn default() -> Self {
         Self {
             actions: vec![ButtonNodeActions::Press],
+            hold_duration: false,
+            last_action: false,
         }
     }
 }
 
+impl ButtonNodeConfig {
+    /// Check the config-provided enum values before building the node.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        validate_enum_format(
+            &BUTTON_NODE_ACTION_PROP_ID,
+            self.all_variants().iter().map(|a| a.to_string()),
+        )
+    }
+
+    /// The actions advertised by a node built from this config, in the order
+    /// of [`ButtonNodeActions::all_variants`] and without duplicates.
+    pub fn all_variants(&self) -> Vec<ButtonNodeActions> {
+        ButtonNodeActions::all_variants()
+            .iter()
+            .filter(|a| self.actions.contains(a))
+            .copied()
+            .collect()
+    }
+}
+
 pub struct ButtonNodeBuilder {
+    config: ButtonNodeConfig,
     node_builder: NodeDescriptionBuilder,
 }
 
@@ -92,20 +257,46 @@ impl ButtonNodeBuilder {
         )
         .r#type(SMARTHOME_CAP_BUTTON);
 
-        Self { node_builder: db }
+        Self {
+            config: config.clone(),
+            node_builder: db,
+        }
     }
 
     fn build_node(db: NodeDescriptionBuilder, config: &ButtonNodeConfig) -> NodeDescriptionBuilder {
-        db.add_property(
-            BUTTON_NODE_ACTION_PROP_ID,
-            PropertyDescriptionBuilder::enumeration(
-                config.actions.iter().map(|a| a.to_string()).collect::<Vec<_>>(),
+        config.add_optional_properties(
+            db.add_property(
+                BUTTON_NODE_ACTION_PROP_ID,
+                PropertyDescriptionBuilder::enumeration(
+                    config.all_variants().iter().map(|a| a.to_string()),
+                )
+                .unwrap()
+                .name("Button action event")
+                .settable(false)
+                .retained(false)
+                .build(),
             )
-            .unwrap()
-            .name("Button action event")
-            .settable(false)
-            .retained(false)
-            .build(),
+            .add_property_cond(BUTTON_NODE_LAST_ACTION_PROP_ID, config.last_action, || {
+                PropertyDescriptionBuilder::enumeration(
+                    config.all_variants().iter().map(|a| a.to_string()),
+                )
+                .unwrap()
+                .name("Last button action")
+                .settable(false)
+                .retained(true)
+                .build()
+            })
+            .add_property_cond(
+                BUTTON_NODE_LAST_ACTION_TIME_PROP_ID,
+                config.last_action,
+                || {
+                    PropertyDescriptionBuilder::datetime()
+                        .name("Last button action time")
+                        .settable(false)
+                        .retained(true)
+                        .build()
+                },
+            ),
         )
     }
 
@@ -113,10 +304,23 @@ impl ButtonNodeBuilder {
         self.node_builder = self.node_builder.name(name);
         self
     }
+
+    /// Append a subtype suffix to the node type (`button-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::Button.with_subtype(subtype));
+        self
+    }
     pub fn build(self) -> HomieNodeDescription {
         self.node_builder.build()
     }
 
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &ButtonNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
     pub fn build_with_publisher(
         self,
         node_id: HomieID,
@@ -127,6 +331,7 @@ impl ButtonNodeBuilder {
             ButtonNodePublisher::new(
                 NodeRef::new(client.homie_domain().clone(), client.id().clone(), node_id),
                 client.clone(),
+                self.config,
             ),
         )
     }
@@ -141,16 +346,27 @@ impl Default for ButtonNodeBuilder {
 #[derive(Debug)]
 pub struct ButtonNodePublisher {
     client: Homie5DeviceProtocol,
+    config: ButtonNodeConfig,
     node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
     action_prop: HomieID,
+    hold_duration_prop: HomieID,
+    last_action_prop: HomieID,
+    last_action_time_prop: HomieID,
 }
 
 impl ButtonNodePublisher {
-    pub fn new(node: NodeRef, client: Homie5DeviceProtocol) -> Self {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: ButtonNodeConfig) -> Self {
+        let metadata = property_metadata(&ButtonNodeBuilder::new(&config).build());
         Self {
             node,
+            metadata,
             client,
+            config,
             action_prop: BUTTON_NODE_ACTION_PROP_ID,
+            hold_duration_prop: BUTTON_NODE_HOLD_DURATION_PROP_ID,
+            last_action_prop: BUTTON_NODE_LAST_ACTION_PROP_ID,
+            last_action_time_prop: BUTTON_NODE_LAST_ACTION_TIME_PROP_ID,
         }
     }
 
@@ -162,4 +378,407 @@ impl ButtonNodePublisher {
             false,
         )
     }
+
+    pub fn last_action(&self, kind: &ButtonNodeActions) -> Option<homie5::client::Publish> {
+        if !self.config.last_action {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.last_action_prop,
+            kind.to_string(),
+            true,
+        ))
+    }
+
+    pub fn last_action_time(&self, value: DateTime<Utc>) -> Option<homie5::client::Publish> {
+        if !self.config.last_action {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.last_action_time_prop,
+            value.to_rfc3339_opts(SecondsFormat::Millis, true),
+            true,
+        ))
+    }
+}
+
+impl SmarthomePublisher for ButtonNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::Button
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![
+            self.action_prop.clone(),
+            self.hold_duration_prop.clone(),
+            self.last_action_prop.clone(),
+            self.last_action_time_prop.clone(),
+        ]
+    }
+}
+
+impl ButtonNodePublisher {
+    /// Decode a published button action value (controller side).
+    ///
+    /// Actions that are valid but not enabled in the node config are reported
+    /// as [`ParseErrorKind::InvalidVariant`].
+    pub fn parse_value(
+        &self,
+        property: &PropertyRef,
+        value: &str,
+    ) -> ParseOutcome<ButtonNodeActions> {
+        if !property.match_with_node(&self.node, &self.action_prop) {
+            return ParseOutcome::NoMatch;
+        }
+
+        match ButtonNodeActions::from_str(value) {
+            Ok(action) if self.config.actions.contains(&action) => ParseOutcome::Parsed(action),
+            _ => ParseOutcome::Invalid(ParseError::new(
+                property.prop_id().to_string(),
+                value,
+                ParseErrorKind::InvalidVariant,
+            )),
+        }
+    }
+
+    pub fn parse_value_event(&self, event: &Homie5Message) -> ParseOutcome<ButtonNodeActions> {
+        match event {
+            Homie5Message::PropertyValue { property, value } => self.parse_value(property, value),
+            _ => ParseOutcome::Invalid(ParseError::new(
+                self.action_prop.to_string(),
+                "",
+                ParseErrorKind::UnexpectedMessageType,
+            )),
+        }
+    }
+}
+
+// ── Multi-channel buttons ───────────────────────────────────────────────────
+
+/// Property ID of the action property for a (1-based) button channel,
+/// e.g. `action-1`.
+pub fn multi_button_action_prop_id(channel: u8) -> HomieID {
+    HomieID::try_from(format!("{}-{}", BUTTON_NODE_ACTION_PROP_ID, channel))
+        .expect("indexed action id is always a valid HomieID")
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+#[serde(default)]
+pub struct MultiButtonNodeConfig {
+    /// Number of buttons, exposed as `action-1` .. `action-<channels>`.
+    pub channels: u8,
+    #[cfg_attr(
+        feature = "proptest",
+        proptest(strategy = "crate::arbitrary::distinct()")
+    )]
+    pub actions: Vec<ButtonNodeActions>,
+}
+
+impl Default for MultiButtonNodeConfig {
+    fn default() -> Self {
+        Self {
+            channels: 2,
+            actions: vec![ButtonNodeActions::Press],
+        }
+    }
+}
+
+impl MultiButtonNodeConfig {
+    /// Check the channel count and the config-provided enum values before
+    /// building the node.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.channels == 0 {
+            return Err(ConfigError {
+                property_id: multi_button_action_prop_id(1),
+                kind: ConfigErrorKind::NoChannels,
+            });
+        }
+        validate_enum_format(
+            &multi_button_action_prop_id(1),
+            self.button_config()
+                .all_variants()
+                .iter()
+                .map(|a| a.to_string()),
+        )
+    }
+
+    fn button_config(&self) -> ButtonNodeConfig {
+        ButtonNodeConfig {
+            actions: self.actions.clone(),
+            ..ButtonNodeConfig::default()
+        }
+    }
+}
+
+/// Builds a single button capability node for wall remotes with several keys.
+pub struct MultiButtonNodeBuilder {
+    config: MultiButtonNodeConfig,
+    node_builder: NodeDescriptionBuilder,
+}
+
+impl MultiButtonNodeBuilder {
+    pub fn new(config: &MultiButtonNodeConfig) -> Self {
+        let db = Self::build_node(
+            NodeDescriptionBuilder::new().name(MULTI_BUTTON_NODE_DEFAULT_NAME),
+            config,
+        )
+        .r#type(SMARTHOME_CAP_BUTTON);
+
+        Self {
+            config: config.clone(),
+            node_builder: db,
+        }
+    }
+
+    fn build_node(
+        db: NodeDescriptionBuilder,
+        config: &MultiButtonNodeConfig,
+    ) -> NodeDescriptionBuilder {
+        let actions = config.button_config().all_variants();
+        (1..=config.channels).fold(db, |db, channel| {
+            db.add_property(
+                multi_button_action_prop_id(channel),
+                PropertyDescriptionBuilder::enumeration(actions.iter().map(|a| a.to_string()))
+                    .unwrap()
+                    .name(format!("Button {} action event", channel))
+                    .settable(false)
+                    .retained(false)
+                    .build(),
+            )
+        })
+    }
+
+    pub fn name<S: Into<String>>(mut self, name: impl Into<Option<S>>) -> Self {
+        self.node_builder = self.node_builder.name(name);
+        self
+    }
+
+    /// Append a subtype suffix to the node type (`button-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::Button.with_subtype(subtype));
+        self
+    }
+
+    pub fn build(self) -> HomieNodeDescription {
+        self.node_builder.build()
+    }
+
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &MultiButtonNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
+    pub fn build_with_publisher(
+        self,
+        node_id: HomieID,
+        client: &Homie5DeviceProtocol,
+    ) -> (HomieNodeDescription, MultiButtonNodePublisher) {
+        (
+            self.node_builder.build(),
+            MultiButtonNodePublisher::new(
+                NodeRef::new(client.homie_domain().clone(), client.id().clone(), node_id),
+                client.clone(),
+                self.config,
+            ),
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct MultiButtonNodePublisher {
+    client: Homie5DeviceProtocol,
+    config: MultiButtonNodeConfig,
+    node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
+    action_props: Vec<HomieID>,
+}
+
+impl MultiButtonNodePublisher {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: MultiButtonNodeConfig) -> Self {
+        let metadata = property_metadata(&MultiButtonNodeBuilder::new(&config).build());
+        let action_props = (1..=config.channels)
+            .map(multi_button_action_prop_id)
+            .collect();
+        Self {
+            node,
+            metadata,
+            client,
+            config,
+            action_props,
+        }
+    }
+
+    /// Publish an action for the given 1-based channel. Returns `None` for
+    /// channels outside of `1..=channels`.
+    pub fn action(&self, channel: u8, kind: &ButtonNodeActions) -> Option<homie5::client::Publish> {
+        let prop = self
+            .action_props
+            .get(usize::from(channel).checked_sub(1)?)?;
+        Some(
+            self.client
+                .publish_value(self.node.node_id(), prop, kind.to_string(), false),
+        )
+    }
+
+    /// Decode a published action value into `(channel, action)` (controller side).
+    pub fn parse_value(
+        &self,
+        property: &PropertyRef,
+        value: &str,
+    ) -> ParseOutcome<(u8, ButtonNodeActions)> {
+        let Some(index) = self
+            .action_props
+            .iter()
+            .position(|prop| property.match_with_node(&self.node, prop))
+        else {
+            return ParseOutcome::NoMatch;
+        };
+
+        match ButtonNodeActions::from_str(value) {
+            Ok(action) if self.config.actions.contains(&action) => {
+                ParseOutcome::Parsed((index as u8 + 1, action))
+            }
+            _ => ParseOutcome::Invalid(ParseError::new(
+                property.prop_id().to_string(),
+                value,
+                ParseErrorKind::InvalidVariant,
+            )),
+        }
+    }
+
+    pub fn parse_value_event(
+        &self,
+        event: &Homie5Message,
+    ) -> ParseOutcome<(u8, ButtonNodeActions)> {
+        match event {
+            Homie5Message::PropertyValue { property, value } => self.parse_value(property, value),
+            _ => ParseOutcome::Invalid(ParseError::new(
+                BUTTON_NODE_ACTION_PROP_ID.to_string(),
+                "",
+                ParseErrorKind::UnexpectedMessageType,
+            )),
+        }
+    }
+}
+
+impl SmarthomePublisher for MultiButtonNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::Button
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        self.action_props.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use homie5::HomieDomain;
+
+    #[test]
+    fn all_actions_round_trip_through_display_and_from_str() {
+        for action in ButtonNodeActions::all_variants() {
+            let parsed = ButtonNodeActions::from_str(&action.to_string()).expect("should parse");
+            assert_eq!(&parsed, action);
+        }
+    }
+
+    #[test]
+    fn parse_value_event_honors_configured_actions() {
+        let config = ButtonNodeConfig {
+            actions: vec![ButtonNodeActions::Press, ButtonNodeActions::LongPress],
+            ..ButtonNodeConfig::default()
+        };
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let (_, publisher) =
+            ButtonNodeBuilder::new(&config).build_with_publisher(BUTTON_NODE_DEFAULT_ID, &client);
+        let property = PropertyRef::from_node(publisher.node.clone(), BUTTON_NODE_ACTION_PROP_ID);
+
+        let event = Homie5Message::PropertyValue {
+            property: property.clone(),
+            value: "long-press".to_string(),
+        };
+        assert_eq!(
+            publisher.parse_value_event(&event),
+            ParseOutcome::Parsed(ButtonNodeActions::LongPress)
+        );
+
+        let outcome = publisher.parse_value(&property, "double-press");
+        assert!(matches!(
+            outcome,
+            ParseOutcome::Invalid(ParseError {
+                kind: ParseErrorKind::InvalidVariant,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn multi_button_generates_indexed_action_properties() {
+        let config = MultiButtonNodeConfig {
+            channels: 4,
+            ..MultiButtonNodeConfig::default()
+        };
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let (node, publisher) = MultiButtonNodeBuilder::new(&config)
+            .build_with_publisher(BUTTON_NODE_DEFAULT_ID, &client);
+
+        assert_eq!(node.properties.len(), 4);
+        for channel in 1..=4 {
+            assert!(
+                node.properties
+                    .contains_key(&multi_button_action_prop_id(channel))
+            );
+        }
+
+        let publish = publisher
+            .action(3, &ButtonNodeActions::Press)
+            .expect("channel 3 must exist");
+        assert!(publish.topic.ends_with("/button/action-3"));
+        assert!(publisher.action(0, &ButtonNodeActions::Press).is_none());
+        assert!(publisher.action(5, &ButtonNodeActions::Press).is_none());
+
+        let property =
+            PropertyRef::from_node(publisher.node.clone(), multi_button_action_prop_id(2));
+        assert_eq!(
+            publisher.parse_value(&property, "press"),
+            ParseOutcome::Parsed((2, ButtonNodeActions::Press))
+        );
+    }
+
+    #[test]
+    fn multi_button_without_channels_is_rejected() {
+        let err = MultiButtonNodeConfig {
+            channels: 0,
+            ..Default::default()
+        }
+        .validate()
+        .expect_err("zero channels must be rejected");
+        assert_eq!(err.property_id, multi_button_action_prop_id(1));
+        assert_eq!(err.kind, ConfigErrorKind::NoChannels);
+    }
 }
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use homie5::{
     Homie5DeviceProtocol, Homie5Message, HomieID, HomieValue, NodeRef, PropertyRef,
     device_description::{
@@ -7,7 +9,11 @@ use homie5::{
 };
 use serde::{Deserialize, Serialize};
 
-use crate::{ParseError, ParseErrorKind, ParseOutcome, SMARTHOME_CAP_CAMERA, SetCommandParser};
+use crate::{
+    ParseError, ParseErrorKind, ParseOutcome, PropertyMetadata, SMARTHOME_CAP_CAMERA,
+    SetCommandParser, SmarthomePublisher, SmarthomeType, property_metadata, readonly_properties,
+    reject_readonly,
+};
 
 pub const CAMERA_NODE_DEFAULT_ID: HomieID = HomieID::new_const("camera");
 pub const CAMERA_NODE_DEFAULT_NAME: &str = "Camera";
@@ -22,9 +28,9 @@ pub const CAMERA_NODE_ZOOM_PROP_ID: HomieID = HomieID::new_const("zoom");
 
 // ── Node (state) ────────────────────────────────────────────────────────────
 
-#[derive(Debug)]
-pub struct CameraNode {
-    pub publisher: CameraNodePublisher,
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CameraNodeState {
     pub stream_url: String,
     pub snapshot_url: Option<String>,
     pub recording: Option<bool>,
@@ -35,9 +41,120 @@ pub struct CameraNode {
     pub zoom: Option<f64>,
 }
 
+#[derive(Debug)]
+pub struct CameraNode {
+    pub publisher: CameraNodePublisher,
+    pub state: CameraNodeState,
+}
+
+impl CameraNode {
+    /// Apply a parsed set command and return the `$target` and value publishes.
+    pub fn apply_set(&mut self, event: CameraNodeSetEvents) -> Vec<homie5::client::Publish> {
+        match event {
+            CameraNodeSetEvents::Recording(value) => {
+                self.state.recording = Some(value);
+                vec![
+                    self.publisher.recording_target(value),
+                    self.publisher.recording(value),
+                ]
+            }
+            CameraNodeSetEvents::Pan(value) => {
+                self.state.pan = Some(value);
+                vec![self.publisher.pan_target(value), self.publisher.pan(value)]
+            }
+            CameraNodeSetEvents::Tilt(value) => {
+                self.state.tilt = Some(value);
+                vec![
+                    self.publisher.tilt_target(value),
+                    self.publisher.tilt(value),
+                ]
+            }
+            CameraNodeSetEvents::Zoom(value) => {
+                self.state.zoom = Some(value);
+                vec![
+                    self.publisher.zoom_target(value),
+                    self.publisher.zoom(value),
+                ]
+            }
+        }
+    }
+
+    /// Apply a value reported by the device and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::String(value)) if p == &CAMERA_NODE_STREAM_URL_PROP_ID => {
+                self.state.stream_url = value.clone();
+                vec![self.publisher.stream_url(value)]
+            }
+            (p, HomieValue::String(value)) if p == &CAMERA_NODE_SNAPSHOT_URL_PROP_ID => {
+                self.state.snapshot_url = Some(value.clone());
+                vec![self.publisher.snapshot_url(value)]
+            }
+            (p, HomieValue::Bool(value)) if p == &CAMERA_NODE_RECORDING_PROP_ID => {
+                self.state.recording = Some(*value);
+                vec![self.publisher.recording(*value)]
+            }
+            (p, HomieValue::Bool(value)) if p == &CAMERA_NODE_MOTION_DETECTED_PROP_ID => {
+                self.state.motion_detected = Some(*value);
+                vec![self.publisher.motion_detected(*value)]
+            }
+            (p, HomieValue::String(value)) if p == &CAMERA_NODE_OBJECT_DETECTED_PROP_ID => {
+                self.state.object_detected = Some(value.clone());
+                vec![self.publisher.object_detected(value)]
+            }
+            (p, HomieValue::Float(value)) if p == &CAMERA_NODE_PAN_PROP_ID => {
+                self.state.pan = Some(*value);
+                vec![self.publisher.pan(*value)]
+            }
+            (p, HomieValue::Float(value)) if p == &CAMERA_NODE_TILT_PROP_ID => {
+                self.state.tilt = Some(*value);
+                vec![self.publisher.tilt(*value)]
+            }
+            (p, HomieValue::Float(value)) if p == &CAMERA_NODE_ZOOM_PROP_ID => {
+                self.state.zoom = Some(*value);
+                vec![self.publisher.zoom(*value)]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            Some(self.publisher.stream_url(&self.state.stream_url)),
+            self.state
+                .snapshot_url
+                .as_deref()
+                .map(|value| self.publisher.snapshot_url(value)),
+            self.state
+                .recording
+                .map(|value| self.publisher.recording(value)),
+            self.state
+                .motion_detected
+                .map(|value| self.publisher.motion_detected(value)),
+            self.state
+                .object_detected
+                .as_deref()
+                .map(|value| self.publisher.object_detected(value)),
+            self.state.pan.map(|value| self.publisher.pan(value)),
+            self.state.tilt.map(|value| self.publisher.tilt(value)),
+            self.state.zoom.map(|value| self.publisher.zoom(value)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
 // ── Set events ──────────────────────────────────────────────────────────────
 
 #[derive(Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 pub enum CameraNodeSetEvents {
     Recording(bool),
     Pan(f64),
@@ -48,6 +165,7 @@ pub enum CameraNodeSetEvents {
 // ── Config ──────────────────────────────────────────────────────────────────
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 #[serde(default)]
 pub struct CameraNodeConfig {
     pub snapshot: bool,
@@ -58,6 +176,12 @@ pub struct CameraNodeConfig {
     pub tilt: bool,
     pub zoom: bool,
     pub zoom_max: f64,
+    /// Properties to publish as read-only; sets to them are rejected.
+    #[cfg_attr(
+        feature = "proptest",
+        proptest(strategy = "crate::arbitrary::homie_ids()")
+    )]
+    pub readonly: Vec<HomieID>,
 }
 
 impl Default for CameraNodeConfig {
@@ -71,6 +195,7 @@ impl Default for CameraNodeConfig {
             tilt: false,
             zoom: false,
             zoom_max: 10.0,
+            readonly: Vec::new(),
         }
     }
 }
@@ -79,6 +204,7 @@ impl Default for CameraNodeConfig {
 
 pub struct CameraNodeBuilder {
     node_builder: NodeDescriptionBuilder,
+    config: CameraNodeConfig,
 }
 
 impl CameraNodeBuilder {
@@ -88,8 +214,12 @@ impl CameraNodeBuilder {
             config,
         )
         .r#type(SMARTHOME_CAP_CAMERA);
+        let db = readonly_properties(db, &config.readonly);
 
-        Self { node_builder: db }
+        Self {
+            node_builder: db,
+            config: config.clone(),
+        }
     }
 
     fn build_node(db: NodeDescriptionBuilder, config: &CameraNodeConfig) -> NodeDescriptionBuilder {
@@ -184,10 +314,23 @@ impl CameraNodeBuilder {
         self
     }
 
+    /// Append a subtype suffix to the node type (`camera-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::Camera.with_subtype(subtype));
+        self
+    }
+
     pub fn build(self) -> HomieNodeDescription {
         self.node_builder.build()
     }
 
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &CameraNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
     pub fn build_with_publisher(
         self,
         node_id: HomieID,
@@ -202,6 +345,7 @@ impl CameraNodeBuilder {
                     node_id,
                 ),
                 client.clone(),
+                &self.config,
             ),
         )
     }
@@ -213,6 +357,7 @@ impl CameraNodeBuilder {
 pub struct CameraNodePublisher {
     client: Homie5DeviceProtocol,
     node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
     stream_url_prop: HomieID,
     snapshot_url_prop: HomieID,
     recording_prop: HomieID,
@@ -224,9 +369,11 @@ pub struct CameraNodePublisher {
 }
 
 impl CameraNodePublisher {
-    pub fn new(node: NodeRef, client: Homie5DeviceProtocol) -> Self {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: &CameraNodeConfig) -> Self {
+        let metadata = property_metadata(&CameraNodeBuilder::new(config).build());
         Self {
             node,
+            metadata,
             client,
             stream_url_prop: CAMERA_NODE_STREAM_URL_PROP_ID,
             snapshot_url_prop: CAMERA_NODE_SNAPSHOT_URL_PROP_ID,
@@ -258,6 +405,15 @@ impl CameraNodePublisher {
         )
     }
 
+    pub fn recording_target(&self, value: bool) -> homie5::client::Publish {
+        self.client.publish_target(
+            self.node.node_id(),
+            &self.recording_prop,
+            value.to_string(),
+            true,
+        )
+    }
+
     pub fn motion_detected(&self, value: bool) -> homie5::client::Publish {
         self.client.publish_value(
             self.node.node_id(),
@@ -277,6 +433,11 @@ impl CameraNodePublisher {
             .publish_value(self.node.node_id(), &self.pan_prop, value.to_string(), true)
     }
 
+    pub fn pan_target(&self, value: f64) -> homie5::client::Publish {
+        self.client
+            .publish_target(self.node.node_id(), &self.pan_prop, value.to_string(), true)
+    }
+
     pub fn tilt(&self, value: f64) -> homie5::client::Publish {
         self.client.publish_value(
             self.node.node_id(),
@@ -286,6 +447,15 @@ impl CameraNodePublisher {
         )
     }
 
+    pub fn tilt_target(&self, value: f64) -> homie5::client::Publish {
+        self.client.publish_target(
+            self.node.node_id(),
+            &self.tilt_prop,
+            value.to_string(),
+            true,
+        )
+    }
+
     pub fn zoom(&self, value: f64) -> homie5::client::Publish {
         self.client.publish_value(
             self.node.node_id(),
@@ -294,6 +464,42 @@ impl CameraNodePublisher {
             true,
         )
     }
+
+    pub fn zoom_target(&self, value: f64) -> homie5::client::Publish {
+        self.client.publish_target(
+            self.node.node_id(),
+            &self.zoom_prop,
+            value.to_string(),
+            true,
+        )
+    }
+}
+
+impl SmarthomePublisher for CameraNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::Camera
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![
+            self.stream_url_prop.clone(),
+            self.snapshot_url_prop.clone(),
+            self.recording_prop.clone(),
+            self.motion_detected_prop.clone(),
+            self.object_detected_prop.clone(),
+            self.pan_prop.clone(),
+            self.tilt_prop.clone(),
+            self.zoom_prop.clone(),
+        ]
+    }
 }
 
 impl SetCommandParser for CameraNodePublisher {
@@ -305,6 +511,10 @@ impl SetCommandParser for CameraNodePublisher {
         desc: &HomieDeviceDescription,
         set_value: &str,
     ) -> ParseOutcome<Self::Event> {
+        if let Some(outcome) = reject_readonly(&self.node, property, desc, set_value) {
+            return outcome;
+        }
+
         let property_id = property.prop_id().to_string();
 
         if property.match_with_node(&self.node, &self.recording_prop) {
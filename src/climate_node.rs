@@ -1,13 +1,17 @@
+use std::collections::BTreeMap;
+
 use homie5::{
     HOMIE_UNIT_DEGREE_CELSIUS, HOMIE_UNIT_KILOPASCAL, HOMIE_UNIT_PERCENT, Homie5DeviceProtocol,
-    HomieID, NodeRef,
+    HomieID, HomieValue, NodeRef,
     device_description::{
         HomieNodeDescription, NodeDescriptionBuilder, PropertyDescriptionBuilder,
     },
 };
 use serde::{Deserialize, Serialize};
 
-use crate::SMARTHOME_CAP_CLIMATE;
+use crate::{
+    PropertyMetadata, SMARTHOME_CAP_CLIMATE, SmarthomePublisher, SmarthomeType, property_metadata,
+};
 
 pub const CLIMATE_NODE_DEFAULT_ID: HomieID = HomieID::new_const("climate");
 pub const CLIMATE_NODE_DEFAULT_NAME: &str = "Climate sensor";
@@ -15,15 +19,66 @@ pub const CLIMATE_NODE_TEMP_PROP_ID: HomieID = HomieID::new_const("temperature")
 pub const CLIMATE_NODE_HUM_PROP_ID: HomieID = HomieID::new_const("humidity");
 pub const CLIMATE_NODE_PRES_PROP_ID: HomieID = HomieID::new_const("pressure");
 
-#[derive(Debug)]
-pub struct ClimateNode {
-    pub publisher: ClimateNodePublisher,
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClimateNodeState {
     pub temperature: Option<f64>,
     pub humidity: Option<i64>,
     pub pressure: Option<f64>,
 }
 
+#[derive(Debug)]
+pub struct ClimateNode {
+    pub publisher: ClimateNodePublisher,
+    pub state: ClimateNodeState,
+}
+
+impl ClimateNode {
+    /// Apply a value reported by the device and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Float(value)) if p == &CLIMATE_NODE_TEMP_PROP_ID => {
+                self.state.temperature = Some(*value);
+                vec![self.publisher.temperature(*value)]
+            }
+            (p, HomieValue::Integer(value)) if p == &CLIMATE_NODE_HUM_PROP_ID => {
+                self.state.humidity = Some(*value);
+                vec![self.publisher.humidity(*value)]
+            }
+            (p, HomieValue::Float(value)) if p == &CLIMATE_NODE_PRES_PROP_ID => {
+                self.state.pressure = Some(*value);
+                vec![self.publisher.pressure(*value)]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            self.state
+                .temperature
+                .map(|value| self.publisher.temperature(value)),
+            self.state
+                .humidity
+                .map(|value| self.publisher.humidity(value)),
+            self.state
+                .pressure
+                .map(|value| self.publisher.pressure(value)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 #[serde(default)]
 pub struct ClimateNodeConfig {
     pub temperature: bool,
@@ -45,6 +100,7 @@ impl Default for ClimateNodeConfig {
 
 pub struct ClimateNodeBuilder {
     node_builder: NodeDescriptionBuilder,
+    config: ClimateNodeConfig,
 }
 
 impl ClimateNodeBuilder {
@@ -55,7 +111,10 @@ impl ClimateNodeBuilder {
         )
         .r#type(SMARTHOME_CAP_CLIMATE);
 
-        Self { node_builder: db }
+        Self {
+            node_builder: db,
+            config: config.clone(),
+        }
     }
 
     fn build_node(
@@ -93,10 +152,23 @@ impl ClimateNodeBuilder {
         self
     }
 
+    /// Append a subtype suffix to the node type (`climate-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::Climate.with_subtype(subtype));
+        self
+    }
+
     pub fn build(self) -> HomieNodeDescription {
         self.node_builder.build()
     }
 
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &ClimateNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
     pub fn build_with_publisher(
         self,
         node_id: HomieID,
@@ -111,6 +183,7 @@ impl ClimateNodeBuilder {
                     node_id,
                 ),
                 client.clone(),
+                &self.config,
             ),
         )
     }
@@ -120,15 +193,18 @@ impl ClimateNodeBuilder {
 pub struct ClimateNodePublisher {
     client: Homie5DeviceProtocol,
     node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
     temp_prop: HomieID,
     hum_prop: HomieID,
     pres_prop: HomieID,
 }
 
 impl ClimateNodePublisher {
-    pub fn new(node: NodeRef, client: Homie5DeviceProtocol) -> Self {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: &ClimateNodeConfig) -> Self {
+        let metadata = property_metadata(&ClimateNodeBuilder::new(config).build());
         Self {
             node,
+            metadata,
             client,
             temp_prop: CLIMATE_NODE_TEMP_PROP_ID,
             hum_prop: CLIMATE_NODE_HUM_PROP_ID,
@@ -159,3 +235,25 @@ impl ClimateNodePublisher {
         )
     }
 }
+
+impl SmarthomePublisher for ClimateNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::Climate
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![
+            self.temp_prop.clone(),
+            self.hum_prop.clone(),
+            self.pres_prop.clone(),
+        ]
+    }
+}
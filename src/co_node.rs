@@ -1,53 +1,135 @@
+use std::collections::BTreeMap;
+
 use homie5::{
-    Homie5DeviceProtocol, HomieID, NodeRef,
+    Homie5DeviceProtocol, HomieID, HomieValue, NodeRef,
     device_description::{
-        HomieNodeDescription, NodeDescriptionBuilder, PropertyDescriptionBuilder,
+        HomieNodeDescription, IntegerRange, NodeDescriptionBuilder, PropertyDescriptionBuilder,
     },
 };
+use serde::{Deserialize, Serialize};
 
-use crate::SMARTHOME_CAP_CO;
+use crate::{
+    PropertyMetadata, SMARTHOME_CAP_CO, SmarthomePublisher, SmarthomeType, property_metadata,
+    smarthome_node_config,
+};
 
 pub const CO_NODE_DEFAULT_ID: HomieID = HomieID::new_const("co");
 pub const CO_NODE_DEFAULT_NAME: &str = "Carbon monoxide detector";
 pub const CO_NODE_DETECTED_PROP_ID: HomieID = HomieID::new_const("detected");
+pub const CO_NODE_LEVEL_PROP_ID: HomieID = HomieID::new_const("level");
 
 // ── Node (state) ────────────────────────────────────────────────────────────
 
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CoNodeState {
+    pub detected: bool,
+    pub level: Option<i64>,
+}
+
 #[derive(Debug)]
 pub struct CoNode {
     pub publisher: CoNodePublisher,
-    pub detected: bool,
+    pub state: CoNodeState,
+}
+
+impl CoNode {
+    /// Apply a value reported by the device and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Bool(value)) if p == &CO_NODE_DETECTED_PROP_ID => {
+                self.state.detected = *value;
+                vec![self.publisher.detected(*value)]
+            }
+            (p, HomieValue::Integer(value)) if p == &CO_NODE_LEVEL_PROP_ID => {
+                self.state.level = Some(*value);
+                self.publisher.level(*value).into_iter().collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            Some(self.publisher.detected(self.state.detected)),
+            self.state
+                .level
+                .and_then(|value| self.publisher.level(value)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+// ── Config ──────────────────────────────────────────────────────────────────
+
+smarthome_node_config! {
+    #[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+    #[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+    #[serde(default)]
+    pub struct CoNodeConfig for CoNodePublisher {
+        /// Expose the measured CO concentration in ppm.
+        pub level: bool => CO_NODE_LEVEL_PROP_ID, level(i64), |_config| {
+            PropertyDescriptionBuilder::integer()
+                .name("CO level")
+                .unit("ppm")
+                .integer_range(IntegerRange {
+                    min: Some(0),
+                    max: None,
+                    step: None,
+                })
+                .settable(false)
+                .retained(true)
+                .build()
+        },
+    }
 }
 
 // ── Builder ─────────────────────────────────────────────────────────────────
 
 pub struct CoNodeBuilder {
     node_builder: NodeDescriptionBuilder,
+    config: CoNodeConfig,
 }
 
 impl Default for CoNodeBuilder {
     fn default() -> Self {
-        let db = Self::build_node(NodeDescriptionBuilder::new().name(CO_NODE_DEFAULT_NAME))
-            .r#type(SMARTHOME_CAP_CO);
-
-        Self { node_builder: db }
+        Self::new(&CoNodeConfig::default())
     }
 }
 
 impl CoNodeBuilder {
-    pub fn new() -> Self {
-        Default::default()
+    pub fn new(config: &CoNodeConfig) -> Self {
+        let db = Self::build_node(
+            NodeDescriptionBuilder::new().name(CO_NODE_DEFAULT_NAME),
+            config,
+        )
+        .r#type(SMARTHOME_CAP_CO);
+
+        Self {
+            node_builder: db,
+            config: config.clone(),
+        }
     }
 
-    fn build_node(db: NodeDescriptionBuilder) -> NodeDescriptionBuilder {
-        db.add_property(
-            CO_NODE_DETECTED_PROP_ID,
-            PropertyDescriptionBuilder::boolean()
-                .name("CO detected")
-                .boolean_labels("clear", "co detected")
-                .settable(false)
-                .retained(true)
-                .build(),
+    fn build_node(db: NodeDescriptionBuilder, config: &CoNodeConfig) -> NodeDescriptionBuilder {
+        config.add_optional_properties(
+            db.add_property(
+                CO_NODE_DETECTED_PROP_ID,
+                PropertyDescriptionBuilder::boolean()
+                    .name("CO detected")
+                    .boolean_labels("clear", "co detected")
+                    .settable(false)
+                    .retained(true)
+                    .build(),
+            ),
         )
     }
 
@@ -56,10 +138,23 @@ impl CoNodeBuilder {
         self
     }
 
+    /// Append a subtype suffix to the node type (`co-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::Co.with_subtype(subtype));
+        self
+    }
+
     pub fn build(self) -> HomieNodeDescription {
         self.node_builder.build()
     }
 
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &CoNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
     pub fn build_with_publisher(
         self,
         node_id: HomieID,
@@ -74,6 +169,7 @@ impl CoNodeBuilder {
                     node_id,
                 ),
                 client.clone(),
+                self.config,
             ),
         )
     }
@@ -85,15 +181,22 @@ impl CoNodeBuilder {
 pub struct CoNodePublisher {
     client: Homie5DeviceProtocol,
     node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
+    config: CoNodeConfig,
     detected_prop: HomieID,
+    level_prop: HomieID,
 }
 
 impl CoNodePublisher {
-    pub fn new(node: NodeRef, client: Homie5DeviceProtocol) -> Self {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: CoNodeConfig) -> Self {
+        let metadata = property_metadata(&CoNodeBuilder::new(&config).build());
         Self {
             node,
+            metadata,
             client,
+            config,
             detected_prop: CO_NODE_DETECTED_PROP_ID,
+            level_prop: CO_NODE_LEVEL_PROP_ID,
         }
     }
 
@@ -106,3 +209,21 @@ impl CoNodePublisher {
         )
     }
 }
+
+impl SmarthomePublisher for CoNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::Co
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![self.detected_prop.clone(), self.level_prop.clone()]
+    }
+}
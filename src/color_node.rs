@@ -7,36 +7,262 @@ use homie5::{
     },
 };
 use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, str::FromStr};
 
-use crate::{ParseError, ParseErrorKind, ParseOutcome, SMARTHOME_CAP_COLOR, SetCommandParser};
+use crate::{
+    ParseError, ParseErrorKind, ParseOutcome, PropertyMetadata, SMARTHOME_CAP_COLOR,
+    SetCommandParser, SmarthomePublisher, SmarthomeType, property_metadata, readonly_properties,
+    reject_readonly,
+};
 
 pub const COLOR_NODE_DEFAULT_ID: HomieID = HomieID::new_const("color");
 pub const COLOR_NODE_DEFAULT_NAME: &str = "Color control";
 pub const COLOR_NODE_COLOR_PROP_ID: HomieID = HomieID::new_const("color");
 pub const COLOR_NODE_COLOR_TEMP_PROP_ID: HomieID = HomieID::new_const("color-temperature");
+pub const COLOR_NODE_GRADIENT_PROP_ID: HomieID = HomieID::new_const("gradient");
 
-#[derive(Debug)]
-pub struct ColorNode {
-    pub publisher: ColorNodePublisher,
+/// Separator between the zone colors of the packed `gradient` property.
+pub const COLOR_NODE_GRADIENT_SEPARATOR: char = ';';
+
+/// Property ID of the color property for a (1-based) gradient zone, e.g.
+/// `gradient-1`.
+pub fn color_node_gradient_zone_prop_id(zone: u8) -> HomieID {
+    HomieID::try_from(format!("{}-{}", COLOR_NODE_GRADIENT_PROP_ID, zone))
+        .expect("indexed gradient id is always a valid HomieID")
+}
+
+/// Join zone colors into the payload of the packed `gradient` property,
+/// e.g. `rgb,255,0,0;rgb,0,0,255`.
+pub fn pack_gradient(colors: &[HomieColorValue]) -> String {
+    colors
+        .iter()
+        .map(|color| color.to_string())
+        .collect::<Vec<_>>()
+        .join(&COLOR_NODE_GRADIENT_SEPARATOR.to_string())
+}
+
+/// Split a packed `gradient` payload into the zone colors. Returns `None` if
+/// any of the colors is invalid.
+pub fn unpack_gradient(value: &str) -> Option<Vec<HomieColorValue>> {
+    value
+        .split(COLOR_NODE_GRADIENT_SEPARATOR)
+        .map(|color| HomieColorValue::from_str(color.trim()).ok())
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ColorNodeState {
     pub color: HomieColorValue,
     pub color_target: HomieColorValue,
     pub color_temperature: i64,
     pub color_temperature_target: i64,
+    /// Gradient zone colors, `gradient[0]` being zone 1.
+    pub gradient: Vec<HomieColorValue>,
+}
+
+impl Default for ColorNodeState {
+    /// White at the lowest color temperature of the default config.
+    fn default() -> Self {
+        Self {
+            color: HomieColorValue::RGB(255, 255, 255),
+            color_target: HomieColorValue::RGB(255, 255, 255),
+            color_temperature: 153,
+            color_temperature_target: 153,
+            gradient: Vec::new(),
+        }
+    }
 }
 
 #[derive(Debug)]
+pub struct ColorNode {
+    pub publisher: ColorNodePublisher,
+    pub state: ColorNodeState,
+}
+
+impl ColorNode {
+    /// Apply a parsed set command and return the `$target` and value publishes.
+    pub fn apply_set(&mut self, event: ColorNodeSetEvents) -> Vec<homie5::client::Publish> {
+        match event {
+            ColorNodeSetEvents::Color(value) => {
+                self.state.color_target = value;
+                self.state.color = value;
+                vec![
+                    self.publisher.color_target(value),
+                    self.publisher.color(value),
+                ]
+            }
+            ColorNodeSetEvents::ColorTemperature(value) => {
+                self.state.color_temperature_target = value;
+                self.state.color_temperature = value;
+                vec![
+                    self.publisher.color_temperature_target(value),
+                    self.publisher.color_temperature(value),
+                ]
+            }
+            ColorNodeSetEvents::GradientZone(zone, value) => {
+                let Some(index) = usize::from(zone).checked_sub(1) else {
+                    return Vec::new();
+                };
+                if self.state.gradient.len() <= index {
+                    self.state
+                        .gradient
+                        .resize(index + 1, HomieColorValue::RGB(0, 0, 0));
+                }
+                self.state.gradient[index] = value;
+                [
+                    self.publisher.gradient_zone_target(zone, value),
+                    self.publisher.gradient_zone(zone, value),
+                ]
+                .into_iter()
+                .flatten()
+                .collect()
+            }
+            ColorNodeSetEvents::Gradient(colors) => {
+                self.state.gradient = colors;
+                [
+                    self.publisher.gradient_target(&self.state.gradient),
+                    self.publisher.gradient(&self.state.gradient),
+                ]
+                .into_iter()
+                .flatten()
+                .collect()
+            }
+        }
+    }
+
+    /// Apply a value reported by the device and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Color(value)) if p == &COLOR_NODE_COLOR_PROP_ID => {
+                self.state.color = *value;
+                vec![self.publisher.color(*value)]
+            }
+            (p, HomieValue::Integer(value)) if p == &COLOR_NODE_COLOR_TEMP_PROP_ID => {
+                self.state.color_temperature = *value;
+                vec![self.publisher.color_temperature(*value)]
+            }
+            (p, HomieValue::String(value)) if p == &COLOR_NODE_GRADIENT_PROP_ID => {
+                let Some(colors) = unpack_gradient(value) else {
+                    return Vec::new();
+                };
+                self.state.gradient = colors;
+                self.publisher
+                    .gradient(&self.state.gradient)
+                    .into_iter()
+                    .collect()
+            }
+            (p, HomieValue::Color(value)) => {
+                let Some(zone) = self.publisher.gradient_zone_of(p) else {
+                    return Vec::new();
+                };
+                let index = usize::from(zone) - 1;
+                if self.state.gradient.len() <= index {
+                    self.state
+                        .gradient
+                        .resize(index + 1, HomieColorValue::RGB(0, 0, 0));
+                }
+                self.state.gradient[index] = *value;
+                self.publisher
+                    .gradient_zone(zone, *value)
+                    .into_iter()
+                    .collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        let gradient: Vec<_> = if self.publisher.config.gradient_packed {
+            self.publisher
+                .gradient(&self.state.gradient)
+                .into_iter()
+                .collect()
+        } else {
+            (1..=self.publisher.config.gradient_zones)
+                .zip(self.state.gradient.iter())
+                .filter_map(|(zone, color)| self.publisher.gradient_zone(zone, *color))
+                .collect()
+        };
+        [
+            Some(self.publisher.color(self.state.color)),
+            Some(self.publisher.color_target(self.state.color_target)),
+            Some(
+                self.publisher
+                    .color_temperature(self.state.color_temperature),
+            ),
+            Some(
+                self.publisher
+                    .color_temperature_target(self.state.color_temperature_target),
+            ),
+        ]
+        .into_iter()
+        .flatten()
+        .chain(gradient)
+        .collect()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 pub enum ColorNodeSetEvents {
-    Color(HomieColorValue),
+    Color(
+        #[cfg_attr(
+            feature = "proptest",
+            proptest(strategy = "crate::arbitrary::color_value()")
+        )]
+        HomieColorValue,
+    ),
     ColorTemperature(i64),
+    /// Color of the given 1-based gradient zone.
+    GradientZone(
+        u8,
+        #[cfg_attr(
+            feature = "proptest",
+            proptest(strategy = "crate::arbitrary::color_value()")
+        )]
+        HomieColorValue,
+    ),
+    /// Colors of all gradient zones, from the packed `gradient` property.
+    Gradient(
+        #[cfg_attr(
+            feature = "proptest",
+            proptest(strategy = "crate::arbitrary::color_values()")
+        )]
+        Vec<HomieColorValue>,
+    ),
 }
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 #[serde(default)]
 pub struct ColorNodeConfig {
     pub settable: bool,
+    #[cfg_attr(
+        feature = "proptest",
+        proptest(strategy = "crate::arbitrary::color_formats()")
+    )]
     pub color_formats: Vec<ColorFormat>,
     pub ctmin: i64,
     pub ctmax: i64,
+    /// Number of independently colored zones of a gradient light, e.g. a
+    /// gradient light strip; `0` for a single-color light.
+    pub gradient_zones: u8,
+    /// Expose the gradient as a single packed `gradient` property instead of
+    /// one `gradient-<n>` color property per zone.
+    pub gradient_packed: bool,
+    /// Properties to publish as read-only; sets to them are rejected.
+    #[cfg_attr(
+        feature = "proptest",
+        proptest(strategy = "crate::arbitrary::homie_ids()")
+    )]
+    pub readonly: Vec<HomieID>,
 }
 
 impl Default for ColorNodeConfig {
@@ -46,12 +272,16 @@ impl Default for ColorNodeConfig {
             color_formats: vec![ColorFormat::Rgb],
             ctmin: 153,
             ctmax: 555,
+            gradient_zones: 0,
+            gradient_packed: false,
+            readonly: Vec::new(),
         }
     }
 }
 
 pub struct ColorNodeBuilder {
     node_builder: NodeDescriptionBuilder,
+    config: ColorNodeConfig,
 }
 
 impl ColorNodeBuilder {
@@ -61,33 +291,63 @@ impl ColorNodeBuilder {
             config,
         )
         .r#type(SMARTHOME_CAP_COLOR);
+        let db = readonly_properties(db, &config.readonly);
 
-        Self { node_builder: db }
+        Self {
+            node_builder: db,
+            config: config.clone(),
+        }
     }
 
     fn build_node(db: NodeDescriptionBuilder, config: &ColorNodeConfig) -> NodeDescriptionBuilder {
-        db.add_property(
-            COLOR_NODE_COLOR_PROP_ID,
-            PropertyDescriptionBuilder::color(config.color_formats.clone())
-                .unwrap()
-                .name("Color")
-                .settable(config.settable)
-                .retained(true)
-                .build(),
-        )
-        .add_property(
-            COLOR_NODE_COLOR_TEMP_PROP_ID,
-            PropertyDescriptionBuilder::integer()
-                .name("Color temperature")
-                .integer_range(IntegerRange {
-                    min: Some(config.ctmin),
-                    max: Some(config.ctmax),
-                    step: None,
-                })
-                .settable(config.settable)
-                .retained(true)
-                .build(),
-        )
+        let db = db
+            .add_property(
+                COLOR_NODE_COLOR_PROP_ID,
+                PropertyDescriptionBuilder::color(config.color_formats.clone())
+                    .unwrap()
+                    .name("Color")
+                    .settable(config.settable)
+                    .retained(true)
+                    .build(),
+            )
+            .add_property(
+                COLOR_NODE_COLOR_TEMP_PROP_ID,
+                PropertyDescriptionBuilder::integer()
+                    .name("Color temperature")
+                    .integer_range(IntegerRange {
+                        min: Some(config.ctmin),
+                        max: Some(config.ctmax),
+                        step: None,
+                    })
+                    .settable(config.settable)
+                    .retained(true)
+                    .build(),
+            )
+            .add_property_cond(
+                COLOR_NODE_GRADIENT_PROP_ID,
+                config.gradient_packed && config.gradient_zones > 0,
+                || {
+                    PropertyDescriptionBuilder::string()
+                        .name("Gradient")
+                        .settable(config.settable)
+                        .retained(true)
+                        .build()
+                },
+            );
+        if config.gradient_packed {
+            return db;
+        }
+        (1..=config.gradient_zones).fold(db, |db, zone| {
+            db.add_property(
+                color_node_gradient_zone_prop_id(zone),
+                PropertyDescriptionBuilder::color(config.color_formats.clone())
+                    .unwrap()
+                    .name(format!("Gradient zone {} color", zone))
+                    .settable(config.settable)
+                    .retained(true)
+                    .build(),
+            )
+        })
     }
 
     pub fn name<S: Into<String>>(mut self, name: impl Into<Option<S>>) -> Self {
@@ -95,10 +355,23 @@ impl ColorNodeBuilder {
         self
     }
 
+    /// Append a subtype suffix to the node type (`color-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::Color.with_subtype(subtype));
+        self
+    }
+
     pub fn build(self) -> HomieNodeDescription {
         self.node_builder.build()
     }
 
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &ColorNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
     pub fn build_with_publisher(
         self,
         node_id: HomieID,
@@ -113,6 +386,7 @@ impl ColorNodeBuilder {
                     node_id,
                 ),
                 client.clone(),
+                self.config,
             ),
         )
     }
@@ -122,20 +396,49 @@ impl ColorNodeBuilder {
 pub struct ColorNodePublisher {
     client: Homie5DeviceProtocol,
     node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
     color_prop_id: HomieID,
     color_temp_prop_id: HomieID,
+    gradient_prop_id: HomieID,
+    gradient_zone_prop_ids: Vec<HomieID>,
+    config: ColorNodeConfig,
 }
 
 impl ColorNodePublisher {
-    pub fn new(node: NodeRef, client: Homie5DeviceProtocol) -> Self {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: ColorNodeConfig) -> Self {
+        let metadata = property_metadata(&ColorNodeBuilder::new(&config).build());
+        let gradient_zone_prop_ids = if config.gradient_packed {
+            Vec::new()
+        } else {
+            (1..=config.gradient_zones)
+                .map(color_node_gradient_zone_prop_id)
+                .collect()
+        };
         Self {
             node,
+            metadata,
             client,
             color_prop_id: COLOR_NODE_COLOR_PROP_ID,
             color_temp_prop_id: COLOR_NODE_COLOR_TEMP_PROP_ID,
+            gradient_prop_id: COLOR_NODE_GRADIENT_PROP_ID,
+            gradient_zone_prop_ids,
+            config,
         }
     }
 
+    fn gradient_zone_prop(&self, zone: u8) -> Option<&HomieID> {
+        self.gradient_zone_prop_ids
+            .get(usize::from(zone).checked_sub(1)?)
+    }
+
+    /// The 1-based gradient zone `property` is the color of, if any.
+    fn gradient_zone_of(&self, property: &HomieID) -> Option<u8> {
+        self.gradient_zone_prop_ids
+            .iter()
+            .position(|prop| prop == property)
+            .map(|index| index as u8 + 1)
+    }
+
     pub fn color(&self, value: HomieColorValue) -> homie5::client::Publish {
         self.client
             .publish_value(self.node.node_id(), &self.color_prop_id, value, true)
@@ -163,6 +466,82 @@ impl ColorNodePublisher {
             true,
         )
     }
+
+    /// Publish the color of the given 1-based gradient zone. Returns `None`
+    /// for zones outside of `1..=gradient_zones` and for packed gradients.
+    pub fn gradient_zone(
+        &self,
+        zone: u8,
+        value: HomieColorValue,
+    ) -> Option<homie5::client::Publish> {
+        let prop = self.gradient_zone_prop(zone)?;
+        Some(
+            self.client
+                .publish_value(self.node.node_id(), prop, value, true),
+        )
+    }
+
+    pub fn gradient_zone_target(
+        &self,
+        zone: u8,
+        value: HomieColorValue,
+    ) -> Option<homie5::client::Publish> {
+        let prop = self.gradient_zone_prop(zone)?;
+        Some(
+            self.client
+                .publish_target(self.node.node_id(), prop, value, true),
+        )
+    }
+
+    /// Publish all zone colors on the packed `gradient` property. Returns
+    /// `None` unless the light is configured with `gradient_packed`.
+    pub fn gradient(&self, colors: &[HomieColorValue]) -> Option<homie5::client::Publish> {
+        if !self.config.gradient_packed || self.config.gradient_zones == 0 {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.gradient_prop_id,
+            pack_gradient(colors),
+            true,
+        ))
+    }
+
+    pub fn gradient_target(&self, colors: &[HomieColorValue]) -> Option<homie5::client::Publish> {
+        if !self.config.gradient_packed || self.config.gradient_zones == 0 {
+            return None;
+        }
+        Some(self.client.publish_target(
+            self.node.node_id(),
+            &self.gradient_prop_id,
+            pack_gradient(colors),
+            true,
+        ))
+    }
+}
+
+impl SmarthomePublisher for ColorNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::Color
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        let mut ids = vec![
+            self.color_prop_id.clone(),
+            self.color_temp_prop_id.clone(),
+            self.gradient_prop_id.clone(),
+        ];
+        ids.extend(self.gradient_zone_prop_ids.iter().cloned());
+        ids
+    }
 }
 
 impl SetCommandParser for ColorNodePublisher {
@@ -174,6 +553,10 @@ impl SetCommandParser for ColorNodePublisher {
         desc: &HomieDeviceDescription,
         set_value: &str,
     ) -> ParseOutcome<Self::Event> {
+        if let Some(outcome) = reject_readonly(&self.node, property, desc, set_value) {
+            return outcome;
+        }
+
         let property_id = property.prop_id().to_string();
 
         if property.match_with_node(&self.node, &self.color_prop_id) {
@@ -218,6 +601,48 @@ impl SetCommandParser for ColorNodePublisher {
                     ParseErrorKind::InvalidHomieValue,
                 )),
             }
+        } else if property.match_with_node(&self.node, &self.gradient_prop_id) {
+            match unpack_gradient(set_value) {
+                Some(colors)
+                    if colors.len() == usize::from(self.config.gradient_zones)
+                        && colors
+                            .iter()
+                            .all(|c| self.config.color_formats.contains(&c.color_format())) =>
+                {
+                    ParseOutcome::Parsed(ColorNodeSetEvents::Gradient(colors))
+                }
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else if let Some(zone) = self
+            .gradient_zone_prop_ids
+            .iter()
+            .position(|prop| property.match_with_node(&self.node, prop))
+            .map(|index| index as u8 + 1)
+        {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Color(value)) => {
+                    ParseOutcome::Parsed(ColorNodeSetEvents::GradientZone(zone, value))
+                }
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
         } else {
             ParseOutcome::NoMatch
         }
@@ -241,3 +666,84 @@ impl SetCommandParser for ColorNodePublisher {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use homie5::HomieDomain;
+
+    use super::*;
+    use crate::StandaloneSetParser;
+
+    #[test]
+    fn gradient_zones_keep_their_own_colors() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let config = ColorNodeConfig {
+            gradient_zones: 3,
+            ..ColorNodeConfig::default()
+        };
+        assert_eq!(ColorNodeBuilder::property_ids(&config).len(), 5);
+        let parser: StandaloneSetParser<_> = ColorNodeBuilder::new(&config)
+            .build_with_publisher(COLOR_NODE_DEFAULT_ID, &client)
+            .into();
+        let zone = PropertyRef::from_node(
+            parser.publisher().node_ref().clone(),
+            color_node_gradient_zone_prop_id(2),
+        );
+
+        let ParseOutcome::Parsed(event) = parser.parse_set(&zone, "rgb,0,0,255") else {
+            panic!("zone color must parse");
+        };
+        assert_eq!(
+            event,
+            ColorNodeSetEvents::GradientZone(2, HomieColorValue::RGB(0, 0, 255))
+        );
+
+        let mut node = ColorNode {
+            publisher: parser.into_publisher(),
+            state: ColorNodeState::default(),
+        };
+        assert_eq!(node.apply_set(event).len(), 2);
+        assert_eq!(
+            node.state.gradient,
+            vec![
+                HomieColorValue::RGB(0, 0, 0),
+                HomieColorValue::RGB(0, 0, 255)
+            ]
+        );
+        assert!(node.publisher.gradient(&node.state.gradient).is_none());
+    }
+
+    #[test]
+    fn packed_gradient_round_trips() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let config = ColorNodeConfig {
+            gradient_zones: 2,
+            gradient_packed: true,
+            ..ColorNodeConfig::default()
+        };
+        let parser: StandaloneSetParser<_> = ColorNodeBuilder::new(&config)
+            .build_with_publisher(COLOR_NODE_DEFAULT_ID, &client)
+            .into();
+        let gradient = PropertyRef::from_node(
+            parser.publisher().node_ref().clone(),
+            COLOR_NODE_GRADIENT_PROP_ID,
+        );
+        let colors = vec![
+            HomieColorValue::RGB(255, 0, 0),
+            HomieColorValue::RGB(0, 0, 255),
+        ];
+        assert_eq!(pack_gradient(&colors), "rgb,255,0,0;rgb,0,0,255");
+
+        assert_eq!(
+            parser.parse_set(&gradient, &pack_gradient(&colors)),
+            ParseOutcome::Parsed(ColorNodeSetEvents::Gradient(colors))
+        );
+        // one color for a two-zone gradient
+        assert!(matches!(
+            parser.parse_set(&gradient, "rgb,255,0,0"),
+            ParseOutcome::Invalid(_)
+        ));
+    }
+}
@@ -1,49 +1,236 @@
+use chrono::prelude::*;
+use std::collections::BTreeMap;
+
 use homie5::{
-    Homie5DeviceProtocol, HomieID, NodeRef,
+    Homie5DeviceProtocol, HomieID, HomieValue, NodeRef,
     device_description::{
         HomieNodeDescription, NodeDescriptionBuilder, PropertyDescriptionBuilder,
     },
 };
+use serde::{Deserialize, Serialize};
 
-use crate::SMARTHOME_CAP_CONTACT;
+use crate::{
+    PropertyMetadata, SMARTHOME_CAP_CONTACT, SmarthomePublisher, SmarthomeType, property_metadata,
+    smarthome_node_config,
+};
 
 pub const CONTACT_NODE_DEFAULT_ID: HomieID = HomieID::new_const("contact");
 pub const CONTACT_NODE_DEFAULT_NAME: &str = "Open/Close contact";
 pub const CONTACT_NODE_STATE_PROP_ID: HomieID = HomieID::new_const("state");
+pub const CONTACT_NODE_TAMPER_PROP_ID: HomieID = HomieID::new_const("tamper");
+pub const CONTACT_NODE_LAST_OPENED_PROP_ID: HomieID = HomieID::new_const("last-opened");
+pub const CONTACT_NODE_LAST_CLOSED_PROP_ID: HomieID = HomieID::new_const("last-closed");
+pub const CONTACT_NODE_OPEN_TOO_LONG_PROP_ID: HomieID = HomieID::new_const("open-too-long");
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ContactNodeState {
+    pub state: bool,
+    pub tamper: Option<bool>,
+    pub last_opened: Option<DateTime<Utc>>,
+    pub last_closed: Option<DateTime<Utc>>,
+    pub open_too_long: Option<bool>,
+    /// Since when the contact is open, the start of the `open-too-long` timer.
+    pub opened_at: Option<DateTime<Utc>>,
+}
 
 #[derive(Debug)]
 pub struct ContactNode {
     pub publisher: ContactNodePublisher,
-    pub state: bool,
+    pub state: ContactNodeState,
+}
+
+impl ContactNode {
+    /// Apply a value reported by the device and return the matching publishes.
+    /// `state` changes go through [`Self::state_changed`] at the current system
+    /// time; call that directly to supply the time of the change.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Bool(value)) if p == &CONTACT_NODE_STATE_PROP_ID => {
+                self.state_changed(*value, Utc::now())
+            }
+            (p, HomieValue::Bool(value)) if p == &CONTACT_NODE_TAMPER_PROP_ID => {
+                self.state.tamper = Some(*value);
+                self.publisher.tamper(*value).into_iter().collect()
+            }
+            (p, HomieValue::DateTime(value)) if p == &CONTACT_NODE_LAST_OPENED_PROP_ID => {
+                self.state.last_opened = Some(*value);
+                self.publisher.last_opened(*value).into_iter().collect()
+            }
+            (p, HomieValue::DateTime(value)) if p == &CONTACT_NODE_LAST_CLOSED_PROP_ID => {
+                self.state.last_closed = Some(*value);
+                self.publisher.last_closed(*value).into_iter().collect()
+            }
+            (p, HomieValue::Bool(value)) if p == &CONTACT_NODE_OPEN_TOO_LONG_PROP_ID => {
+                self.state.open_too_long = Some(*value);
+                self.publisher.open_too_long(*value).into_iter().collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            Some(self.publisher.state(self.state.state)),
+            self.state
+                .tamper
+                .and_then(|value| self.publisher.tamper(value)),
+            self.state
+                .last_opened
+                .and_then(|value| self.publisher.last_opened(value)),
+            self.state
+                .last_closed
+                .and_then(|value| self.publisher.last_closed(value)),
+            self.state
+                .open_too_long
+                .and_then(|value| self.publisher.open_too_long(value)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    /// Record a state change and publish it together with the matching
+    /// `last-opened` or `last-closed` timestamp, if enabled.
+    ///
+    /// Also tracks since when the contact is open: closing it clears a raised
+    /// `open-too-long` alert, which is raised by
+    /// [`Self::poll_open_too_long`] once `max_open_duration` has elapsed.
+    pub fn state_changed(
+        &mut self,
+        value: bool,
+        at: DateTime<Utc>,
+    ) -> Vec<homie5::client::Publish> {
+        let mut publishes = self.publisher.state_changed(value, at);
+        self.state.state = value;
+        if value {
+            self.state.last_opened = Some(at);
+            self.state.opened_at.get_or_insert(at);
+        } else {
+            self.state.last_closed = Some(at);
+            self.state.opened_at = None;
+            if self.state.open_too_long == Some(true) {
+                self.state.open_too_long = Some(false);
+                publishes.extend(self.publisher.open_too_long(false));
+            }
+        }
+        publishes
+    }
+
+    /// The point in time at which the `open-too-long` alert will be raised,
+    /// if it is pending.
+    pub fn open_too_long_deadline(&self) -> Option<DateTime<Utc>> {
+        if self.state.open_too_long == Some(true) {
+            return None;
+        }
+        let max = chrono::TimeDelta::try_seconds(self.publisher.config.max_open_duration?.into())?;
+        self.state.opened_at?.checked_add_signed(max)
+    }
+
+    /// Produce the `open-too-long` alert once the contact has been open for
+    /// `max_open_duration`.
+    pub fn poll_open_too_long(&mut self, now: DateTime<Utc>) -> Option<homie5::client::Publish> {
+        let deadline = self.open_too_long_deadline()?;
+        if now < deadline {
+            return None;
+        }
+        self.state.open_too_long = Some(true);
+        self.publisher.open_too_long(true)
+    }
+}
+
+smarthome_node_config! {
+    #[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+    #[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+    #[serde(default)]
+    pub struct ContactNodeConfig for ContactNodePublisher {
+        pub tamper: bool => CONTACT_NODE_TAMPER_PROP_ID, tamper(bool), |_config| {
+            PropertyDescriptionBuilder::boolean()
+                .name("Tamper")
+                .boolean_labels("ok", "tampered")
+                .settable(false)
+                .retained(true)
+                .build()
+        },
+        pub last_opened: bool,
+        pub last_closed: bool,
+        /// Seconds the contact may stay open before the derived `open-too-long`
+        /// alert turns true; `None` to omit the property.
+        pub max_open_duration: Option<u32>,
+    }
 }
 
 pub struct ContactNodeBuilder {
     node_builder: NodeDescriptionBuilder,
+    config: ContactNodeConfig,
 }
 
 impl Default for ContactNodeBuilder {
     fn default() -> Self {
-        let db = Self::build_node(NodeDescriptionBuilder::new().name(CONTACT_NODE_DEFAULT_NAME))
-            .r#type(SMARTHOME_CAP_CONTACT);
-
-        Self { node_builder: db }
+        Self::new(&ContactNodeConfig::default())
     }
 }
 
 impl ContactNodeBuilder {
-    pub fn new() -> Self {
-        Default::default()
+    pub fn new(config: &ContactNodeConfig) -> Self {
+        let db = Self::build_node(
+            NodeDescriptionBuilder::new().name(CONTACT_NODE_DEFAULT_NAME),
+            config,
+        )
+        .r#type(SMARTHOME_CAP_CONTACT);
+
+        Self {
+            node_builder: db,
+            config: config.clone(),
+        }
     }
 
-    fn build_node(db: NodeDescriptionBuilder) -> NodeDescriptionBuilder {
-        db.add_property(
-            CONTACT_NODE_STATE_PROP_ID,
-            PropertyDescriptionBuilder::boolean()
-                .name("Open/Close state")
-                .boolean_labels("closed", "open")
-                .settable(false)
-                .retained(true)
-                .build(),
+    fn build_node(
+        db: NodeDescriptionBuilder,
+        config: &ContactNodeConfig,
+    ) -> NodeDescriptionBuilder {
+        config.add_optional_properties(
+            db.add_property(
+                CONTACT_NODE_STATE_PROP_ID,
+                PropertyDescriptionBuilder::boolean()
+                    .name("Open/Close state")
+                    .boolean_labels("closed", "open")
+                    .settable(false)
+                    .retained(true)
+                    .build(),
+            )
+            .add_property_cond(CONTACT_NODE_LAST_OPENED_PROP_ID, config.last_opened, || {
+                PropertyDescriptionBuilder::datetime()
+                    .name("Last opened")
+                    .settable(false)
+                    .retained(true)
+                    .build()
+            })
+            .add_property_cond(CONTACT_NODE_LAST_CLOSED_PROP_ID, config.last_closed, || {
+                PropertyDescriptionBuilder::datetime()
+                    .name("Last closed")
+                    .settable(false)
+                    .retained(true)
+                    .build()
+            })
+            .add_property_cond(
+                CONTACT_NODE_OPEN_TOO_LONG_PROP_ID,
+                config.max_open_duration.is_some(),
+                || {
+                    PropertyDescriptionBuilder::boolean()
+                        .name("Open too long")
+                        .boolean_labels("ok", "alert")
+                        .settable(false)
+                        .retained(true)
+                        .build()
+                },
+            ),
         )
     }
 
@@ -52,10 +239,23 @@ impl ContactNodeBuilder {
         self
     }
 
+    /// Append a subtype suffix to the node type (`contact-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::Contact.with_subtype(subtype));
+        self
+    }
+
     pub fn build(self) -> HomieNodeDescription {
         self.node_builder.build()
     }
 
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &ContactNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
     pub fn build_with_publisher(
         self,
         node_id: HomieID,
@@ -70,6 +270,7 @@ impl ContactNodeBuilder {
                     node_id,
                 ),
                 client.clone(),
+                self.config,
             ),
         )
     }
@@ -79,15 +280,28 @@ impl ContactNodeBuilder {
 pub struct ContactNodePublisher {
     client: Homie5DeviceProtocol,
     node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
+    config: ContactNodeConfig,
     state_prop: HomieID,
+    tamper_prop: HomieID,
+    last_opened_prop: HomieID,
+    last_closed_prop: HomieID,
+    open_too_long_prop: HomieID,
 }
 
 impl ContactNodePublisher {
-    pub fn new(node: NodeRef, client: Homie5DeviceProtocol) -> Self {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: ContactNodeConfig) -> Self {
+        let metadata = property_metadata(&ContactNodeBuilder::new(&config).build());
         Self {
             node,
+            metadata,
             client,
+            config,
             state_prop: CONTACT_NODE_STATE_PROP_ID,
+            tamper_prop: CONTACT_NODE_TAMPER_PROP_ID,
+            last_opened_prop: CONTACT_NODE_LAST_OPENED_PROP_ID,
+            last_closed_prop: CONTACT_NODE_LAST_CLOSED_PROP_ID,
+            open_too_long_prop: CONTACT_NODE_OPEN_TOO_LONG_PROP_ID,
         }
     }
 
@@ -99,4 +313,140 @@ impl ContactNodePublisher {
             true,
         )
     }
+
+    /// Publish a state change together with the matching `last-opened` or
+    /// `last-closed` timestamp, if enabled.
+    pub fn state_changed(&self, value: bool, at: DateTime<Utc>) -> Vec<homie5::client::Publish> {
+        let timestamp = if value {
+            self.last_opened(at)
+        } else {
+            self.last_closed(at)
+        };
+        std::iter::once(self.state(value))
+            .chain(timestamp)
+            .collect()
+    }
+
+    pub fn last_opened(&self, value: DateTime<Utc>) -> Option<homie5::client::Publish> {
+        if !self.config.last_opened {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.last_opened_prop,
+            value.to_rfc3339_opts(SecondsFormat::Millis, true),
+            true,
+        ))
+    }
+
+    pub fn last_closed(&self, value: DateTime<Utc>) -> Option<homie5::client::Publish> {
+        if !self.config.last_closed {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.last_closed_prop,
+            value.to_rfc3339_opts(SecondsFormat::Millis, true),
+            true,
+        ))
+    }
+
+    pub fn open_too_long(&self, value: bool) -> Option<homie5::client::Publish> {
+        self.config.max_open_duration?;
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.open_too_long_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+}
+
+impl SmarthomePublisher for ContactNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::Contact
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![
+            self.state_prop.clone(),
+            self.tamper_prop.clone(),
+            self.last_opened_prop.clone(),
+            self.last_closed_prop.clone(),
+            self.open_too_long_prop.clone(),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use homie5::HomieDomain;
+
+    use super::*;
+
+    fn node(config: &ContactNodeConfig) -> ContactNode {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let (_, publisher) =
+            ContactNodeBuilder::new(config).build_with_publisher(CONTACT_NODE_DEFAULT_ID, &client);
+        ContactNode {
+            publisher,
+            state: ContactNodeState::default(),
+        }
+    }
+
+    #[test]
+    fn open_too_long_raises_after_max_duration_and_clears_on_close() {
+        let mut node = node(&ContactNodeConfig {
+            max_open_duration: Some(300),
+            ..ContactNodeConfig::default()
+        });
+        let opened = Utc::now();
+
+        assert!(node.open_too_long_deadline().is_none());
+        assert_eq!(node.state_changed(true, opened).len(), 1);
+        // a repeated open report keeps the original open time
+        node.state_changed(true, opened + chrono::Duration::seconds(120));
+        assert_eq!(node.state.opened_at, Some(opened));
+        assert_eq!(
+            node.open_too_long_deadline(),
+            Some(opened + chrono::Duration::seconds(300))
+        );
+        assert!(
+            node.poll_open_too_long(opened + chrono::Duration::seconds(299))
+                .is_none()
+        );
+
+        let alert = node
+            .poll_open_too_long(opened + chrono::Duration::seconds(300))
+            .expect("alert must be raised after max_open_duration");
+        assert_eq!(alert.payload, b"true".to_vec());
+        assert_eq!(node.state.open_too_long, Some(true));
+        assert!(node.open_too_long_deadline().is_none());
+
+        let publishes = node.state_changed(false, opened + chrono::Duration::seconds(400));
+        assert_eq!(publishes.len(), 2);
+        assert_eq!(publishes[1].payload, b"false".to_vec());
+        assert_eq!(node.state.open_too_long, Some(false));
+        assert_eq!(node.state.opened_at, None);
+    }
+
+    #[test]
+    fn open_too_long_deadline_does_not_overflow() {
+        let mut node = node(&ContactNodeConfig {
+            max_open_duration: Some(u32::MAX),
+            ..ContactNodeConfig::default()
+        });
+        node.state_changed(true, DateTime::<Utc>::MAX_UTC);
+        assert!(node.open_too_long_deadline().is_none());
+        assert!(node.poll_open_too_long(DateTime::<Utc>::MAX_UTC).is_none());
+    }
 }
@@ -0,0 +1,590 @@
+use core::fmt;
+use std::{collections::BTreeMap, str::FromStr};
+
+use homie5::{
+    HOMIE_UNIT_PERCENT, Homie5DeviceProtocol, Homie5Message, Homie5ProtocolError, HomieID,
+    HomieValue, NodeRef, PropertyRef,
+    device_description::{
+        HomieDeviceDescription, HomieNodeDescription, IntegerRange, NodeDescriptionBuilder,
+        PropertyDescriptionBuilder,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ParseError, ParseErrorKind, ParseOutcome, PropertyMetadata, SMARTHOME_CAP_CURTAIN,
+    SetCommandParser, SmarthomePublisher, SmarthomeType, property_metadata, readonly_properties,
+    reject_readonly,
+};
+
+pub const CURTAIN_NODE_DEFAULT_ID: HomieID = HomieID::new_const("curtain");
+pub const CURTAIN_NODE_DEFAULT_NAME: &str = "Curtain";
+pub const CURTAIN_NODE_POSITION_PROP_ID: HomieID = HomieID::new_const("position");
+pub const CURTAIN_NODE_ACTION_PROP_ID: HomieID = HomieID::new_const("action");
+pub const CURTAIN_NODE_SPEED_PROP_ID: HomieID = HomieID::new_const("speed");
+pub const CURTAIN_NODE_HAND_PULL_PROP_ID: HomieID = HomieID::new_const("hand-pull");
+
+// ── Node (state) ────────────────────────────────────────────────────────────
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CurtainNodeState {
+    pub position: i64,
+    pub speed: Option<i64>,
+}
+
+#[derive(Debug)]
+pub struct CurtainNode {
+    pub publisher: CurtainNodePublisher,
+    pub state: CurtainNodeState,
+}
+
+impl CurtainNode {
+    /// Apply a parsed set command and return the `$target` and value publishes
+    /// for the change. Actions only publish the `$target` of the position they
+    /// move to; the motor reports the reached position.
+    pub fn apply_set(&mut self, event: CurtainNodeSetEvents) -> Vec<homie5::client::Publish> {
+        match event {
+            CurtainNodeSetEvents::Position(value) => {
+                vec![self.publisher.position_target(value)]
+            }
+            CurtainNodeSetEvents::Action(CurtainNodeActions::Open) => {
+                vec![self.publisher.position_target(100)]
+            }
+            CurtainNodeSetEvents::Action(CurtainNodeActions::Close) => {
+                vec![self.publisher.position_target(0)]
+            }
+            CurtainNodeSetEvents::Action(_) => Vec::new(),
+            CurtainNodeSetEvents::Speed(value) => {
+                self.state.speed = Some(value);
+                [
+                    self.publisher.speed_target(value),
+                    self.publisher.speed(value),
+                ]
+                .into_iter()
+                .flatten()
+                .collect()
+            }
+        }
+    }
+
+    /// Apply a value reported by the motor and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Integer(value)) if p == &CURTAIN_NODE_POSITION_PROP_ID => {
+                self.state.position = *value;
+                vec![self.publisher.position(*value)]
+            }
+            (p, HomieValue::Integer(value)) if p == &CURTAIN_NODE_SPEED_PROP_ID => {
+                self.state.speed = Some(*value);
+                self.publisher.speed(*value).into_iter().collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            Some(self.publisher.position(self.state.position)),
+            self.state
+                .speed
+                .and_then(|value| self.publisher.speed(value)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+// ── Actions / set events ────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+pub enum CurtainNodeActions {
+    Open,
+    Close,
+    Stop,
+    /// Re-learn the end positions of the track.
+    Calibrate,
+}
+
+impl CurtainNodeActions {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Open => "open",
+            Self::Close => "close",
+            Self::Stop => "stop",
+            Self::Calibrate => "calibrate",
+        }
+    }
+}
+
+impl fmt::Display for CurtainNodeActions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for CurtainNodeActions {
+    type Err = Homie5ProtocolError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "open" => Ok(Self::Open),
+            "close" => Ok(Self::Close),
+            "stop" => Ok(Self::Stop),
+            "calibrate" => Ok(Self::Calibrate),
+            _ => Err(Homie5ProtocolError::InvalidPayload),
+        }
+    }
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+pub enum CurtainNodeSetEvents {
+    Position(i64),
+    Action(CurtainNodeActions),
+    Speed(i64),
+}
+
+// ── Config ──────────────────────────────────────────────────────────────────
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+#[serde(default)]
+pub struct CurtainNodeConfig {
+    /// Offer the `calibrate` action.
+    pub calibrate: bool,
+    /// Expose the motor `speed` in percent.
+    pub speed: bool,
+    /// Expose the `hand-pull` event, published when the curtain is pulled by
+    /// hand and the motor takes over.
+    pub hand_pull: bool,
+    /// Properties to publish as read-only; sets to them are rejected.
+    #[cfg_attr(
+        feature = "proptest",
+        proptest(strategy = "crate::arbitrary::homie_ids()")
+    )]
+    pub readonly: Vec<HomieID>,
+}
+
+impl Default for CurtainNodeConfig {
+    fn default() -> Self {
+        Self {
+            calibrate: false,
+            speed: false,
+            hand_pull: true,
+            readonly: Vec::new(),
+        }
+    }
+}
+
+impl CurtainNodeConfig {
+    fn actions(&self) -> Vec<CurtainNodeActions> {
+        let mut actions = vec![
+            CurtainNodeActions::Open,
+            CurtainNodeActions::Close,
+            CurtainNodeActions::Stop,
+        ];
+        if self.calibrate {
+            actions.push(CurtainNodeActions::Calibrate);
+        }
+        actions
+    }
+}
+
+// ── Builder ─────────────────────────────────────────────────────────────────
+
+pub struct CurtainNodeBuilder {
+    node_builder: NodeDescriptionBuilder,
+    config: CurtainNodeConfig,
+}
+
+impl Default for CurtainNodeBuilder {
+    fn default() -> Self {
+        Self::new(&CurtainNodeConfig::default())
+    }
+}
+
+impl CurtainNodeBuilder {
+    pub fn new(config: &CurtainNodeConfig) -> Self {
+        let db = Self::build_node(
+            NodeDescriptionBuilder::new().name(CURTAIN_NODE_DEFAULT_NAME),
+            config,
+        )
+        .r#type(SMARTHOME_CAP_CURTAIN);
+        let db = readonly_properties(db, &config.readonly);
+
+        Self {
+            node_builder: db,
+            config: config.clone(),
+        }
+    }
+
+    fn build_node(
+        db: NodeDescriptionBuilder,
+        config: &CurtainNodeConfig,
+    ) -> NodeDescriptionBuilder {
+        db.add_property(
+            CURTAIN_NODE_POSITION_PROP_ID,
+            PropertyDescriptionBuilder::integer()
+                .name("Curtain position")
+                .unit(HOMIE_UNIT_PERCENT)
+                .integer_range(IntegerRange {
+                    min: Some(0),
+                    max: Some(100),
+                    step: None,
+                })
+                .settable(true)
+                .retained(true)
+                .build(),
+        )
+        .add_property(
+            CURTAIN_NODE_ACTION_PROP_ID,
+            PropertyDescriptionBuilder::enumeration(config.actions().iter().map(|a| a.as_str()))
+                .unwrap()
+                .name("Curtain action")
+                .settable(true)
+                .retained(false)
+                .build(),
+        )
+        .add_property_cond(CURTAIN_NODE_SPEED_PROP_ID, config.speed, || {
+            PropertyDescriptionBuilder::integer()
+                .name("Motor speed")
+                .unit(HOMIE_UNIT_PERCENT)
+                .integer_range(IntegerRange {
+                    min: Some(0),
+                    max: Some(100),
+                    step: None,
+                })
+                .settable(true)
+                .retained(true)
+                .build()
+        })
+        .add_property_cond(CURTAIN_NODE_HAND_PULL_PROP_ID, config.hand_pull, || {
+            PropertyDescriptionBuilder::enumeration(["pulled"])
+                .unwrap()
+                .name("Hand pull detected")
+                .settable(false)
+                .retained(false)
+                .build()
+        })
+    }
+
+    pub fn name<S: Into<String>>(mut self, name: impl Into<Option<S>>) -> Self {
+        self.node_builder = self.node_builder.name(name);
+        self
+    }
+
+    /// Append a subtype suffix to the node type (`curtain-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::Curtain.with_subtype(subtype));
+        self
+    }
+
+    pub fn build(self) -> HomieNodeDescription {
+        self.node_builder.build()
+    }
+
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &CurtainNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
+    pub fn build_with_publisher(
+        self,
+        node_id: HomieID,
+        client: &Homie5DeviceProtocol,
+    ) -> (HomieNodeDescription, CurtainNodePublisher) {
+        (
+            self.node_builder.build(),
+            CurtainNodePublisher::new(
+                NodeRef::new(
+                    client.homie_domain().to_owned(),
+                    client.id().clone(),
+                    node_id,
+                ),
+                client.clone(),
+                self.config,
+            ),
+        )
+    }
+}
+
+// ── Publisher ────────────────────────────────────────────────────────────────
+
+#[derive(Debug)]
+pub struct CurtainNodePublisher {
+    client: Homie5DeviceProtocol,
+    node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
+    config: CurtainNodeConfig,
+    position_prop: HomieID,
+    action_prop: HomieID,
+    speed_prop: HomieID,
+    hand_pull_prop: HomieID,
+}
+
+impl CurtainNodePublisher {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: CurtainNodeConfig) -> Self {
+        let metadata = property_metadata(&CurtainNodeBuilder::new(&config).build());
+        Self {
+            node,
+            metadata,
+            client,
+            config,
+            position_prop: CURTAIN_NODE_POSITION_PROP_ID,
+            action_prop: CURTAIN_NODE_ACTION_PROP_ID,
+            speed_prop: CURTAIN_NODE_SPEED_PROP_ID,
+            hand_pull_prop: CURTAIN_NODE_HAND_PULL_PROP_ID,
+        }
+    }
+
+    pub fn position(&self, value: i64) -> homie5::client::Publish {
+        self.client.publish_value(
+            self.node.node_id(),
+            &self.position_prop,
+            value.to_string(),
+            true,
+        )
+    }
+
+    pub fn position_target(&self, value: i64) -> homie5::client::Publish {
+        self.client.publish_target(
+            self.node.node_id(),
+            &self.position_prop,
+            value.to_string(),
+            true,
+        )
+    }
+
+    pub fn action(&self, action: CurtainNodeActions) -> homie5::client::Publish {
+        self.client.publish_value(
+            self.node.node_id(),
+            &self.action_prop,
+            action.as_str(),
+            false,
+        )
+    }
+
+    pub fn speed(&self, value: i64) -> Option<homie5::client::Publish> {
+        if !self.config.speed {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.speed_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+
+    pub fn speed_target(&self, value: i64) -> Option<homie5::client::Publish> {
+        if !self.config.speed {
+            return None;
+        }
+        Some(self.client.publish_target(
+            self.node.node_id(),
+            &self.speed_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+
+    /// Publish the `hand-pull` event.
+    pub fn hand_pull(&self) -> Option<homie5::client::Publish> {
+        if !self.config.hand_pull {
+            return None;
+        }
+        Some(
+            self.client
+                .publish_value(self.node.node_id(), &self.hand_pull_prop, "pulled", false),
+        )
+    }
+}
+
+impl SmarthomePublisher for CurtainNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::Curtain
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![
+            self.position_prop.clone(),
+            self.action_prop.clone(),
+            self.speed_prop.clone(),
+            self.hand_pull_prop.clone(),
+        ]
+    }
+}
+
+impl SetCommandParser for CurtainNodePublisher {
+    type Event = CurtainNodeSetEvents;
+
+    fn parse_set(
+        &self,
+        property: &PropertyRef,
+        desc: &HomieDeviceDescription,
+        set_value: &str,
+    ) -> ParseOutcome<Self::Event> {
+        if let Some(outcome) = reject_readonly(&self.node, property, desc, set_value) {
+            return outcome;
+        }
+
+        let property_id = property.prop_id().to_string();
+
+        if property.match_with_node(&self.node, &self.position_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Integer(value)) => {
+                    ParseOutcome::Parsed(CurtainNodeSetEvents::Position(value))
+                }
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else if property.match_with_node(&self.node, &self.action_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Enum(value)) => match CurtainNodeActions::from_str(&value) {
+                    Ok(action) => ParseOutcome::Parsed(CurtainNodeSetEvents::Action(action)),
+                    Err(_) => ParseOutcome::Invalid(ParseError::new(
+                        property_id,
+                        set_value,
+                        ParseErrorKind::InvalidVariant,
+                    )),
+                },
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else if property.match_with_node(&self.node, &self.speed_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Integer(value)) => {
+                    ParseOutcome::Parsed(CurtainNodeSetEvents::Speed(value))
+                }
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else {
+            ParseOutcome::NoMatch
+        }
+    }
+
+    fn parse_set_event(
+        &self,
+        desc: &HomieDeviceDescription,
+        event: &Homie5Message,
+    ) -> ParseOutcome<Self::Event> {
+        match event {
+            Homie5Message::PropertySet {
+                property,
+                set_value,
+            } => self.parse_set(property, desc, set_value),
+            _ => ParseOutcome::Invalid(ParseError::new(
+                self.position_prop.to_string(),
+                "",
+                ParseErrorKind::UnexpectedMessageType,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use homie5::HomieDomain;
+
+    use super::*;
+    use crate::StandaloneSetParser;
+
+    #[test]
+    fn position_action_and_speed_parse() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let config = CurtainNodeConfig {
+            speed: true,
+            ..CurtainNodeConfig::default()
+        };
+        let parser: StandaloneSetParser<_> = CurtainNodeBuilder::new(&config)
+            .build_with_publisher(CURTAIN_NODE_DEFAULT_ID, &client)
+            .into();
+        let node = parser.publisher().node_ref().clone();
+        let position = PropertyRef::from_node(node.clone(), CURTAIN_NODE_POSITION_PROP_ID);
+        let action = PropertyRef::from_node(node.clone(), CURTAIN_NODE_ACTION_PROP_ID);
+        let speed = PropertyRef::from_node(node, CURTAIN_NODE_SPEED_PROP_ID);
+
+        assert!(matches!(
+            parser.parse_set(&position, "40"),
+            ParseOutcome::Parsed(CurtainNodeSetEvents::Position(40))
+        ));
+        assert!(matches!(
+            parser.parse_set(&action, "stop"),
+            ParseOutcome::Parsed(CurtainNodeSetEvents::Action(CurtainNodeActions::Stop))
+        ));
+        // `calibrate` is not enabled
+        assert!(matches!(
+            parser.parse_set(&action, "calibrate"),
+            ParseOutcome::Invalid(_)
+        ));
+        assert!(matches!(
+            parser.parse_set(&speed, "80"),
+            ParseOutcome::Parsed(CurtainNodeSetEvents::Speed(80))
+        ));
+
+        let publish = parser
+            .into_publisher()
+            .hand_pull()
+            .expect("hand-pull is enabled by default");
+        assert!(!publish.retain);
+    }
+}
@@ -0,0 +1,444 @@
+//! Declarative definition of vendor-specific node types.
+//!
+//! [`define_smarthome_node!`](crate::define_smarthome_node) generates the
+//! builder, publisher, set event enum and [`SetCommandParser`](crate::SetCommandParser)
+//! implementation the built-in node modules write by hand, from a compact
+//! property specification. Custom nodes should use an extension type string
+//! created with [`smarthome_ext!`](crate::smarthome_ext).
+//!
+//! ```
+//! use hc_homie5_smarthome::{define_smarthome_node, smarthome_ext};
+//! use homie5::{HomieID, device_description::PropertyDescriptionBuilder};
+//!
+//! pub const SIREN_NODE_TONE_PROP_ID: HomieID = HomieID::new_const("tone");
+//! pub const SIREN_NODE_SIGNAL_PROP_ID: HomieID = HomieID::new_const("signal");
+//!
+//! define_smarthome_node! {
+//!     builder: SirenNodeBuilder,
+//!     publisher: SirenNodePublisher,
+//!     events: SirenNodeSetEvents,
+//!     node_type: smarthome_ext!("acme-siren"),
+//!     name: "Siren",
+//!     properties: {
+//!         /// Publish the selected tone.
+//!         tone(String) = SIREN_NODE_TONE_PROP_ID, set Tone =>
+//!             PropertyDescriptionBuilder::enumeration(["chime", "alarm"])
+//!                 .unwrap()
+//!                 .name("Tone")
+//!                 .settable(true)
+//!                 .build(),
+//!         /// Publish the signal strength.
+//!         signal(i64) = SIREN_NODE_SIGNAL_PROP_ID =>
+//!             PropertyDescriptionBuilder::integer().name("Signal").build(),
+//!     }
+//! }
+//! ```
+//!
+//! Properties declared with `set <Variant>` get a variant in the set event
+//! enum and are parsed by the generated `SetCommandParser`. Every property
+//! gets a publisher method named after it; its retained flag is taken from
+//! the property description.
+//!
+//! The publisher implements [`SmarthomePublisher`](crate::SmarthomePublisher)
+//! with [`SmarthomeType::Extension`](crate::SmarthomeType::Extension), so
+//! `node_type` must be a `&'static str`.
+
+use chrono::{DateTime, Utc};
+use homie5::{HomieColorValue, HomieValue};
+
+#[doc(hidden)]
+pub use homie5;
+
+/// Conversion from a parsed [`HomieValue`] into the payload type of a custom
+/// node's set event.
+pub trait FromHomieValue: Sized {
+    fn from_homie_value(value: HomieValue) -> Option<Self>;
+}
+
+impl FromHomieValue for String {
+    fn from_homie_value(value: HomieValue) -> Option<Self> {
+        match value {
+            HomieValue::String(value) | HomieValue::Enum(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl FromHomieValue for i64 {
+    fn from_homie_value(value: HomieValue) -> Option<Self> {
+        match value {
+            HomieValue::Integer(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl FromHomieValue for f64 {
+    fn from_homie_value(value: HomieValue) -> Option<Self> {
+        match value {
+            HomieValue::Float(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl FromHomieValue for bool {
+    fn from_homie_value(value: HomieValue) -> Option<Self> {
+        match value {
+            HomieValue::Bool(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl FromHomieValue for HomieColorValue {
+    fn from_homie_value(value: HomieValue) -> Option<Self> {
+        match value {
+            HomieValue::Color(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl FromHomieValue for DateTime<Utc> {
+    fn from_homie_value(value: HomieValue) -> Option<Self> {
+        match value {
+            HomieValue::DateTime(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl FromHomieValue for chrono::Duration {
+    fn from_homie_value(value: HomieValue) -> Option<Self> {
+        match value {
+            HomieValue::Duration(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl FromHomieValue for serde_json::Value {
+    fn from_homie_value(value: HomieValue) -> Option<Self> {
+        match value {
+            HomieValue::JSON(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+/// Generate a custom node type from a property specification.
+///
+/// See the [module documentation](crate::custom_node) for the syntax.
+#[macro_export]
+macro_rules! define_smarthome_node {
+    (
+        builder: $builder:ident,
+        publisher: $publisher:ident,
+        events: $events:ident,
+        node_type: $node_type:expr,
+        name: $name:expr,
+        properties: {
+            $(
+                $(#[$meta:meta])*
+                $method:ident ($ty:ty) = $prop_id:expr $(, set $variant:ident)? => $desc:expr
+            ),+ $(,)?
+        } $(,)?
+    ) => {
+        #[derive(Debug)]
+        pub enum $events {
+            $($($variant($ty),)?)+
+        }
+
+        pub struct $builder {
+            node_builder: $crate::custom_node::homie5::device_description::NodeDescriptionBuilder,
+        }
+
+        impl Default for $builder {
+            fn default() -> Self {
+                let db = $crate::custom_node::homie5::device_description::NodeDescriptionBuilder::new()
+                    .name($name)
+                    .r#type($node_type)
+                    $(.add_property($prop_id, $desc))+;
+
+                Self { node_builder: db }
+            }
+        }
+
+        impl $builder {
+            pub fn new() -> Self {
+                Default::default()
+            }
+
+            pub fn name<S: Into<String>>(mut self, name: impl Into<Option<S>>) -> Self {
+                self.node_builder = self.node_builder.name(name);
+                self
+            }
+
+            pub fn build(self) -> $crate::custom_node::homie5::device_description::HomieNodeDescription {
+                self.node_builder.build()
+            }
+
+            /// Ids of the properties the node contains.
+            pub fn property_ids() -> Vec<$crate::custom_node::homie5::HomieID> {
+                Self::new().build().properties.into_keys().collect()
+            }
+
+            pub fn build_with_publisher(
+                self,
+                node_id: $crate::custom_node::homie5::HomieID,
+                client: &$crate::custom_node::homie5::Homie5DeviceProtocol,
+            ) -> (
+                $crate::custom_node::homie5::device_description::HomieNodeDescription,
+                $publisher,
+            ) {
+                let desc = self.node_builder.build();
+                let publisher = $publisher {
+                    client: client.clone(),
+                    node: $crate::custom_node::homie5::NodeRef::new(
+                        client.homie_domain().to_owned(),
+                        client.id().clone(),
+                        node_id,
+                    ),
+                    metadata: $crate::property_metadata(&desc),
+                };
+                (desc, publisher)
+            }
+        }
+
+        #[derive(Debug)]
+        pub struct $publisher {
+            client: $crate::custom_node::homie5::Homie5DeviceProtocol,
+            node: $crate::custom_node::homie5::NodeRef,
+            metadata: ::std::collections::BTreeMap<
+                $crate::custom_node::homie5::HomieID,
+                $crate::PropertyMetadata,
+            >,
+        }
+
+        impl $publisher {
+            fn retained(&self, prop_id: &$crate::custom_node::homie5::HomieID) -> bool {
+                self.metadata
+                    .get(prop_id)
+                    .is_none_or(|prop| prop.retained)
+            }
+
+            $(
+                $(#[$meta])*
+                pub fn $method(&self, value: $ty) -> $crate::custom_node::homie5::client::Publish {
+                    let prop_id = $prop_id;
+                    self.client.publish_value(
+                        self.node.node_id(),
+                        &prop_id,
+                        $crate::custom_node::homie5::HomieValue::from(value).to_string(),
+                        self.retained(&prop_id),
+                    )
+                }
+            )+
+
+            /// Publish the `$target` attribute of a settable property.
+            pub fn target(
+                &self,
+                prop_id: &$crate::custom_node::homie5::HomieID,
+                value: impl Into<$crate::custom_node::homie5::HomieValue>,
+            ) -> $crate::custom_node::homie5::client::Publish {
+                self.client.publish_target(
+                    self.node.node_id(),
+                    prop_id,
+                    value.into().to_string(),
+                    self.retained(prop_id),
+                )
+            }
+        }
+
+        impl $crate::SmarthomePublisher for $publisher {
+            fn smarthome_type(&self) -> $crate::SmarthomeType {
+                $crate::SmarthomeType::Extension($node_type)
+            }
+
+            fn node_ref(&self) -> &$crate::custom_node::homie5::NodeRef {
+                &self.node
+            }
+
+            fn property_ids(&self) -> Vec<$crate::custom_node::homie5::HomieID> {
+                self.metadata.keys().cloned().collect()
+            }
+
+            fn property_metadata(
+                &self,
+            ) -> &::std::collections::BTreeMap<
+                $crate::custom_node::homie5::HomieID,
+                $crate::PropertyMetadata,
+            > {
+                &self.metadata
+            }
+        }
+
+        impl $crate::SetCommandParser for $publisher {
+            type Event = $events;
+
+            fn parse_set(
+                &self,
+                property: &$crate::custom_node::homie5::PropertyRef,
+                desc: &$crate::custom_node::homie5::device_description::HomieDeviceDescription,
+                set_value: &str,
+            ) -> $crate::ParseOutcome<Self::Event> {
+                if let Some(outcome) = $crate::reject_readonly(&self.node, property, desc, set_value) {
+                    return outcome;
+                }
+                $($(
+                    if property.match_with_node(&self.node, &$prop_id) {
+                        let Some(parsed) = desc.with_property(property, |prop_desc| {
+                            $crate::custom_node::homie5::HomieValue::parse(set_value, prop_desc)
+                        }) else {
+                            return $crate::ParseOutcome::Invalid($crate::ParseError::new(
+                                property.prop_id().to_string(),
+                                set_value,
+                                $crate::ParseErrorKind::MissingPropertyDescription,
+                            ));
+                        };
+                        return match parsed
+                            .ok()
+                            .and_then(<$ty as $crate::custom_node::FromHomieValue>::from_homie_value)
+                        {
+                            Some(value) => $crate::ParseOutcome::Parsed($events::$variant(value)),
+                            None => $crate::ParseOutcome::Invalid($crate::ParseError::new(
+                                property.prop_id().to_string(),
+                                set_value,
+                                $crate::ParseErrorKind::InvalidHomieValue,
+                            )),
+                        };
+                    }
+                )?)+
+                $crate::ParseOutcome::NoMatch
+            }
+
+            fn parse_set_event(
+                &self,
+                desc: &$crate::custom_node::homie5::device_description::HomieDeviceDescription,
+                event: &$crate::custom_node::homie5::Homie5Message,
+            ) -> $crate::ParseOutcome<Self::Event> {
+                match event {
+                    $crate::custom_node::homie5::Homie5Message::PropertySet {
+                        property,
+                        set_value,
+                    } => self.parse_set(property, desc, set_value),
+                    _ => $crate::ParseOutcome::Invalid($crate::ParseError::new(
+                        self.node.node_id().to_string(),
+                        "",
+                        $crate::ParseErrorKind::UnexpectedMessageType,
+                    )),
+                }
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use homie5::{
+        Homie5DeviceProtocol, HomieDomain, HomieID, PropertyRef,
+        device_description::{DeviceDescriptionBuilder, PropertyDescriptionBuilder},
+    };
+
+    use crate::{
+        ParseError, ParseErrorKind, ParseOutcome, SetCommandParser, SmarthomePublisher,
+        SmarthomeType, StandaloneSetParser, smarthome_ext,
+    };
+
+    const SIREN_NODE_TONE_PROP_ID: HomieID = HomieID::new_const("tone");
+    const SIREN_NODE_SIGNAL_PROP_ID: HomieID = HomieID::new_const("signal");
+
+    define_smarthome_node! {
+        builder: SirenNodeBuilder,
+        publisher: SirenNodePublisher,
+        events: SirenNodeSetEvents,
+        node_type: smarthome_ext!("acme-siren"),
+        name: "Siren",
+        properties: {
+            tone(String) = SIREN_NODE_TONE_PROP_ID, set Tone =>
+                PropertyDescriptionBuilder::enumeration(["chime", "alarm"])
+                    .unwrap()
+                    .name("Tone")
+                    .settable(true)
+                    .build(),
+            signal(i64) = SIREN_NODE_SIGNAL_PROP_ID =>
+                PropertyDescriptionBuilder::integer()
+                    .name("Signal")
+                    .retained(false)
+                    .build(),
+        }
+    }
+
+    #[test]
+    fn generated_node_builds_publishes_and_parses() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let (desc, publisher) = SirenNodeBuilder::new()
+            .name("Hall siren")
+            .build_with_publisher(HomieID::new_const("siren"), &client);
+
+        assert_eq!(
+            desc.r#type.as_deref(),
+            Some("hc-smarthome/v2/ext/acme-siren")
+        );
+        assert_eq!(
+            SirenNodeBuilder::property_ids(),
+            vec![SIREN_NODE_SIGNAL_PROP_ID, SIREN_NODE_TONE_PROP_ID]
+        );
+        assert_eq!(publisher.property_ids(), SirenNodeBuilder::property_ids());
+        assert!(publisher.tone("alarm".to_owned()).retain);
+        assert!(
+            publisher
+                .target(&SIREN_NODE_TONE_PROP_ID, "alarm".to_owned())
+                .topic
+                .ends_with("/tone/$target")
+        );
+        assert!(!publisher.signal(-60).retain);
+        let metadata = publisher.property_metadata();
+        assert!(metadata[&SIREN_NODE_TONE_PROP_ID].settable);
+        assert!(!metadata[&SIREN_NODE_SIGNAL_PROP_ID].retained);
+        assert_eq!(
+            publisher.smarthome_type(),
+            SmarthomeType::Extension("hc-smarthome/v2/ext/acme-siren")
+        );
+        assert_eq!(publisher.subscriptions(&desc).len(), 1);
+
+        let device = DeviceDescriptionBuilder::new()
+            .add_node(publisher.node_id().clone(), desc)
+            .build();
+        let tone = PropertyRef::from_node(publisher.node_ref().clone(), SIREN_NODE_TONE_PROP_ID);
+        assert!(matches!(
+            publisher.parse_set(&tone, &device, "chime"),
+            ParseOutcome::Parsed(SirenNodeSetEvents::Tone(t)) if t == "chime"
+        ));
+        assert!(matches!(
+            publisher.parse_set(&tone, &device, "siren"),
+            ParseOutcome::Invalid(_)
+        ));
+        let signal =
+            PropertyRef::from_node(publisher.node_ref().clone(), SIREN_NODE_SIGNAL_PROP_ID);
+        assert!(matches!(
+            publisher.parse_set(&signal, &device, "3"),
+            ParseOutcome::Invalid(ParseError {
+                kind: ParseErrorKind::NotSettable,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn generated_publisher_works_with_standalone_parser() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let (desc, publisher) =
+            SirenNodeBuilder::new().build_with_publisher(HomieID::new_const("siren"), &client);
+        let tone = publisher.property_ref(SIREN_NODE_TONE_PROP_ID);
+
+        let parser = StandaloneSetParser::new(desc, publisher);
+        assert!(matches!(
+            parser.parse_set(&tone, "alarm"),
+            ParseOutcome::Parsed(SirenNodeSetEvents::Tone(t)) if t == "alarm"
+        ));
+    }
+}
@@ -2,15 +2,19 @@ use core::fmt;
 
 use chrono::prelude::*;
 
+use std::{collections::BTreeMap, str::FromStr};
+
 use homie5::{
-    Homie5DeviceProtocol, HomieID, HomieValue, NodeRef,
+    Homie5DeviceProtocol, Homie5ProtocolError, HomieID, HomieValue, NodeRef,
     device_description::{
         HomieNodeDescription, NodeDescriptionBuilder, PropertyDescriptionBuilder,
     },
 };
 use serde::{Deserialize, Serialize};
 
-use crate::SMARTHOME_CAP_DAYLIGHT;
+use crate::{
+    PropertyMetadata, SMARTHOME_CAP_DAYLIGHT, SmarthomePublisher, SmarthomeType, property_metadata,
+};
 
 pub const DAYLIGHT_NODE_DEFAULT_ID: HomieID = HomieID::new_const("daylight");
 pub const DAYLIGHT_NODE_DEFAULT_NAME: &str = "Daylight sensor";
@@ -22,7 +26,8 @@ pub const DAYLIGHT_NODE_PHASE_PROP_ID: HomieID = HomieID::new_const("phase");
 
 // ── Daylight phase ──────────────────────────────────────────────────────────
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum DaylightPhase {
     Night,
     Dawn,
@@ -60,11 +65,26 @@ impl fmt::Display for DaylightPhase {
     }
 }
 
+impl FromStr for DaylightPhase {
+    type Err = Homie5ProtocolError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "night" => Ok(Self::Night),
+            "dawn" => Ok(Self::Dawn),
+            "morning" => Ok(Self::Morning),
+            "day" => Ok(Self::Day),
+            "evening" => Ok(Self::Evening),
+            "dusk" => Ok(Self::Dusk),
+            _ => Err(Homie5ProtocolError::InvalidPayload),
+        }
+    }
+}
+
 // ── Node (state) ────────────────────────────────────────────────────────────
 
-#[derive(Debug)]
-pub struct DaylightNode {
-    pub publisher: DaylightNodePublisher,
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DaylightNodeState {
     pub daylight: bool,
     pub dark: bool,
     pub sunrise: DateTime<Utc>,
@@ -72,9 +92,67 @@ pub struct DaylightNode {
     pub phase: Option<DaylightPhase>,
 }
 
+#[derive(Debug)]
+pub struct DaylightNode {
+    pub publisher: DaylightNodePublisher,
+    pub state: DaylightNodeState,
+}
+
+impl DaylightNode {
+    /// Apply a value reported by the device and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Bool(value)) if p == &DAYLIGHT_NODE_DAYLIGHT_PROP_ID => {
+                self.state.daylight = *value;
+                vec![self.publisher.daylight(*value)]
+            }
+            (p, HomieValue::Bool(value)) if p == &DAYLIGHT_NODE_DARK_PROP_ID => {
+                self.state.dark = *value;
+                vec![self.publisher.dark(*value)]
+            }
+            (p, HomieValue::DateTime(value)) if p == &DAYLIGHT_NODE_SUNRISE_PROP_ID => {
+                self.state.sunrise = *value;
+                vec![self.publisher.sunrise(*value)]
+            }
+            (p, HomieValue::DateTime(value)) if p == &DAYLIGHT_NODE_SUNSET_PROP_ID => {
+                self.state.sunset = *value;
+                vec![self.publisher.sunset(*value)]
+            }
+            (p, HomieValue::Enum(value)) if p == &DAYLIGHT_NODE_PHASE_PROP_ID => {
+                let Ok(phase) = value.parse::<DaylightPhase>() else {
+                    return Vec::new();
+                };
+                self.state.phase = Some(phase);
+                vec![self.publisher.phase(phase)]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            Some(self.publisher.daylight(self.state.daylight)),
+            Some(self.publisher.dark(self.state.dark)),
+            Some(self.publisher.sunrise(self.state.sunrise)),
+            Some(self.publisher.sunset(self.state.sunset)),
+            self.state.phase.map(|value| self.publisher.phase(value)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
 // ── Config ──────────────────────────────────────────────────────────────────
 
 #[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 #[serde(default)]
 pub struct DaylightNodeConfig {
     pub phase: bool,
@@ -84,6 +162,7 @@ pub struct DaylightNodeConfig {
 
 pub struct DaylightNodeBuilder {
     node_builder: NodeDescriptionBuilder,
+    config: DaylightNodeConfig,
 }
 
 impl DaylightNodeBuilder {
@@ -94,7 +173,10 @@ impl DaylightNodeBuilder {
         )
         .r#type(SMARTHOME_CAP_DAYLIGHT);
 
-        Self { node_builder: db }
+        Self {
+            node_builder: db,
+            config: config.clone(),
+        }
     }
 
     fn build_node(
@@ -152,10 +234,23 @@ impl DaylightNodeBuilder {
         self
     }
 
+    /// Append a subtype suffix to the node type (`daylight-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::Daylight.with_subtype(subtype));
+        self
+    }
+
     pub fn build(self) -> HomieNodeDescription {
         self.node_builder.build()
     }
 
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &DaylightNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
     pub fn build_with_publisher(
         self,
         node_id: HomieID,
@@ -170,6 +265,7 @@ impl DaylightNodeBuilder {
                     node_id,
                 ),
                 client.clone(),
+                &self.config,
             ),
         )
     }
@@ -181,6 +277,7 @@ impl DaylightNodeBuilder {
 pub struct DaylightNodePublisher {
     client: Homie5DeviceProtocol,
     node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
     daylight_prop: HomieID,
     dark_prop: HomieID,
     sunrise_prop: HomieID,
@@ -189,9 +286,11 @@ pub struct DaylightNodePublisher {
 }
 
 impl DaylightNodePublisher {
-    pub fn new(node: NodeRef, client: Homie5DeviceProtocol) -> Self {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: &DaylightNodeConfig) -> Self {
+        let metadata = property_metadata(&DaylightNodeBuilder::new(config).build());
         Self {
             node,
+            metadata,
             client,
             daylight_prop: DAYLIGHT_NODE_DAYLIGHT_PROP_ID,
             dark_prop: DAYLIGHT_NODE_DARK_PROP_ID,
@@ -242,3 +341,27 @@ impl DaylightNodePublisher {
             .publish_value(self.node.node_id(), &self.phase_prop, value.as_str(), true)
     }
 }
+
+impl SmarthomePublisher for DaylightNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::Daylight
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![
+            self.daylight_prop.clone(),
+            self.dark_prop.clone(),
+            self.sunrise_prop.clone(),
+            self.sunset_prop.clone(),
+            self.phase_prop.clone(),
+        ]
+    }
+}
@@ -0,0 +1,614 @@
+use std::collections::BTreeMap;
+
+use homie5::{
+    HOMIE_UNIT_PERCENT, Homie5DeviceProtocol, Homie5Message, HomieID, HomieValue, NodeRef,
+    PropertyRef,
+    device_description::{
+        HomieDeviceDescription, HomieNodeDescription, IntegerRange, NodeDescriptionBuilder,
+        PropertyDescriptionBuilder,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ConfigError, ParseError, ParseErrorKind, ParseOutcome, PropertyMetadata,
+    SMARTHOME_CAP_DEHUMIDIFIER, SetCommandParser, SmarthomePublisher, SmarthomeType,
+    property_metadata, readonly_properties, reject_readonly, smarthome_node_config,
+    validate_enum_format,
+};
+
+pub const DEHUMIDIFIER_NODE_DEFAULT_ID: HomieID = HomieID::new_const("dehumidifier");
+pub const DEHUMIDIFIER_NODE_DEFAULT_NAME: &str = "Dehumidifier";
+pub const DEHUMIDIFIER_NODE_STATE_PROP_ID: HomieID = HomieID::new_const("state");
+pub const DEHUMIDIFIER_NODE_TARGET_HUMIDITY_PROP_ID: HomieID =
+    HomieID::new_const("target-humidity");
+pub const DEHUMIDIFIER_NODE_FAN_SPEED_PROP_ID: HomieID = HomieID::new_const("fan-speed");
+pub const DEHUMIDIFIER_NODE_TANK_FULL_PROP_ID: HomieID = HomieID::new_const("tank-full");
+pub const DEHUMIDIFIER_NODE_CURRENT_HUMIDITY_PROP_ID: HomieID =
+    HomieID::new_const("current-humidity");
+pub const DEHUMIDIFIER_NODE_DEFROST_PROP_ID: HomieID = HomieID::new_const("defrost");
+
+// ── Node (state) ────────────────────────────────────────────────────────────
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DehumidifierNodeState {
+    pub state: bool,
+    pub target_humidity: i64,
+    pub fan_speed: Option<String>,
+    pub tank_full: Option<bool>,
+    pub current_humidity: Option<i64>,
+    pub defrost: Option<bool>,
+}
+
+#[derive(Debug)]
+pub struct DehumidifierNode {
+    pub publisher: DehumidifierNodePublisher,
+    pub state: DehumidifierNodeState,
+}
+
+impl DehumidifierNode {
+    /// Apply a parsed set command and return the `$target` and value publishes
+    /// for the change.
+    pub fn apply_set(&mut self, event: DehumidifierNodeSetEvents) -> Vec<homie5::client::Publish> {
+        match event {
+            DehumidifierNodeSetEvents::State(value) => {
+                self.state.state = value;
+                vec![
+                    self.publisher.state_target(value),
+                    self.publisher.state(value),
+                ]
+            }
+            DehumidifierNodeSetEvents::TargetHumidity(value) => {
+                self.state.target_humidity = value;
+                vec![
+                    self.publisher.target_humidity_target(value),
+                    self.publisher.target_humidity(value),
+                ]
+            }
+            DehumidifierNodeSetEvents::FanSpeed(value) => {
+                let publishes = [
+                    self.publisher.fan_speed_target(&value),
+                    self.publisher.fan_speed(&value),
+                ]
+                .into_iter()
+                .flatten()
+                .collect();
+                self.state.fan_speed = Some(value);
+                publishes
+            }
+        }
+    }
+
+    /// Apply a value reported by the device and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Bool(value)) if p == &DEHUMIDIFIER_NODE_STATE_PROP_ID => {
+                self.state.state = *value;
+                vec![self.publisher.state(*value)]
+            }
+            (p, HomieValue::Integer(value)) if p == &DEHUMIDIFIER_NODE_TARGET_HUMIDITY_PROP_ID => {
+                self.state.target_humidity = *value;
+                vec![self.publisher.target_humidity(*value)]
+            }
+            (p, HomieValue::Enum(value)) if p == &DEHUMIDIFIER_NODE_FAN_SPEED_PROP_ID => {
+                self.state.fan_speed = Some(value.clone());
+                self.publisher.fan_speed(value).into_iter().collect()
+            }
+            (p, HomieValue::Bool(value)) if p == &DEHUMIDIFIER_NODE_TANK_FULL_PROP_ID => {
+                self.state.tank_full = Some(*value);
+                self.publisher.tank_full(*value).into_iter().collect()
+            }
+            (p, HomieValue::Integer(value)) if p == &DEHUMIDIFIER_NODE_CURRENT_HUMIDITY_PROP_ID => {
+                self.state.current_humidity = Some(*value);
+                self.publisher
+                    .current_humidity(*value)
+                    .into_iter()
+                    .collect()
+            }
+            (p, HomieValue::Bool(value)) if p == &DEHUMIDIFIER_NODE_DEFROST_PROP_ID => {
+                self.state.defrost = Some(*value);
+                self.publisher.defrost(*value).into_iter().collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            Some(self.publisher.state(self.state.state)),
+            Some(self.publisher.target_humidity(self.state.target_humidity)),
+            self.state
+                .fan_speed
+                .as_deref()
+                .and_then(|value| self.publisher.fan_speed(value)),
+            self.state
+                .tank_full
+                .and_then(|value| self.publisher.tank_full(value)),
+            self.state
+                .current_humidity
+                .and_then(|value| self.publisher.current_humidity(value)),
+            self.state
+                .defrost
+                .and_then(|value| self.publisher.defrost(value)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+// ── Set events ──────────────────────────────────────────────────────────────
+
+#[derive(Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+pub enum DehumidifierNodeSetEvents {
+    State(bool),
+    TargetHumidity(i64),
+    FanSpeed(String),
+}
+
+// ── Config ──────────────────────────────────────────────────────────────────
+
+smarthome_node_config! {
+    #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+    #[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+    #[serde(default)]
+    pub struct DehumidifierNodeConfig for DehumidifierNodePublisher {
+        /// Lowest settable target humidity in percent.
+        pub min_humidity: i64,
+        /// Highest settable target humidity in percent.
+        pub max_humidity: i64,
+        /// Fan speed steps (e.g. `low`, `high`). The `fan-speed` property is
+        /// only added when this list is not empty.
+        #[cfg_attr(
+            feature = "proptest",
+            proptest(strategy = "crate::arbitrary::enum_values()")
+        )]
+        pub fan_speeds: Vec<String>,
+        /// Expose the `tank-full` property (units without a drain hose).
+        pub water_tank: bool => DEHUMIDIFIER_NODE_TANK_FULL_PROP_ID, tank_full(bool), |_config| {
+            PropertyDescriptionBuilder::boolean()
+                .name("Water tank full")
+                .boolean_labels("ok", "full")
+                .settable(false)
+                .retained(true)
+                .build()
+        },
+        /// Expose the humidity measured by the unit itself.
+        pub current_humidity: bool => DEHUMIDIFIER_NODE_CURRENT_HUMIDITY_PROP_ID, current_humidity(i64), |_config| {
+            PropertyDescriptionBuilder::integer()
+                .name("Current humidity")
+                .unit(HOMIE_UNIT_PERCENT)
+                .integer_range(IntegerRange {
+                    min: Some(0),
+                    max: Some(100),
+                    step: None,
+                })
+                .settable(false)
+                .retained(true)
+                .build()
+        },
+        /// Expose the `defrost` property (compressor units in cold rooms).
+        pub defrost: bool => DEHUMIDIFIER_NODE_DEFROST_PROP_ID, defrost(bool), |_config| {
+            PropertyDescriptionBuilder::boolean()
+                .name("Defrosting")
+                .boolean_labels("off", "on")
+                .settable(false)
+                .retained(true)
+                .build()
+        },
+        /// Properties to publish as read-only; sets to them are rejected.
+        #[cfg_attr(
+            feature = "proptest",
+            proptest(strategy = "crate::arbitrary::homie_ids()")
+        )]
+        pub readonly: Vec<HomieID>,
+    }
+}
+
+impl Default for DehumidifierNodeConfig {
+    fn default() -> Self {
+        Self {
+            min_humidity: 30,
+            max_humidity: 80,
+            fan_speeds: Vec::new(),
+            water_tank: true,
+            current_humidity: true,
+            defrost: false,
+            readonly: Vec::new(),
+        }
+    }
+}
+
+impl DehumidifierNodeConfig {
+    /// Check the config-provided enum values before building the node.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if !self.fan_speeds.is_empty() {
+            validate_enum_format(&DEHUMIDIFIER_NODE_FAN_SPEED_PROP_ID, &self.fan_speeds)?;
+        }
+        Ok(())
+    }
+}
+
+// ── Builder ─────────────────────────────────────────────────────────────────
+
+pub struct DehumidifierNodeBuilder {
+    node_builder: NodeDescriptionBuilder,
+    config: DehumidifierNodeConfig,
+}
+
+impl Default for DehumidifierNodeBuilder {
+    fn default() -> Self {
+        Self::new(&DehumidifierNodeConfig::default())
+    }
+}
+
+impl DehumidifierNodeBuilder {
+    pub fn new(config: &DehumidifierNodeConfig) -> Self {
+        let db = Self::build_node(
+            NodeDescriptionBuilder::new().name(DEHUMIDIFIER_NODE_DEFAULT_NAME),
+            config,
+        )
+        .r#type(SMARTHOME_CAP_DEHUMIDIFIER);
+        let db = readonly_properties(db, &config.readonly);
+
+        Self {
+            node_builder: db,
+            config: config.clone(),
+        }
+    }
+
+    fn build_node(
+        db: NodeDescriptionBuilder,
+        config: &DehumidifierNodeConfig,
+    ) -> NodeDescriptionBuilder {
+        config.add_optional_properties(
+            db.add_property(
+                DEHUMIDIFIER_NODE_STATE_PROP_ID,
+                PropertyDescriptionBuilder::boolean()
+                    .name("Dehumidifier state")
+                    .boolean_labels("off", "on")
+                    .settable(true)
+                    .retained(true)
+                    .build(),
+            )
+            .add_property(
+                DEHUMIDIFIER_NODE_TARGET_HUMIDITY_PROP_ID,
+                PropertyDescriptionBuilder::integer()
+                    .name("Target humidity")
+                    .unit(HOMIE_UNIT_PERCENT)
+                    .integer_range(IntegerRange {
+                        min: Some(config.min_humidity),
+                        max: Some(config.max_humidity),
+                        step: None,
+                    })
+                    .settable(true)
+                    .retained(true)
+                    .build(),
+            )
+            .add_property_cond(
+                DEHUMIDIFIER_NODE_FAN_SPEED_PROP_ID,
+                !config.fan_speeds.is_empty(),
+                || {
+                    PropertyDescriptionBuilder::enumeration(
+                        config.fan_speeds.iter().map(|s| s.as_str()),
+                    )
+                    .unwrap()
+                    .name("Fan speed")
+                    .settable(true)
+                    .retained(true)
+                    .build()
+                },
+            ),
+        )
+    }
+
+    pub fn name<S: Into<String>>(mut self, name: impl Into<Option<S>>) -> Self {
+        self.node_builder = self.node_builder.name(name);
+        self
+    }
+
+    /// Append a subtype suffix to the node type (`dehumidifier-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::Dehumidifier.with_subtype(subtype));
+        self
+    }
+
+    pub fn build(self) -> HomieNodeDescription {
+        self.node_builder.build()
+    }
+
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &DehumidifierNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
+    pub fn build_with_publisher(
+        self,
+        node_id: HomieID,
+        client: &Homie5DeviceProtocol,
+    ) -> (HomieNodeDescription, DehumidifierNodePublisher) {
+        (
+            self.node_builder.build(),
+            DehumidifierNodePublisher::new(
+                NodeRef::new(
+                    client.homie_domain().to_owned(),
+                    client.id().clone(),
+                    node_id,
+                ),
+                client.clone(),
+                self.config,
+            ),
+        )
+    }
+}
+
+// ── Publisher ────────────────────────────────────────────────────────────────
+
+#[derive(Debug)]
+pub struct DehumidifierNodePublisher {
+    client: Homie5DeviceProtocol,
+    node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
+    config: DehumidifierNodeConfig,
+    state_prop: HomieID,
+    target_humidity_prop: HomieID,
+    fan_speed_prop: HomieID,
+    tank_full_prop: HomieID,
+    current_humidity_prop: HomieID,
+    defrost_prop: HomieID,
+}
+
+impl DehumidifierNodePublisher {
+    pub fn new(
+        node: NodeRef,
+        client: Homie5DeviceProtocol,
+        config: DehumidifierNodeConfig,
+    ) -> Self {
+        let metadata = property_metadata(&DehumidifierNodeBuilder::new(&config).build());
+        Self {
+            node,
+            metadata,
+            client,
+            config,
+            state_prop: DEHUMIDIFIER_NODE_STATE_PROP_ID,
+            target_humidity_prop: DEHUMIDIFIER_NODE_TARGET_HUMIDITY_PROP_ID,
+            fan_speed_prop: DEHUMIDIFIER_NODE_FAN_SPEED_PROP_ID,
+            tank_full_prop: DEHUMIDIFIER_NODE_TANK_FULL_PROP_ID,
+            current_humidity_prop: DEHUMIDIFIER_NODE_CURRENT_HUMIDITY_PROP_ID,
+            defrost_prop: DEHUMIDIFIER_NODE_DEFROST_PROP_ID,
+        }
+    }
+
+    pub fn state(&self, value: bool) -> homie5::client::Publish {
+        self.client.publish_value(
+            self.node.node_id(),
+            &self.state_prop,
+            value.to_string(),
+            true,
+        )
+    }
+
+    pub fn state_target(&self, value: bool) -> homie5::client::Publish {
+        self.client.publish_target(
+            self.node.node_id(),
+            &self.state_prop,
+            value.to_string(),
+            true,
+        )
+    }
+
+    pub fn target_humidity(&self, value: i64) -> homie5::client::Publish {
+        self.client.publish_value(
+            self.node.node_id(),
+            &self.target_humidity_prop,
+            value.to_string(),
+            true,
+        )
+    }
+
+    pub fn target_humidity_target(&self, value: i64) -> homie5::client::Publish {
+        self.client.publish_target(
+            self.node.node_id(),
+            &self.target_humidity_prop,
+            value.to_string(),
+            true,
+        )
+    }
+
+    pub fn fan_speed(&self, value: &str) -> Option<homie5::client::Publish> {
+        if self.config.fan_speeds.is_empty() {
+            return None;
+        }
+        Some(
+            self.client
+                .publish_value(self.node.node_id(), &self.fan_speed_prop, value, true),
+        )
+    }
+
+    pub fn fan_speed_target(&self, value: &str) -> Option<homie5::client::Publish> {
+        if self.config.fan_speeds.is_empty() {
+            return None;
+        }
+        Some(
+            self.client
+                .publish_target(self.node.node_id(), &self.fan_speed_prop, value, true),
+        )
+    }
+}
+
+impl SmarthomePublisher for DehumidifierNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::Dehumidifier
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![
+            self.state_prop.clone(),
+            self.target_humidity_prop.clone(),
+            self.fan_speed_prop.clone(),
+            self.tank_full_prop.clone(),
+            self.current_humidity_prop.clone(),
+            self.defrost_prop.clone(),
+        ]
+    }
+}
+
+impl SetCommandParser for DehumidifierNodePublisher {
+    type Event = DehumidifierNodeSetEvents;
+
+    fn parse_set(
+        &self,
+        property: &PropertyRef,
+        desc: &HomieDeviceDescription,
+        set_value: &str,
+    ) -> ParseOutcome<Self::Event> {
+        if let Some(outcome) = reject_readonly(&self.node, property, desc, set_value) {
+            return outcome;
+        }
+
+        let property_id = property.prop_id().to_string();
+
+        if property.match_with_node(&self.node, &self.state_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Bool(value)) => {
+                    ParseOutcome::Parsed(DehumidifierNodeSetEvents::State(value))
+                }
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else if property.match_with_node(&self.node, &self.target_humidity_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Integer(value)) => {
+                    ParseOutcome::Parsed(DehumidifierNodeSetEvents::TargetHumidity(value))
+                }
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else if property.match_with_node(&self.node, &self.fan_speed_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Enum(value)) => {
+                    ParseOutcome::Parsed(DehumidifierNodeSetEvents::FanSpeed(value))
+                }
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else {
+            ParseOutcome::NoMatch
+        }
+    }
+
+    fn parse_set_event(
+        &self,
+        desc: &HomieDeviceDescription,
+        event: &Homie5Message,
+    ) -> ParseOutcome<Self::Event> {
+        match event {
+            Homie5Message::PropertySet {
+                property,
+                set_value,
+            } => self.parse_set(property, desc, set_value),
+            _ => ParseOutcome::Invalid(ParseError::new(
+                self.state_prop.to_string(),
+                "",
+                ParseErrorKind::UnexpectedMessageType,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use homie5::HomieDomain;
+
+    use super::*;
+    use crate::StandaloneSetParser;
+
+    #[test]
+    fn fan_speed_and_read_only_properties_follow_config() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let config = DehumidifierNodeConfig {
+            fan_speeds: vec!["low".to_owned(), "high".to_owned()],
+            ..DehumidifierNodeConfig::default()
+        };
+        let parser: StandaloneSetParser<_> = DehumidifierNodeBuilder::new(&config)
+            .build_with_publisher(DEHUMIDIFIER_NODE_DEFAULT_ID, &client)
+            .into();
+        let node = parser.publisher().node_ref().clone();
+        let fan_speed = PropertyRef::from_node(node.clone(), DEHUMIDIFIER_NODE_FAN_SPEED_PROP_ID);
+        let tank_full = PropertyRef::from_node(node, DEHUMIDIFIER_NODE_TANK_FULL_PROP_ID);
+
+        assert!(matches!(
+            parser.parse_set(&fan_speed, "high"),
+            ParseOutcome::Parsed(DehumidifierNodeSetEvents::FanSpeed(s)) if s == "high"
+        ));
+        assert!(matches!(
+            parser.parse_set(&tank_full, "true"),
+            ParseOutcome::Invalid(ParseError {
+                kind: ParseErrorKind::NotSettable,
+                ..
+            })
+        ));
+
+        let publisher = parser.into_publisher();
+        assert!(publisher.current_humidity(55).is_some());
+        assert!(publisher.defrost(true).is_none());
+    }
+}
@@ -0,0 +1,671 @@
+use core::fmt;
+use std::{collections::BTreeMap, str::FromStr};
+
+use homie5::{
+    HOMIE_UNIT_PERCENT, HOMIE_UNIT_SECONDS, Homie5DeviceProtocol, Homie5Message,
+    Homie5ProtocolError, HomieID, HomieValue, NodeRef, PropertyRef,
+    device_description::{
+        HomieDeviceDescription, HomieNodeDescription, IntegerRange, NodeDescriptionBuilder,
+        PropertyDescriptionBuilder,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ParseError, ParseErrorKind, ParseOutcome, PropertyMetadata, SMARTHOME_CAP_DOOR,
+    SetCommandParser, SmarthomePublisher, SmarthomeType, property_metadata, readonly_properties,
+    reject_readonly, smarthome_node_config,
+};
+
+pub const DOOR_NODE_DEFAULT_ID: HomieID = HomieID::new_const("door");
+pub const DOOR_NODE_DEFAULT_NAME: &str = "Door";
+pub const DOOR_NODE_STATE_PROP_ID: HomieID = HomieID::new_const("state");
+pub const DOOR_NODE_ACTION_PROP_ID: HomieID = HomieID::new_const("action");
+pub const DOOR_NODE_POSITION_PROP_ID: HomieID = HomieID::new_const("position");
+pub const DOOR_NODE_LOCKED_PROP_ID: HomieID = HomieID::new_const("locked");
+pub const DOOR_NODE_AUTO_CLOSE_PROP_ID: HomieID = HomieID::new_const("auto-close");
+
+// ── Node (state) ────────────────────────────────────────────────────────────
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DoorNodeState {
+    pub state: DoorState,
+    pub position: Option<i64>,
+    pub locked: Option<bool>,
+    pub auto_close: Option<i64>,
+}
+
+#[derive(Debug)]
+pub struct DoorNode {
+    pub publisher: DoorNodePublisher,
+    pub state: DoorNodeState,
+}
+
+impl DoorNode {
+    /// Apply a parsed set command and return the `$target` and value publishes
+    /// for the change. Actions publish nothing; the door reports the resulting
+    /// state once it moves.
+    pub fn apply_set(&mut self, event: DoorNodeSetEvents) -> Vec<homie5::client::Publish> {
+        match event {
+            DoorNodeSetEvents::Action(_) => Vec::new(),
+            DoorNodeSetEvents::Locked(value) => {
+                self.state.locked = Some(value);
+                [
+                    self.publisher.locked_target(value),
+                    self.publisher.locked(value),
+                ]
+                .into_iter()
+                .flatten()
+                .collect()
+            }
+            DoorNodeSetEvents::AutoClose(value) => {
+                self.state.auto_close = Some(value);
+                [
+                    self.publisher.auto_close_target(value),
+                    self.publisher.auto_close(value),
+                ]
+                .into_iter()
+                .flatten()
+                .collect()
+            }
+        }
+    }
+
+    /// Apply a value reported by the device and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Enum(value)) if p == &DOOR_NODE_STATE_PROP_ID => {
+                let Ok(state) = value.parse::<DoorState>() else {
+                    return Vec::new();
+                };
+                self.state.state = state;
+                vec![self.publisher.state(state)]
+            }
+            (p, HomieValue::Integer(value)) if p == &DOOR_NODE_POSITION_PROP_ID => {
+                self.state.position = Some(*value);
+                self.publisher.position(*value).into_iter().collect()
+            }
+            (p, HomieValue::Bool(value)) if p == &DOOR_NODE_LOCKED_PROP_ID => {
+                self.state.locked = Some(*value);
+                self.publisher.locked(*value).into_iter().collect()
+            }
+            (p, HomieValue::Integer(value)) if p == &DOOR_NODE_AUTO_CLOSE_PROP_ID => {
+                self.state.auto_close = Some(*value);
+                self.publisher.auto_close(*value).into_iter().collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            Some(self.publisher.state(self.state.state)),
+            self.state
+                .position
+                .and_then(|value| self.publisher.position(value)),
+            self.state
+                .locked
+                .and_then(|value| self.publisher.locked(value)),
+            self.state
+                .auto_close
+                .and_then(|value| self.publisher.auto_close(value)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+// ── State / actions ─────────────────────────────────────────────────────────
+
+/// Position of the door leaf as reported by its contact or drive.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DoorState {
+    Open,
+    #[default]
+    Closed,
+    Opening,
+    Closing,
+}
+
+impl DoorState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Open => "open",
+            Self::Closed => "closed",
+            Self::Opening => "opening",
+            Self::Closing => "closing",
+        }
+    }
+
+    pub const ALL: [DoorState; 4] = [
+        DoorState::Open,
+        DoorState::Closed,
+        DoorState::Opening,
+        DoorState::Closing,
+    ];
+}
+
+impl fmt::Display for DoorState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for DoorState {
+    type Err = Homie5ProtocolError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "open" => Ok(Self::Open),
+            "closed" => Ok(Self::Closed),
+            "opening" => Ok(Self::Opening),
+            "closing" => Ok(Self::Closing),
+            _ => Err(Homie5ProtocolError::InvalidPayload),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+pub enum DoorAction {
+    Open,
+    Close,
+    Stop,
+}
+
+impl DoorAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Open => "open",
+            Self::Close => "close",
+            Self::Stop => "stop",
+        }
+    }
+}
+
+impl fmt::Display for DoorAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for DoorAction {
+    type Err = Homie5ProtocolError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "open" => Ok(Self::Open),
+            "close" => Ok(Self::Close),
+            "stop" => Ok(Self::Stop),
+            _ => Err(Homie5ProtocolError::InvalidPayload),
+        }
+    }
+}
+
+// ── Set events ──────────────────────────────────────────────────────────────
+
+#[derive(Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+pub enum DoorNodeSetEvents {
+    Action(DoorAction),
+    Locked(bool),
+    AutoClose(i64),
+}
+
+// ── Config ──────────────────────────────────────────────────────────────────
+
+smarthome_node_config! {
+    #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+    #[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+    #[serde(default)]
+    pub struct DoorNodeConfig for DoorNodePublisher {
+        /// Offer the `stop` action for drives that can halt mid-travel.
+        pub stop: bool,
+        /// Expose the opening `position` in percent (0 = closed).
+        pub position: bool => DOOR_NODE_POSITION_PROP_ID, position(i64), |_config| {
+            PropertyDescriptionBuilder::integer()
+                .name("Door position")
+                .unit(HOMIE_UNIT_PERCENT)
+                .integer_range(IntegerRange {
+                    min: Some(0),
+                    max: Some(100),
+                    step: None,
+                })
+                .settable(false)
+                .retained(true)
+                .build()
+        },
+        /// Expose the settable `locked` state of the built-in lock.
+        pub lock: bool,
+        /// Expose the settable `auto-close` timeout in seconds (0 = disabled).
+        pub auto_close: bool,
+        /// Properties to publish as read-only; sets to them are rejected.
+        #[cfg_attr(
+            feature = "proptest",
+            proptest(strategy = "crate::arbitrary::homie_ids()")
+        )]
+        pub readonly: Vec<HomieID>,
+    }
+}
+
+impl Default for DoorNodeConfig {
+    fn default() -> Self {
+        Self {
+            stop: true,
+            position: false,
+            lock: true,
+            auto_close: false,
+            readonly: Vec::new(),
+        }
+    }
+}
+
+impl DoorNodeConfig {
+    fn actions(&self) -> Vec<DoorAction> {
+        let mut actions = vec![DoorAction::Open, DoorAction::Close];
+        if self.stop {
+            actions.push(DoorAction::Stop);
+        }
+        actions
+    }
+}
+
+// ── Builder ─────────────────────────────────────────────────────────────────
+
+pub struct DoorNodeBuilder {
+    node_builder: NodeDescriptionBuilder,
+    config: DoorNodeConfig,
+}
+
+impl Default for DoorNodeBuilder {
+    fn default() -> Self {
+        Self::new(&DoorNodeConfig::default())
+    }
+}
+
+impl DoorNodeBuilder {
+    pub fn new(config: &DoorNodeConfig) -> Self {
+        let db = Self::build_node(
+            NodeDescriptionBuilder::new().name(DOOR_NODE_DEFAULT_NAME),
+            config,
+        )
+        .r#type(SMARTHOME_CAP_DOOR);
+        let db = readonly_properties(db, &config.readonly);
+
+        Self {
+            node_builder: db,
+            config: config.clone(),
+        }
+    }
+
+    fn build_node(db: NodeDescriptionBuilder, config: &DoorNodeConfig) -> NodeDescriptionBuilder {
+        config.add_optional_properties(
+            db.add_property(
+                DOOR_NODE_STATE_PROP_ID,
+                PropertyDescriptionBuilder::enumeration(DoorState::ALL.iter().map(|s| s.as_str()))
+                    .unwrap()
+                    .name("Door state")
+                    .settable(false)
+                    .retained(true)
+                    .build(),
+            )
+            .add_property(
+                DOOR_NODE_ACTION_PROP_ID,
+                PropertyDescriptionBuilder::enumeration(
+                    config.actions().iter().map(|a| a.as_str()),
+                )
+                .unwrap()
+                .name("Door action")
+                .settable(true)
+                .retained(false)
+                .build(),
+            )
+            .add_property_cond(DOOR_NODE_LOCKED_PROP_ID, config.lock, || {
+                PropertyDescriptionBuilder::boolean()
+                    .name("Door locked")
+                    .boolean_labels("unlocked", "locked")
+                    .settable(true)
+                    .retained(true)
+                    .build()
+            })
+            .add_property_cond(DOOR_NODE_AUTO_CLOSE_PROP_ID, config.auto_close, || {
+                PropertyDescriptionBuilder::integer()
+                    .name("Auto-close timeout")
+                    .unit(HOMIE_UNIT_SECONDS)
+                    .integer_range(IntegerRange {
+                        min: Some(0),
+                        max: None,
+                        step: None,
+                    })
+                    .settable(true)
+                    .retained(true)
+                    .build()
+            }),
+        )
+    }
+
+    pub fn name<S: Into<String>>(mut self, name: impl Into<Option<S>>) -> Self {
+        self.node_builder = self.node_builder.name(name);
+        self
+    }
+
+    /// Append a subtype suffix to the node type (`door-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::Door.with_subtype(subtype));
+        self
+    }
+
+    pub fn build(self) -> HomieNodeDescription {
+        self.node_builder.build()
+    }
+
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &DoorNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
+    pub fn build_with_publisher(
+        self,
+        node_id: HomieID,
+        client: &Homie5DeviceProtocol,
+    ) -> (HomieNodeDescription, DoorNodePublisher) {
+        (
+            self.node_builder.build(),
+            DoorNodePublisher::new(
+                NodeRef::new(
+                    client.homie_domain().to_owned(),
+                    client.id().clone(),
+                    node_id,
+                ),
+                client.clone(),
+                self.config,
+            ),
+        )
+    }
+}
+
+// ── Publisher ────────────────────────────────────────────────────────────────
+
+#[derive(Debug)]
+pub struct DoorNodePublisher {
+    client: Homie5DeviceProtocol,
+    node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
+    config: DoorNodeConfig,
+    state_prop: HomieID,
+    action_prop: HomieID,
+    position_prop: HomieID,
+    locked_prop: HomieID,
+    auto_close_prop: HomieID,
+}
+
+impl DoorNodePublisher {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: DoorNodeConfig) -> Self {
+        let metadata = property_metadata(&DoorNodeBuilder::new(&config).build());
+        Self {
+            node,
+            metadata,
+            client,
+            config,
+            state_prop: DOOR_NODE_STATE_PROP_ID,
+            action_prop: DOOR_NODE_ACTION_PROP_ID,
+            position_prop: DOOR_NODE_POSITION_PROP_ID,
+            locked_prop: DOOR_NODE_LOCKED_PROP_ID,
+            auto_close_prop: DOOR_NODE_AUTO_CLOSE_PROP_ID,
+        }
+    }
+
+    pub fn state(&self, state: DoorState) -> homie5::client::Publish {
+        self.client
+            .publish_value(self.node.node_id(), &self.state_prop, state.as_str(), true)
+    }
+
+    pub fn action(&self, action: DoorAction) -> homie5::client::Publish {
+        self.client.publish_value(
+            self.node.node_id(),
+            &self.action_prop,
+            action.as_str(),
+            false,
+        )
+    }
+
+    pub fn locked(&self, value: bool) -> Option<homie5::client::Publish> {
+        if !self.config.lock {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.locked_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+
+    pub fn locked_target(&self, value: bool) -> Option<homie5::client::Publish> {
+        if !self.config.lock {
+            return None;
+        }
+        Some(self.client.publish_target(
+            self.node.node_id(),
+            &self.locked_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+
+    pub fn auto_close(&self, value: i64) -> Option<homie5::client::Publish> {
+        if !self.config.auto_close {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.auto_close_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+
+    pub fn auto_close_target(&self, value: i64) -> Option<homie5::client::Publish> {
+        if !self.config.auto_close {
+            return None;
+        }
+        Some(self.client.publish_target(
+            self.node.node_id(),
+            &self.auto_close_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+}
+
+impl SmarthomePublisher for DoorNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::Door
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![
+            self.state_prop.clone(),
+            self.action_prop.clone(),
+            self.position_prop.clone(),
+            self.locked_prop.clone(),
+            self.auto_close_prop.clone(),
+        ]
+    }
+}
+
+impl SetCommandParser for DoorNodePublisher {
+    type Event = DoorNodeSetEvents;
+
+    fn parse_set(
+        &self,
+        property: &PropertyRef,
+        desc: &HomieDeviceDescription,
+        set_value: &str,
+    ) -> ParseOutcome<Self::Event> {
+        if let Some(outcome) = reject_readonly(&self.node, property, desc, set_value) {
+            return outcome;
+        }
+
+        let property_id = property.prop_id().to_string();
+
+        if property.match_with_node(&self.node, &self.action_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Enum(value)) => match DoorAction::from_str(&value) {
+                    Ok(action) => ParseOutcome::Parsed(DoorNodeSetEvents::Action(action)),
+                    Err(_) => ParseOutcome::Invalid(ParseError::new(
+                        property_id,
+                        set_value,
+                        ParseErrorKind::InvalidVariant,
+                    )),
+                },
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else if property.match_with_node(&self.node, &self.locked_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Bool(value)) => {
+                    ParseOutcome::Parsed(DoorNodeSetEvents::Locked(value))
+                }
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else if property.match_with_node(&self.node, &self.auto_close_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Integer(value)) => {
+                    ParseOutcome::Parsed(DoorNodeSetEvents::AutoClose(value))
+                }
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else {
+            ParseOutcome::NoMatch
+        }
+    }
+
+    fn parse_set_event(
+        &self,
+        desc: &HomieDeviceDescription,
+        event: &Homie5Message,
+    ) -> ParseOutcome<Self::Event> {
+        match event {
+            Homie5Message::PropertySet {
+                property,
+                set_value,
+            } => self.parse_set(property, desc, set_value),
+            _ => ParseOutcome::Invalid(ParseError::new(
+                self.action_prop.to_string(),
+                "",
+                ParseErrorKind::UnexpectedMessageType,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use homie5::HomieDomain;
+
+    use super::*;
+    use crate::StandaloneSetParser;
+
+    #[test]
+    fn actions_lock_and_auto_close_parse() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let config = DoorNodeConfig {
+            stop: false,
+            auto_close: true,
+            ..DoorNodeConfig::default()
+        };
+        let parser: StandaloneSetParser<_> = DoorNodeBuilder::new(&config)
+            .build_with_publisher(DOOR_NODE_DEFAULT_ID, &client)
+            .into();
+        let node = parser.publisher().node_ref().clone();
+        let action = PropertyRef::from_node(node.clone(), DOOR_NODE_ACTION_PROP_ID);
+        let locked = PropertyRef::from_node(node.clone(), DOOR_NODE_LOCKED_PROP_ID);
+        let auto_close = PropertyRef::from_node(node.clone(), DOOR_NODE_AUTO_CLOSE_PROP_ID);
+        let state = PropertyRef::from_node(node, DOOR_NODE_STATE_PROP_ID);
+
+        assert!(matches!(
+            parser.parse_set(&action, "open"),
+            ParseOutcome::Parsed(DoorNodeSetEvents::Action(DoorAction::Open))
+        ));
+        // `stop` is disabled in this config
+        assert!(matches!(
+            parser.parse_set(&action, "stop"),
+            ParseOutcome::Invalid(_)
+        ));
+        assert!(matches!(
+            parser.parse_set(&locked, "true"),
+            ParseOutcome::Parsed(DoorNodeSetEvents::Locked(true))
+        ));
+        assert!(matches!(
+            parser.parse_set(&auto_close, "30"),
+            ParseOutcome::Parsed(DoorNodeSetEvents::AutoClose(30))
+        ));
+        assert!(matches!(
+            parser.parse_set(&state, "open"),
+            ParseOutcome::Invalid(ParseError {
+                kind: ParseErrorKind::NotSettable,
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,521 @@
+use chrono::prelude::*;
+use std::collections::BTreeMap;
+
+use homie5::{
+    Homie5DeviceProtocol, Homie5Message, HomieID, HomieValue, NodeRef, PropertyRef,
+    device_description::{
+        HomieDeviceDescription, HomieNodeDescription, NodeDescriptionBuilder,
+        PropertyDescriptionBuilder,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ConfigError, ParseError, ParseErrorKind, ParseOutcome, PropertyMetadata,
+    SMARTHOME_CAP_DOORBELL, SetCommandParser, SmarthomePublisher, SmarthomeType, property_metadata,
+    readonly_properties, reject_readonly, validate_enum_format,
+};
+
+pub const DOORBELL_NODE_DEFAULT_ID: HomieID = HomieID::new_const("doorbell");
+pub const DOORBELL_NODE_DEFAULT_NAME: &str = "Doorbell";
+pub const DOORBELL_NODE_RING_PROP_ID: HomieID = HomieID::new_const("ring");
+pub const DOORBELL_NODE_LAST_RING_PROP_ID: HomieID = HomieID::new_const("last-ring");
+pub const DOORBELL_NODE_CHIME_PROP_ID: HomieID = HomieID::new_const("chime");
+pub const DOORBELL_NODE_MELODY_PROP_ID: HomieID = HomieID::new_const("melody");
+
+// ── Node (state) ────────────────────────────────────────────────────────────
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DoorbellNodeState {
+    pub last_ring: Option<DateTime<Utc>>,
+    pub chime: Option<bool>,
+    pub melody: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct DoorbellNode {
+    pub publisher: DoorbellNodePublisher,
+    pub state: DoorbellNodeState,
+}
+
+impl DoorbellNode {
+    /// Record a ring reported by the device and return the event publish plus
+    /// the `last-ring` timestamp if enabled.
+    pub fn ring(&mut self, time: DateTime<Utc>) -> Vec<homie5::client::Publish> {
+        self.state.last_ring = Some(time);
+        [Some(self.publisher.ring()), self.publisher.last_ring(time)]
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
+    /// Apply a parsed set command and return the `$target` and value publishes
+    /// for the change.
+    pub fn apply_set(&mut self, event: DoorbellNodeSetEvents) -> Vec<homie5::client::Publish> {
+        match event {
+            DoorbellNodeSetEvents::Chime(value) => {
+                self.state.chime = Some(value);
+                [
+                    self.publisher.chime_target(value),
+                    self.publisher.chime(value),
+                ]
+                .into_iter()
+                .flatten()
+                .collect()
+            }
+            DoorbellNodeSetEvents::Melody(value) => {
+                let publishes = [
+                    self.publisher.melody_target(&value),
+                    self.publisher.melody(&value),
+                ]
+                .into_iter()
+                .flatten()
+                .collect();
+                self.state.melody = Some(value);
+                publishes
+            }
+        }
+    }
+
+    /// Apply a value reported by the device and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Bool(value)) if p == &DOORBELL_NODE_CHIME_PROP_ID => {
+                self.state.chime = Some(*value);
+                self.publisher.chime(*value).into_iter().collect()
+            }
+            (p, HomieValue::Enum(value)) if p == &DOORBELL_NODE_MELODY_PROP_ID => {
+                self.state.melody = Some(value.clone());
+                self.publisher.melody(value).into_iter().collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            self.state
+                .last_ring
+                .and_then(|value| self.publisher.last_ring(value)),
+            self.state
+                .chime
+                .and_then(|value| self.publisher.chime(value)),
+            self.state
+                .melody
+                .as_deref()
+                .and_then(|value| self.publisher.melody(value)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+// ── Set events ──────────────────────────────────────────────────────────────
+
+#[derive(Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+pub enum DoorbellNodeSetEvents {
+    Chime(bool),
+    Melody(String),
+}
+
+// ── Config ──────────────────────────────────────────────────────────────────
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+#[serde(default)]
+pub struct DoorbellNodeConfig {
+    /// Publish the time of the last ring as retained `last-ring`.
+    pub last_ring: bool,
+    /// Expose a settable `chime` switch to mute the indoor chime.
+    pub chime: bool,
+    /// Expose a settable `melody` selection from `melodies`.
+    pub melody: bool,
+    #[cfg_attr(
+        feature = "proptest",
+        proptest(strategy = "crate::arbitrary::enum_format()")
+    )]
+    pub melodies: Vec<String>,
+    /// Properties to publish as read-only; sets to them are rejected.
+    #[cfg_attr(
+        feature = "proptest",
+        proptest(strategy = "crate::arbitrary::homie_ids()")
+    )]
+    pub readonly: Vec<HomieID>,
+}
+
+impl Default for DoorbellNodeConfig {
+    fn default() -> Self {
+        Self {
+            last_ring: false,
+            chime: false,
+            melody: false,
+            melodies: vec!["default".to_owned()],
+            readonly: Vec::new(),
+        }
+    }
+}
+
+impl DoorbellNodeConfig {
+    /// Check the config-provided enum values before building the node.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.melody {
+            validate_enum_format(&DOORBELL_NODE_MELODY_PROP_ID, &self.melodies)?;
+        }
+        Ok(())
+    }
+}
+
+// ── Builder ─────────────────────────────────────────────────────────────────
+
+pub struct DoorbellNodeBuilder {
+    node_builder: NodeDescriptionBuilder,
+    config: DoorbellNodeConfig,
+}
+
+impl Default for DoorbellNodeBuilder {
+    fn default() -> Self {
+        Self::new(&DoorbellNodeConfig::default())
+    }
+}
+
+impl DoorbellNodeBuilder {
+    pub fn new(config: &DoorbellNodeConfig) -> Self {
+        let db = Self::build_node(
+            NodeDescriptionBuilder::new().name(DOORBELL_NODE_DEFAULT_NAME),
+            config,
+        )
+        .r#type(SMARTHOME_CAP_DOORBELL);
+        let db = readonly_properties(db, &config.readonly);
+
+        Self {
+            node_builder: db,
+            config: config.clone(),
+        }
+    }
+
+    fn build_node(
+        db: NodeDescriptionBuilder,
+        config: &DoorbellNodeConfig,
+    ) -> NodeDescriptionBuilder {
+        db.add_property(
+            DOORBELL_NODE_RING_PROP_ID,
+            PropertyDescriptionBuilder::enumeration(["ring"])
+                .unwrap()
+                .name("Doorbell ring")
+                .settable(false)
+                .retained(false)
+                .build(),
+        )
+        .add_property_cond(DOORBELL_NODE_LAST_RING_PROP_ID, config.last_ring, || {
+            PropertyDescriptionBuilder::datetime()
+                .name("Last ring")
+                .settable(false)
+                .retained(true)
+                .build()
+        })
+        .add_property_cond(DOORBELL_NODE_CHIME_PROP_ID, config.chime, || {
+            PropertyDescriptionBuilder::boolean()
+                .name("Chime enabled")
+                .boolean_labels("off", "on")
+                .settable(true)
+                .retained(true)
+                .build()
+        })
+        .add_property_cond(DOORBELL_NODE_MELODY_PROP_ID, config.melody, || {
+            PropertyDescriptionBuilder::enumeration(config.melodies.iter().map(|s| s.as_str()))
+                .unwrap()
+                .name("Chime melody")
+                .settable(true)
+                .retained(true)
+                .build()
+        })
+    }
+
+    pub fn name<S: Into<String>>(mut self, name: impl Into<Option<S>>) -> Self {
+        self.node_builder = self.node_builder.name(name);
+        self
+    }
+
+    /// Append a subtype suffix to the node type (`doorbell-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::Doorbell.with_subtype(subtype));
+        self
+    }
+
+    pub fn build(self) -> HomieNodeDescription {
+        self.node_builder.build()
+    }
+
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &DoorbellNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
+    pub fn build_with_publisher(
+        self,
+        node_id: HomieID,
+        client: &Homie5DeviceProtocol,
+    ) -> (HomieNodeDescription, DoorbellNodePublisher) {
+        (
+            self.node_builder.build(),
+            DoorbellNodePublisher::new(
+                NodeRef::new(
+                    client.homie_domain().to_owned(),
+                    client.id().clone(),
+                    node_id,
+                ),
+                client.clone(),
+                self.config,
+            ),
+        )
+    }
+}
+
+// ── Publisher ────────────────────────────────────────────────────────────────
+
+#[derive(Debug)]
+pub struct DoorbellNodePublisher {
+    client: Homie5DeviceProtocol,
+    node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
+    config: DoorbellNodeConfig,
+    ring_prop: HomieID,
+    last_ring_prop: HomieID,
+    chime_prop: HomieID,
+    melody_prop: HomieID,
+}
+
+impl DoorbellNodePublisher {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: DoorbellNodeConfig) -> Self {
+        let metadata = property_metadata(&DoorbellNodeBuilder::new(&config).build());
+        Self {
+            node,
+            metadata,
+            client,
+            config,
+            ring_prop: DOORBELL_NODE_RING_PROP_ID,
+            last_ring_prop: DOORBELL_NODE_LAST_RING_PROP_ID,
+            chime_prop: DOORBELL_NODE_CHIME_PROP_ID,
+            melody_prop: DOORBELL_NODE_MELODY_PROP_ID,
+        }
+    }
+
+    pub fn ring(&self) -> homie5::client::Publish {
+        self.client
+            .publish_value(self.node.node_id(), &self.ring_prop, "ring", false)
+    }
+
+    pub fn last_ring(&self, value: DateTime<Utc>) -> Option<homie5::client::Publish> {
+        if !self.config.last_ring {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.last_ring_prop,
+            value.to_rfc3339_opts(SecondsFormat::Millis, true),
+            true,
+        ))
+    }
+
+    pub fn chime(&self, value: bool) -> Option<homie5::client::Publish> {
+        if !self.config.chime {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.chime_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+
+    pub fn chime_target(&self, value: bool) -> Option<homie5::client::Publish> {
+        if !self.config.chime {
+            return None;
+        }
+        Some(self.client.publish_target(
+            self.node.node_id(),
+            &self.chime_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+
+    pub fn melody(&self, value: &str) -> Option<homie5::client::Publish> {
+        if !self.config.melody {
+            return None;
+        }
+        Some(
+            self.client
+                .publish_value(self.node.node_id(), &self.melody_prop, value, true),
+        )
+    }
+
+    pub fn melody_target(&self, value: &str) -> Option<homie5::client::Publish> {
+        if !self.config.melody {
+            return None;
+        }
+        Some(
+            self.client
+                .publish_target(self.node.node_id(), &self.melody_prop, value, true),
+        )
+    }
+}
+
+impl SmarthomePublisher for DoorbellNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::Doorbell
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![
+            self.ring_prop.clone(),
+            self.last_ring_prop.clone(),
+            self.chime_prop.clone(),
+            self.melody_prop.clone(),
+        ]
+    }
+}
+
+impl SetCommandParser for DoorbellNodePublisher {
+    type Event = DoorbellNodeSetEvents;
+
+    fn parse_set(
+        &self,
+        property: &PropertyRef,
+        desc: &HomieDeviceDescription,
+        set_value: &str,
+    ) -> ParseOutcome<Self::Event> {
+        if let Some(outcome) = reject_readonly(&self.node, property, desc, set_value) {
+            return outcome;
+        }
+
+        let property_id = property.prop_id().to_string();
+
+        if property.match_with_node(&self.node, &self.chime_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Bool(value)) => {
+                    ParseOutcome::Parsed(DoorbellNodeSetEvents::Chime(value))
+                }
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else if property.match_with_node(&self.node, &self.melody_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Enum(value)) => {
+                    ParseOutcome::Parsed(DoorbellNodeSetEvents::Melody(value))
+                }
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else {
+            ParseOutcome::NoMatch
+        }
+    }
+
+    fn parse_set_event(
+        &self,
+        desc: &HomieDeviceDescription,
+        event: &Homie5Message,
+    ) -> ParseOutcome<Self::Event> {
+        match event {
+            Homie5Message::PropertySet {
+                property,
+                set_value,
+            } => self.parse_set(property, desc, set_value),
+            _ => ParseOutcome::Invalid(ParseError::new(
+                self.ring_prop.to_string(),
+                "",
+                ParseErrorKind::UnexpectedMessageType,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use homie5::HomieDomain;
+
+    use super::*;
+    use crate::StandaloneSetParser;
+
+    #[test]
+    fn melody_set_parses_and_updates_state() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let config = DoorbellNodeConfig {
+            melody: true,
+            melodies: vec!["ding-dong".to_owned(), "westminster".to_owned()],
+            ..DoorbellNodeConfig::default()
+        };
+        let parser: StandaloneSetParser<_> = DoorbellNodeBuilder::new(&config)
+            .build_with_publisher(DOORBELL_NODE_DEFAULT_ID, &client)
+            .into();
+        let property = PropertyRef::from_node(
+            parser.publisher().node_ref().clone(),
+            DOORBELL_NODE_MELODY_PROP_ID,
+        );
+
+        let ParseOutcome::Parsed(event) = parser.parse_set(&property, "westminster") else {
+            panic!("configured melody must parse");
+        };
+        assert!(matches!(
+            parser.parse_set(&property, "fanfare"),
+            ParseOutcome::Invalid(_)
+        ));
+
+        let mut doorbell = DoorbellNode {
+            publisher: parser.into_publisher(),
+            state: DoorbellNodeState::default(),
+        };
+        assert_eq!(doorbell.apply_set(event).len(), 2);
+        assert_eq!(doorbell.state.melody.as_deref(), Some("westminster"));
+        // `last-ring` is disabled, so only the ring event is published.
+        assert_eq!(doorbell.ring(Utc::now()).len(), 1);
+    }
+}
@@ -0,0 +1,411 @@
+use std::collections::BTreeMap;
+
+use homie5::{
+    HOMIE_UNIT_KILOWATTHOUR, HOMIE_UNIT_PERCENT, HOMIE_UNIT_WATT, Homie5DeviceProtocol, HomieID,
+    HomieValue, NodeRef,
+    device_description::{
+        FloatRange, HomieNodeDescription, IntegerRange, NodeDescriptionBuilder,
+        PropertyDescriptionBuilder,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    PropertyMetadata, SMARTHOME_CAP_ENERGY_DASHBOARD, SmarthomePublisher, SmarthomeType,
+    property_metadata, smarthome_node_config,
+};
+
+pub const ENERGY_DASHBOARD_NODE_DEFAULT_ID: HomieID = HomieID::new_const("energy-dashboard");
+pub const ENERGY_DASHBOARD_NODE_DEFAULT_NAME: &str = "Energy dashboard";
+pub const ENERGY_DASHBOARD_NODE_CONSUMPTION_PROP_ID: HomieID = HomieID::new_const("consumption");
+pub const ENERGY_DASHBOARD_NODE_PRODUCTION_PROP_ID: HomieID = HomieID::new_const("production");
+pub const ENERGY_DASHBOARD_NODE_SELF_CONSUMPTION_PROP_ID: HomieID =
+    HomieID::new_const("self-consumption");
+pub const ENERGY_DASHBOARD_NODE_GRID_IMPORT_PROP_ID: HomieID = HomieID::new_const("grid-import");
+pub const ENERGY_DASHBOARD_NODE_GRID_EXPORT_PROP_ID: HomieID = HomieID::new_const("grid-export");
+
+// ── Node (state) ────────────────────────────────────────────────────────────
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EnergyDashboardNodeState {
+    pub consumption: f64,
+    pub production: Option<f64>,
+    pub self_consumption: Option<i64>,
+    pub grid_import: Option<f64>,
+    pub grid_export: Option<f64>,
+}
+
+impl EnergyDashboardNodeState {
+    /// Share of the produced energy that was used locally instead of being
+    /// exported, in percent. `None` while nothing has been produced.
+    pub fn self_consumption_from(production: f64, exported: f64) -> Option<i64> {
+        if production <= 0.0 {
+            return None;
+        }
+        let used = (production - exported).clamp(0.0, production);
+        Some((used / production * 100.0).round() as i64)
+    }
+}
+
+#[derive(Debug)]
+pub struct EnergyDashboardNode {
+    pub publisher: EnergyDashboardNodePublisher,
+    pub state: EnergyDashboardNodeState,
+}
+
+impl EnergyDashboardNode {
+    /// Apply a value reported by the device and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Float(value)) if p == &ENERGY_DASHBOARD_NODE_CONSUMPTION_PROP_ID => {
+                self.state.consumption = *value;
+                vec![self.publisher.consumption(*value)]
+            }
+            (p, HomieValue::Float(value)) if p == &ENERGY_DASHBOARD_NODE_PRODUCTION_PROP_ID => {
+                self.state.production = Some(*value);
+                self.publisher.production(*value).into_iter().collect()
+            }
+            (p, HomieValue::Integer(value))
+                if p == &ENERGY_DASHBOARD_NODE_SELF_CONSUMPTION_PROP_ID =>
+            {
+                self.state.self_consumption = Some(*value);
+                self.publisher
+                    .self_consumption(*value)
+                    .into_iter()
+                    .collect()
+            }
+            (p, HomieValue::Float(value)) if p == &ENERGY_DASHBOARD_NODE_GRID_IMPORT_PROP_ID => {
+                self.state.grid_import = Some(*value);
+                self.publisher.grid_import(*value).into_iter().collect()
+            }
+            (p, HomieValue::Float(value)) if p == &ENERGY_DASHBOARD_NODE_GRID_EXPORT_PROP_ID => {
+                self.state.grid_export = Some(*value);
+                self.publisher.grid_export(*value).into_iter().collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            Some(self.publisher.consumption(self.state.consumption)),
+            self.state
+                .production
+                .and_then(|value| self.publisher.production(value)),
+            self.state
+                .self_consumption
+                .and_then(|value| self.publisher.self_consumption(value)),
+            self.state
+                .grid_import
+                .and_then(|value| self.publisher.grid_import(value)),
+            self.state
+                .grid_export
+                .and_then(|value| self.publisher.grid_export(value)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+// ── Config ──────────────────────────────────────────────────────────────────
+
+smarthome_node_config! {
+    #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+    #[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+    #[serde(default)]
+    pub struct EnergyDashboardNodeConfig for EnergyDashboardNodePublisher {
+        /// Expose the total energy produced locally (e.g. PV) in kWh.
+        pub production: bool => ENERGY_DASHBOARD_NODE_PRODUCTION_PROP_ID, production(f64), |_config| {
+            PropertyDescriptionBuilder::float()
+                .name("Total production")
+                .unit(HOMIE_UNIT_KILOWATTHOUR)
+                .float_range(FloatRange {
+                    min: Some(0.0),
+                    max: None,
+                    step: None,
+                })
+                .settable(false)
+                .retained(true)
+                .build()
+        },
+        /// Expose the share of the production used locally in percent.
+        pub self_consumption: bool => ENERGY_DASHBOARD_NODE_SELF_CONSUMPTION_PROP_ID, self_consumption(i64), |_config| {
+            PropertyDescriptionBuilder::integer()
+                .name("Self-consumption")
+                .unit(HOMIE_UNIT_PERCENT)
+                .integer_range(IntegerRange {
+                    min: Some(0),
+                    max: Some(100),
+                    step: None,
+                })
+                .settable(false)
+                .retained(true)
+                .build()
+        },
+        /// Expose the power currently drawn from the grid in W.
+        pub grid_import: bool => ENERGY_DASHBOARD_NODE_GRID_IMPORT_PROP_ID, grid_import(f64), |_config| {
+            PropertyDescriptionBuilder::float()
+                .name("Grid import power")
+                .unit(HOMIE_UNIT_WATT)
+                .float_range(FloatRange {
+                    min: Some(0.0),
+                    max: None,
+                    step: None,
+                })
+                .settable(false)
+                .retained(true)
+                .build()
+        },
+        /// Expose the power currently fed into the grid in W.
+        pub grid_export: bool => ENERGY_DASHBOARD_NODE_GRID_EXPORT_PROP_ID, grid_export(f64), |_config| {
+            PropertyDescriptionBuilder::float()
+                .name("Grid export power")
+                .unit(HOMIE_UNIT_WATT)
+                .float_range(FloatRange {
+                    min: Some(0.0),
+                    max: None,
+                    step: None,
+                })
+                .settable(false)
+                .retained(true)
+                .build()
+        },
+    }
+}
+
+impl Default for EnergyDashboardNodeConfig {
+    fn default() -> Self {
+        Self {
+            production: true,
+            self_consumption: true,
+            grid_import: true,
+            grid_export: true,
+        }
+    }
+}
+
+// ── Builder ─────────────────────────────────────────────────────────────────
+
+pub struct EnergyDashboardNodeBuilder {
+    node_builder: NodeDescriptionBuilder,
+    config: EnergyDashboardNodeConfig,
+}
+
+impl Default for EnergyDashboardNodeBuilder {
+    fn default() -> Self {
+        Self::new(&EnergyDashboardNodeConfig::default())
+    }
+}
+
+impl EnergyDashboardNodeBuilder {
+    pub fn new(config: &EnergyDashboardNodeConfig) -> Self {
+        let db = Self::build_node(
+            NodeDescriptionBuilder::new().name(ENERGY_DASHBOARD_NODE_DEFAULT_NAME),
+            config,
+        )
+        .r#type(SMARTHOME_CAP_ENERGY_DASHBOARD);
+
+        Self {
+            node_builder: db,
+            config: config.clone(),
+        }
+    }
+
+    fn build_node(
+        db: NodeDescriptionBuilder,
+        config: &EnergyDashboardNodeConfig,
+    ) -> NodeDescriptionBuilder {
+        config.add_optional_properties(
+            db.add_property(
+                ENERGY_DASHBOARD_NODE_CONSUMPTION_PROP_ID,
+                PropertyDescriptionBuilder::float()
+                    .name("Total consumption")
+                    .unit(HOMIE_UNIT_KILOWATTHOUR)
+                    .float_range(FloatRange {
+                        min: Some(0.0),
+                        max: None,
+                        step: None,
+                    })
+                    .settable(false)
+                    .retained(true)
+                    .build(),
+            ),
+        )
+    }
+
+    pub fn name<S: Into<String>>(mut self, name: impl Into<Option<S>>) -> Self {
+        self.node_builder = self.node_builder.name(name);
+        self
+    }
+
+    /// Append a subtype suffix to the node type (`energy-dashboard-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::EnergyDashboard.with_subtype(subtype));
+        self
+    }
+
+    pub fn build(self) -> HomieNodeDescription {
+        self.node_builder.build()
+    }
+
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &EnergyDashboardNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
+    pub fn build_with_publisher(
+        self,
+        node_id: HomieID,
+        client: &Homie5DeviceProtocol,
+    ) -> (HomieNodeDescription, EnergyDashboardNodePublisher) {
+        (
+            self.node_builder.build(),
+            EnergyDashboardNodePublisher::new(
+                NodeRef::new(
+                    client.homie_domain().to_owned(),
+                    client.id().clone(),
+                    node_id,
+                ),
+                client.clone(),
+                self.config,
+            ),
+        )
+    }
+}
+
+// ── Publisher ────────────────────────────────────────────────────────────────
+
+#[derive(Debug)]
+pub struct EnergyDashboardNodePublisher {
+    client: Homie5DeviceProtocol,
+    node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
+    config: EnergyDashboardNodeConfig,
+    consumption_prop: HomieID,
+    production_prop: HomieID,
+    self_consumption_prop: HomieID,
+    grid_import_prop: HomieID,
+    grid_export_prop: HomieID,
+}
+
+impl EnergyDashboardNodePublisher {
+    pub fn new(
+        node: NodeRef,
+        client: Homie5DeviceProtocol,
+        config: EnergyDashboardNodeConfig,
+    ) -> Self {
+        let metadata = property_metadata(&EnergyDashboardNodeBuilder::new(&config).build());
+        Self {
+            node,
+            metadata,
+            client,
+            config,
+            consumption_prop: ENERGY_DASHBOARD_NODE_CONSUMPTION_PROP_ID,
+            production_prop: ENERGY_DASHBOARD_NODE_PRODUCTION_PROP_ID,
+            self_consumption_prop: ENERGY_DASHBOARD_NODE_SELF_CONSUMPTION_PROP_ID,
+            grid_import_prop: ENERGY_DASHBOARD_NODE_GRID_IMPORT_PROP_ID,
+            grid_export_prop: ENERGY_DASHBOARD_NODE_GRID_EXPORT_PROP_ID,
+        }
+    }
+
+    pub fn consumption(&self, value: f64) -> homie5::client::Publish {
+        self.client.publish_value(
+            self.node.node_id(),
+            &self.consumption_prop,
+            value.to_string(),
+            true,
+        )
+    }
+}
+
+impl SmarthomePublisher for EnergyDashboardNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::EnergyDashboard
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![
+            self.consumption_prop.clone(),
+            self.production_prop.clone(),
+            self.self_consumption_prop.clone(),
+            self.grid_import_prop.clone(),
+            self.grid_export_prop.clone(),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use homie5::HomieDomain;
+
+    use super::*;
+
+    #[test]
+    fn self_consumption_is_derived_from_production_and_export() {
+        assert_eq!(
+            EnergyDashboardNodeState::self_consumption_from(10.0, 2.5),
+            Some(75)
+        );
+        assert_eq!(
+            EnergyDashboardNodeState::self_consumption_from(10.0, 12.0),
+            Some(0)
+        );
+        assert_eq!(
+            EnergyDashboardNodeState::self_consumption_from(0.0, 0.0),
+            None
+        );
+    }
+
+    #[test]
+    fn disabled_grid_power_is_not_published() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let config = EnergyDashboardNodeConfig {
+            grid_import: false,
+            grid_export: false,
+            ..Default::default()
+        };
+        let (desc, publisher) = EnergyDashboardNodeBuilder::new(&config)
+            .build_with_publisher(ENERGY_DASHBOARD_NODE_DEFAULT_ID, &client);
+
+        assert!(
+            desc.properties
+                .contains_key(&ENERGY_DASHBOARD_NODE_PRODUCTION_PROP_ID)
+        );
+        assert!(
+            !desc
+                .properties
+                .contains_key(&ENERGY_DASHBOARD_NODE_GRID_IMPORT_PROP_ID)
+        );
+
+        let node = EnergyDashboardNode {
+            publisher,
+            state: EnergyDashboardNodeState {
+                consumption: 1234.5,
+                production: Some(800.0),
+                grid_import: Some(300.0),
+                grid_export: Some(0.0),
+                ..Default::default()
+            },
+        };
+        assert_eq!(node.publishes().len(), 2);
+    }
+}
@@ -0,0 +1,602 @@
+use core::fmt;
+use std::{collections::BTreeMap, str::FromStr};
+
+use homie5::{
+    HOMIE_UNIT_AMPERE, HOMIE_UNIT_KILOWATTHOUR, Homie5DeviceProtocol, Homie5Message,
+    Homie5ProtocolError, HomieID, HomieValue, NodeRef, PropertyRef,
+    device_description::{
+        FloatRange, HomieDeviceDescription, HomieNodeDescription, IntegerRange,
+        NodeDescriptionBuilder, PropertyDescriptionBuilder,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ParseError, ParseErrorKind, ParseOutcome, PropertyMetadata, SMARTHOME_CAP_EV_CHARGER,
+    SetCommandParser, SmarthomePublisher, SmarthomeType, property_metadata, readonly_properties,
+    reject_readonly, smarthome_node_config,
+};
+
+pub const EV_CHARGER_NODE_DEFAULT_ID: HomieID = HomieID::new_const("ev-charger");
+pub const EV_CHARGER_NODE_DEFAULT_NAME: &str = "EV charger";
+pub const EV_CHARGER_NODE_STATE_PROP_ID: HomieID = HomieID::new_const("state");
+pub const EV_CHARGER_NODE_ACTION_PROP_ID: HomieID = HomieID::new_const("action");
+pub const EV_CHARGER_NODE_CURRENT_LIMIT_PROP_ID: HomieID =
+    HomieID::new_const("charging-current-limit");
+pub const EV_CHARGER_NODE_SESSION_ENERGY_PROP_ID: HomieID = HomieID::new_const("session-energy");
+pub const EV_CHARGER_NODE_CABLE_CONNECTED_PROP_ID: HomieID = HomieID::new_const("cable-connected");
+
+// ── Node (state) ────────────────────────────────────────────────────────────
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EvChargerNodeState {
+    pub state: EvChargerState,
+    pub current_limit: i64,
+    pub session_energy: Option<f64>,
+    pub cable_connected: bool,
+}
+
+#[derive(Debug)]
+pub struct EvChargerNode {
+    pub publisher: EvChargerNodePublisher,
+    pub state: EvChargerNodeState,
+}
+
+impl EvChargerNode {
+    /// Apply a parsed set command and return the `$target` and value publishes
+    /// for the change. Start/stop actions are commands for the wallbox; the
+    /// resulting `state` is reported back by the device.
+    pub fn apply_set(&mut self, event: EvChargerNodeSetEvents) -> Vec<homie5::client::Publish> {
+        match event {
+            EvChargerNodeSetEvents::Action(_) => Vec::new(),
+            EvChargerNodeSetEvents::CurrentLimit(value) => {
+                self.state.current_limit = value;
+                vec![
+                    self.publisher.current_limit_target(value),
+                    self.publisher.current_limit(value),
+                ]
+            }
+        }
+    }
+
+    /// Apply a value reported by the device and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Enum(value)) if p == &EV_CHARGER_NODE_STATE_PROP_ID => {
+                let Ok(state) = value.parse::<EvChargerState>() else {
+                    return Vec::new();
+                };
+                self.state.state = state;
+                vec![self.publisher.state(state)]
+            }
+            (p, HomieValue::Integer(value)) if p == &EV_CHARGER_NODE_CURRENT_LIMIT_PROP_ID => {
+                self.state.current_limit = *value;
+                vec![self.publisher.current_limit(*value)]
+            }
+            (p, HomieValue::Float(value)) if p == &EV_CHARGER_NODE_SESSION_ENERGY_PROP_ID => {
+                self.state.session_energy = Some(*value);
+                self.publisher.session_energy(*value).into_iter().collect()
+            }
+            (p, HomieValue::Bool(value)) if p == &EV_CHARGER_NODE_CABLE_CONNECTED_PROP_ID => {
+                self.state.cable_connected = *value;
+                vec![self.publisher.cable_connected(*value)]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            Some(self.publisher.state(self.state.state)),
+            Some(self.publisher.current_limit(self.state.current_limit)),
+            self.state
+                .session_energy
+                .and_then(|value| self.publisher.session_energy(value)),
+            Some(self.publisher.cable_connected(self.state.cable_connected)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+// ── State ───────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EvChargerState {
+    /// No vehicle connected.
+    #[default]
+    Available,
+    /// Vehicle connected, not charging yet.
+    Connected,
+    Charging,
+    /// Charging paused by the vehicle or the wallbox (e.g. no surplus power).
+    Suspended,
+    /// Vehicle connected, charging session completed.
+    Finished,
+    Error,
+}
+
+impl EvChargerState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Available => "available",
+            Self::Connected => "connected",
+            Self::Charging => "charging",
+            Self::Suspended => "suspended",
+            Self::Finished => "finished",
+            Self::Error => "error",
+        }
+    }
+
+    pub const ALL: [EvChargerState; 6] = [
+        EvChargerState::Available,
+        EvChargerState::Connected,
+        EvChargerState::Charging,
+        EvChargerState::Suspended,
+        EvChargerState::Finished,
+        EvChargerState::Error,
+    ];
+}
+
+impl fmt::Display for EvChargerState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for EvChargerState {
+    type Err = Homie5ProtocolError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "available" => Ok(Self::Available),
+            "connected" => Ok(Self::Connected),
+            "charging" => Ok(Self::Charging),
+            "suspended" => Ok(Self::Suspended),
+            "finished" => Ok(Self::Finished),
+            "error" => Ok(Self::Error),
+            _ => Err(Homie5ProtocolError::InvalidPayload),
+        }
+    }
+}
+
+// ── Actions / set events ────────────────────────────────────────────────────
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+pub enum EvChargerNodeActions {
+    Start,
+    Stop,
+}
+
+impl EvChargerNodeActions {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Start => "start",
+            Self::Stop => "stop",
+        }
+    }
+
+    pub const ALL: [EvChargerNodeActions; 2] =
+        [EvChargerNodeActions::Start, EvChargerNodeActions::Stop];
+}
+
+impl fmt::Display for EvChargerNodeActions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for EvChargerNodeActions {
+    type Err = Homie5ProtocolError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "start" => Ok(Self::Start),
+            "stop" => Ok(Self::Stop),
+            _ => Err(Homie5ProtocolError::InvalidPayload),
+        }
+    }
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+pub enum EvChargerNodeSetEvents {
+    Action(EvChargerNodeActions),
+    CurrentLimit(i64),
+}
+
+// ── Config ──────────────────────────────────────────────────────────────────
+
+smarthome_node_config! {
+    #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+    #[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+    #[serde(default)]
+    pub struct EvChargerNodeConfig for EvChargerNodePublisher {
+        /// Lowest settable charging current in ampere (6 A per IEC 61851).
+        pub min_current: i64,
+        /// Highest settable charging current in ampere, as wired/installed.
+        pub max_current: i64,
+        /// Expose the energy delivered in the current session in kWh.
+        pub session_energy: bool => EV_CHARGER_NODE_SESSION_ENERGY_PROP_ID, session_energy(f64), |_config| {
+            PropertyDescriptionBuilder::float()
+                .name("Session energy")
+                .unit(HOMIE_UNIT_KILOWATTHOUR)
+                .float_range(FloatRange {
+                    min: Some(0.0),
+                    max: None,
+                    step: None,
+                })
+                .settable(false)
+                .retained(true)
+                .build()
+        },
+        /// Properties to publish as read-only; sets to them are rejected.
+        #[cfg_attr(
+            feature = "proptest",
+            proptest(strategy = "crate::arbitrary::homie_ids()")
+        )]
+        pub readonly: Vec<HomieID>,
+    }
+}
+
+impl Default for EvChargerNodeConfig {
+    fn default() -> Self {
+        Self {
+            min_current: 6,
+            max_current: 16,
+            session_energy: true,
+            readonly: Vec::new(),
+        }
+    }
+}
+
+// ── Builder ─────────────────────────────────────────────────────────────────
+
+pub struct EvChargerNodeBuilder {
+    node_builder: NodeDescriptionBuilder,
+    config: EvChargerNodeConfig,
+}
+
+impl Default for EvChargerNodeBuilder {
+    fn default() -> Self {
+        Self::new(&EvChargerNodeConfig::default())
+    }
+}
+
+impl EvChargerNodeBuilder {
+    pub fn new(config: &EvChargerNodeConfig) -> Self {
+        let db = Self::build_node(
+            NodeDescriptionBuilder::new().name(EV_CHARGER_NODE_DEFAULT_NAME),
+            config,
+        )
+        .r#type(SMARTHOME_CAP_EV_CHARGER);
+        let db = readonly_properties(db, &config.readonly);
+
+        Self {
+            node_builder: db,
+            config: config.clone(),
+        }
+    }
+
+    fn build_node(
+        db: NodeDescriptionBuilder,
+        config: &EvChargerNodeConfig,
+    ) -> NodeDescriptionBuilder {
+        config.add_optional_properties(
+            db.add_property(
+                EV_CHARGER_NODE_STATE_PROP_ID,
+                PropertyDescriptionBuilder::enumeration(
+                    EvChargerState::ALL.iter().map(|s| s.as_str()),
+                )
+                .unwrap()
+                .name("Charging state")
+                .settable(false)
+                .retained(true)
+                .build(),
+            )
+            .add_property(
+                EV_CHARGER_NODE_ACTION_PROP_ID,
+                PropertyDescriptionBuilder::enumeration(
+                    EvChargerNodeActions::ALL.iter().map(|a| a.as_str()),
+                )
+                .unwrap()
+                .name("Charging action")
+                .settable(true)
+                .retained(false)
+                .build(),
+            )
+            .add_property(
+                EV_CHARGER_NODE_CURRENT_LIMIT_PROP_ID,
+                PropertyDescriptionBuilder::integer()
+                    .name("Charging current limit")
+                    .unit(HOMIE_UNIT_AMPERE)
+                    .integer_range(IntegerRange {
+                        min: Some(config.min_current),
+                        max: Some(config.max_current),
+                        step: None,
+                    })
+                    .settable(true)
+                    .retained(true)
+                    .build(),
+            )
+            .add_property(
+                EV_CHARGER_NODE_CABLE_CONNECTED_PROP_ID,
+                PropertyDescriptionBuilder::boolean()
+                    .name("Cable connected")
+                    .boolean_labels("disconnected", "connected")
+                    .settable(false)
+                    .retained(true)
+                    .build(),
+            ),
+        )
+    }
+
+    pub fn name<S: Into<String>>(mut self, name: impl Into<Option<S>>) -> Self {
+        self.node_builder = self.node_builder.name(name);
+        self
+    }
+
+    /// Append a subtype suffix to the node type (`ev-charger-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::EvCharger.with_subtype(subtype));
+        self
+    }
+
+    pub fn build(self) -> HomieNodeDescription {
+        self.node_builder.build()
+    }
+
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &EvChargerNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
+    pub fn build_with_publisher(
+        self,
+        node_id: HomieID,
+        client: &Homie5DeviceProtocol,
+    ) -> (HomieNodeDescription, EvChargerNodePublisher) {
+        (
+            self.node_builder.build(),
+            EvChargerNodePublisher::new(
+                NodeRef::new(
+                    client.homie_domain().to_owned(),
+                    client.id().clone(),
+                    node_id,
+                ),
+                client.clone(),
+                self.config,
+            ),
+        )
+    }
+}
+
+// ── Publisher ────────────────────────────────────────────────────────────────
+
+#[derive(Debug)]
+pub struct EvChargerNodePublisher {
+    client: Homie5DeviceProtocol,
+    node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
+    config: EvChargerNodeConfig,
+    state_prop: HomieID,
+    action_prop: HomieID,
+    current_limit_prop: HomieID,
+    session_energy_prop: HomieID,
+    cable_connected_prop: HomieID,
+}
+
+impl EvChargerNodePublisher {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: EvChargerNodeConfig) -> Self {
+        let metadata = property_metadata(&EvChargerNodeBuilder::new(&config).build());
+        Self {
+            node,
+            metadata,
+            client,
+            config,
+            state_prop: EV_CHARGER_NODE_STATE_PROP_ID,
+            action_prop: EV_CHARGER_NODE_ACTION_PROP_ID,
+            current_limit_prop: EV_CHARGER_NODE_CURRENT_LIMIT_PROP_ID,
+            session_energy_prop: EV_CHARGER_NODE_SESSION_ENERGY_PROP_ID,
+            cable_connected_prop: EV_CHARGER_NODE_CABLE_CONNECTED_PROP_ID,
+        }
+    }
+
+    pub fn state(&self, state: EvChargerState) -> homie5::client::Publish {
+        self.client
+            .publish_value(self.node.node_id(), &self.state_prop, state.as_str(), true)
+    }
+
+    pub fn action(&self, action: EvChargerNodeActions) -> homie5::client::Publish {
+        self.client.publish_value(
+            self.node.node_id(),
+            &self.action_prop,
+            action.as_str(),
+            false,
+        )
+    }
+
+    pub fn current_limit(&self, value: i64) -> homie5::client::Publish {
+        self.client.publish_value(
+            self.node.node_id(),
+            &self.current_limit_prop,
+            value.to_string(),
+            true,
+        )
+    }
+
+    pub fn current_limit_target(&self, value: i64) -> homie5::client::Publish {
+        self.client.publish_target(
+            self.node.node_id(),
+            &self.current_limit_prop,
+            value.to_string(),
+            true,
+        )
+    }
+
+    pub fn cable_connected(&self, value: bool) -> homie5::client::Publish {
+        self.client.publish_value(
+            self.node.node_id(),
+            &self.cable_connected_prop,
+            value.to_string(),
+            true,
+        )
+    }
+}
+
+impl SmarthomePublisher for EvChargerNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::EvCharger
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![
+            self.state_prop.clone(),
+            self.action_prop.clone(),
+            self.current_limit_prop.clone(),
+            self.session_energy_prop.clone(),
+            self.cable_connected_prop.clone(),
+        ]
+    }
+}
+
+impl SetCommandParser for EvChargerNodePublisher {
+    type Event = EvChargerNodeSetEvents;
+
+    fn parse_set(
+        &self,
+        property: &PropertyRef,
+        desc: &HomieDeviceDescription,
+        set_value: &str,
+    ) -> ParseOutcome<Self::Event> {
+        if let Some(outcome) = reject_readonly(&self.node, property, desc, set_value) {
+            return outcome;
+        }
+
+        let property_id = property.prop_id().to_string();
+
+        if property.match_with_node(&self.node, &self.action_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Enum(value)) => match EvChargerNodeActions::from_str(&value) {
+                    Ok(action) => ParseOutcome::Parsed(EvChargerNodeSetEvents::Action(action)),
+                    Err(_) => ParseOutcome::Invalid(ParseError::new(
+                        property_id,
+                        set_value,
+                        ParseErrorKind::InvalidVariant,
+                    )),
+                },
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else if property.match_with_node(&self.node, &self.current_limit_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Integer(value)) => {
+                    ParseOutcome::Parsed(EvChargerNodeSetEvents::CurrentLimit(value))
+                }
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else {
+            ParseOutcome::NoMatch
+        }
+    }
+
+    fn parse_set_event(
+        &self,
+        desc: &HomieDeviceDescription,
+        event: &Homie5Message,
+    ) -> ParseOutcome<Self::Event> {
+        match event {
+            Homie5Message::PropertySet {
+                property,
+                set_value,
+            } => self.parse_set(property, desc, set_value),
+            _ => ParseOutcome::Invalid(ParseError::new(
+                self.action_prop.to_string(),
+                "",
+                ParseErrorKind::UnexpectedMessageType,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use homie5::HomieDomain;
+
+    use super::*;
+    use crate::StandaloneSetParser;
+
+    #[test]
+    fn current_limit_is_bounded_by_config() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let config = EvChargerNodeConfig {
+            max_current: 32,
+            ..EvChargerNodeConfig::default()
+        };
+        let parser: StandaloneSetParser<_> = EvChargerNodeBuilder::new(&config)
+            .build_with_publisher(EV_CHARGER_NODE_DEFAULT_ID, &client)
+            .into();
+        let node = parser.publisher().node_ref().clone();
+        let limit = PropertyRef::from_node(node.clone(), EV_CHARGER_NODE_CURRENT_LIMIT_PROP_ID);
+        let action = PropertyRef::from_node(node, EV_CHARGER_NODE_ACTION_PROP_ID);
+
+        assert!(matches!(
+            parser.parse_set(&limit, "32"),
+            ParseOutcome::Parsed(EvChargerNodeSetEvents::CurrentLimit(32))
+        ));
+        assert!(matches!(
+            parser.parse_set(&limit, "40"),
+            ParseOutcome::Invalid(_)
+        ));
+        assert!(matches!(
+            parser.parse_set(&action, "stop"),
+            ParseOutcome::Parsed(EvChargerNodeSetEvents::Action(EvChargerNodeActions::Stop))
+        ));
+    }
+}
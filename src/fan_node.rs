@@ -0,0 +1,733 @@
+use core::fmt;
+use std::{collections::BTreeMap, str::FromStr};
+
+use homie5::{
+    HOMIE_UNIT_PERCENT, Homie5DeviceProtocol, Homie5Message, Homie5ProtocolError, HomieID,
+    HomieValue, NodeRef, PropertyRef,
+    device_description::{
+        HomieDeviceDescription, HomieNodeDescription, IntegerRange, NodeDescriptionBuilder,
+        PropertyDescriptionBuilder,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ConfigError, ParseError, ParseErrorKind, ParseOutcome, PropertyMetadata, SMARTHOME_CAP_FAN,
+    SetCommandParser, SmarthomePublisher, SmarthomeType, property_metadata, readonly_properties,
+    reject_readonly, validate_enum_format,
+};
+
+pub const FAN_NODE_DEFAULT_ID: HomieID = HomieID::new_const("fan");
+pub const FAN_NODE_DEFAULT_NAME: &str = "Fan";
+pub const FAN_NODE_STATE_PROP_ID: HomieID = HomieID::new_const("state");
+pub const FAN_NODE_SPEED_PROP_ID: HomieID = HomieID::new_const("speed");
+pub const FAN_NODE_PRESET_PROP_ID: HomieID = HomieID::new_const("preset");
+pub const FAN_NODE_OSCILLATION_PROP_ID: HomieID = HomieID::new_const("oscillation");
+pub const FAN_NODE_DIRECTION_PROP_ID: HomieID = HomieID::new_const("direction");
+
+// ── Node (state) ────────────────────────────────────────────────────────────
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FanNodeState {
+    pub state: bool,
+    pub speed: Option<i64>,
+    pub preset: Option<String>,
+    pub oscillation: Option<bool>,
+    pub direction: Option<FanDirection>,
+}
+
+#[derive(Debug)]
+pub struct FanNode {
+    pub publisher: FanNodePublisher,
+    pub state: FanNodeState,
+}
+
+impl FanNode {
+    /// Apply a parsed set command and return the `$target` and value publishes
+    /// for the change.
+    pub fn apply_set(&mut self, event: FanNodeSetEvents) -> Vec<homie5::client::Publish> {
+        match event {
+            FanNodeSetEvents::State(value) => {
+                self.state.state = value;
+                vec![
+                    self.publisher.state_target(value),
+                    self.publisher.state(value),
+                ]
+            }
+            FanNodeSetEvents::Speed(value) => {
+                self.state.speed = Some(value);
+                [
+                    self.publisher.speed_target(value),
+                    self.publisher.speed(value),
+                ]
+                .into_iter()
+                .flatten()
+                .collect()
+            }
+            FanNodeSetEvents::Preset(value) => {
+                let publishes = [
+                    self.publisher.preset_target(&value),
+                    self.publisher.preset(&value),
+                ]
+                .into_iter()
+                .flatten()
+                .collect();
+                self.state.preset = Some(value);
+                publishes
+            }
+            FanNodeSetEvents::Oscillation(value) => {
+                self.state.oscillation = Some(value);
+                [
+                    self.publisher.oscillation_target(value),
+                    self.publisher.oscillation(value),
+                ]
+                .into_iter()
+                .flatten()
+                .collect()
+            }
+            FanNodeSetEvents::Direction(value) => {
+                self.state.direction = Some(value);
+                [
+                    self.publisher.direction_target(value),
+                    self.publisher.direction(value),
+                ]
+                .into_iter()
+                .flatten()
+                .collect()
+            }
+        }
+    }
+
+    /// Apply a value reported by the device and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Bool(value)) if p == &FAN_NODE_STATE_PROP_ID => {
+                self.state.state = *value;
+                vec![self.publisher.state(*value)]
+            }
+            (p, HomieValue::Integer(value)) if p == &FAN_NODE_SPEED_PROP_ID => {
+                self.state.speed = Some(*value);
+                self.publisher.speed(*value).into_iter().collect()
+            }
+            (p, HomieValue::Enum(value)) if p == &FAN_NODE_PRESET_PROP_ID => {
+                self.state.preset = Some(value.clone());
+                self.publisher.preset(value).into_iter().collect()
+            }
+            (p, HomieValue::Bool(value)) if p == &FAN_NODE_OSCILLATION_PROP_ID => {
+                self.state.oscillation = Some(*value);
+                self.publisher.oscillation(*value).into_iter().collect()
+            }
+            (p, HomieValue::Enum(value)) if p == &FAN_NODE_DIRECTION_PROP_ID => {
+                let Ok(direction) = value.parse::<FanDirection>() else {
+                    return Vec::new();
+                };
+                self.state.direction = Some(direction);
+                self.publisher.direction(direction).into_iter().collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            Some(self.publisher.state(self.state.state)),
+            self.state
+                .speed
+                .and_then(|value| self.publisher.speed(value)),
+            self.state
+                .preset
+                .as_deref()
+                .and_then(|value| self.publisher.preset(value)),
+            self.state
+                .oscillation
+                .and_then(|value| self.publisher.oscillation(value)),
+            self.state
+                .direction
+                .and_then(|value| self.publisher.direction(value)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+// ── Direction ───────────────────────────────────────────────────────────────
+
+/// Rotation direction of the fan blades (summer/winter mode on ceiling fans).
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+#[serde(rename_all = "kebab-case")]
+pub enum FanDirection {
+    Forward,
+    Reverse,
+}
+
+impl FanDirection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Forward => "forward",
+            Self::Reverse => "reverse",
+        }
+    }
+
+    pub const ALL: [FanDirection; 2] = [FanDirection::Forward, FanDirection::Reverse];
+}
+
+impl fmt::Display for FanDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for FanDirection {
+    type Err = Homie5ProtocolError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "forward" => Ok(Self::Forward),
+            "reverse" => Ok(Self::Reverse),
+            _ => Err(Homie5ProtocolError::InvalidPayload),
+        }
+    }
+}
+
+// ── Set events ──────────────────────────────────────────────────────────────
+
+#[derive(Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+pub enum FanNodeSetEvents {
+    State(bool),
+    Speed(i64),
+    Preset(String),
+    Oscillation(bool),
+    Direction(FanDirection),
+}
+
+// ── Config ──────────────────────────────────────────────────────────────────
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+#[serde(default)]
+pub struct FanNodeConfig {
+    /// Expose a continuous `speed` in percent.
+    pub speed: bool,
+    /// Named speed presets (e.g. `low`, `medium`, `high`). The `preset`
+    /// property is only added when this list is not empty.
+    #[cfg_attr(
+        feature = "proptest",
+        proptest(strategy = "crate::arbitrary::enum_values()")
+    )]
+    pub presets: Vec<String>,
+    pub oscillation: bool,
+    pub direction: bool,
+    /// Properties to publish as read-only; sets to them are rejected.
+    #[cfg_attr(
+        feature = "proptest",
+        proptest(strategy = "crate::arbitrary::homie_ids()")
+    )]
+    pub readonly: Vec<HomieID>,
+}
+
+impl Default for FanNodeConfig {
+    fn default() -> Self {
+        Self {
+            speed: true,
+            presets: Vec::new(),
+            oscillation: false,
+            direction: false,
+            readonly: Vec::new(),
+        }
+    }
+}
+
+impl FanNodeConfig {
+    /// Check the config-provided enum values before building the node.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if !self.presets.is_empty() {
+            validate_enum_format(&FAN_NODE_PRESET_PROP_ID, &self.presets)?;
+        }
+        Ok(())
+    }
+}
+
+// ── Builder ─────────────────────────────────────────────────────────────────
+
+pub struct FanNodeBuilder {
+    node_builder: NodeDescriptionBuilder,
+    config: FanNodeConfig,
+}
+
+impl Default for FanNodeBuilder {
+    fn default() -> Self {
+        Self::new(&FanNodeConfig::default())
+    }
+}
+
+impl FanNodeBuilder {
+    pub fn new(config: &FanNodeConfig) -> Self {
+        let db = Self::build_node(
+            NodeDescriptionBuilder::new().name(FAN_NODE_DEFAULT_NAME),
+            config,
+        )
+        .r#type(SMARTHOME_CAP_FAN);
+        let db = readonly_properties(db, &config.readonly);
+
+        Self {
+            node_builder: db,
+            config: config.clone(),
+        }
+    }
+
+    fn build_node(db: NodeDescriptionBuilder, config: &FanNodeConfig) -> NodeDescriptionBuilder {
+        db.add_property(
+            FAN_NODE_STATE_PROP_ID,
+            PropertyDescriptionBuilder::boolean()
+                .name("Fan state")
+                .boolean_labels("off", "on")
+                .settable(true)
+                .retained(true)
+                .build(),
+        )
+        .add_property_cond(FAN_NODE_SPEED_PROP_ID, config.speed, || {
+            PropertyDescriptionBuilder::integer()
+                .name("Fan speed")
+                .unit(HOMIE_UNIT_PERCENT)
+                .integer_range(IntegerRange {
+                    min: Some(0),
+                    max: Some(100),
+                    step: None,
+                })
+                .settable(true)
+                .retained(true)
+                .build()
+        })
+        .add_property_cond(FAN_NODE_PRESET_PROP_ID, !config.presets.is_empty(), || {
+            PropertyDescriptionBuilder::enumeration(config.presets.iter().map(|s| s.as_str()))
+                .unwrap()
+                .name("Fan speed preset")
+                .settable(true)
+                .retained(true)
+                .build()
+        })
+        .add_property_cond(FAN_NODE_OSCILLATION_PROP_ID, config.oscillation, || {
+            PropertyDescriptionBuilder::boolean()
+                .name("Oscillation")
+                .boolean_labels("off", "on")
+                .settable(true)
+                .retained(true)
+                .build()
+        })
+        .add_property_cond(FAN_NODE_DIRECTION_PROP_ID, config.direction, || {
+            PropertyDescriptionBuilder::enumeration(FanDirection::ALL.iter().map(|d| d.as_str()))
+                .unwrap()
+                .name("Rotation direction")
+                .settable(true)
+                .retained(true)
+                .build()
+        })
+    }
+
+    pub fn name<S: Into<String>>(mut self, name: impl Into<Option<S>>) -> Self {
+        self.node_builder = self.node_builder.name(name);
+        self
+    }
+
+    /// Append a subtype suffix to the node type (`fan-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::Fan.with_subtype(subtype));
+        self
+    }
+
+    pub fn build(self) -> HomieNodeDescription {
+        self.node_builder.build()
+    }
+
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &FanNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
+    pub fn build_with_publisher(
+        self,
+        node_id: HomieID,
+        client: &Homie5DeviceProtocol,
+    ) -> (HomieNodeDescription, FanNodePublisher) {
+        (
+            self.node_builder.build(),
+            FanNodePublisher::new(
+                NodeRef::new(
+                    client.homie_domain().to_owned(),
+                    client.id().clone(),
+                    node_id,
+                ),
+                client.clone(),
+                self.config,
+            ),
+        )
+    }
+}
+
+// ── Publisher ────────────────────────────────────────────────────────────────
+
+#[derive(Debug)]
+pub struct FanNodePublisher {
+    client: Homie5DeviceProtocol,
+    node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
+    config: FanNodeConfig,
+    state_prop: HomieID,
+    speed_prop: HomieID,
+    preset_prop: HomieID,
+    oscillation_prop: HomieID,
+    direction_prop: HomieID,
+}
+
+impl FanNodePublisher {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: FanNodeConfig) -> Self {
+        let metadata = property_metadata(&FanNodeBuilder::new(&config).build());
+        Self {
+            node,
+            metadata,
+            client,
+            config,
+            state_prop: FAN_NODE_STATE_PROP_ID,
+            speed_prop: FAN_NODE_SPEED_PROP_ID,
+            preset_prop: FAN_NODE_PRESET_PROP_ID,
+            oscillation_prop: FAN_NODE_OSCILLATION_PROP_ID,
+            direction_prop: FAN_NODE_DIRECTION_PROP_ID,
+        }
+    }
+
+    pub fn state(&self, value: bool) -> homie5::client::Publish {
+        self.client.publish_value(
+            self.node.node_id(),
+            &self.state_prop,
+            value.to_string(),
+            true,
+        )
+    }
+
+    pub fn state_target(&self, value: bool) -> homie5::client::Publish {
+        self.client.publish_target(
+            self.node.node_id(),
+            &self.state_prop,
+            value.to_string(),
+            true,
+        )
+    }
+
+    pub fn speed(&self, value: i64) -> Option<homie5::client::Publish> {
+        if !self.config.speed {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.speed_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+
+    pub fn speed_target(&self, value: i64) -> Option<homie5::client::Publish> {
+        if !self.config.speed {
+            return None;
+        }
+        Some(self.client.publish_target(
+            self.node.node_id(),
+            &self.speed_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+
+    pub fn preset(&self, value: &str) -> Option<homie5::client::Publish> {
+        if self.config.presets.is_empty() {
+            return None;
+        }
+        Some(
+            self.client
+                .publish_value(self.node.node_id(), &self.preset_prop, value, true),
+        )
+    }
+
+    pub fn preset_target(&self, value: &str) -> Option<homie5::client::Publish> {
+        if self.config.presets.is_empty() {
+            return None;
+        }
+        Some(
+            self.client
+                .publish_target(self.node.node_id(), &self.preset_prop, value, true),
+        )
+    }
+
+    pub fn oscillation(&self, value: bool) -> Option<homie5::client::Publish> {
+        if !self.config.oscillation {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.oscillation_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+
+    pub fn oscillation_target(&self, value: bool) -> Option<homie5::client::Publish> {
+        if !self.config.oscillation {
+            return None;
+        }
+        Some(self.client.publish_target(
+            self.node.node_id(),
+            &self.oscillation_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+
+    pub fn direction(&self, value: FanDirection) -> Option<homie5::client::Publish> {
+        if !self.config.direction {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.direction_prop,
+            value.as_str(),
+            true,
+        ))
+    }
+
+    pub fn direction_target(&self, value: FanDirection) -> Option<homie5::client::Publish> {
+        if !self.config.direction {
+            return None;
+        }
+        Some(self.client.publish_target(
+            self.node.node_id(),
+            &self.direction_prop,
+            value.as_str(),
+            true,
+        ))
+    }
+}
+
+impl SmarthomePublisher for FanNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::Fan
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![
+            self.state_prop.clone(),
+            self.speed_prop.clone(),
+            self.preset_prop.clone(),
+            self.oscillation_prop.clone(),
+            self.direction_prop.clone(),
+        ]
+    }
+}
+
+impl SetCommandParser for FanNodePublisher {
+    type Event = FanNodeSetEvents;
+
+    fn parse_set(
+        &self,
+        property: &PropertyRef,
+        desc: &HomieDeviceDescription,
+        set_value: &str,
+    ) -> ParseOutcome<Self::Event> {
+        if let Some(outcome) = reject_readonly(&self.node, property, desc, set_value) {
+            return outcome;
+        }
+
+        let property_id = property.prop_id().to_string();
+
+        if property.match_with_node(&self.node, &self.state_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Bool(value)) => ParseOutcome::Parsed(FanNodeSetEvents::State(value)),
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else if property.match_with_node(&self.node, &self.speed_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Integer(value)) => {
+                    ParseOutcome::Parsed(FanNodeSetEvents::Speed(value))
+                }
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else if property.match_with_node(&self.node, &self.preset_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Enum(value)) => {
+                    ParseOutcome::Parsed(FanNodeSetEvents::Preset(value))
+                }
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else if property.match_with_node(&self.node, &self.oscillation_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Bool(value)) => {
+                    ParseOutcome::Parsed(FanNodeSetEvents::Oscillation(value))
+                }
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else if property.match_with_node(&self.node, &self.direction_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Enum(value)) => match FanDirection::from_str(&value) {
+                    Ok(direction) => ParseOutcome::Parsed(FanNodeSetEvents::Direction(direction)),
+                    Err(_) => ParseOutcome::Invalid(ParseError::new(
+                        property_id,
+                        set_value,
+                        ParseErrorKind::InvalidVariant,
+                    )),
+                },
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else {
+            ParseOutcome::NoMatch
+        }
+    }
+
+    fn parse_set_event(
+        &self,
+        desc: &HomieDeviceDescription,
+        event: &Homie5Message,
+    ) -> ParseOutcome<Self::Event> {
+        match event {
+            Homie5Message::PropertySet {
+                property,
+                set_value,
+            } => self.parse_set(property, desc, set_value),
+            _ => ParseOutcome::Invalid(ParseError::new(
+                self.state_prop.to_string(),
+                "",
+                ParseErrorKind::UnexpectedMessageType,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use homie5::HomieDomain;
+
+    use super::*;
+    use crate::StandaloneSetParser;
+
+    #[test]
+    fn presets_and_direction_parse_against_description() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let config = FanNodeConfig {
+            presets: vec!["low".to_owned(), "high".to_owned()],
+            direction: true,
+            ..FanNodeConfig::default()
+        };
+        let parser: StandaloneSetParser<_> = FanNodeBuilder::new(&config)
+            .build_with_publisher(FAN_NODE_DEFAULT_ID, &client)
+            .into();
+        let node = parser.publisher().node_ref().clone();
+        let preset = PropertyRef::from_node(node.clone(), FAN_NODE_PRESET_PROP_ID);
+        let direction = PropertyRef::from_node(node.clone(), FAN_NODE_DIRECTION_PROP_ID);
+        let oscillation = PropertyRef::from_node(node, FAN_NODE_OSCILLATION_PROP_ID);
+
+        assert!(matches!(
+            parser.parse_set(&preset, "high"),
+            ParseOutcome::Parsed(FanNodeSetEvents::Preset(p)) if p == "high"
+        ));
+        assert!(matches!(
+            parser.parse_set(&preset, "turbo"),
+            ParseOutcome::Invalid(_)
+        ));
+        assert!(matches!(
+            parser.parse_set(&direction, "reverse"),
+            ParseOutcome::Parsed(FanNodeSetEvents::Direction(FanDirection::Reverse))
+        ));
+        // Oscillation is disabled, so there is no property description to parse against.
+        assert!(matches!(
+            parser.parse_set(&oscillation, "true"),
+            ParseOutcome::Invalid(_)
+        ));
+    }
+}
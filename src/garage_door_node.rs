@@ -1,5 +1,5 @@
 use core::fmt;
-use std::str::FromStr;
+use std::{collections::BTreeMap, str::FromStr};
 
 use homie5::{
     Homie5DeviceProtocol, Homie5Message, Homie5ProtocolError, HomieID, HomieValue, NodeRef,
@@ -12,21 +12,26 @@ use homie5::{
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    ParseError, ParseErrorKind, ParseOutcome, SMARTHOME_CAP_GARAGE_DOOR, SetCommandParser,
+    ParseError, ParseErrorKind, ParseOutcome, PropertyMetadata, SMARTHOME_CAP_GARAGE_DOOR,
+    SetCommandParser, SmarthomePublisher, SmarthomeType, property_metadata, readonly_properties,
+    reject_readonly, smarthome_node_config,
 };
 
 pub const GARAGE_DOOR_NODE_DEFAULT_ID: HomieID = HomieID::new_const("garage-door");
 pub const GARAGE_DOOR_NODE_DEFAULT_NAME: &str = "Garage door";
 pub const GARAGE_DOOR_NODE_STATE_PROP_ID: HomieID = HomieID::new_const("state");
 pub const GARAGE_DOOR_NODE_ACTION_PROP_ID: HomieID = HomieID::new_const("action");
+pub const GARAGE_DOOR_NODE_OBSTRUCTION_PROP_ID: HomieID = HomieID::new_const("obstruction");
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum GarageDoorState {
     Open,
     Closed,
     Opening,
     Closing,
     Stopped,
+    #[default]
     Unknown,
 }
 
@@ -49,7 +54,23 @@ impl fmt::Display for GarageDoorState {
     }
 }
 
+impl FromStr for GarageDoorState {
+    type Err = Homie5ProtocolError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "open" => Ok(Self::Open),
+            "closed" => Ok(Self::Closed),
+            "opening" => Ok(Self::Opening),
+            "closing" => Ok(Self::Closing),
+            "stopped" => Ok(Self::Stopped),
+            "unknown" => Ok(Self::Unknown),
+            _ => Err(Homie5ProtocolError::InvalidPayload),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 pub enum GarageDoorAction {
     Open,
     Close,
@@ -87,29 +108,101 @@ impl FromStr for GarageDoorAction {
     }
 }
 
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GarageDoorNodeState {
+    pub state: GarageDoorState,
+    pub obstruction: Option<bool>,
+}
+
 #[derive(Debug)]
 pub struct GarageDoorNode {
     pub publisher: GarageDoorNodePublisher,
-    pub state: GarageDoorState,
+    pub state: GarageDoorNodeState,
+}
+
+impl GarageDoorNode {
+    /// Apply a parsed set command. Actions are carried out by the device, which
+    /// reports the resulting `state`, so there is nothing to publish yet.
+    pub fn apply_set(&mut self, event: GarageDoorNodeSetEvents) -> Vec<homie5::client::Publish> {
+        match event {
+            GarageDoorNodeSetEvents::Action(_) => Vec::new(),
+        }
+    }
+
+    /// Apply a value reported by the device and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Enum(value)) if p == &GARAGE_DOOR_NODE_STATE_PROP_ID => {
+                let Ok(state) = value.parse::<GarageDoorState>() else {
+                    return Vec::new();
+                };
+                self.state.state = state;
+                vec![self.publisher.state(state)]
+            }
+            (p, HomieValue::Bool(value)) if p == &GARAGE_DOOR_NODE_OBSTRUCTION_PROP_ID => {
+                self.state.obstruction = Some(*value);
+                self.publisher.obstruction(*value).into_iter().collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            Some(self.publisher.state(self.state.state)),
+            self.state
+                .obstruction
+                .and_then(|value| self.publisher.obstruction(value)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 pub enum GarageDoorNodeSetEvents {
     Action(GarageDoorAction),
 }
 
-#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
-#[serde(default)]
-pub struct GarageDoorNodeConfig {
-    pub settable: bool,
-    pub action_open: bool,
-    pub action_close: bool,
-    pub action_trigger: bool,
-    pub action_stop: bool,
-    pub state_opening: bool,
-    pub state_closing: bool,
-    pub state_stopped: bool,
-    pub state_unknown: bool,
+smarthome_node_config! {
+    #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+    #[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+    #[serde(default)]
+    pub struct GarageDoorNodeConfig for GarageDoorNodePublisher {
+        pub settable: bool,
+        pub action_open: bool,
+        pub action_close: bool,
+        pub action_trigger: bool,
+        pub action_stop: bool,
+        pub state_opening: bool,
+        pub state_closing: bool,
+        pub state_stopped: bool,
+        pub state_unknown: bool,
+        /// Expose the `obstruction` property (safety beam / photo-eye).
+        pub obstruction: bool => GARAGE_DOOR_NODE_OBSTRUCTION_PROP_ID, obstruction(bool), |_config| {
+            PropertyDescriptionBuilder::boolean()
+                .name("Obstruction")
+                .boolean_labels("clear", "obstructed")
+                .settable(false)
+                .retained(true)
+                .build()
+        },
+        /// Properties to publish as read-only; sets to them are rejected.
+        #[cfg_attr(
+            feature = "proptest",
+            proptest(strategy = "crate::arbitrary::homie_ids()")
+        )]
+        pub readonly: Vec<HomieID>,
+    }
 }
 
 impl Default for GarageDoorNodeConfig {
@@ -124,6 +217,8 @@ impl Default for GarageDoorNodeConfig {
             state_closing: true,
             state_stopped: true,
             state_unknown: true,
+            obstruction: false,
+            readonly: Vec::new(),
         }
     }
 }
@@ -176,6 +271,7 @@ impl GarageDoorNodeConfig {
 
 pub struct GarageDoorNodeBuilder {
     node_builder: NodeDescriptionBuilder,
+    config: GarageDoorNodeConfig,
 }
 
 impl GarageDoorNodeBuilder {
@@ -185,31 +281,41 @@ impl GarageDoorNodeBuilder {
             config,
         )
         .r#type(SMARTHOME_CAP_GARAGE_DOOR);
+        let db = readonly_properties(db, &config.readonly);
 
-        Self { node_builder: db }
+        Self {
+            node_builder: db,
+            config: config.clone(),
+        }
     }
 
     fn build_node(
         db: NodeDescriptionBuilder,
         config: &GarageDoorNodeConfig,
     ) -> NodeDescriptionBuilder {
-        db.add_property(
-            GARAGE_DOOR_NODE_STATE_PROP_ID,
-            PropertyDescriptionBuilder::enumeration(config.build_state_format().iter().map(|s| s.as_str()))
+        config.add_optional_properties(
+            db.add_property(
+                GARAGE_DOOR_NODE_STATE_PROP_ID,
+                PropertyDescriptionBuilder::enumeration(
+                    config.build_state_format().iter().map(|s| s.as_str()),
+                )
                 .unwrap()
                 .name("Garage door state")
                 .settable(false)
                 .retained(true)
                 .build(),
-        )
-        .add_property(
-            GARAGE_DOOR_NODE_ACTION_PROP_ID,
-            PropertyDescriptionBuilder::enumeration(config.build_action_format().iter().map(|s| s.as_str()))
+            )
+            .add_property(
+                GARAGE_DOOR_NODE_ACTION_PROP_ID,
+                PropertyDescriptionBuilder::enumeration(
+                    config.build_action_format().iter().map(|s| s.as_str()),
+                )
                 .unwrap()
                 .name("Garage door action")
                 .settable(config.settable)
                 .retained(false)
                 .build(),
+            ),
         )
     }
 
@@ -218,10 +324,23 @@ impl GarageDoorNodeBuilder {
         self
     }
 
+    /// Append a subtype suffix to the node type (`garage-door-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::GarageDoor.with_subtype(subtype));
+        self
+    }
+
     pub fn build(self) -> HomieNodeDescription {
         self.node_builder.build()
     }
 
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &GarageDoorNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
     pub fn build_with_publisher(
         self,
         node_id: HomieID,
@@ -236,6 +355,7 @@ impl GarageDoorNodeBuilder {
                     node_id,
                 ),
                 client.clone(),
+                self.config,
             ),
         )
     }
@@ -245,17 +365,24 @@ impl GarageDoorNodeBuilder {
 pub struct GarageDoorNodePublisher {
     client: Homie5DeviceProtocol,
     node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
+    config: GarageDoorNodeConfig,
     state_prop: HomieID,
     action_prop: HomieID,
+    obstruction_prop: HomieID,
 }
 
 impl GarageDoorNodePublisher {
-    pub fn new(node: NodeRef, client: Homie5DeviceProtocol) -> Self {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: GarageDoorNodeConfig) -> Self {
+        let metadata = property_metadata(&GarageDoorNodeBuilder::new(&config).build());
         Self {
             node,
+            metadata,
             client,
+            config,
             state_prop: GARAGE_DOOR_NODE_STATE_PROP_ID,
             action_prop: GARAGE_DOOR_NODE_ACTION_PROP_ID,
+            obstruction_prop: GARAGE_DOOR_NODE_OBSTRUCTION_PROP_ID,
         }
     }
 
@@ -274,6 +401,28 @@ impl GarageDoorNodePublisher {
     }
 }
 
+impl SmarthomePublisher for GarageDoorNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::GarageDoor
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![
+            self.state_prop.clone(),
+            self.action_prop.clone(),
+            self.obstruction_prop.clone(),
+        ]
+    }
+}
+
 impl SetCommandParser for GarageDoorNodePublisher {
     type Event = GarageDoorNodeSetEvents;
 
@@ -283,6 +432,10 @@ impl SetCommandParser for GarageDoorNodePublisher {
         desc: &HomieDeviceDescription,
         set_value: &str,
     ) -> ParseOutcome<Self::Event> {
+        if let Some(outcome) = reject_readonly(&self.node, property, desc, set_value) {
+            return outcome;
+        }
+
         let property_id = property.prop_id().to_string();
 
         if property.match_with_node(&self.node, &self.action_prop) {
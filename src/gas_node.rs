@@ -0,0 +1,382 @@
+use core::fmt;
+use std::{collections::BTreeMap, str::FromStr};
+
+use homie5::{
+    Homie5DeviceProtocol, Homie5Message, Homie5ProtocolError, HomieID, HomieValue, NodeRef,
+    PropertyRef,
+    device_description::{
+        HomieDeviceDescription, HomieNodeDescription, IntegerRange, NodeDescriptionBuilder,
+        PropertyDescriptionBuilder,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ParseError, ParseErrorKind, ParseOutcome, PropertyMetadata, SMARTHOME_CAP_GAS,
+    SetCommandParser, SmarthomePublisher, SmarthomeType, property_metadata, readonly_properties,
+    reject_readonly, smarthome_node_config,
+};
+
+pub const GAS_NODE_DEFAULT_ID: HomieID = HomieID::new_const("gas");
+pub const GAS_NODE_DEFAULT_NAME: &str = "Gas leak detector";
+pub const GAS_NODE_DETECTED_PROP_ID: HomieID = HomieID::new_const("detected");
+pub const GAS_NODE_CONCENTRATION_PROP_ID: HomieID = HomieID::new_const("concentration");
+pub const GAS_NODE_ACTION_PROP_ID: HomieID = HomieID::new_const("action");
+
+// ── Node (state) ────────────────────────────────────────────────────────────
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GasNodeState {
+    pub detected: bool,
+    pub concentration: Option<i64>,
+}
+
+#[derive(Debug)]
+pub struct GasNode {
+    pub publisher: GasNodePublisher,
+    pub state: GasNodeState,
+}
+
+impl GasNode {
+    /// Apply a parsed set command. Actions are carried out by the device, which
+    /// reports the outcome, so there is nothing to publish yet.
+    pub fn apply_set(&mut self, event: GasNodeSetEvents) -> Vec<homie5::client::Publish> {
+        match event {
+            GasNodeSetEvents::Action(_) => Vec::new(),
+        }
+    }
+
+    /// Apply a value reported by the device and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Bool(value)) if p == &GAS_NODE_DETECTED_PROP_ID => {
+                self.state.detected = *value;
+                vec![self.publisher.detected(*value)]
+            }
+            (p, HomieValue::Integer(value)) if p == &GAS_NODE_CONCENTRATION_PROP_ID => {
+                self.state.concentration = Some(*value);
+                self.publisher.concentration(*value).into_iter().collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            Some(self.publisher.detected(self.state.detected)),
+            self.state
+                .concentration
+                .and_then(|value| self.publisher.concentration(value)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+// ── Actions / set events ────────────────────────────────────────────────────
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+pub enum GasNodeActions {
+    /// Trigger the detector's self-test.
+    Test,
+}
+
+impl GasNodeActions {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GasNodeActions::Test => "test",
+        }
+    }
+}
+
+impl fmt::Display for GasNodeActions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for GasNodeActions {
+    type Err = Homie5ProtocolError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "test" => Ok(GasNodeActions::Test),
+            _ => Err(Homie5ProtocolError::InvalidPayload),
+        }
+    }
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+pub enum GasNodeSetEvents {
+    Action(GasNodeActions),
+}
+
+// ── Config ──────────────────────────────────────────────────────────────────
+
+smarthome_node_config! {
+    #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+    #[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+    #[serde(default)]
+    pub struct GasNodeConfig for GasNodePublisher {
+        /// Expose the measured gas `concentration`.
+        pub concentration: bool => GAS_NODE_CONCENTRATION_PROP_ID, concentration(i64), |config| {
+            PropertyDescriptionBuilder::integer()
+                .name("Gas concentration")
+                .unit(config.concentration_unit.to_owned())
+                .integer_range(IntegerRange {
+                    min: Some(0),
+                    max: None,
+                    step: None,
+                })
+                .settable(false)
+                .retained(true)
+                .build()
+        },
+        /// Unit of `concentration`, e.g. `ppm` or `%LEL`.
+        pub concentration_unit: String,
+        /// Expose the `test` action.
+        pub test: bool,
+        /// Properties to publish as read-only; sets to them are rejected.
+        #[cfg_attr(
+            feature = "proptest",
+            proptest(strategy = "crate::arbitrary::homie_ids()")
+        )]
+        pub readonly: Vec<HomieID>,
+    }
+}
+
+impl Default for GasNodeConfig {
+    fn default() -> Self {
+        Self {
+            concentration: false,
+            concentration_unit: "ppm".to_owned(),
+            test: false,
+            readonly: Vec::new(),
+        }
+    }
+}
+
+// ── Builder ─────────────────────────────────────────────────────────────────
+
+pub struct GasNodeBuilder {
+    node_builder: NodeDescriptionBuilder,
+    config: GasNodeConfig,
+}
+
+impl Default for GasNodeBuilder {
+    fn default() -> Self {
+        Self::new(&GasNodeConfig::default())
+    }
+}
+
+impl GasNodeBuilder {
+    pub fn new(config: &GasNodeConfig) -> Self {
+        let db = Self::build_node(
+            NodeDescriptionBuilder::new().name(GAS_NODE_DEFAULT_NAME),
+            config,
+        )
+        .r#type(SMARTHOME_CAP_GAS);
+        let db = readonly_properties(db, &config.readonly);
+
+        Self {
+            node_builder: db,
+            config: config.clone(),
+        }
+    }
+
+    fn build_node(db: NodeDescriptionBuilder, config: &GasNodeConfig) -> NodeDescriptionBuilder {
+        config.add_optional_properties(
+            db.add_property(
+                GAS_NODE_DETECTED_PROP_ID,
+                PropertyDescriptionBuilder::boolean()
+                    .name("Gas detected")
+                    .boolean_labels("clear", "gas detected")
+                    .settable(false)
+                    .retained(true)
+                    .build(),
+            )
+            .add_property_cond(GAS_NODE_ACTION_PROP_ID, config.test, || {
+                PropertyDescriptionBuilder::enumeration([GasNodeActions::Test.as_str()])
+                    .unwrap()
+                    .name("Gas detector action")
+                    .settable(true)
+                    .retained(false)
+                    .build()
+            }),
+        )
+    }
+
+    pub fn name<S: Into<String>>(mut self, name: impl Into<Option<S>>) -> Self {
+        self.node_builder = self.node_builder.name(name);
+        self
+    }
+
+    /// Append a subtype suffix to the node type (`gas-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::Gas.with_subtype(subtype));
+        self
+    }
+
+    pub fn build(self) -> HomieNodeDescription {
+        self.node_builder.build()
+    }
+
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &GasNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
+    pub fn build_with_publisher(
+        self,
+        node_id: HomieID,
+        client: &Homie5DeviceProtocol,
+    ) -> (HomieNodeDescription, GasNodePublisher) {
+        (
+            self.node_builder.build(),
+            GasNodePublisher::new(
+                NodeRef::new(
+                    client.homie_domain().to_owned(),
+                    client.id().clone(),
+                    node_id,
+                ),
+                client.clone(),
+                self.config,
+            ),
+        )
+    }
+}
+
+// ── Publisher ────────────────────────────────────────────────────────────────
+
+#[derive(Debug)]
+pub struct GasNodePublisher {
+    client: Homie5DeviceProtocol,
+    node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
+    config: GasNodeConfig,
+    detected_prop: HomieID,
+    concentration_prop: HomieID,
+    action_prop: HomieID,
+}
+
+impl GasNodePublisher {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: GasNodeConfig) -> Self {
+        let metadata = property_metadata(&GasNodeBuilder::new(&config).build());
+        Self {
+            node,
+            metadata,
+            client,
+            config,
+            detected_prop: GAS_NODE_DETECTED_PROP_ID,
+            concentration_prop: GAS_NODE_CONCENTRATION_PROP_ID,
+            action_prop: GAS_NODE_ACTION_PROP_ID,
+        }
+    }
+
+    pub fn detected(&self, value: bool) -> homie5::client::Publish {
+        self.client.publish_value(
+            self.node.node_id(),
+            &self.detected_prop,
+            value.to_string(),
+            true,
+        )
+    }
+}
+
+impl SmarthomePublisher for GasNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::Gas
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![
+            self.detected_prop.clone(),
+            self.concentration_prop.clone(),
+            self.action_prop.clone(),
+        ]
+    }
+}
+
+impl SetCommandParser for GasNodePublisher {
+    type Event = GasNodeSetEvents;
+
+    fn parse_set(
+        &self,
+        property: &PropertyRef,
+        desc: &HomieDeviceDescription,
+        set_value: &str,
+    ) -> ParseOutcome<Self::Event> {
+        if let Some(outcome) = reject_readonly(&self.node, property, desc, set_value) {
+            return outcome;
+        }
+
+        let property_id = property.prop_id().to_string();
+
+        if property.match_with_node(&self.node, &self.action_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Enum(value)) => match GasNodeActions::from_str(&value) {
+                    Ok(action) => ParseOutcome::Parsed(GasNodeSetEvents::Action(action)),
+                    Err(_) => ParseOutcome::Invalid(ParseError::new(
+                        property_id,
+                        set_value,
+                        ParseErrorKind::InvalidVariant,
+                    )),
+                },
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else {
+            ParseOutcome::NoMatch
+        }
+    }
+
+    fn parse_set_event(
+        &self,
+        desc: &HomieDeviceDescription,
+        event: &Homie5Message,
+    ) -> ParseOutcome<Self::Event> {
+        match event {
+            Homie5Message::PropertySet {
+                property,
+                set_value,
+            } => self.parse_set(property, desc, set_value),
+            _ => ParseOutcome::Invalid(ParseError::new(
+                self.action_prop.to_string(),
+                "",
+                ParseErrorKind::UnexpectedMessageType,
+            )),
+        }
+    }
+}
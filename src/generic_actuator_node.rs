@@ -0,0 +1,437 @@
+use std::collections::BTreeMap;
+
+use homie5::{
+    Homie5DeviceProtocol, Homie5Message, HomieID, HomieValue, NodeRef, PropertyRef,
+    device_description::{
+        HomieDeviceDescription, HomieNodeDescription, NodeDescriptionBuilder,
+        PropertyDescriptionBuilder,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ConfigError, ParseError, ParseErrorKind, ParseOutcome, PropertyMetadata,
+    SMARTHOME_CAP_GENERIC_ACTUATOR, SetCommandParser, SmarthomePublisher, SmarthomeType,
+    property_metadata, readonly_properties, reject_readonly, validate_enum_format,
+};
+
+pub const GENERIC_ACTUATOR_NODE_DEFAULT_ID: HomieID = HomieID::new_const("actuator");
+pub const GENERIC_ACTUATOR_NODE_DEFAULT_NAME: &str = "Actuator";
+pub const GENERIC_ACTUATOR_NODE_COMMAND_PROP_ID: HomieID = HomieID::new_const("command");
+pub const GENERIC_ACTUATOR_NODE_STATE_PROP_ID: HomieID = HomieID::new_const("state");
+
+// ── Node (state) ────────────────────────────────────────────────────────────
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GenericActuatorNodeState {
+    /// Last state reported by the device, one of the configured commands.
+    pub state: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct GenericActuatorNode {
+    pub publisher: GenericActuatorNodePublisher,
+    pub state: GenericActuatorNodeState,
+}
+
+impl GenericActuatorNode {
+    /// Apply a parsed set command and return the `$target` and value publishes
+    /// for the change. The `state` only follows once the device reports it.
+    pub fn apply_set(
+        &mut self,
+        event: GenericActuatorNodeSetEvents,
+    ) -> Vec<homie5::client::Publish> {
+        match event {
+            GenericActuatorNodeSetEvents::Command(command) => {
+                self.publisher.state_target(&command).into_iter().collect()
+            }
+        }
+    }
+
+    /// Apply a state reported by the device and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Enum(value)) if p == &GENERIC_ACTUATOR_NODE_STATE_PROP_ID => {
+                self.state.state = Some(value.clone());
+                self.publisher.state(value).into_iter().collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        self.state
+            .state
+            .as_deref()
+            .and_then(|value| self.publisher.state(value))
+            .into_iter()
+            .collect()
+    }
+}
+
+// ── Set events ──────────────────────────────────────────────────────────────
+
+#[derive(Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+pub enum GenericActuatorNodeSetEvents {
+    Command(String),
+}
+
+// ── Config ──────────────────────────────────────────────────────────────────
+
+/// Declarative description of a one-off actuator: the device accepts one of
+/// `commands` and reports the command it last executed as its state.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+#[serde(default)]
+pub struct GenericActuatorNodeConfig {
+    /// Allowed values of `command`; the node has no properties while empty.
+    #[cfg_attr(
+        feature = "proptest",
+        proptest(strategy = "crate::arbitrary::enum_values()")
+    )]
+    pub commands: Vec<String>,
+    /// Expose a read-only `state` mirroring the values of `command`.
+    pub state: bool,
+    /// Properties to publish as read-only; sets to them are rejected.
+    #[cfg_attr(
+        feature = "proptest",
+        proptest(strategy = "crate::arbitrary::homie_ids()")
+    )]
+    pub readonly: Vec<HomieID>,
+}
+
+impl Default for GenericActuatorNodeConfig {
+    fn default() -> Self {
+        Self {
+            commands: Vec::new(),
+            state: true,
+            readonly: Vec::new(),
+        }
+    }
+}
+
+impl GenericActuatorNodeConfig {
+    /// Check the config-provided enum values before building the node.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if !self.commands.is_empty() {
+            validate_enum_format(&GENERIC_ACTUATOR_NODE_COMMAND_PROP_ID, &self.commands)?;
+        }
+        Ok(())
+    }
+}
+
+// ── Builder ─────────────────────────────────────────────────────────────────
+
+pub struct GenericActuatorNodeBuilder {
+    node_builder: NodeDescriptionBuilder,
+    config: GenericActuatorNodeConfig,
+}
+
+impl Default for GenericActuatorNodeBuilder {
+    fn default() -> Self {
+        Self::new(&GenericActuatorNodeConfig::default())
+    }
+}
+
+impl GenericActuatorNodeBuilder {
+    pub fn new(config: &GenericActuatorNodeConfig) -> Self {
+        let db = Self::build_node(
+            NodeDescriptionBuilder::new().name(GENERIC_ACTUATOR_NODE_DEFAULT_NAME),
+            config,
+        )
+        .r#type(SMARTHOME_CAP_GENERIC_ACTUATOR);
+        let db = readonly_properties(db, &config.readonly);
+
+        Self {
+            node_builder: db,
+            config: config.clone(),
+        }
+    }
+
+    fn build_node(
+        db: NodeDescriptionBuilder,
+        config: &GenericActuatorNodeConfig,
+    ) -> NodeDescriptionBuilder {
+        db.add_property_cond(
+            GENERIC_ACTUATOR_NODE_COMMAND_PROP_ID,
+            !config.commands.is_empty(),
+            || {
+                PropertyDescriptionBuilder::enumeration(config.commands.iter())
+                    .unwrap()
+                    .name("Command")
+                    .settable(true)
+                    .retained(false)
+                    .build()
+            },
+        )
+        .add_property_cond(
+            GENERIC_ACTUATOR_NODE_STATE_PROP_ID,
+            config.state && !config.commands.is_empty(),
+            || {
+                PropertyDescriptionBuilder::enumeration(config.commands.iter())
+                    .unwrap()
+                    .name("State")
+                    .settable(false)
+                    .retained(true)
+                    .build()
+            },
+        )
+    }
+
+    pub fn name<S: Into<String>>(mut self, name: impl Into<Option<S>>) -> Self {
+        self.node_builder = self.node_builder.name(name);
+        self
+    }
+
+    /// Append a subtype suffix to the node type (`generic-actuator-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::GenericActuator.with_subtype(subtype));
+        self
+    }
+
+    pub fn build(self) -> HomieNodeDescription {
+        self.node_builder.build()
+    }
+
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &GenericActuatorNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
+    pub fn build_with_publisher(
+        self,
+        node_id: HomieID,
+        client: &Homie5DeviceProtocol,
+    ) -> (HomieNodeDescription, GenericActuatorNodePublisher) {
+        (
+            self.node_builder.build(),
+            GenericActuatorNodePublisher::new(
+                NodeRef::new(
+                    client.homie_domain().to_owned(),
+                    client.id().clone(),
+                    node_id,
+                ),
+                client.clone(),
+                self.config,
+            ),
+        )
+    }
+}
+
+// ── Publisher ────────────────────────────────────────────────────────────────
+
+#[derive(Debug)]
+pub struct GenericActuatorNodePublisher {
+    client: Homie5DeviceProtocol,
+    node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
+    config: GenericActuatorNodeConfig,
+    command_prop: HomieID,
+    state_prop: HomieID,
+}
+
+impl GenericActuatorNodePublisher {
+    pub fn new(
+        node: NodeRef,
+        client: Homie5DeviceProtocol,
+        config: GenericActuatorNodeConfig,
+    ) -> Self {
+        let metadata = property_metadata(&GenericActuatorNodeBuilder::new(&config).build());
+        Self {
+            node,
+            metadata,
+            client,
+            config,
+            command_prop: GENERIC_ACTUATOR_NODE_COMMAND_PROP_ID,
+            state_prop: GENERIC_ACTUATOR_NODE_STATE_PROP_ID,
+        }
+    }
+
+    fn has_state(&self) -> bool {
+        self.config.state && !self.config.commands.is_empty()
+    }
+
+    pub fn command(&self, value: &str) -> Option<homie5::client::Publish> {
+        if self.config.commands.is_empty() {
+            return None;
+        }
+        Some(
+            self.client
+                .publish_value(self.node.node_id(), &self.command_prop, value, false),
+        )
+    }
+
+    pub fn state(&self, value: &str) -> Option<homie5::client::Publish> {
+        if !self.has_state() {
+            return None;
+        }
+        Some(
+            self.client
+                .publish_value(self.node.node_id(), &self.state_prop, value, true),
+        )
+    }
+
+    pub fn state_target(&self, value: &str) -> Option<homie5::client::Publish> {
+        if !self.has_state() {
+            return None;
+        }
+        Some(
+            self.client
+                .publish_target(self.node.node_id(), &self.state_prop, value, true),
+        )
+    }
+}
+
+impl SmarthomePublisher for GenericActuatorNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::GenericActuator
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![self.command_prop.clone(), self.state_prop.clone()]
+    }
+}
+
+impl SetCommandParser for GenericActuatorNodePublisher {
+    type Event = GenericActuatorNodeSetEvents;
+
+    fn parse_set(
+        &self,
+        property: &PropertyRef,
+        desc: &HomieDeviceDescription,
+        set_value: &str,
+    ) -> ParseOutcome<Self::Event> {
+        if let Some(outcome) = reject_readonly(&self.node, property, desc, set_value) {
+            return outcome;
+        }
+
+        let property_id = property.prop_id().to_string();
+
+        if property.match_with_node(&self.node, &self.command_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Enum(value)) => {
+                    ParseOutcome::Parsed(GenericActuatorNodeSetEvents::Command(value))
+                }
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidVariant,
+                )),
+            }
+        } else {
+            ParseOutcome::NoMatch
+        }
+    }
+
+    fn parse_set_event(
+        &self,
+        desc: &HomieDeviceDescription,
+        event: &Homie5Message,
+    ) -> ParseOutcome<Self::Event> {
+        match event {
+            Homie5Message::PropertySet {
+                property,
+                set_value,
+            } => self.parse_set(property, desc, set_value),
+            _ => ParseOutcome::Invalid(ParseError::new(
+                self.command_prop.to_string(),
+                "",
+                ParseErrorKind::UnexpectedMessageType,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use homie5::{HomieDomain, device_description::PropertyDescriptionValidationError};
+
+    use super::*;
+    use crate::{ConfigErrorKind, StandaloneSetParser};
+
+    #[test]
+    fn repeated_commands_are_rejected() {
+        let config: GenericActuatorNodeConfig =
+            serde_json::from_str(r#"{"commands": ["on", "on"]}"#).expect("config must deserialize");
+        let err = config
+            .validate()
+            .expect_err("repeated commands must be rejected");
+        assert_eq!(err.property_id, GENERIC_ACTUATOR_NODE_COMMAND_PROP_ID);
+        assert_eq!(
+            err.kind,
+            ConfigErrorKind::InvalidEnum(PropertyDescriptionValidationError::DuplicateEnumValues)
+        );
+        assert!(GenericActuatorNodeConfig::default().validate().is_ok());
+    }
+
+    #[test]
+    fn commands_come_from_config_and_are_mirrored_by_state() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let config = GenericActuatorNodeConfig {
+            commands: vec!["eco".to_owned(), "intensive".to_owned(), "rinse".to_owned()],
+            ..Default::default()
+        };
+        assert_eq!(GenericActuatorNodeBuilder::property_ids(&config).len(), 2);
+        let parser: StandaloneSetParser<_> = GenericActuatorNodeBuilder::new(&config)
+            .build_with_publisher(GENERIC_ACTUATOR_NODE_DEFAULT_ID, &client)
+            .into();
+        let node = parser.publisher().node_ref().clone();
+        let command = PropertyRef::from_node(node.clone(), GENERIC_ACTUATOR_NODE_COMMAND_PROP_ID);
+        let state = PropertyRef::from_node(node, GENERIC_ACTUATOR_NODE_STATE_PROP_ID);
+
+        let ParseOutcome::Parsed(event) = parser.parse_set(&command, "rinse") else {
+            panic!("configured command must parse");
+        };
+        assert!(matches!(
+            parser.parse_set(&command, "spin"),
+            ParseOutcome::Invalid(_)
+        ));
+        assert!(matches!(
+            parser.parse_set(&state, "eco"),
+            ParseOutcome::Invalid(ParseError {
+                kind: ParseErrorKind::NotSettable,
+                ..
+            })
+        ));
+
+        let mut node = GenericActuatorNode {
+            publisher: parser.into_publisher(),
+            state: GenericActuatorNodeState::default(),
+        };
+        assert_eq!(node.apply_set(event).len(), 1);
+        node.apply_value(
+            &GENERIC_ACTUATOR_NODE_STATE_PROP_ID,
+            &HomieValue::Enum("rinse".to_owned()),
+        );
+        assert_eq!(node.state.state.as_deref(), Some("rinse"));
+    }
+}
@@ -0,0 +1,585 @@
+use core::fmt;
+use std::{collections::BTreeMap, str::FromStr};
+
+use homie5::{
+    HOMIE_UNIT_DEGREE_CELSIUS, HOMIE_UNIT_PERCENT, Homie5DeviceProtocol, Homie5Message,
+    Homie5ProtocolError, HomieID, HomieValue, NodeRef, PropertyRef,
+    device_description::{
+        FloatRange, HomieDeviceDescription, HomieNodeDescription, IntegerRange,
+        NodeDescriptionBuilder, PropertyDescriptionBuilder,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ConfigError, ParseError, ParseErrorKind, ParseOutcome, PropertyMetadata,
+    SMARTHOME_CAP_HEATING_ZONE, SetCommandParser, SmarthomePublisher, SmarthomeType,
+    property_metadata, readonly_properties, reject_readonly, smarthome_node_config,
+    validate_enum_format,
+};
+
+pub const HEATING_ZONE_NODE_DEFAULT_ID: HomieID = HomieID::new_const("heating-zone");
+pub const HEATING_ZONE_NODE_DEFAULT_NAME: &str = "Heating zone";
+pub const HEATING_ZONE_NODE_SET_TEMPERATURE_PROP_ID: HomieID =
+    HomieID::new_const("set-temperature");
+pub const HEATING_ZONE_NODE_MODE_PROP_ID: HomieID = HomieID::new_const("mode");
+pub const HEATING_ZONE_NODE_DEMAND_PROP_ID: HomieID = HomieID::new_const("demand");
+pub const HEATING_ZONE_NODE_WINDOWOPEN_PROP_ID: HomieID = HomieID::new_const("window-open");
+
+// ── Node (state) ────────────────────────────────────────────────────────────
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HeatingZoneNodeState {
+    pub set_temperature: f64,
+    pub mode: Option<HeatingZoneMode>,
+    pub demand: Option<i64>,
+    pub window_open: Option<bool>,
+}
+
+#[derive(Debug)]
+pub struct HeatingZoneNode {
+    pub publisher: HeatingZoneNodePublisher,
+    pub state: HeatingZoneNodeState,
+}
+
+impl HeatingZoneNode {
+    /// Apply a parsed set command and return the `$target` and value publishes
+    /// for the change.
+    pub fn apply_set(&mut self, event: HeatingZoneNodeSetEvents) -> Vec<homie5::client::Publish> {
+        match event {
+            HeatingZoneNodeSetEvents::SetTemperature(value) => {
+                self.state.set_temperature = value;
+                vec![
+                    self.publisher.set_temperature_target(value),
+                    self.publisher.set_temperature(value),
+                ]
+            }
+            HeatingZoneNodeSetEvents::Mode(mode) => {
+                self.state.mode = Some(mode);
+                [self.publisher.mode_target(mode), self.publisher.mode(mode)]
+                    .into_iter()
+                    .flatten()
+                    .collect()
+            }
+        }
+    }
+
+    /// Apply a value reported by the zone controller and return the matching
+    /// publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Float(value)) if p == &HEATING_ZONE_NODE_SET_TEMPERATURE_PROP_ID => {
+                self.state.set_temperature = *value;
+                vec![self.publisher.set_temperature(*value)]
+            }
+            (p, HomieValue::Enum(value)) if p == &HEATING_ZONE_NODE_MODE_PROP_ID => {
+                let Ok(mode) = value.parse::<HeatingZoneMode>() else {
+                    return Vec::new();
+                };
+                self.state.mode = Some(mode);
+                self.publisher.mode(mode).into_iter().collect()
+            }
+            (p, HomieValue::Integer(value)) if p == &HEATING_ZONE_NODE_DEMAND_PROP_ID => {
+                self.state.demand = Some(*value);
+                self.publisher.demand(*value).into_iter().collect()
+            }
+            (p, HomieValue::Bool(value)) if p == &HEATING_ZONE_NODE_WINDOWOPEN_PROP_ID => {
+                self.state.window_open = Some(*value);
+                self.publisher.window_open(*value).into_iter().collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            Some(self.publisher.set_temperature(self.state.set_temperature)),
+            self.state.mode.and_then(|mode| self.publisher.mode(mode)),
+            self.state
+                .demand
+                .and_then(|value| self.publisher.demand(value)),
+            self.state
+                .window_open
+                .and_then(|value| self.publisher.window_open(value)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+// ── Mode ────────────────────────────────────────────────────────────────────
+
+/// Operating mode applied to every thermostat in the zone.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+#[serde(rename_all = "kebab-case")]
+pub enum HeatingZoneMode {
+    /// Heating disabled for the zone.
+    Off,
+    /// Setpoint follows the zone schedule.
+    #[default]
+    Auto,
+    /// Setpoint held at the manually set value.
+    Manual,
+    /// Reduced setpoint while the zone is unoccupied.
+    Eco,
+    /// Minimal heating to keep the zone above freezing.
+    FrostProtection,
+}
+
+impl HeatingZoneMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Auto => "auto",
+            Self::Manual => "manual",
+            Self::Eco => "eco",
+            Self::FrostProtection => "frost-protection",
+        }
+    }
+}
+
+impl fmt::Display for HeatingZoneMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for HeatingZoneMode {
+    type Err = Homie5ProtocolError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "off" => Ok(Self::Off),
+            "auto" => Ok(Self::Auto),
+            "manual" => Ok(Self::Manual),
+            "eco" => Ok(Self::Eco),
+            "frost-protection" => Ok(Self::FrostProtection),
+            _ => Err(Homie5ProtocolError::InvalidPayload),
+        }
+    }
+}
+
+// ── Set events ──────────────────────────────────────────────────────────────
+
+#[derive(Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+pub enum HeatingZoneNodeSetEvents {
+    SetTemperature(f64),
+    Mode(HeatingZoneMode),
+}
+
+// ── Config ──────────────────────────────────────────────────────────────────
+
+smarthome_node_config! {
+    #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+    #[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+    #[serde(default)]
+    pub struct HeatingZoneNodeConfig for HeatingZoneNodePublisher {
+        /// Unit of the zone setpoint.
+        pub unit: String,
+        /// Range of the zone setpoint.
+        #[cfg_attr(
+            feature = "proptest",
+            proptest(strategy = "crate::arbitrary::float_range()")
+        )]
+        pub temp_range: FloatRange,
+        /// Zone modes offered by the controller. The `mode` property is only
+        /// added when this is non-empty.
+        #[cfg_attr(
+            feature = "proptest",
+            proptest(strategy = "crate::arbitrary::distinct()")
+        )]
+        pub modes: Vec<HeatingZoneMode>,
+        /// Expose the aggregated heat demand of the zone in percent.
+        pub demand: bool => HEATING_ZONE_NODE_DEMAND_PROP_ID, demand(i64), |_config| {
+            PropertyDescriptionBuilder::integer()
+                .name("Heat demand")
+                .unit(HOMIE_UNIT_PERCENT)
+                .integer_range(IntegerRange {
+                    min: Some(0),
+                    max: Some(100),
+                    step: None,
+                })
+                .settable(false)
+                .retained(true)
+                .build()
+        },
+        /// Expose the `window-open` flag, set while any window in the zone is open.
+        pub windowopen: bool => HEATING_ZONE_NODE_WINDOWOPEN_PROP_ID, window_open(bool), |_config| {
+            PropertyDescriptionBuilder::boolean()
+                .name("Window open in zone")
+                .boolean_labels("closed", "open")
+                .settable(false)
+                .retained(true)
+                .build()
+        },
+        /// Properties to publish as read-only; sets to them are rejected.
+        #[cfg_attr(
+            feature = "proptest",
+            proptest(strategy = "crate::arbitrary::homie_ids()")
+        )]
+        pub readonly: Vec<HomieID>,
+    }
+}
+
+impl Default for HeatingZoneNodeConfig {
+    fn default() -> Self {
+        Self {
+            unit: HOMIE_UNIT_DEGREE_CELSIUS.to_string(),
+            temp_range: FloatRange {
+                min: Some(5.0),
+                max: Some(30.0),
+                step: Some(0.5),
+            },
+            modes: vec![
+                HeatingZoneMode::Off,
+                HeatingZoneMode::Auto,
+                HeatingZoneMode::Manual,
+            ],
+            demand: true,
+            windowopen: true,
+            readonly: Vec::new(),
+        }
+    }
+}
+
+impl HeatingZoneNodeConfig {
+    /// Check the config-provided enum values before building the node.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if !self.modes.is_empty() {
+            validate_enum_format(
+                &HEATING_ZONE_NODE_MODE_PROP_ID,
+                self.modes.iter().map(|m| m.as_str()),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// ── Builder ─────────────────────────────────────────────────────────────────
+
+pub struct HeatingZoneNodeBuilder {
+    node_builder: NodeDescriptionBuilder,
+    config: HeatingZoneNodeConfig,
+}
+
+impl Default for HeatingZoneNodeBuilder {
+    fn default() -> Self {
+        Self::new(&HeatingZoneNodeConfig::default())
+    }
+}
+
+impl HeatingZoneNodeBuilder {
+    pub fn new(config: &HeatingZoneNodeConfig) -> Self {
+        let db = Self::build_node(
+            NodeDescriptionBuilder::new().name(HEATING_ZONE_NODE_DEFAULT_NAME),
+            config,
+        )
+        .r#type(SMARTHOME_CAP_HEATING_ZONE);
+        let db = readonly_properties(db, &config.readonly);
+
+        Self {
+            node_builder: db,
+            config: config.clone(),
+        }
+    }
+
+    fn build_node(
+        db: NodeDescriptionBuilder,
+        config: &HeatingZoneNodeConfig,
+    ) -> NodeDescriptionBuilder {
+        config.add_optional_properties(
+            db.add_property(
+                HEATING_ZONE_NODE_SET_TEMPERATURE_PROP_ID,
+                PropertyDescriptionBuilder::float()
+                    .name("Zone setpoint")
+                    .float_range(config.temp_range.clone())
+                    .unit(config.unit.to_owned())
+                    .settable(true)
+                    .retained(true)
+                    .build(),
+            )
+            .add_property_cond(
+                HEATING_ZONE_NODE_MODE_PROP_ID,
+                !config.modes.is_empty(),
+                || {
+                    PropertyDescriptionBuilder::enumeration(config.modes.iter().map(|m| m.as_str()))
+                        .unwrap()
+                        .name("Zone mode")
+                        .settable(true)
+                        .retained(true)
+                        .build()
+                },
+            ),
+        )
+    }
+
+    pub fn name<S: Into<String>>(mut self, name: impl Into<Option<S>>) -> Self {
+        self.node_builder = self.node_builder.name(name);
+        self
+    }
+
+    /// Append a subtype suffix to the node type (`heating-zone-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::HeatingZone.with_subtype(subtype));
+        self
+    }
+
+    pub fn build(self) -> HomieNodeDescription {
+        self.node_builder.build()
+    }
+
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &HeatingZoneNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
+    pub fn build_with_publisher(
+        self,
+        node_id: HomieID,
+        client: &Homie5DeviceProtocol,
+    ) -> (HomieNodeDescription, HeatingZoneNodePublisher) {
+        (
+            self.node_builder.build(),
+            HeatingZoneNodePublisher::new(
+                NodeRef::new(
+                    client.homie_domain().to_owned(),
+                    client.id().clone(),
+                    node_id,
+                ),
+                client.clone(),
+                self.config,
+            ),
+        )
+    }
+}
+
+// ── Publisher ────────────────────────────────────────────────────────────────
+
+#[derive(Debug)]
+pub struct HeatingZoneNodePublisher {
+    client: Homie5DeviceProtocol,
+    node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
+    config: HeatingZoneNodeConfig,
+    set_temperature_prop: HomieID,
+    mode_prop: HomieID,
+    demand_prop: HomieID,
+    windowopen_prop: HomieID,
+}
+
+impl HeatingZoneNodePublisher {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: HeatingZoneNodeConfig) -> Self {
+        let metadata = property_metadata(&HeatingZoneNodeBuilder::new(&config).build());
+        Self {
+            node,
+            metadata,
+            client,
+            config,
+            set_temperature_prop: HEATING_ZONE_NODE_SET_TEMPERATURE_PROP_ID,
+            mode_prop: HEATING_ZONE_NODE_MODE_PROP_ID,
+            demand_prop: HEATING_ZONE_NODE_DEMAND_PROP_ID,
+            windowopen_prop: HEATING_ZONE_NODE_WINDOWOPEN_PROP_ID,
+        }
+    }
+
+    pub fn set_temperature(&self, value: f64) -> homie5::client::Publish {
+        self.client.publish_value(
+            self.node.node_id(),
+            &self.set_temperature_prop,
+            value.to_string(),
+            true,
+        )
+    }
+
+    pub fn set_temperature_target(&self, value: f64) -> homie5::client::Publish {
+        self.client.publish_target(
+            self.node.node_id(),
+            &self.set_temperature_prop,
+            value.to_string(),
+            true,
+        )
+    }
+
+    pub fn mode(&self, mode: HeatingZoneMode) -> Option<homie5::client::Publish> {
+        if self.config.modes.is_empty() {
+            return None;
+        }
+        Some(
+            self.client
+                .publish_value(self.node.node_id(), &self.mode_prop, mode.as_str(), true),
+        )
+    }
+
+    pub fn mode_target(&self, mode: HeatingZoneMode) -> Option<homie5::client::Publish> {
+        if self.config.modes.is_empty() {
+            return None;
+        }
+        Some(
+            self.client
+                .publish_target(self.node.node_id(), &self.mode_prop, mode.as_str(), true),
+        )
+    }
+}
+
+impl SmarthomePublisher for HeatingZoneNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::HeatingZone
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![
+            self.set_temperature_prop.clone(),
+            self.mode_prop.clone(),
+            self.demand_prop.clone(),
+            self.windowopen_prop.clone(),
+        ]
+    }
+}
+
+impl SetCommandParser for HeatingZoneNodePublisher {
+    type Event = HeatingZoneNodeSetEvents;
+
+    fn parse_set(
+        &self,
+        property: &PropertyRef,
+        desc: &HomieDeviceDescription,
+        set_value: &str,
+    ) -> ParseOutcome<Self::Event> {
+        if let Some(outcome) = reject_readonly(&self.node, property, desc, set_value) {
+            return outcome;
+        }
+
+        let property_id = property.prop_id().to_string();
+
+        if property.match_with_node(&self.node, &self.set_temperature_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Float(value)) => {
+                    ParseOutcome::Parsed(HeatingZoneNodeSetEvents::SetTemperature(value))
+                }
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else if property.match_with_node(&self.node, &self.mode_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Enum(value)) => match HeatingZoneMode::from_str(&value) {
+                    Ok(mode) => ParseOutcome::Parsed(HeatingZoneNodeSetEvents::Mode(mode)),
+                    Err(_) => ParseOutcome::Invalid(ParseError::new(
+                        property_id,
+                        set_value,
+                        ParseErrorKind::InvalidVariant,
+                    )),
+                },
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else {
+            ParseOutcome::NoMatch
+        }
+    }
+
+    fn parse_set_event(
+        &self,
+        desc: &HomieDeviceDescription,
+        event: &Homie5Message,
+    ) -> ParseOutcome<Self::Event> {
+        match event {
+            Homie5Message::PropertySet {
+                property,
+                set_value,
+            } => self.parse_set(property, desc, set_value),
+            _ => ParseOutcome::Invalid(ParseError::new(
+                self.set_temperature_prop.to_string(),
+                "",
+                ParseErrorKind::UnexpectedMessageType,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use homie5::HomieDomain;
+
+    use super::*;
+    use crate::StandaloneSetParser;
+
+    #[test]
+    fn setpoint_and_mode_parse_and_zone_flags_are_read_only() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let parser: StandaloneSetParser<_> = HeatingZoneNodeBuilder::default()
+            .build_with_publisher(HEATING_ZONE_NODE_DEFAULT_ID, &client)
+            .into();
+        let node = parser.publisher().node_ref().clone();
+        let setpoint =
+            PropertyRef::from_node(node.clone(), HEATING_ZONE_NODE_SET_TEMPERATURE_PROP_ID);
+        let mode = PropertyRef::from_node(node.clone(), HEATING_ZONE_NODE_MODE_PROP_ID);
+        let window = PropertyRef::from_node(node, HEATING_ZONE_NODE_WINDOWOPEN_PROP_ID);
+
+        assert!(matches!(
+            parser.parse_set(&setpoint, "21.5"),
+            ParseOutcome::Parsed(HeatingZoneNodeSetEvents::SetTemperature(value)) if value == 21.5
+        ));
+        assert!(matches!(
+            parser.parse_set(&mode, "manual"),
+            ParseOutcome::Parsed(HeatingZoneNodeSetEvents::Mode(HeatingZoneMode::Manual))
+        ));
+        // `eco` is a known mode but not offered by the default config.
+        assert!(matches!(
+            parser.parse_set(&mode, "eco"),
+            ParseOutcome::Invalid(_)
+        ));
+        assert!(matches!(
+            parser.parse_set(&window, "true"),
+            ParseOutcome::Invalid(ParseError {
+                kind: ParseErrorKind::NotSettable,
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,548 @@
+use std::collections::BTreeMap;
+
+use homie5::{
+    HOMIE_UNIT_PERCENT, Homie5DeviceProtocol, Homie5Message, HomieID, HomieValue, NodeRef,
+    PropertyRef,
+    device_description::{
+        HomieDeviceDescription, HomieNodeDescription, IntegerRange, NodeDescriptionBuilder,
+        PropertyDescriptionBuilder,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ConfigError, ParseError, ParseErrorKind, ParseOutcome, PropertyMetadata,
+    SMARTHOME_CAP_HUMIDIFIER, SetCommandParser, SmarthomePublisher, SmarthomeType,
+    property_metadata, readonly_properties, reject_readonly, smarthome_node_config,
+    validate_enum_format,
+};
+
+pub const HUMIDIFIER_NODE_DEFAULT_ID: HomieID = HomieID::new_const("humidifier");
+pub const HUMIDIFIER_NODE_DEFAULT_NAME: &str = "Humidifier";
+pub const HUMIDIFIER_NODE_STATE_PROP_ID: HomieID = HomieID::new_const("state");
+pub const HUMIDIFIER_NODE_TARGET_HUMIDITY_PROP_ID: HomieID = HomieID::new_const("target-humidity");
+pub const HUMIDIFIER_NODE_MODE_PROP_ID: HomieID = HomieID::new_const("mode");
+pub const HUMIDIFIER_NODE_TANK_EMPTY_PROP_ID: HomieID = HomieID::new_const("water-tank-empty");
+
+// ── Node (state) ────────────────────────────────────────────────────────────
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HumidifierNodeState {
+    pub state: bool,
+    pub target_humidity: i64,
+    pub mode: Option<String>,
+    pub tank_empty: Option<bool>,
+}
+
+#[derive(Debug)]
+pub struct HumidifierNode {
+    pub publisher: HumidifierNodePublisher,
+    pub state: HumidifierNodeState,
+}
+
+impl HumidifierNode {
+    /// Apply a parsed set command and return the `$target` and value publishes
+    /// for the change.
+    pub fn apply_set(&mut self, event: HumidifierNodeSetEvents) -> Vec<homie5::client::Publish> {
+        match event {
+            HumidifierNodeSetEvents::State(value) => {
+                self.state.state = value;
+                vec![
+                    self.publisher.state_target(value),
+                    self.publisher.state(value),
+                ]
+            }
+            HumidifierNodeSetEvents::TargetHumidity(value) => {
+                self.state.target_humidity = value;
+                vec![
+                    self.publisher.target_humidity_target(value),
+                    self.publisher.target_humidity(value),
+                ]
+            }
+            HumidifierNodeSetEvents::Mode(value) => {
+                let publishes = [
+                    self.publisher.mode_target(&value),
+                    self.publisher.mode(&value),
+                ]
+                .into_iter()
+                .flatten()
+                .collect();
+                self.state.mode = Some(value);
+                publishes
+            }
+        }
+    }
+
+    /// Apply a value reported by the device and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Bool(value)) if p == &HUMIDIFIER_NODE_STATE_PROP_ID => {
+                self.state.state = *value;
+                vec![self.publisher.state(*value)]
+            }
+            (p, HomieValue::Integer(value)) if p == &HUMIDIFIER_NODE_TARGET_HUMIDITY_PROP_ID => {
+                self.state.target_humidity = *value;
+                vec![self.publisher.target_humidity(*value)]
+            }
+            (p, HomieValue::Enum(value)) if p == &HUMIDIFIER_NODE_MODE_PROP_ID => {
+                self.state.mode = Some(value.clone());
+                self.publisher.mode(value).into_iter().collect()
+            }
+            (p, HomieValue::Bool(value)) if p == &HUMIDIFIER_NODE_TANK_EMPTY_PROP_ID => {
+                self.state.tank_empty = Some(*value);
+                self.publisher.tank_empty(*value).into_iter().collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            Some(self.publisher.state(self.state.state)),
+            Some(self.publisher.target_humidity(self.state.target_humidity)),
+            self.state
+                .mode
+                .as_deref()
+                .and_then(|value| self.publisher.mode(value)),
+            self.state
+                .tank_empty
+                .and_then(|value| self.publisher.tank_empty(value)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+// ── Set events ──────────────────────────────────────────────────────────────
+
+#[derive(Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+pub enum HumidifierNodeSetEvents {
+    State(bool),
+    TargetHumidity(i64),
+    Mode(String),
+}
+
+// ── Config ──────────────────────────────────────────────────────────────────
+
+smarthome_node_config! {
+    #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+    #[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+    #[serde(default)]
+    pub struct HumidifierNodeConfig for HumidifierNodePublisher {
+        /// Lowest settable target humidity in percent.
+        pub min_humidity: i64,
+        /// Highest settable target humidity in percent.
+        pub max_humidity: i64,
+        /// Operating modes (e.g. `auto`, `sleep`, `baby`). The `mode` property is
+        /// only added when this list is not empty.
+        #[cfg_attr(
+            feature = "proptest",
+            proptest(strategy = "crate::arbitrary::enum_values()")
+        )]
+        pub modes: Vec<String>,
+        /// Expose the `water-tank-empty` property.
+        pub water_tank: bool => HUMIDIFIER_NODE_TANK_EMPTY_PROP_ID, tank_empty(bool), |_config| {
+            PropertyDescriptionBuilder::boolean()
+                .name("Water tank empty")
+                .boolean_labels("ok", "empty")
+                .settable(false)
+                .retained(true)
+                .build()
+        },
+        /// Properties to publish as read-only; sets to them are rejected.
+        #[cfg_attr(
+            feature = "proptest",
+            proptest(strategy = "crate::arbitrary::homie_ids()")
+        )]
+        pub readonly: Vec<HomieID>,
+    }
+}
+
+impl Default for HumidifierNodeConfig {
+    fn default() -> Self {
+        Self {
+            min_humidity: 30,
+            max_humidity: 80,
+            modes: Vec::new(),
+            water_tank: true,
+            readonly: Vec::new(),
+        }
+    }
+}
+
+impl HumidifierNodeConfig {
+    /// Check the config-provided enum values before building the node.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if !self.modes.is_empty() {
+            validate_enum_format(&HUMIDIFIER_NODE_MODE_PROP_ID, &self.modes)?;
+        }
+        Ok(())
+    }
+}
+
+// ── Builder ─────────────────────────────────────────────────────────────────
+
+pub struct HumidifierNodeBuilder {
+    node_builder: NodeDescriptionBuilder,
+    config: HumidifierNodeConfig,
+}
+
+impl Default for HumidifierNodeBuilder {
+    fn default() -> Self {
+        Self::new(&HumidifierNodeConfig::default())
+    }
+}
+
+impl HumidifierNodeBuilder {
+    pub fn new(config: &HumidifierNodeConfig) -> Self {
+        let db = Self::build_node(
+            NodeDescriptionBuilder::new().name(HUMIDIFIER_NODE_DEFAULT_NAME),
+            config,
+        )
+        .r#type(SMARTHOME_CAP_HUMIDIFIER);
+        let db = readonly_properties(db, &config.readonly);
+
+        Self {
+            node_builder: db,
+            config: config.clone(),
+        }
+    }
+
+    fn build_node(
+        db: NodeDescriptionBuilder,
+        config: &HumidifierNodeConfig,
+    ) -> NodeDescriptionBuilder {
+        config.add_optional_properties(
+            db.add_property(
+                HUMIDIFIER_NODE_STATE_PROP_ID,
+                PropertyDescriptionBuilder::boolean()
+                    .name("Humidifier state")
+                    .boolean_labels("off", "on")
+                    .settable(true)
+                    .retained(true)
+                    .build(),
+            )
+            .add_property(
+                HUMIDIFIER_NODE_TARGET_HUMIDITY_PROP_ID,
+                PropertyDescriptionBuilder::integer()
+                    .name("Target humidity")
+                    .unit(HOMIE_UNIT_PERCENT)
+                    .integer_range(IntegerRange {
+                        min: Some(config.min_humidity),
+                        max: Some(config.max_humidity),
+                        step: None,
+                    })
+                    .settable(true)
+                    .retained(true)
+                    .build(),
+            )
+            .add_property_cond(
+                HUMIDIFIER_NODE_MODE_PROP_ID,
+                !config.modes.is_empty(),
+                || {
+                    PropertyDescriptionBuilder::enumeration(config.modes.iter().map(|s| s.as_str()))
+                        .unwrap()
+                        .name("Humidifier mode")
+                        .settable(true)
+                        .retained(true)
+                        .build()
+                },
+            ),
+        )
+    }
+
+    pub fn name<S: Into<String>>(mut self, name: impl Into<Option<S>>) -> Self {
+        self.node_builder = self.node_builder.name(name);
+        self
+    }
+
+    /// Append a subtype suffix to the node type (`humidifier-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::Humidifier.with_subtype(subtype));
+        self
+    }
+
+    pub fn build(self) -> HomieNodeDescription {
+        self.node_builder.build()
+    }
+
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &HumidifierNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
+    pub fn build_with_publisher(
+        self,
+        node_id: HomieID,
+        client: &Homie5DeviceProtocol,
+    ) -> (HomieNodeDescription, HumidifierNodePublisher) {
+        (
+            self.node_builder.build(),
+            HumidifierNodePublisher::new(
+                NodeRef::new(
+                    client.homie_domain().to_owned(),
+                    client.id().clone(),
+                    node_id,
+                ),
+                client.clone(),
+                self.config,
+            ),
+        )
+    }
+}
+
+// ── Publisher ────────────────────────────────────────────────────────────────
+
+#[derive(Debug)]
+pub struct HumidifierNodePublisher {
+    client: Homie5DeviceProtocol,
+    node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
+    config: HumidifierNodeConfig,
+    state_prop: HomieID,
+    target_humidity_prop: HomieID,
+    mode_prop: HomieID,
+    tank_empty_prop: HomieID,
+}
+
+impl HumidifierNodePublisher {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: HumidifierNodeConfig) -> Self {
+        let metadata = property_metadata(&HumidifierNodeBuilder::new(&config).build());
+        Self {
+            node,
+            metadata,
+            client,
+            config,
+            state_prop: HUMIDIFIER_NODE_STATE_PROP_ID,
+            target_humidity_prop: HUMIDIFIER_NODE_TARGET_HUMIDITY_PROP_ID,
+            mode_prop: HUMIDIFIER_NODE_MODE_PROP_ID,
+            tank_empty_prop: HUMIDIFIER_NODE_TANK_EMPTY_PROP_ID,
+        }
+    }
+
+    pub fn state(&self, value: bool) -> homie5::client::Publish {
+        self.client.publish_value(
+            self.node.node_id(),
+            &self.state_prop,
+            value.to_string(),
+            true,
+        )
+    }
+
+    pub fn state_target(&self, value: bool) -> homie5::client::Publish {
+        self.client.publish_target(
+            self.node.node_id(),
+            &self.state_prop,
+            value.to_string(),
+            true,
+        )
+    }
+
+    pub fn target_humidity(&self, value: i64) -> homie5::client::Publish {
+        self.client.publish_value(
+            self.node.node_id(),
+            &self.target_humidity_prop,
+            value.to_string(),
+            true,
+        )
+    }
+
+    pub fn target_humidity_target(&self, value: i64) -> homie5::client::Publish {
+        self.client.publish_target(
+            self.node.node_id(),
+            &self.target_humidity_prop,
+            value.to_string(),
+            true,
+        )
+    }
+
+    pub fn mode(&self, value: &str) -> Option<homie5::client::Publish> {
+        if self.config.modes.is_empty() {
+            return None;
+        }
+        Some(
+            self.client
+                .publish_value(self.node.node_id(), &self.mode_prop, value, true),
+        )
+    }
+
+    pub fn mode_target(&self, value: &str) -> Option<homie5::client::Publish> {
+        if self.config.modes.is_empty() {
+            return None;
+        }
+        Some(
+            self.client
+                .publish_target(self.node.node_id(), &self.mode_prop, value, true),
+        )
+    }
+}
+
+impl SmarthomePublisher for HumidifierNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::Humidifier
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![
+            self.state_prop.clone(),
+            self.target_humidity_prop.clone(),
+            self.mode_prop.clone(),
+            self.tank_empty_prop.clone(),
+        ]
+    }
+}
+
+impl SetCommandParser for HumidifierNodePublisher {
+    type Event = HumidifierNodeSetEvents;
+
+    fn parse_set(
+        &self,
+        property: &PropertyRef,
+        desc: &HomieDeviceDescription,
+        set_value: &str,
+    ) -> ParseOutcome<Self::Event> {
+        if let Some(outcome) = reject_readonly(&self.node, property, desc, set_value) {
+            return outcome;
+        }
+
+        let property_id = property.prop_id().to_string();
+
+        if property.match_with_node(&self.node, &self.state_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Bool(value)) => {
+                    ParseOutcome::Parsed(HumidifierNodeSetEvents::State(value))
+                }
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else if property.match_with_node(&self.node, &self.target_humidity_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Integer(value)) => {
+                    ParseOutcome::Parsed(HumidifierNodeSetEvents::TargetHumidity(value))
+                }
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else if property.match_with_node(&self.node, &self.mode_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Enum(value)) => {
+                    ParseOutcome::Parsed(HumidifierNodeSetEvents::Mode(value))
+                }
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else {
+            ParseOutcome::NoMatch
+        }
+    }
+
+    fn parse_set_event(
+        &self,
+        desc: &HomieDeviceDescription,
+        event: &Homie5Message,
+    ) -> ParseOutcome<Self::Event> {
+        match event {
+            Homie5Message::PropertySet {
+                property,
+                set_value,
+            } => self.parse_set(property, desc, set_value),
+            _ => ParseOutcome::Invalid(ParseError::new(
+                self.state_prop.to_string(),
+                "",
+                ParseErrorKind::UnexpectedMessageType,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use homie5::HomieDomain;
+
+    use super::*;
+    use crate::StandaloneSetParser;
+
+    #[test]
+    fn target_humidity_respects_configured_range() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let parser: StandaloneSetParser<_> = HumidifierNodeBuilder::default()
+            .build_with_publisher(HUMIDIFIER_NODE_DEFAULT_ID, &client)
+            .into();
+        let node = parser.publisher().node_ref().clone();
+        let target = PropertyRef::from_node(node.clone(), HUMIDIFIER_NODE_TARGET_HUMIDITY_PROP_ID);
+        let mode = PropertyRef::from_node(node, HUMIDIFIER_NODE_MODE_PROP_ID);
+
+        assert!(matches!(
+            parser.parse_set(&target, "55"),
+            ParseOutcome::Parsed(HumidifierNodeSetEvents::TargetHumidity(55))
+        ));
+        assert!(matches!(
+            parser.parse_set(&target, "95"),
+            ParseOutcome::Invalid(_)
+        ));
+        // No modes configured, so there is no property description to parse against.
+        assert!(matches!(
+            parser.parse_set(&mode, "auto"),
+            ParseOutcome::Invalid(_)
+        ));
+    }
+}
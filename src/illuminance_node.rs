@@ -1,11 +1,17 @@
+use std::collections::BTreeMap;
+
 use homie5::{
-    HOMIE_UNIT_LUX, Homie5DeviceProtocol, HomieID, NodeRef,
+    HOMIE_UNIT_LUX, Homie5DeviceProtocol, HomieID, HomieValue, NodeRef,
     device_description::{
         HomieNodeDescription, NodeDescriptionBuilder, PropertyDescriptionBuilder,
     },
 };
+use serde::{Deserialize, Serialize};
 
-use crate::SMARTHOME_CAP_ILLUMINANCE;
+use crate::{
+    PropertyMetadata, SMARTHOME_CAP_ILLUMINANCE, SmarthomePublisher, SmarthomeType,
+    property_metadata,
+};
 
 pub const ILLUMINANCE_NODE_DEFAULT_ID: HomieID = HomieID::new_const("illuminance");
 pub const ILLUMINANCE_NODE_DEFAULT_NAME: &str = "Illuminance sensor";
@@ -13,10 +19,42 @@ pub const ILLUMINANCE_NODE_ILLUMINANCE_PROP_ID: HomieID = HomieID::new_const("il
 
 // ── Node (state) ────────────────────────────────────────────────────────────
 
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IlluminanceNodeState {
+    pub illuminance: i64,
+}
+
 #[derive(Debug)]
 pub struct IlluminanceNode {
     pub publisher: IlluminanceNodePublisher,
-    pub illuminance: i64,
+    pub state: IlluminanceNodeState,
+}
+
+impl IlluminanceNode {
+    /// Apply a value reported by the device and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Integer(value)) if p == &ILLUMINANCE_NODE_ILLUMINANCE_PROP_ID => {
+                self.state.illuminance = *value;
+                vec![self.publisher.illuminance(*value)]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [Some(self.publisher.illuminance(self.state.illuminance))]
+            .into_iter()
+            .flatten()
+            .collect()
+    }
 }
 
 // ── Builder ─────────────────────────────────────────────────────────────────
@@ -57,10 +95,23 @@ impl IlluminanceNodeBuilder {
         self
     }
 
+    /// Append a subtype suffix to the node type (`illuminance-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::Illuminance.with_subtype(subtype));
+        self
+    }
+
     pub fn build(self) -> HomieNodeDescription {
         self.node_builder.build()
     }
 
+    /// Ids of the properties the node contains.
+    pub fn property_ids() -> Vec<HomieID> {
+        Self::new().build().properties.into_keys().collect()
+    }
+
     pub fn build_with_publisher(
         self,
         node_id: HomieID,
@@ -86,13 +137,16 @@ impl IlluminanceNodeBuilder {
 pub struct IlluminanceNodePublisher {
     client: Homie5DeviceProtocol,
     node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
     illuminance_prop: HomieID,
 }
 
 impl IlluminanceNodePublisher {
     pub fn new(node: NodeRef, client: Homie5DeviceProtocol) -> Self {
+        let metadata = property_metadata(&IlluminanceNodeBuilder::new().build());
         Self {
             node,
+            metadata,
             client,
             illuminance_prop: ILLUMINANCE_NODE_ILLUMINANCE_PROP_ID,
         }
@@ -107,3 +161,21 @@ impl IlluminanceNodePublisher {
         )
     }
 }
+
+impl SmarthomePublisher for IlluminanceNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::Illuminance
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![self.illuminance_prop.clone()]
+    }
+}
@@ -0,0 +1,476 @@
+use std::collections::BTreeMap;
+
+use homie5::{
+    HOMIE_UNIT_SECONDS, Homie5DeviceProtocol, Homie5Message, HomieID, HomieValue, NodeRef,
+    PropertyRef,
+    device_description::{
+        FloatRange, HomieDeviceDescription, HomieNodeDescription, IntegerRange,
+        NodeDescriptionBuilder, PropertyDescriptionBuilder,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ParseError, ParseErrorKind, ParseOutcome, PropertyMetadata, SMARTHOME_CAP_IRRIGATION,
+    SetCommandParser, SmarthomePublisher, SmarthomeType, property_metadata, readonly_properties,
+    reject_readonly, smarthome_node_config,
+};
+
+pub const IRRIGATION_NODE_DEFAULT_ID: HomieID = HomieID::new_const("irrigation");
+pub const IRRIGATION_NODE_DEFAULT_NAME: &str = "Irrigation zone";
+pub const IRRIGATION_NODE_RUN_PROP_ID: HomieID = HomieID::new_const("run");
+pub const IRRIGATION_NODE_DURATION_PROP_ID: HomieID = HomieID::new_const("duration");
+pub const IRRIGATION_NODE_REMAINING_PROP_ID: HomieID = HomieID::new_const("remaining-time");
+pub const IRRIGATION_NODE_FLOW_PROP_ID: HomieID = HomieID::new_const("flow");
+
+// ── Node (state) ────────────────────────────────────────────────────────────
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IrrigationNodeState {
+    pub run: bool,
+    pub duration: i64,
+    pub remaining: i64,
+    pub flow: Option<f64>,
+}
+
+#[derive(Debug)]
+pub struct IrrigationNode {
+    pub publisher: IrrigationNodePublisher,
+    pub state: IrrigationNodeState,
+}
+
+impl IrrigationNode {
+    /// Apply a parsed set command and return the `$target` and value publishes
+    /// for the change.
+    pub fn apply_set(&mut self, event: IrrigationNodeSetEvents) -> Vec<homie5::client::Publish> {
+        match event {
+            IrrigationNodeSetEvents::Run(value) => {
+                self.state.run = value;
+                vec![self.publisher.run_target(value), self.publisher.run(value)]
+            }
+            IrrigationNodeSetEvents::Duration(value) => {
+                self.state.duration = value;
+                vec![
+                    self.publisher.duration_target(value),
+                    self.publisher.duration(value),
+                ]
+            }
+        }
+    }
+
+    /// Apply a value reported by the device and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Bool(value)) if p == &IRRIGATION_NODE_RUN_PROP_ID => {
+                self.state.run = *value;
+                vec![self.publisher.run(*value)]
+            }
+            (p, HomieValue::Integer(value)) if p == &IRRIGATION_NODE_DURATION_PROP_ID => {
+                self.state.duration = *value;
+                vec![self.publisher.duration(*value)]
+            }
+            (p, HomieValue::Integer(value)) if p == &IRRIGATION_NODE_REMAINING_PROP_ID => {
+                self.state.remaining = *value;
+                vec![self.publisher.remaining(*value)]
+            }
+            (p, HomieValue::Float(value)) if p == &IRRIGATION_NODE_FLOW_PROP_ID => {
+                self.state.flow = Some(*value);
+                self.publisher.flow(*value).into_iter().collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            Some(self.publisher.run(self.state.run)),
+            Some(self.publisher.duration(self.state.duration)),
+            Some(self.publisher.remaining(self.state.remaining)),
+            self.state.flow.and_then(|value| self.publisher.flow(value)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+// ── Set events ──────────────────────────────────────────────────────────────
+
+#[derive(Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+pub enum IrrigationNodeSetEvents {
+    Run(bool),
+    Duration(i64),
+}
+
+// ── Config ──────────────────────────────────────────────────────────────────
+
+smarthome_node_config! {
+    #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+    #[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+    #[serde(default)]
+    pub struct IrrigationNodeConfig for IrrigationNodePublisher {
+        /// Expose the measured water `flow` (zones with a flow sensor).
+        pub flow: bool => IRRIGATION_NODE_FLOW_PROP_ID, flow(f64), |config| {
+            PropertyDescriptionBuilder::float()
+                .name("Water flow")
+                .unit(config.flow_unit.to_owned())
+                .float_range(FloatRange {
+                    min: Some(0.0),
+                    max: None,
+                    step: None,
+                })
+                .settable(false)
+                .retained(true)
+                .build()
+        },
+        /// Unit of `flow`, e.g. `L/min` or `gal/min`.
+        pub flow_unit: String,
+        /// Properties to publish as read-only; sets to them are rejected.
+        #[cfg_attr(
+            feature = "proptest",
+            proptest(strategy = "crate::arbitrary::homie_ids()")
+        )]
+        pub readonly: Vec<HomieID>,
+    }
+}
+
+impl Default for IrrigationNodeConfig {
+    fn default() -> Self {
+        Self {
+            flow: false,
+            flow_unit: "L/min".to_owned(),
+            readonly: Vec::new(),
+        }
+    }
+}
+
+// ── Builder ─────────────────────────────────────────────────────────────────
+
+pub struct IrrigationNodeBuilder {
+    node_builder: NodeDescriptionBuilder,
+    config: IrrigationNodeConfig,
+}
+
+impl Default for IrrigationNodeBuilder {
+    fn default() -> Self {
+        Self::new(&IrrigationNodeConfig::default())
+    }
+}
+
+impl IrrigationNodeBuilder {
+    pub fn new(config: &IrrigationNodeConfig) -> Self {
+        let db = Self::build_node(
+            NodeDescriptionBuilder::new().name(IRRIGATION_NODE_DEFAULT_NAME),
+            config,
+        )
+        .r#type(SMARTHOME_CAP_IRRIGATION);
+        let db = readonly_properties(db, &config.readonly);
+
+        Self {
+            node_builder: db,
+            config: config.clone(),
+        }
+    }
+
+    fn build_node(
+        db: NodeDescriptionBuilder,
+        config: &IrrigationNodeConfig,
+    ) -> NodeDescriptionBuilder {
+        config.add_optional_properties(
+            db.add_property(
+                IRRIGATION_NODE_RUN_PROP_ID,
+                PropertyDescriptionBuilder::boolean()
+                    .name("Watering")
+                    .boolean_labels("off", "on")
+                    .settable(true)
+                    .retained(true)
+                    .build(),
+            )
+            .add_property(
+                IRRIGATION_NODE_DURATION_PROP_ID,
+                PropertyDescriptionBuilder::integer()
+                    .name("Run duration")
+                    .unit(HOMIE_UNIT_SECONDS)
+                    .integer_range(IntegerRange {
+                        min: Some(0),
+                        max: None,
+                        step: None,
+                    })
+                    .settable(true)
+                    .retained(true)
+                    .build(),
+            )
+            .add_property(
+                IRRIGATION_NODE_REMAINING_PROP_ID,
+                PropertyDescriptionBuilder::integer()
+                    .name("Remaining time")
+                    .unit(HOMIE_UNIT_SECONDS)
+                    .integer_range(IntegerRange {
+                        min: Some(0),
+                        max: None,
+                        step: None,
+                    })
+                    .settable(false)
+                    .retained(true)
+                    .build(),
+            ),
+        )
+    }
+
+    pub fn name<S: Into<String>>(mut self, name: impl Into<Option<S>>) -> Self {
+        self.node_builder = self.node_builder.name(name);
+        self
+    }
+
+    /// Append a subtype suffix to the node type (`irrigation-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::Irrigation.with_subtype(subtype));
+        self
+    }
+
+    pub fn build(self) -> HomieNodeDescription {
+        self.node_builder.build()
+    }
+
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &IrrigationNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
+    pub fn build_with_publisher(
+        self,
+        node_id: HomieID,
+        client: &Homie5DeviceProtocol,
+    ) -> (HomieNodeDescription, IrrigationNodePublisher) {
+        (
+            self.node_builder.build(),
+            IrrigationNodePublisher::new(
+                NodeRef::new(
+                    client.homie_domain().to_owned(),
+                    client.id().clone(),
+                    node_id,
+                ),
+                client.clone(),
+                self.config,
+            ),
+        )
+    }
+}
+
+// ── Publisher ────────────────────────────────────────────────────────────────
+
+#[derive(Debug)]
+pub struct IrrigationNodePublisher {
+    client: Homie5DeviceProtocol,
+    node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
+    config: IrrigationNodeConfig,
+    run_prop: HomieID,
+    duration_prop: HomieID,
+    remaining_prop: HomieID,
+    flow_prop: HomieID,
+}
+
+impl IrrigationNodePublisher {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: IrrigationNodeConfig) -> Self {
+        let metadata = property_metadata(&IrrigationNodeBuilder::new(&config).build());
+        Self {
+            node,
+            metadata,
+            client,
+            config,
+            run_prop: IRRIGATION_NODE_RUN_PROP_ID,
+            duration_prop: IRRIGATION_NODE_DURATION_PROP_ID,
+            remaining_prop: IRRIGATION_NODE_REMAINING_PROP_ID,
+            flow_prop: IRRIGATION_NODE_FLOW_PROP_ID,
+        }
+    }
+
+    pub fn run(&self, value: bool) -> homie5::client::Publish {
+        self.client
+            .publish_value(self.node.node_id(), &self.run_prop, value.to_string(), true)
+    }
+
+    pub fn run_target(&self, value: bool) -> homie5::client::Publish {
+        self.client
+            .publish_target(self.node.node_id(), &self.run_prop, value.to_string(), true)
+    }
+
+    pub fn duration(&self, value: i64) -> homie5::client::Publish {
+        self.client.publish_value(
+            self.node.node_id(),
+            &self.duration_prop,
+            value.to_string(),
+            true,
+        )
+    }
+
+    pub fn duration_target(&self, value: i64) -> homie5::client::Publish {
+        self.client.publish_target(
+            self.node.node_id(),
+            &self.duration_prop,
+            value.to_string(),
+            true,
+        )
+    }
+
+    pub fn remaining(&self, value: i64) -> homie5::client::Publish {
+        self.client.publish_value(
+            self.node.node_id(),
+            &self.remaining_prop,
+            value.to_string(),
+            true,
+        )
+    }
+}
+
+impl SmarthomePublisher for IrrigationNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::Irrigation
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![
+            self.run_prop.clone(),
+            self.duration_prop.clone(),
+            self.remaining_prop.clone(),
+            self.flow_prop.clone(),
+        ]
+    }
+}
+
+impl SetCommandParser for IrrigationNodePublisher {
+    type Event = IrrigationNodeSetEvents;
+
+    fn parse_set(
+        &self,
+        property: &PropertyRef,
+        desc: &HomieDeviceDescription,
+        set_value: &str,
+    ) -> ParseOutcome<Self::Event> {
+        if let Some(outcome) = reject_readonly(&self.node, property, desc, set_value) {
+            return outcome;
+        }
+
+        let property_id = property.prop_id().to_string();
+
+        if property.match_with_node(&self.node, &self.run_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Bool(value)) => {
+                    ParseOutcome::Parsed(IrrigationNodeSetEvents::Run(value))
+                }
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else if property.match_with_node(&self.node, &self.duration_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Integer(value)) => {
+                    ParseOutcome::Parsed(IrrigationNodeSetEvents::Duration(value))
+                }
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else {
+            ParseOutcome::NoMatch
+        }
+    }
+
+    fn parse_set_event(
+        &self,
+        desc: &HomieDeviceDescription,
+        event: &Homie5Message,
+    ) -> ParseOutcome<Self::Event> {
+        match event {
+            Homie5Message::PropertySet {
+                property,
+                set_value,
+            } => self.parse_set(property, desc, set_value),
+            _ => ParseOutcome::Invalid(ParseError::new(
+                self.run_prop.to_string(),
+                "",
+                ParseErrorKind::UnexpectedMessageType,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use homie5::HomieDomain;
+
+    use super::*;
+    use crate::StandaloneSetParser;
+
+    #[test]
+    fn run_and_duration_are_settable_remaining_is_not() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let parser: StandaloneSetParser<_> = IrrigationNodeBuilder::default()
+            .build_with_publisher(IRRIGATION_NODE_DEFAULT_ID, &client)
+            .into();
+        let node = parser.publisher().node_ref().clone();
+        let run = PropertyRef::from_node(node.clone(), IRRIGATION_NODE_RUN_PROP_ID);
+        let duration = PropertyRef::from_node(node.clone(), IRRIGATION_NODE_DURATION_PROP_ID);
+        let remaining = PropertyRef::from_node(node, IRRIGATION_NODE_REMAINING_PROP_ID);
+
+        assert!(matches!(
+            parser.parse_set(&run, "true"),
+            ParseOutcome::Parsed(IrrigationNodeSetEvents::Run(true))
+        ));
+        assert!(matches!(
+            parser.parse_set(&duration, "600"),
+            ParseOutcome::Parsed(IrrigationNodeSetEvents::Duration(600))
+        ));
+        assert!(matches!(
+            parser.parse_set(&remaining, "10"),
+            ParseOutcome::Invalid(ParseError {
+                kind: ParseErrorKind::NotSettable,
+                ..
+            })
+        ));
+    }
+}
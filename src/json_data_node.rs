@@ -0,0 +1,399 @@
+use std::collections::BTreeMap;
+
+use homie5::{
+    Homie5DeviceProtocol, Homie5Message, HomieID, HomieValue, NodeRef, PropertyRef,
+    device_description::{
+        HomieDeviceDescription, HomieNodeDescription, NodeDescriptionBuilder,
+        PropertyDescriptionBuilder,
+    },
+};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use crate::{
+    ParseError, ParseErrorKind, ParseOutcome, PropertyMetadata, SMARTHOME_CAP_JSON_DATA,
+    SetCommandParser, SmarthomePublisher, SmarthomeType, property_metadata, reject_readonly,
+};
+
+pub const JSON_DATA_NODE_DEFAULT_ID: HomieID = HomieID::new_const("data");
+pub const JSON_DATA_NODE_DEFAULT_NAME: &str = "Data";
+pub const JSON_DATA_NODE_VALUE_PROP_ID: HomieID = HomieID::new_const("value");
+
+// ── Node (state) ────────────────────────────────────────────────────────────
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JsonDataNodeState {
+    /// Current payload; `Null` until a value has been set.
+    pub value: serde_json::Value,
+}
+
+impl JsonDataNodeState {
+    /// Deserialize the current payload into `T`.
+    pub fn value_as<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        T::deserialize(&self.value)
+    }
+
+    /// Replace the current payload with the serialized form of `value`.
+    pub fn set_value_as<T: Serialize>(&mut self, value: &T) -> Result<(), serde_json::Error> {
+        self.value = serde_json::to_value(value)?;
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct JsonDataNode {
+    pub publisher: JsonDataNodePublisher,
+    pub state: JsonDataNodeState,
+}
+
+impl JsonDataNode {
+    /// Apply a parsed set command and return the `$target` and value publishes
+    /// for the change.
+    pub fn apply_set(&mut self, event: JsonDataNodeSetEvents) -> Vec<homie5::client::Publish> {
+        match event {
+            JsonDataNodeSetEvents::Value(value) => {
+                let publishes = vec![
+                    self.publisher.value_target(&value),
+                    self.publisher.value(&value),
+                ];
+                self.state.value = value;
+                publishes
+            }
+        }
+    }
+
+    /// Apply a value reported by the device and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::JSON(value)) if p == &JSON_DATA_NODE_VALUE_PROP_ID => {
+                self.state.value = value.clone();
+                vec![self.publisher.value(value)]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        if self.state.value.is_null() {
+            return Vec::new();
+        }
+        vec![self.publisher.value(&self.state.value)]
+    }
+}
+
+// ── Set events ──────────────────────────────────────────────────────────────
+
+#[derive(Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+pub enum JsonDataNodeSetEvents {
+    Value(
+        #[cfg_attr(
+            feature = "proptest",
+            proptest(strategy = "crate::arbitrary::json_value()")
+        )]
+        serde_json::Value,
+    ),
+}
+
+impl JsonDataNodeSetEvents {
+    /// Deserialize the payload of the set command into `T`.
+    pub fn value_as<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        match self {
+            Self::Value(value) => T::deserialize(value),
+        }
+    }
+}
+
+// ── Config ──────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+#[serde(default)]
+pub struct JsonDataNodeConfig {
+    /// Name of the `value` property, e.g. "Diagnostics".
+    pub label: Option<String>,
+    /// JSON schema of the payload, published as the property format.
+    pub schema: Option<String>,
+    pub settable: bool,
+}
+
+// ── Builder ─────────────────────────────────────────────────────────────────
+
+pub struct JsonDataNodeBuilder {
+    node_builder: NodeDescriptionBuilder,
+    config: JsonDataNodeConfig,
+}
+
+impl Default for JsonDataNodeBuilder {
+    fn default() -> Self {
+        Self::new(&JsonDataNodeConfig::default())
+    }
+}
+
+impl JsonDataNodeBuilder {
+    pub fn new(config: &JsonDataNodeConfig) -> Self {
+        let db = Self::build_node(
+            NodeDescriptionBuilder::new().name(JSON_DATA_NODE_DEFAULT_NAME),
+            config,
+        )
+        .r#type(SMARTHOME_CAP_JSON_DATA);
+
+        Self {
+            node_builder: db,
+            config: config.clone(),
+        }
+    }
+
+    fn build_node(
+        db: NodeDescriptionBuilder,
+        config: &JsonDataNodeConfig,
+    ) -> NodeDescriptionBuilder {
+        let prop = PropertyDescriptionBuilder::json()
+            .name(config.label.as_deref().unwrap_or("Value"))
+            .settable(config.settable)
+            .retained(true);
+        let prop = match &config.schema {
+            Some(schema) => prop.json_schema(schema),
+            None => prop,
+        };
+        db.add_property(JSON_DATA_NODE_VALUE_PROP_ID, prop.build())
+    }
+
+    pub fn name<S: Into<String>>(mut self, name: impl Into<Option<S>>) -> Self {
+        self.node_builder = self.node_builder.name(name);
+        self
+    }
+
+    /// Append a subtype suffix to the node type (`json-data-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::JsonData.with_subtype(subtype));
+        self
+    }
+
+    pub fn build(self) -> HomieNodeDescription {
+        self.node_builder.build()
+    }
+
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &JsonDataNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
+    pub fn build_with_publisher(
+        self,
+        node_id: HomieID,
+        client: &Homie5DeviceProtocol,
+    ) -> (HomieNodeDescription, JsonDataNodePublisher) {
+        (
+            self.node_builder.build(),
+            JsonDataNodePublisher::new(
+                NodeRef::new(
+                    client.homie_domain().to_owned(),
+                    client.id().clone(),
+                    node_id,
+                ),
+                client.clone(),
+                &self.config,
+            ),
+        )
+    }
+}
+
+// ── Publisher ────────────────────────────────────────────────────────────────
+
+#[derive(Debug)]
+pub struct JsonDataNodePublisher {
+    client: Homie5DeviceProtocol,
+    node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
+    value_prop: HomieID,
+}
+
+impl JsonDataNodePublisher {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: &JsonDataNodeConfig) -> Self {
+        let metadata = property_metadata(&JsonDataNodeBuilder::new(config).build());
+        Self {
+            node,
+            metadata,
+            client,
+            value_prop: JSON_DATA_NODE_VALUE_PROP_ID,
+        }
+    }
+
+    pub fn value(&self, value: &serde_json::Value) -> homie5::client::Publish {
+        self.client.publish_value(
+            self.node.node_id(),
+            &self.value_prop,
+            value.to_string(),
+            true,
+        )
+    }
+
+    pub fn value_target(&self, value: &serde_json::Value) -> homie5::client::Publish {
+        self.client.publish_target(
+            self.node.node_id(),
+            &self.value_prop,
+            value.to_string(),
+            true,
+        )
+    }
+
+    /// Publish the serialized form of `value`. Returns `None` if it fails to
+    /// serialize or is not a JSON object or array, which the Homie JSON
+    /// datatype requires.
+    pub fn value_as<T: Serialize>(&self, value: &T) -> Option<homie5::client::Publish> {
+        let value = serde_json::to_value(value).ok()?;
+        if !value.is_object() && !value.is_array() {
+            return None;
+        }
+        Some(self.value(&value))
+    }
+}
+
+impl SmarthomePublisher for JsonDataNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::JsonData
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![self.value_prop.clone()]
+    }
+}
+
+impl SetCommandParser for JsonDataNodePublisher {
+    type Event = JsonDataNodeSetEvents;
+
+    fn parse_set(
+        &self,
+        property: &PropertyRef,
+        desc: &HomieDeviceDescription,
+        set_value: &str,
+    ) -> ParseOutcome<Self::Event> {
+        if let Some(outcome) = reject_readonly(&self.node, property, desc, set_value) {
+            return outcome;
+        }
+
+        let property_id = property.prop_id().to_string();
+
+        if property.match_with_node(&self.node, &self.value_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::JSON(value)) => {
+                    ParseOutcome::Parsed(JsonDataNodeSetEvents::Value(value))
+                }
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else {
+            ParseOutcome::NoMatch
+        }
+    }
+
+    fn parse_set_event(
+        &self,
+        desc: &HomieDeviceDescription,
+        event: &Homie5Message,
+    ) -> ParseOutcome<Self::Event> {
+        match event {
+            Homie5Message::PropertySet {
+                property,
+                set_value,
+            } => self.parse_set(property, desc, set_value),
+            _ => ParseOutcome::Invalid(ParseError::new(
+                self.value_prop.to_string(),
+                "",
+                ParseErrorKind::UnexpectedMessageType,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use homie5::{HomieDomain, device_description::HomiePropertyFormat};
+
+    use super::*;
+    use crate::StandaloneSetParser;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Schedule {
+        days: Vec<String>,
+        start: String,
+    }
+
+    #[test]
+    fn typed_payloads_round_trip_through_set_and_state() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let config = JsonDataNodeConfig {
+            schema: Some(r#"{"type":"object"}"#.to_owned()),
+            settable: true,
+            ..Default::default()
+        };
+        let desc = JsonDataNodeBuilder::new(&config).build();
+        assert_eq!(
+            desc.properties[&JSON_DATA_NODE_VALUE_PROP_ID].format,
+            HomiePropertyFormat::Json(r#"{"type":"object"}"#.to_owned())
+        );
+
+        let parser: StandaloneSetParser<_> = JsonDataNodeBuilder::new(&config)
+            .build_with_publisher(JSON_DATA_NODE_DEFAULT_ID, &client)
+            .into();
+        let value = PropertyRef::from_node(
+            parser.publisher().node_ref().clone(),
+            JSON_DATA_NODE_VALUE_PROP_ID,
+        );
+
+        assert!(matches!(
+            parser.parse_set(&value, "42"),
+            ParseOutcome::Invalid(_)
+        ));
+        let ParseOutcome::Parsed(event) =
+            parser.parse_set(&value, r#"{"days":["mon","fri"],"start":"07:30"}"#)
+        else {
+            panic!("object payload must parse");
+        };
+        let expected = Schedule {
+            days: vec!["mon".to_owned(), "fri".to_owned()],
+            start: "07:30".to_owned(),
+        };
+        assert_eq!(event.value_as::<Schedule>().unwrap(), expected);
+
+        let mut node = JsonDataNode {
+            publisher: parser.into_publisher(),
+            state: JsonDataNodeState::default(),
+        };
+        assert!(node.publishes().is_empty());
+        assert_eq!(node.apply_set(event).len(), 2);
+        assert_eq!(node.state.value_as::<Schedule>().unwrap(), expected);
+        assert!(node.publisher.value_as(&"not an object").is_none());
+    }
+}
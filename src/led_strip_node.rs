@@ -0,0 +1,729 @@
+use std::{collections::BTreeMap, str::FromStr};
+
+use homie5::{
+    HOMIE_UNIT_PERCENT, Homie5DeviceProtocol, Homie5Message, HomieColorValue, HomieID, HomieValue,
+    NodeRef, PropertyRef,
+    device_description::{
+        ColorFormat, HomieDeviceDescription, HomieNodeDescription, IntegerRange,
+        NodeDescriptionBuilder, PropertyDescriptionBuilder,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ConfigError, ParseError, ParseErrorKind, ParseOutcome, PropertyMetadata,
+    SMARTHOME_CAP_LED_STRIP, SetCommandParser, SmarthomePublisher, SmarthomeType,
+    property_metadata, readonly_properties, reject_readonly, validate_enum_format,
+};
+
+pub const LED_STRIP_NODE_DEFAULT_ID: HomieID = HomieID::new_const("led-strip");
+pub const LED_STRIP_NODE_DEFAULT_NAME: &str = "LED strip";
+pub const LED_STRIP_NODE_BRIGHTNESS_PROP_ID: HomieID = HomieID::new_const("brightness");
+pub const LED_STRIP_NODE_EFFECT_PROP_ID: HomieID = HomieID::new_const("effect");
+pub const LED_STRIP_NODE_COLOR_PROP_ID: HomieID = HomieID::new_const("color");
+pub const LED_STRIP_NODE_COLORS_PROP_ID: HomieID = HomieID::new_const("colors");
+
+/// Separator between the segment colors of the packed `colors` property.
+pub const LED_STRIP_PACKED_COLOR_SEPARATOR: char = ';';
+
+/// Property ID of the color property for a (1-based) segment, e.g. `color-1`.
+pub fn led_strip_segment_color_prop_id(segment: u8) -> HomieID {
+    HomieID::try_from(format!("{}-{}", LED_STRIP_NODE_COLOR_PROP_ID, segment))
+        .expect("indexed color id is always a valid HomieID")
+}
+
+/// Join segment colors into the payload of the packed `colors` property,
+/// e.g. `rgb,255,0,0;rgb,0,0,255`.
+pub fn pack_segment_colors(colors: &[HomieColorValue]) -> String {
+    colors
+        .iter()
+        .map(|color| color.to_string())
+        .collect::<Vec<_>>()
+        .join(&LED_STRIP_PACKED_COLOR_SEPARATOR.to_string())
+}
+
+/// Split a packed `colors` payload into the segment colors. Returns `None` if
+/// any of the colors is invalid.
+pub fn unpack_segment_colors(value: &str) -> Option<Vec<HomieColorValue>> {
+    value
+        .split(LED_STRIP_PACKED_COLOR_SEPARATOR)
+        .map(|color| HomieColorValue::from_str(color.trim()).ok())
+        .collect()
+}
+
+// ── Node (state) ────────────────────────────────────────────────────────────
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LedStripNodeState {
+    pub brightness: i64,
+    /// Segment colors, `colors[0]` being segment 1.
+    pub colors: Vec<HomieColorValue>,
+    pub effect: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct LedStripNode {
+    pub publisher: LedStripNodePublisher,
+    pub state: LedStripNodeState,
+}
+
+impl LedStripNode {
+    /// Apply a parsed set command and return the `$target` and value publishes
+    /// for the change.
+    pub fn apply_set(&mut self, event: LedStripNodeSetEvents) -> Vec<homie5::client::Publish> {
+        match event {
+            LedStripNodeSetEvents::Brightness(value) => {
+                self.state.brightness = value;
+                vec![
+                    self.publisher.brightness_target(value),
+                    self.publisher.brightness(value),
+                ]
+            }
+            LedStripNodeSetEvents::Effect(value) => {
+                let publishes = [
+                    self.publisher.effect_target(&value),
+                    self.publisher.effect(&value),
+                ]
+                .into_iter()
+                .flatten()
+                .collect();
+                self.state.effect = Some(value);
+                publishes
+            }
+            LedStripNodeSetEvents::SegmentColor(segment, value) => {
+                let Some(index) = usize::from(segment).checked_sub(1) else {
+                    return Vec::new();
+                };
+                self.store_segment_color(index, value);
+                [
+                    self.publisher.segment_color_target(segment, value),
+                    self.publisher.segment_color(segment, value),
+                ]
+                .into_iter()
+                .flatten()
+                .collect()
+            }
+            LedStripNodeSetEvents::Colors(colors) => {
+                self.state.colors = colors;
+                [
+                    self.publisher.colors_target(&self.state.colors),
+                    self.publisher.colors(&self.state.colors),
+                ]
+                .into_iter()
+                .flatten()
+                .collect()
+            }
+        }
+    }
+
+    /// Apply a value reported by the device and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Integer(value)) if p == &LED_STRIP_NODE_BRIGHTNESS_PROP_ID => {
+                self.state.brightness = *value;
+                vec![self.publisher.brightness(*value)]
+            }
+            (p, HomieValue::Enum(value)) if p == &LED_STRIP_NODE_EFFECT_PROP_ID => {
+                self.state.effect = Some(value.clone());
+                self.publisher.effect(value).into_iter().collect()
+            }
+            (p, HomieValue::String(value)) if p == &LED_STRIP_NODE_COLORS_PROP_ID => {
+                let Some(colors) = unpack_segment_colors(value) else {
+                    return Vec::new();
+                };
+                self.state.colors = colors;
+                self.publisher
+                    .colors(&self.state.colors)
+                    .into_iter()
+                    .collect()
+            }
+            (p, HomieValue::Color(value)) => {
+                let Some(index) = self
+                    .publisher
+                    .segment_color_props
+                    .iter()
+                    .position(|prop| prop == p)
+                else {
+                    return Vec::new();
+                };
+                self.store_segment_color(index, *value);
+                u8::try_from(index + 1)
+                    .ok()
+                    .and_then(|segment| self.publisher.segment_color(segment, *value))
+                    .into_iter()
+                    .collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn store_segment_color(&mut self, index: usize, value: HomieColorValue) {
+        if self.state.colors.len() <= index {
+            self.state
+                .colors
+                .resize(index + 1, HomieColorValue::RGB(0, 0, 0));
+        }
+        self.state.colors[index] = value;
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        let colors: Vec<_> = if self.publisher.config.packed {
+            self.publisher
+                .colors(&self.state.colors)
+                .into_iter()
+                .collect()
+        } else {
+            (1..=self.publisher.config.segments)
+                .zip(self.state.colors.iter())
+                .filter_map(|(segment, color)| self.publisher.segment_color(segment, *color))
+                .collect()
+        };
+        [
+            Some(self.publisher.brightness(self.state.brightness)),
+            self.state
+                .effect
+                .as_deref()
+                .and_then(|value| self.publisher.effect(value)),
+        ]
+        .into_iter()
+        .flatten()
+        .chain(colors)
+        .collect()
+    }
+}
+
+// ── Set events ──────────────────────────────────────────────────────────────
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+pub enum LedStripNodeSetEvents {
+    Brightness(i64),
+    Effect(String),
+    /// Color of the given 1-based segment.
+    SegmentColor(
+        u8,
+        #[cfg_attr(
+            feature = "proptest",
+            proptest(strategy = "crate::arbitrary::color_value()")
+        )]
+        HomieColorValue,
+    ),
+    /// Colors of all segments, from the packed `colors` property.
+    Colors(
+        #[cfg_attr(
+            feature = "proptest",
+            proptest(strategy = "crate::arbitrary::color_values()")
+        )]
+        Vec<HomieColorValue>,
+    ),
+}
+
+// ── Config ──────────────────────────────────────────────────────────────────
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+#[serde(default)]
+pub struct LedStripNodeConfig {
+    /// Number of segments, exposed as `color-1` .. `color-<segments>`.
+    pub segments: u8,
+    /// Expose all segment colors as a single packed `colors` property instead
+    /// of one color property per segment.
+    pub packed: bool,
+    #[cfg_attr(
+        feature = "proptest",
+        proptest(strategy = "crate::arbitrary::color_formats()")
+    )]
+    pub color_formats: Vec<ColorFormat>,
+    /// Effects offered by the strip (e.g. `solid`, `rainbow`, `chase`). The
+    /// `effect` property is only added when this is non-empty.
+    #[cfg_attr(
+        feature = "proptest",
+        proptest(strategy = "crate::arbitrary::enum_values()")
+    )]
+    pub effects: Vec<String>,
+    /// Properties to publish as read-only; sets to them are rejected.
+    #[cfg_attr(
+        feature = "proptest",
+        proptest(strategy = "crate::arbitrary::homie_ids()")
+    )]
+    pub readonly: Vec<HomieID>,
+}
+
+impl Default for LedStripNodeConfig {
+    fn default() -> Self {
+        Self {
+            segments: 1,
+            packed: false,
+            color_formats: vec![ColorFormat::Rgb],
+            effects: Vec::new(),
+            readonly: Vec::new(),
+        }
+    }
+}
+
+impl LedStripNodeConfig {
+    /// Check the config-provided enum values before building the node.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if !self.effects.is_empty() {
+            validate_enum_format(&LED_STRIP_NODE_EFFECT_PROP_ID, &self.effects)?;
+        }
+        Ok(())
+    }
+}
+
+// ── Builder ─────────────────────────────────────────────────────────────────
+
+pub struct LedStripNodeBuilder {
+    node_builder: NodeDescriptionBuilder,
+    config: LedStripNodeConfig,
+}
+
+impl Default for LedStripNodeBuilder {
+    fn default() -> Self {
+        Self::new(&LedStripNodeConfig::default())
+    }
+}
+
+impl LedStripNodeBuilder {
+    pub fn new(config: &LedStripNodeConfig) -> Self {
+        let db = Self::build_node(
+            NodeDescriptionBuilder::new().name(LED_STRIP_NODE_DEFAULT_NAME),
+            config,
+        )
+        .r#type(SMARTHOME_CAP_LED_STRIP);
+        let db = readonly_properties(db, &config.readonly);
+
+        Self {
+            node_builder: db,
+            config: config.clone(),
+        }
+    }
+
+    fn build_node(
+        db: NodeDescriptionBuilder,
+        config: &LedStripNodeConfig,
+    ) -> NodeDescriptionBuilder {
+        let db = db
+            .add_property(
+                LED_STRIP_NODE_BRIGHTNESS_PROP_ID,
+                PropertyDescriptionBuilder::integer()
+                    .name("Brightness")
+                    .unit(HOMIE_UNIT_PERCENT)
+                    .integer_range(IntegerRange {
+                        min: Some(0),
+                        max: Some(100),
+                        step: None,
+                    })
+                    .settable(true)
+                    .retained(true)
+                    .build(),
+            )
+            .add_property_cond(
+                LED_STRIP_NODE_EFFECT_PROP_ID,
+                !config.effects.is_empty(),
+                || {
+                    PropertyDescriptionBuilder::enumeration(config.effects.iter())
+                        .unwrap()
+                        .name("Effect")
+                        .settable(true)
+                        .retained(true)
+                        .build()
+                },
+            )
+            .add_property_cond(LED_STRIP_NODE_COLORS_PROP_ID, config.packed, || {
+                PropertyDescriptionBuilder::string()
+                    .name("Segment colors")
+                    .settable(true)
+                    .retained(true)
+                    .build()
+            });
+        if config.packed {
+            return db;
+        }
+        (1..=config.segments).fold(db, |db, segment| {
+            db.add_property(
+                led_strip_segment_color_prop_id(segment),
+                PropertyDescriptionBuilder::color(config.color_formats.clone())
+                    .unwrap()
+                    .name(format!("Segment {} color", segment))
+                    .settable(true)
+                    .retained(true)
+                    .build(),
+            )
+        })
+    }
+
+    pub fn name<S: Into<String>>(mut self, name: impl Into<Option<S>>) -> Self {
+        self.node_builder = self.node_builder.name(name);
+        self
+    }
+
+    /// Append a subtype suffix to the node type (`led-strip-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::LedStrip.with_subtype(subtype));
+        self
+    }
+
+    pub fn build(self) -> HomieNodeDescription {
+        self.node_builder.build()
+    }
+
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &LedStripNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
+    pub fn build_with_publisher(
+        self,
+        node_id: HomieID,
+        client: &Homie5DeviceProtocol,
+    ) -> (HomieNodeDescription, LedStripNodePublisher) {
+        (
+            self.node_builder.build(),
+            LedStripNodePublisher::new(
+                NodeRef::new(
+                    client.homie_domain().to_owned(),
+                    client.id().clone(),
+                    node_id,
+                ),
+                client.clone(),
+                self.config,
+            ),
+        )
+    }
+}
+
+// ── Publisher ────────────────────────────────────────────────────────────────
+
+#[derive(Debug)]
+pub struct LedStripNodePublisher {
+    client: Homie5DeviceProtocol,
+    node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
+    config: LedStripNodeConfig,
+    brightness_prop: HomieID,
+    effect_prop: HomieID,
+    colors_prop: HomieID,
+    segment_color_props: Vec<HomieID>,
+}
+
+impl LedStripNodePublisher {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: LedStripNodeConfig) -> Self {
+        let metadata = property_metadata(&LedStripNodeBuilder::new(&config).build());
+        let segment_color_props = if config.packed {
+            Vec::new()
+        } else {
+            (1..=config.segments)
+                .map(led_strip_segment_color_prop_id)
+                .collect()
+        };
+        Self {
+            node,
+            metadata,
+            client,
+            config,
+            brightness_prop: LED_STRIP_NODE_BRIGHTNESS_PROP_ID,
+            effect_prop: LED_STRIP_NODE_EFFECT_PROP_ID,
+            colors_prop: LED_STRIP_NODE_COLORS_PROP_ID,
+            segment_color_props,
+        }
+    }
+
+    fn segment_color_prop(&self, segment: u8) -> Option<&HomieID> {
+        self.segment_color_props
+            .get(usize::from(segment).checked_sub(1)?)
+    }
+
+    pub fn brightness(&self, value: i64) -> homie5::client::Publish {
+        self.client.publish_value(
+            self.node.node_id(),
+            &self.brightness_prop,
+            value.to_string(),
+            true,
+        )
+    }
+
+    pub fn brightness_target(&self, value: i64) -> homie5::client::Publish {
+        self.client.publish_target(
+            self.node.node_id(),
+            &self.brightness_prop,
+            value.to_string(),
+            true,
+        )
+    }
+
+    pub fn effect(&self, value: &str) -> Option<homie5::client::Publish> {
+        if self.config.effects.is_empty() {
+            return None;
+        }
+        Some(
+            self.client
+                .publish_value(self.node.node_id(), &self.effect_prop, value, true),
+        )
+    }
+
+    pub fn effect_target(&self, value: &str) -> Option<homie5::client::Publish> {
+        if self.config.effects.is_empty() {
+            return None;
+        }
+        Some(
+            self.client
+                .publish_target(self.node.node_id(), &self.effect_prop, value, true),
+        )
+    }
+
+    /// Publish the color of the given 1-based segment. Returns `None` for
+    /// segments outside of `1..=segments` and for packed strips.
+    pub fn segment_color(
+        &self,
+        segment: u8,
+        value: HomieColorValue,
+    ) -> Option<homie5::client::Publish> {
+        let prop = self.segment_color_prop(segment)?;
+        Some(
+            self.client
+                .publish_value(self.node.node_id(), prop, value, true),
+        )
+    }
+
+    pub fn segment_color_target(
+        &self,
+        segment: u8,
+        value: HomieColorValue,
+    ) -> Option<homie5::client::Publish> {
+        let prop = self.segment_color_prop(segment)?;
+        Some(
+            self.client
+                .publish_target(self.node.node_id(), prop, value, true),
+        )
+    }
+
+    /// Publish all segment colors on the packed `colors` property. Returns
+    /// `None` unless the strip is configured as `packed`.
+    pub fn colors(&self, colors: &[HomieColorValue]) -> Option<homie5::client::Publish> {
+        if !self.config.packed {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.colors_prop,
+            pack_segment_colors(colors),
+            true,
+        ))
+    }
+
+    pub fn colors_target(&self, colors: &[HomieColorValue]) -> Option<homie5::client::Publish> {
+        if !self.config.packed {
+            return None;
+        }
+        Some(self.client.publish_target(
+            self.node.node_id(),
+            &self.colors_prop,
+            pack_segment_colors(colors),
+            true,
+        ))
+    }
+}
+
+impl SmarthomePublisher for LedStripNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::LedStrip
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        let mut ids = vec![
+            self.brightness_prop.clone(),
+            self.effect_prop.clone(),
+            self.colors_prop.clone(),
+        ];
+        ids.extend(self.segment_color_props.iter().cloned());
+        ids
+    }
+}
+
+impl SetCommandParser for LedStripNodePublisher {
+    type Event = LedStripNodeSetEvents;
+
+    fn parse_set(
+        &self,
+        property: &PropertyRef,
+        desc: &HomieDeviceDescription,
+        set_value: &str,
+    ) -> ParseOutcome<Self::Event> {
+        if let Some(outcome) = reject_readonly(&self.node, property, desc, set_value) {
+            return outcome;
+        }
+
+        let property_id = property.prop_id().to_string();
+        let segment = self
+            .segment_color_props
+            .iter()
+            .position(|prop| property.match_with_node(&self.node, prop))
+            .map(|index| index as u8 + 1);
+
+        if !property.match_with_node(&self.node, &self.brightness_prop)
+            && !property.match_with_node(&self.node, &self.effect_prop)
+            && !property.match_with_node(&self.node, &self.colors_prop)
+            && segment.is_none()
+        {
+            return ParseOutcome::NoMatch;
+        }
+
+        let Some(parsed) = desc.with_property(property, |prop_desc| {
+            HomieValue::parse(set_value, prop_desc)
+        }) else {
+            return ParseOutcome::Invalid(ParseError::new(
+                property_id,
+                set_value,
+                ParseErrorKind::MissingPropertyDescription,
+            ));
+        };
+
+        match (parsed, segment) {
+            (Ok(HomieValue::Integer(value)), None) => {
+                ParseOutcome::Parsed(LedStripNodeSetEvents::Brightness(value))
+            }
+            (Ok(HomieValue::Enum(value)), None) => {
+                ParseOutcome::Parsed(LedStripNodeSetEvents::Effect(value))
+            }
+            (Ok(HomieValue::String(value)), None) => match unpack_segment_colors(&value) {
+                Some(colors)
+                    if colors.len() == usize::from(self.config.segments)
+                        && colors
+                            .iter()
+                            .all(|c| self.config.color_formats.contains(&c.color_format())) =>
+                {
+                    ParseOutcome::Parsed(LedStripNodeSetEvents::Colors(colors))
+                }
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            },
+            (Ok(HomieValue::Color(value)), Some(segment)) => {
+                ParseOutcome::Parsed(LedStripNodeSetEvents::SegmentColor(segment, value))
+            }
+            _ => ParseOutcome::Invalid(ParseError::new(
+                property_id,
+                set_value,
+                ParseErrorKind::InvalidHomieValue,
+            )),
+        }
+    }
+
+    fn parse_set_event(
+        &self,
+        desc: &HomieDeviceDescription,
+        event: &Homie5Message,
+    ) -> ParseOutcome<Self::Event> {
+        match event {
+            Homie5Message::PropertySet {
+                property,
+                set_value,
+            } => self.parse_set(property, desc, set_value),
+            _ => ParseOutcome::Invalid(ParseError::new(
+                self.brightness_prop.to_string(),
+                "",
+                ParseErrorKind::UnexpectedMessageType,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use homie5::HomieDomain;
+
+    use super::*;
+    use crate::StandaloneSetParser;
+
+    #[test]
+    fn segment_colors_parse_per_segment() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let config = LedStripNodeConfig {
+            segments: 3,
+            effects: vec!["solid".to_string(), "rainbow".to_string()],
+            ..LedStripNodeConfig::default()
+        };
+        let parser: StandaloneSetParser<_> = LedStripNodeBuilder::new(&config)
+            .build_with_publisher(LED_STRIP_NODE_DEFAULT_ID, &client)
+            .into();
+        let node = parser.publisher().node_ref().clone();
+        let color = PropertyRef::from_node(node.clone(), led_strip_segment_color_prop_id(2));
+        let effect = PropertyRef::from_node(node, LED_STRIP_NODE_EFFECT_PROP_ID);
+
+        assert_eq!(
+            parser.parse_set(&color, "rgb,255,0,0"),
+            ParseOutcome::Parsed(LedStripNodeSetEvents::SegmentColor(
+                2,
+                HomieColorValue::RGB(255, 0, 0)
+            ))
+        );
+        assert_eq!(
+            parser.parse_set(&effect, "rainbow"),
+            ParseOutcome::Parsed(LedStripNodeSetEvents::Effect("rainbow".to_string()))
+        );
+        assert!(parser.into_publisher().colors(&[]).is_none());
+    }
+
+    #[test]
+    fn packed_colors_round_trip() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let config = LedStripNodeConfig {
+            segments: 2,
+            packed: true,
+            ..LedStripNodeConfig::default()
+        };
+        let parser: StandaloneSetParser<_> = LedStripNodeBuilder::new(&config)
+            .build_with_publisher(LED_STRIP_NODE_DEFAULT_ID, &client)
+            .into();
+        let colors = PropertyRef::from_node(
+            parser.publisher().node_ref().clone(),
+            LED_STRIP_NODE_COLORS_PROP_ID,
+        );
+        let packed = pack_segment_colors(&[
+            HomieColorValue::RGB(255, 0, 0),
+            HomieColorValue::RGB(0, 0, 255),
+        ]);
+        assert_eq!(packed, "rgb,255,0,0;rgb,0,0,255");
+
+        assert_eq!(
+            parser.parse_set(&colors, &packed),
+            ParseOutcome::Parsed(LedStripNodeSetEvents::Colors(vec![
+                HomieColorValue::RGB(255, 0, 0),
+                HomieColorValue::RGB(0, 0, 255),
+            ]))
+        );
+        // one color for a two-segment strip
+        assert!(matches!(
+            parser.parse_set(&colors, "rgb,255,0,0"),
+            ParseOutcome::Invalid(_)
+        ));
+        assert!(
+            parser
+                .into_publisher()
+                .segment_color(1, HomieColorValue::RGB(0, 0, 0))
+                .is_none()
+        );
+    }
+}
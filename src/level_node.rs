@@ -1,4 +1,4 @@
-use std::str::FromStr;
+use std::{collections::BTreeMap, str::FromStr};
 
 use homie5::{
     HOMIE_UNIT_PERCENT, Homie5DeviceProtocol, Homie5Message, Homie5ProtocolError, HomieID,
@@ -10,21 +10,78 @@ use homie5::{
 };
 use serde::{Deserialize, Serialize};
 
-use crate::{ParseError, ParseErrorKind, ParseOutcome, SMARTHOME_CAP_LEVEL, SetCommandParser};
+use crate::{
+    ParseError, ParseErrorKind, ParseOutcome, PropertyMetadata, SMARTHOME_CAP_LEVEL,
+    SetCommandParser, SmarthomePublisher, SmarthomeType, property_metadata, readonly_properties,
+    reject_readonly,
+};
 
 pub const LEVEL_NODE_DEFAULT_ID: HomieID = HomieID::new_const("level");
 pub const LEVEL_NODE_DEFAULT_NAME: &str = "Level control";
 pub const LEVEL_NODE_VALUE_PROP_ID: HomieID = HomieID::new_const("value");
 pub const LEVEL_NODE_ACTION_PROP_ID: HomieID = HomieID::new_const("action");
 
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LevelNodeState {
+    pub value: i64,
+    pub value_target: i64,
+}
+
 #[derive(Debug)]
 pub struct LevelNode {
     pub publisher: LevelNodePublisher,
-    pub value: i64,
-    pub value_target: i64,
+    pub state: LevelNodeState,
+}
+
+impl LevelNode {
+    /// Apply a parsed set command and return the `$target` and value publishes
+    /// for the new level. Step actions are relative commands for the device and
+    /// leave the state unchanged.
+    pub fn apply_set(&mut self, event: LevelNodeSetEvents) -> Vec<homie5::client::Publish> {
+        match event {
+            LevelNodeSetEvents::Value(value) => {
+                self.state.value_target = value;
+                self.state.value = value;
+                vec![
+                    self.publisher.value_target(value),
+                    self.publisher.value(value),
+                ]
+            }
+            LevelNodeSetEvents::Action(_) => Vec::new(),
+        }
+    }
+
+    /// Apply a value reported by the device and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Integer(value)) if p == &LEVEL_NODE_VALUE_PROP_ID => {
+                self.state.value = *value;
+                vec![self.publisher.value(*value)]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            Some(self.publisher.value(self.state.value)),
+            Some(self.publisher.value_target(self.state.value_target)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 pub enum LevelNodeActions {
     StepUp,
     StepDown,
@@ -42,16 +99,24 @@ impl FromStr for LevelNodeActions {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 pub enum LevelNodeSetEvents {
     Value(i64),
     Action(LevelNodeActions),
 }
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 #[serde(default)]
 pub struct LevelNodeConfig {
     pub settable: bool,
     pub step_action: bool,
+    /// Properties to publish as read-only; sets to them are rejected.
+    #[cfg_attr(
+        feature = "proptest",
+        proptest(strategy = "crate::arbitrary::homie_ids()")
+    )]
+    pub readonly: Vec<HomieID>,
 }
 
 impl Default for LevelNodeConfig {
@@ -59,12 +124,14 @@ impl Default for LevelNodeConfig {
         Self {
             settable: true,
             step_action: true,
+            readonly: Vec::new(),
         }
     }
 }
 
 pub struct LevelNodeBuilder {
     node_builder: NodeDescriptionBuilder,
+    config: LevelNodeConfig,
 }
 
 impl LevelNodeBuilder {
@@ -74,8 +141,12 @@ impl LevelNodeBuilder {
             config,
         )
         .r#type(SMARTHOME_CAP_LEVEL);
+        let db = readonly_properties(db, &config.readonly);
 
-        Self { node_builder: db }
+        Self {
+            node_builder: db,
+            config: config.clone(),
+        }
     }
 
     fn build_node(db: NodeDescriptionBuilder, config: &LevelNodeConfig) -> NodeDescriptionBuilder {
@@ -108,10 +179,23 @@ impl LevelNodeBuilder {
         self
     }
 
+    /// Append a subtype suffix to the node type (`level-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::Level.with_subtype(subtype));
+        self
+    }
+
     pub fn build(self) -> HomieNodeDescription {
         self.node_builder.build()
     }
 
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &LevelNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
     pub fn build_with_publisher(
         self,
         node_id: HomieID,
@@ -123,6 +207,7 @@ impl LevelNodeBuilder {
             LevelNodePublisher::new(
                 NodeRef::new(client.homie_domain().to_owned(), did, node_id),
                 client.clone(),
+                &self.config,
             ),
         )
     }
@@ -132,14 +217,17 @@ impl LevelNodeBuilder {
 pub struct LevelNodePublisher {
     client: Homie5DeviceProtocol,
     node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
     value_prop: HomieID,
     action_prop: HomieID,
 }
 
 impl LevelNodePublisher {
-    pub fn new(node: NodeRef, client: Homie5DeviceProtocol) -> Self {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: &LevelNodeConfig) -> Self {
+        let metadata = property_metadata(&LevelNodeBuilder::new(config).build());
         Self {
             node,
+            metadata,
             client,
             value_prop: LEVEL_NODE_VALUE_PROP_ID,
             action_prop: LEVEL_NODE_ACTION_PROP_ID,
@@ -174,6 +262,24 @@ impl LevelNodePublisher {
     }
 }
 
+impl SmarthomePublisher for LevelNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::Level
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![self.value_prop.clone(), self.action_prop.clone()]
+    }
+}
+
 impl SetCommandParser for LevelNodePublisher {
     type Event = LevelNodeSetEvents;
 
@@ -183,6 +289,10 @@ impl SetCommandParser for LevelNodePublisher {
         desc: &HomieDeviceDescription,
         set_value: &str,
     ) -> ParseOutcome<Self::Event> {
+        if let Some(outcome) = reject_readonly(&self.node, property, desc, set_value) {
+            return outcome;
+        }
+
         let property_id = property.prop_id().to_string();
 
         if property.match_with_node(&self.node, &self.value_prop) {
@@ -1,72 +1,302 @@
+#[cfg(feature = "air-purifier")]
+pub mod air_purifier_node;
+#[cfg(feature = "air-quality")]
 pub mod air_quality_node;
+#[cfg(feature = "alarm")]
 pub mod alarm_node;
 pub mod alerts;
+#[cfg(feature = "proptest")]
+pub mod arbitrary;
+#[cfg(feature = "awning")]
+pub mod awning_node;
+#[cfg(feature = "battery")]
 pub mod battery_node;
+#[cfg(feature = "button")]
 pub mod button_node;
+#[cfg(feature = "camera")]
 pub mod camera_node;
+#[cfg(feature = "climate")]
 pub mod climate_node;
+#[cfg(feature = "co")]
 pub mod co_node;
+#[cfg(feature = "color")]
 pub mod color_node;
+#[cfg(feature = "contact")]
 pub mod contact_node;
+#[cfg(feature = "curtain")]
+pub mod curtain_node;
+pub mod custom_node;
+#[cfg(feature = "daylight")]
 pub mod daylight_node;
+#[cfg(feature = "dehumidifier")]
+pub mod dehumidifier_node;
+#[cfg(feature = "door")]
+pub mod door_node;
+#[cfg(feature = "doorbell")]
+pub mod doorbell_node;
+#[cfg(feature = "energy-dashboard")]
+pub mod energy_dashboard_node;
+#[cfg(feature = "ev-charger")]
+pub mod ev_charger_node;
+#[cfg(feature = "fan")]
+pub mod fan_node;
+#[cfg(feature = "garage-door")]
 pub mod garage_door_node;
+#[cfg(feature = "gas")]
+pub mod gas_node;
+#[cfg(feature = "generic-actuator")]
+pub mod generic_actuator_node;
+#[cfg(feature = "heating-zone")]
+pub mod heating_zone_node;
+#[cfg(feature = "humidifier")]
+pub mod humidifier_node;
+#[cfg(feature = "illuminance")]
 pub mod illuminance_node;
+#[cfg(feature = "irrigation")]
+pub mod irrigation_node;
+#[cfg(feature = "json-data")]
+pub mod json_data_node;
+#[cfg(feature = "led-strip")]
+pub mod led_strip_node;
+#[cfg(feature = "level")]
 pub mod level_node;
+#[cfg(feature = "link")]
 pub mod link_node;
+#[cfg(feature = "lock")]
 pub mod lock_node;
+#[cfg(feature = "maintenance")]
+pub mod maintenance_node;
+#[cfg(feature = "media-info")]
 pub mod media_info_node;
+#[cfg(feature = "mediaplayer")]
 pub mod mediaplayer_node;
+#[cfg(feature = "motion")]
 pub mod motion_node;
+pub mod node_config;
+pub mod node_factory;
+#[cfg(feature = "numeric-sensor")]
+pub mod numeric_sensor_node;
+#[cfg(feature = "orientation")]
 pub mod orientation_node;
+#[cfg(feature = "powermeter")]
 pub mod powermeter_node;
+#[cfg(feature = "scene")]
 pub mod scene_node;
+#[cfg(feature = "shutter")]
 pub mod shutter_node;
+#[cfg(feature = "smoke")]
 pub mod smoke_node;
+#[cfg(feature = "string-sensor")]
+pub mod string_sensor_node;
+#[cfg(feature = "switch")]
 pub mod switch_node;
+#[cfg(feature = "text")]
 pub mod text_node;
+#[cfg(feature = "thermostat")]
 pub mod thermostat_node;
+#[cfg(feature = "tilt")]
 pub mod tilt_node;
+#[cfg(feature = "time")]
+pub mod time_compat;
+#[cfg(feature = "timer")]
 pub mod timer_node;
+#[cfg(feature = "vacuum")]
+pub mod vacuum_node;
+#[cfg(feature = "valve")]
 pub mod valve_node;
+#[cfg(feature = "ventilation")]
+pub mod ventilation_node;
+#[cfg(feature = "vibration")]
 pub mod vibration_node;
+#[cfg(feature = "volume")]
 pub mod volume_node;
+#[cfg(feature = "water-sensor")]
 pub mod water_sensor_node;
+#[cfg(feature = "weather")]
+pub mod weather_node;
+#[cfg(all(feature = "weather", feature = "numeric-sensor"))]
+pub mod weather_station;
 
-use std::{fmt, str::FromStr};
+use std::{collections::BTreeMap, fmt, str::FromStr, sync::Arc};
 
+#[cfg(feature = "air-purifier")]
+use air_purifier_node::{AirPurifierNode, AirPurifierNodeConfig};
+#[cfg(feature = "air-quality")]
 use air_quality_node::{AirQualityNode, AirQualityNodeConfig};
+#[cfg(feature = "alarm")]
 use alarm_node::{AlarmNode, AlarmNodeConfig};
+#[cfg(feature = "awning")]
+use awning_node::{AwningNode, AwningNodeConfig};
+#[cfg(feature = "battery")]
 use battery_node::{BatteryNode, BatteryNodeConfig};
-use button_node::ButtonNodeConfig;
+#[cfg(feature = "button")]
+use button_node::{ButtonNode, ButtonNodeConfig, MultiButtonNode, MultiButtonNodeConfig};
+#[cfg(feature = "camera")]
 use camera_node::{CameraNode, CameraNodeConfig};
+use chrono::{DateTime, Utc};
+#[cfg(feature = "climate")]
 use climate_node::{ClimateNode, ClimateNodeConfig};
-use co_node::CoNode;
+#[cfg(feature = "co")]
+use co_node::{CoNode, CoNodeConfig};
+#[cfg(feature = "color")]
 use color_node::{ColorNode, ColorNodeConfig};
-use contact_node::ContactNode;
+#[cfg(feature = "contact")]
+use contact_node::{ContactNode, ContactNodeConfig};
+#[cfg(feature = "curtain")]
+use curtain_node::{CurtainNode, CurtainNodeConfig};
+#[cfg(feature = "daylight")]
 use daylight_node::{DaylightNode, DaylightNodeConfig};
+#[cfg(feature = "dehumidifier")]
+use dehumidifier_node::{DehumidifierNode, DehumidifierNodeConfig};
+#[cfg(feature = "door")]
+use door_node::{DoorNode, DoorNodeConfig};
+#[cfg(feature = "doorbell")]
+use doorbell_node::{DoorbellNode, DoorbellNodeConfig};
+#[cfg(feature = "energy-dashboard")]
+use energy_dashboard_node::{EnergyDashboardNode, EnergyDashboardNodeConfig};
+#[cfg(feature = "ev-charger")]
+use ev_charger_node::{EvChargerNode, EvChargerNodeConfig};
+#[cfg(feature = "fan")]
+use fan_node::{FanNode, FanNodeConfig};
+#[cfg(feature = "garage-door")]
 use garage_door_node::{GarageDoorNode, GarageDoorNodeConfig};
+#[cfg(feature = "gas")]
+use gas_node::{GasNode, GasNodeConfig};
+#[cfg(feature = "generic-actuator")]
+use generic_actuator_node::{GenericActuatorNode, GenericActuatorNodeConfig};
+#[cfg(feature = "heating-zone")]
+use heating_zone_node::{HeatingZoneNode, HeatingZoneNodeConfig};
+use homie5::{
+    Homie5DeviceProtocol, Homie5Message, HomieDataType, HomieID, HomieValue, NodeRef,
+    PROPERTY_SET_TOPIC, PropertyRef, ToTopic,
+    client::{Publish, QoS, Subscription},
+    device_description::{
+        DeviceDescriptionBuilder, HomieDeviceDescription, HomieNodeDescription,
+        HomiePropertyDescription, NodeDescriptionBuilder, PropertyDescriptionBuilder,
+        PropertyDescriptionValidationError,
+    },
+};
+#[cfg(feature = "humidifier")]
+use humidifier_node::{HumidifierNode, HumidifierNodeConfig};
+#[cfg(feature = "illuminance")]
 use illuminance_node::IlluminanceNode;
+#[cfg(feature = "irrigation")]
+use irrigation_node::{IrrigationNode, IrrigationNodeConfig};
+#[cfg(feature = "json-data")]
+use json_data_node::{JsonDataNode, JsonDataNodeConfig};
+#[cfg(feature = "led-strip")]
+use led_strip_node::{LedStripNode, LedStripNodeConfig};
+#[cfg(feature = "level")]
 use level_node::{LevelNode, LevelNodeConfig};
+#[cfg(feature = "link")]
 use link_node::{LinkNode, LinkNodeConfig};
+#[cfg(feature = "lock")]
 use lock_node::{LockNode, LockNodeConfig};
+#[cfg(feature = "maintenance")]
+use maintenance_node::{MaintenanceNode, MaintenanceNodeConfig};
+#[cfg(feature = "media-info")]
 use media_info_node::{MediaInfoNode, MediaInfoNodeConfig};
+#[cfg(feature = "mediaplayer")]
 use mediaplayer_node::{MediaplayerNode, MediaplayerNodeConfig};
-use motion_node::MotionNode;
-use orientation_node::OrientationNode;
+#[cfg(feature = "motion")]
+use motion_node::{MotionNode, MotionNodeConfig};
+#[cfg(feature = "numeric-sensor")]
+use numeric_sensor_node::{NumericSensorNode, NumericSensorNodeConfig};
+#[cfg(feature = "orientation")]
+use orientation_node::{OrientationNode, OrientationNodeConfig};
+#[cfg(feature = "powermeter")]
 use powermeter_node::{PowermeterNode, PowermeterNodeConfig};
-use scene_node::SceneNodeConfig;
+#[cfg(feature = "scene")]
+use scene_node::{SceneNode, SceneNodeConfig};
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "shutter")]
 use shutter_node::{ShutterNode, ShutterNodeConfig};
-use smoke_node::SmokeNode;
-use switch_node::{SwitchNode, SwitchNodeConfig};
+#[cfg(feature = "smoke")]
+use smoke_node::{SmokeNode, SmokeNodeConfig};
+#[cfg(feature = "string-sensor")]
+use string_sensor_node::{StringSensorNode, StringSensorNodeConfig};
+#[cfg(feature = "switch")]
+use switch_node::{MultiRelayNode, MultiRelayNodeConfig, SwitchNode, SwitchNodeConfig};
+#[cfg(feature = "text")]
 use text_node::TextNode;
+#[cfg(feature = "thermostat")]
 use thermostat_node::{ThermostatNode, ThermostatNodeConfig};
-use tilt_node::TiltNode;
+#[cfg(feature = "tilt")]
+use tilt_node::{TiltNode, TiltNodeConfig};
+#[cfg(feature = "timer")]
 use timer_node::{TimerNode, TimerNodeConfig};
+#[cfg(feature = "vacuum")]
+use vacuum_node::{VacuumNode, VacuumNodeConfig};
+#[cfg(feature = "valve")]
 use valve_node::{ValveNode, ValveNodeConfig};
+#[cfg(feature = "ventilation")]
+use ventilation_node::{VentilationNode, VentilationNodeConfig};
+#[cfg(feature = "vibration")]
 use vibration_node::{VibrationNode, VibrationNodeConfig};
+#[cfg(feature = "volume")]
 use volume_node::{VolumeNode, VolumeNodeConfig};
-use water_sensor_node::WaterSensorNode;
+#[cfg(feature = "water-sensor")]
+use water_sensor_node::{WaterSensorNode, WaterSensorNodeConfig};
+#[cfg(feature = "weather")]
+use weather_node::{WeatherNode, WeatherNodeConfig};
+
+#[cfg(not(any(
+    feature = "air-purifier",
+    feature = "air-quality",
+    feature = "alarm",
+    feature = "battery",
+    feature = "button",
+    feature = "camera",
+    feature = "climate",
+    feature = "co",
+    feature = "color",
+    feature = "contact",
+    feature = "daylight",
+    feature = "dehumidifier",
+    feature = "doorbell",
+    feature = "ev-charger",
+    feature = "fan",
+    feature = "garage-door",
+    feature = "gas",
+    feature = "humidifier",
+    feature = "illuminance",
+    feature = "irrigation",
+    feature = "level",
+    feature = "link",
+    feature = "lock",
+    feature = "maintenance",
+    feature = "media-info",
+    feature = "mediaplayer",
+    feature = "motion",
+    feature = "numeric-sensor",
+    feature = "orientation",
+    feature = "powermeter",
+    feature = "scene",
+    feature = "shutter",
+    feature = "smoke",
+    feature = "switch",
+    feature = "text",
+    feature = "thermostat",
+    feature = "tilt",
+    feature = "timer",
+    feature = "vacuum",
+    feature = "valve",
+    feature = "ventilation",
+    feature = "vibration",
+    feature = "volume",
+    feature = "water-sensor",
+    feature = "weather",
+    feature = "heating-zone",
+    feature = "energy-dashboard",
+    feature = "led-strip",
+    feature = "door",
+    feature = "curtain",
+    feature = "awning",
+    feature = "generic-actuator",
+    feature = "string-sensor",
+    feature = "json-data",
+)))]
+compile_error!("at least one node type feature must be enabled");
 
 /// Helper macro to generate capability type strings (`hc-smarthome/v2/cap/<name>`)
 macro_rules! smarthome_cap {
@@ -113,6 +343,7 @@ pub const SMARTHOME_CAP_LOCK: &str = smarthome_cap!("lock");
 pub const SMARTHOME_CAP_VALVE: &str = smarthome_cap!("valve");
 pub const SMARTHOME_CAP_BATTERY: &str = smarthome_cap!("battery");
 pub const SMARTHOME_CAP_LINK: &str = smarthome_cap!("link");
+pub const SMARTHOME_CAP_MAINTENANCE: &str = smarthome_cap!("maintenance");
 pub const SMARTHOME_CAP_MEDIAPLAYER: &str = smarthome_cap!("mediaplayer");
 pub const SMARTHOME_CAP_MEDIA_INFO: &str = smarthome_cap!("media-info");
 pub const SMARTHOME_CAP_VOLUME: &str = smarthome_cap!("volume");
@@ -121,11 +352,32 @@ pub const SMARTHOME_CAP_CO: &str = smarthome_cap!("co");
 pub const SMARTHOME_CAP_ALARM: &str = smarthome_cap!("alarm");
 pub const SMARTHOME_CAP_ILLUMINANCE: &str = smarthome_cap!("illuminance");
 pub const SMARTHOME_CAP_DAYLIGHT: &str = smarthome_cap!("daylight");
+pub const SMARTHOME_CAP_WEATHER: &str = smarthome_cap!("weather");
 pub const SMARTHOME_CAP_GARAGE_DOOR: &str = smarthome_cap!("garage-door");
 pub const SMARTHOME_CAP_AIR_QUALITY: &str = smarthome_cap!("air-quality");
 pub const SMARTHOME_CAP_CAMERA: &str = smarthome_cap!("camera");
 pub const SMARTHOME_CAP_TIMER: &str = smarthome_cap!("timer");
 pub const SMARTHOME_CAP_TEXT: &str = smarthome_cap!("text");
+pub const SMARTHOME_CAP_NUMERIC: &str = smarthome_cap!("numeric");
+pub const SMARTHOME_CAP_DOORBELL: &str = smarthome_cap!("doorbell");
+pub const SMARTHOME_CAP_GAS: &str = smarthome_cap!("gas");
+pub const SMARTHOME_CAP_FAN: &str = smarthome_cap!("fan");
+pub const SMARTHOME_CAP_VACUUM: &str = smarthome_cap!("vacuum");
+pub const SMARTHOME_CAP_EV_CHARGER: &str = smarthome_cap!("ev-charger");
+pub const SMARTHOME_CAP_IRRIGATION: &str = smarthome_cap!("irrigation");
+pub const SMARTHOME_CAP_HUMIDIFIER: &str = smarthome_cap!("humidifier");
+pub const SMARTHOME_CAP_DEHUMIDIFIER: &str = smarthome_cap!("dehumidifier");
+pub const SMARTHOME_CAP_VENTILATION: &str = smarthome_cap!("ventilation");
+pub const SMARTHOME_CAP_AIR_PURIFIER: &str = smarthome_cap!("air-purifier");
+pub const SMARTHOME_CAP_HEATING_ZONE: &str = smarthome_cap!("heating-zone");
+pub const SMARTHOME_CAP_ENERGY_DASHBOARD: &str = smarthome_cap!("energy-dashboard");
+pub const SMARTHOME_CAP_LED_STRIP: &str = smarthome_cap!("led-strip");
+pub const SMARTHOME_CAP_DOOR: &str = smarthome_cap!("door");
+pub const SMARTHOME_CAP_CURTAIN: &str = smarthome_cap!("curtain");
+pub const SMARTHOME_CAP_AWNING: &str = smarthome_cap!("awning");
+pub const SMARTHOME_CAP_GENERIC_ACTUATOR: &str = smarthome_cap!("generic-actuator");
+pub const SMARTHOME_CAP_STRING_SENSOR: &str = smarthome_cap!("string-sensor");
+pub const SMARTHOME_CAP_JSON_DATA: &str = smarthome_cap!("json-data");
 
 // ── Well-known device class constants ───────────────────────────────────────
 //
@@ -150,12 +402,27 @@ pub const DEVICE_CLASS_SIREN: &str = smarthome_dc!("siren");
 pub const DEVICE_CLASS_POWERMETER: &str = smarthome_dc!("powermeter");
 pub const DEVICE_CLASS_MEDIAPLAYER: &str = smarthome_dc!("mediaplayer");
 pub const DEVICE_CLASS_SMOKE_SENSOR: &str = smarthome_dc!("smoke-sensor");
+pub const DEVICE_CLASS_GAS_SENSOR: &str = smarthome_dc!("gas-sensor");
 pub const DEVICE_CLASS_SPEAKER: &str = smarthome_dc!("speaker");
 pub const DEVICE_CLASS_GARAGE_DOOR: &str = smarthome_dc!("garage-door");
 pub const DEVICE_CLASS_DOORBELL: &str = smarthome_dc!("doorbell");
 pub const DEVICE_CLASS_VIBRATION_SENSOR: &str = smarthome_dc!("vibration-sensor");
 pub const DEVICE_CLASS_TILT_SENSOR: &str = smarthome_dc!("tilt-sensor");
 pub const DEVICE_CLASS_CAMERA: &str = smarthome_dc!("camera");
+pub const DEVICE_CLASS_VACUUM: &str = smarthome_dc!("vacuum");
+pub const DEVICE_CLASS_EV_CHARGER: &str = smarthome_dc!("ev-charger");
+pub const DEVICE_CLASS_IRRIGATION_CONTROLLER: &str = smarthome_dc!("irrigation-controller");
+pub const DEVICE_CLASS_HUMIDIFIER: &str = smarthome_dc!("humidifier");
+pub const DEVICE_CLASS_DEHUMIDIFIER: &str = smarthome_dc!("dehumidifier");
+pub const DEVICE_CLASS_VENTILATION: &str = smarthome_dc!("ventilation");
+pub const DEVICE_CLASS_AIR_PURIFIER: &str = smarthome_dc!("air-purifier");
+pub const DEVICE_CLASS_ZONE_CONTROLLER: &str = smarthome_dc!("zone-controller");
+pub const DEVICE_CLASS_ENERGY_MONITOR: &str = smarthome_dc!("energy-monitor");
+pub const DEVICE_CLASS_LED_STRIP: &str = smarthome_dc!("led-strip");
+pub const DEVICE_CLASS_DOOR: &str = smarthome_dc!("door");
+pub const DEVICE_CLASS_CURTAIN: &str = smarthome_dc!("curtain");
+pub const DEVICE_CLASS_AWNING: &str = smarthome_dc!("awning");
+pub const DEVICE_CLASS_WEATHER_STATION: &str = smarthome_dc!("weather-station");
 
 // ── Parse infrastructure ────────────────────────────────────────────────────
 
@@ -165,6 +432,8 @@ pub enum ParseErrorKind {
     MissingPropertyDescription,
     InvalidHomieValue,
     InvalidVariant,
+    /// The property is published as read-only.
+    NotSettable,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -242,6 +511,102 @@ impl<T> From<T> for ParseOutcome<T> {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigErrorKind {
+    /// The config-provided values cannot be published as the property's enum
+    /// format.
+    InvalidEnum(PropertyDescriptionValidationError),
+    /// A multi-channel node is configured without channels.
+    NoChannels,
+}
+
+impl fmt::Display for ConfigErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigErrorKind::InvalidEnum(err) => write!(f, "{err}"),
+            ConfigErrorKind::NoChannels => f.write_str("at least one channel is required"),
+        }
+    }
+}
+
+/// A node config that cannot be built into a valid node description, e.g. an
+/// empty scene list, duplicate generic actuator commands or a multi-channel
+/// node without channels.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigError {
+    pub property_id: HomieID,
+    pub kind: ConfigErrorKind,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid config for property '{}': {}",
+            self.property_id, self.kind
+        )
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            ConfigErrorKind::InvalidEnum(err) => Some(err),
+            ConfigErrorKind::NoChannels => None,
+        }
+    }
+}
+
+/// Check that config-provided `values` form a valid enum format for
+/// `property_id`: non-empty, without empty or duplicate values.
+pub fn validate_enum_format(
+    property_id: &HomieID,
+    values: impl IntoIterator<Item = impl Into<String>>,
+) -> Result<(), ConfigError> {
+    PropertyDescriptionBuilder::enumeration(values)
+        .map(drop)
+        .map_err(|err| ConfigError {
+            property_id: property_id.clone(),
+            kind: ConfigErrorKind::InvalidEnum(err),
+        })
+}
+
+/// Mark properties of a node description under construction as read-only.
+/// Ids of properties the node does not have are ignored.
+pub fn readonly_properties(
+    db: NodeDescriptionBuilder,
+    readonly: &[HomieID],
+) -> NodeDescriptionBuilder {
+    let mut desc = db.build();
+    for prop_id in readonly {
+        if let Some(prop) = desc.properties.get_mut(prop_id) {
+            prop.settable = false;
+        }
+    }
+    NodeDescriptionBuilder::from_description(&desc)
+}
+
+/// Reject a set command to a property of `node` that `desc` describes as
+/// read-only. Returns `None` for settable properties and for properties of
+/// other nodes, so `parse_set` implementations can go on matching.
+pub fn reject_readonly<T>(
+    node: &NodeRef,
+    property: &PropertyRef,
+    desc: &HomieDeviceDescription,
+    set_value: &str,
+) -> Option<ParseOutcome<T>> {
+    if !property.belongs_to_node(node)
+        || desc.with_property(property, |prop| prop.settable) != Some(false)
+    {
+        return None;
+    }
+    Some(ParseOutcome::Invalid(ParseError::new(
+        property.prop_id().to_string(),
+        set_value,
+        ParseErrorKind::NotSettable,
+    )))
+}
+
 pub trait SetCommandParser {
     type Event;
 
@@ -259,129 +624,775 @@ pub trait SetCommandParser {
     ) -> ParseOutcome<Self::Event>;
 }
 
+/// Datatype, retention, settability and unit of a property, taken from the
+/// node description the builder produced.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropertyMetadata {
+    pub datatype: HomieDataType,
+    pub retained: bool,
+    pub settable: bool,
+    pub unit: Option<String>,
+}
+
+impl From<&HomiePropertyDescription> for PropertyMetadata {
+    fn from(desc: &HomiePropertyDescription) -> Self {
+        Self {
+            datatype: desc.datatype,
+            retained: desc.retained,
+            settable: desc.settable,
+            unit: desc.unit.clone(),
+        }
+    }
+}
+
+/// Metadata of all properties of `node_desc`, keyed by property id.
+pub fn property_metadata(node_desc: &HomieNodeDescription) -> BTreeMap<HomieID, PropertyMetadata> {
+    node_desc
+        .properties
+        .iter()
+        .map(|(prop_id, desc)| (prop_id.clone(), PropertyMetadata::from(desc)))
+        .collect()
+}
+
+/// Common view on all capability publishers, independent of their concrete type.
+pub trait SmarthomePublisher {
+    fn smarthome_type(&self) -> SmarthomeType;
+
+    fn node_ref(&self) -> &NodeRef;
+
+    fn node_id(&self) -> &HomieID {
+        self.node_ref().node_id()
+    }
+
+    /// Ids of all properties this publisher can publish, whether or not the
+    /// node was built with them.
+    fn property_ids(&self) -> Vec<HomieID>;
+
+    /// Metadata of the properties the node was built with, keyed by property
+    /// id. Publishers derive it from their config, so it is the same whether
+    /// they were created with `new` or the builder's `build_with_publisher`.
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata>;
+
+    /// Metadata of a single property; `None` if the node was built without it.
+    fn metadata(&self, prop_id: &HomieID) -> Option<&PropertyMetadata> {
+        self.property_metadata().get(prop_id)
+    }
+
+    fn property_ref(&self, prop_id: HomieID) -> PropertyRef {
+        PropertyRef::from_node(self.node_ref().clone(), prop_id)
+    }
+
+    /// /set subscriptions for the settable properties in `node_desc`.
+    ///
+    /// Allows subscribing per node instead of per device, e.g. when nodes are
+    /// added to a device at runtime.
+    fn subscriptions(&self, node_desc: &HomieNodeDescription) -> Vec<Subscription> {
+        node_desc
+            .properties
+            .iter()
+            .filter(|(_, prop)| prop.settable)
+            .map(|(prop_id, _)| Subscription {
+                topic: self
+                    .property_ref(prop_id.clone())
+                    .to_topic()
+                    .add_attr(PROPERTY_SET_TOPIC)
+                    .build(),
+                qos: QoS::ExactlyOnce,
+            })
+            .collect()
+    }
+}
+
+/// Nodes that apply values reported by the device to their state.
+///
+/// Implemented by every `*Node` with an `apply_value` method, so bridges that
+/// receive several readings at once, e.g. a vendor JSON blob, can update a
+/// node in one call instead of fanning the values out property by property.
+pub trait ApplyValue {
+    type State: Clone + PartialEq;
+
+    fn state(&self) -> &Self::State;
+
+    /// Apply a single reported value; see the node's `apply_value`.
+    fn apply_value(&mut self, property: &HomieID, value: &HomieValue) -> Vec<Publish>;
+
+    /// Apply several reported values and return the publishes for the ones
+    /// that changed the node state. Values equal to the current state, for
+    /// properties the node does not have or of the wrong datatype produce no
+    /// publishes.
+    fn apply_values<'a>(
+        &mut self,
+        values: impl IntoIterator<Item = (&'a HomieID, &'a HomieValue)>,
+    ) -> Vec<Publish> {
+        let mut publishes = Vec::new();
+        for (property, value) in values {
+            let before = self.state().clone();
+            let changed = self.apply_value(property, value);
+            if *self.state() != before {
+                publishes.extend(changed);
+            }
+        }
+        publishes
+    }
+}
+
+/// Parse the members of a JSON object whose keys are property ids of
+/// `node_desc` into values for [`ApplyValue::apply_values`].
+///
+/// Strings are parsed as published payloads, other JSON values by their JSON
+/// text, e.g. `{"state": true, "brightness": 40}` for a level node. Members
+/// that are not properties of the node, `null` or invalid for the property
+/// format are skipped.
+pub fn values_from_json(
+    node_desc: &HomieNodeDescription,
+    json: &serde_json::Value,
+) -> Vec<(HomieID, HomieValue)> {
+    let Some(members) = json.as_object() else {
+        return Vec::new();
+    };
+    members
+        .iter()
+        .filter_map(|(key, value)| {
+            let prop_id = HomieID::try_from(key.clone()).ok()?;
+            let prop_desc = node_desc.properties.get(&prop_id)?;
+            let payload = match value {
+                serde_json::Value::Null => return None,
+                serde_json::Value::String(payload) => payload.clone(),
+                value => value.to_string(),
+            };
+            let value = HomieValue::parse(&payload, prop_desc).ok()?;
+            Some((prop_id, value))
+        })
+        .collect()
+}
+
+/// Parses set commands for a single node without a full device description.
+///
+/// Keeps the publisher together with the description of its own node, as
+/// returned by `build_with_publisher`, which is all the parsers need to check
+/// payloads against the property datatypes and formats.
+#[derive(Debug)]
+pub struct StandaloneSetParser<P> {
+    publisher: P,
+    desc: HomieDeviceDescription,
+}
+
+impl<P: SetCommandParser + SmarthomePublisher> StandaloneSetParser<P> {
+    pub fn new(node_desc: HomieNodeDescription, publisher: P) -> Self {
+        let desc = DeviceDescriptionBuilder::new()
+            .add_node(publisher.node_id().clone(), node_desc)
+            .build();
+        Self { publisher, desc }
+    }
+
+    pub fn publisher(&self) -> &P {
+        &self.publisher
+    }
+
+    pub fn publisher_mut(&mut self) -> &mut P {
+        &mut self.publisher
+    }
+
+    pub fn into_publisher(self) -> P {
+        self.publisher
+    }
+
+    pub fn subscriptions(&self) -> Vec<Subscription> {
+        self.desc
+            .nodes
+            .get(self.publisher.node_id())
+            .map(|node_desc| self.publisher.subscriptions(node_desc))
+            .unwrap_or_default()
+    }
+
+    pub fn parse_set(&self, property: &PropertyRef, set_value: &str) -> ParseOutcome<P::Event> {
+        self.publisher.parse_set(property, &self.desc, set_value)
+    }
+
+    pub fn parse_set_event(&self, event: &Homie5Message) -> ParseOutcome<P::Event> {
+        self.publisher.parse_set_event(&self.desc, event)
+    }
+}
+
+impl<P: SetCommandParser + SmarthomePublisher> From<(HomieNodeDescription, P)>
+    for StandaloneSetParser<P>
+{
+    fn from((node_desc, publisher): (HomieNodeDescription, P)) -> Self {
+        Self::new(node_desc, publisher)
+    }
+}
+
+// ── Reusable publish buffers ────────────────────────────────────────────────
+
+/// Value topic of a single property, built once.
+///
+/// `publish_value` allocates a new topic and payload for every publish. For
+/// properties updated several times per second, [`PropertyTopic::write`]
+/// instead overwrites a caller-owned [`Publish`], reusing its allocations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropertyTopic {
+    topic: String,
+    retain: bool,
+}
+
+impl PropertyTopic {
+    pub fn new(
+        client: &Homie5DeviceProtocol,
+        node_id: &HomieID,
+        prop_id: &HomieID,
+        retain: bool,
+    ) -> Self {
+        Self {
+            topic: client.publish_value(node_id, prop_id, "", retain).topic,
+            retain,
+        }
+    }
+
+    pub fn topic(&self) -> &str {
+        &self.topic
+    }
+
+    /// Overwrite `buffer` with the publish of `value`. Produces the same
+    /// message as `publish_value` with `value.to_string()`, without
+    /// allocating once the buffer has grown to fit.
+    pub fn write(&self, value: impl fmt::Display, buffer: &mut Publish) {
+        use std::io::Write;
+
+        buffer.topic.clear();
+        buffer.topic.push_str(&self.topic);
+        buffer.retain = self.retain;
+        buffer.qos = QoS::ExactlyOnce;
+        buffer.payload.clear();
+        // Writing to a `Vec` cannot fail.
+        let _ = write!(buffer.payload, "{value}");
+        if buffer.payload.is_empty() {
+            // Empty strings are published as a single 0 byte.
+            buffer.payload.push(0);
+        }
+    }
+}
+
+/// An empty publish to hand to [`PropertyTopic::write`] or the `*_into`
+/// methods of high-rate publishers, which overwrite all of its fields.
+pub fn publish_buffer() -> Publish {
+    Publish {
+        topic: String::new(),
+        retain: false,
+        payload: Vec::new(),
+        qos: QoS::ExactlyOnce,
+    }
+}
+
+// ── Float formatting ────────────────────────────────────────────────────────
+
+/// Payload of a float property, rounded to the configured number of decimal
+/// places and formatted without allocating.
+///
+/// `precision: None` publishes the full value, which can carry binary
+/// artifacts such as `12.400000000000001`; `Some(1)` publishes `12.4`.
+#[cfg(any(
+    feature = "numeric-sensor",
+    feature = "orientation",
+    feature = "powermeter",
+    feature = "thermostat",
+    feature = "weather"
+))]
+pub(crate) struct Decimal {
+    pub(crate) value: f64,
+    pub(crate) precision: Option<usize>,
+    /// Round to a whole number, for properties declared as integers.
+    pub(crate) integer: bool,
+}
+
+#[cfg(any(
+    feature = "numeric-sensor",
+    feature = "orientation",
+    feature = "powermeter",
+    feature = "thermostat",
+    feature = "weather"
+))]
+impl Decimal {
+    pub(crate) fn new(value: f64, precision: Option<usize>) -> Self {
+        Self {
+            value,
+            precision,
+            integer: false,
+        }
+    }
+}
+
+#[cfg(any(
+    feature = "numeric-sensor",
+    feature = "orientation",
+    feature = "powermeter",
+    feature = "thermostat",
+    feature = "weather"
+))]
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = self.value;
+        if self.integer {
+            return write!(f, "{}", value.round() as i64);
+        }
+        match self.precision {
+            Some(precision) => write!(f, "{value:.precision$}"),
+            None => write!(f, "{value}"),
+        }
+    }
+}
+
+// ── Clock ───────────────────────────────────────────────────────────────────
+
+/// Source of the current time for publishers that timestamp publishes on their
+/// own, such as the maintenance `last-update` heartbeat or the numeric
+/// `last-changed` property.
+///
+/// Implemented for closures, so tests and simulations can pin or step time:
+///
+/// ```
+/// use chrono::{TimeZone, Utc};
+/// use hc_homie5_smarthome::Clock;
+///
+/// let start = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+/// let clock = move || start;
+/// assert_eq!(clock.now(), start);
+/// ```
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+impl<F> Clock for F
+where
+    F: Fn() -> DateTime<Utc> + Send + Sync,
+{
+    fn now(&self) -> DateTime<Utc> {
+        self()
+    }
+}
+
+/// The system clock, used unless a publisher is given another one.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A [`Clock`] shared between a publisher and the tasks it spawns.
+#[derive(Clone)]
+pub struct SharedClock(Arc<dyn Clock>);
+
+impl SharedClock {
+    pub fn new(clock: impl Clock + 'static) -> Self {
+        Self(Arc::new(clock))
+    }
+}
+
+impl Default for SharedClock {
+    fn default() -> Self {
+        Self::new(SystemClock)
+    }
+}
+
+impl fmt::Debug for SharedClock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SharedClock").finish_non_exhaustive()
+    }
+}
+
+impl Clock for SharedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0.now()
+    }
+}
+
 // ── SmarthomeType enum ──────────────────────────────────────────────────────
 
 /// SmarthomeType enum representing the capability node types.
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum SmarthomeType {
+    #[cfg(feature = "switch")]
     Switch,
+    #[cfg(feature = "level")]
     Level,
+    #[cfg(feature = "contact")]
     Contact,
+    #[cfg(feature = "climate")]
     Climate,
+    #[cfg(feature = "motion")]
     Motion,
+    #[cfg(feature = "button")]
     Button,
+    #[cfg(feature = "color")]
     Color,
+    #[cfg(feature = "scene")]
     Scene,
+    #[cfg(feature = "vibration")]
     Vibration,
+    #[cfg(feature = "orientation")]
     Orientation,
+    #[cfg(feature = "water-sensor")]
     WaterSensor,
+    #[cfg(feature = "shutter")]
     Shutter,
+    #[cfg(feature = "tilt")]
     Tilt,
+    #[cfg(feature = "thermostat")]
     Thermostat,
+    #[cfg(feature = "powermeter")]
     Powermeter,
+    #[cfg(feature = "lock")]
     Lock,
+    #[cfg(feature = "valve")]
     Valve,
+    #[cfg(feature = "battery")]
     Battery,
+    #[cfg(feature = "link")]
     Link,
+    #[cfg(feature = "maintenance")]
+    Maintenance,
+    #[cfg(feature = "mediaplayer")]
     Mediaplayer,
+    #[cfg(feature = "media-info")]
     MediaInfo,
+    #[cfg(feature = "volume")]
     Volume,
+    #[cfg(feature = "smoke")]
     Smoke,
+    #[cfg(feature = "co")]
     Co,
+    #[cfg(feature = "alarm")]
     Alarm,
+    #[cfg(feature = "illuminance")]
     Illuminance,
+    #[cfg(feature = "daylight")]
     Daylight,
+    #[cfg(feature = "weather")]
+    Weather,
+    #[cfg(feature = "garage-door")]
     GarageDoor,
+    #[cfg(feature = "air-quality")]
     AirQuality,
+    #[cfg(feature = "camera")]
     Camera,
+    #[cfg(feature = "timer")]
     Timer,
+    #[cfg(feature = "text")]
     Text,
+    #[cfg(feature = "numeric-sensor")]
+    Numeric,
+    #[cfg(feature = "doorbell")]
+    Doorbell,
+    #[cfg(feature = "gas")]
+    Gas,
+    #[cfg(feature = "fan")]
+    Fan,
+    #[cfg(feature = "vacuum")]
+    Vacuum,
+    #[cfg(feature = "ev-charger")]
+    EvCharger,
+    #[cfg(feature = "irrigation")]
+    Irrigation,
+    #[cfg(feature = "humidifier")]
+    Humidifier,
+    #[cfg(feature = "dehumidifier")]
+    Dehumidifier,
+    #[cfg(feature = "ventilation")]
+    Ventilation,
+    #[cfg(feature = "air-purifier")]
+    AirPurifier,
+    #[cfg(feature = "heating-zone")]
+    HeatingZone,
+    #[cfg(feature = "energy-dashboard")]
+    EnergyDashboard,
+    #[cfg(feature = "led-strip")]
+    LedStrip,
+    #[cfg(feature = "door")]
+    Door,
+    #[cfg(feature = "curtain")]
+    Curtain,
+    #[cfg(feature = "awning")]
+    Awning,
+    #[cfg(feature = "generic-actuator")]
+    GenericActuator,
+    #[cfg(feature = "string-sensor")]
+    StringSensor,
+    #[cfg(feature = "json-data")]
+    JsonData,
+    /// A vendor-specific node type declared with
+    /// [`define_smarthome_node!`](crate::define_smarthome_node), carrying its
+    /// `smarthome_ext!` type string.
+    Extension(&'static str),
 }
 
 impl SmarthomeType {
     /// Convert the enum variant into its corresponding string representation.
     pub const fn as_str(&self) -> &'static str {
         match self {
+            #[cfg(feature = "switch")]
             SmarthomeType::Switch => SMARTHOME_CAP_SWITCH,
+            #[cfg(feature = "level")]
             SmarthomeType::Level => SMARTHOME_CAP_LEVEL,
+            #[cfg(feature = "contact")]
             SmarthomeType::Contact => SMARTHOME_CAP_CONTACT,
+            #[cfg(feature = "climate")]
             SmarthomeType::Climate => SMARTHOME_CAP_CLIMATE,
+            #[cfg(feature = "motion")]
             SmarthomeType::Motion => SMARTHOME_CAP_MOTION,
+            #[cfg(feature = "button")]
             SmarthomeType::Button => SMARTHOME_CAP_BUTTON,
+            #[cfg(feature = "color")]
             SmarthomeType::Color => SMARTHOME_CAP_COLOR,
+            #[cfg(feature = "scene")]
             SmarthomeType::Scene => SMARTHOME_CAP_SCENE,
+            #[cfg(feature = "vibration")]
             SmarthomeType::Vibration => SMARTHOME_CAP_VIBRATION,
+            #[cfg(feature = "orientation")]
             SmarthomeType::Orientation => SMARTHOME_CAP_ORIENTATION,
+            #[cfg(feature = "water-sensor")]
             SmarthomeType::WaterSensor => SMARTHOME_CAP_WATER_SENSOR,
+            #[cfg(feature = "shutter")]
             SmarthomeType::Shutter => SMARTHOME_CAP_SHUTTER,
+            #[cfg(feature = "tilt")]
             SmarthomeType::Tilt => SMARTHOME_CAP_TILT,
+            #[cfg(feature = "thermostat")]
             SmarthomeType::Thermostat => SMARTHOME_CAP_THERMOSTAT,
+            #[cfg(feature = "powermeter")]
             SmarthomeType::Powermeter => SMARTHOME_CAP_POWERMETER,
+            #[cfg(feature = "lock")]
             SmarthomeType::Lock => SMARTHOME_CAP_LOCK,
+            #[cfg(feature = "valve")]
             SmarthomeType::Valve => SMARTHOME_CAP_VALVE,
+            #[cfg(feature = "battery")]
             SmarthomeType::Battery => SMARTHOME_CAP_BATTERY,
+            #[cfg(feature = "link")]
             SmarthomeType::Link => SMARTHOME_CAP_LINK,
+            #[cfg(feature = "maintenance")]
+            SmarthomeType::Maintenance => SMARTHOME_CAP_MAINTENANCE,
+            #[cfg(feature = "mediaplayer")]
             SmarthomeType::Mediaplayer => SMARTHOME_CAP_MEDIAPLAYER,
+            #[cfg(feature = "media-info")]
             SmarthomeType::MediaInfo => SMARTHOME_CAP_MEDIA_INFO,
+            #[cfg(feature = "volume")]
             SmarthomeType::Volume => SMARTHOME_CAP_VOLUME,
+            #[cfg(feature = "smoke")]
             SmarthomeType::Smoke => SMARTHOME_CAP_SMOKE,
+            #[cfg(feature = "co")]
             SmarthomeType::Co => SMARTHOME_CAP_CO,
+            #[cfg(feature = "alarm")]
             SmarthomeType::Alarm => SMARTHOME_CAP_ALARM,
+            #[cfg(feature = "illuminance")]
             SmarthomeType::Illuminance => SMARTHOME_CAP_ILLUMINANCE,
+            #[cfg(feature = "daylight")]
             SmarthomeType::Daylight => SMARTHOME_CAP_DAYLIGHT,
+            #[cfg(feature = "weather")]
+            SmarthomeType::Weather => SMARTHOME_CAP_WEATHER,
+            #[cfg(feature = "garage-door")]
             SmarthomeType::GarageDoor => SMARTHOME_CAP_GARAGE_DOOR,
+            #[cfg(feature = "air-quality")]
             SmarthomeType::AirQuality => SMARTHOME_CAP_AIR_QUALITY,
+            #[cfg(feature = "camera")]
             SmarthomeType::Camera => SMARTHOME_CAP_CAMERA,
+            #[cfg(feature = "timer")]
             SmarthomeType::Timer => SMARTHOME_CAP_TIMER,
+            #[cfg(feature = "text")]
             SmarthomeType::Text => SMARTHOME_CAP_TEXT,
+            #[cfg(feature = "numeric-sensor")]
+            SmarthomeType::Numeric => SMARTHOME_CAP_NUMERIC,
+            #[cfg(feature = "doorbell")]
+            SmarthomeType::Doorbell => SMARTHOME_CAP_DOORBELL,
+            #[cfg(feature = "gas")]
+            SmarthomeType::Gas => SMARTHOME_CAP_GAS,
+            #[cfg(feature = "fan")]
+            SmarthomeType::Fan => SMARTHOME_CAP_FAN,
+            #[cfg(feature = "vacuum")]
+            SmarthomeType::Vacuum => SMARTHOME_CAP_VACUUM,
+            #[cfg(feature = "ev-charger")]
+            SmarthomeType::EvCharger => SMARTHOME_CAP_EV_CHARGER,
+            #[cfg(feature = "irrigation")]
+            SmarthomeType::Irrigation => SMARTHOME_CAP_IRRIGATION,
+            #[cfg(feature = "humidifier")]
+            SmarthomeType::Humidifier => SMARTHOME_CAP_HUMIDIFIER,
+            #[cfg(feature = "dehumidifier")]
+            SmarthomeType::Dehumidifier => SMARTHOME_CAP_DEHUMIDIFIER,
+            #[cfg(feature = "ventilation")]
+            SmarthomeType::Ventilation => SMARTHOME_CAP_VENTILATION,
+            #[cfg(feature = "air-purifier")]
+            SmarthomeType::AirPurifier => SMARTHOME_CAP_AIR_PURIFIER,
+            #[cfg(feature = "heating-zone")]
+            SmarthomeType::HeatingZone => SMARTHOME_CAP_HEATING_ZONE,
+            #[cfg(feature = "energy-dashboard")]
+            SmarthomeType::EnergyDashboard => SMARTHOME_CAP_ENERGY_DASHBOARD,
+            #[cfg(feature = "led-strip")]
+            SmarthomeType::LedStrip => SMARTHOME_CAP_LED_STRIP,
+            #[cfg(feature = "door")]
+            SmarthomeType::Door => SMARTHOME_CAP_DOOR,
+            #[cfg(feature = "curtain")]
+            SmarthomeType::Curtain => SMARTHOME_CAP_CURTAIN,
+            #[cfg(feature = "awning")]
+            SmarthomeType::Awning => SMARTHOME_CAP_AWNING,
+            #[cfg(feature = "generic-actuator")]
+            SmarthomeType::GenericActuator => SMARTHOME_CAP_GENERIC_ACTUATOR,
+            #[cfg(feature = "string-sensor")]
+            SmarthomeType::StringSensor => SMARTHOME_CAP_STRING_SENSOR,
+            #[cfg(feature = "json-data")]
+            SmarthomeType::JsonData => SMARTHOME_CAP_JSON_DATA,
+            SmarthomeType::Extension(type_str) => type_str,
         }
     }
 
     /// Create a SmarthomeType from a string containing a constant value.
     pub fn from_constant(value: &str) -> Option<Self> {
         match value {
+            #[cfg(feature = "switch")]
             SMARTHOME_CAP_SWITCH => Some(SmarthomeType::Switch),
+            #[cfg(feature = "level")]
             SMARTHOME_CAP_LEVEL => Some(SmarthomeType::Level),
+            #[cfg(feature = "contact")]
             SMARTHOME_CAP_CONTACT => Some(SmarthomeType::Contact),
+            #[cfg(feature = "climate")]
             SMARTHOME_CAP_CLIMATE => Some(SmarthomeType::Climate),
+            #[cfg(feature = "motion")]
             SMARTHOME_CAP_MOTION => Some(SmarthomeType::Motion),
+            #[cfg(feature = "button")]
             SMARTHOME_CAP_BUTTON => Some(SmarthomeType::Button),
+            #[cfg(feature = "color")]
             SMARTHOME_CAP_COLOR => Some(SmarthomeType::Color),
+            #[cfg(feature = "scene")]
             SMARTHOME_CAP_SCENE => Some(SmarthomeType::Scene),
+            #[cfg(feature = "vibration")]
             SMARTHOME_CAP_VIBRATION => Some(SmarthomeType::Vibration),
+            #[cfg(feature = "orientation")]
             SMARTHOME_CAP_ORIENTATION => Some(SmarthomeType::Orientation),
+            #[cfg(feature = "water-sensor")]
             SMARTHOME_CAP_WATER_SENSOR => Some(SmarthomeType::WaterSensor),
+            #[cfg(feature = "shutter")]
             SMARTHOME_CAP_SHUTTER => Some(SmarthomeType::Shutter),
+            #[cfg(feature = "tilt")]
             SMARTHOME_CAP_TILT => Some(SmarthomeType::Tilt),
+            #[cfg(feature = "thermostat")]
             SMARTHOME_CAP_THERMOSTAT => Some(SmarthomeType::Thermostat),
+            #[cfg(feature = "powermeter")]
             SMARTHOME_CAP_POWERMETER => Some(SmarthomeType::Powermeter),
+            #[cfg(feature = "lock")]
             SMARTHOME_CAP_LOCK => Some(SmarthomeType::Lock),
+            #[cfg(feature = "valve")]
             SMARTHOME_CAP_VALVE => Some(SmarthomeType::Valve),
+            #[cfg(feature = "battery")]
             SMARTHOME_CAP_BATTERY => Some(SmarthomeType::Battery),
+            #[cfg(feature = "link")]
             SMARTHOME_CAP_LINK => Some(SmarthomeType::Link),
+            #[cfg(feature = "maintenance")]
+            SMARTHOME_CAP_MAINTENANCE => Some(SmarthomeType::Maintenance),
+            #[cfg(feature = "mediaplayer")]
             SMARTHOME_CAP_MEDIAPLAYER => Some(SmarthomeType::Mediaplayer),
+            #[cfg(feature = "media-info")]
             SMARTHOME_CAP_MEDIA_INFO => Some(SmarthomeType::MediaInfo),
+            #[cfg(feature = "volume")]
             SMARTHOME_CAP_VOLUME => Some(SmarthomeType::Volume),
+            #[cfg(feature = "smoke")]
             SMARTHOME_CAP_SMOKE => Some(SmarthomeType::Smoke),
+            #[cfg(feature = "co")]
             SMARTHOME_CAP_CO => Some(SmarthomeType::Co),
+            #[cfg(feature = "alarm")]
             SMARTHOME_CAP_ALARM => Some(SmarthomeType::Alarm),
+            #[cfg(feature = "illuminance")]
             SMARTHOME_CAP_ILLUMINANCE => Some(SmarthomeType::Illuminance),
+            #[cfg(feature = "daylight")]
             SMARTHOME_CAP_DAYLIGHT => Some(SmarthomeType::Daylight),
+            #[cfg(feature = "weather")]
+            SMARTHOME_CAP_WEATHER => Some(SmarthomeType::Weather),
+            #[cfg(feature = "garage-door")]
             SMARTHOME_CAP_GARAGE_DOOR => Some(SmarthomeType::GarageDoor),
+            #[cfg(feature = "air-quality")]
             SMARTHOME_CAP_AIR_QUALITY => Some(SmarthomeType::AirQuality),
+            #[cfg(feature = "camera")]
             SMARTHOME_CAP_CAMERA => Some(SmarthomeType::Camera),
+            #[cfg(feature = "timer")]
             SMARTHOME_CAP_TIMER => Some(SmarthomeType::Timer),
+            #[cfg(feature = "text")]
             SMARTHOME_CAP_TEXT => Some(SmarthomeType::Text),
+            #[cfg(feature = "numeric-sensor")]
+            SMARTHOME_CAP_NUMERIC => Some(SmarthomeType::Numeric),
+            #[cfg(feature = "doorbell")]
+            SMARTHOME_CAP_DOORBELL => Some(SmarthomeType::Doorbell),
+            #[cfg(feature = "gas")]
+            SMARTHOME_CAP_GAS => Some(SmarthomeType::Gas),
+            #[cfg(feature = "fan")]
+            SMARTHOME_CAP_FAN => Some(SmarthomeType::Fan),
+            #[cfg(feature = "vacuum")]
+            SMARTHOME_CAP_VACUUM => Some(SmarthomeType::Vacuum),
+            #[cfg(feature = "ev-charger")]
+            SMARTHOME_CAP_EV_CHARGER => Some(SmarthomeType::EvCharger),
+            #[cfg(feature = "irrigation")]
+            SMARTHOME_CAP_IRRIGATION => Some(SmarthomeType::Irrigation),
+            #[cfg(feature = "humidifier")]
+            SMARTHOME_CAP_HUMIDIFIER => Some(SmarthomeType::Humidifier),
+            #[cfg(feature = "dehumidifier")]
+            SMARTHOME_CAP_DEHUMIDIFIER => Some(SmarthomeType::Dehumidifier),
+            #[cfg(feature = "ventilation")]
+            SMARTHOME_CAP_VENTILATION => Some(SmarthomeType::Ventilation),
+            #[cfg(feature = "air-purifier")]
+            SMARTHOME_CAP_AIR_PURIFIER => Some(SmarthomeType::AirPurifier),
+            #[cfg(feature = "heating-zone")]
+            SMARTHOME_CAP_HEATING_ZONE => Some(SmarthomeType::HeatingZone),
+            #[cfg(feature = "energy-dashboard")]
+            SMARTHOME_CAP_ENERGY_DASHBOARD => Some(SmarthomeType::EnergyDashboard),
+            #[cfg(feature = "led-strip")]
+            SMARTHOME_CAP_LED_STRIP => Some(SmarthomeType::LedStrip),
+            #[cfg(feature = "door")]
+            SMARTHOME_CAP_DOOR => Some(SmarthomeType::Door),
+            #[cfg(feature = "curtain")]
+            SMARTHOME_CAP_CURTAIN => Some(SmarthomeType::Curtain),
+            #[cfg(feature = "awning")]
+            SMARTHOME_CAP_AWNING => Some(SmarthomeType::Awning),
+            #[cfg(feature = "generic-actuator")]
+            SMARTHOME_CAP_GENERIC_ACTUATOR => Some(SmarthomeType::GenericActuator),
+            #[cfg(feature = "string-sensor")]
+            SMARTHOME_CAP_STRING_SENSOR => Some(SmarthomeType::StringSensor),
+            #[cfg(feature = "json-data")]
+            SMARTHOME_CAP_JSON_DATA => Some(SmarthomeType::JsonData),
             _ => None,
         }
     }
+
+    /// The type string with a subtype suffix, e.g. `hc-smarthome/v2/cap/switch-relay`
+    /// for `Switch` and `relay`. An empty subtype gives the plain type string.
+    pub fn with_subtype(&self, subtype: &str) -> String {
+        if subtype.is_empty() {
+            self.as_str().to_owned()
+        } else {
+            format!("{}-{subtype}", self.as_str())
+        }
+    }
+
+    /// Classify a node type string that may carry a subtype suffix, e.g.
+    /// `hc-smarthome/v2/cap/contact-window` gives `(Contact, Some("window"))`.
+    /// Subtypes may contain `-` themselves; the longest matching capability wins.
+    pub fn from_type_with_subtype(value: &str) -> Option<(Self, Option<&str>)> {
+        if let Some(smarthome_type) = Self::from_constant(value) {
+            return Some((smarthome_type, None));
+        }
+        value.rmatch_indices('-').find_map(|(pos, _)| {
+            let subtype = &value[pos + 1..];
+            Self::from_constant(&value[..pos])
+                .filter(|_| !subtype.is_empty())
+                .map(|smarthome_type| (smarthome_type, Some(subtype)))
+        })
+    }
 }
 
+/// Parses canonical type strings as well as type strings with a subtype suffix.
+/// Deserialization goes through the same classification.
 impl FromStr for SmarthomeType {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        SmarthomeType::from_constant(s).ok_or(())
+        SmarthomeType::from_type_with_subtype(s)
+            .map(|(smarthome_type, _)| smarthome_type)
+            .ok_or(())
     }
 }
 
@@ -406,72 +1417,465 @@ impl<'de> Deserialize<'de> for SmarthomeType {
         D: serde::Deserializer<'de>,
     {
         let value = <&str>::deserialize(deserializer)?;
-        SmarthomeType::from_constant(value)
-            .ok_or_else(|| serde::de::Error::custom(format!("invalid smarthome type: {value}")))
+        value
+            .parse()
+            .map_err(|()| serde::de::Error::custom(format!("invalid smarthome type: {value}")))
     }
 }
 
 // ── Convenience config/node enums ───────────────────────────────────────────
 
+/// Node configuration for any capability, tagged with the capability name so it
+/// can be used in device definition files:
+///
+/// ```yaml
+/// - type: switch
+///   settable: true
+/// - type: water
+///   alarm_level: true
+/// ```
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
-pub enum SmarthomeProperyConfig {
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum SmarthomePropertyConfig {
+    #[cfg(feature = "air-purifier")]
+    AirPurifier(AirPurifierNodeConfig),
+    #[cfg(feature = "air-quality")]
     AirQuality(AirQualityNodeConfig),
+    #[cfg(feature = "alarm")]
     Alarm(AlarmNodeConfig),
+    #[cfg(feature = "awning")]
+    Awning(AwningNodeConfig),
+    #[cfg(feature = "battery")]
     Battery(BatteryNodeConfig),
+    #[cfg(feature = "button")]
     Button(ButtonNodeConfig),
+    #[cfg(feature = "camera")]
     Camera(CameraNodeConfig),
+    #[cfg(feature = "climate")]
     Climate(ClimateNodeConfig),
+    #[cfg(feature = "co")]
+    Co(CoNodeConfig),
+    #[cfg(feature = "color")]
     Color(ColorNodeConfig),
+    #[cfg(feature = "contact")]
+    Contact(ContactNodeConfig),
+    #[cfg(feature = "curtain")]
+    Curtain(CurtainNodeConfig),
+    #[cfg(feature = "daylight")]
     Daylight(DaylightNodeConfig),
+    #[cfg(feature = "dehumidifier")]
+    Dehumidifier(DehumidifierNodeConfig),
+    #[cfg(feature = "door")]
+    Door(DoorNodeConfig),
+    #[cfg(feature = "doorbell")]
+    Doorbell(DoorbellNodeConfig),
+    #[cfg(feature = "energy-dashboard")]
+    EnergyDashboard(EnergyDashboardNodeConfig),
+    #[cfg(feature = "ev-charger")]
+    EvCharger(EvChargerNodeConfig),
+    #[cfg(feature = "fan")]
+    Fan(FanNodeConfig),
+    #[cfg(feature = "garage-door")]
     GarageDoor(GarageDoorNodeConfig),
+    #[cfg(feature = "gas")]
+    Gas(GasNodeConfig),
+    #[cfg(feature = "generic-actuator")]
+    GenericActuator(GenericActuatorNodeConfig),
+    #[cfg(feature = "heating-zone")]
+    HeatingZone(HeatingZoneNodeConfig),
+    #[cfg(feature = "humidifier")]
+    Humidifier(HumidifierNodeConfig),
+    #[cfg(feature = "illuminance")]
+    Illuminance,
+    #[cfg(feature = "irrigation")]
+    Irrigation(IrrigationNodeConfig),
+    #[cfg(feature = "json-data")]
+    JsonData(JsonDataNodeConfig),
+    #[cfg(feature = "led-strip")]
+    LedStrip(LedStripNodeConfig),
+    #[cfg(feature = "level")]
     Level(LevelNodeConfig),
+    #[cfg(feature = "link")]
     Link(LinkNodeConfig),
+    #[cfg(feature = "lock")]
     Lock(LockNodeConfig),
+    #[cfg(feature = "maintenance")]
+    Maintenance(MaintenanceNodeConfig),
+    #[cfg(feature = "media-info")]
     MediaInfo(MediaInfoNodeConfig),
+    #[cfg(feature = "mediaplayer")]
     Mediaplayer(MediaplayerNodeConfig),
+    #[cfg(feature = "motion")]
+    Motion(MotionNodeConfig),
+    #[cfg(feature = "button")]
+    MultiButton(MultiButtonNodeConfig),
+    #[cfg(feature = "switch")]
+    MultiRelay(MultiRelayNodeConfig),
+    #[cfg(feature = "numeric-sensor")]
+    Numeric(NumericSensorNodeConfig),
+    #[cfg(feature = "orientation")]
+    Orientation(OrientationNodeConfig),
+    #[cfg(feature = "powermeter")]
     Powermeter(PowermeterNodeConfig),
+    #[cfg(feature = "scene")]
     Scene(SceneNodeConfig),
+    #[cfg(feature = "shutter")]
     Shutter(ShutterNodeConfig),
+    #[cfg(feature = "smoke")]
+    Smoke(SmokeNodeConfig),
+    #[cfg(feature = "string-sensor")]
+    StringSensor(StringSensorNodeConfig),
+    #[cfg(feature = "switch")]
     Switch(SwitchNodeConfig),
+    #[cfg(feature = "text")]
+    Text,
+    #[cfg(feature = "thermostat")]
     Thermostat(ThermostatNodeConfig),
+    #[cfg(feature = "tilt")]
+    Tilt(TiltNodeConfig),
+    #[cfg(feature = "timer")]
     Timer(TimerNodeConfig),
+    #[cfg(feature = "vacuum")]
+    Vacuum(VacuumNodeConfig),
+    #[cfg(feature = "valve")]
     Valve(ValveNodeConfig),
+    #[cfg(feature = "ventilation")]
+    Ventilation(VentilationNodeConfig),
+    #[cfg(feature = "vibration")]
     Vibration(VibrationNodeConfig),
+    #[cfg(feature = "volume")]
     Volume(VolumeNodeConfig),
+    #[cfg(feature = "water-sensor")]
+    #[serde(rename = "water")]
+    WaterSensor(WaterSensorNodeConfig),
+    #[cfg(feature = "weather")]
+    Weather(WeatherNodeConfig),
 }
 
+#[deprecated(note = "renamed to `SmarthomePropertyConfig`")]
+pub type SmarthomeProperyConfig = SmarthomePropertyConfig;
+
 #[derive(Debug)]
 pub enum SmarthomeNode {
+    #[cfg(feature = "air-purifier")]
+    AirPurifierNode(AirPurifierNode),
+    #[cfg(feature = "air-quality")]
     AirQualityNode(AirQualityNode),
+    #[cfg(feature = "alarm")]
     AlarmNode(AlarmNode),
+    #[cfg(feature = "awning")]
+    AwningNode(AwningNode),
+    #[cfg(feature = "battery")]
     BatteryNode(BatteryNode),
+    #[cfg(feature = "button")]
+    ButtonNode(ButtonNode),
+    #[cfg(feature = "camera")]
     CameraNode(CameraNode),
+    #[cfg(feature = "climate")]
     ClimateNode(ClimateNode),
+    #[cfg(feature = "co")]
     CoNode(CoNode),
+    #[cfg(feature = "color")]
     ColorNode(ColorNode),
+    #[cfg(feature = "contact")]
     ContactNode(ContactNode),
+    #[cfg(feature = "curtain")]
+    CurtainNode(CurtainNode),
+    #[cfg(feature = "daylight")]
     DaylightNode(DaylightNode),
+    #[cfg(feature = "dehumidifier")]
+    DehumidifierNode(DehumidifierNode),
+    #[cfg(feature = "doorbell")]
+    DoorbellNode(DoorbellNode),
+    #[cfg(feature = "door")]
+    DoorNode(DoorNode),
+    #[cfg(feature = "energy-dashboard")]
+    EnergyDashboardNode(EnergyDashboardNode),
+    #[cfg(feature = "ev-charger")]
+    EvChargerNode(EvChargerNode),
+    #[cfg(feature = "fan")]
+    FanNode(FanNode),
+    #[cfg(feature = "garage-door")]
     GarageDoorNode(GarageDoorNode),
+    #[cfg(feature = "gas")]
+    GasNode(GasNode),
+    #[cfg(feature = "generic-actuator")]
+    GenericActuatorNode(GenericActuatorNode),
+    #[cfg(feature = "heating-zone")]
+    HeatingZoneNode(HeatingZoneNode),
+    #[cfg(feature = "humidifier")]
+    HumidifierNode(HumidifierNode),
+    #[cfg(feature = "illuminance")]
     IlluminanceNode(IlluminanceNode),
+    #[cfg(feature = "irrigation")]
+    IrrigationNode(IrrigationNode),
+    #[cfg(feature = "json-data")]
+    JsonDataNode(JsonDataNode),
+    #[cfg(feature = "led-strip")]
+    LedStripNode(LedStripNode),
+    #[cfg(feature = "level")]
     LevelNode(LevelNode),
+    #[cfg(feature = "link")]
     LinkNode(LinkNode),
+    #[cfg(feature = "lock")]
     LockNode(LockNode),
+    #[cfg(feature = "maintenance")]
+    MaintenanceNode(MaintenanceNode),
+    #[cfg(feature = "media-info")]
     MediaInfoNode(MediaInfoNode),
+    #[cfg(feature = "mediaplayer")]
     MediaplayerNode(MediaplayerNode),
+    #[cfg(feature = "motion")]
     MotionNode(MotionNode),
+    #[cfg(feature = "button")]
+    MultiButtonNode(MultiButtonNode),
+    #[cfg(feature = "switch")]
+    MultiRelayNode(MultiRelayNode),
+    #[cfg(feature = "numeric-sensor")]
+    NumericSensorNode(NumericSensorNode),
+    #[cfg(feature = "orientation")]
     OrientationNode(OrientationNode),
+    #[cfg(feature = "powermeter")]
     Powermeter(PowermeterNode),
+    #[cfg(feature = "scene")]
+    SceneNode(SceneNode),
+    #[cfg(feature = "shutter")]
     ShutterNode(ShutterNode),
+    #[cfg(feature = "smoke")]
     SmokeNode(SmokeNode),
+    #[cfg(feature = "string-sensor")]
+    StringSensorNode(StringSensorNode),
+    #[cfg(feature = "switch")]
     SwitchNode(SwitchNode),
+    #[cfg(feature = "text")]
     TextNode(TextNode),
+    #[cfg(feature = "thermostat")]
     ThermostatNode(ThermostatNode),
+    #[cfg(feature = "tilt")]
     TiltNode(TiltNode),
+    #[cfg(feature = "timer")]
     TimerNode(TimerNode),
+    #[cfg(feature = "vacuum")]
+    VacuumNode(VacuumNode),
+    #[cfg(feature = "valve")]
     ValveNode(ValveNode),
+    #[cfg(feature = "ventilation")]
+    VentilationNode(VentilationNode),
+    #[cfg(feature = "vibration")]
     VibrationNode(VibrationNode),
+    #[cfg(feature = "volume")]
     VolumeNode(VolumeNode),
+    #[cfg(feature = "water-sensor")]
     WaterSensor(WaterSensorNode),
+    #[cfg(feature = "weather")]
+    WeatherNode(WeatherNode),
+}
+
+impl SmarthomeNode {
+    /// The publisher of the contained node as a type-erased [`SmarthomePublisher`].
+    pub fn publisher(&self) -> &dyn SmarthomePublisher {
+        match self {
+            #[cfg(feature = "air-purifier")]
+            SmarthomeNode::AirPurifierNode(node) => &node.publisher,
+            #[cfg(feature = "air-quality")]
+            SmarthomeNode::AirQualityNode(node) => &node.publisher,
+            #[cfg(feature = "alarm")]
+            SmarthomeNode::AlarmNode(node) => &node.publisher,
+            #[cfg(feature = "awning")]
+            SmarthomeNode::AwningNode(node) => &node.publisher,
+            #[cfg(feature = "battery")]
+            SmarthomeNode::BatteryNode(node) => &node.publisher,
+            #[cfg(feature = "button")]
+            SmarthomeNode::ButtonNode(node) => &node.publisher,
+            #[cfg(feature = "camera")]
+            SmarthomeNode::CameraNode(node) => &node.publisher,
+            #[cfg(feature = "climate")]
+            SmarthomeNode::ClimateNode(node) => &node.publisher,
+            #[cfg(feature = "co")]
+            SmarthomeNode::CoNode(node) => &node.publisher,
+            #[cfg(feature = "color")]
+            SmarthomeNode::ColorNode(node) => &node.publisher,
+            #[cfg(feature = "contact")]
+            SmarthomeNode::ContactNode(node) => &node.publisher,
+            #[cfg(feature = "curtain")]
+            SmarthomeNode::CurtainNode(node) => &node.publisher,
+            #[cfg(feature = "daylight")]
+            SmarthomeNode::DaylightNode(node) => &node.publisher,
+            #[cfg(feature = "dehumidifier")]
+            SmarthomeNode::DehumidifierNode(node) => &node.publisher,
+            #[cfg(feature = "doorbell")]
+            SmarthomeNode::DoorbellNode(node) => &node.publisher,
+            #[cfg(feature = "door")]
+            SmarthomeNode::DoorNode(node) => &node.publisher,
+            #[cfg(feature = "energy-dashboard")]
+            SmarthomeNode::EnergyDashboardNode(node) => &node.publisher,
+            #[cfg(feature = "ev-charger")]
+            SmarthomeNode::EvChargerNode(node) => &node.publisher,
+            #[cfg(feature = "fan")]
+            SmarthomeNode::FanNode(node) => &node.publisher,
+            #[cfg(feature = "garage-door")]
+            SmarthomeNode::GarageDoorNode(node) => &node.publisher,
+            #[cfg(feature = "gas")]
+            SmarthomeNode::GasNode(node) => &node.publisher,
+            #[cfg(feature = "generic-actuator")]
+            SmarthomeNode::GenericActuatorNode(node) => &node.publisher,
+            #[cfg(feature = "heating-zone")]
+            SmarthomeNode::HeatingZoneNode(node) => &node.publisher,
+            #[cfg(feature = "humidifier")]
+            SmarthomeNode::HumidifierNode(node) => &node.publisher,
+            #[cfg(feature = "illuminance")]
+            SmarthomeNode::IlluminanceNode(node) => &node.publisher,
+            #[cfg(feature = "irrigation")]
+            SmarthomeNode::IrrigationNode(node) => &node.publisher,
+            #[cfg(feature = "json-data")]
+            SmarthomeNode::JsonDataNode(node) => &node.publisher,
+            #[cfg(feature = "led-strip")]
+            SmarthomeNode::LedStripNode(node) => &node.publisher,
+            #[cfg(feature = "level")]
+            SmarthomeNode::LevelNode(node) => &node.publisher,
+            #[cfg(feature = "link")]
+            SmarthomeNode::LinkNode(node) => &node.publisher,
+            #[cfg(feature = "lock")]
+            SmarthomeNode::LockNode(node) => &node.publisher,
+            #[cfg(feature = "maintenance")]
+            SmarthomeNode::MaintenanceNode(node) => &node.publisher,
+            #[cfg(feature = "media-info")]
+            SmarthomeNode::MediaInfoNode(node) => &node.publisher,
+            #[cfg(feature = "mediaplayer")]
+            SmarthomeNode::MediaplayerNode(node) => &node.publisher,
+            #[cfg(feature = "motion")]
+            SmarthomeNode::MotionNode(node) => &node.publisher,
+            #[cfg(feature = "button")]
+            SmarthomeNode::MultiButtonNode(node) => &node.publisher,
+            #[cfg(feature = "switch")]
+            SmarthomeNode::MultiRelayNode(node) => &node.publisher,
+            #[cfg(feature = "numeric-sensor")]
+            SmarthomeNode::NumericSensorNode(node) => &node.publisher,
+            #[cfg(feature = "orientation")]
+            SmarthomeNode::OrientationNode(node) => &node.publisher,
+            #[cfg(feature = "powermeter")]
+            SmarthomeNode::Powermeter(node) => &node.publisher,
+            #[cfg(feature = "scene")]
+            SmarthomeNode::SceneNode(node) => &node.publisher,
+            #[cfg(feature = "shutter")]
+            SmarthomeNode::ShutterNode(node) => &node.publisher,
+            #[cfg(feature = "smoke")]
+            SmarthomeNode::SmokeNode(node) => &node.publisher,
+            #[cfg(feature = "string-sensor")]
+            SmarthomeNode::StringSensorNode(node) => &node.publisher,
+            #[cfg(feature = "switch")]
+            SmarthomeNode::SwitchNode(node) => &node.publisher,
+            #[cfg(feature = "text")]
+            SmarthomeNode::TextNode(node) => &node.publisher,
+            #[cfg(feature = "thermostat")]
+            SmarthomeNode::ThermostatNode(node) => &node.publisher,
+            #[cfg(feature = "tilt")]
+            SmarthomeNode::TiltNode(node) => &node.publisher,
+            #[cfg(feature = "timer")]
+            SmarthomeNode::TimerNode(node) => &node.publisher,
+            #[cfg(feature = "vacuum")]
+            SmarthomeNode::VacuumNode(node) => &node.publisher,
+            #[cfg(feature = "valve")]
+            SmarthomeNode::ValveNode(node) => &node.publisher,
+            #[cfg(feature = "ventilation")]
+            SmarthomeNode::VentilationNode(node) => &node.publisher,
+            #[cfg(feature = "vibration")]
+            SmarthomeNode::VibrationNode(node) => &node.publisher,
+            #[cfg(feature = "volume")]
+            SmarthomeNode::VolumeNode(node) => &node.publisher,
+            #[cfg(feature = "water-sensor")]
+            SmarthomeNode::WaterSensor(node) => &node.publisher,
+            #[cfg(feature = "weather")]
+            SmarthomeNode::WeatherNode(node) => &node.publisher,
+        }
+    }
+
+    pub fn smarthome_type(&self) -> SmarthomeType {
+        self.publisher().smarthome_type()
+    }
+
+    pub fn node_ref(&self) -> &NodeRef {
+        self.publisher().node_ref()
+    }
+
+    pub fn node_id(&self) -> &HomieID {
+        self.publisher().node_id()
+    }
+}
+
+macro_rules! impl_apply_value {
+    ($($feature:literal => $module:ident::$node:ident($state:ident),)*) => {$(
+        #[cfg(feature = $feature)]
+        impl ApplyValue for $module::$node {
+            type State = $module::$state;
+
+            fn state(&self) -> &Self::State {
+                &self.state
+            }
+
+            fn apply_value(&mut self, property: &HomieID, value: &HomieValue) -> Vec<Publish> {
+                $module::$node::apply_value(self, property, value)
+            }
+        }
+    )*};
+}
+
+impl_apply_value! {
+    "air-purifier" => air_purifier_node::AirPurifierNode(AirPurifierNodeState),
+    "air-quality" => air_quality_node::AirQualityNode(AirQualityNodeState),
+    "alarm" => alarm_node::AlarmNode(AlarmNodeState),
+    "awning" => awning_node::AwningNode(AwningNodeState),
+    "battery" => battery_node::BatteryNode(BatteryNodeState),
+    "button" => button_node::ButtonNode(ButtonNodeState),
+    "camera" => camera_node::CameraNode(CameraNodeState),
+    "climate" => climate_node::ClimateNode(ClimateNodeState),
+    "co" => co_node::CoNode(CoNodeState),
+    "color" => color_node::ColorNode(ColorNodeState),
+    "contact" => contact_node::ContactNode(ContactNodeState),
+    "curtain" => curtain_node::CurtainNode(CurtainNodeState),
+    "daylight" => daylight_node::DaylightNode(DaylightNodeState),
+    "dehumidifier" => dehumidifier_node::DehumidifierNode(DehumidifierNodeState),
+    "door" => door_node::DoorNode(DoorNodeState),
+    "doorbell" => doorbell_node::DoorbellNode(DoorbellNodeState),
+    "energy-dashboard" => energy_dashboard_node::EnergyDashboardNode(EnergyDashboardNodeState),
+    "ev-charger" => ev_charger_node::EvChargerNode(EvChargerNodeState),
+    "fan" => fan_node::FanNode(FanNodeState),
+    "garage-door" => garage_door_node::GarageDoorNode(GarageDoorNodeState),
+    "gas" => gas_node::GasNode(GasNodeState),
+    "generic-actuator" => generic_actuator_node::GenericActuatorNode(GenericActuatorNodeState),
+    "heating-zone" => heating_zone_node::HeatingZoneNode(HeatingZoneNodeState),
+    "humidifier" => humidifier_node::HumidifierNode(HumidifierNodeState),
+    "illuminance" => illuminance_node::IlluminanceNode(IlluminanceNodeState),
+    "irrigation" => irrigation_node::IrrigationNode(IrrigationNodeState),
+    "json-data" => json_data_node::JsonDataNode(JsonDataNodeState),
+    "led-strip" => led_strip_node::LedStripNode(LedStripNodeState),
+    "level" => level_node::LevelNode(LevelNodeState),
+    "link" => link_node::LinkNode(LinkNodeState),
+    "lock" => lock_node::LockNode(LockNodeState),
+    "maintenance" => maintenance_node::MaintenanceNode(MaintenanceNodeState),
+    "media-info" => media_info_node::MediaInfoNode(MediaInfoNodeState),
+    "mediaplayer" => mediaplayer_node::MediaplayerNode(MediaplayerNodeState),
+    "motion" => motion_node::MotionNode(MotionNodeState),
+    "numeric-sensor" => numeric_sensor_node::NumericSensorNode(NumericSensorNodeState),
+    "orientation" => orientation_node::OrientationNode(OrientationNodeState),
+    "powermeter" => powermeter_node::PowermeterNode(PowermeterNodeState),
+    "scene" => scene_node::SceneNode(SceneNodeState),
+    "shutter" => shutter_node::ShutterNode(ShutterNodeState),
+    "smoke" => smoke_node::SmokeNode(SmokeNodeState),
+    "string-sensor" => string_sensor_node::StringSensorNode(StringSensorNodeState),
+    "switch" => switch_node::SwitchNode(SwitchNodeState),
+    "switch" => switch_node::MultiRelayNode(MultiRelayNodeState),
+    "text" => text_node::TextNode(TextNodeState),
+    "thermostat" => thermostat_node::ThermostatNode(ThermostatNodeState),
+    "tilt" => tilt_node::TiltNode(TiltNodeState),
+    "timer" => timer_node::TimerNode(TimerNodeState),
+    "vacuum" => vacuum_node::VacuumNode(VacuumNodeState),
+    "valve" => valve_node::ValveNode(ValveNodeState),
+    "ventilation" => ventilation_node::VentilationNode(VentilationNodeState),
+    "vibration" => vibration_node::VibrationNode(VibrationNodeState),
+    "volume" => volume_node::VolumeNode(VolumeNodeState),
+    "water-sensor" => water_sensor_node::WaterSensorNode(WaterSensorNodeState),
+    "weather" => weather_node::WeatherNode(WeatherNodeState),
 }
 
 // ── Tests ───────────────────────────────────────────────────────────────────
@@ -536,104 +1940,324 @@ mod parse_outcome_tests {
 
 #[cfg(test)]
 mod config_serde_default_tests {
+    // Unused when only nodes without a config struct are enabled.
+    #[allow(unused_imports)]
     use super::*;
 
     #[test]
     fn empty_object_deserializes_to_default_for_all_node_configs() {
+        #[cfg(feature = "switch")]
         let switch: SwitchNodeConfig =
             serde_json::from_str("{}").expect("switch config must deserialize");
+        #[cfg(feature = "switch")]
         assert_eq!(switch, SwitchNodeConfig::default());
 
+        #[cfg(feature = "level")]
         let level: LevelNodeConfig =
             serde_json::from_str("{}").expect("level config must deserialize");
+        #[cfg(feature = "level")]
         assert_eq!(level, LevelNodeConfig::default());
 
+        #[cfg(feature = "shutter")]
         let shutter: ShutterNodeConfig =
             serde_json::from_str("{}").expect("shutter config must deserialize");
+        #[cfg(feature = "shutter")]
         assert_eq!(shutter, ShutterNodeConfig::default());
 
+        #[cfg(feature = "color")]
         let color: ColorNodeConfig =
             serde_json::from_str("{}").expect("color config must deserialize");
+        #[cfg(feature = "color")]
         assert_eq!(color, ColorNodeConfig::default());
 
+        #[cfg(feature = "scene")]
         let scene: SceneNodeConfig =
             serde_json::from_str("{}").expect("scene config must deserialize");
+        #[cfg(feature = "scene")]
         assert_eq!(scene, SceneNodeConfig::default());
 
+        #[cfg(feature = "thermostat")]
         let thermostat: ThermostatNodeConfig =
             serde_json::from_str("{}").expect("thermostat config must deserialize");
+        #[cfg(feature = "thermostat")]
         assert_eq!(thermostat, ThermostatNodeConfig::default());
 
+        #[cfg(feature = "climate")]
         let climate: ClimateNodeConfig =
             serde_json::from_str("{}").expect("climate config must deserialize");
+        #[cfg(feature = "climate")]
         assert_eq!(climate, ClimateNodeConfig::default());
 
+        #[cfg(feature = "vibration")]
         let vibration: VibrationNodeConfig =
             serde_json::from_str("{}").expect("vibration config must deserialize");
+        #[cfg(feature = "vibration")]
         assert_eq!(vibration, VibrationNodeConfig::default());
 
+        #[cfg(feature = "contact")]
+        let contact: ContactNodeConfig =
+            serde_json::from_str("{}").expect("contact config must deserialize");
+        #[cfg(feature = "contact")]
+        assert_eq!(contact, ContactNodeConfig::default());
+
+        #[cfg(feature = "tilt")]
+        let tilt: TiltNodeConfig =
+            serde_json::from_str("{}").expect("tilt config must deserialize");
+        #[cfg(feature = "tilt")]
+        assert_eq!(tilt, TiltNodeConfig::default());
+
+        #[cfg(feature = "water-sensor")]
+        let water: WaterSensorNodeConfig =
+            serde_json::from_str("{}").expect("water sensor config must deserialize");
+        #[cfg(feature = "water-sensor")]
+        assert_eq!(water, WaterSensorNodeConfig::default());
+
+        #[cfg(feature = "orientation")]
+        let orientation: OrientationNodeConfig =
+            serde_json::from_str("{}").expect("orientation config must deserialize");
+        #[cfg(feature = "orientation")]
+        assert_eq!(orientation, OrientationNodeConfig::default());
+
+        #[cfg(feature = "motion")]
+        let motion: MotionNodeConfig =
+            serde_json::from_str("{}").expect("motion config must deserialize");
+        #[cfg(feature = "motion")]
+        assert_eq!(motion, MotionNodeConfig::default());
+
+        #[cfg(feature = "battery")]
         let battery: BatteryNodeConfig =
             serde_json::from_str("{}").expect("battery config must deserialize");
+        #[cfg(feature = "battery")]
         assert_eq!(battery, BatteryNodeConfig::default());
 
+        #[cfg(feature = "link")]
         let link: LinkNodeConfig =
             serde_json::from_str("{}").expect("link config must deserialize");
+        #[cfg(feature = "link")]
         assert_eq!(link, LinkNodeConfig::default());
 
+        #[cfg(feature = "maintenance")]
+        let maintenance: MaintenanceNodeConfig =
+            serde_json::from_str("{}").expect("maintenance config must deserialize");
+        #[cfg(feature = "maintenance")]
+        assert_eq!(maintenance, MaintenanceNodeConfig::default());
+
+        #[cfg(feature = "button")]
         let button: ButtonNodeConfig =
             serde_json::from_str("{}").expect("button config must deserialize");
+        #[cfg(feature = "button")]
         assert_eq!(button, ButtonNodeConfig::default());
 
+        #[cfg(feature = "powermeter")]
         let powermeter: PowermeterNodeConfig =
             serde_json::from_str("{}").expect("powermeter config must deserialize");
+        #[cfg(feature = "powermeter")]
         assert_eq!(powermeter, PowermeterNodeConfig::default());
 
+        #[cfg(feature = "lock")]
         let lock: LockNodeConfig =
             serde_json::from_str("{}").expect("lock config must deserialize");
+        #[cfg(feature = "lock")]
         assert_eq!(lock, LockNodeConfig::default());
 
+        #[cfg(feature = "valve")]
         let valve: ValveNodeConfig =
             serde_json::from_str("{}").expect("valve config must deserialize");
+        #[cfg(feature = "valve")]
         assert_eq!(valve, ValveNodeConfig::default());
 
+        #[cfg(feature = "mediaplayer")]
         let mediaplayer: MediaplayerNodeConfig =
             serde_json::from_str("{}").expect("mediaplayer config must deserialize");
+        #[cfg(feature = "mediaplayer")]
         assert_eq!(mediaplayer, MediaplayerNodeConfig::default());
 
+        #[cfg(feature = "media-info")]
         let media_info: MediaInfoNodeConfig =
             serde_json::from_str("{}").expect("media-info config must deserialize");
+        #[cfg(feature = "media-info")]
         assert_eq!(media_info, MediaInfoNodeConfig::default());
 
+        #[cfg(feature = "volume")]
         let volume: VolumeNodeConfig =
             serde_json::from_str("{}").expect("volume config must deserialize");
+        #[cfg(feature = "volume")]
         assert_eq!(volume, VolumeNodeConfig::default());
 
+        #[cfg(feature = "alarm")]
         let alarm: AlarmNodeConfig =
             serde_json::from_str("{}").expect("alarm config must deserialize");
+        #[cfg(feature = "alarm")]
         assert_eq!(alarm, AlarmNodeConfig::default());
 
+        #[cfg(feature = "daylight")]
         let daylight: DaylightNodeConfig =
             serde_json::from_str("{}").expect("daylight config must deserialize");
+        #[cfg(feature = "daylight")]
         assert_eq!(daylight, DaylightNodeConfig::default());
 
+        #[cfg(feature = "weather")]
+        let weather: WeatherNodeConfig =
+            serde_json::from_str("{}").expect("weather config must deserialize");
+        #[cfg(feature = "weather")]
+        assert_eq!(weather, WeatherNodeConfig::default());
+
+        #[cfg(feature = "air-quality")]
         let air_quality: AirQualityNodeConfig =
             serde_json::from_str("{}").expect("air-quality config must deserialize");
+        #[cfg(feature = "air-quality")]
         assert_eq!(air_quality, AirQualityNodeConfig::default());
 
+        #[cfg(feature = "garage-door")]
         let garage_door: GarageDoorNodeConfig =
             serde_json::from_str("{}").expect("garage-door config must deserialize");
+        #[cfg(feature = "garage-door")]
         assert_eq!(garage_door, GarageDoorNodeConfig::default());
 
+        #[cfg(feature = "camera")]
         let camera: CameraNodeConfig =
             serde_json::from_str("{}").expect("camera config must deserialize");
+        #[cfg(feature = "camera")]
         assert_eq!(camera, CameraNodeConfig::default());
 
+        #[cfg(feature = "timer")]
         let timer: TimerNodeConfig =
             serde_json::from_str("{}").expect("timer config must deserialize");
+        #[cfg(feature = "timer")]
         assert_eq!(timer, TimerNodeConfig::default());
+
+        #[cfg(feature = "numeric-sensor")]
+        let numeric: NumericSensorNodeConfig =
+            serde_json::from_str("{}").expect("numeric config must deserialize");
+        #[cfg(feature = "numeric-sensor")]
+        assert_eq!(numeric, NumericSensorNodeConfig::default());
+
+        #[cfg(feature = "smoke")]
+        let smoke: SmokeNodeConfig =
+            serde_json::from_str("{}").expect("smoke config must deserialize");
+        #[cfg(feature = "smoke")]
+        assert_eq!(smoke, SmokeNodeConfig::default());
+
+        #[cfg(feature = "co")]
+        let co: CoNodeConfig = serde_json::from_str("{}").expect("co config must deserialize");
+        #[cfg(feature = "co")]
+        assert_eq!(co, CoNodeConfig::default());
+
+        #[cfg(feature = "doorbell")]
+        let doorbell: DoorbellNodeConfig =
+            serde_json::from_str("{}").expect("doorbell config must deserialize");
+        #[cfg(feature = "doorbell")]
+        assert_eq!(doorbell, DoorbellNodeConfig::default());
+
+        #[cfg(feature = "gas")]
+        let gas: GasNodeConfig = serde_json::from_str("{}").expect("gas config must deserialize");
+        #[cfg(feature = "gas")]
+        assert_eq!(gas, GasNodeConfig::default());
+
+        #[cfg(feature = "fan")]
+        let fan: FanNodeConfig = serde_json::from_str("{}").expect("fan config must deserialize");
+        #[cfg(feature = "fan")]
+        assert_eq!(fan, FanNodeConfig::default());
+
+        #[cfg(feature = "vacuum")]
+        let vacuum: VacuumNodeConfig =
+            serde_json::from_str("{}").expect("vacuum config must deserialize");
+        #[cfg(feature = "vacuum")]
+        assert_eq!(vacuum, VacuumNodeConfig::default());
+
+        #[cfg(feature = "ev-charger")]
+        let ev_charger: EvChargerNodeConfig =
+            serde_json::from_str("{}").expect("ev-charger config must deserialize");
+        #[cfg(feature = "ev-charger")]
+        assert_eq!(ev_charger, EvChargerNodeConfig::default());
+
+        #[cfg(feature = "irrigation")]
+        let irrigation: IrrigationNodeConfig =
+            serde_json::from_str("{}").expect("irrigation config must deserialize");
+        #[cfg(feature = "irrigation")]
+        assert_eq!(irrigation, IrrigationNodeConfig::default());
+
+        #[cfg(feature = "humidifier")]
+        let humidifier: HumidifierNodeConfig =
+            serde_json::from_str("{}").expect("humidifier config must deserialize");
+        #[cfg(feature = "humidifier")]
+        assert_eq!(humidifier, HumidifierNodeConfig::default());
+
+        #[cfg(feature = "dehumidifier")]
+        let dehumidifier: DehumidifierNodeConfig =
+            serde_json::from_str("{}").expect("dehumidifier config must deserialize");
+        #[cfg(feature = "dehumidifier")]
+        assert_eq!(dehumidifier, DehumidifierNodeConfig::default());
+
+        #[cfg(feature = "ventilation")]
+        let ventilation: VentilationNodeConfig =
+            serde_json::from_str("{}").expect("ventilation config must deserialize");
+        #[cfg(feature = "ventilation")]
+        assert_eq!(ventilation, VentilationNodeConfig::default());
+
+        #[cfg(feature = "air-purifier")]
+        let air_purifier: AirPurifierNodeConfig =
+            serde_json::from_str("{}").expect("air-purifier config must deserialize");
+        #[cfg(feature = "air-purifier")]
+        assert_eq!(air_purifier, AirPurifierNodeConfig::default());
+
+        #[cfg(feature = "heating-zone")]
+        let heating_zone: HeatingZoneNodeConfig =
+            serde_json::from_str("{}").expect("heating-zone config must deserialize");
+        #[cfg(feature = "heating-zone")]
+        assert_eq!(heating_zone, HeatingZoneNodeConfig::default());
+
+        #[cfg(feature = "energy-dashboard")]
+        let energy_dashboard: EnergyDashboardNodeConfig =
+            serde_json::from_str("{}").expect("energy-dashboard config must deserialize");
+        #[cfg(feature = "energy-dashboard")]
+        assert_eq!(energy_dashboard, EnergyDashboardNodeConfig::default());
+
+        #[cfg(feature = "led-strip")]
+        let led_strip: LedStripNodeConfig =
+            serde_json::from_str("{}").expect("led-strip config must deserialize");
+        #[cfg(feature = "led-strip")]
+        assert_eq!(led_strip, LedStripNodeConfig::default());
+
+        #[cfg(feature = "door")]
+        let door: DoorNodeConfig =
+            serde_json::from_str("{}").expect("door config must deserialize");
+        #[cfg(feature = "door")]
+        assert_eq!(door, DoorNodeConfig::default());
+
+        #[cfg(feature = "curtain")]
+        let curtain: CurtainNodeConfig =
+            serde_json::from_str("{}").expect("curtain config must deserialize");
+        #[cfg(feature = "curtain")]
+        assert_eq!(curtain, CurtainNodeConfig::default());
+
+        #[cfg(feature = "awning")]
+        let awning: AwningNodeConfig =
+            serde_json::from_str("{}").expect("awning config must deserialize");
+        #[cfg(feature = "awning")]
+        assert_eq!(awning, AwningNodeConfig::default());
+
+        #[cfg(feature = "generic-actuator")]
+        let generic_actuator: GenericActuatorNodeConfig =
+            serde_json::from_str("{}").expect("generic-actuator config must deserialize");
+        #[cfg(feature = "generic-actuator")]
+        assert_eq!(generic_actuator, GenericActuatorNodeConfig::default());
+
+        #[cfg(feature = "string-sensor")]
+        let string_sensor: StringSensorNodeConfig =
+            serde_json::from_str("{}").expect("string-sensor config must deserialize");
+        #[cfg(feature = "string-sensor")]
+        assert_eq!(string_sensor, StringSensorNodeConfig::default());
+
+        #[cfg(feature = "json-data")]
+        let json_data: JsonDataNodeConfig =
+            serde_json::from_str("{}").expect("json-data config must deserialize");
+        #[cfg(feature = "json-data")]
+        assert_eq!(json_data, JsonDataNodeConfig::default());
     }
 
     #[test]
+    #[cfg(all(feature = "scene", feature = "thermostat"))]
     fn partial_config_deserialization_keeps_defaults_for_missing_fields() {
         let thermostat: ThermostatNodeConfig = serde_json::from_str(r#"{"unit":"F"}"#)
             .expect("thermostat partial config must deserialize");
@@ -650,13 +2274,75 @@ mod config_serde_default_tests {
         assert_eq!(
             scene,
             SceneNodeConfig {
-                scenes: vec!["scene-a".to_string()],
+                scenes: vec!["scene-a".into()],
                 ..SceneNodeConfig::default()
             }
         );
     }
 }
 
+#[cfg(test)]
+mod property_config_serde_tests {
+    use super::*;
+
+    #[test]
+    #[cfg(all(
+        feature = "switch",
+        feature = "water-sensor",
+        feature = "garage-door",
+        feature = "scene"
+    ))]
+    fn deserializes_tagged_configs() {
+        let configs: Vec<SmarthomePropertyConfig> = serde_json::from_str(
+            r#"[
+                {"type": "switch"},
+                {"type": "water", "alarm_level": true},
+                {"type": "garage-door"},
+                {"type": "scene", "scenes": ["evening", {"id": "night", "label": "Night"}]}
+            ]"#,
+        )
+        .expect("tagged configs must deserialize");
+
+        assert_eq!(
+            configs,
+            vec![
+                SmarthomePropertyConfig::Switch(SwitchNodeConfig::default()),
+                SmarthomePropertyConfig::WaterSensor(WaterSensorNodeConfig {
+                    alarm_level: true,
+                    ..WaterSensorNodeConfig::default()
+                }),
+                SmarthomePropertyConfig::GarageDoor(GarageDoorNodeConfig::default()),
+                SmarthomePropertyConfig::Scene(SceneNodeConfig {
+                    scenes: vec![
+                        "evening".into(),
+                        scene_node::SceneNodeScene::new("night", "Night")
+                    ],
+                    ..SceneNodeConfig::default()
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "numeric-sensor")]
+    fn serialization_round_trips() {
+        let config = SmarthomePropertyConfig::Numeric(NumericSensorNodeConfig::default());
+        let json = serde_json::to_value(&config).expect("serialize config");
+        assert_eq!(json["type"], "numeric");
+
+        let parsed: SmarthomePropertyConfig =
+            serde_json::from_value(json).expect("deserialize config");
+        assert_eq!(parsed, config);
+    }
+
+    #[test]
+    fn rejects_unknown_type() {
+        let err = serde_json::from_str::<SmarthomePropertyConfig>(r#"{"type": "toaster"}"#)
+            .expect_err("must reject unknown capability");
+        assert!(err.to_string().contains("toaster"));
+    }
+}
+
 #[cfg(test)]
 mod smarthome_type_serde_tests {
     use super::*;
@@ -664,38 +2350,114 @@ mod smarthome_type_serde_tests {
     #[test]
     fn serializes_and_deserializes_canonical_constants() {
         let types = [
+            #[cfg(feature = "switch")]
             SmarthomeType::Switch,
+            #[cfg(feature = "level")]
             SmarthomeType::Level,
+            #[cfg(feature = "contact")]
             SmarthomeType::Contact,
+            #[cfg(feature = "climate")]
             SmarthomeType::Climate,
+            #[cfg(feature = "motion")]
             SmarthomeType::Motion,
+            #[cfg(feature = "button")]
             SmarthomeType::Button,
+            #[cfg(feature = "color")]
             SmarthomeType::Color,
+            #[cfg(feature = "scene")]
             SmarthomeType::Scene,
+            #[cfg(feature = "vibration")]
             SmarthomeType::Vibration,
+            #[cfg(feature = "orientation")]
             SmarthomeType::Orientation,
+            #[cfg(feature = "water-sensor")]
             SmarthomeType::WaterSensor,
+            #[cfg(feature = "shutter")]
             SmarthomeType::Shutter,
+            #[cfg(feature = "tilt")]
             SmarthomeType::Tilt,
+            #[cfg(feature = "thermostat")]
             SmarthomeType::Thermostat,
+            #[cfg(feature = "powermeter")]
             SmarthomeType::Powermeter,
+            #[cfg(feature = "lock")]
             SmarthomeType::Lock,
+            #[cfg(feature = "valve")]
             SmarthomeType::Valve,
+            #[cfg(feature = "battery")]
             SmarthomeType::Battery,
+            #[cfg(feature = "link")]
             SmarthomeType::Link,
+            #[cfg(feature = "maintenance")]
+            SmarthomeType::Maintenance,
+            #[cfg(feature = "mediaplayer")]
             SmarthomeType::Mediaplayer,
+            #[cfg(feature = "media-info")]
             SmarthomeType::MediaInfo,
+            #[cfg(feature = "volume")]
             SmarthomeType::Volume,
+            #[cfg(feature = "smoke")]
             SmarthomeType::Smoke,
+            #[cfg(feature = "co")]
             SmarthomeType::Co,
+            #[cfg(feature = "alarm")]
             SmarthomeType::Alarm,
+            #[cfg(feature = "illuminance")]
             SmarthomeType::Illuminance,
+            #[cfg(feature = "daylight")]
             SmarthomeType::Daylight,
+            #[cfg(feature = "weather")]
+            SmarthomeType::Weather,
+            #[cfg(feature = "garage-door")]
             SmarthomeType::GarageDoor,
+            #[cfg(feature = "air-quality")]
             SmarthomeType::AirQuality,
+            #[cfg(feature = "camera")]
             SmarthomeType::Camera,
+            #[cfg(feature = "timer")]
             SmarthomeType::Timer,
+            #[cfg(feature = "text")]
             SmarthomeType::Text,
+            #[cfg(feature = "numeric-sensor")]
+            SmarthomeType::Numeric,
+            #[cfg(feature = "doorbell")]
+            SmarthomeType::Doorbell,
+            #[cfg(feature = "gas")]
+            SmarthomeType::Gas,
+            #[cfg(feature = "fan")]
+            SmarthomeType::Fan,
+            #[cfg(feature = "vacuum")]
+            SmarthomeType::Vacuum,
+            #[cfg(feature = "ev-charger")]
+            SmarthomeType::EvCharger,
+            #[cfg(feature = "irrigation")]
+            SmarthomeType::Irrigation,
+            #[cfg(feature = "humidifier")]
+            SmarthomeType::Humidifier,
+            #[cfg(feature = "dehumidifier")]
+            SmarthomeType::Dehumidifier,
+            #[cfg(feature = "ventilation")]
+            SmarthomeType::Ventilation,
+            #[cfg(feature = "air-purifier")]
+            SmarthomeType::AirPurifier,
+            #[cfg(feature = "heating-zone")]
+            SmarthomeType::HeatingZone,
+            #[cfg(feature = "energy-dashboard")]
+            SmarthomeType::EnergyDashboard,
+            #[cfg(feature = "led-strip")]
+            SmarthomeType::LedStrip,
+            #[cfg(feature = "door")]
+            SmarthomeType::Door,
+            #[cfg(feature = "curtain")]
+            SmarthomeType::Curtain,
+            #[cfg(feature = "awning")]
+            SmarthomeType::Awning,
+            #[cfg(feature = "generic-actuator")]
+            SmarthomeType::GenericActuator,
+            #[cfg(feature = "string-sensor")]
+            SmarthomeType::StringSensor,
+            #[cfg(feature = "json-data")]
+            SmarthomeType::JsonData,
         ];
 
         for ty in types {
@@ -714,9 +2476,205 @@ mod smarthome_type_serde_tests {
             .expect_err("must reject short name");
         assert!(err.to_string().contains("invalid smarthome type"));
     }
+
+    #[test]
+    #[cfg(all(feature = "contact", feature = "air-quality"))]
+    fn classifies_subtype_suffixes() {
+        let window = SmarthomeType::Contact.with_subtype("window");
+        assert_eq!(window, "hc-smarthome/v2/cap/contact-window");
+        assert_eq!(
+            SmarthomeType::from_type_with_subtype(&window),
+            Some((SmarthomeType::Contact, Some("window")))
+        );
+        assert_eq!(
+            SmarthomeType::from_type_with_subtype("hc-smarthome/v2/cap/contact-sliding-door"),
+            Some((SmarthomeType::Contact, Some("sliding-door")))
+        );
+        assert_eq!(
+            SmarthomeType::from_type_with_subtype(SMARTHOME_CAP_AIR_QUALITY),
+            Some((SmarthomeType::AirQuality, None))
+        );
+        assert_eq!(
+            SmarthomeType::from_type_with_subtype("hc-smarthome/v2/cap/air-quality-outdoor"),
+            Some((SmarthomeType::AirQuality, Some("outdoor")))
+        );
+        assert_eq!(
+            SmarthomeType::from_type_with_subtype("hc-smarthome/v2/cap/contact-"),
+            None
+        );
+        assert_eq!(window.parse(), Ok(SmarthomeType::Contact));
+        assert_eq!(
+            serde_json::from_str::<SmarthomeType>(&format!("\"{window}\"")).unwrap(),
+            SmarthomeType::Contact
+        );
+    }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "level"))]
+mod standalone_set_parser_tests {
+    use homie5::{Homie5DeviceProtocol, HomieDomain};
+
+    use super::*;
+    use crate::level_node::{
+        LEVEL_NODE_DEFAULT_ID, LEVEL_NODE_VALUE_PROP_ID, LevelNodeBuilder, LevelNodeSetEvents,
+    };
+
+    #[test]
+    fn parses_against_own_node_description() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let parser: StandaloneSetParser<_> = LevelNodeBuilder::new(&LevelNodeConfig::default())
+            .build_with_publisher(LEVEL_NODE_DEFAULT_ID, &client)
+            .into();
+        let property = PropertyRef::from_node(
+            parser.publisher().node_ref().clone(),
+            LEVEL_NODE_VALUE_PROP_ID,
+        );
+
+        assert!(matches!(
+            parser.parse_set(&property, "42"),
+            ParseOutcome::Parsed(LevelNodeSetEvents::Value(42))
+        ));
+        assert!(matches!(
+            parser.parse_set(&property, "142"),
+            ParseOutcome::Invalid(_)
+        ));
+        let event = Homie5Message::PropertySet {
+            property,
+            set_value: "7".to_string(),
+        };
+        assert!(matches!(
+            parser.parse_set_event(&event),
+            ParseOutcome::Parsed(LevelNodeSetEvents::Value(7))
+        ));
+    }
+
+    #[test]
+    fn subscribes_to_settable_properties_of_own_node() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let parser: StandaloneSetParser<_> = LevelNodeBuilder::new(&LevelNodeConfig::default())
+            .build_with_publisher(LEVEL_NODE_DEFAULT_ID, &client)
+            .into();
+        let publisher = parser.publisher();
+
+        assert!(publisher.property_ids().contains(&LEVEL_NODE_VALUE_PROP_ID));
+        assert_eq!(
+            publisher.property_ref(LEVEL_NODE_VALUE_PROP_ID).node_id(),
+            &LEVEL_NODE_DEFAULT_ID
+        );
+        let topics: Vec<_> = parser
+            .subscriptions()
+            .into_iter()
+            .map(|s| s.topic)
+            .collect();
+        assert!(topics.contains(&"homie/5/dev/level/value/set".to_string()));
+    }
+}
+
+#[cfg(all(test, feature = "button", feature = "scene", feature = "switch"))]
+mod smarthome_node_tests {
+    use homie5::{Homie5DeviceProtocol, HomieDomain};
+
+    use super::*;
+    use crate::{
+        button_node::{BUTTON_NODE_DEFAULT_ID, ButtonNodeBuilder, ButtonNodeState},
+        scene_node::{SCENE_NODE_DEFAULT_ID, SceneNodeBuilder, SceneNodeState},
+        switch_node::{SWITCH_NODE_DEFAULT_ID, SwitchNodeBuilder, SwitchNodeState},
+    };
+
+    #[test]
+    fn heterogeneous_nodes_expose_type_and_id() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+
+        let (_, publisher) = SwitchNodeBuilder::new(&SwitchNodeConfig::default())
+            .build_with_publisher(SWITCH_NODE_DEFAULT_ID, &client);
+        let switch = SmarthomeNode::SwitchNode(SwitchNode {
+            publisher,
+            state: SwitchNodeState::default(),
+        });
+        let (_, publisher) =
+            ButtonNodeBuilder::default().build_with_publisher(BUTTON_NODE_DEFAULT_ID, &client);
+        let button = SmarthomeNode::ButtonNode(ButtonNode {
+            publisher,
+            state: ButtonNodeState::default(),
+        });
+        let scene_config = SceneNodeConfig {
+            scenes: vec!["evening".into()],
+            ..SceneNodeConfig::default()
+        };
+        let (_, publisher) = SceneNodeBuilder::new(&scene_config)
+            .build_with_publisher(SCENE_NODE_DEFAULT_ID, &client);
+        let scene = SmarthomeNode::SceneNode(SceneNode {
+            publisher,
+            state: SceneNodeState::default(),
+        });
+
+        let nodes = [switch, button, scene];
+        let summary: Vec<_> = nodes
+            .iter()
+            .map(|node| (node.smarthome_type(), node.node_id().clone()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (SmarthomeType::Switch, SWITCH_NODE_DEFAULT_ID),
+                (SmarthomeType::Button, BUTTON_NODE_DEFAULT_ID),
+                (SmarthomeType::Scene, SCENE_NODE_DEFAULT_ID),
+            ]
+        );
+        assert_eq!(nodes[0].node_ref().device_id(), &HomieID::new_const("dev"));
+    }
+}
+
+#[cfg(all(test, feature = "switch"))]
+mod apply_value_tests {
+    use homie5::{Homie5DeviceProtocol, HomieDomain};
+
+    use super::*;
+    use crate::switch_node::{
+        SWITCH_NODE_DEFAULT_ID, SWITCH_NODE_STATE_PROP_ID, SwitchNodeBuilder, SwitchNodeState,
+    };
+
+    #[test]
+    fn bulk_updates_publish_only_changed_values() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let (desc, publisher) = SwitchNodeBuilder::new(&SwitchNodeConfig::default())
+            .build_with_publisher(SWITCH_NODE_DEFAULT_ID, &client);
+        let mut node = SwitchNode {
+            publisher,
+            state: SwitchNodeState::default(),
+        };
+
+        let values = values_from_json(
+            &desc,
+            &serde_json::json!({"state": false, "unknown": 1, "impulse-duration": null}),
+        );
+        assert_eq!(
+            values,
+            vec![(SWITCH_NODE_STATE_PROP_ID, HomieValue::Bool(false))]
+        );
+        let publishes = node.apply_values(values.iter().map(|(id, value)| (id, value)));
+        assert!(publishes.is_empty());
+
+        let values = values_from_json(&desc, &serde_json::json!({"state": "true"}));
+        let publishes = node.apply_values(values.iter().map(|(id, value)| (id, value)));
+        assert_eq!(publishes.len(), 1);
+        assert_eq!(publishes[0].topic, "homie/5/dev/switch/state");
+        assert!(node.state().state);
+    }
+}
+
+#[cfg(all(
+    test,
+    feature = "battery",
+    feature = "climate",
+    feature = "level",
+    feature = "link",
+    feature = "switch"
+))]
 mod tests {
     use rumqttc::{AsyncClient, ClientError};
     use std::{env, time::Duration};
@@ -881,7 +2839,7 @@ mod tests {
             .build_with_publisher(SWITCH_NODE_DEFAULT_ID, &client);
 
         let (switch_node2, switch_node_publisher2) = SwitchNodeBuilder::new(&Default::default())
-            .build_with_publisher("switch2".try_into().unwrap(), &client);
+            .build_with_publisher(HomieID::new_const("switch2"), &client);
 
         let (level_node, level_node_publisher) = LevelNodeBuilder::new(&Default::default())
             .build_with_publisher(LEVEL_NODE_DEFAULT_ID, &client);
@@ -896,7 +2854,7 @@ mod tests {
             .add_node(SWITCH_NODE_DEFAULT_ID, switch_node)
             .add_node(LEVEL_NODE_DEFAULT_ID, level_node)
             .add_node(CLIMATE_NODE_DEFAULT_ID, climate_node)
-            .add_node("switch2".try_into().unwrap(), switch_node2)
+            .add_node(HomieID::new_const("switch2"), switch_node2)
             .build();
 
         loop {
@@ -953,8 +2911,11 @@ mod tests {
                                             )
                                             .await;
                                         }
+                                        SwitchNodeActions::Impulse => {}
                                     }
                                 }
+                                SwitchNodeSetEvents::ImpulseDuration(_)
+                                | SwitchNodeSetEvents::Led(_) => {}
                             }
                         }
                         if let Some(switch_node_event) =
@@ -996,8 +2957,11 @@ mod tests {
                                             )
                                             .await;
                                         }
+                                        SwitchNodeActions::Impulse => {}
                                     }
                                 }
+                                SwitchNodeSetEvents::ImpulseDuration(_)
+                                | SwitchNodeSetEvents::Led(_) => {}
                             }
                         }
                         if let Some(level_node_event) =
@@ -1,14 +1,19 @@
 use chrono::prelude::*;
 
+use std::collections::BTreeMap;
+
 use homie5::{
-    Homie5DeviceProtocol, HomieID, NodeRef,
+    Homie5DeviceProtocol, HomieID, HomieValue, NodeRef,
     device_description::{
         HomieNodeDescription, IntegerRange, NodeDescriptionBuilder, PropertyDescriptionBuilder,
     },
 };
 use serde::{Deserialize, Serialize};
 
-use crate::SMARTHOME_CAP_LINK;
+use crate::{
+    PropertyMetadata, SMARTHOME_CAP_LINK, SmarthomePublisher, SmarthomeType, property_metadata,
+    smarthome_node_config,
+};
 
 pub const LINK_NODE_DEFAULT_ID: HomieID = HomieID::new_const("link");
 pub const LINK_NODE_DEFAULT_NAME: &str = "Link quality";
@@ -16,20 +21,91 @@ pub const LINK_NODE_SIGNAL_PROP_ID: HomieID = HomieID::new_const("signal");
 pub const LINK_NODE_QUALITY_PROP_ID: HomieID = HomieID::new_const("quality");
 pub const LINK_NODE_LAST_SEEN_PROP_ID: HomieID = HomieID::new_const("last-seen");
 
-#[derive(Debug)]
-pub struct LinkNode {
-    pub publisher: LinkNodePublisher,
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LinkNodeState {
     pub signal: Option<i64>,
     pub quality: Option<i64>,
     pub last_seen: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
-#[serde(default)]
-pub struct LinkNodeConfig {
-    pub signal: bool,
-    pub quality: bool,
-    pub last_seen: bool,
+#[derive(Debug)]
+pub struct LinkNode {
+    pub publisher: LinkNodePublisher,
+    pub state: LinkNodeState,
+}
+
+impl LinkNode {
+    /// Apply a value reported by the device and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Integer(value)) if p == &LINK_NODE_SIGNAL_PROP_ID => {
+                self.state.signal = Some(*value);
+                self.publisher.signal(*value).into_iter().collect()
+            }
+            (p, HomieValue::Integer(value)) if p == &LINK_NODE_QUALITY_PROP_ID => {
+                self.state.quality = Some(*value);
+                self.publisher.quality(*value).into_iter().collect()
+            }
+            (p, HomieValue::DateTime(value)) if p == &LINK_NODE_LAST_SEEN_PROP_ID => {
+                self.state.last_seen = Some(*value);
+                self.publisher.last_seen(*value).into_iter().collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            self.state
+                .signal
+                .and_then(|value| self.publisher.signal(value)),
+            self.state
+                .quality
+                .and_then(|value| self.publisher.quality(value)),
+            self.state
+                .last_seen
+                .and_then(|value| self.publisher.last_seen(value)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+smarthome_node_config! {
+    #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+    #[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+    #[serde(default)]
+    pub struct LinkNodeConfig for LinkNodePublisher {
+        pub signal: bool => LINK_NODE_SIGNAL_PROP_ID, signal(i64), |_config| {
+            PropertyDescriptionBuilder::integer()
+                .name("Signal strength")
+                .unit("dBm")
+                .settable(false)
+                .retained(true)
+                .build()
+        },
+        pub quality: bool => LINK_NODE_QUALITY_PROP_ID, quality(i64), |_config| {
+            PropertyDescriptionBuilder::integer()
+                .name("Link quality")
+                .integer_range(IntegerRange {
+                    min: Some(0),
+                    max: Some(255),
+                    step: None,
+                })
+                .settable(false)
+                .retained(true)
+                .build()
+        },
+        pub last_seen: bool,
+    }
 }
 
 impl Default for LinkNodeConfig {
@@ -62,33 +138,17 @@ impl LinkNodeBuilder {
     }
 
     fn build_node(db: NodeDescriptionBuilder, config: &LinkNodeConfig) -> NodeDescriptionBuilder {
-        db.add_property_cond(LINK_NODE_SIGNAL_PROP_ID, config.signal, || {
-            PropertyDescriptionBuilder::integer()
-                .name("Signal strength")
-                .unit("dBm")
-                .settable(false)
-                .retained(true)
-                .build()
-        })
-        .add_property_cond(LINK_NODE_QUALITY_PROP_ID, config.quality, || {
-            PropertyDescriptionBuilder::integer()
-                .name("Link quality")
-                .integer_range(IntegerRange {
-                    min: Some(0),
-                    max: Some(255),
-                    step: None,
-                })
-                .settable(false)
-                .retained(true)
-                .build()
-        })
-        .add_property_cond(LINK_NODE_LAST_SEEN_PROP_ID, config.last_seen, || {
-            PropertyDescriptionBuilder::datetime()
-                .name("Last seen")
-                .settable(false)
-                .retained(true)
-                .build()
-        })
+        config.add_optional_properties(db.add_property_cond(
+            LINK_NODE_LAST_SEEN_PROP_ID,
+            config.last_seen,
+            || {
+                PropertyDescriptionBuilder::datetime()
+                    .name("Last seen")
+                    .settable(false)
+                    .retained(true)
+                    .build()
+            },
+        ))
     }
 
     pub fn name<S: Into<String>>(mut self, name: impl Into<Option<S>>) -> Self {
@@ -96,10 +156,23 @@ impl LinkNodeBuilder {
         self
     }
 
+    /// Append a subtype suffix to the node type (`link-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::Link.with_subtype(subtype));
+        self
+    }
+
     pub fn build(self) -> HomieNodeDescription {
         self.node_builder.build()
     }
 
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &LinkNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
     pub fn build_with_publisher(
         self,
         node_id: HomieID,
@@ -125,6 +198,7 @@ pub struct LinkNodePublisher {
     client: Homie5DeviceProtocol,
     config: LinkNodeConfig,
     node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
     signal_prop: HomieID,
     quality_prop: HomieID,
     last_seen_prop: HomieID,
@@ -132,8 +206,10 @@ pub struct LinkNodePublisher {
 
 impl LinkNodePublisher {
     pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: LinkNodeConfig) -> Self {
+        let metadata = property_metadata(&LinkNodeBuilder::new(&config).build());
         Self {
             node,
+            metadata,
             client,
             config,
             signal_prop: LINK_NODE_SIGNAL_PROP_ID,
@@ -142,30 +218,6 @@ impl LinkNodePublisher {
         }
     }
 
-    pub fn signal(&self, value: i64) -> Option<homie5::client::Publish> {
-        if !self.config.signal {
-            return None;
-        }
-        Some(self.client.publish_value(
-            self.node.node_id(),
-            &self.signal_prop,
-            value.to_string(),
-            true,
-        ))
-    }
-
-    pub fn quality(&self, value: i64) -> Option<homie5::client::Publish> {
-        if !self.config.quality {
-            return None;
-        }
-        Some(self.client.publish_value(
-            self.node.node_id(),
-            &self.quality_prop,
-            value.to_string(),
-            true,
-        ))
-    }
-
     pub fn last_seen(&self, value: DateTime<Utc>) -> Option<homie5::client::Publish> {
         if !self.config.last_seen {
             return None;
@@ -178,3 +230,25 @@ impl LinkNodePublisher {
         ))
     }
 }
+
+impl SmarthomePublisher for LinkNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::Link
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![
+            self.signal_prop.clone(),
+            self.quality_prop.clone(),
+            self.last_seen_prop.clone(),
+        ]
+    }
+}
@@ -1,31 +1,164 @@
 use core::fmt;
-use std::str::FromStr;
+use std::{collections::BTreeMap, str::FromStr};
 
 use homie5::{
-    Homie5DeviceProtocol, Homie5Message, Homie5ProtocolError, HomieID, HomieValue, NodeRef,
-    PropertyRef,
+    HOMIE_UNIT_SECONDS, Homie5DeviceProtocol, Homie5Message, Homie5ProtocolError, HomieID,
+    HomieValue, NodeRef, PropertyRef,
     device_description::{
-        HomieDeviceDescription, HomieNodeDescription, NodeDescriptionBuilder,
+        HomieDeviceDescription, HomieNodeDescription, IntegerRange, NodeDescriptionBuilder,
         PropertyDescriptionBuilder,
     },
 };
 use serde::{Deserialize, Serialize};
 
-use crate::{ParseError, ParseErrorKind, ParseOutcome, SMARTHOME_CAP_LOCK, SetCommandParser};
+use crate::{
+    ParseError, ParseErrorKind, ParseOutcome, PropertyMetadata, SMARTHOME_CAP_LOCK,
+    SetCommandParser, SmarthomePublisher, SmarthomeType, property_metadata, readonly_properties,
+    reject_readonly, smarthome_node_config,
+};
 
 pub const LOCK_NODE_DEFAULT_ID: HomieID = HomieID::new_const("lock");
 pub const LOCK_NODE_DEFAULT_NAME: &str = "Lock control";
 pub const LOCK_NODE_STATE_PROP_ID: HomieID = HomieID::new_const("state");
 pub const LOCK_NODE_ACTION_PROP_ID: HomieID = HomieID::new_const("action");
+pub const LOCK_NODE_JAMMED_PROP_ID: HomieID = HomieID::new_const("jammed");
+pub const LOCK_NODE_DOOR_PROP_ID: HomieID = HomieID::new_const("door");
+pub const LOCK_NODE_AUTO_RELOCK_PROP_ID: HomieID = HomieID::new_const("auto-relock");
+pub const LOCK_NODE_CODE_PROP_ID: HomieID = HomieID::new_const("code");
+pub const LOCK_NODE_CODES_PROP_ID: HomieID = HomieID::new_const("codes");
+pub const LOCK_NODE_LAST_USER_PROP_ID: HomieID = HomieID::new_const("last-user");
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LockNodeState {
+    pub state: bool,
+    pub state_target: bool,
+    pub jammed: Option<bool>,
+    pub door: Option<bool>,
+    pub auto_relock: Option<i64>,
+    pub codes: Vec<LockCodeSlot>,
+    pub last_user: Option<String>,
+}
 
 #[derive(Debug)]
 pub struct LockNode {
     pub publisher: LockNodePublisher,
-    pub state: bool,
-    pub state_target: bool,
+    pub state: LockNodeState,
+}
+
+impl LockNode {
+    /// Apply a parsed set command and return the `$target` and value publishes
+    /// for the new lock state.
+    pub fn apply_set(&mut self, event: LockNodeSetEvents) -> Vec<homie5::client::Publish> {
+        let locked = match event {
+            LockNodeSetEvents::State(value) => value,
+            LockNodeSetEvents::Action(LockNodeActions::Lock) => true,
+            LockNodeSetEvents::Action(LockNodeActions::Unlock) => false,
+            LockNodeSetEvents::Action(LockNodeActions::Toggle) => !self.state.state,
+            LockNodeSetEvents::AutoRelock(value) => {
+                self.state.auto_relock = Some(value);
+                return [
+                    self.publisher.auto_relock_target(value),
+                    self.publisher.auto_relock(value),
+                ]
+                .into_iter()
+                .flatten()
+                .collect();
+            }
+            LockNodeSetEvents::Code(command) => {
+                match command {
+                    LockCodeCommand::Add { slot, name, .. } => {
+                        self.state.codes.retain(|entry| entry.slot != slot);
+                        self.state.codes.push(LockCodeSlot { slot, name });
+                        self.state.codes.sort_by_key(|entry| entry.slot);
+                    }
+                    LockCodeCommand::Remove { slot } => {
+                        self.state.codes.retain(|entry| entry.slot != slot);
+                    }
+                }
+                return self
+                    .publisher
+                    .codes(&self.state.codes)
+                    .into_iter()
+                    .collect();
+            }
+        };
+        self.state.state_target = locked;
+        self.state.state = locked;
+        vec![
+            self.publisher.state_target(locked),
+            self.publisher.state(locked),
+        ]
+    }
+
+    /// Apply a value reported by the device and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Bool(value)) if p == &LOCK_NODE_STATE_PROP_ID => {
+                self.state.state = *value;
+                vec![self.publisher.state(*value)]
+            }
+            (p, HomieValue::Bool(value)) if p == &LOCK_NODE_JAMMED_PROP_ID => {
+                self.state.jammed = Some(*value);
+                self.publisher.jammed(*value).into_iter().collect()
+            }
+            (p, HomieValue::Bool(value)) if p == &LOCK_NODE_DOOR_PROP_ID => {
+                self.state.door = Some(*value);
+                self.publisher.door(*value).into_iter().collect()
+            }
+            (p, HomieValue::Integer(value)) if p == &LOCK_NODE_AUTO_RELOCK_PROP_ID => {
+                self.state.auto_relock = Some(*value);
+                self.publisher.auto_relock(*value).into_iter().collect()
+            }
+            (p, HomieValue::JSON(value)) if p == &LOCK_NODE_CODES_PROP_ID => {
+                let Ok(codes) = serde_json::from_value::<Vec<LockCodeSlot>>(value.clone()) else {
+                    return Vec::new();
+                };
+                self.state.codes = codes;
+                self.publisher
+                    .codes(&self.state.codes)
+                    .into_iter()
+                    .collect()
+            }
+            (p, HomieValue::String(value)) if p == &LOCK_NODE_LAST_USER_PROP_ID => {
+                self.state.last_user = Some(value.clone());
+                self.publisher.last_user(value).into_iter().collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            Some(self.publisher.state(self.state.state)),
+            Some(self.publisher.state_target(self.state.state_target)),
+            self.state
+                .jammed
+                .and_then(|value| self.publisher.jammed(value)),
+            self.state.door.and_then(|value| self.publisher.door(value)),
+            self.state
+                .auto_relock
+                .and_then(|value| self.publisher.auto_relock(value)),
+            self.publisher.codes(&self.state.codes),
+            self.state
+                .last_user
+                .as_deref()
+                .and_then(|value| self.publisher.last_user(value)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 pub enum LockNodeActions {
     Lock,
     Unlock,
@@ -60,26 +193,114 @@ impl FromStr for LockNodeActions {
     }
 }
 
+/// A configured user code slot as published on the `codes` property. The PIN
+/// itself is never published.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockCodeSlot {
+    pub slot: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// Code management command received on the `code` property, e.g.
+/// `{"action":"add","slot":1,"code":"1234","name":"Alice"}` or
+/// `{"action":"remove","slot":1}`.
+///
+/// The PIN is write-only: it is neither serialized nor shown in `Debug` output.
+#[derive(Clone, PartialEq, Eq, Deserialize)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+#[serde(tag = "action", rename_all = "kebab-case")]
+pub enum LockCodeCommand {
+    Add {
+        slot: u32,
+        code: String,
+        #[serde(default)]
+        name: Option<String>,
+    },
+    Remove {
+        slot: u32,
+    },
+}
+
+impl fmt::Debug for LockCodeCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Add { slot, name, .. } => f
+                .debug_struct("Add")
+                .field("slot", slot)
+                .field("code", &"<redacted>")
+                .field("name", name)
+                .finish(),
+            Self::Remove { slot } => f.debug_struct("Remove").field("slot", slot).finish(),
+        }
+    }
+}
+
 #[derive(Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 pub enum LockNodeSetEvents {
     State(bool),
     Action(LockNodeActions),
+    AutoRelock(i64),
+    Code(LockCodeCommand),
 }
 
-#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
-#[serde(default)]
-pub struct LockNodeConfig {
-    pub settable: bool,
+smarthome_node_config! {
+    #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+    #[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+    #[serde(default)]
+    pub struct LockNodeConfig for LockNodePublisher {
+        pub settable: bool,
+        /// Expose a `jammed` flag for locks that detect a blocked bolt.
+        pub jammed: bool => LOCK_NODE_JAMMED_PROP_ID, jammed(bool), |_config| {
+            PropertyDescriptionBuilder::boolean()
+                .name("Jammed")
+                .boolean_labels("ok", "jammed")
+                .settable(false)
+                .retained(true)
+                .build()
+        },
+        /// Expose the door state (open/closed) reported by locks with a door sensor.
+        pub door: bool => LOCK_NODE_DOOR_PROP_ID, door(bool), |_config| {
+            PropertyDescriptionBuilder::boolean()
+                .name("Door state")
+                .boolean_labels("closed", "open")
+                .settable(false)
+                .retained(true)
+                .build()
+        },
+        /// Expose a settable `auto-relock` timeout in seconds (0 = disabled).
+        pub auto_relock: bool,
+        /// Expose user code management (`code` command and `codes` slot list).
+        pub codes: bool,
+        /// Expose `last-user`, the name or slot of the code that last unlocked.
+        pub last_user: bool,
+        /// Properties to publish as read-only; sets to them are rejected.
+        #[cfg_attr(
+            feature = "proptest",
+            proptest(strategy = "crate::arbitrary::homie_ids()")
+        )]
+        pub readonly: Vec<HomieID>,
+    }
 }
 
 impl Default for LockNodeConfig {
     fn default() -> Self {
-        Self { settable: true }
+        Self {
+            settable: true,
+            jammed: false,
+            door: false,
+            auto_relock: false,
+            codes: false,
+            last_user: false,
+            readonly: Vec::new(),
+        }
     }
 }
 
 pub struct LockNodeBuilder {
     node_builder: NodeDescriptionBuilder,
+    config: LockNodeConfig,
 }
 
 impl LockNodeBuilder {
@@ -89,28 +310,68 @@ impl LockNodeBuilder {
             config,
         )
         .r#type(SMARTHOME_CAP_LOCK);
+        let db = readonly_properties(db, &config.readonly);
 
-        Self { node_builder: db }
+        Self {
+            node_builder: db,
+            config: config.clone(),
+        }
     }
 
     fn build_node(db: NodeDescriptionBuilder, config: &LockNodeConfig) -> NodeDescriptionBuilder {
-        db.add_property(
-            LOCK_NODE_STATE_PROP_ID,
-            PropertyDescriptionBuilder::boolean()
-                .name("Lock state")
-                .boolean_labels("unlocked", "locked")
-                .settable(config.settable)
-                .retained(true)
-                .build(),
-        )
-        .add_property(
-            LOCK_NODE_ACTION_PROP_ID,
-            PropertyDescriptionBuilder::enumeration(["lock", "unlock", "toggle"])
-                .unwrap()
-                .name("Lock action")
-                .settable(config.settable)
-                .retained(false)
-                .build(),
+        config.add_optional_properties(
+            db.add_property(
+                LOCK_NODE_STATE_PROP_ID,
+                PropertyDescriptionBuilder::boolean()
+                    .name("Lock state")
+                    .boolean_labels("unlocked", "locked")
+                    .settable(config.settable)
+                    .retained(true)
+                    .build(),
+            )
+            .add_property(
+                LOCK_NODE_ACTION_PROP_ID,
+                PropertyDescriptionBuilder::enumeration(["lock", "unlock", "toggle"])
+                    .unwrap()
+                    .name("Lock action")
+                    .settable(config.settable)
+                    .retained(false)
+                    .build(),
+            )
+            .add_property_cond(LOCK_NODE_AUTO_RELOCK_PROP_ID, config.auto_relock, || {
+                PropertyDescriptionBuilder::integer()
+                    .name("Auto-relock timeout")
+                    .unit(HOMIE_UNIT_SECONDS)
+                    .integer_range(IntegerRange {
+                        min: Some(0),
+                        max: None,
+                        step: None,
+                    })
+                    .settable(config.settable)
+                    .retained(true)
+                    .build()
+            })
+            .add_property_cond(LOCK_NODE_CODE_PROP_ID, config.codes, || {
+                PropertyDescriptionBuilder::json()
+                    .name("Code management")
+                    .settable(true)
+                    .retained(false)
+                    .build()
+            })
+            .add_property_cond(LOCK_NODE_CODES_PROP_ID, config.codes, || {
+                PropertyDescriptionBuilder::json()
+                    .name("Code slots")
+                    .settable(false)
+                    .retained(true)
+                    .build()
+            })
+            .add_property_cond(LOCK_NODE_LAST_USER_PROP_ID, config.last_user, || {
+                PropertyDescriptionBuilder::string()
+                    .name("Last user")
+                    .settable(false)
+                    .retained(true)
+                    .build()
+            }),
         )
     }
 
@@ -119,10 +380,23 @@ impl LockNodeBuilder {
         self
     }
 
+    /// Append a subtype suffix to the node type (`lock-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::Lock.with_subtype(subtype));
+        self
+    }
+
     pub fn build(self) -> HomieNodeDescription {
         self.node_builder.build()
     }
 
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &LockNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
     pub fn build_with_publisher(
         self,
         node_id: HomieID,
@@ -137,6 +411,7 @@ impl LockNodeBuilder {
                     node_id,
                 ),
                 client.clone(),
+                self.config,
             ),
         )
     }
@@ -146,17 +421,34 @@ impl LockNodeBuilder {
 pub struct LockNodePublisher {
     client: Homie5DeviceProtocol,
     node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
+    config: LockNodeConfig,
     state_prop: HomieID,
     action_prop: HomieID,
+    jammed_prop: HomieID,
+    door_prop: HomieID,
+    auto_relock_prop: HomieID,
+    code_prop: HomieID,
+    codes_prop: HomieID,
+    last_user_prop: HomieID,
 }
 
 impl LockNodePublisher {
-    pub fn new(node: NodeRef, client: Homie5DeviceProtocol) -> Self {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: LockNodeConfig) -> Self {
+        let metadata = property_metadata(&LockNodeBuilder::new(&config).build());
         Self {
             node,
+            metadata,
             client,
+            config,
             state_prop: LOCK_NODE_STATE_PROP_ID,
             action_prop: LOCK_NODE_ACTION_PROP_ID,
+            jammed_prop: LOCK_NODE_JAMMED_PROP_ID,
+            door_prop: LOCK_NODE_DOOR_PROP_ID,
+            auto_relock_prop: LOCK_NODE_AUTO_RELOCK_PROP_ID,
+            code_prop: LOCK_NODE_CODE_PROP_ID,
+            codes_prop: LOCK_NODE_CODES_PROP_ID,
+            last_user_prop: LOCK_NODE_LAST_USER_PROP_ID,
         }
     }
 
@@ -186,6 +478,79 @@ impl LockNodePublisher {
             false,
         )
     }
+
+    pub fn auto_relock(&self, value: i64) -> Option<homie5::client::Publish> {
+        if !self.config.auto_relock {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.auto_relock_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+
+    pub fn auto_relock_target(&self, value: i64) -> Option<homie5::client::Publish> {
+        if !self.config.auto_relock {
+            return None;
+        }
+        Some(self.client.publish_target(
+            self.node.node_id(),
+            &self.auto_relock_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+
+    pub fn codes(&self, codes: &[LockCodeSlot]) -> Option<homie5::client::Publish> {
+        if !self.config.codes {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.codes_prop,
+            serde_json::to_string(codes).ok()?,
+            true,
+        ))
+    }
+
+    pub fn last_user(&self, user: &str) -> Option<homie5::client::Publish> {
+        if !self.config.last_user {
+            return None;
+        }
+        Some(
+            self.client
+                .publish_value(self.node.node_id(), &self.last_user_prop, user, true),
+        )
+    }
+}
+
+impl SmarthomePublisher for LockNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::Lock
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![
+            self.state_prop.clone(),
+            self.action_prop.clone(),
+            self.jammed_prop.clone(),
+            self.door_prop.clone(),
+            self.auto_relock_prop.clone(),
+            self.code_prop.clone(),
+            self.codes_prop.clone(),
+            self.last_user_prop.clone(),
+        ]
+    }
 }
 
 impl SetCommandParser for LockNodePublisher {
@@ -197,6 +562,10 @@ impl SetCommandParser for LockNodePublisher {
         desc: &HomieDeviceDescription,
         set_value: &str,
     ) -> ParseOutcome<Self::Event> {
+        if let Some(outcome) = reject_readonly(&self.node, property, desc, set_value) {
+            return outcome;
+        }
+
         let property_id = property.prop_id().to_string();
 
         if property.match_with_node(&self.node, &self.state_prop) {
@@ -246,6 +615,53 @@ impl SetCommandParser for LockNodePublisher {
                     ParseErrorKind::InvalidHomieValue,
                 )),
             }
+        } else if property.match_with_node(&self.node, &self.auto_relock_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Integer(value)) => {
+                    ParseOutcome::Parsed(LockNodeSetEvents::AutoRelock(value))
+                }
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else if property.match_with_node(&self.node, &self.code_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::JSON(value)) => match serde_json::from_value(value) {
+                    Ok(command) => ParseOutcome::Parsed(LockNodeSetEvents::Code(command)),
+                    Err(_) => ParseOutcome::Invalid(ParseError::new(
+                        property_id,
+                        set_value,
+                        ParseErrorKind::InvalidVariant,
+                    )),
+                },
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
         } else {
             ParseOutcome::NoMatch
         }
@@ -269,3 +685,98 @@ impl SetCommandParser for LockNodePublisher {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use homie5::HomieDomain;
+
+    use super::*;
+    use crate::StandaloneSetParser;
+
+    #[test]
+    fn auto_relock_is_gated_and_settable() {
+        let node = LockNodeBuilder::new(&LockNodeConfig::default()).build();
+        assert!(!node.properties.contains_key(&LOCK_NODE_AUTO_RELOCK_PROP_ID));
+        assert!(!node.properties.contains_key(&LOCK_NODE_JAMMED_PROP_ID));
+
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let config = LockNodeConfig {
+            auto_relock: true,
+            ..LockNodeConfig::default()
+        };
+        let parser: StandaloneSetParser<_> = LockNodeBuilder::new(&config)
+            .build_with_publisher(LOCK_NODE_DEFAULT_ID, &client)
+            .into();
+        let property = PropertyRef::from_node(
+            parser.publisher().node_ref().clone(),
+            LOCK_NODE_AUTO_RELOCK_PROP_ID,
+        );
+
+        assert!(matches!(
+            parser.parse_set(&property, "30"),
+            ParseOutcome::Parsed(LockNodeSetEvents::AutoRelock(30))
+        ));
+        assert!(matches!(
+            parser.parse_set(&property, "-1"),
+            ParseOutcome::Invalid(_)
+        ));
+    }
+
+    #[test]
+    fn code_commands_update_slots_without_publishing_pins() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let config = LockNodeConfig {
+            codes: true,
+            ..LockNodeConfig::default()
+        };
+        let parser: StandaloneSetParser<_> = LockNodeBuilder::new(&config)
+            .build_with_publisher(LOCK_NODE_DEFAULT_ID, &client)
+            .into();
+        let property = PropertyRef::from_node(
+            parser.publisher().node_ref().clone(),
+            LOCK_NODE_CODE_PROP_ID,
+        );
+
+        let ParseOutcome::Parsed(add) = parser.parse_set(
+            &property,
+            r#"{"action":"add","slot":2,"code":"1234","name":"Alice"}"#,
+        ) else {
+            panic!("add command must parse");
+        };
+        assert!(matches!(
+            parser.parse_set(&property, r#"{"action":"wipe"}"#),
+            ParseOutcome::Invalid(_)
+        ));
+
+        let mut lock = LockNode {
+            publisher: parser.into_publisher(),
+            state: LockNodeState::default(),
+        };
+        let publishes = lock.apply_set(add);
+        assert_eq!(
+            lock.state.codes,
+            vec![LockCodeSlot {
+                slot: 2,
+                name: Some("Alice".to_string())
+            }]
+        );
+        assert_eq!(publishes.len(), 1);
+
+        lock.apply_set(LockNodeSetEvents::Code(LockCodeCommand::Remove { slot: 2 }));
+        assert!(lock.state.codes.is_empty());
+    }
+
+    #[test]
+    fn code_commands_do_not_leak_the_pin() {
+        let command = LockCodeCommand::Add {
+            slot: 1,
+            code: "4711".to_owned(),
+            name: Some("Alice".to_owned()),
+        };
+        let debug = format!("{:?}", LockNodeSetEvents::Code(command));
+        assert!(!debug.contains("4711"));
+        assert!(debug.contains("Alice"));
+    }
+}
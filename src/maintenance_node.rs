@@ -0,0 +1,699 @@
+use std::collections::{BTreeMap, HashSet};
+
+use chrono::prelude::*;
+use homie5::{
+    HOMIE_UNIT_DEGREE_CELSIUS, Homie5DeviceProtocol, Homie5Message, HomieID, HomieValue, NodeRef,
+    PropertyRef,
+    device_description::{
+        HomieDeviceDescription, HomieNodeDescription, IntegerRange, NodeDescriptionBuilder,
+        PropertyDescriptionBuilder,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Clock, ParseError, ParseErrorKind, ParseOutcome, PropertyMetadata, SMARTHOME_CAP_MAINTENANCE,
+    SetCommandParser, SharedClock, SmarthomePublisher, SmarthomeType, alerts::SmarthomeAlert,
+    property_metadata, readonly_properties, reject_readonly, smarthome_node_config,
+};
+
+pub const MAINTENANCE_NODE_DEFAULT_ID: HomieID = HomieID::new_const("maintenance");
+pub const MAINTENANCE_NODE_DEFAULT_NAME: &str = "Maintenance";
+pub const MAINTENANCE_NODE_FIRMWARE_VERSION_PROP_ID: HomieID =
+    HomieID::new_const("firmware-version");
+pub const MAINTENANCE_NODE_HARDWARE_VERSION_PROP_ID: HomieID =
+    HomieID::new_const("hardware-version");
+pub const MAINTENANCE_NODE_DEVICE_TEMPERATURE_PROP_ID: HomieID =
+    HomieID::new_const("device-temperature");
+pub const MAINTENANCE_NODE_RSSI_PROP_ID: HomieID = HomieID::new_const("rssi");
+pub const MAINTENANCE_NODE_LINK_QUALITY_PROP_ID: HomieID = HomieID::new_const("link-quality");
+pub const MAINTENANCE_NODE_LAST_UPDATE_PROP_ID: HomieID = HomieID::new_const("last-update");
+pub const MAINTENANCE_NODE_IDENTIFY_PROP_ID: HomieID = HomieID::new_const("identify");
+pub const MAINTENANCE_NODE_REBOOT_PROP_ID: HomieID = HomieID::new_const("reboot");
+
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+pub enum MaintenanceNodeSetEvents {
+    Identify,
+    Reboot,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MaintenanceNodeState {
+    pub firmware_version: Option<String>,
+    pub hardware_version: Option<String>,
+    pub device_temperature: Option<f64>,
+    pub rssi: Option<i64>,
+    pub link_quality: Option<i64>,
+    pub last_update: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug)]
+pub struct MaintenanceNode {
+    pub publisher: MaintenanceNodePublisher,
+    pub state: MaintenanceNodeState,
+}
+
+impl MaintenanceNode {
+    /// Apply a parsed set command. `identify` and `reboot` are carried out by
+    /// the device, so there is nothing to publish.
+    pub fn apply_set(&mut self, event: MaintenanceNodeSetEvents) -> Vec<homie5::client::Publish> {
+        match event {
+            MaintenanceNodeSetEvents::Identify | MaintenanceNodeSetEvents::Reboot => Vec::new(),
+        }
+    }
+
+    /// Apply a value reported by the device and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::String(value)) if p == &MAINTENANCE_NODE_FIRMWARE_VERSION_PROP_ID => {
+                self.state.firmware_version = Some(value.clone());
+                self.publisher.firmware_version(value).into_iter().collect()
+            }
+            (p, HomieValue::String(value)) if p == &MAINTENANCE_NODE_HARDWARE_VERSION_PROP_ID => {
+                self.state.hardware_version = Some(value.clone());
+                self.publisher.hardware_version(value).into_iter().collect()
+            }
+            (p, HomieValue::Float(value)) if p == &MAINTENANCE_NODE_DEVICE_TEMPERATURE_PROP_ID => {
+                self.state.device_temperature = Some(*value);
+                self.publisher
+                    .device_temperature(*value)
+                    .into_iter()
+                    .collect()
+            }
+            (p, HomieValue::Integer(value)) if p == &MAINTENANCE_NODE_RSSI_PROP_ID => {
+                self.state.rssi = Some(*value);
+                self.publisher.rssi(*value).into_iter().collect()
+            }
+            (p, HomieValue::Integer(value)) if p == &MAINTENANCE_NODE_LINK_QUALITY_PROP_ID => {
+                self.state.link_quality = Some(*value);
+                self.publisher.link_quality(*value).into_iter().collect()
+            }
+            (p, HomieValue::DateTime(value)) if p == &MAINTENANCE_NODE_LAST_UPDATE_PROP_ID => {
+                self.state.last_update = Some(*value);
+                self.publisher.last_update(*value).into_iter().collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            self.state
+                .firmware_version
+                .as_deref()
+                .and_then(|value| self.publisher.firmware_version(value)),
+            self.state
+                .hardware_version
+                .as_deref()
+                .and_then(|value| self.publisher.hardware_version(value)),
+            self.state
+                .device_temperature
+                .and_then(|value| self.publisher.device_temperature(value)),
+            self.state.rssi.and_then(|value| self.publisher.rssi(value)),
+            self.state
+                .link_quality
+                .and_then(|value| self.publisher.link_quality(value)),
+            self.state
+                .last_update
+                .and_then(|value| self.publisher.last_update(value)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+smarthome_node_config! {
+    #[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+    #[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+    #[serde(default)]
+    pub struct MaintenanceNodeConfig for MaintenanceNodePublisher {
+        pub firmware_version: bool => MAINTENANCE_NODE_FIRMWARE_VERSION_PROP_ID, firmware_version(&str), |_config| {
+            PropertyDescriptionBuilder::string()
+                .name("Firmware version")
+                .settable(false)
+                .retained(true)
+                .build()
+        },
+        pub hardware_version: bool => MAINTENANCE_NODE_HARDWARE_VERSION_PROP_ID, hardware_version(&str), |_config| {
+            PropertyDescriptionBuilder::string()
+                .name("Hardware version")
+                .settable(false)
+                .retained(true)
+                .build()
+        },
+        /// Expose the internal/chip temperature reported by the device.
+        pub device_temperature: bool => MAINTENANCE_NODE_DEVICE_TEMPERATURE_PROP_ID, device_temperature(f64), |_config| {
+            PropertyDescriptionBuilder::float()
+                .name("Device temperature")
+                .unit(HOMIE_UNIT_DEGREE_CELSIUS)
+                .settable(false)
+                .retained(true)
+                .build()
+        },
+        /// Expose the radio signal strength in dBm.
+        pub rssi: bool => MAINTENANCE_NODE_RSSI_PROP_ID, rssi(i64), |_config| {
+            PropertyDescriptionBuilder::integer()
+                .name("Signal strength")
+                .unit("dBm")
+                .settable(false)
+                .retained(true)
+                .build()
+        },
+        /// Expose the radio link quality (`0:255`), e.g. Zigbee LQI.
+        pub link_quality: bool => MAINTENANCE_NODE_LINK_QUALITY_PROP_ID, link_quality(i64), |_config| {
+            PropertyDescriptionBuilder::integer()
+                .name("Link quality")
+                .integer_range(IntegerRange {
+                    min: Some(0),
+                    max: Some(255),
+                    step: None,
+                })
+                .settable(false)
+                .retained(true)
+                .build()
+        },
+        /// Expose a `last-update` timestamp refreshed whenever the device reports.
+        pub last_update: bool,
+        /// Expose a settable `identify` action (e.g. blink an LED).
+        pub identify: bool,
+        /// Expose a settable `reboot` action.
+        pub reboot: bool,
+        /// Properties to publish as read-only; sets to them are rejected.
+        #[cfg_attr(
+            feature = "proptest",
+            proptest(strategy = "crate::arbitrary::homie_ids()")
+        )]
+        pub readonly: Vec<HomieID>,
+    }
+}
+
+pub struct MaintenanceNodeBuilder {
+    config: MaintenanceNodeConfig,
+    node_builder: NodeDescriptionBuilder,
+    clock: SharedClock,
+}
+
+impl MaintenanceNodeBuilder {
+    pub fn new(config: &MaintenanceNodeConfig) -> Self {
+        let db = Self::build_node(
+            NodeDescriptionBuilder::new().name(MAINTENANCE_NODE_DEFAULT_NAME),
+            config,
+        )
+        .r#type(SMARTHOME_CAP_MAINTENANCE);
+        let db = readonly_properties(db, &config.readonly);
+
+        Self {
+            node_builder: db,
+            config: config.clone(),
+            clock: SharedClock::default(),
+        }
+    }
+
+    fn build_node(
+        db: NodeDescriptionBuilder,
+        config: &MaintenanceNodeConfig,
+    ) -> NodeDescriptionBuilder {
+        config.add_optional_properties(
+            db.add_property_cond(
+                MAINTENANCE_NODE_LAST_UPDATE_PROP_ID,
+                config.last_update,
+                || {
+                    PropertyDescriptionBuilder::datetime()
+                        .name("Last update")
+                        .settable(false)
+                        .retained(true)
+                        .build()
+                },
+            )
+            .add_property_cond(MAINTENANCE_NODE_IDENTIFY_PROP_ID, config.identify, || {
+                PropertyDescriptionBuilder::enumeration(["identify"])
+                    .unwrap()
+                    .name("Identify device")
+                    .settable(true)
+                    .retained(false)
+                    .build()
+            })
+            .add_property_cond(MAINTENANCE_NODE_REBOOT_PROP_ID, config.reboot, || {
+                PropertyDescriptionBuilder::enumeration(["reboot"])
+                    .unwrap()
+                    .name("Reboot device")
+                    .settable(true)
+                    .retained(false)
+                    .build()
+            }),
+        )
+    }
+
+    pub fn name<S: Into<String>>(mut self, name: impl Into<Option<S>>) -> Self {
+        self.node_builder = self.node_builder.name(name);
+        self
+    }
+
+    /// Append a subtype suffix to the node type (`maintenance-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::Maintenance.with_subtype(subtype));
+        self
+    }
+
+    /// Clock for the timestamps of [`MaintenanceNodePublisher::last_update_now`]
+    /// and the `last-update` heartbeat; the system clock by default.
+    pub fn clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = SharedClock::new(clock);
+        self
+    }
+
+    pub fn build(self) -> HomieNodeDescription {
+        self.node_builder.build()
+    }
+
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &MaintenanceNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
+    pub fn build_with_publisher(
+        self,
+        node_id: HomieID,
+        client: &Homie5DeviceProtocol,
+    ) -> (HomieNodeDescription, MaintenanceNodePublisher) {
+        let desc = self.node_builder.build();
+        let mut publisher = MaintenanceNodePublisher::new(
+            NodeRef::new(
+                client.homie_domain().to_owned(),
+                client.id().to_owned(),
+                node_id,
+            ),
+            client.clone(),
+            self.config,
+        );
+        publisher.clock = self.clock;
+        (desc, publisher)
+    }
+}
+
+#[derive(Debug)]
+pub struct MaintenanceNodePublisher {
+    client: Homie5DeviceProtocol,
+    config: MaintenanceNodeConfig,
+    node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
+    clock: SharedClock,
+    firmware_version_prop: HomieID,
+    hardware_version_prop: HomieID,
+    device_temperature_prop: HomieID,
+    rssi_prop: HomieID,
+    link_quality_prop: HomieID,
+    last_update_prop: HomieID,
+    identify_prop: HomieID,
+    reboot_prop: HomieID,
+    active_alerts: HashSet<SmarthomeAlert>,
+}
+
+impl MaintenanceNodePublisher {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: MaintenanceNodeConfig) -> Self {
+        let metadata = property_metadata(&MaintenanceNodeBuilder::new(&config).build());
+        Self {
+            node,
+            metadata,
+            clock: SharedClock::default(),
+            client,
+            config,
+            firmware_version_prop: MAINTENANCE_NODE_FIRMWARE_VERSION_PROP_ID,
+            hardware_version_prop: MAINTENANCE_NODE_HARDWARE_VERSION_PROP_ID,
+            device_temperature_prop: MAINTENANCE_NODE_DEVICE_TEMPERATURE_PROP_ID,
+            rssi_prop: MAINTENANCE_NODE_RSSI_PROP_ID,
+            link_quality_prop: MAINTENANCE_NODE_LINK_QUALITY_PROP_ID,
+            last_update_prop: MAINTENANCE_NODE_LAST_UPDATE_PROP_ID,
+            identify_prop: MAINTENANCE_NODE_IDENTIFY_PROP_ID,
+            reboot_prop: MAINTENANCE_NODE_REBOOT_PROP_ID,
+            active_alerts: HashSet::new(),
+        }
+    }
+
+    pub fn last_update(&self, value: DateTime<Utc>) -> Option<homie5::client::Publish> {
+        if !self.config.last_update {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.last_update_prop,
+            value.to_rfc3339_opts(SecondsFormat::Millis, true),
+            true,
+        ))
+    }
+
+    /// Publish `last-update` with the current time of the publisher's clock.
+    pub fn last_update_now(&self) -> Option<homie5::client::Publish> {
+        self.last_update(self.clock.now())
+    }
+
+    /// Raise a well-known alert on the device (`$alert/<id>`, retained).
+    ///
+    /// Raising an already active alert republishes it with the new message.
+    /// Alerts stay retained on the broker until cleared, also across restarts
+    /// of the device, while the active set tracked here starts out empty.
+    pub fn raise_alert(&mut self, alert: SmarthomeAlert, msg: &str) -> homie5::client::Publish {
+        self.active_alerts.insert(alert);
+        self.client.publish_alert(&alert.homie_id(), msg)
+    }
+
+    /// Clear a previously raised alert. Returns `None` if the alert is not active.
+    pub fn clear_alert(&mut self, alert: SmarthomeAlert) -> Option<homie5::client::Publish> {
+        if !self.active_alerts.remove(&alert) {
+            return None;
+        }
+        Some(self.client.publish_clear_alert(&alert.homie_id()))
+    }
+
+    /// Clear an alert whether or not it was raised by this publisher, e.g. a
+    /// retained alert left on the broker by a previous run of the device.
+    pub fn clear_alert_unconditionally(
+        &mut self,
+        alert: SmarthomeAlert,
+    ) -> homie5::client::Publish {
+        self.active_alerts.remove(&alert);
+        self.client.publish_clear_alert(&alert.homie_id())
+    }
+
+    /// Clear all alerts raised by this publisher.
+    pub fn clear_all_alerts(&mut self) -> Vec<homie5::client::Publish> {
+        self.active_alerts
+            .drain()
+            .map(|alert| self.client.publish_clear_alert(&alert.homie_id()))
+            .collect()
+    }
+
+    pub fn is_alert_active(&self, alert: SmarthomeAlert) -> bool {
+        self.active_alerts.contains(&alert)
+    }
+
+    pub fn active_alerts(&self) -> impl Iterator<Item = &SmarthomeAlert> {
+        self.active_alerts.iter()
+    }
+}
+
+impl SmarthomePublisher for MaintenanceNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::Maintenance
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![
+            self.firmware_version_prop.clone(),
+            self.hardware_version_prop.clone(),
+            self.device_temperature_prop.clone(),
+            self.rssi_prop.clone(),
+            self.link_quality_prop.clone(),
+            self.last_update_prop.clone(),
+            self.identify_prop.clone(),
+            self.reboot_prop.clone(),
+        ]
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl MaintenanceNodePublisher {
+    /// Spawn a task that sends a `last-update` publish to `tx` every `interval` and
+    /// whenever [`LastUpdateHeartbeat::touch`] is called.
+    ///
+    /// The task stops when the returned handle is dropped or `tx` is closed.
+    /// Returns `None` if `last_update` is not enabled in the config.
+    pub fn spawn_last_update_heartbeat(
+        &self,
+        interval: std::time::Duration,
+        tx: tokio::sync::mpsc::Sender<homie5::client::Publish>,
+    ) -> Option<LastUpdateHeartbeat> {
+        if !self.config.last_update {
+            return None;
+        }
+        let notify = std::sync::Arc::new(tokio::sync::Notify::new());
+        let client = self.client.clone();
+        let node = self.node.clone();
+        let prop = self.last_update_prop.clone();
+        let touched = notify.clone();
+        let clock = self.clock.clone();
+
+        let task = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {}
+                    _ = touched.notified() => ticker.reset(),
+                }
+                let publish = client.publish_value(
+                    node.node_id(),
+                    &prop,
+                    clock.now().to_rfc3339_opts(SecondsFormat::Millis, true),
+                    true,
+                );
+                if tx.send(publish).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        Some(LastUpdateHeartbeat { notify, task })
+    }
+}
+
+/// Handle to a running `last-update` heartbeat task. Dropping it stops the task.
+#[cfg(feature = "tokio")]
+#[derive(Debug)]
+pub struct LastUpdateHeartbeat {
+    notify: std::sync::Arc<tokio::sync::Notify>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+#[cfg(feature = "tokio")]
+impl LastUpdateHeartbeat {
+    /// Signal that the device just reported; publishes `last-update` immediately
+    /// and restarts the interval.
+    pub fn touch(&self) {
+        self.notify.notify_one();
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl Drop for LastUpdateHeartbeat {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+impl SetCommandParser for MaintenanceNodePublisher {
+    type Event = MaintenanceNodeSetEvents;
+
+    fn parse_set(
+        &self,
+        property: &PropertyRef,
+        desc: &HomieDeviceDescription,
+        set_value: &str,
+    ) -> ParseOutcome<Self::Event> {
+        if let Some(outcome) = reject_readonly(&self.node, property, desc, set_value) {
+            return outcome;
+        }
+
+        let event = if property.match_with_node(&self.node, &self.identify_prop) {
+            MaintenanceNodeSetEvents::Identify
+        } else if property.match_with_node(&self.node, &self.reboot_prop) {
+            MaintenanceNodeSetEvents::Reboot
+        } else {
+            return ParseOutcome::NoMatch;
+        };
+
+        let Some(parsed) = desc.with_property(property, |prop_desc| {
+            HomieValue::parse(set_value, prop_desc)
+        }) else {
+            return ParseOutcome::Invalid(ParseError::new(
+                property.prop_id().to_string(),
+                set_value,
+                ParseErrorKind::MissingPropertyDescription,
+            ));
+        };
+
+        match parsed {
+            Ok(HomieValue::Enum(_)) => ParseOutcome::Parsed(event),
+            _ => ParseOutcome::Invalid(ParseError::new(
+                property.prop_id().to_string(),
+                set_value,
+                ParseErrorKind::InvalidHomieValue,
+            )),
+        }
+    }
+
+    fn parse_set_event(
+        &self,
+        desc: &HomieDeviceDescription,
+        event: &Homie5Message,
+    ) -> ParseOutcome<Self::Event> {
+        match event {
+            Homie5Message::PropertySet {
+                property,
+                set_value,
+            } => self.parse_set(property, desc, set_value),
+            _ => ParseOutcome::Invalid(ParseError::new(
+                self.identify_prop.to_string(),
+                "",
+                ParseErrorKind::UnexpectedMessageType,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use homie5::{HomieDomain, device_description::DeviceDescriptionBuilder};
+
+    use super::*;
+
+    #[test]
+    fn radio_link_properties_are_config_gated() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let (desc, publisher) = MaintenanceNodeBuilder::new(&Default::default())
+            .build_with_publisher(MAINTENANCE_NODE_DEFAULT_ID, &client);
+        assert!(!desc.properties.contains_key(&MAINTENANCE_NODE_RSSI_PROP_ID));
+        assert!(publisher.rssi(-70).is_none());
+
+        let config = MaintenanceNodeConfig {
+            rssi: true,
+            link_quality: true,
+            ..Default::default()
+        };
+        let (desc, publisher) = MaintenanceNodeBuilder::new(&config)
+            .build_with_publisher(MAINTENANCE_NODE_DEFAULT_ID, &client);
+        assert_eq!(
+            desc.properties[&MAINTENANCE_NODE_RSSI_PROP_ID]
+                .unit
+                .as_deref(),
+            Some("dBm")
+        );
+        assert!(
+            desc.properties
+                .contains_key(&MAINTENANCE_NODE_LINK_QUALITY_PROP_ID)
+        );
+        assert_eq!(
+            publisher.rssi(-70).map(|p| p.payload),
+            Some(b"-70".to_vec())
+        );
+        assert_eq!(
+            publisher.link_quality(180).map(|p| p.payload),
+            Some(b"180".to_vec())
+        );
+    }
+
+    #[test]
+    fn alerts_follow_raise_and_clear_semantics() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let (_, mut publisher) = MaintenanceNodeBuilder::new(&Default::default())
+            .build_with_publisher(MAINTENANCE_NODE_DEFAULT_ID, &client);
+
+        assert!(publisher.clear_alert(SmarthomeAlert::Tamper).is_none());
+
+        let raised = publisher.raise_alert(SmarthomeAlert::Tamper, "case opened");
+        assert!(raised.retain);
+        assert!(raised.topic.ends_with("/$alert/hc-tamper"));
+        assert_eq!(raised.payload, b"case opened".to_vec());
+        assert!(publisher.is_alert_active(SmarthomeAlert::Tamper));
+
+        let cleared = publisher
+            .clear_alert(SmarthomeAlert::Tamper)
+            .expect("active alert must clear");
+        assert!(cleared.retain);
+        assert!(cleared.payload.is_empty());
+        assert!(!publisher.is_alert_active(SmarthomeAlert::Tamper));
+
+        // a retained alert from a previous run is not in the active set
+        assert!(publisher.clear_alert(SmarthomeAlert::SensorFault).is_none());
+        let cleared = publisher.clear_alert_unconditionally(SmarthomeAlert::SensorFault);
+        assert!(cleared.retain);
+        assert!(cleared.topic.ends_with("/$alert/hc-sensor-fault"));
+        assert!(cleared.payload.is_empty());
+    }
+
+    #[test]
+    fn parses_identify_and_reboot_actions() {
+        let config = MaintenanceNodeConfig {
+            identify: true,
+            reboot: true,
+            ..MaintenanceNodeConfig::default()
+        };
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let (node, publisher) = MaintenanceNodeBuilder::new(&config)
+            .build_with_publisher(MAINTENANCE_NODE_DEFAULT_ID, &client);
+        let desc = DeviceDescriptionBuilder::new()
+            .add_node(MAINTENANCE_NODE_DEFAULT_ID, node)
+            .build();
+
+        let identify =
+            PropertyRef::from_node(publisher.node.clone(), MAINTENANCE_NODE_IDENTIFY_PROP_ID);
+        assert!(matches!(
+            publisher.parse_set(&identify, &desc, "identify"),
+            ParseOutcome::Parsed(MaintenanceNodeSetEvents::Identify)
+        ));
+
+        let reboot =
+            PropertyRef::from_node(publisher.node.clone(), MAINTENANCE_NODE_REBOOT_PROP_ID);
+        assert!(matches!(
+            publisher.parse_set(&reboot, &desc, "reboot"),
+            ParseOutcome::Parsed(MaintenanceNodeSetEvents::Reboot)
+        ));
+        assert!(matches!(
+            publisher.parse_set(&reboot, &desc, "identify"),
+            ParseOutcome::Invalid(_)
+        ));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test(start_paused = true)]
+    async fn heartbeat_publishes_on_schedule_and_touch() {
+        let config = MaintenanceNodeConfig {
+            last_update: true,
+            ..MaintenanceNodeConfig::default()
+        };
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let (_, publisher) = MaintenanceNodeBuilder::new(&config)
+            .clock(|| Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap())
+            .build_with_publisher(MAINTENANCE_NODE_DEFAULT_ID, &client);
+        let (tx, mut rx) = tokio::sync::mpsc::channel(4);
+
+        let heartbeat = publisher
+            .spawn_last_update_heartbeat(std::time::Duration::from_secs(3600), tx)
+            .expect("heartbeat must spawn when last_update is enabled");
+
+        let first = rx.recv().await.expect("initial tick must publish");
+        assert!(first.retain);
+        assert!(first.topic.ends_with("/maintenance/last-update"));
+        assert_eq!(first.payload, b"2026-03-01T12:00:00.000Z");
+        assert!(publisher.last_update_now() == Some(first));
+
+        heartbeat.touch();
+        assert!(rx.recv().await.is_some());
+
+        drop(heartbeat);
+        assert!(rx.recv().await.is_none());
+    }
+}
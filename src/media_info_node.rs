@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use homie5::{
     Homie5DeviceProtocol, Homie5Message, HomieID, HomieValue, NodeRef, PropertyRef,
     device_description::{
@@ -7,7 +9,11 @@ use homie5::{
 };
 use serde::{Deserialize, Serialize};
 
-use crate::{ParseError, ParseErrorKind, ParseOutcome, SMARTHOME_CAP_MEDIA_INFO, SetCommandParser};
+use crate::{
+    ParseError, ParseErrorKind, ParseOutcome, PropertyMetadata, SMARTHOME_CAP_MEDIA_INFO,
+    SetCommandParser, SmarthomePublisher, SmarthomeType, property_metadata, readonly_properties,
+    reject_readonly,
+};
 
 pub const MEDIA_INFO_NODE_DEFAULT_ID: HomieID = HomieID::new_const("media-info");
 pub const MEDIA_INFO_NODE_DEFAULT_NAME: &str = "Media information";
@@ -22,9 +28,9 @@ pub const MEDIA_INFO_NODE_METADATA_PROP_ID: HomieID = HomieID::new_const("metada
 
 // ── Node (state) ────────────────────────────────────────────────────────────
 
-#[derive(Debug)]
-pub struct MediaInfoNode {
-    pub publisher: MediaInfoNodePublisher,
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MediaInfoNodeState {
     pub title: String,
     pub subtitle: Option<String>,
     pub description: Option<String>,
@@ -35,9 +41,110 @@ pub struct MediaInfoNode {
     pub metadata: Option<String>,
 }
 
+#[derive(Debug)]
+pub struct MediaInfoNode {
+    pub publisher: MediaInfoNodePublisher,
+    pub state: MediaInfoNodeState,
+}
+
+impl MediaInfoNode {
+    /// Apply a parsed set command and return the `$target` and value publishes.
+    pub fn apply_set(&mut self, event: MediaInfoNodeSetEvents) -> Vec<homie5::client::Publish> {
+        match event {
+            MediaInfoNodeSetEvents::Progress(value) => {
+                self.state.progress = Some(value);
+                vec![
+                    self.publisher.progress_target(value),
+                    self.publisher.progress(value),
+                ]
+            }
+        }
+    }
+
+    /// Apply a value reported by the device and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::String(value)) if p == &MEDIA_INFO_NODE_TITLE_PROP_ID => {
+                self.state.title = value.clone();
+                vec![self.publisher.title(value)]
+            }
+            (p, HomieValue::String(value)) if p == &MEDIA_INFO_NODE_SUBTITLE_PROP_ID => {
+                self.state.subtitle = Some(value.clone());
+                vec![self.publisher.subtitle(value)]
+            }
+            (p, HomieValue::String(value)) if p == &MEDIA_INFO_NODE_DESCRIPTION_PROP_ID => {
+                self.state.description = Some(value.clone());
+                vec![self.publisher.description(value)]
+            }
+            (p, HomieValue::String(value)) if p == &MEDIA_INFO_NODE_ARTWORK_PROP_ID => {
+                self.state.artwork = Some(value.clone());
+                vec![self.publisher.artwork(value)]
+            }
+            (p, HomieValue::Integer(value)) if p == &MEDIA_INFO_NODE_PROGRESS_PROP_ID => {
+                self.state.progress = Some(*value);
+                vec![self.publisher.progress(*value)]
+            }
+            (p, HomieValue::Integer(value)) if p == &MEDIA_INFO_NODE_LENGTH_PROP_ID => {
+                self.state.length = Some(*value);
+                vec![self.publisher.length(*value)]
+            }
+            (p, HomieValue::Bool(value)) if p == &MEDIA_INFO_NODE_SEEKABLE_PROP_ID => {
+                self.state.seekable = Some(*value);
+                vec![self.publisher.seekable(*value)]
+            }
+            (p, HomieValue::JSON(value)) if p == &MEDIA_INFO_NODE_METADATA_PROP_ID => {
+                let value = value.to_string();
+                let publish = self.publisher.metadata_value(&value);
+                self.state.metadata = Some(value);
+                vec![publish]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            Some(self.publisher.title(&self.state.title)),
+            self.state
+                .subtitle
+                .as_deref()
+                .map(|value| self.publisher.subtitle(value)),
+            self.state
+                .description
+                .as_deref()
+                .map(|value| self.publisher.description(value)),
+            self.state
+                .artwork
+                .as_deref()
+                .map(|value| self.publisher.artwork(value)),
+            self.state
+                .progress
+                .map(|value| self.publisher.progress(value)),
+            self.state.length.map(|value| self.publisher.length(value)),
+            self.state
+                .seekable
+                .map(|value| self.publisher.seekable(value)),
+            self.state
+                .metadata
+                .as_deref()
+                .map(|value| self.publisher.metadata_value(value)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
 // ── Set events ──────────────────────────────────────────────────────────────
 
 #[derive(Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 pub enum MediaInfoNodeSetEvents {
     Progress(i64),
 }
@@ -45,6 +152,7 @@ pub enum MediaInfoNodeSetEvents {
 // ── Config ──────────────────────────────────────────────────────────────────
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 #[serde(default)]
 pub struct MediaInfoNodeConfig {
     pub subtitle: bool,
@@ -54,6 +162,12 @@ pub struct MediaInfoNodeConfig {
     pub length: bool,
     pub seekable: bool,
     pub metadata: bool,
+    /// Properties to publish as read-only; sets to them are rejected.
+    #[cfg_attr(
+        feature = "proptest",
+        proptest(strategy = "crate::arbitrary::homie_ids()")
+    )]
+    pub readonly: Vec<HomieID>,
 }
 
 impl Default for MediaInfoNodeConfig {
@@ -66,6 +180,7 @@ impl Default for MediaInfoNodeConfig {
             length: true,
             seekable: false,
             metadata: false,
+            readonly: Vec::new(),
         }
     }
 }
@@ -74,6 +189,7 @@ impl Default for MediaInfoNodeConfig {
 
 pub struct MediaInfoNodeBuilder {
     node_builder: NodeDescriptionBuilder,
+    config: MediaInfoNodeConfig,
 }
 
 impl MediaInfoNodeBuilder {
@@ -83,8 +199,12 @@ impl MediaInfoNodeBuilder {
             config,
         )
         .r#type(SMARTHOME_CAP_MEDIA_INFO);
+        let db = readonly_properties(db, &config.readonly);
 
-        Self { node_builder: db }
+        Self {
+            node_builder: db,
+            config: config.clone(),
+        }
     }
 
     fn build_node(
@@ -161,10 +281,23 @@ impl MediaInfoNodeBuilder {
         self
     }
 
+    /// Append a subtype suffix to the node type (`media-info-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::MediaInfo.with_subtype(subtype));
+        self
+    }
+
     pub fn build(self) -> HomieNodeDescription {
         self.node_builder.build()
     }
 
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &MediaInfoNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
     pub fn build_with_publisher(
         self,
         node_id: HomieID,
@@ -179,6 +312,7 @@ impl MediaInfoNodeBuilder {
                     node_id,
                 ),
                 client.clone(),
+                &self.config,
             ),
         )
     }
@@ -190,6 +324,7 @@ impl MediaInfoNodeBuilder {
 pub struct MediaInfoNodePublisher {
     client: Homie5DeviceProtocol,
     node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
     title_prop: HomieID,
     subtitle_prop: HomieID,
     description_prop: HomieID,
@@ -201,9 +336,11 @@ pub struct MediaInfoNodePublisher {
 }
 
 impl MediaInfoNodePublisher {
-    pub fn new(node: NodeRef, client: Homie5DeviceProtocol) -> Self {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: &MediaInfoNodeConfig) -> Self {
+        let metadata = property_metadata(&MediaInfoNodeBuilder::new(config).build());
         Self {
             node,
+            metadata,
             client,
             title_prop: MEDIA_INFO_NODE_TITLE_PROP_ID,
             subtitle_prop: MEDIA_INFO_NODE_SUBTITLE_PROP_ID,
@@ -245,6 +382,15 @@ impl MediaInfoNodePublisher {
         )
     }
 
+    pub fn progress_target(&self, value: i64) -> homie5::client::Publish {
+        self.client.publish_target(
+            self.node.node_id(),
+            &self.progress_prop,
+            value.to_string(),
+            true,
+        )
+    }
+
     pub fn length(&self, value: i64) -> homie5::client::Publish {
         self.client.publish_value(
             self.node.node_id(),
@@ -263,12 +409,41 @@ impl MediaInfoNodePublisher {
         )
     }
 
-    pub fn metadata(&self, value: &str) -> homie5::client::Publish {
+    /// Publish the `metadata` property. Named apart from
+    /// [`SmarthomePublisher::metadata`], which looks up property metadata.
+    pub fn metadata_value(&self, value: &str) -> homie5::client::Publish {
         self.client
             .publish_value(self.node.node_id(), &self.metadata_prop, value, true)
     }
 }
 
+impl SmarthomePublisher for MediaInfoNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::MediaInfo
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![
+            self.title_prop.clone(),
+            self.subtitle_prop.clone(),
+            self.description_prop.clone(),
+            self.artwork_prop.clone(),
+            self.progress_prop.clone(),
+            self.length_prop.clone(),
+            self.seekable_prop.clone(),
+            self.metadata_prop.clone(),
+        ]
+    }
+}
+
 impl SetCommandParser for MediaInfoNodePublisher {
     type Event = MediaInfoNodeSetEvents;
 
@@ -278,6 +453,10 @@ impl SetCommandParser for MediaInfoNodePublisher {
         desc: &HomieDeviceDescription,
         set_value: &str,
     ) -> ParseOutcome<Self::Event> {
+        if let Some(outcome) = reject_readonly(&self.node, property, desc, set_value) {
+            return outcome;
+        }
+
         let property_id = property.prop_id().to_string();
 
         if property.match_with_node(&self.node, &self.progress_prop) {
@@ -1,5 +1,5 @@
 use core::fmt;
-use std::str::FromStr;
+use std::{collections::BTreeMap, str::FromStr};
 
 use homie5::{
     Homie5DeviceProtocol, Homie5Message, Homie5ProtocolError, HomieID, HomieValue, NodeRef,
@@ -12,7 +12,9 @@ use homie5::{
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    ParseError, ParseErrorKind, ParseOutcome, SMARTHOME_CAP_MEDIAPLAYER, SetCommandParser,
+    ConfigError, ParseError, ParseErrorKind, ParseOutcome, PropertyMetadata,
+    SMARTHOME_CAP_MEDIAPLAYER, SetCommandParser, SmarthomePublisher, SmarthomeType,
+    property_metadata, readonly_properties, reject_readonly, validate_enum_format,
 };
 
 pub const MEDIAPLAYER_NODE_DEFAULT_ID: HomieID = HomieID::new_const("mediaplayer");
@@ -21,10 +23,12 @@ pub const MEDIAPLAYER_NODE_ACTION_PROP_ID: HomieID = HomieID::new_const("action"
 pub const MEDIAPLAYER_NODE_STATE_PROP_ID: HomieID = HomieID::new_const("state");
 pub const MEDIAPLAYER_NODE_SHUFFLE_PROP_ID: HomieID = HomieID::new_const("shuffle");
 pub const MEDIAPLAYER_NODE_REPEAT_PROP_ID: HomieID = HomieID::new_const("repeat");
+pub const MEDIAPLAYER_NODE_SOURCE_PROP_ID: HomieID = HomieID::new_const("source");
 
 // ── Actions ─────────────────────────────────────────────────────────────────
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 pub enum MediaplayerAction {
     Play,
     Pause,
@@ -73,10 +77,12 @@ impl FromStr for MediaplayerAction {
 
 // ── Play state ──────────────────────────────────────────────────────────────
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum MediaplayerState {
     Playing,
     Paused,
+    #[default]
     Stopped,
 }
 
@@ -96,9 +102,23 @@ impl fmt::Display for MediaplayerState {
     }
 }
 
+impl FromStr for MediaplayerState {
+    type Err = Homie5ProtocolError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "playing" => Ok(Self::Playing),
+            "paused" => Ok(Self::Paused),
+            "stopped" => Ok(Self::Stopped),
+            _ => Err(Homie5ProtocolError::InvalidPayload),
+        }
+    }
+}
+
 // ── Control state (tri-state for shuffle/repeat) ────────────────────────────
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+#[serde(rename_all = "kebab-case")]
 pub enum ControlState {
     On,
     Off,
@@ -137,26 +157,133 @@ const CONTROL_STATE_FORMAT: [&str; 3] = ["on", "off", "disabled"];
 
 // ── Node (state) ────────────────────────────────────────────────────────────
 
-#[derive(Debug)]
-pub struct MediaplayerNode {
-    pub publisher: MediaplayerNodePublisher,
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MediaplayerNodeState {
     pub state: MediaplayerState,
     pub shuffle: Option<ControlState>,
     pub repeat: Option<ControlState>,
+    pub source: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct MediaplayerNode {
+    pub publisher: MediaplayerNodePublisher,
+    pub state: MediaplayerNodeState,
+}
+
+impl MediaplayerNode {
+    /// Apply a parsed set command and return the resulting publishes.
+    /// `play`, `pause` and `stop` update the play state; the remaining
+    /// actions are left to the device to report back.
+    pub fn apply_set(&mut self, event: MediaplayerNodeSetEvents) -> Vec<homie5::client::Publish> {
+        match event {
+            MediaplayerNodeSetEvents::Action(action) => {
+                let state = match action {
+                    MediaplayerAction::Play => MediaplayerState::Playing,
+                    MediaplayerAction::Pause => MediaplayerState::Paused,
+                    MediaplayerAction::Stop => MediaplayerState::Stopped,
+                    _ => return Vec::new(),
+                };
+                self.state.state = state;
+                vec![self.publisher.state(state)]
+            }
+            MediaplayerNodeSetEvents::Shuffle(value) => {
+                self.state.shuffle = Some(value);
+                vec![
+                    self.publisher.shuffle_target(value),
+                    self.publisher.shuffle(value),
+                ]
+            }
+            MediaplayerNodeSetEvents::Repeat(value) => {
+                self.state.repeat = Some(value);
+                vec![
+                    self.publisher.repeat_target(value),
+                    self.publisher.repeat(value),
+                ]
+            }
+            MediaplayerNodeSetEvents::Source(value) => {
+                let publishes = vec![
+                    self.publisher.source_target(&value),
+                    self.publisher.source(&value),
+                ];
+                self.state.source = Some(value);
+                publishes
+            }
+        }
+    }
+
+    /// Apply a value reported by the device and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Enum(value)) if p == &MEDIAPLAYER_NODE_STATE_PROP_ID => {
+                let Ok(state) = value.parse::<MediaplayerState>() else {
+                    return Vec::new();
+                };
+                self.state.state = state;
+                vec![self.publisher.state(state)]
+            }
+            (p, HomieValue::Enum(value)) if p == &MEDIAPLAYER_NODE_SHUFFLE_PROP_ID => {
+                let Ok(shuffle) = value.parse::<ControlState>() else {
+                    return Vec::new();
+                };
+                self.state.shuffle = Some(shuffle);
+                vec![self.publisher.shuffle(shuffle)]
+            }
+            (p, HomieValue::Enum(value)) if p == &MEDIAPLAYER_NODE_REPEAT_PROP_ID => {
+                let Ok(repeat) = value.parse::<ControlState>() else {
+                    return Vec::new();
+                };
+                self.state.repeat = Some(repeat);
+                vec![self.publisher.repeat(repeat)]
+            }
+            (p, HomieValue::Enum(value)) if p == &MEDIAPLAYER_NODE_SOURCE_PROP_ID => {
+                self.state.source = Some(value.clone());
+                vec![self.publisher.source(value)]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            Some(self.publisher.state(self.state.state)),
+            self.state
+                .shuffle
+                .map(|value| self.publisher.shuffle(value)),
+            self.state.repeat.map(|value| self.publisher.repeat(value)),
+            self.state
+                .source
+                .as_deref()
+                .map(|value| self.publisher.source(value)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
 }
 
 // ── Set events ──────────────────────────────────────────────────────────────
 
 #[derive(Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 pub enum MediaplayerNodeSetEvents {
     Action(MediaplayerAction),
     Shuffle(ControlState),
     Repeat(ControlState),
+    Source(String),
 }
 
 // ── Config ──────────────────────────────────────────────────────────────────
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 #[serde(default)]
 pub struct MediaplayerNodeConfig {
     pub next: bool,
@@ -166,6 +293,19 @@ pub struct MediaplayerNodeConfig {
     pub stop: bool,
     pub shuffle: bool,
     pub repeat: bool,
+    /// Selectable input sources (e.g. `hdmi1`, `tv`, `spotify`). The `source`
+    /// property is only added when this list is not empty.
+    #[cfg_attr(
+        feature = "proptest",
+        proptest(strategy = "crate::arbitrary::enum_values()")
+    )]
+    pub sources: Vec<String>,
+    /// Properties to publish as read-only; sets to them are rejected.
+    #[cfg_attr(
+        feature = "proptest",
+        proptest(strategy = "crate::arbitrary::homie_ids()")
+    )]
+    pub readonly: Vec<HomieID>,
 }
 
 impl Default for MediaplayerNodeConfig {
@@ -178,11 +318,21 @@ impl Default for MediaplayerNodeConfig {
             stop: true,
             shuffle: true,
             repeat: true,
+            sources: Vec::new(),
+            readonly: Vec::new(),
         }
     }
 }
 
 impl MediaplayerNodeConfig {
+    /// Check the config-provided enum values before building the node.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if !self.sources.is_empty() {
+            validate_enum_format(&MEDIAPLAYER_NODE_SOURCE_PROP_ID, &self.sources)?;
+        }
+        Ok(())
+    }
+
     fn build_action_format(&self) -> Vec<String> {
         let mut actions = vec!["play".to_owned(), "pause".to_owned()];
         if self.stop {
@@ -208,6 +358,7 @@ impl MediaplayerNodeConfig {
 
 pub struct MediaplayerNodeBuilder {
     node_builder: NodeDescriptionBuilder,
+    config: MediaplayerNodeConfig,
 }
 
 impl MediaplayerNodeBuilder {
@@ -217,8 +368,12 @@ impl MediaplayerNodeBuilder {
             config,
         )
         .r#type(SMARTHOME_CAP_MEDIAPLAYER);
+        let db = readonly_properties(db, &config.readonly);
 
-        Self { node_builder: db }
+        Self {
+            node_builder: db,
+            config: config.clone(),
+        }
     }
 
     fn build_node(
@@ -259,6 +414,18 @@ impl MediaplayerNodeBuilder {
                 .retained(true)
                 .build()
         })
+        .add_property_cond(
+            MEDIAPLAYER_NODE_SOURCE_PROP_ID,
+            !config.sources.is_empty(),
+            || {
+                PropertyDescriptionBuilder::enumeration(config.sources.iter().map(|s| s.as_str()))
+                    .unwrap()
+                    .name("Input source")
+                    .settable(true)
+                    .retained(true)
+                    .build()
+            },
+        )
     }
 
     pub fn name<S: Into<String>>(mut self, name: impl Into<Option<S>>) -> Self {
@@ -266,10 +433,23 @@ impl MediaplayerNodeBuilder {
         self
     }
 
+    /// Append a subtype suffix to the node type (`mediaplayer-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::Mediaplayer.with_subtype(subtype));
+        self
+    }
+
     pub fn build(self) -> HomieNodeDescription {
         self.node_builder.build()
     }
 
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &MediaplayerNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
     pub fn build_with_publisher(
         self,
         node_id: HomieID,
@@ -284,6 +464,7 @@ impl MediaplayerNodeBuilder {
                     node_id,
                 ),
                 client.clone(),
+                &self.config,
             ),
         )
     }
@@ -295,21 +476,30 @@ impl MediaplayerNodeBuilder {
 pub struct MediaplayerNodePublisher {
     client: Homie5DeviceProtocol,
     node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
     action_prop: HomieID,
     state_prop: HomieID,
     shuffle_prop: HomieID,
     repeat_prop: HomieID,
+    source_prop: HomieID,
 }
 
 impl MediaplayerNodePublisher {
-    pub fn new(node: NodeRef, client: Homie5DeviceProtocol) -> Self {
+    pub fn new(
+        node: NodeRef,
+        client: Homie5DeviceProtocol,
+        config: &MediaplayerNodeConfig,
+    ) -> Self {
+        let metadata = property_metadata(&MediaplayerNodeBuilder::new(config).build());
         Self {
             node,
+            metadata,
             client,
             action_prop: MEDIAPLAYER_NODE_ACTION_PROP_ID,
             state_prop: MEDIAPLAYER_NODE_STATE_PROP_ID,
             shuffle_prop: MEDIAPLAYER_NODE_SHUFFLE_PROP_ID,
             repeat_prop: MEDIAPLAYER_NODE_REPEAT_PROP_ID,
+            source_prop: MEDIAPLAYER_NODE_SOURCE_PROP_ID,
         }
     }
 
@@ -327,10 +517,58 @@ impl MediaplayerNodePublisher {
         )
     }
 
+    pub fn shuffle_target(&self, value: ControlState) -> homie5::client::Publish {
+        self.client.publish_target(
+            self.node.node_id(),
+            &self.shuffle_prop,
+            value.as_str(),
+            true,
+        )
+    }
+
     pub fn repeat(&self, value: ControlState) -> homie5::client::Publish {
         self.client
             .publish_value(self.node.node_id(), &self.repeat_prop, value.as_str(), true)
     }
+
+    pub fn repeat_target(&self, value: ControlState) -> homie5::client::Publish {
+        self.client
+            .publish_target(self.node.node_id(), &self.repeat_prop, value.as_str(), true)
+    }
+
+    pub fn source(&self, value: &str) -> homie5::client::Publish {
+        self.client
+            .publish_value(self.node.node_id(), &self.source_prop, value, true)
+    }
+
+    pub fn source_target(&self, value: &str) -> homie5::client::Publish {
+        self.client
+            .publish_target(self.node.node_id(), &self.source_prop, value, true)
+    }
+}
+
+impl SmarthomePublisher for MediaplayerNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::Mediaplayer
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![
+            self.action_prop.clone(),
+            self.state_prop.clone(),
+            self.shuffle_prop.clone(),
+            self.repeat_prop.clone(),
+            self.source_prop.clone(),
+        ]
+    }
 }
 
 impl SetCommandParser for MediaplayerNodePublisher {
@@ -342,6 +580,10 @@ impl SetCommandParser for MediaplayerNodePublisher {
         desc: &HomieDeviceDescription,
         set_value: &str,
     ) -> ParseOutcome<Self::Event> {
+        if let Some(outcome) = reject_readonly(&self.node, property, desc, set_value) {
+            return outcome;
+        }
+
         let property_id = property.prop_id().to_string();
 
         if property.match_with_node(&self.node, &self.action_prop) {
@@ -422,6 +664,27 @@ impl SetCommandParser for MediaplayerNodePublisher {
                     ParseErrorKind::InvalidHomieValue,
                 )),
             }
+        } else if property.match_with_node(&self.node, &self.source_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Enum(value)) => {
+                    ParseOutcome::Parsed(MediaplayerNodeSetEvents::Source(value))
+                }
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property.prop_id().to_string(),
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
         } else {
             ParseOutcome::NoMatch
         }
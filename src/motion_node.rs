@@ -1,49 +1,444 @@
+use chrono::prelude::*;
+use std::collections::BTreeMap;
+
 use homie5::{
-    Homie5DeviceProtocol, HomieID, NodeRef,
+    HOMIE_UNIT_SECONDS, Homie5DeviceProtocol, Homie5Message, HomieID, HomieValue, NodeRef,
+    PropertyRef,
     device_description::{
-        HomieNodeDescription, NodeDescriptionBuilder, PropertyDescriptionBuilder,
+        HomieDeviceDescription, HomieNodeDescription, IntegerRange, NodeDescriptionBuilder,
+        PropertyDescriptionBuilder,
     },
 };
+use serde::{Deserialize, Serialize};
 
-use crate::SMARTHOME_CAP_MOTION;
+use crate::{
+    ConfigError, ParseError, ParseErrorKind, ParseOutcome, PropertyMetadata, SMARTHOME_CAP_MOTION,
+    SetCommandParser, SmarthomePublisher, SmarthomeType, property_metadata, readonly_properties,
+    reject_readonly, smarthome_node_config, validate_enum_format,
+};
 
 pub const MOTION_NODE_DEFAULT_ID: HomieID = HomieID::new_const("motion");
 pub const MOTION_NODE_DEFAULT_NAME: &str = "Motion sensor";
 pub const MOTION_NODE_MOTION_PROP_ID: HomieID = HomieID::new_const("motion");
+pub const MOTION_NODE_OCCUPANCY_PROP_ID: HomieID = HomieID::new_const("occupancy");
+pub const MOTION_NODE_SENSITIVITY_PROP_ID: HomieID = HomieID::new_const("sensitivity");
+pub const MOTION_NODE_BLIND_TIME_PROP_ID: HomieID = HomieID::new_const("blind-time");
+pub const MOTION_NODE_DETECTION_ZONE_PROP_ID: HomieID = HomieID::new_const("detection-zone");
+pub const MOTION_NODE_PET_IMMUNE_PROP_ID: HomieID = HomieID::new_const("pet-immune");
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+pub enum MotionNodeSetEvents {
+    SensitivityLevel(String),
+    SensitivityValue(i64),
+    BlindTime(i64),
+    DetectionZone(String),
+    DetectionZoneValue(i64),
+    PetImmune(bool),
+}
+
+/// Format of the settable `sensitivity` property: named levels or a numeric range.
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+#[serde(rename_all = "kebab-case")]
+pub enum MotionNodeSensitivity {
+    #[default]
+    None,
+    Levels(
+        #[cfg_attr(
+            feature = "proptest",
+            proptest(strategy = "crate::arbitrary::enum_format()")
+        )]
+        Vec<String>,
+    ),
+    Range(
+        #[cfg_attr(
+            feature = "proptest",
+            proptest(strategy = "crate::arbitrary::integer_range()")
+        )]
+        IntegerRange,
+    ),
+}
+
+impl MotionNodeSensitivity {
+    /// The common `low`/`medium`/`high` level set.
+    pub fn default_levels() -> Self {
+        Self::Levels(vec!["low".into(), "medium".into(), "high".into()])
+    }
+}
+
+/// Format of the settable `detection-zone` property: named zones or a numeric
+/// range, e.g. the detection distance of a PIR sensor.
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+#[serde(rename_all = "kebab-case")]
+pub enum MotionNodeDetectionZone {
+    #[default]
+    None,
+    Zones(
+        #[cfg_attr(
+            feature = "proptest",
+            proptest(strategy = "crate::arbitrary::enum_format()")
+        )]
+        Vec<String>,
+    ),
+    Range(
+        #[cfg_attr(
+            feature = "proptest",
+            proptest(strategy = "crate::arbitrary::integer_range()")
+        )]
+        IntegerRange,
+    ),
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MotionNodeState {
+    pub motion: bool,
+    pub occupancy: Option<bool>,
+    pub sensitivity_level: Option<String>,
+    pub sensitivity_value: Option<i64>,
+    pub blind_time: Option<i64>,
+    pub detection_zone: Option<String>,
+    pub detection_zone_value: Option<i64>,
+    pub pet_immune: Option<bool>,
+}
 
 #[derive(Debug)]
 pub struct MotionNode {
     pub publisher: MotionNodePublisher,
-    pub motion: bool,
+    pub state: MotionNodeState,
+}
+
+impl MotionNode {
+    /// Apply a parsed set command and return the `$target` and value publishes.
+    pub fn apply_set(&mut self, event: MotionNodeSetEvents) -> Vec<homie5::client::Publish> {
+        match event {
+            MotionNodeSetEvents::SensitivityLevel(value) => {
+                let publishes = [
+                    self.publisher.sensitivity_level_target(&value),
+                    self.publisher.sensitivity_level(&value),
+                ]
+                .into_iter()
+                .flatten()
+                .collect();
+                self.state.sensitivity_level = Some(value);
+                publishes
+            }
+            MotionNodeSetEvents::SensitivityValue(value) => {
+                self.state.sensitivity_value = Some(value);
+                [
+                    self.publisher.sensitivity_value_target(value),
+                    self.publisher.sensitivity_value(value),
+                ]
+                .into_iter()
+                .flatten()
+                .collect()
+            }
+            MotionNodeSetEvents::BlindTime(value) => {
+                self.state.blind_time = Some(value);
+                [
+                    self.publisher.blind_time_target(value),
+                    self.publisher.blind_time(value),
+                ]
+                .into_iter()
+                .flatten()
+                .collect()
+            }
+            MotionNodeSetEvents::DetectionZone(value) => {
+                let publishes = [
+                    self.publisher.detection_zone_target(&value),
+                    self.publisher.detection_zone(&value),
+                ]
+                .into_iter()
+                .flatten()
+                .collect();
+                self.state.detection_zone = Some(value);
+                publishes
+            }
+            MotionNodeSetEvents::DetectionZoneValue(value) => {
+                self.state.detection_zone_value = Some(value);
+                [
+                    self.publisher.detection_zone_value_target(value),
+                    self.publisher.detection_zone_value(value),
+                ]
+                .into_iter()
+                .flatten()
+                .collect()
+            }
+            MotionNodeSetEvents::PetImmune(value) => {
+                self.state.pet_immune = Some(value);
+                [
+                    self.publisher.pet_immune_target(value),
+                    self.publisher.pet_immune(value),
+                ]
+                .into_iter()
+                .flatten()
+                .collect()
+            }
+        }
+    }
+
+    /// Apply a value reported by the device and return the matching publishes.
+    /// Motion readings go through [`MotionNodePublisher::motion_event`] at the
+    /// current system time, so occupancy is tracked as well; call that
+    /// directly to supply the event time.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Bool(value)) if p == &MOTION_NODE_MOTION_PROP_ID => {
+                self.state.motion = *value;
+                let publishes = self.publisher.motion_event(*value, Utc::now());
+                if self.publisher.config.occupancy {
+                    self.state.occupancy = Some(self.publisher.occupied);
+                }
+                publishes
+            }
+            (p, HomieValue::Bool(value)) if p == &MOTION_NODE_OCCUPANCY_PROP_ID => {
+                self.state.occupancy = Some(*value);
+                self.publisher.occupancy(*value).into_iter().collect()
+            }
+            (p, HomieValue::Enum(value)) if p == &MOTION_NODE_SENSITIVITY_PROP_ID => {
+                self.state.sensitivity_level = Some(value.clone());
+                self.publisher
+                    .sensitivity_level(value)
+                    .into_iter()
+                    .collect()
+            }
+            (p, HomieValue::Integer(value)) if p == &MOTION_NODE_SENSITIVITY_PROP_ID => {
+                self.state.sensitivity_value = Some(*value);
+                self.publisher
+                    .sensitivity_value(*value)
+                    .into_iter()
+                    .collect()
+            }
+            (p, HomieValue::Integer(value)) if p == &MOTION_NODE_BLIND_TIME_PROP_ID => {
+                self.state.blind_time = Some(*value);
+                self.publisher.blind_time(*value).into_iter().collect()
+            }
+            (p, HomieValue::Enum(value)) if p == &MOTION_NODE_DETECTION_ZONE_PROP_ID => {
+                self.state.detection_zone = Some(value.clone());
+                self.publisher.detection_zone(value).into_iter().collect()
+            }
+            (p, HomieValue::Integer(value)) if p == &MOTION_NODE_DETECTION_ZONE_PROP_ID => {
+                self.state.detection_zone_value = Some(*value);
+                self.publisher
+                    .detection_zone_value(*value)
+                    .into_iter()
+                    .collect()
+            }
+            (p, HomieValue::Bool(value)) if p == &MOTION_NODE_PET_IMMUNE_PROP_ID => {
+                self.state.pet_immune = Some(*value);
+                self.publisher.pet_immune(*value).into_iter().collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            Some(self.publisher.motion(self.state.motion)),
+            self.state
+                .occupancy
+                .and_then(|value| self.publisher.occupancy(value)),
+            self.state
+                .sensitivity_level
+                .as_deref()
+                .and_then(|value| self.publisher.sensitivity_level(value)),
+            self.state
+                .sensitivity_value
+                .and_then(|value| self.publisher.sensitivity_value(value)),
+            self.state
+                .blind_time
+                .and_then(|value| self.publisher.blind_time(value)),
+            self.state
+                .detection_zone
+                .as_deref()
+                .and_then(|value| self.publisher.detection_zone(value)),
+            self.state
+                .detection_zone_value
+                .and_then(|value| self.publisher.detection_zone_value(value)),
+            self.state
+                .pet_immune
+                .and_then(|value| self.publisher.pet_immune(value)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+smarthome_node_config! {
+    #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+    #[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+    #[serde(default)]
+    pub struct MotionNodeConfig for MotionNodePublisher {
+        /// Expose a debounced `occupancy` property derived from motion events.
+        pub occupancy: bool => MOTION_NODE_OCCUPANCY_PROP_ID, occupancy(bool), |_config| {
+            PropertyDescriptionBuilder::boolean()
+                .name("Occupancy")
+                .boolean_labels("unoccupied", "occupied")
+                .retained(true)
+                .settable(false)
+                .build()
+        },
+        /// Seconds `occupancy` stays true after the last motion event.
+        pub occupancy_hold: u32,
+        pub sensitivity: MotionNodeSensitivity,
+        /// Expose the sensor's re-trigger blind time in seconds.
+        pub blind_time: bool,
+        /// Allow controllers to configure the blind time.
+        pub blind_time_settable: bool,
+        pub detection_zone: MotionNodeDetectionZone,
+        /// Expose a settable `pet-immune` switch for PIR sensors that can ignore
+        /// small animals.
+        pub pet_immune: bool,
+        /// Properties to publish as read-only; sets to them are rejected.
+        #[cfg_attr(
+            feature = "proptest",
+            proptest(strategy = "crate::arbitrary::homie_ids()")
+        )]
+        pub readonly: Vec<HomieID>,
+    }
+}
+
+impl Default for MotionNodeConfig {
+    fn default() -> Self {
+        Self {
+            occupancy: false,
+            occupancy_hold: 120,
+            sensitivity: MotionNodeSensitivity::None,
+            blind_time: false,
+            blind_time_settable: false,
+            detection_zone: MotionNodeDetectionZone::None,
+            pet_immune: false,
+            readonly: Vec::new(),
+        }
+    }
+}
+
+impl MotionNodeConfig {
+    /// Check the config-provided enum values before building the node.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if let MotionNodeSensitivity::Levels(levels) = &self.sensitivity {
+            validate_enum_format(&MOTION_NODE_SENSITIVITY_PROP_ID, levels)?;
+        }
+        if let MotionNodeDetectionZone::Zones(zones) = &self.detection_zone {
+            validate_enum_format(&MOTION_NODE_DETECTION_ZONE_PROP_ID, zones)?;
+        }
+        Ok(())
+    }
 }
 
 pub struct MotionNodeBuilder {
     node_builder: NodeDescriptionBuilder,
+    config: MotionNodeConfig,
 }
 
 impl Default for MotionNodeBuilder {
     fn default() -> Self {
-        let db = Self::build_node(NodeDescriptionBuilder::new().name(MOTION_NODE_DEFAULT_NAME))
-            .r#type(SMARTHOME_CAP_MOTION);
-
-        Self { node_builder: db }
+        Self::new(&MotionNodeConfig::default())
     }
 }
 
 impl MotionNodeBuilder {
-    pub fn new() -> Self {
-        Default::default()
+    pub fn new(config: &MotionNodeConfig) -> Self {
+        let db = Self::build_node(
+            NodeDescriptionBuilder::new().name(MOTION_NODE_DEFAULT_NAME),
+            config,
+        )
+        .r#type(SMARTHOME_CAP_MOTION);
+        let db = readonly_properties(db, &config.readonly);
+
+        Self {
+            node_builder: db,
+            config: config.clone(),
+        }
     }
 
-    fn build_node(db: NodeDescriptionBuilder) -> NodeDescriptionBuilder {
-        db.add_property(
-            MOTION_NODE_MOTION_PROP_ID,
-            PropertyDescriptionBuilder::boolean()
-                .name("Motion detected")
-                .boolean_labels("no-motion", "motion")
-                .retained(true)
-                .settable(false)
-                .build(),
+    fn build_node(db: NodeDescriptionBuilder, config: &MotionNodeConfig) -> NodeDescriptionBuilder {
+        config.add_optional_properties(
+            db.add_property(
+                MOTION_NODE_MOTION_PROP_ID,
+                PropertyDescriptionBuilder::boolean()
+                    .name("Motion detected")
+                    .boolean_labels("no-motion", "motion")
+                    .retained(true)
+                    .settable(false)
+                    .build(),
+            )
+            .add_property_cond(
+                MOTION_NODE_SENSITIVITY_PROP_ID,
+                config.sensitivity != MotionNodeSensitivity::None,
+                || match &config.sensitivity {
+                    MotionNodeSensitivity::Range(range) => PropertyDescriptionBuilder::integer()
+                        .integer_range(range.clone())
+                        .name("Sensitivity")
+                        .retained(true)
+                        .settable(true)
+                        .build(),
+                    MotionNodeSensitivity::Levels(levels) => {
+                        PropertyDescriptionBuilder::enumeration(levels)
+                            .unwrap()
+                            .name("Sensitivity")
+                            .retained(true)
+                            .settable(true)
+                            .build()
+                    }
+                    MotionNodeSensitivity::None => unreachable!(),
+                },
+            )
+            .add_property_cond(MOTION_NODE_BLIND_TIME_PROP_ID, config.blind_time, || {
+                PropertyDescriptionBuilder::integer()
+                    .name("Blind time")
+                    .unit(HOMIE_UNIT_SECONDS)
+                    .integer_range(IntegerRange {
+                        min: Some(0),
+                        max: None,
+                        step: None,
+                    })
+                    .retained(true)
+                    .settable(config.blind_time_settable)
+                    .build()
+            })
+            .add_property_cond(
+                MOTION_NODE_DETECTION_ZONE_PROP_ID,
+                config.detection_zone != MotionNodeDetectionZone::None,
+                || match &config.detection_zone {
+                    MotionNodeDetectionZone::Range(range) => PropertyDescriptionBuilder::integer()
+                        .integer_range(range.clone())
+                        .name("Detection zone")
+                        .retained(true)
+                        .settable(true)
+                        .build(),
+                    MotionNodeDetectionZone::Zones(zones) => {
+                        PropertyDescriptionBuilder::enumeration(zones)
+                            .unwrap()
+                            .name("Detection zone")
+                            .retained(true)
+                            .settable(true)
+                            .build()
+                    }
+                    MotionNodeDetectionZone::None => unreachable!(),
+                },
+            )
+            .add_property_cond(
+                MOTION_NODE_PET_IMMUNE_PROP_ID,
+                config.pet_immune,
+                || {
+                    PropertyDescriptionBuilder::boolean()
+                        .name("Pet immunity")
+                        .boolean_labels("off", "on")
+                        .retained(true)
+                        .settable(true)
+                        .build()
+                },
+            ),
         )
     }
 
@@ -52,10 +447,23 @@ impl MotionNodeBuilder {
         self
     }
 
+    /// Append a subtype suffix to the node type (`motion-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::Motion.with_subtype(subtype));
+        self
+    }
+
     pub fn build(self) -> HomieNodeDescription {
         self.node_builder.build()
     }
 
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &MotionNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
     pub fn build_with_publisher(
         self,
         node_id: HomieID,
@@ -70,6 +478,7 @@ impl MotionNodeBuilder {
                     node_id,
                 ),
                 client.clone(),
+                self.config,
             ),
         )
     }
@@ -79,15 +488,36 @@ impl MotionNodeBuilder {
 pub struct MotionNodePublisher {
     client: Homie5DeviceProtocol,
     node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
+    config: MotionNodeConfig,
     motion_prop: HomieID,
+    occupancy_prop: HomieID,
+    sensitivity_prop: HomieID,
+    blind_time_prop: HomieID,
+    detection_zone_prop: HomieID,
+    pet_immune_prop: HomieID,
+    motion_active: bool,
+    occupied: bool,
+    last_motion: Option<DateTime<Utc>>,
 }
 
 impl MotionNodePublisher {
-    pub fn new(node: NodeRef, client: Homie5DeviceProtocol) -> Self {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: MotionNodeConfig) -> Self {
+        let metadata = property_metadata(&MotionNodeBuilder::new(&config).build());
         Self {
             node,
+            metadata,
             client,
+            config,
             motion_prop: MOTION_NODE_MOTION_PROP_ID,
+            occupancy_prop: MOTION_NODE_OCCUPANCY_PROP_ID,
+            sensitivity_prop: MOTION_NODE_SENSITIVITY_PROP_ID,
+            blind_time_prop: MOTION_NODE_BLIND_TIME_PROP_ID,
+            detection_zone_prop: MOTION_NODE_DETECTION_ZONE_PROP_ID,
+            pet_immune_prop: MOTION_NODE_PET_IMMUNE_PROP_ID,
+            motion_active: false,
+            occupied: false,
+            last_motion: None,
         }
     }
 
@@ -99,4 +529,534 @@ impl MotionNodePublisher {
             true,
         )
     }
+
+    pub fn sensitivity_level(&self, value: &str) -> Option<homie5::client::Publish> {
+        let MotionNodeSensitivity::Levels(levels) = &self.config.sensitivity else {
+            return None;
+        };
+        if !levels.iter().any(|level| level == value) {
+            return None;
+        }
+        Some(
+            self.client
+                .publish_value(self.node.node_id(), &self.sensitivity_prop, value, true),
+        )
+    }
+
+    pub fn sensitivity_level_target(&self, value: &str) -> Option<homie5::client::Publish> {
+        let MotionNodeSensitivity::Levels(levels) = &self.config.sensitivity else {
+            return None;
+        };
+        if !levels.iter().any(|level| level == value) {
+            return None;
+        }
+        Some(
+            self.client
+                .publish_target(self.node.node_id(), &self.sensitivity_prop, value, true),
+        )
+    }
+
+    pub fn sensitivity_value(&self, value: i64) -> Option<homie5::client::Publish> {
+        if !matches!(self.config.sensitivity, MotionNodeSensitivity::Range(_)) {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.sensitivity_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+
+    pub fn sensitivity_value_target(&self, value: i64) -> Option<homie5::client::Publish> {
+        if !matches!(self.config.sensitivity, MotionNodeSensitivity::Range(_)) {
+            return None;
+        }
+        Some(self.client.publish_target(
+            self.node.node_id(),
+            &self.sensitivity_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+
+    pub fn blind_time(&self, value: i64) -> Option<homie5::client::Publish> {
+        if !self.config.blind_time {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.blind_time_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+
+    pub fn blind_time_target(&self, value: i64) -> Option<homie5::client::Publish> {
+        if !self.config.blind_time {
+            return None;
+        }
+        Some(self.client.publish_target(
+            self.node.node_id(),
+            &self.blind_time_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+
+    pub fn detection_zone(&self, value: &str) -> Option<homie5::client::Publish> {
+        let MotionNodeDetectionZone::Zones(zones) = &self.config.detection_zone else {
+            return None;
+        };
+        if !zones.iter().any(|zone| zone == value) {
+            return None;
+        }
+        Some(
+            self.client
+                .publish_value(self.node.node_id(), &self.detection_zone_prop, value, true),
+        )
+    }
+
+    pub fn detection_zone_target(&self, value: &str) -> Option<homie5::client::Publish> {
+        let MotionNodeDetectionZone::Zones(zones) = &self.config.detection_zone else {
+            return None;
+        };
+        if !zones.iter().any(|zone| zone == value) {
+            return None;
+        }
+        Some(self.client.publish_target(
+            self.node.node_id(),
+            &self.detection_zone_prop,
+            value,
+            true,
+        ))
+    }
+
+    pub fn detection_zone_value(&self, value: i64) -> Option<homie5::client::Publish> {
+        if !matches!(
+            self.config.detection_zone,
+            MotionNodeDetectionZone::Range(_)
+        ) {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.detection_zone_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+
+    pub fn detection_zone_value_target(&self, value: i64) -> Option<homie5::client::Publish> {
+        if !matches!(
+            self.config.detection_zone,
+            MotionNodeDetectionZone::Range(_)
+        ) {
+            return None;
+        }
+        Some(self.client.publish_target(
+            self.node.node_id(),
+            &self.detection_zone_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+
+    pub fn pet_immune(&self, value: bool) -> Option<homie5::client::Publish> {
+        if !self.config.pet_immune {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.pet_immune_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+
+    pub fn pet_immune_target(&self, value: bool) -> Option<homie5::client::Publish> {
+        if !self.config.pet_immune {
+            return None;
+        }
+        Some(self.client.publish_target(
+            self.node.node_id(),
+            &self.pet_immune_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+
+    /// Publish a motion reading and update the occupancy state.
+    ///
+    /// Returns the `motion` publish, followed by an `occupancy` publish when
+    /// the node becomes occupied. Every `true` event, and the `false` event
+    /// ending a motion, restarts the hold, so sensors that only ever report
+    /// `true` clear as well. The matching clear is produced by
+    /// [`Self::poll_occupancy`] once the hold time has elapsed.
+    pub fn motion_event(
+        &mut self,
+        value: bool,
+        now: DateTime<Utc>,
+    ) -> Vec<homie5::client::Publish> {
+        let mut publishes = vec![self.motion(value)];
+        if !self.config.occupancy {
+            return publishes;
+        }
+        if value || self.motion_active {
+            self.last_motion = Some(now);
+        }
+        self.motion_active = value;
+        if value && !self.occupied {
+            self.occupied = true;
+            publishes.extend(self.occupancy(true));
+        }
+        publishes
+    }
+
+    /// The point in time at which occupancy will clear, if it is pending.
+    pub fn occupancy_deadline(&self) -> Option<DateTime<Utc>> {
+        if !self.occupied {
+            return None;
+        }
+        let hold = chrono::TimeDelta::try_seconds(self.config.occupancy_hold.into())?;
+        self.last_motion?.checked_add_signed(hold)
+    }
+
+    /// Produce the delayed occupancy clear once the hold time has elapsed.
+    pub fn poll_occupancy(&mut self, now: DateTime<Utc>) -> Option<homie5::client::Publish> {
+        let deadline = self.occupancy_deadline()?;
+        if now < deadline {
+            return None;
+        }
+        self.occupied = false;
+        self.occupancy(false)
+    }
+}
+
+impl SmarthomePublisher for MotionNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::Motion
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![
+            self.motion_prop.clone(),
+            self.occupancy_prop.clone(),
+            self.sensitivity_prop.clone(),
+            self.blind_time_prop.clone(),
+            self.detection_zone_prop.clone(),
+            self.pet_immune_prop.clone(),
+        ]
+    }
+}
+
+impl SetCommandParser for MotionNodePublisher {
+    type Event = MotionNodeSetEvents;
+
+    fn parse_set(
+        &self,
+        property: &PropertyRef,
+        desc: &HomieDeviceDescription,
+        set_value: &str,
+    ) -> ParseOutcome<Self::Event> {
+        if let Some(outcome) = reject_readonly(&self.node, property, desc, set_value) {
+            return outcome;
+        }
+
+        let is_blind_time = property.match_with_node(&self.node, &self.blind_time_prop);
+        let is_detection_zone = property.match_with_node(&self.node, &self.detection_zone_prop);
+        let is_pet_immune = property.match_with_node(&self.node, &self.pet_immune_prop);
+        if !is_blind_time
+            && !is_detection_zone
+            && !is_pet_immune
+            && !property.match_with_node(&self.node, &self.sensitivity_prop)
+        {
+            return ParseOutcome::NoMatch;
+        }
+
+        let Some(parsed) = desc.with_property(property, |prop_desc| {
+            HomieValue::parse(set_value, prop_desc)
+        }) else {
+            return ParseOutcome::Invalid(ParseError::new(
+                property.prop_id().to_string(),
+                set_value,
+                ParseErrorKind::MissingPropertyDescription,
+            ));
+        };
+
+        match parsed {
+            Ok(HomieValue::Bool(value)) if is_pet_immune => {
+                ParseOutcome::Parsed(MotionNodeSetEvents::PetImmune(value))
+            }
+            Ok(HomieValue::Enum(value)) if is_detection_zone => {
+                ParseOutcome::Parsed(MotionNodeSetEvents::DetectionZone(value))
+            }
+            Ok(HomieValue::Integer(value)) if is_detection_zone => {
+                ParseOutcome::Parsed(MotionNodeSetEvents::DetectionZoneValue(value))
+            }
+            Ok(HomieValue::Enum(value)) => {
+                ParseOutcome::Parsed(MotionNodeSetEvents::SensitivityLevel(value))
+            }
+            Ok(HomieValue::Integer(value)) if is_blind_time => {
+                ParseOutcome::Parsed(MotionNodeSetEvents::BlindTime(value))
+            }
+            Ok(HomieValue::Integer(value)) => {
+                ParseOutcome::Parsed(MotionNodeSetEvents::SensitivityValue(value))
+            }
+            _ => ParseOutcome::Invalid(ParseError::new(
+                property.prop_id().to_string(),
+                set_value,
+                ParseErrorKind::InvalidHomieValue,
+            )),
+        }
+    }
+
+    fn parse_set_event(
+        &self,
+        desc: &HomieDeviceDescription,
+        event: &Homie5Message,
+    ) -> ParseOutcome<Self::Event> {
+        match event {
+            Homie5Message::PropertySet {
+                property,
+                set_value,
+            } => self.parse_set(property, desc, set_value),
+            _ => ParseOutcome::Invalid(ParseError::new(
+                self.sensitivity_prop.to_string(),
+                "",
+                ParseErrorKind::UnexpectedMessageType,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use homie5::{HomieDomain, device_description::DeviceDescriptionBuilder};
+
+    use super::*;
+
+    #[test]
+    fn occupancy_holds_after_motion_ends() {
+        let config = MotionNodeConfig {
+            occupancy: true,
+            occupancy_hold: 60,
+            ..MotionNodeConfig::default()
+        };
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let (_, mut publisher) =
+            MotionNodeBuilder::new(&config).build_with_publisher(MOTION_NODE_DEFAULT_ID, &client);
+        let start = Utc::now();
+
+        let publishes = publisher.motion_event(true, start);
+        assert_eq!(publishes.len(), 2);
+        assert_eq!(publishes[1].payload, b"true".to_vec());
+        assert_eq!(
+            publisher.occupancy_deadline(),
+            Some(start + chrono::Duration::seconds(60))
+        );
+
+        let ended = start + chrono::Duration::seconds(10);
+        assert_eq!(publisher.motion_event(false, ended).len(), 1);
+        assert_eq!(
+            publisher.occupancy_deadline(),
+            Some(ended + chrono::Duration::seconds(60))
+        );
+        assert!(
+            publisher
+                .poll_occupancy(ended + chrono::Duration::seconds(59))
+                .is_none()
+        );
+
+        let cleared = publisher
+            .poll_occupancy(ended + chrono::Duration::seconds(60))
+            .expect("occupancy must clear after hold time");
+        assert_eq!(cleared.payload, b"false".to_vec());
+        assert!(publisher.occupancy_deadline().is_none());
+    }
+
+    #[test]
+    fn occupancy_clears_for_sensors_reporting_only_motion() {
+        let config = MotionNodeConfig {
+            occupancy: true,
+            occupancy_hold: 60,
+            ..MotionNodeConfig::default()
+        };
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let (_, mut publisher) =
+            MotionNodeBuilder::new(&config).build_with_publisher(MOTION_NODE_DEFAULT_ID, &client);
+        let start = Utc::now();
+
+        assert_eq!(publisher.motion_event(true, start).len(), 2);
+        let retriggered = start + chrono::Duration::seconds(30);
+        assert_eq!(publisher.motion_event(true, retriggered).len(), 1);
+        assert!(
+            publisher
+                .poll_occupancy(start + chrono::Duration::seconds(60))
+                .is_none()
+        );
+
+        let cleared = publisher
+            .poll_occupancy(start + chrono::Duration::hours(1))
+            .expect("occupancy must clear after the hold following the last motion");
+        assert_eq!(cleared.payload, b"false".to_vec());
+
+        let config = MotionNodeConfig {
+            occupancy_hold: u32::MAX,
+            ..config
+        };
+        let (_, mut publisher) =
+            MotionNodeBuilder::new(&config).build_with_publisher(MOTION_NODE_DEFAULT_ID, &client);
+        publisher.motion_event(true, start);
+        assert!(publisher.occupancy_deadline().is_some());
+    }
+
+    #[test]
+    fn parses_sensitivity_for_levels_and_range() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+
+        for (sensitivity, input, expected) in [
+            (
+                MotionNodeSensitivity::default_levels(),
+                "high",
+                MotionNodeSetEvents::SensitivityLevel("high".to_string()),
+            ),
+            (
+                MotionNodeSensitivity::Range(IntegerRange {
+                    min: Some(1),
+                    max: Some(10),
+                    step: None,
+                }),
+                "7",
+                MotionNodeSetEvents::SensitivityValue(7),
+            ),
+        ] {
+            let config = MotionNodeConfig {
+                sensitivity,
+                ..MotionNodeConfig::default()
+            };
+            let (node, publisher) = MotionNodeBuilder::new(&config)
+                .build_with_publisher(MOTION_NODE_DEFAULT_ID, &client);
+            let desc = DeviceDescriptionBuilder::new()
+                .add_node(MOTION_NODE_DEFAULT_ID, node)
+                .build();
+            let property =
+                PropertyRef::from_node(publisher.node.clone(), MOTION_NODE_SENSITIVITY_PROP_ID);
+
+            match publisher.parse_set(&property, &desc, input) {
+                ParseOutcome::Parsed(event) => assert_eq!(event, expected),
+                _ => panic!("sensitivity {input} must parse"),
+            }
+            assert!(matches!(
+                publisher.parse_set(&property, &desc, "max"),
+                ParseOutcome::Invalid(_)
+            ));
+        }
+    }
+
+    #[test]
+    fn parses_settable_blind_time() {
+        let config = MotionNodeConfig {
+            blind_time: true,
+            blind_time_settable: true,
+            ..MotionNodeConfig::default()
+        };
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let (node, publisher) =
+            MotionNodeBuilder::new(&config).build_with_publisher(MOTION_NODE_DEFAULT_ID, &client);
+        let desc = DeviceDescriptionBuilder::new()
+            .add_node(MOTION_NODE_DEFAULT_ID, node)
+            .build();
+        let property =
+            PropertyRef::from_node(publisher.node.clone(), MOTION_NODE_BLIND_TIME_PROP_ID);
+
+        assert!(matches!(
+            publisher.parse_set(&property, &desc, "30"),
+            ParseOutcome::Parsed(MotionNodeSetEvents::BlindTime(30))
+        ));
+        assert!(matches!(
+            publisher.parse_set(&property, &desc, "-1"),
+            ParseOutcome::Invalid(_)
+        ));
+    }
+
+    #[test]
+    fn parses_detection_zone_and_pet_immunity() {
+        let config = MotionNodeConfig {
+            sensitivity: MotionNodeSensitivity::default_levels(),
+            detection_zone: MotionNodeDetectionZone::Zones(vec![
+                "near".to_string(),
+                "far".to_string(),
+            ]),
+            pet_immune: true,
+            ..MotionNodeConfig::default()
+        };
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let (node, publisher) =
+            MotionNodeBuilder::new(&config).build_with_publisher(MOTION_NODE_DEFAULT_ID, &client);
+        let desc = DeviceDescriptionBuilder::new()
+            .add_node(MOTION_NODE_DEFAULT_ID, node)
+            .build();
+        let zone =
+            PropertyRef::from_node(publisher.node.clone(), MOTION_NODE_DETECTION_ZONE_PROP_ID);
+        let pet_immune =
+            PropertyRef::from_node(publisher.node.clone(), MOTION_NODE_PET_IMMUNE_PROP_ID);
+
+        assert_eq!(
+            publisher.parse_set(&zone, &desc, "far"),
+            ParseOutcome::Parsed(MotionNodeSetEvents::DetectionZone("far".to_string()))
+        );
+        assert!(matches!(
+            publisher.parse_set(&zone, &desc, "high"),
+            ParseOutcome::Invalid(_)
+        ));
+        assert_eq!(
+            publisher.parse_set(&pet_immune, &desc, "true"),
+            ParseOutcome::Parsed(MotionNodeSetEvents::PetImmune(true))
+        );
+    }
+
+    #[test]
+    fn apply_value_tracks_occupancy_and_tuning() {
+        let config = MotionNodeConfig {
+            occupancy: true,
+            pet_immune: true,
+            ..MotionNodeConfig::default()
+        };
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let (_, publisher) =
+            MotionNodeBuilder::new(&config).build_with_publisher(MOTION_NODE_DEFAULT_ID, &client);
+        let mut node = MotionNode {
+            publisher,
+            state: MotionNodeState::default(),
+        };
+
+        let publishes = node.apply_value(&MOTION_NODE_MOTION_PROP_ID, &HomieValue::Bool(true));
+        assert_eq!(publishes.len(), 2);
+        assert!(node.state.motion);
+        assert_eq!(node.state.occupancy, Some(true));
+
+        let publishes = node.apply_set(MotionNodeSetEvents::PetImmune(true));
+        assert_eq!(publishes.len(), 2);
+        assert_eq!(node.state.pet_immune, Some(true));
+        assert!(
+            node.apply_set(MotionNodeSetEvents::BlindTime(30))
+                .is_empty()
+        );
+        assert_eq!(node.publishes().len(), 3);
+    }
 }
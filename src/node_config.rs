@@ -0,0 +1,124 @@
+//! Config structs for the optional-property pattern.
+//!
+//! Most node configs carry one `bool` per optional property, which has to be
+//! checked in two places: the builder adds the property with
+//! `add_property_cond`, and the publisher method returns `None` when the
+//! property is disabled. [`smarthome_node_config!`](crate::smarthome_node_config)
+//! generates both from the field declaration, so they cannot drift apart.
+//!
+//! A field followed by `=> PROP_ID, method(Type), |config| description`
+//! becomes an optional property:
+//!
+//! - `add_optional_properties(db)` on the config adds the property when the
+//!   field is `true`; `description` can read other fields through `config`.
+//! - the publisher gets `method(value: Type) -> Option<Publish>`, publishing
+//!   the value when the field is `true`. Its retained flag is taken from
+//!   `description`.
+//!
+//! Other fields are copied into the struct unchanged. The publisher must have
+//! the usual `client`, `node`, `metadata` and `config` fields. Derives and `Default` are
+//! written as for any other config struct.
+
+/// Generate a node config struct with config-gated properties.
+///
+/// See the [module documentation](crate::node_config) for the syntax.
+#[macro_export]
+macro_rules! smarthome_node_config {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $config:ident for $publisher:ident {
+            $(
+                $(#[$field_meta:meta])*
+                $field_vis:vis $field:ident: $field_ty:ty
+                $(=> $prop_id:expr, $method:ident($value_ty:ty), |$cfg:ident| $desc:expr)?
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis struct $config {
+            $(
+                $(#[$field_meta])*
+                $field_vis $field: $field_ty,
+            )*
+        }
+
+        impl $config {
+            /// Add the properties enabled in this config to `db`.
+            pub fn add_optional_properties(
+                &self,
+                db: $crate::custom_node::homie5::device_description::NodeDescriptionBuilder,
+            ) -> $crate::custom_node::homie5::device_description::NodeDescriptionBuilder {
+                $($(
+                    let db = db.add_property_cond($prop_id, self.$field, || {
+                        let $cfg = self;
+                        $desc
+                    });
+                )?)*
+                db
+            }
+        }
+
+        impl $publisher {
+            $($(
+                pub fn $method(
+                    &self,
+                    value: $value_ty,
+                ) -> Option<$crate::custom_node::homie5::client::Publish> {
+                    if !self.config.$field {
+                        return None;
+                    }
+                    Some(self.client.publish_value(
+                        self.node.node_id(),
+                        &$prop_id,
+                        value.to_string(),
+                        self.metadata
+                            .get(&$prop_id)
+                            .is_none_or(|prop| prop.retained),
+                    ))
+                }
+            )?)*
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(any(feature = "co", feature = "button"))]
+    use homie5::{Homie5DeviceProtocol, HomieDomain, HomieID};
+
+    #[cfg(feature = "button")]
+    use crate::button_node::{BUTTON_NODE_DEFAULT_ID, ButtonNodeBuilder, ButtonNodeConfig};
+    #[cfg(feature = "co")]
+    use crate::co_node::{CO_NODE_DEFAULT_ID, CO_NODE_LEVEL_PROP_ID, CoNodeBuilder, CoNodeConfig};
+
+    #[test]
+    #[cfg(feature = "co")]
+    fn description_and_publisher_follow_the_same_flag() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+
+        let (desc, publisher) = CoNodeBuilder::new(&CoNodeConfig::default())
+            .build_with_publisher(CO_NODE_DEFAULT_ID, &client);
+        assert!(!desc.properties.contains_key(&CO_NODE_LEVEL_PROP_ID));
+        assert!(publisher.level(12).is_none());
+
+        let (desc, publisher) = CoNodeBuilder::new(&CoNodeConfig { level: true })
+            .build_with_publisher(CO_NODE_DEFAULT_ID, &client);
+        assert!(desc.properties.contains_key(&CO_NODE_LEVEL_PROP_ID));
+        assert_eq!(publisher.level(12).map(|p| p.payload), Some(b"12".to_vec()));
+    }
+
+    #[test]
+    #[cfg(feature = "button")]
+    fn publisher_takes_retain_from_the_description() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+
+        let (_, publisher) = ButtonNodeBuilder::new(&ButtonNodeConfig {
+            hold_duration: true,
+            ..Default::default()
+        })
+        .build_with_publisher(BUTTON_NODE_DEFAULT_ID, &client);
+        assert!(!publisher.hold_duration(1200).unwrap().retain);
+    }
+}
@@ -0,0 +1,1038 @@
+//! Builds node descriptions and publishers from deserialized
+//! [`SmarthomePropertyConfig`]s, so bridges can construct whole devices from
+//! configuration files.
+
+use std::collections::BTreeMap;
+
+use homie5::{Homie5DeviceProtocol, HomieID, NodeRef, device_description::HomieNodeDescription};
+
+#[cfg(feature = "air-purifier")]
+use crate::air_purifier_node::{AirPurifierNodeBuilder, AirPurifierNodePublisher};
+#[cfg(feature = "air-quality")]
+use crate::air_quality_node::{AirQualityNodeBuilder, AirQualityNodePublisher};
+#[cfg(feature = "alarm")]
+use crate::alarm_node::{AlarmNodeBuilder, AlarmNodePublisher};
+#[cfg(feature = "awning")]
+use crate::awning_node::{AwningNodeBuilder, AwningNodePublisher};
+#[cfg(feature = "battery")]
+use crate::battery_node::{BatteryNodeBuilder, BatteryNodePublisher};
+#[cfg(feature = "button")]
+use crate::button_node::{
+    ButtonNodeBuilder, ButtonNodePublisher, MultiButtonNodeBuilder, MultiButtonNodePublisher,
+};
+#[cfg(feature = "camera")]
+use crate::camera_node::{CameraNodeBuilder, CameraNodePublisher};
+#[cfg(feature = "climate")]
+use crate::climate_node::{ClimateNodeBuilder, ClimateNodePublisher};
+#[cfg(feature = "co")]
+use crate::co_node::{CoNodeBuilder, CoNodePublisher};
+#[cfg(feature = "color")]
+use crate::color_node::{ColorNodeBuilder, ColorNodePublisher};
+#[cfg(feature = "contact")]
+use crate::contact_node::{ContactNodeBuilder, ContactNodePublisher};
+#[cfg(feature = "curtain")]
+use crate::curtain_node::{CurtainNodeBuilder, CurtainNodePublisher};
+#[cfg(feature = "daylight")]
+use crate::daylight_node::{DaylightNodeBuilder, DaylightNodePublisher};
+#[cfg(feature = "dehumidifier")]
+use crate::dehumidifier_node::{DehumidifierNodeBuilder, DehumidifierNodePublisher};
+#[cfg(feature = "door")]
+use crate::door_node::{DoorNodeBuilder, DoorNodePublisher};
+#[cfg(feature = "doorbell")]
+use crate::doorbell_node::{DoorbellNodeBuilder, DoorbellNodePublisher};
+#[cfg(feature = "energy-dashboard")]
+use crate::energy_dashboard_node::{EnergyDashboardNodeBuilder, EnergyDashboardNodePublisher};
+#[cfg(feature = "ev-charger")]
+use crate::ev_charger_node::{EvChargerNodeBuilder, EvChargerNodePublisher};
+#[cfg(feature = "fan")]
+use crate::fan_node::{FanNodeBuilder, FanNodePublisher};
+#[cfg(feature = "garage-door")]
+use crate::garage_door_node::{GarageDoorNodeBuilder, GarageDoorNodePublisher};
+#[cfg(feature = "gas")]
+use crate::gas_node::{GasNodeBuilder, GasNodePublisher};
+#[cfg(feature = "generic-actuator")]
+use crate::generic_actuator_node::{GenericActuatorNodeBuilder, GenericActuatorNodePublisher};
+#[cfg(feature = "heating-zone")]
+use crate::heating_zone_node::{HeatingZoneNodeBuilder, HeatingZoneNodePublisher};
+#[cfg(feature = "humidifier")]
+use crate::humidifier_node::{HumidifierNodeBuilder, HumidifierNodePublisher};
+#[cfg(feature = "illuminance")]
+use crate::illuminance_node::{IlluminanceNodeBuilder, IlluminanceNodePublisher};
+#[cfg(feature = "irrigation")]
+use crate::irrigation_node::{IrrigationNodeBuilder, IrrigationNodePublisher};
+#[cfg(feature = "json-data")]
+use crate::json_data_node::{JsonDataNodeBuilder, JsonDataNodePublisher};
+#[cfg(feature = "led-strip")]
+use crate::led_strip_node::{LedStripNodeBuilder, LedStripNodePublisher};
+#[cfg(feature = "level")]
+use crate::level_node::{LevelNodeBuilder, LevelNodePublisher};
+#[cfg(feature = "link")]
+use crate::link_node::{LinkNodeBuilder, LinkNodePublisher};
+#[cfg(feature = "lock")]
+use crate::lock_node::{LockNodeBuilder, LockNodePublisher};
+#[cfg(feature = "maintenance")]
+use crate::maintenance_node::{MaintenanceNodeBuilder, MaintenanceNodePublisher};
+#[cfg(feature = "media-info")]
+use crate::media_info_node::{MediaInfoNodeBuilder, MediaInfoNodePublisher};
+#[cfg(feature = "mediaplayer")]
+use crate::mediaplayer_node::{MediaplayerNodeBuilder, MediaplayerNodePublisher};
+#[cfg(feature = "motion")]
+use crate::motion_node::{MotionNodeBuilder, MotionNodePublisher};
+#[cfg(feature = "numeric-sensor")]
+use crate::numeric_sensor_node::{NumericSensorNodeBuilder, NumericSensorNodePublisher};
+#[cfg(feature = "orientation")]
+use crate::orientation_node::{OrientationNodeBuilder, OrientationNodePublisher};
+#[cfg(feature = "powermeter")]
+use crate::powermeter_node::{PowermeterNodeBuilder, PowermeterNodePublisher};
+#[cfg(feature = "scene")]
+use crate::scene_node::{SceneNodeBuilder, SceneNodePublisher};
+#[cfg(feature = "shutter")]
+use crate::shutter_node::{ShutterNodeBuilder, ShutterNodePublisher};
+#[cfg(feature = "smoke")]
+use crate::smoke_node::{SmokeNodeBuilder, SmokeNodePublisher};
+#[cfg(feature = "string-sensor")]
+use crate::string_sensor_node::{StringSensorNodeBuilder, StringSensorNodePublisher};
+#[cfg(feature = "switch")]
+use crate::switch_node::{
+    MultiRelayNodeBuilder, MultiRelayNodePublisher, SwitchNodeBuilder, SwitchNodePublisher,
+};
+#[cfg(feature = "text")]
+use crate::text_node::{TextNodeBuilder, TextNodePublisher};
+#[cfg(feature = "thermostat")]
+use crate::thermostat_node::{ThermostatNodeBuilder, ThermostatNodePublisher};
+#[cfg(feature = "tilt")]
+use crate::tilt_node::{TiltNodeBuilder, TiltNodePublisher};
+#[cfg(feature = "timer")]
+use crate::timer_node::{TimerNodeBuilder, TimerNodePublisher};
+#[cfg(feature = "vacuum")]
+use crate::vacuum_node::{VacuumNodeBuilder, VacuumNodePublisher};
+#[cfg(feature = "valve")]
+use crate::valve_node::{ValveNodeBuilder, ValveNodePublisher};
+#[cfg(feature = "ventilation")]
+use crate::ventilation_node::{VentilationNodeBuilder, VentilationNodePublisher};
+#[cfg(feature = "vibration")]
+use crate::vibration_node::{VibrationNodeBuilder, VibrationNodePublisher};
+#[cfg(feature = "volume")]
+use crate::volume_node::{VolumeNodeBuilder, VolumeNodePublisher};
+#[cfg(feature = "water-sensor")]
+use crate::water_sensor_node::{WaterSensorNodeBuilder, WaterSensorNodePublisher};
+#[cfg(feature = "weather")]
+use crate::weather_node::{WeatherNodeBuilder, WeatherNodePublisher};
+use crate::{
+    ConfigError, PropertyMetadata, SmarthomePropertyConfig, SmarthomePublisher, SmarthomeType,
+};
+
+/// Publisher of a node built by [`build_node`], one variant per capability.
+#[derive(Debug)]
+pub enum SmarthomeNodePublisher {
+    #[cfg(feature = "air-purifier")]
+    AirPurifier(AirPurifierNodePublisher),
+    #[cfg(feature = "air-quality")]
+    AirQuality(AirQualityNodePublisher),
+    #[cfg(feature = "alarm")]
+    Alarm(AlarmNodePublisher),
+    #[cfg(feature = "awning")]
+    Awning(AwningNodePublisher),
+    #[cfg(feature = "battery")]
+    Battery(BatteryNodePublisher),
+    #[cfg(feature = "button")]
+    Button(ButtonNodePublisher),
+    #[cfg(feature = "camera")]
+    Camera(CameraNodePublisher),
+    #[cfg(feature = "climate")]
+    Climate(ClimateNodePublisher),
+    #[cfg(feature = "co")]
+    Co(CoNodePublisher),
+    #[cfg(feature = "color")]
+    Color(ColorNodePublisher),
+    #[cfg(feature = "contact")]
+    Contact(ContactNodePublisher),
+    #[cfg(feature = "curtain")]
+    Curtain(CurtainNodePublisher),
+    #[cfg(feature = "daylight")]
+    Daylight(DaylightNodePublisher),
+    #[cfg(feature = "dehumidifier")]
+    Dehumidifier(DehumidifierNodePublisher),
+    #[cfg(feature = "door")]
+    Door(DoorNodePublisher),
+    #[cfg(feature = "doorbell")]
+    Doorbell(DoorbellNodePublisher),
+    #[cfg(feature = "energy-dashboard")]
+    EnergyDashboard(EnergyDashboardNodePublisher),
+    #[cfg(feature = "ev-charger")]
+    EvCharger(EvChargerNodePublisher),
+    #[cfg(feature = "fan")]
+    Fan(FanNodePublisher),
+    #[cfg(feature = "garage-door")]
+    GarageDoor(GarageDoorNodePublisher),
+    #[cfg(feature = "gas")]
+    Gas(GasNodePublisher),
+    #[cfg(feature = "generic-actuator")]
+    GenericActuator(GenericActuatorNodePublisher),
+    #[cfg(feature = "heating-zone")]
+    HeatingZone(HeatingZoneNodePublisher),
+    #[cfg(feature = "humidifier")]
+    Humidifier(HumidifierNodePublisher),
+    #[cfg(feature = "illuminance")]
+    Illuminance(IlluminanceNodePublisher),
+    #[cfg(feature = "irrigation")]
+    Irrigation(IrrigationNodePublisher),
+    #[cfg(feature = "json-data")]
+    JsonData(JsonDataNodePublisher),
+    #[cfg(feature = "led-strip")]
+    LedStrip(LedStripNodePublisher),
+    #[cfg(feature = "level")]
+    Level(LevelNodePublisher),
+    #[cfg(feature = "link")]
+    Link(LinkNodePublisher),
+    #[cfg(feature = "lock")]
+    Lock(LockNodePublisher),
+    #[cfg(feature = "maintenance")]
+    Maintenance(MaintenanceNodePublisher),
+    #[cfg(feature = "media-info")]
+    MediaInfo(MediaInfoNodePublisher),
+    #[cfg(feature = "mediaplayer")]
+    Mediaplayer(MediaplayerNodePublisher),
+    #[cfg(feature = "motion")]
+    Motion(MotionNodePublisher),
+    #[cfg(feature = "button")]
+    MultiButton(MultiButtonNodePublisher),
+    #[cfg(feature = "switch")]
+    MultiRelay(MultiRelayNodePublisher),
+    #[cfg(feature = "numeric-sensor")]
+    Numeric(NumericSensorNodePublisher),
+    #[cfg(feature = "orientation")]
+    Orientation(OrientationNodePublisher),
+    #[cfg(feature = "powermeter")]
+    Powermeter(PowermeterNodePublisher),
+    #[cfg(feature = "scene")]
+    Scene(SceneNodePublisher),
+    #[cfg(feature = "shutter")]
+    Shutter(ShutterNodePublisher),
+    #[cfg(feature = "smoke")]
+    Smoke(SmokeNodePublisher),
+    #[cfg(feature = "string-sensor")]
+    StringSensor(StringSensorNodePublisher),
+    #[cfg(feature = "switch")]
+    Switch(SwitchNodePublisher),
+    #[cfg(feature = "text")]
+    Text(TextNodePublisher),
+    #[cfg(feature = "thermostat")]
+    Thermostat(ThermostatNodePublisher),
+    #[cfg(feature = "tilt")]
+    Tilt(TiltNodePublisher),
+    #[cfg(feature = "timer")]
+    Timer(TimerNodePublisher),
+    #[cfg(feature = "vacuum")]
+    Vacuum(VacuumNodePublisher),
+    #[cfg(feature = "valve")]
+    Valve(ValveNodePublisher),
+    #[cfg(feature = "ventilation")]
+    Ventilation(VentilationNodePublisher),
+    #[cfg(feature = "vibration")]
+    Vibration(VibrationNodePublisher),
+    #[cfg(feature = "volume")]
+    Volume(VolumeNodePublisher),
+    #[cfg(feature = "water-sensor")]
+    WaterSensor(WaterSensorNodePublisher),
+    #[cfg(feature = "weather")]
+    Weather(WeatherNodePublisher),
+}
+
+impl SmarthomeNodePublisher {
+    fn as_publisher(&self) -> &dyn SmarthomePublisher {
+        match self {
+            #[cfg(feature = "air-purifier")]
+            SmarthomeNodePublisher::AirPurifier(publisher) => publisher,
+            #[cfg(feature = "air-quality")]
+            SmarthomeNodePublisher::AirQuality(publisher) => publisher,
+            #[cfg(feature = "alarm")]
+            SmarthomeNodePublisher::Alarm(publisher) => publisher,
+            #[cfg(feature = "awning")]
+            SmarthomeNodePublisher::Awning(publisher) => publisher,
+            #[cfg(feature = "battery")]
+            SmarthomeNodePublisher::Battery(publisher) => publisher,
+            #[cfg(feature = "button")]
+            SmarthomeNodePublisher::Button(publisher) => publisher,
+            #[cfg(feature = "camera")]
+            SmarthomeNodePublisher::Camera(publisher) => publisher,
+            #[cfg(feature = "climate")]
+            SmarthomeNodePublisher::Climate(publisher) => publisher,
+            #[cfg(feature = "co")]
+            SmarthomeNodePublisher::Co(publisher) => publisher,
+            #[cfg(feature = "color")]
+            SmarthomeNodePublisher::Color(publisher) => publisher,
+            #[cfg(feature = "contact")]
+            SmarthomeNodePublisher::Contact(publisher) => publisher,
+            #[cfg(feature = "curtain")]
+            SmarthomeNodePublisher::Curtain(publisher) => publisher,
+            #[cfg(feature = "daylight")]
+            SmarthomeNodePublisher::Daylight(publisher) => publisher,
+            #[cfg(feature = "dehumidifier")]
+            SmarthomeNodePublisher::Dehumidifier(publisher) => publisher,
+            #[cfg(feature = "door")]
+            SmarthomeNodePublisher::Door(publisher) => publisher,
+            #[cfg(feature = "doorbell")]
+            SmarthomeNodePublisher::Doorbell(publisher) => publisher,
+            #[cfg(feature = "energy-dashboard")]
+            SmarthomeNodePublisher::EnergyDashboard(publisher) => publisher,
+            #[cfg(feature = "ev-charger")]
+            SmarthomeNodePublisher::EvCharger(publisher) => publisher,
+            #[cfg(feature = "fan")]
+            SmarthomeNodePublisher::Fan(publisher) => publisher,
+            #[cfg(feature = "garage-door")]
+            SmarthomeNodePublisher::GarageDoor(publisher) => publisher,
+            #[cfg(feature = "gas")]
+            SmarthomeNodePublisher::Gas(publisher) => publisher,
+            #[cfg(feature = "generic-actuator")]
+            SmarthomeNodePublisher::GenericActuator(publisher) => publisher,
+            #[cfg(feature = "heating-zone")]
+            SmarthomeNodePublisher::HeatingZone(publisher) => publisher,
+            #[cfg(feature = "humidifier")]
+            SmarthomeNodePublisher::Humidifier(publisher) => publisher,
+            #[cfg(feature = "illuminance")]
+            SmarthomeNodePublisher::Illuminance(publisher) => publisher,
+            #[cfg(feature = "irrigation")]
+            SmarthomeNodePublisher::Irrigation(publisher) => publisher,
+            #[cfg(feature = "json-data")]
+            SmarthomeNodePublisher::JsonData(publisher) => publisher,
+            #[cfg(feature = "led-strip")]
+            SmarthomeNodePublisher::LedStrip(publisher) => publisher,
+            #[cfg(feature = "level")]
+            SmarthomeNodePublisher::Level(publisher) => publisher,
+            #[cfg(feature = "link")]
+            SmarthomeNodePublisher::Link(publisher) => publisher,
+            #[cfg(feature = "lock")]
+            SmarthomeNodePublisher::Lock(publisher) => publisher,
+            #[cfg(feature = "maintenance")]
+            SmarthomeNodePublisher::Maintenance(publisher) => publisher,
+            #[cfg(feature = "media-info")]
+            SmarthomeNodePublisher::MediaInfo(publisher) => publisher,
+            #[cfg(feature = "mediaplayer")]
+            SmarthomeNodePublisher::Mediaplayer(publisher) => publisher,
+            #[cfg(feature = "motion")]
+            SmarthomeNodePublisher::Motion(publisher) => publisher,
+            #[cfg(feature = "button")]
+            SmarthomeNodePublisher::MultiButton(publisher) => publisher,
+            #[cfg(feature = "switch")]
+            SmarthomeNodePublisher::MultiRelay(publisher) => publisher,
+            #[cfg(feature = "numeric-sensor")]
+            SmarthomeNodePublisher::Numeric(publisher) => publisher,
+            #[cfg(feature = "orientation")]
+            SmarthomeNodePublisher::Orientation(publisher) => publisher,
+            #[cfg(feature = "powermeter")]
+            SmarthomeNodePublisher::Powermeter(publisher) => publisher,
+            #[cfg(feature = "scene")]
+            SmarthomeNodePublisher::Scene(publisher) => publisher,
+            #[cfg(feature = "shutter")]
+            SmarthomeNodePublisher::Shutter(publisher) => publisher,
+            #[cfg(feature = "smoke")]
+            SmarthomeNodePublisher::Smoke(publisher) => publisher,
+            #[cfg(feature = "string-sensor")]
+            SmarthomeNodePublisher::StringSensor(publisher) => publisher,
+            #[cfg(feature = "switch")]
+            SmarthomeNodePublisher::Switch(publisher) => publisher,
+            #[cfg(feature = "text")]
+            SmarthomeNodePublisher::Text(publisher) => publisher,
+            #[cfg(feature = "thermostat")]
+            SmarthomeNodePublisher::Thermostat(publisher) => publisher,
+            #[cfg(feature = "tilt")]
+            SmarthomeNodePublisher::Tilt(publisher) => publisher,
+            #[cfg(feature = "timer")]
+            SmarthomeNodePublisher::Timer(publisher) => publisher,
+            #[cfg(feature = "vacuum")]
+            SmarthomeNodePublisher::Vacuum(publisher) => publisher,
+            #[cfg(feature = "valve")]
+            SmarthomeNodePublisher::Valve(publisher) => publisher,
+            #[cfg(feature = "ventilation")]
+            SmarthomeNodePublisher::Ventilation(publisher) => publisher,
+            #[cfg(feature = "vibration")]
+            SmarthomeNodePublisher::Vibration(publisher) => publisher,
+            #[cfg(feature = "volume")]
+            SmarthomeNodePublisher::Volume(publisher) => publisher,
+            #[cfg(feature = "water-sensor")]
+            SmarthomeNodePublisher::WaterSensor(publisher) => publisher,
+            #[cfg(feature = "weather")]
+            SmarthomeNodePublisher::Weather(publisher) => publisher,
+        }
+    }
+}
+
+impl SmarthomePublisher for SmarthomeNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        self.as_publisher().smarthome_type()
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        self.as_publisher().node_ref()
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        self.as_publisher().property_ids()
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        self.as_publisher().property_metadata()
+    }
+}
+
+/// Check the lists a config provides for enum properties, e.g. scene ids or
+/// generic actuator commands, which deserialize fine but cannot be published
+/// when empty or when they contain duplicates.
+pub fn validate_config(config: &SmarthomePropertyConfig) -> Result<(), ConfigError> {
+    match config {
+        #[cfg(feature = "alarm")]
+        SmarthomePropertyConfig::Alarm(config) => config.validate(),
+        #[cfg(feature = "button")]
+        SmarthomePropertyConfig::Button(config) => config.validate(),
+        #[cfg(feature = "dehumidifier")]
+        SmarthomePropertyConfig::Dehumidifier(config) => config.validate(),
+        #[cfg(feature = "doorbell")]
+        SmarthomePropertyConfig::Doorbell(config) => config.validate(),
+        #[cfg(feature = "fan")]
+        SmarthomePropertyConfig::Fan(config) => config.validate(),
+        #[cfg(feature = "generic-actuator")]
+        SmarthomePropertyConfig::GenericActuator(config) => config.validate(),
+        #[cfg(feature = "heating-zone")]
+        SmarthomePropertyConfig::HeatingZone(config) => config.validate(),
+        #[cfg(feature = "humidifier")]
+        SmarthomePropertyConfig::Humidifier(config) => config.validate(),
+        #[cfg(feature = "led-strip")]
+        SmarthomePropertyConfig::LedStrip(config) => config.validate(),
+        #[cfg(feature = "mediaplayer")]
+        SmarthomePropertyConfig::Mediaplayer(config) => config.validate(),
+        #[cfg(feature = "motion")]
+        SmarthomePropertyConfig::Motion(config) => config.validate(),
+        #[cfg(feature = "button")]
+        SmarthomePropertyConfig::MultiButton(config) => config.validate(),
+        #[cfg(feature = "switch")]
+        SmarthomePropertyConfig::MultiRelay(config) => config.validate(),
+        #[cfg(feature = "scene")]
+        SmarthomePropertyConfig::Scene(config) => config.validate(),
+        #[cfg(feature = "thermostat")]
+        SmarthomePropertyConfig::Thermostat(config) => config.validate(),
+        #[cfg(feature = "vacuum")]
+        SmarthomePropertyConfig::Vacuum(config) => config.validate(),
+        #[allow(unreachable_patterns)]
+        _ => Ok(()),
+    }
+}
+
+/// Build the node description and matching publisher for any capability config.
+/// Fails if the config does not pass [`validate_config`].
+pub fn build_node(
+    config: &SmarthomePropertyConfig,
+    node_id: HomieID,
+    client: &Homie5DeviceProtocol,
+) -> Result<(HomieNodeDescription, SmarthomeNodePublisher), ConfigError> {
+    validate_config(config)?;
+    Ok(match config {
+        #[cfg(feature = "air-purifier")]
+        SmarthomePropertyConfig::AirPurifier(config) => {
+            let (desc, publisher) =
+                AirPurifierNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::AirPurifier(publisher))
+        }
+        #[cfg(feature = "air-quality")]
+        SmarthomePropertyConfig::AirQuality(config) => {
+            let (desc, publisher) =
+                AirQualityNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::AirQuality(publisher))
+        }
+        #[cfg(feature = "alarm")]
+        SmarthomePropertyConfig::Alarm(config) => {
+            let (desc, publisher) =
+                AlarmNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::Alarm(publisher))
+        }
+        #[cfg(feature = "awning")]
+        SmarthomePropertyConfig::Awning(config) => {
+            let (desc, publisher) =
+                AwningNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::Awning(publisher))
+        }
+        #[cfg(feature = "battery")]
+        SmarthomePropertyConfig::Battery(config) => {
+            let (desc, publisher) =
+                BatteryNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::Battery(publisher))
+        }
+        #[cfg(feature = "button")]
+        SmarthomePropertyConfig::Button(config) => {
+            let (desc, publisher) =
+                ButtonNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::Button(publisher))
+        }
+        #[cfg(feature = "camera")]
+        SmarthomePropertyConfig::Camera(config) => {
+            let (desc, publisher) =
+                CameraNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::Camera(publisher))
+        }
+        #[cfg(feature = "climate")]
+        SmarthomePropertyConfig::Climate(config) => {
+            let (desc, publisher) =
+                ClimateNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::Climate(publisher))
+        }
+        #[cfg(feature = "co")]
+        SmarthomePropertyConfig::Co(config) => {
+            let (desc, publisher) =
+                CoNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::Co(publisher))
+        }
+        #[cfg(feature = "color")]
+        SmarthomePropertyConfig::Color(config) => {
+            let (desc, publisher) =
+                ColorNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::Color(publisher))
+        }
+        #[cfg(feature = "contact")]
+        SmarthomePropertyConfig::Contact(config) => {
+            let (desc, publisher) =
+                ContactNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::Contact(publisher))
+        }
+        #[cfg(feature = "curtain")]
+        SmarthomePropertyConfig::Curtain(config) => {
+            let (desc, publisher) =
+                CurtainNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::Curtain(publisher))
+        }
+        #[cfg(feature = "daylight")]
+        SmarthomePropertyConfig::Daylight(config) => {
+            let (desc, publisher) =
+                DaylightNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::Daylight(publisher))
+        }
+        #[cfg(feature = "dehumidifier")]
+        SmarthomePropertyConfig::Dehumidifier(config) => {
+            let (desc, publisher) =
+                DehumidifierNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::Dehumidifier(publisher))
+        }
+        #[cfg(feature = "door")]
+        SmarthomePropertyConfig::Door(config) => {
+            let (desc, publisher) =
+                DoorNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::Door(publisher))
+        }
+        #[cfg(feature = "doorbell")]
+        SmarthomePropertyConfig::Doorbell(config) => {
+            let (desc, publisher) =
+                DoorbellNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::Doorbell(publisher))
+        }
+        #[cfg(feature = "energy-dashboard")]
+        SmarthomePropertyConfig::EnergyDashboard(config) => {
+            let (desc, publisher) =
+                EnergyDashboardNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::EnergyDashboard(publisher))
+        }
+        #[cfg(feature = "ev-charger")]
+        SmarthomePropertyConfig::EvCharger(config) => {
+            let (desc, publisher) =
+                EvChargerNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::EvCharger(publisher))
+        }
+        #[cfg(feature = "fan")]
+        SmarthomePropertyConfig::Fan(config) => {
+            let (desc, publisher) =
+                FanNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::Fan(publisher))
+        }
+        #[cfg(feature = "garage-door")]
+        SmarthomePropertyConfig::GarageDoor(config) => {
+            let (desc, publisher) =
+                GarageDoorNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::GarageDoor(publisher))
+        }
+        #[cfg(feature = "gas")]
+        SmarthomePropertyConfig::Gas(config) => {
+            let (desc, publisher) =
+                GasNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::Gas(publisher))
+        }
+        #[cfg(feature = "generic-actuator")]
+        SmarthomePropertyConfig::GenericActuator(config) => {
+            let (desc, publisher) =
+                GenericActuatorNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::GenericActuator(publisher))
+        }
+        #[cfg(feature = "heating-zone")]
+        SmarthomePropertyConfig::HeatingZone(config) => {
+            let (desc, publisher) =
+                HeatingZoneNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::HeatingZone(publisher))
+        }
+        #[cfg(feature = "humidifier")]
+        SmarthomePropertyConfig::Humidifier(config) => {
+            let (desc, publisher) =
+                HumidifierNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::Humidifier(publisher))
+        }
+        #[cfg(feature = "illuminance")]
+        SmarthomePropertyConfig::Illuminance => {
+            let (desc, publisher) =
+                IlluminanceNodeBuilder::new().build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::Illuminance(publisher))
+        }
+        #[cfg(feature = "irrigation")]
+        SmarthomePropertyConfig::Irrigation(config) => {
+            let (desc, publisher) =
+                IrrigationNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::Irrigation(publisher))
+        }
+        #[cfg(feature = "json-data")]
+        SmarthomePropertyConfig::JsonData(config) => {
+            let (desc, publisher) =
+                JsonDataNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::JsonData(publisher))
+        }
+        #[cfg(feature = "led-strip")]
+        SmarthomePropertyConfig::LedStrip(config) => {
+            let (desc, publisher) =
+                LedStripNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::LedStrip(publisher))
+        }
+        #[cfg(feature = "level")]
+        SmarthomePropertyConfig::Level(config) => {
+            let (desc, publisher) =
+                LevelNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::Level(publisher))
+        }
+        #[cfg(feature = "link")]
+        SmarthomePropertyConfig::Link(config) => {
+            let (desc, publisher) =
+                LinkNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::Link(publisher))
+        }
+        #[cfg(feature = "lock")]
+        SmarthomePropertyConfig::Lock(config) => {
+            let (desc, publisher) =
+                LockNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::Lock(publisher))
+        }
+        #[cfg(feature = "maintenance")]
+        SmarthomePropertyConfig::Maintenance(config) => {
+            let (desc, publisher) =
+                MaintenanceNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::Maintenance(publisher))
+        }
+        #[cfg(feature = "media-info")]
+        SmarthomePropertyConfig::MediaInfo(config) => {
+            let (desc, publisher) =
+                MediaInfoNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::MediaInfo(publisher))
+        }
+        #[cfg(feature = "mediaplayer")]
+        SmarthomePropertyConfig::Mediaplayer(config) => {
+            let (desc, publisher) =
+                MediaplayerNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::Mediaplayer(publisher))
+        }
+        #[cfg(feature = "motion")]
+        SmarthomePropertyConfig::Motion(config) => {
+            let (desc, publisher) =
+                MotionNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::Motion(publisher))
+        }
+        #[cfg(feature = "button")]
+        SmarthomePropertyConfig::MultiButton(config) => {
+            let (desc, publisher) =
+                MultiButtonNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::MultiButton(publisher))
+        }
+        #[cfg(feature = "switch")]
+        SmarthomePropertyConfig::MultiRelay(config) => {
+            let (desc, publisher) =
+                MultiRelayNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::MultiRelay(publisher))
+        }
+        #[cfg(feature = "numeric-sensor")]
+        SmarthomePropertyConfig::Numeric(config) => {
+            let (desc, publisher) =
+                NumericSensorNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::Numeric(publisher))
+        }
+        #[cfg(feature = "orientation")]
+        SmarthomePropertyConfig::Orientation(config) => {
+            let (desc, publisher) =
+                OrientationNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::Orientation(publisher))
+        }
+        #[cfg(feature = "powermeter")]
+        SmarthomePropertyConfig::Powermeter(config) => {
+            let (desc, publisher) =
+                PowermeterNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::Powermeter(publisher))
+        }
+        #[cfg(feature = "scene")]
+        SmarthomePropertyConfig::Scene(config) => {
+            let (desc, publisher) =
+                SceneNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::Scene(publisher))
+        }
+        #[cfg(feature = "shutter")]
+        SmarthomePropertyConfig::Shutter(config) => {
+            let (desc, publisher) =
+                ShutterNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::Shutter(publisher))
+        }
+        #[cfg(feature = "smoke")]
+        SmarthomePropertyConfig::Smoke(config) => {
+            let (desc, publisher) =
+                SmokeNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::Smoke(publisher))
+        }
+        #[cfg(feature = "string-sensor")]
+        SmarthomePropertyConfig::StringSensor(config) => {
+            let (desc, publisher) =
+                StringSensorNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::StringSensor(publisher))
+        }
+        #[cfg(feature = "switch")]
+        SmarthomePropertyConfig::Switch(config) => {
+            let (desc, publisher) =
+                SwitchNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::Switch(publisher))
+        }
+        #[cfg(feature = "text")]
+        SmarthomePropertyConfig::Text => {
+            let (desc, publisher) = TextNodeBuilder::new().build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::Text(publisher))
+        }
+        #[cfg(feature = "thermostat")]
+        SmarthomePropertyConfig::Thermostat(config) => {
+            let (desc, publisher) =
+                ThermostatNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::Thermostat(publisher))
+        }
+        #[cfg(feature = "tilt")]
+        SmarthomePropertyConfig::Tilt(config) => {
+            let (desc, publisher) =
+                TiltNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::Tilt(publisher))
+        }
+        #[cfg(feature = "timer")]
+        SmarthomePropertyConfig::Timer(config) => {
+            let (desc, publisher) =
+                TimerNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::Timer(publisher))
+        }
+        #[cfg(feature = "vacuum")]
+        SmarthomePropertyConfig::Vacuum(config) => {
+            let (desc, publisher) =
+                VacuumNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::Vacuum(publisher))
+        }
+        #[cfg(feature = "valve")]
+        SmarthomePropertyConfig::Valve(config) => {
+            let (desc, publisher) =
+                ValveNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::Valve(publisher))
+        }
+        #[cfg(feature = "ventilation")]
+        SmarthomePropertyConfig::Ventilation(config) => {
+            let (desc, publisher) =
+                VentilationNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::Ventilation(publisher))
+        }
+        #[cfg(feature = "vibration")]
+        SmarthomePropertyConfig::Vibration(config) => {
+            let (desc, publisher) =
+                VibrationNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::Vibration(publisher))
+        }
+        #[cfg(feature = "volume")]
+        SmarthomePropertyConfig::Volume(config) => {
+            let (desc, publisher) =
+                VolumeNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::Volume(publisher))
+        }
+        #[cfg(feature = "water-sensor")]
+        SmarthomePropertyConfig::WaterSensor(config) => {
+            let (desc, publisher) =
+                WaterSensorNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::WaterSensor(publisher))
+        }
+        #[cfg(feature = "weather")]
+        SmarthomePropertyConfig::Weather(config) => {
+            let (desc, publisher) =
+                WeatherNodeBuilder::new(config).build_with_publisher(node_id, client);
+            (desc, SmarthomeNodePublisher::Weather(publisher))
+        }
+    })
+}
+
+/// Ids of the properties a node built from `config` contains. Fails if the
+/// config does not pass [`validate_config`].
+pub fn node_property_ids(config: &SmarthomePropertyConfig) -> Result<Vec<HomieID>, ConfigError> {
+    validate_config(config)?;
+    Ok(match config {
+        #[cfg(feature = "air-purifier")]
+        SmarthomePropertyConfig::AirPurifier(config) => {
+            AirPurifierNodeBuilder::property_ids(config)
+        }
+        #[cfg(feature = "air-quality")]
+        SmarthomePropertyConfig::AirQuality(config) => AirQualityNodeBuilder::property_ids(config),
+        #[cfg(feature = "alarm")]
+        SmarthomePropertyConfig::Alarm(config) => AlarmNodeBuilder::property_ids(config),
+        #[cfg(feature = "awning")]
+        SmarthomePropertyConfig::Awning(config) => AwningNodeBuilder::property_ids(config),
+        #[cfg(feature = "battery")]
+        SmarthomePropertyConfig::Battery(config) => BatteryNodeBuilder::property_ids(config),
+        #[cfg(feature = "button")]
+        SmarthomePropertyConfig::Button(config) => ButtonNodeBuilder::property_ids(config),
+        #[cfg(feature = "camera")]
+        SmarthomePropertyConfig::Camera(config) => CameraNodeBuilder::property_ids(config),
+        #[cfg(feature = "climate")]
+        SmarthomePropertyConfig::Climate(config) => ClimateNodeBuilder::property_ids(config),
+        #[cfg(feature = "co")]
+        SmarthomePropertyConfig::Co(config) => CoNodeBuilder::property_ids(config),
+        #[cfg(feature = "color")]
+        SmarthomePropertyConfig::Color(config) => ColorNodeBuilder::property_ids(config),
+        #[cfg(feature = "contact")]
+        SmarthomePropertyConfig::Contact(config) => ContactNodeBuilder::property_ids(config),
+        #[cfg(feature = "curtain")]
+        SmarthomePropertyConfig::Curtain(config) => CurtainNodeBuilder::property_ids(config),
+        #[cfg(feature = "daylight")]
+        SmarthomePropertyConfig::Daylight(config) => DaylightNodeBuilder::property_ids(config),
+        #[cfg(feature = "dehumidifier")]
+        SmarthomePropertyConfig::Dehumidifier(config) => {
+            DehumidifierNodeBuilder::property_ids(config)
+        }
+        #[cfg(feature = "door")]
+        SmarthomePropertyConfig::Door(config) => DoorNodeBuilder::property_ids(config),
+        #[cfg(feature = "doorbell")]
+        SmarthomePropertyConfig::Doorbell(config) => DoorbellNodeBuilder::property_ids(config),
+        #[cfg(feature = "energy-dashboard")]
+        SmarthomePropertyConfig::EnergyDashboard(config) => {
+            EnergyDashboardNodeBuilder::property_ids(config)
+        }
+        #[cfg(feature = "ev-charger")]
+        SmarthomePropertyConfig::EvCharger(config) => EvChargerNodeBuilder::property_ids(config),
+        #[cfg(feature = "fan")]
+        SmarthomePropertyConfig::Fan(config) => FanNodeBuilder::property_ids(config),
+        #[cfg(feature = "garage-door")]
+        SmarthomePropertyConfig::GarageDoor(config) => GarageDoorNodeBuilder::property_ids(config),
+        #[cfg(feature = "gas")]
+        SmarthomePropertyConfig::Gas(config) => GasNodeBuilder::property_ids(config),
+        #[cfg(feature = "generic-actuator")]
+        SmarthomePropertyConfig::GenericActuator(config) => {
+            GenericActuatorNodeBuilder::property_ids(config)
+        }
+        #[cfg(feature = "heating-zone")]
+        SmarthomePropertyConfig::HeatingZone(config) => {
+            HeatingZoneNodeBuilder::property_ids(config)
+        }
+        #[cfg(feature = "humidifier")]
+        SmarthomePropertyConfig::Humidifier(config) => HumidifierNodeBuilder::property_ids(config),
+        #[cfg(feature = "json-data")]
+        SmarthomePropertyConfig::JsonData(config) => JsonDataNodeBuilder::property_ids(config),
+        #[cfg(feature = "led-strip")]
+        SmarthomePropertyConfig::LedStrip(config) => LedStripNodeBuilder::property_ids(config),
+        #[cfg(feature = "illuminance")]
+        SmarthomePropertyConfig::Illuminance => IlluminanceNodeBuilder::property_ids(),
+        #[cfg(feature = "irrigation")]
+        SmarthomePropertyConfig::Irrigation(config) => IrrigationNodeBuilder::property_ids(config),
+        #[cfg(feature = "level")]
+        SmarthomePropertyConfig::Level(config) => LevelNodeBuilder::property_ids(config),
+        #[cfg(feature = "link")]
+        SmarthomePropertyConfig::Link(config) => LinkNodeBuilder::property_ids(config),
+        #[cfg(feature = "lock")]
+        SmarthomePropertyConfig::Lock(config) => LockNodeBuilder::property_ids(config),
+        #[cfg(feature = "maintenance")]
+        SmarthomePropertyConfig::Maintenance(config) => {
+            MaintenanceNodeBuilder::property_ids(config)
+        }
+        #[cfg(feature = "media-info")]
+        SmarthomePropertyConfig::MediaInfo(config) => MediaInfoNodeBuilder::property_ids(config),
+        #[cfg(feature = "mediaplayer")]
+        SmarthomePropertyConfig::Mediaplayer(config) => {
+            MediaplayerNodeBuilder::property_ids(config)
+        }
+        #[cfg(feature = "motion")]
+        SmarthomePropertyConfig::Motion(config) => MotionNodeBuilder::property_ids(config),
+        #[cfg(feature = "button")]
+        SmarthomePropertyConfig::MultiButton(config) => {
+            MultiButtonNodeBuilder::property_ids(config)
+        }
+        #[cfg(feature = "switch")]
+        SmarthomePropertyConfig::MultiRelay(config) => MultiRelayNodeBuilder::property_ids(config),
+        #[cfg(feature = "numeric-sensor")]
+        SmarthomePropertyConfig::Numeric(config) => NumericSensorNodeBuilder::property_ids(config),
+        #[cfg(feature = "orientation")]
+        SmarthomePropertyConfig::Orientation(config) => {
+            OrientationNodeBuilder::property_ids(config)
+        }
+        #[cfg(feature = "powermeter")]
+        SmarthomePropertyConfig::Powermeter(config) => PowermeterNodeBuilder::property_ids(config),
+        #[cfg(feature = "scene")]
+        SmarthomePropertyConfig::Scene(config) => SceneNodeBuilder::property_ids(config),
+        #[cfg(feature = "shutter")]
+        SmarthomePropertyConfig::Shutter(config) => ShutterNodeBuilder::property_ids(config),
+        #[cfg(feature = "smoke")]
+        SmarthomePropertyConfig::Smoke(config) => SmokeNodeBuilder::property_ids(config),
+        #[cfg(feature = "string-sensor")]
+        SmarthomePropertyConfig::StringSensor(config) => {
+            StringSensorNodeBuilder::property_ids(config)
+        }
+        #[cfg(feature = "switch")]
+        SmarthomePropertyConfig::Switch(config) => SwitchNodeBuilder::property_ids(config),
+        #[cfg(feature = "text")]
+        SmarthomePropertyConfig::Text => TextNodeBuilder::property_ids(),
+        #[cfg(feature = "thermostat")]
+        SmarthomePropertyConfig::Thermostat(config) => ThermostatNodeBuilder::property_ids(config),
+        #[cfg(feature = "tilt")]
+        SmarthomePropertyConfig::Tilt(config) => TiltNodeBuilder::property_ids(config),
+        #[cfg(feature = "timer")]
+        SmarthomePropertyConfig::Timer(config) => TimerNodeBuilder::property_ids(config),
+        #[cfg(feature = "vacuum")]
+        SmarthomePropertyConfig::Vacuum(config) => VacuumNodeBuilder::property_ids(config),
+        #[cfg(feature = "valve")]
+        SmarthomePropertyConfig::Valve(config) => ValveNodeBuilder::property_ids(config),
+        #[cfg(feature = "ventilation")]
+        SmarthomePropertyConfig::Ventilation(config) => {
+            VentilationNodeBuilder::property_ids(config)
+        }
+        #[cfg(feature = "vibration")]
+        SmarthomePropertyConfig::Vibration(config) => VibrationNodeBuilder::property_ids(config),
+        #[cfg(feature = "volume")]
+        SmarthomePropertyConfig::Volume(config) => VolumeNodeBuilder::property_ids(config),
+        #[cfg(feature = "water-sensor")]
+        SmarthomePropertyConfig::WaterSensor(config) => {
+            WaterSensorNodeBuilder::property_ids(config)
+        }
+        #[cfg(feature = "weather")]
+        SmarthomePropertyConfig::Weather(config) => WeatherNodeBuilder::property_ids(config),
+    })
+}
+
+#[cfg(all(test, feature = "smoke", feature = "switch", feature = "water-sensor"))]
+mod tests {
+    use homie5::{HomieDataType, HomieDomain, device_description::DeviceDescriptionBuilder};
+
+    use super::*;
+    use crate::property_metadata;
+
+    #[test]
+    fn builds_device_from_configuration() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let configs: Vec<(HomieID, SmarthomePropertyConfig)> = serde_json::from_str(
+            r#"[
+                ["light", {"type": "switch", "settable": true}],
+                ["smoke", {"type": "smoke"}],
+                ["probe", {"type": "water", "last_checked": true}]
+            ]"#,
+        )
+        .expect("configs must deserialize");
+
+        let mut device = DeviceDescriptionBuilder::new();
+        let mut publishers = Vec::new();
+        for (node_id, config) in &configs {
+            let (desc, publisher) =
+                build_node(config, node_id.clone(), &client).expect("config must be valid");
+            device = device.add_node(node_id.clone(), desc);
+            publishers.push(publisher);
+        }
+        let device = device.build();
+
+        assert_eq!(
+            device.nodes[&HomieID::new_const("probe")].r#type.as_deref(),
+            Some(SmarthomeType::WaterSensor.as_str())
+        );
+        assert!(
+            device.nodes[&HomieID::new_const("probe")]
+                .properties
+                .contains_key(&HomieID::new_const("last-checked"))
+        );
+        assert!(matches!(publishers[0], SmarthomeNodePublisher::Switch(_)));
+        let summary: Vec<_> = publishers
+            .iter()
+            .map(|p| (p.smarthome_type(), p.node_id().clone()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (SmarthomeType::Switch, HomieID::new_const("light")),
+                (SmarthomeType::Smoke, HomieID::new_const("smoke")),
+                (SmarthomeType::WaterSensor, HomieID::new_const("probe")),
+            ]
+        );
+    }
+
+    #[test]
+    fn property_ids_follow_configuration() {
+        let plain: SmarthomePropertyConfig =
+            serde_json::from_str(r#"{"type": "water"}"#).expect("config must deserialize");
+        let probe: SmarthomePropertyConfig =
+            serde_json::from_str(r#"{"type": "water", "last_checked": true}"#)
+                .expect("config must deserialize");
+        let last_checked = HomieID::new_const("last-checked");
+
+        let ids = node_property_ids(&plain).expect("config must be valid");
+        assert!(ids.contains(&HomieID::new_const("detected")));
+        assert!(!ids.contains(&last_checked));
+        assert!(
+            node_property_ids(&probe)
+                .expect("config must be valid")
+                .contains(&last_checked)
+        );
+    }
+
+    #[cfg(feature = "scene")]
+    #[test]
+    fn invalid_enum_lists_are_rejected() {
+        use homie5::device_description::PropertyDescriptionValidationError;
+
+        use crate::ConfigErrorKind;
+
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let config: SmarthomePropertyConfig =
+            serde_json::from_str(r#"{"type": "scene", "scenes": []}"#)
+                .expect("config must deserialize");
+
+        let err = build_node(&config, HomieID::new_const("scenes"), &client)
+            .expect_err("empty scene list must be rejected");
+        assert_eq!(err.property_id, HomieID::new_const("recall"));
+        assert_eq!(
+            err.kind,
+            ConfigErrorKind::InvalidEnum(PropertyDescriptionValidationError::MissingEnumFormat)
+        );
+        assert_eq!(node_property_ids(&config), Err(err));
+    }
+
+    #[test]
+    fn publishers_expose_property_metadata() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let config: SmarthomePropertyConfig =
+            serde_json::from_str(r#"{"type": "switch", "settable": true}"#)
+                .expect("config must deserialize");
+        let (desc, publisher) = build_node(&config, HomieID::new_const("light"), &client)
+            .expect("config must be valid");
+
+        assert_eq!(publisher.property_metadata(), &property_metadata(&desc));
+        let state = publisher
+            .metadata(&HomieID::new_const("state"))
+            .expect("state property must have metadata");
+        assert_eq!(state.datatype, HomieDataType::Boolean);
+        assert!(state.settable && state.retained);
+        let action = publisher
+            .metadata(&HomieID::new_const("action"))
+            .expect("action property must have metadata");
+        assert!(!action.retained);
+        assert!(publisher.metadata(&HomieID::new_const("led")).is_none());
+
+        // publishers constructed directly derive the same metadata from their config
+        let SmarthomePropertyConfig::Switch(switch_config) = &config else {
+            unreachable!("config is a switch");
+        };
+        let direct =
+            SwitchNodePublisher::new(publisher.node_ref().clone(), client, switch_config.clone());
+        assert_eq!(direct.property_metadata(), publisher.property_metadata());
+    }
+}
@@ -0,0 +1,1113 @@
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use chrono::prelude::*;
+use homie5::{
+    HOMIE_UNIT_DEGREE_CELSIUS, HOMIE_UNIT_KILOPASCAL, HOMIE_UNIT_METER, HOMIE_UNIT_PERCENT,
+    Homie5DeviceProtocol, Homie5Message, HomieID, HomieValue, NodeRef, PropertyRef,
+    device_description::{
+        FloatRange, HomieDeviceDescription, HomieNodeDescription, HomiePropertyDescription,
+        IntegerRange, NodeDescriptionBuilder, PropertyDescriptionBuilder,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Clock, Decimal, ParseError, ParseErrorKind, ParseOutcome, PropertyMetadata,
+    SMARTHOME_CAP_NUMERIC, SetCommandParser, SharedClock, SmarthomePublisher, SmarthomeType,
+    property_metadata, readonly_properties, reject_readonly,
+};
+
+pub const NUMERIC_SENSOR_NODE_DEFAULT_ID: HomieID = HomieID::new_const("sensor");
+pub const NUMERIC_SENSOR_NODE_DEFAULT_NAME: &str = "Sensor";
+pub const NUMERIC_SENSOR_NODE_VALUE_PROP_ID: HomieID = HomieID::new_const("value");
+pub const NUMERIC_SENSOR_NODE_VALUE_MIN_PROP_ID: HomieID = HomieID::new_const("value-min");
+pub const NUMERIC_SENSOR_NODE_VALUE_MAX_PROP_ID: HomieID = HomieID::new_const("value-max");
+pub const NUMERIC_SENSOR_NODE_VALUE_AVG_PROP_ID: HomieID = HomieID::new_const("value-avg");
+pub const NUMERIC_SENSOR_NODE_LAST_CHANGED_PROP_ID: HomieID = HomieID::new_const("last-changed");
+pub const NUMERIC_SENSOR_NODE_THRESHOLD_PROP_ID: HomieID = HomieID::new_const("threshold");
+pub const NUMERIC_SENSOR_NODE_ALARM_PROP_ID: HomieID = HomieID::new_const("alarm");
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NumericSensorNodeState {
+    pub value: f64,
+    pub last_changed: Option<DateTime<Utc>>,
+    pub threshold: Option<f64>,
+    pub alarm: Option<bool>,
+}
+
+#[derive(Debug)]
+pub struct NumericSensorNode {
+    pub publisher: NumericSensorNodePublisher,
+    pub state: NumericSensorNodeState,
+}
+
+impl NumericSensorNode {
+    /// Apply a parsed set command and return the resulting publishes,
+    /// including `last-changed` and `alarm` where they change.
+    pub fn apply_set(&mut self, event: NumericSensorNodeSetEvents) -> Vec<homie5::client::Publish> {
+        match event {
+            NumericSensorNodeSetEvents::Value(value) => {
+                let mut publishes = vec![self.publisher.value_target(value)];
+                publishes.extend(self.apply_reading(value));
+                publishes
+            }
+            NumericSensorNodeSetEvents::Threshold(value) => {
+                let mut publishes: Vec<_> =
+                    self.publisher.threshold_target(value).into_iter().collect();
+                publishes.extend(self.apply_threshold(value));
+                publishes
+            }
+        }
+    }
+
+    /// Apply a value reported by the device and return the matching publishes.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Float(value)) if p == &NUMERIC_SENSOR_NODE_VALUE_PROP_ID => {
+                self.apply_reading(*value)
+            }
+            (p, HomieValue::Integer(value)) if p == &NUMERIC_SENSOR_NODE_VALUE_PROP_ID => {
+                self.apply_reading(*value as f64)
+            }
+            (p, HomieValue::Float(value)) if p == &NUMERIC_SENSOR_NODE_THRESHOLD_PROP_ID => {
+                self.apply_threshold(*value)
+            }
+            (p, HomieValue::Integer(value)) if p == &NUMERIC_SENSOR_NODE_THRESHOLD_PROP_ID => {
+                self.apply_threshold(*value as f64)
+            }
+            (p, HomieValue::DateTime(value)) if p == &NUMERIC_SENSOR_NODE_LAST_CHANGED_PROP_ID => {
+                self.state.last_changed = Some(*value);
+                self.publisher.last_changed(*value).into_iter().collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn apply_reading(&mut self, value: f64) -> Vec<homie5::client::Publish> {
+        let now = self.publisher.clock.now();
+        if self.publisher.last_value != Some(value) && self.publisher.config.last_changed {
+            self.state.last_changed = Some(now);
+        }
+        self.state.value = value;
+        let publishes = self.publisher.value_at(value, now);
+        self.state.alarm = self.publisher.alarm_state;
+        publishes
+    }
+
+    fn apply_threshold(&mut self, value: f64) -> Vec<homie5::client::Publish> {
+        if self.publisher.config.threshold {
+            self.state.threshold = Some(value);
+        }
+        let publishes = self.publisher.threshold(value);
+        self.state.alarm = self.publisher.alarm_state;
+        publishes
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            Some(self.publisher.reading(self.state.value)),
+            self.state
+                .last_changed
+                .and_then(|value| self.publisher.last_changed(value)),
+            self.state
+                .threshold
+                .and_then(|value| self.publisher.threshold_reading(value)),
+            self.state
+                .alarm
+                .and_then(|value| self.publisher.alarm(value)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+pub enum NumericSensorNodeSetEvents {
+    Value(f64),
+    Threshold(f64),
+}
+
+/// Whether `alarm` is raised when the value is above or below the threshold.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+#[serde(rename_all = "lowercase")]
+pub enum NumericSensorThresholdMode {
+    #[default]
+    Above,
+    Below,
+}
+
+impl NumericSensorThresholdMode {
+    pub fn exceeded(&self, value: f64, threshold: f64) -> bool {
+        match self {
+            Self::Above => value > threshold,
+            Self::Below => value < threshold,
+        }
+    }
+}
+
+/// Homie datatype used for the `value` property.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+#[serde(rename_all = "lowercase")]
+pub enum NumericSensorDataType {
+    Integer,
+    Float,
+}
+
+/// Well-known sensor kinds providing default unit, datatype and range.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+pub enum NumericSensorType {
+    #[default]
+    Generic,
+    Temperature,
+    Humidity,
+    Pressure,
+    Distance,
+    Gas,
+    SoundLevel,
+    UvIndex,
+    SoilMoisture,
+    Pm25,
+    Pm10,
+    Radon,
+    Weight,
+    WindSpeed,
+}
+
+impl NumericSensorType {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Generic => "generic",
+            Self::Temperature => "temperature",
+            Self::Humidity => "humidity",
+            Self::Pressure => "pressure",
+            Self::Distance => "distance",
+            Self::Gas => "gas",
+            Self::SoundLevel => "sound-level",
+            Self::UvIndex => "uv-index",
+            Self::SoilMoisture => "soil-moisture",
+            Self::Pm25 => "pm25",
+            Self::Pm10 => "pm10",
+            Self::Radon => "radon",
+            Self::Weight => "weight",
+            Self::WindSpeed => "wind-speed",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Generic => "Value",
+            Self::Temperature => "Temperature",
+            Self::Humidity => "Humidity",
+            Self::Pressure => "Pressure",
+            Self::Distance => "Distance",
+            Self::Gas => "Gas concentration",
+            Self::SoundLevel => "Sound level",
+            Self::UvIndex => "UV index",
+            Self::SoilMoisture => "Soil moisture",
+            Self::Pm25 => "PM2.5",
+            Self::Pm10 => "PM10",
+            Self::Radon => "Radon",
+            Self::Weight => "Weight",
+            Self::WindSpeed => "Wind speed",
+        }
+    }
+
+    pub fn unit(&self) -> Option<&'static str> {
+        match self {
+            Self::Generic | Self::UvIndex => None,
+            Self::Temperature => Some(HOMIE_UNIT_DEGREE_CELSIUS),
+            Self::Humidity | Self::SoilMoisture => Some(HOMIE_UNIT_PERCENT),
+            Self::Pressure => Some(HOMIE_UNIT_KILOPASCAL),
+            Self::Distance => Some(HOMIE_UNIT_METER),
+            Self::Gas => Some("ppm"),
+            Self::SoundLevel => Some("dB"),
+            Self::Pm25 | Self::Pm10 => Some("µg/m³"),
+            Self::Radon => Some("Bq/m³"),
+            Self::Weight => Some("kg"),
+            Self::WindSpeed => Some("m/s"),
+        }
+    }
+
+    pub fn datatype(&self) -> NumericSensorDataType {
+        match self {
+            Self::Gas | Self::Pm25 | Self::Pm10 | Self::Radon => NumericSensorDataType::Integer,
+            _ => NumericSensorDataType::Float,
+        }
+    }
+
+    /// Default `(min, max)` bounds of the value.
+    pub fn range(&self) -> (Option<f64>, Option<f64>) {
+        match self {
+            Self::Humidity | Self::SoilMoisture => (Some(0.0), Some(100.0)),
+            Self::Generic | Self::Temperature | Self::Pressure => (None, None),
+            _ => (Some(0.0), None),
+        }
+    }
+}
+
+impl FromStr for NumericSensorType {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "generic" => Ok(Self::Generic),
+            "temperature" => Ok(Self::Temperature),
+            "humidity" => Ok(Self::Humidity),
+            "pressure" => Ok(Self::Pressure),
+            "distance" => Ok(Self::Distance),
+            "gas" => Ok(Self::Gas),
+            "sound-level" => Ok(Self::SoundLevel),
+            "uv-index" => Ok(Self::UvIndex),
+            "soil-moisture" => Ok(Self::SoilMoisture),
+            "pm25" => Ok(Self::Pm25),
+            "pm10" => Ok(Self::Pm10),
+            "radon" => Ok(Self::Radon),
+            "weight" => Ok(Self::Weight),
+            "wind-speed" => Ok(Self::WindSpeed),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for NumericSensorType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for NumericSensorType {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for NumericSensorType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        value
+            .parse()
+            .map_err(|_| serde::de::Error::custom(format!("invalid numeric sensor type: {value}")))
+    }
+}
+
+/// Declarative description of a numeric sensor. Unset fields fall back to the
+/// defaults of `sensor_type`.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+#[serde(default)]
+pub struct NumericSensorNodeConfig {
+    pub sensor_type: NumericSensorType,
+    pub unit: Option<String>,
+    pub datatype: Option<NumericSensorDataType>,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub step: Option<f64>,
+    /// Number of decimal places used when publishing float values.
+    #[cfg_attr(
+        feature = "proptest",
+        proptest(strategy = "crate::arbitrary::precision()")
+    )]
+    pub precision: Option<usize>,
+    pub settable: bool,
+    /// Expose `value-min`, `value-max` and `value-avg` over a sample window.
+    pub statistics: bool,
+    /// Length of the statistics window in seconds.
+    #[cfg_attr(
+        feature = "proptest",
+        proptest(strategy = "crate::arbitrary::seconds()")
+    )]
+    pub statistics_window: i64,
+    /// Expose a `last-changed` timestamp updated whenever the value changes.
+    pub last_changed: bool,
+    /// Expose a settable `threshold` and a derived `alarm` property.
+    pub threshold: bool,
+    pub threshold_mode: NumericSensorThresholdMode,
+    /// Properties to publish as read-only; sets to them are rejected.
+    #[cfg_attr(
+        feature = "proptest",
+        proptest(strategy = "crate::arbitrary::homie_ids()")
+    )]
+    pub readonly: Vec<HomieID>,
+}
+
+impl Default for NumericSensorNodeConfig {
+    fn default() -> Self {
+        Self {
+            sensor_type: NumericSensorType::Generic,
+            unit: None,
+            datatype: None,
+            min: None,
+            max: None,
+            step: None,
+            precision: None,
+            settable: false,
+            statistics: false,
+            statistics_window: 300,
+            last_changed: false,
+            threshold: false,
+            threshold_mode: NumericSensorThresholdMode::Above,
+            readonly: Vec::new(),
+        }
+    }
+}
+
+impl NumericSensorNodeConfig {
+    pub fn datatype(&self) -> NumericSensorDataType {
+        self.datatype.unwrap_or_else(|| self.sensor_type.datatype())
+    }
+
+    pub fn unit(&self) -> Option<&str> {
+        self.unit.as_deref().or_else(|| self.sensor_type.unit())
+    }
+
+    fn float_range(&self) -> FloatRange {
+        let (min, max) = self.sensor_type.range();
+        FloatRange {
+            min: self.min.or(min),
+            max: self.max.or(max),
+            step: self.step,
+        }
+    }
+
+    fn integer_range(&self) -> IntegerRange {
+        let range = self.float_range();
+        IntegerRange {
+            min: range.min.map(|v| v as i64),
+            max: range.max.map(|v| v as i64),
+            step: range.step.map(|v| v as i64),
+        }
+    }
+}
+
+pub struct NumericSensorNodeBuilder {
+    config: NumericSensorNodeConfig,
+    node_builder: NodeDescriptionBuilder,
+    clock: SharedClock,
+}
+
+impl NumericSensorNodeBuilder {
+    pub fn new(config: &NumericSensorNodeConfig) -> Self {
+        let db = Self::build_node(
+            NodeDescriptionBuilder::new().name(NUMERIC_SENSOR_NODE_DEFAULT_NAME),
+            config,
+        )
+        .r#type(SMARTHOME_CAP_NUMERIC);
+        let db = readonly_properties(db, &config.readonly);
+
+        Self {
+            node_builder: db,
+            config: config.clone(),
+            clock: SharedClock::default(),
+        }
+    }
+
+    fn build_node(
+        db: NodeDescriptionBuilder,
+        config: &NumericSensorNodeConfig,
+    ) -> NodeDescriptionBuilder {
+        let name = config.sensor_type.name();
+        db.add_property(
+            NUMERIC_SENSOR_NODE_VALUE_PROP_ID,
+            Self::value_property(config, name.to_owned(), config.datatype(), config.settable),
+        )
+        .add_property_cond(
+            NUMERIC_SENSOR_NODE_VALUE_MIN_PROP_ID,
+            config.statistics,
+            || Self::value_property(config, format!("{name} (min)"), config.datatype(), false),
+        )
+        .add_property_cond(
+            NUMERIC_SENSOR_NODE_VALUE_MAX_PROP_ID,
+            config.statistics,
+            || Self::value_property(config, format!("{name} (max)"), config.datatype(), false),
+        )
+        .add_property_cond(
+            NUMERIC_SENSOR_NODE_VALUE_AVG_PROP_ID,
+            config.statistics,
+            || {
+                Self::value_property(
+                    config,
+                    format!("{name} (average)"),
+                    NumericSensorDataType::Float,
+                    false,
+                )
+            },
+        )
+        .add_property_cond(
+            NUMERIC_SENSOR_NODE_LAST_CHANGED_PROP_ID,
+            config.last_changed,
+            || {
+                PropertyDescriptionBuilder::datetime()
+                    .name("Last changed")
+                    .settable(false)
+                    .retained(true)
+                    .build()
+            },
+        )
+        .add_property_cond(
+            NUMERIC_SENSOR_NODE_THRESHOLD_PROP_ID,
+            config.threshold,
+            || Self::value_property(config, format!("{name} threshold"), config.datatype(), true),
+        )
+        .add_property_cond(NUMERIC_SENSOR_NODE_ALARM_PROP_ID, config.threshold, || {
+            PropertyDescriptionBuilder::boolean()
+                .name("Threshold alarm")
+                .boolean_labels("ok", "alarm")
+                .settable(false)
+                .retained(true)
+                .build()
+        })
+    }
+
+    fn value_property(
+        config: &NumericSensorNodeConfig,
+        name: String,
+        datatype: NumericSensorDataType,
+        settable: bool,
+    ) -> HomiePropertyDescription {
+        let unit = config.unit();
+        match datatype {
+            NumericSensorDataType::Integer => PropertyDescriptionBuilder::integer()
+                .name(name)
+                .integer_range(config.integer_range())
+                .unit::<&str>(unit)
+                .settable(settable)
+                .retained(true)
+                .build(),
+            NumericSensorDataType::Float => PropertyDescriptionBuilder::float()
+                .name(name)
+                .float_range(config.float_range())
+                .unit::<&str>(unit)
+                .settable(settable)
+                .retained(true)
+                .build(),
+        }
+    }
+
+    pub fn name<S: Into<String>>(mut self, name: impl Into<Option<S>>) -> Self {
+        self.node_builder = self.node_builder.name(name);
+        self
+    }
+
+    /// Append a subtype suffix to the node type (`numeric-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::Numeric.with_subtype(subtype));
+        self
+    }
+
+    /// Clock for the `last-changed` timestamps of
+    /// [`NumericSensorNodePublisher::value`]; the system clock by default.
+    pub fn clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = SharedClock::new(clock);
+        self
+    }
+
+    pub fn build(self) -> HomieNodeDescription {
+        self.node_builder.build()
+    }
+
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &NumericSensorNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
+    pub fn build_with_publisher(
+        self,
+        node_id: HomieID,
+        client: &Homie5DeviceProtocol,
+    ) -> (HomieNodeDescription, NumericSensorNodePublisher) {
+        let desc = self.node_builder.build();
+        let mut publisher = NumericSensorNodePublisher::new(
+            NodeRef::new(
+                client.homie_domain().to_owned(),
+                client.id().to_owned(),
+                node_id,
+            ),
+            client.clone(),
+            self.config,
+        );
+        publisher.clock = self.clock;
+        (desc, publisher)
+    }
+}
+
+#[derive(Debug)]
+pub struct NumericSensorNodePublisher {
+    client: Homie5DeviceProtocol,
+    config: NumericSensorNodeConfig,
+    node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
+    clock: SharedClock,
+    value_prop: HomieID,
+    value_min_prop: HomieID,
+    value_max_prop: HomieID,
+    value_avg_prop: HomieID,
+    last_changed_prop: HomieID,
+    threshold_prop: HomieID,
+    alarm_prop: HomieID,
+    last_value: Option<f64>,
+    current_threshold: Option<f64>,
+    alarm_state: Option<bool>,
+}
+
+impl NumericSensorNodePublisher {
+    pub fn new(
+        node: NodeRef,
+        client: Homie5DeviceProtocol,
+        config: NumericSensorNodeConfig,
+    ) -> Self {
+        let metadata = property_metadata(&NumericSensorNodeBuilder::new(&config).build());
+        Self {
+            node,
+            metadata,
+            clock: SharedClock::default(),
+            client,
+            config,
+            value_prop: NUMERIC_SENSOR_NODE_VALUE_PROP_ID,
+            value_min_prop: NUMERIC_SENSOR_NODE_VALUE_MIN_PROP_ID,
+            value_max_prop: NUMERIC_SENSOR_NODE_VALUE_MAX_PROP_ID,
+            value_avg_prop: NUMERIC_SENSOR_NODE_VALUE_AVG_PROP_ID,
+            last_changed_prop: NUMERIC_SENSOR_NODE_LAST_CHANGED_PROP_ID,
+            threshold_prop: NUMERIC_SENSOR_NODE_THRESHOLD_PROP_ID,
+            alarm_prop: NUMERIC_SENSOR_NODE_ALARM_PROP_ID,
+            last_value: None,
+            current_threshold: None,
+            alarm_state: None,
+        }
+    }
+
+    /// Publish a reading, formatted to match the declared datatype. If
+    /// `last_changed` is enabled and the value differs from the previous one,
+    /// a `last-changed` publish follows.
+    pub fn value(&mut self, value: f64) -> Vec<homie5::client::Publish> {
+        self.value_at(value, self.clock.now())
+    }
+
+    fn value_at(&mut self, value: f64, now: DateTime<Utc>) -> Vec<homie5::client::Publish> {
+        let mut publishes = vec![self.reading(value)];
+        if self.last_value.replace(value) != Some(value) {
+            publishes.extend(self.last_changed(now));
+        }
+        publishes.extend(self.update_alarm());
+        publishes
+    }
+
+    /// The `value` publish alone, without tracking it as a new reading.
+    fn reading(&self, value: f64) -> homie5::client::Publish {
+        self.client.publish_value(
+            self.node.node_id(),
+            &self.value_prop,
+            self.format_value(value),
+            true,
+        )
+    }
+
+    pub fn value_target(&self, value: f64) -> homie5::client::Publish {
+        self.client.publish_target(
+            self.node.node_id(),
+            &self.value_prop,
+            self.format_value(value),
+            true,
+        )
+    }
+
+    /// Publish a new threshold and re-evaluate `alarm` against the last value.
+    pub fn threshold(&mut self, value: f64) -> Vec<homie5::client::Publish> {
+        if !self.config.threshold {
+            return Vec::new();
+        }
+        self.current_threshold = Some(value);
+        let mut publishes: Vec<_> = self.threshold_reading(value).into_iter().collect();
+        publishes.extend(self.update_alarm());
+        publishes
+    }
+
+    /// The `threshold` publish alone, without re-evaluating `alarm`.
+    fn threshold_reading(&self, value: f64) -> Option<homie5::client::Publish> {
+        if !self.config.threshold {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.threshold_prop,
+            self.format_value(value),
+            true,
+        ))
+    }
+
+    pub fn threshold_target(&self, value: f64) -> Option<homie5::client::Publish> {
+        if !self.config.threshold {
+            return None;
+        }
+        Some(self.client.publish_target(
+            self.node.node_id(),
+            &self.threshold_prop,
+            self.format_value(value),
+            true,
+        ))
+    }
+
+    pub fn alarm(&self, value: bool) -> Option<homie5::client::Publish> {
+        if !self.config.threshold {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.alarm_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+
+    fn update_alarm(&mut self) -> Option<homie5::client::Publish> {
+        let (Some(value), Some(threshold)) = (self.last_value, self.current_threshold) else {
+            return None;
+        };
+        let exceeded = self.config.threshold_mode.exceeded(value, threshold);
+        if self.alarm_state.replace(exceeded) == Some(exceeded) {
+            return None;
+        }
+        self.alarm(exceeded)
+    }
+
+    pub fn value_int(&mut self, value: i64) -> Vec<homie5::client::Publish> {
+        self.value(value as f64)
+    }
+
+    pub fn value_float(&mut self, value: f64) -> Vec<homie5::client::Publish> {
+        self.value(value)
+    }
+
+    pub fn last_changed(&self, value: DateTime<Utc>) -> Option<homie5::client::Publish> {
+        if !self.config.last_changed {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.last_changed_prop,
+            value.to_rfc3339_opts(SecondsFormat::Millis, true),
+            true,
+        ))
+    }
+
+    /// Publish the min/max/average of a completed statistics window.
+    pub fn statistics(&self, stats: &NumericSensorStatistics) -> Vec<homie5::client::Publish> {
+        if !self.config.statistics {
+            return Vec::new();
+        }
+        vec![
+            self.client.publish_value(
+                self.node.node_id(),
+                &self.value_min_prop,
+                self.format_value(stats.min),
+                true,
+            ),
+            self.client.publish_value(
+                self.node.node_id(),
+                &self.value_max_prop,
+                self.format_value(stats.max),
+                true,
+            ),
+            self.client.publish_value(
+                self.node.node_id(),
+                &self.value_avg_prop,
+                self.format_float(stats.avg),
+                true,
+            ),
+        ]
+    }
+
+    /// Create an aggregator using the configured statistics window.
+    pub fn aggregator(&self) -> NumericSensorAggregator {
+        NumericSensorAggregator::new(chrono::Duration::seconds(self.config.statistics_window))
+    }
+
+    /// Format a value according to the declared datatype: integers are
+    /// rounded, floats honor the configured precision.
+    fn format_value(&self, value: f64) -> String {
+        match self.config.datatype() {
+            NumericSensorDataType::Integer => (value.round() as i64).to_string(),
+            NumericSensorDataType::Float => self.format_float(value),
+        }
+    }
+
+    fn format_float(&self, value: f64) -> String {
+        Decimal::new(value, self.config.precision).to_string()
+    }
+}
+
+impl SmarthomePublisher for NumericSensorNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::Numeric
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![
+            self.value_prop.clone(),
+            self.value_min_prop.clone(),
+            self.value_max_prop.clone(),
+            self.value_avg_prop.clone(),
+            self.last_changed_prop.clone(),
+            self.threshold_prop.clone(),
+            self.alarm_prop.clone(),
+        ]
+    }
+}
+
+/// Min/max/average of the samples in one statistics window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NumericSensorStatistics {
+    pub min: f64,
+    pub max: f64,
+    pub avg: f64,
+    pub count: usize,
+}
+
+/// Collects samples into fixed time windows for the statistics properties.
+#[derive(Debug, Clone)]
+pub struct NumericSensorAggregator {
+    window: chrono::Duration,
+    window_start: Option<DateTime<Utc>>,
+    min: f64,
+    max: f64,
+    sum: f64,
+    count: usize,
+}
+
+impl NumericSensorAggregator {
+    pub fn new(window: chrono::Duration) -> Self {
+        Self {
+            window,
+            window_start: None,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            sum: 0.0,
+            count: 0,
+        }
+    }
+
+    /// Add a sample. When the sample falls past the current window, the
+    /// statistics of the finished window are returned and a new window starts
+    /// with this sample.
+    pub fn ingest(&mut self, value: f64, now: DateTime<Utc>) -> Option<NumericSensorStatistics> {
+        let finished = match self.window_start {
+            Some(start) if now >= start + self.window => self.flush(),
+            _ => None,
+        };
+        if self.window_start.is_none() {
+            self.window_start = Some(now);
+        }
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.sum += value;
+        self.count += 1;
+        finished
+    }
+
+    /// Close the current window and return its statistics, if it has samples.
+    pub fn flush(&mut self) -> Option<NumericSensorStatistics> {
+        let stats = (self.count > 0).then(|| NumericSensorStatistics {
+            min: self.min,
+            max: self.max,
+            avg: self.sum / self.count as f64,
+            count: self.count,
+        });
+        *self = Self::new(self.window);
+        stats
+    }
+}
+
+impl SetCommandParser for NumericSensorNodePublisher {
+    type Event = NumericSensorNodeSetEvents;
+
+    fn parse_set(
+        &self,
+        property: &PropertyRef,
+        desc: &HomieDeviceDescription,
+        set_value: &str,
+    ) -> ParseOutcome<Self::Event> {
+        if let Some(outcome) = reject_readonly(&self.node, property, desc, set_value) {
+            return outcome;
+        }
+
+        let is_threshold = property.match_with_node(&self.node, &self.threshold_prop);
+        if !is_threshold && !property.match_with_node(&self.node, &self.value_prop) {
+            return ParseOutcome::NoMatch;
+        }
+
+        let Some(parsed) = desc.with_property(property, |prop_desc| {
+            HomieValue::parse(set_value, prop_desc)
+        }) else {
+            return ParseOutcome::Invalid(ParseError::new(
+                property.prop_id().to_string(),
+                set_value,
+                ParseErrorKind::MissingPropertyDescription,
+            ));
+        };
+
+        let value = match parsed {
+            Ok(HomieValue::Float(value)) => value,
+            Ok(HomieValue::Integer(value)) => value as f64,
+            _ => {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property.prop_id().to_string(),
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                ));
+            }
+        };
+
+        if is_threshold {
+            ParseOutcome::Parsed(NumericSensorNodeSetEvents::Threshold(value))
+        } else {
+            ParseOutcome::Parsed(NumericSensorNodeSetEvents::Value(value))
+        }
+    }
+
+    fn parse_set_event(
+        &self,
+        desc: &HomieDeviceDescription,
+        event: &Homie5Message,
+    ) -> ParseOutcome<Self::Event> {
+        match event {
+            Homie5Message::PropertySet {
+                property,
+                set_value,
+            } => self.parse_set(property, desc, set_value),
+            _ => ParseOutcome::Invalid(ParseError::new(
+                self.value_prop.to_string(),
+                "",
+                ParseErrorKind::UnexpectedMessageType,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use homie5::{HomieDataType, device_description::HomiePropertyFormat};
+
+    use super::*;
+
+    #[test]
+    fn config_overrides_sensor_type_defaults() {
+        let config: NumericSensorNodeConfig =
+            serde_json::from_str(r#"{"sensor_type":"humidity","max":90,"datatype":"integer"}"#)
+                .expect("numeric config must deserialize");
+        let node = NumericSensorNodeBuilder::new(&config).build();
+        let prop = node
+            .properties
+            .get(&NUMERIC_SENSOR_NODE_VALUE_PROP_ID)
+            .expect("value property must exist");
+
+        assert_eq!(prop.datatype, HomieDataType::Integer);
+        assert_eq!(prop.unit.as_deref(), Some(HOMIE_UNIT_PERCENT));
+        assert_eq!(
+            prop.format,
+            HomiePropertyFormat::IntegerRange(IntegerRange {
+                min: Some(0),
+                max: Some(90),
+                step: None,
+            })
+        );
+    }
+
+    #[test]
+    fn sensor_type_roundtrips_through_str_and_serde() {
+        for ty in [
+            NumericSensorType::Generic,
+            NumericSensorType::SoundLevel,
+            NumericSensorType::UvIndex,
+            NumericSensorType::SoilMoisture,
+            NumericSensorType::Pm25,
+            NumericSensorType::Pm10,
+            NumericSensorType::Radon,
+            NumericSensorType::Weight,
+            NumericSensorType::WindSpeed,
+        ] {
+            assert_eq!(ty.as_str().parse::<NumericSensorType>(), Ok(ty));
+
+            let json = serde_json::to_string(&ty).expect("serialize sensor type");
+            assert_eq!(json, format!("\"{ty}\""));
+            let parsed: NumericSensorType =
+                serde_json::from_str(&json).expect("deserialize sensor type");
+            assert_eq!(parsed, ty);
+        }
+        assert!(serde_json::from_str::<NumericSensorType>("\"lightning\"").is_err());
+    }
+
+    #[test]
+    fn aggregator_yields_statistics_on_window_boundary() {
+        let mut aggregator = NumericSensorAggregator::new(chrono::Duration::seconds(60));
+        let start = Utc::now();
+
+        assert!(aggregator.ingest(2.0, start).is_none());
+        assert!(
+            aggregator
+                .ingest(6.0, start + chrono::Duration::seconds(30))
+                .is_none()
+        );
+        assert!(
+            aggregator
+                .ingest(1.0, start + chrono::Duration::seconds(59))
+                .is_none()
+        );
+
+        let stats = aggregator
+            .ingest(10.0, start + chrono::Duration::seconds(60))
+            .expect("window boundary must yield statistics");
+        assert_eq!(
+            stats,
+            NumericSensorStatistics {
+                min: 1.0,
+                max: 6.0,
+                avg: 3.0,
+                count: 3,
+            }
+        );
+
+        let rest = aggregator.flush().expect("new window holds one sample");
+        assert_eq!(rest.count, 1);
+        assert_eq!(rest.avg, 10.0);
+        assert!(aggregator.flush().is_none());
+    }
+
+    #[test]
+    fn last_changed_follows_value_changes_only() {
+        let config = NumericSensorNodeConfig {
+            last_changed: true,
+            ..NumericSensorNodeConfig::default()
+        };
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), homie5::HomieDomain::Default);
+        let changed = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+        let (_, mut publisher) = NumericSensorNodeBuilder::new(&config)
+            .clock(move || changed)
+            .build_with_publisher(NUMERIC_SENSOR_NODE_DEFAULT_ID, &client);
+
+        assert_eq!(publisher.value(21.5).len(), 2);
+        assert_eq!(publisher.value(21.5).len(), 1);
+
+        let publishes = publisher.value(22.0);
+        assert_eq!(publishes.len(), 2);
+        assert!(publishes[1].topic.ends_with("/last-changed"));
+        assert_eq!(publishes[1].payload, b"2026-03-01T12:00:00.000Z");
+    }
+
+    #[test]
+    fn threshold_parses_and_drives_alarm() {
+        let config = NumericSensorNodeConfig {
+            threshold: true,
+            ..NumericSensorNodeConfig::default()
+        };
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), homie5::HomieDomain::Default);
+        let (node, mut publisher) = NumericSensorNodeBuilder::new(&config)
+            .build_with_publisher(NUMERIC_SENSOR_NODE_DEFAULT_ID, &client);
+        let desc = homie5::device_description::DeviceDescriptionBuilder::new()
+            .add_node(NUMERIC_SENSOR_NODE_DEFAULT_ID, node)
+            .build();
+        let property = PropertyRef::from_node(
+            publisher.node.clone(),
+            NUMERIC_SENSOR_NODE_THRESHOLD_PROP_ID,
+        );
+
+        assert_eq!(
+            publisher.parse_set(&property, &desc, "25.5"),
+            ParseOutcome::Parsed(NumericSensorNodeSetEvents::Threshold(25.5))
+        );
+
+        assert_eq!(publisher.value(20.0).len(), 1);
+        let publishes = publisher.threshold(25.5);
+        assert_eq!(publishes.len(), 2);
+        assert_eq!(publishes[1].payload, b"false".to_vec());
+
+        let publishes = publisher.value(30.0);
+        assert_eq!(publishes.len(), 2);
+        assert_eq!(publishes[1].payload, b"true".to_vec());
+        assert_eq!(publisher.value(31.0).len(), 1);
+    }
+
+    #[test]
+    fn payload_matches_declared_datatype() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), homie5::HomieDomain::Default);
+
+        let config = NumericSensorNodeConfig {
+            sensor_type: NumericSensorType::Gas,
+            ..NumericSensorNodeConfig::default()
+        };
+        let (_, mut publisher) = NumericSensorNodeBuilder::new(&config)
+            .build_with_publisher(NUMERIC_SENSOR_NODE_DEFAULT_ID, &client);
+        assert_eq!(publisher.value(412.6)[0].payload, b"413".to_vec());
+        assert_eq!(publisher.value_int(400)[0].payload, b"400".to_vec());
+
+        let config = NumericSensorNodeConfig {
+            precision: Some(1),
+            ..NumericSensorNodeConfig::default()
+        };
+        let (_, mut publisher) = NumericSensorNodeBuilder::new(&config)
+            .build_with_publisher(NUMERIC_SENSOR_NODE_DEFAULT_ID, &client);
+        assert_eq!(publisher.value_float(21.04)[0].payload, b"21.0".to_vec());
+
+        let target = publisher.value_target(21.04);
+        assert!(target.topic.ends_with("/sensor/value/$target"));
+        assert_eq!(target.payload, b"21.0".to_vec());
+        assert!(publisher.threshold_target(25.0).is_none());
+    }
+
+    #[test]
+    fn apply_keeps_threshold_and_alarm_in_state() {
+        let config = NumericSensorNodeConfig {
+            threshold: true,
+            ..NumericSensorNodeConfig::default()
+        };
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), homie5::HomieDomain::Default);
+        let (_, publisher) = NumericSensorNodeBuilder::new(&config)
+            .build_with_publisher(NUMERIC_SENSOR_NODE_DEFAULT_ID, &client);
+        let mut node = NumericSensorNode {
+            publisher,
+            state: NumericSensorNodeState::default(),
+        };
+
+        let publishes = node.apply_set(NumericSensorNodeSetEvents::Threshold(25.0));
+        assert_eq!(publishes.len(), 2);
+        assert_eq!(node.state.threshold, Some(25.0));
+        assert_eq!(node.state.alarm, None);
+
+        let publishes =
+            node.apply_value(&NUMERIC_SENSOR_NODE_VALUE_PROP_ID, &HomieValue::Float(30.0));
+        assert_eq!(publishes.len(), 2);
+        assert_eq!(node.state.value, 30.0);
+        assert_eq!(node.state.alarm, Some(true));
+        assert_eq!(node.publishes().len(), 3);
+    }
+}
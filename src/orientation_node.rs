@@ -1,9 +1,20 @@
+use std::collections::BTreeMap;
+
 use homie5::{
-    HOMIE_UNIT_DEGREE, Homie5DeviceProtocol, HomieID, NodeRef,
-    device_description::{HomieNodeDescription, NodeDescriptionBuilder, PropertyDescriptionBuilder},
+    HOMIE_UNIT_DEGREE, HOMIE_UNIT_HERTZ, Homie5DeviceProtocol, Homie5Message, HomieID, HomieValue,
+    NodeRef, PropertyRef,
+    device_description::{
+        FloatRange, HomieDeviceDescription, HomieNodeDescription, HomiePropertyDescription,
+        IntegerRange, NodeDescriptionBuilder, PropertyDescriptionBuilder,
+    },
 };
+use serde::{Deserialize, Serialize};
 
-use crate::SMARTHOME_CAP_ORIENTATION;
+use crate::{
+    Decimal, ParseError, ParseErrorKind, ParseOutcome, PropertyMetadata, PropertyTopic,
+    SMARTHOME_CAP_ORIENTATION, SetCommandParser, SmarthomePublisher, SmarthomeType,
+    property_metadata, readonly_properties, reject_readonly,
+};
 
 pub const ORIENTATION_NODE_DEFAULT_ID: HomieID = HomieID::new_const("orientation");
 pub const ORIENTATION_NODE_DEFAULT_NAME: &str = "Orientation sensor";
@@ -11,72 +22,311 @@ pub const ORIENTATION_NODE_ORIENT_X_PROP_ID: HomieID = HomieID::new_const("orien
 pub const ORIENTATION_NODE_ORIENT_Y_PROP_ID: HomieID = HomieID::new_const("orientation-y");
 pub const ORIENTATION_NODE_ORIENT_Z_PROP_ID: HomieID = HomieID::new_const("orientation-z");
 pub const ORIENTATION_NODE_TILT_PROP_ID: HomieID = HomieID::new_const("tilt");
+pub const ORIENTATION_NODE_ACCEL_X_PROP_ID: HomieID = HomieID::new_const("acceleration-x");
+pub const ORIENTATION_NODE_ACCEL_Y_PROP_ID: HomieID = HomieID::new_const("acceleration-y");
+pub const ORIENTATION_NODE_ACCEL_Z_PROP_ID: HomieID = HomieID::new_const("acceleration-z");
+pub const ORIENTATION_NODE_SAMPLE_RATE_PROP_ID: HomieID = HomieID::new_const("sample-rate");
+pub const ORIENTATION_NODE_REPORT_THRESHOLD_PROP_ID: HomieID =
+    HomieID::new_const("report-threshold");
 
-#[derive(Debug)]
-pub struct OrientationNode {
-    pub publisher: OrientationNodePublisher,
+const UNIT_METER_PER_SQUARE_SECOND: &str = "m/s²";
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OrientationNodeState {
     pub orientation_x: i64,
     pub orientation_y: i64,
     pub orientation_z: i64,
     pub tilt: i64,
+    pub sample_rate: Option<i64>,
+    pub report_threshold: Option<f64>,
+}
+
+#[derive(Debug)]
+pub struct OrientationNode {
+    pub publisher: OrientationNodePublisher,
+    pub state: OrientationNodeState,
+}
+
+impl OrientationNode {
+    /// Apply a parsed set command and return the `$target` and value publishes
+    /// for the change.
+    pub fn apply_set(&mut self, event: OrientationNodeSetEvents) -> Vec<homie5::client::Publish> {
+        match event {
+            OrientationNodeSetEvents::SampleRate(value) => {
+                self.state.sample_rate = Some(value);
+                [
+                    self.publisher.sample_rate_target(value),
+                    self.publisher.sample_rate(value),
+                ]
+                .into_iter()
+                .flatten()
+                .collect()
+            }
+            OrientationNodeSetEvents::ReportThreshold(value) => {
+                self.state.report_threshold = Some(value);
+                [
+                    self.publisher.report_threshold_target(value),
+                    self.publisher.report_threshold(value),
+                ]
+                .into_iter()
+                .flatten()
+                .collect()
+            }
+        }
+    }
+
+    /// Apply a value reported by the device and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, value) if p == &ORIENTATION_NODE_ORIENT_X_PROP_ID => {
+                let Some(value) = angle_value(value) else {
+                    return Vec::new();
+                };
+                self.state.orientation_x = value.round() as i64;
+                vec![self.publisher.angle(p, value)]
+            }
+            (p, value) if p == &ORIENTATION_NODE_ORIENT_Y_PROP_ID => {
+                let Some(value) = angle_value(value) else {
+                    return Vec::new();
+                };
+                self.state.orientation_y = value.round() as i64;
+                vec![self.publisher.angle(p, value)]
+            }
+            (p, value) if p == &ORIENTATION_NODE_ORIENT_Z_PROP_ID => {
+                let Some(value) = angle_value(value) else {
+                    return Vec::new();
+                };
+                self.state.orientation_z = value.round() as i64;
+                vec![self.publisher.angle(p, value)]
+            }
+            (p, value) if p == &ORIENTATION_NODE_TILT_PROP_ID => {
+                let Some(value) = angle_value(value) else {
+                    return Vec::new();
+                };
+                self.state.tilt = value.round() as i64;
+                self.publisher.tilt(value).into_iter().collect()
+            }
+            (p, HomieValue::Integer(value)) if p == &ORIENTATION_NODE_SAMPLE_RATE_PROP_ID => {
+                self.state.sample_rate = Some(*value);
+                self.publisher.sample_rate(*value).into_iter().collect()
+            }
+            (p, HomieValue::Float(value)) if p == &ORIENTATION_NODE_REPORT_THRESHOLD_PROP_ID => {
+                self.state.report_threshold = Some(*value);
+                self.publisher
+                    .report_threshold(*value)
+                    .into_iter()
+                    .collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            Some(self.publisher.orientation_x(self.state.orientation_x)),
+            Some(self.publisher.orientation_y(self.state.orientation_y)),
+            Some(self.publisher.orientation_z(self.state.orientation_z)),
+            self.publisher.tilt(self.state.tilt as f64),
+            self.state
+                .sample_rate
+                .and_then(|value| self.publisher.sample_rate(value)),
+            self.state
+                .report_threshold
+                .and_then(|value| self.publisher.report_threshold(value)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+/// Angle readings are integers or floats depending on `OrientationNodeConfig::float`.
+fn angle_value(value: &HomieValue) -> Option<f64> {
+    match value {
+        HomieValue::Integer(value) => Some(*value as f64),
+        HomieValue::Float(value) => Some(*value),
+        _ => None,
+    }
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+pub enum OrientationNodeSetEvents {
+    SampleRate(i64),
+    ReportThreshold(f64),
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+#[serde(default)]
+pub struct OrientationNodeConfig {
+    /// Use float instead of integer degrees for the rotation and tilt angles.
+    pub float: bool,
+    /// Number of decimal places used when publishing float angles and
+    /// accelerations; `None` publishes the full value.
+    #[cfg_attr(
+        feature = "proptest",
+        proptest(strategy = "crate::arbitrary::precision()")
+    )]
+    pub precision: Option<usize>,
+    pub tilt: bool,
+    /// Expose `acceleration-x/y/z` in m/s².
+    pub acceleration: bool,
+    /// Expose the settable IMU `sample-rate` in Hz.
+    pub sample_rate: bool,
+    /// Expose the settable `report-threshold`, the angle change in degrees
+    /// that triggers a new report.
+    pub report_threshold: bool,
+    /// Properties to publish as read-only; sets to them are rejected.
+    #[cfg_attr(
+        feature = "proptest",
+        proptest(strategy = "crate::arbitrary::homie_ids()")
+    )]
+    pub readonly: Vec<HomieID>,
+}
+
+impl Default for OrientationNodeConfig {
+    fn default() -> Self {
+        Self {
+            float: false,
+            precision: None,
+            tilt: true,
+            acceleration: false,
+            sample_rate: false,
+            report_threshold: false,
+            readonly: Vec::new(),
+        }
+    }
 }
 
 pub struct OrientationNodeBuilder {
     node_builder: NodeDescriptionBuilder,
+    config: OrientationNodeConfig,
 }
 
 impl Default for OrientationNodeBuilder {
     fn default() -> Self {
-        let db =
-            Self::build_node(NodeDescriptionBuilder::new().name(ORIENTATION_NODE_DEFAULT_NAME))
-                .r#type(SMARTHOME_CAP_ORIENTATION);
-
-        Self { node_builder: db }
+        Self::new(&OrientationNodeConfig::default())
     }
 }
 
 impl OrientationNodeBuilder {
-    pub fn new() -> Self {
-        Default::default()
+    pub fn new(config: &OrientationNodeConfig) -> Self {
+        let db = Self::build_node(
+            NodeDescriptionBuilder::new().name(ORIENTATION_NODE_DEFAULT_NAME),
+            config,
+        )
+        .r#type(SMARTHOME_CAP_ORIENTATION);
+        let db = readonly_properties(db, &config.readonly);
+
+        Self {
+            node_builder: db,
+            config: config.clone(),
+        }
     }
 
-    fn build_node(db: NodeDescriptionBuilder) -> NodeDescriptionBuilder {
+    fn build_node(
+        db: NodeDescriptionBuilder,
+        config: &OrientationNodeConfig,
+    ) -> NodeDescriptionBuilder {
         db.add_property(
             ORIENTATION_NODE_ORIENT_X_PROP_ID,
-            PropertyDescriptionBuilder::integer()
-                .name("Rotation X-Axis")
-                .retained(true)
-                .settable(false)
-                .unit(HOMIE_UNIT_DEGREE)
-                .build(),
+            Self::angle_property("Rotation X-Axis", config.float),
         )
         .add_property(
             ORIENTATION_NODE_ORIENT_Y_PROP_ID,
-            PropertyDescriptionBuilder::integer()
-                .name("Rotation Y-Axis")
-                .retained(true)
-                .settable(false)
-                .unit(HOMIE_UNIT_DEGREE)
-                .build(),
+            Self::angle_property("Rotation Y-Axis", config.float),
         )
         .add_property(
             ORIENTATION_NODE_ORIENT_Z_PROP_ID,
-            PropertyDescriptionBuilder::integer()
-                .name("Rotation Z-Axis")
+            Self::angle_property("Rotation Z-Axis", config.float),
+        )
+        .add_property_cond(ORIENTATION_NODE_TILT_PROP_ID, config.tilt, || {
+            Self::angle_property("Tilt angle", config.float)
+        })
+        .add_property_cond(
+            ORIENTATION_NODE_ACCEL_X_PROP_ID,
+            config.acceleration,
+            || Self::acceleration_property("Acceleration X-Axis"),
+        )
+        .add_property_cond(
+            ORIENTATION_NODE_ACCEL_Y_PROP_ID,
+            config.acceleration,
+            || Self::acceleration_property("Acceleration Y-Axis"),
+        )
+        .add_property_cond(
+            ORIENTATION_NODE_ACCEL_Z_PROP_ID,
+            config.acceleration,
+            || Self::acceleration_property("Acceleration Z-Axis"),
+        )
+        .add_property_cond(
+            ORIENTATION_NODE_SAMPLE_RATE_PROP_ID,
+            config.sample_rate,
+            || {
+                PropertyDescriptionBuilder::integer()
+                    .name("Sample rate")
+                    .unit(HOMIE_UNIT_HERTZ)
+                    .integer_range(IntegerRange {
+                        min: Some(1),
+                        max: None,
+                        step: None,
+                    })
+                    .retained(true)
+                    .settable(true)
+                    .build()
+            },
+        )
+        .add_property_cond(
+            ORIENTATION_NODE_REPORT_THRESHOLD_PROP_ID,
+            config.report_threshold,
+            || {
+                PropertyDescriptionBuilder::float()
+                    .name("Report threshold")
+                    .unit(HOMIE_UNIT_DEGREE)
+                    .float_range(FloatRange {
+                        min: Some(0.0),
+                        max: None,
+                        step: None,
+                    })
+                    .retained(true)
+                    .settable(true)
+                    .build()
+            },
+        )
+    }
+
+    fn angle_property(name: &str, float: bool) -> HomiePropertyDescription {
+        if float {
+            PropertyDescriptionBuilder::float()
+                .name(name)
                 .retained(true)
                 .settable(false)
                 .unit(HOMIE_UNIT_DEGREE)
-                .build(),
-        )
-        .add_property(
-            ORIENTATION_NODE_TILT_PROP_ID,
+                .build()
+        } else {
             PropertyDescriptionBuilder::integer()
-                .name("Tilt angle")
+                .name(name)
                 .retained(true)
                 .settable(false)
                 .unit(HOMIE_UNIT_DEGREE)
-                .build(),
-        )
+                .build()
+        }
+    }
+
+    fn acceleration_property(name: &str) -> HomiePropertyDescription {
+        PropertyDescriptionBuilder::float()
+            .name(name)
+            .retained(true)
+            .settable(false)
+            .unit(UNIT_METER_PER_SQUARE_SECOND)
+            .build()
     }
 
     pub fn name<S: Into<String>>(mut self, name: impl Into<Option<S>>) -> Self {
@@ -84,10 +334,23 @@ impl OrientationNodeBuilder {
         self
     }
 
+    /// Append a subtype suffix to the node type (`orientation-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::Orientation.with_subtype(subtype));
+        self
+    }
+
     pub fn build(self) -> HomieNodeDescription {
         self.node_builder.build()
     }
 
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &OrientationNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
     pub fn build_with_publisher(
         self,
         node_id: HomieID,
@@ -102,6 +365,7 @@ impl OrientationNodeBuilder {
                     node_id,
                 ),
                 client.clone(),
+                self.config,
             ),
         )
     }
@@ -111,21 +375,51 @@ impl OrientationNodeBuilder {
 pub struct OrientationNodePublisher {
     client: Homie5DeviceProtocol,
     node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
+    config: OrientationNodeConfig,
     orient_x_prop: HomieID,
     orient_y_prop: HomieID,
     orient_z_prop: HomieID,
     tilt_prop: HomieID,
+    accel_x_prop: HomieID,
+    accel_y_prop: HomieID,
+    accel_z_prop: HomieID,
+    sample_rate_prop: HomieID,
+    report_threshold_prop: HomieID,
+    orient_topics: [PropertyTopic; 3],
+    tilt_topic: PropertyTopic,
+    accel_topics: [PropertyTopic; 3],
 }
 
 impl OrientationNodePublisher {
-    pub fn new(node: NodeRef, client: Homie5DeviceProtocol) -> Self {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: OrientationNodeConfig) -> Self {
+        let metadata = property_metadata(&OrientationNodeBuilder::new(&config).build());
+        let topic = |prop_id| PropertyTopic::new(&client, node.node_id(), prop_id, true);
         Self {
+            orient_topics: [
+                topic(&ORIENTATION_NODE_ORIENT_X_PROP_ID),
+                topic(&ORIENTATION_NODE_ORIENT_Y_PROP_ID),
+                topic(&ORIENTATION_NODE_ORIENT_Z_PROP_ID),
+            ],
+            tilt_topic: topic(&ORIENTATION_NODE_TILT_PROP_ID),
+            accel_topics: [
+                topic(&ORIENTATION_NODE_ACCEL_X_PROP_ID),
+                topic(&ORIENTATION_NODE_ACCEL_Y_PROP_ID),
+                topic(&ORIENTATION_NODE_ACCEL_Z_PROP_ID),
+            ],
             node,
+            metadata,
             client,
+            config,
             orient_x_prop: ORIENTATION_NODE_ORIENT_X_PROP_ID,
             orient_y_prop: ORIENTATION_NODE_ORIENT_Y_PROP_ID,
             orient_z_prop: ORIENTATION_NODE_ORIENT_Z_PROP_ID,
             tilt_prop: ORIENTATION_NODE_TILT_PROP_ID,
+            accel_x_prop: ORIENTATION_NODE_ACCEL_X_PROP_ID,
+            accel_y_prop: ORIENTATION_NODE_ACCEL_Y_PROP_ID,
+            accel_z_prop: ORIENTATION_NODE_ACCEL_Z_PROP_ID,
+            sample_rate_prop: ORIENTATION_NODE_SAMPLE_RATE_PROP_ID,
+            report_threshold_prop: ORIENTATION_NODE_REPORT_THRESHOLD_PROP_ID,
         }
     }
 
@@ -156,12 +450,332 @@ impl OrientationNodePublisher {
         )
     }
 
-    pub fn tilt(&self, value: i64) -> homie5::client::Publish {
+    /// Publish all three rotation angles, formatted for the configured datatype.
+    pub fn orientation(&self, x: f64, y: f64, z: f64) -> Vec<homie5::client::Publish> {
+        vec![
+            self.angle(&self.orient_x_prop, x),
+            self.angle(&self.orient_y_prop, y),
+            self.angle(&self.orient_z_prop, z),
+        ]
+    }
+
+    fn angle(&self, prop: &HomieID, value: f64) -> homie5::client::Publish {
         self.client.publish_value(
+            self.node.node_id(),
+            prop,
+            self.format_angle(value).to_string(),
+            true,
+        )
+    }
+
+    pub fn tilt(&self, value: f64) -> Option<homie5::client::Publish> {
+        if !self.config.tilt {
+            return None;
+        }
+        Some(self.client.publish_value(
             self.node.node_id(),
             &self.tilt_prop,
+            self.format_angle(value).to_string(),
+            true,
+        ))
+    }
+
+    pub fn acceleration(&self, x: f64, y: f64, z: f64) -> Vec<homie5::client::Publish> {
+        if !self.config.acceleration {
+            return Vec::new();
+        }
+        vec![
+            self.client.publish_value(
+                self.node.node_id(),
+                &self.accel_x_prop,
+                self.format_float(x).to_string(),
+                true,
+            ),
+            self.client.publish_value(
+                self.node.node_id(),
+                &self.accel_y_prop,
+                self.format_float(y).to_string(),
+                true,
+            ),
+            self.client.publish_value(
+                self.node.node_id(),
+                &self.accel_z_prop,
+                self.format_float(z).to_string(),
+                true,
+            ),
+        ]
+    }
+
+    pub fn sample_rate(&self, value: i64) -> Option<homie5::client::Publish> {
+        if !self.config.sample_rate {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.sample_rate_prop,
             value.to_string(),
             true,
-        )
+        ))
+    }
+
+    pub fn sample_rate_target(&self, value: i64) -> Option<homie5::client::Publish> {
+        if !self.config.sample_rate {
+            return None;
+        }
+        Some(self.client.publish_target(
+            self.node.node_id(),
+            &self.sample_rate_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+
+    pub fn report_threshold(&self, value: f64) -> Option<homie5::client::Publish> {
+        if !self.config.report_threshold {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.report_threshold_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+
+    pub fn report_threshold_target(&self, value: f64) -> Option<homie5::client::Publish> {
+        if !self.config.report_threshold {
+            return None;
+        }
+        Some(self.client.publish_target(
+            self.node.node_id(),
+            &self.report_threshold_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+
+    /// Like [`Self::orientation`], but overwrites `buffers` instead of
+    /// allocating new publishes; for sensors reporting several times per
+    /// second.
+    pub fn orientation_into(
+        &self,
+        x: f64,
+        y: f64,
+        z: f64,
+        buffers: &mut [homie5::client::Publish; 3],
+    ) {
+        for ((topic, value), buffer) in self.orient_topics.iter().zip([x, y, z]).zip(buffers) {
+            topic.write(self.format_angle(value), buffer);
+        }
+    }
+
+    /// Like [`Self::tilt`], but overwrites `buffer`. Returns `false` without
+    /// touching it when the node has no `tilt` property.
+    pub fn tilt_into(&self, value: f64, buffer: &mut homie5::client::Publish) -> bool {
+        if !self.config.tilt {
+            return false;
+        }
+        self.tilt_topic.write(self.format_angle(value), buffer);
+        true
+    }
+
+    /// Like [`Self::acceleration`], but overwrites `buffers`. Returns `false`
+    /// without touching them when the node has no acceleration properties.
+    pub fn acceleration_into(
+        &self,
+        x: f64,
+        y: f64,
+        z: f64,
+        buffers: &mut [homie5::client::Publish; 3],
+    ) -> bool {
+        if !self.config.acceleration {
+            return false;
+        }
+        for ((topic, value), buffer) in self.accel_topics.iter().zip([x, y, z]).zip(buffers) {
+            topic.write(self.format_float(value), buffer);
+        }
+        true
+    }
+
+    fn format_angle(&self, value: f64) -> Decimal {
+        Decimal {
+            value,
+            precision: self.config.precision,
+            integer: !self.config.float,
+        }
+    }
+
+    fn format_float(&self, value: f64) -> Decimal {
+        Decimal::new(value, self.config.precision)
+    }
+}
+
+impl SmarthomePublisher for OrientationNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::Orientation
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![
+            self.orient_x_prop.clone(),
+            self.orient_y_prop.clone(),
+            self.orient_z_prop.clone(),
+            self.tilt_prop.clone(),
+            self.accel_x_prop.clone(),
+            self.accel_y_prop.clone(),
+            self.accel_z_prop.clone(),
+            self.sample_rate_prop.clone(),
+            self.report_threshold_prop.clone(),
+        ]
+    }
+}
+
+impl SetCommandParser for OrientationNodePublisher {
+    type Event = OrientationNodeSetEvents;
+
+    fn parse_set(
+        &self,
+        property: &PropertyRef,
+        desc: &HomieDeviceDescription,
+        set_value: &str,
+    ) -> ParseOutcome<Self::Event> {
+        if let Some(outcome) = reject_readonly(&self.node, property, desc, set_value) {
+            return outcome;
+        }
+
+        let is_sample_rate = property.match_with_node(&self.node, &self.sample_rate_prop);
+        if !is_sample_rate && !property.match_with_node(&self.node, &self.report_threshold_prop) {
+            return ParseOutcome::NoMatch;
+        }
+
+        let Some(parsed) = desc.with_property(property, |prop_desc| {
+            HomieValue::parse(set_value, prop_desc)
+        }) else {
+            return ParseOutcome::Invalid(ParseError::new(
+                property.prop_id().to_string(),
+                set_value,
+                ParseErrorKind::MissingPropertyDescription,
+            ));
+        };
+
+        match parsed {
+            Ok(HomieValue::Integer(value)) if is_sample_rate => {
+                ParseOutcome::Parsed(OrientationNodeSetEvents::SampleRate(value))
+            }
+            Ok(HomieValue::Float(value)) if !is_sample_rate => {
+                ParseOutcome::Parsed(OrientationNodeSetEvents::ReportThreshold(value))
+            }
+            _ => ParseOutcome::Invalid(ParseError::new(
+                property.prop_id().to_string(),
+                set_value,
+                ParseErrorKind::InvalidHomieValue,
+            )),
+        }
+    }
+
+    fn parse_set_event(
+        &self,
+        desc: &HomieDeviceDescription,
+        event: &Homie5Message,
+    ) -> ParseOutcome<Self::Event> {
+        match event {
+            Homie5Message::PropertySet {
+                property,
+                set_value,
+            } => self.parse_set(property, desc, set_value),
+            _ => ParseOutcome::Invalid(ParseError::new(
+                self.sample_rate_prop.to_string(),
+                "",
+                ParseErrorKind::UnexpectedMessageType,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use homie5::HomieDomain;
+
+    use super::*;
+    use crate::{StandaloneSetParser, publish_buffer};
+
+    #[test]
+    fn tuning_properties_parse_and_precision_applies() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let config = OrientationNodeConfig {
+            float: true,
+            precision: Some(2),
+            sample_rate: true,
+            report_threshold: true,
+            ..OrientationNodeConfig::default()
+        };
+        let parser: StandaloneSetParser<_> = OrientationNodeBuilder::new(&config)
+            .build_with_publisher(ORIENTATION_NODE_DEFAULT_ID, &client)
+            .into();
+        let node = parser.publisher().node_ref().clone();
+        let sample_rate =
+            PropertyRef::from_node(node.clone(), ORIENTATION_NODE_SAMPLE_RATE_PROP_ID);
+        let threshold = PropertyRef::from_node(node, ORIENTATION_NODE_REPORT_THRESHOLD_PROP_ID);
+
+        assert_eq!(
+            parser.parse_set(&sample_rate, "50"),
+            ParseOutcome::Parsed(OrientationNodeSetEvents::SampleRate(50))
+        );
+        assert!(matches!(
+            parser.parse_set(&sample_rate, "0"),
+            ParseOutcome::Invalid(_)
+        ));
+        assert_eq!(
+            parser.parse_set(&threshold, "0.5"),
+            ParseOutcome::Parsed(OrientationNodeSetEvents::ReportThreshold(0.5))
+        );
+
+        let publisher = parser.into_publisher();
+        let publishes = publisher.orientation(12.3456, -0.5, 90.0);
+        assert_eq!(publishes[0].payload, b"12.35".to_vec());
+        assert_eq!(publishes[2].payload, b"90.00".to_vec());
+
+        let mut buffers = [publish_buffer(), publish_buffer(), publish_buffer()];
+        publisher.orientation_into(12.3456, -0.5, 90.0, &mut buffers);
+        assert!(buffers.iter().eq(publishes.iter()));
+        assert!(!publisher.acceleration_into(0.0, 0.0, 9.81, &mut buffers));
+    }
+
+    #[test]
+    fn apply_value_accepts_float_angles() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let config = OrientationNodeConfig {
+            float: true,
+            precision: Some(1),
+            ..OrientationNodeConfig::default()
+        };
+        let (_, publisher) = OrientationNodeBuilder::new(&config)
+            .build_with_publisher(ORIENTATION_NODE_DEFAULT_ID, &client);
+        let mut node = OrientationNode {
+            publisher,
+            state: OrientationNodeState::default(),
+        };
+
+        let publishes = node.apply_value(
+            &ORIENTATION_NODE_ORIENT_Y_PROP_ID,
+            &HomieValue::Float(-12.34),
+        );
+        assert_eq!(publishes.len(), 1);
+        assert_eq!(publishes[0].payload, b"-12.3".to_vec());
+        assert_eq!(node.state.orientation_y, -12);
+        assert!(
+            node.apply_value(&ORIENTATION_NODE_ORIENT_Y_PROP_ID, &HomieValue::Bool(true))
+                .is_empty()
+        );
     }
 }
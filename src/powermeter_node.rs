@@ -1,6 +1,8 @@
+use std::collections::BTreeMap;
+
 use homie5::{
     HOMIE_UNIT_HERTZ, HOMIE_UNIT_MILI_AMPERE, HOMIE_UNIT_VOLT, HOMIE_UNIT_WATT,
-    Homie5DeviceProtocol, HomieID, NodeRef,
+    Homie5DeviceProtocol, HomieID, HomieValue, NodeRef,
     device_description::{
         FloatRange, HomieNodeDescription, NodeDescriptionBuilder,
         PropertyDescriptionBuilder,
@@ -8,7 +10,10 @@ use homie5::{
 };
 use serde::{Deserialize, Serialize};
 
-use crate::SMARTHOME_CAP_POWERMETER;
+use crate::{
+    Decimal, PropertyMetadata, PropertyTopic, SMARTHOME_CAP_POWERMETER, SmarthomePublisher,
+    SmarthomeType, property_metadata,
+};
 
 pub const POWERMETER_NODE_DEFAULT_ID: HomieID = HomieID::new_const("powermeter");
 pub const POWERMETER_NODE_DEFAULT_NAME: &str = "Powermeter";
@@ -18,9 +23,9 @@ pub const POWERMETER_NODE_VOLTAGE_PROP_ID: HomieID = HomieID::new_const("voltage
 pub const POWERMETER_NODE_FREQUENCY_PROP_ID: HomieID = HomieID::new_const("frequency");
 pub const POWERMETER_NODE_CONSUMPTION_PROP_ID: HomieID = HomieID::new_const("consumption");
 
-#[derive(Debug)]
-pub struct PowermeterNode {
-    pub publisher: PowermeterNodePublisher,
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PowermeterNodeState {
     pub power: f64,
     pub current: Option<f64>,
     pub voltage: Option<f64>,
@@ -28,13 +33,83 @@ pub struct PowermeterNode {
     pub consumption: Option<f64>,
 }
 
+#[derive(Debug)]
+pub struct PowermeterNode {
+    pub publisher: PowermeterNodePublisher,
+    pub state: PowermeterNodeState,
+}
+
+impl PowermeterNode {
+    /// Apply a value reported by the device and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Float(value)) if p == &POWERMETER_NODE_POWER_PROP_ID => {
+                self.state.power = *value;
+                vec![self.publisher.power(*value)]
+            }
+            (p, HomieValue::Float(value)) if p == &POWERMETER_NODE_CURRENT_PROP_ID => {
+                self.state.current = Some(*value);
+                vec![self.publisher.current(*value)]
+            }
+            (p, HomieValue::Float(value)) if p == &POWERMETER_NODE_VOLTAGE_PROP_ID => {
+                self.state.voltage = Some(*value);
+                vec![self.publisher.voltage(*value)]
+            }
+            (p, HomieValue::Float(value)) if p == &POWERMETER_NODE_FREQUENCY_PROP_ID => {
+                self.state.frequency = Some(*value);
+                vec![self.publisher.frequency(*value)]
+            }
+            (p, HomieValue::Float(value)) if p == &POWERMETER_NODE_CONSUMPTION_PROP_ID => {
+                self.state.consumption = Some(*value);
+                vec![self.publisher.consumption(*value)]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            Some(self.publisher.power(self.state.power)),
+            self.state
+                .current
+                .map(|value| self.publisher.current(value)),
+            self.state
+                .voltage
+                .map(|value| self.publisher.voltage(value)),
+            self.state
+                .frequency
+                .map(|value| self.publisher.frequency(value)),
+            self.state
+                .consumption
+                .map(|value| self.publisher.consumption(value)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 #[serde(default)]
 pub struct PowermeterNodeConfig {
     pub current: bool,
     pub voltage: bool,
     pub frequency: bool,
     pub consumption: bool,
+    /// Decimal places of the published readings; `None` publishes them as
+    /// reported.
+    #[cfg_attr(
+        feature = "proptest",
+        proptest(strategy = "crate::arbitrary::precision()")
+    )]
+    pub precision: Option<usize>,
 }
 
 impl Default for PowermeterNodeConfig {
@@ -44,22 +119,18 @@ impl Default for PowermeterNodeConfig {
             voltage: true,
             frequency: false,
             consumption: true,
+            precision: None,
         }
     }
 }
 pub struct PowermeterNodeBuilder {
     node_builder: NodeDescriptionBuilder,
+    config: PowermeterNodeConfig,
 }
 
 impl Default for PowermeterNodeBuilder {
     fn default() -> Self {
-        let db = Self::build_node(
-            NodeDescriptionBuilder::new().name(POWERMETER_NODE_DEFAULT_NAME),
-            &Default::default(),
-        )
-        .r#type(SMARTHOME_CAP_POWERMETER);
-
-        Self { node_builder: db }
+        Self::new(&PowermeterNodeConfig::default())
     }
 }
 
@@ -71,7 +142,10 @@ impl PowermeterNodeBuilder {
         )
         .r#type(SMARTHOME_CAP_POWERMETER);
 
-        Self { node_builder: db }
+        Self {
+            node_builder: db,
+            config: config.clone(),
+        }
     }
 
     fn build_node(
@@ -155,10 +229,23 @@ impl PowermeterNodeBuilder {
         self
     }
 
+    /// Append a subtype suffix to the node type (`powermeter-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::Powermeter.with_subtype(subtype));
+        self
+    }
+
     pub fn build(self) -> HomieNodeDescription {
         self.node_builder.build()
     }
 
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &PowermeterNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
     pub fn build_with_publisher(
         self,
         node_id: HomieID,
@@ -173,6 +260,7 @@ impl PowermeterNodeBuilder {
                     node_id,
                 ),
                 client.clone(),
+                &self.config,
             ),
         )
     }
@@ -182,17 +270,33 @@ impl PowermeterNodeBuilder {
 pub struct PowermeterNodePublisher {
     client: Homie5DeviceProtocol,
     node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
+    precision: Option<usize>,
     power_prop: HomieID,
     current_prop: HomieID,
     voltage_prop: HomieID,
     frequency_prop: HomieID,
     consumption_prop: HomieID,
+    power_topic: PropertyTopic,
+    current_topic: PropertyTopic,
+    voltage_topic: PropertyTopic,
+    frequency_topic: PropertyTopic,
+    consumption_topic: PropertyTopic,
 }
 
 impl PowermeterNodePublisher {
-    pub fn new(node: NodeRef, client: Homie5DeviceProtocol) -> Self {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: &PowermeterNodeConfig) -> Self {
+        let metadata = property_metadata(&PowermeterNodeBuilder::new(config).build());
+        let topic = |prop_id| PropertyTopic::new(&client, node.node_id(), prop_id, true);
         Self {
+            power_topic: topic(&POWERMETER_NODE_POWER_PROP_ID),
+            current_topic: topic(&POWERMETER_NODE_CURRENT_PROP_ID),
+            voltage_topic: topic(&POWERMETER_NODE_VOLTAGE_PROP_ID),
+            frequency_topic: topic(&POWERMETER_NODE_FREQUENCY_PROP_ID),
+            consumption_topic: topic(&POWERMETER_NODE_CONSUMPTION_PROP_ID),
             node,
+            metadata,
+            precision: config.precision,
             client,
             power_prop: POWERMETER_NODE_POWER_PROP_ID,
             current_prop: POWERMETER_NODE_CURRENT_PROP_ID,
@@ -206,7 +310,7 @@ impl PowermeterNodePublisher {
         self.client.publish_value(
             self.node.node_id(),
             &self.power_prop,
-            value.to_string(),
+            self.format(value).to_string(),
             true,
         )
     }
@@ -214,7 +318,7 @@ impl PowermeterNodePublisher {
         self.client.publish_value(
             self.node.node_id(),
             &self.current_prop,
-            value.to_string(),
+            self.format(value).to_string(),
             true,
         )
     }
@@ -222,7 +326,7 @@ impl PowermeterNodePublisher {
         self.client.publish_value(
             self.node.node_id(),
             &self.voltage_prop,
-            value.to_string(),
+            self.format(value).to_string(),
             true,
         )
     }
@@ -230,7 +334,7 @@ impl PowermeterNodePublisher {
         self.client.publish_value(
             self.node.node_id(),
             &self.frequency_prop,
-            value.to_string(),
+            self.format(value).to_string(),
             true,
         )
     }
@@ -238,8 +342,104 @@ impl PowermeterNodePublisher {
         self.client.publish_value(
             self.node.node_id(),
             &self.consumption_prop,
-            value.to_string(),
+            self.format(value).to_string(),
             true,
         )
     }
+
+    /// Like [`Self::power`], but overwrites `buffer` instead of allocating a
+    /// new publish; for meters reporting several times per second.
+    pub fn power_into(&self, value: f64, buffer: &mut homie5::client::Publish) {
+        self.power_topic.write(self.format(value), buffer);
+    }
+
+    pub fn current_into(&self, value: f64, buffer: &mut homie5::client::Publish) {
+        self.current_topic.write(self.format(value), buffer);
+    }
+
+    pub fn voltage_into(&self, value: f64, buffer: &mut homie5::client::Publish) {
+        self.voltage_topic.write(self.format(value), buffer);
+    }
+
+    pub fn frequency_into(&self, value: f64, buffer: &mut homie5::client::Publish) {
+        self.frequency_topic.write(self.format(value), buffer);
+    }
+
+    pub fn consumption_into(&self, value: f64, buffer: &mut homie5::client::Publish) {
+        self.consumption_topic.write(self.format(value), buffer);
+    }
+
+    fn format(&self, value: f64) -> Decimal {
+        Decimal::new(value, self.precision)
+    }
+}
+
+impl SmarthomePublisher for PowermeterNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::Powermeter
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![
+            self.power_prop.clone(),
+            self.current_prop.clone(),
+            self.voltage_prop.clone(),
+            self.frequency_prop.clone(),
+            self.consumption_prop.clone(),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use homie5::HomieDomain;
+
+    use super::*;
+    use crate::publish_buffer;
+
+    #[test]
+    fn buffered_publishes_match_allocating_ones() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let (_, publisher) = PowermeterNodeBuilder::default()
+            .build_with_publisher(POWERMETER_NODE_DEFAULT_ID, &client);
+
+        let mut buffer = publish_buffer();
+        publisher.voltage_into(230.1, &mut buffer);
+        assert!(buffer == publisher.voltage(230.1));
+        let topic = buffer.topic.as_ptr();
+
+        publisher.power_into(1234.5, &mut buffer);
+        assert!(buffer == publisher.power(1234.5));
+        assert_eq!(buffer.topic.as_ptr(), topic);
+    }
+
+    #[test]
+    fn precision_rounds_float_artifacts() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let value = 0.1 + 0.2;
+        let (_, publisher) = PowermeterNodeBuilder::default()
+            .build_with_publisher(POWERMETER_NODE_DEFAULT_ID, &client);
+        assert_eq!(publisher.power(value).payload, b"0.30000000000000004");
+
+        let config = PowermeterNodeConfig {
+            precision: Some(1),
+            ..Default::default()
+        };
+        let (_, publisher) = PowermeterNodeBuilder::new(&config)
+            .build_with_publisher(POWERMETER_NODE_DEFAULT_ID, &client);
+        assert_eq!(publisher.power(value).payload, b"0.3");
+        let mut buffer = publish_buffer();
+        publisher.consumption_into(value, &mut buffer);
+        assert_eq!(buffer.payload, b"0.3");
+    }
 }
@@ -1,28 +1,210 @@
+use std::collections::BTreeMap;
+
 use homie5::{
     Homie5DeviceProtocol, Homie5Message, HomieID, HomieValue, NodeRef, PropertyRef,
     device_description::{
-        HomieDeviceDescription, HomieNodeDescription, NodeDescriptionBuilder,
+        HomieDeviceDescription, HomieNodeDescription, HomiePropertyFormat, NodeDescriptionBuilder,
         PropertyDescriptionBuilder,
     },
 };
 use serde::{Deserialize, Serialize};
 
-use crate::{ParseError, ParseErrorKind, ParseOutcome, SMARTHOME_CAP_SCENE, SetCommandParser};
+use crate::{
+    ConfigError, ParseError, ParseErrorKind, ParseOutcome, PropertyMetadata, SMARTHOME_CAP_SCENE,
+    SetCommandParser, SmarthomePublisher, SmarthomeType, property_metadata, readonly_properties,
+    reject_readonly, validate_enum_format,
+};
 
 pub const SCENE_NODE_DEFAULT_ID: HomieID = HomieID::new_const("scene");
 pub const SCENE_NODE_DEFAULT_NAME: &str = "Scene recall";
 pub const SCENE_NODE_RECALL_PROP_ID: HomieID = HomieID::new_const("recall");
+pub const SCENE_NODE_REMOVE_PROP_ID: HomieID = HomieID::new_const("remove");
+pub const SCENE_NODE_LABELS_PROP_ID: HomieID = HomieID::new_const("labels");
+pub const SCENE_NODE_ACTIVE_SCENE_PROP_ID: HomieID = HomieID::new_const("active-scene");
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SceneNodeState {
+    pub active_scene: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct SceneNode {
+    pub publisher: SceneNodePublisher,
+    pub state: SceneNodeState,
+}
+
+impl SceneNode {
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        self.state
+            .active_scene
+            .as_deref()
+            .and_then(|value| self.publisher.active_scene(value))
+            .into_iter()
+            .collect()
+    }
+
+    /// Apply a parsed set command. A recall of a configured scene records it as
+    /// the active scene and returns the `recall` and `active-scene` publishes.
+    /// Remove commands change the node description and produce no publishes;
+    /// handle them with [`Self::remove_scene`].
+    pub fn apply_set(&mut self, event: SceneNodeActions) -> Vec<homie5::client::Publish> {
+        let Some(recall) = self.publisher.recall(&event) else {
+            return Vec::new();
+        };
+        let SceneNodeActions::Recall(scene) = event else {
+            return Vec::new();
+        };
+        let active_scene = self.publisher.active_scene(&scene);
+        self.state.active_scene = Some(scene);
+        std::iter::once(recall).chain(active_scene).collect()
+    }
+
+    /// Apply an `active-scene` value reported by the device, e.g. a scene
+    /// recalled from a wall panel, and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Enum(value)) if p == &SCENE_NODE_ACTIVE_SCENE_PROP_ID => {
+                let Some(publish) = self.publisher.active_scene(value) else {
+                    return Vec::new();
+                };
+                self.state.active_scene = Some(value.clone());
+                vec![publish]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Remove a scene like [`SceneNodePublisher::remove_scene`]. If it was the
+    /// active scene, it is forgotten and the publish clearing the retained
+    /// `active-scene` value is returned along with the updated description.
+    pub fn remove_scene(
+        &mut self,
+        scene: &str,
+        desc: &HomieNodeDescription,
+    ) -> Option<(HomieNodeDescription, Option<homie5::client::Publish>)> {
+        let desc = self.publisher.remove_scene(scene, desc)?;
+        if self.state.active_scene.as_deref() != Some(scene) {
+            return Some((desc, None));
+        }
+        self.state.active_scene = None;
+        Some((desc, self.publisher.clear_active_scene()))
+    }
+}
 
 #[derive(Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 pub enum SceneNodeActions {
     Recall(String),
+    Remove(String),
+}
+
+/// A configured scene. The `id` is used on the wire (`recall`/`remove`), the
+/// optional `label` is meant for display.
+///
+/// Deserializes from either a plain id string or a `{ id, label }` map.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+#[serde(from = "SceneNodeSceneRepr")]
+pub struct SceneNodeScene {
+    #[cfg_attr(
+        feature = "proptest",
+        proptest(strategy = "crate::arbitrary::enum_value()")
+    )]
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+impl SceneNodeScene {
+    pub fn new(id: impl Into<String>, label: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            label: Some(label.into()),
+        }
+    }
+
+    /// The display label, falling back to the id.
+    pub fn label(&self) -> &str {
+        self.label.as_deref().unwrap_or(&self.id)
+    }
+}
+
+impl From<String> for SceneNodeScene {
+    fn from(id: String) -> Self {
+        Self { id, label: None }
+    }
+}
+
+impl From<&str> for SceneNodeScene {
+    fn from(id: &str) -> Self {
+        id.to_string().into()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SceneNodeSceneRepr {
+    Id(String),
+    Full {
+        id: String,
+        #[serde(default)]
+        label: Option<String>,
+    },
+}
+
+impl From<SceneNodeSceneRepr> for SceneNodeScene {
+    fn from(value: SceneNodeSceneRepr) -> Self {
+        match value {
+            SceneNodeSceneRepr::Id(id) => id.into(),
+            SceneNodeSceneRepr::Full { id, label } => Self { id, label },
+        }
+    }
 }
 
 #[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 #[serde(default)]
 pub struct SceneNodeConfig {
-    pub scenes: Vec<String>,
+    #[cfg_attr(
+        feature = "proptest",
+        proptest(strategy = "crate::arbitrary::scenes()")
+    )]
+    pub scenes: Vec<SceneNodeScene>,
     pub settable: bool,
+    /// Expose a settable `remove` property to delete scenes through the node.
+    pub remove: bool,
+    /// Expose a retained JSON `labels` property mapping scene ids to labels.
+    pub labels: bool,
+    /// Expose a retained `active-scene` property holding the last recalled scene.
+    pub active_scene: bool,
+    /// Properties to publish as read-only; sets to them are rejected.
+    #[cfg_attr(
+        feature = "proptest",
+        proptest(strategy = "crate::arbitrary::homie_ids()")
+    )]
+    pub readonly: Vec<HomieID>,
+}
+
+impl SceneNodeConfig {
+    /// Check the config-provided enum values before building the node.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        validate_enum_format(&SCENE_NODE_RECALL_PROP_ID, self.scene_ids())
+    }
+
+    pub fn scene_ids(&self) -> impl Iterator<Item = &str> {
+        self.scenes.iter().map(|s| s.id.as_str())
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.scene_ids().any(|s| s == id)
+    }
 }
 
 pub struct SceneNodeBuilder {
@@ -37,6 +219,7 @@ impl SceneNodeBuilder {
             config,
         )
         .r#type(SMARTHOME_CAP_SCENE);
+        let db = readonly_properties(db, &config.readonly);
 
         Self {
             node_builder: db,
@@ -47,13 +230,36 @@ impl SceneNodeBuilder {
     fn build_node(db: NodeDescriptionBuilder, config: &SceneNodeConfig) -> NodeDescriptionBuilder {
         db.add_property(
             SCENE_NODE_RECALL_PROP_ID,
-            PropertyDescriptionBuilder::enumeration(config.scenes.clone())
+            PropertyDescriptionBuilder::enumeration(config.scene_ids())
                 .unwrap()
                 .name("Recall a scene")
                 .settable(config.settable)
                 .retained(false)
                 .build(),
         )
+        .add_property_cond(SCENE_NODE_REMOVE_PROP_ID, config.remove, || {
+            PropertyDescriptionBuilder::enumeration(config.scene_ids())
+                .unwrap()
+                .name("Remove a scene")
+                .settable(true)
+                .retained(false)
+                .build()
+        })
+        .add_property_cond(SCENE_NODE_LABELS_PROP_ID, config.labels, || {
+            PropertyDescriptionBuilder::json()
+                .name("Scene labels")
+                .settable(false)
+                .retained(true)
+                .build()
+        })
+        .add_property_cond(SCENE_NODE_ACTIVE_SCENE_PROP_ID, config.active_scene, || {
+            PropertyDescriptionBuilder::enumeration(config.scene_ids())
+                .unwrap()
+                .name("Active scene")
+                .settable(false)
+                .retained(true)
+                .build()
+        })
     }
 
     pub fn name<S: Into<String>>(mut self, name: impl Into<Option<S>>) -> Self {
@@ -61,10 +267,23 @@ impl SceneNodeBuilder {
         self
     }
 
+    /// Append a subtype suffix to the node type (`scene-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::Scene.with_subtype(subtype));
+        self
+    }
+
     pub fn build(self) -> HomieNodeDescription {
         self.node_builder.build()
     }
 
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &SceneNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
     pub fn build_with_publisher(
         self,
         node_id: HomieID,
@@ -89,25 +308,34 @@ impl SceneNodeBuilder {
 pub struct SceneNodePublisher {
     client: Homie5DeviceProtocol,
     node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
     recall_prop: HomieID,
+    remove_prop: HomieID,
+    labels_prop: HomieID,
+    active_scene_prop: HomieID,
     config: SceneNodeConfig,
 }
 
 impl SceneNodePublisher {
     pub fn new(node: NodeRef, config: SceneNodeConfig, client: Homie5DeviceProtocol) -> Self {
+        let metadata = property_metadata(&SceneNodeBuilder::new(&config).build());
         Self {
             node,
+            metadata,
             config,
             client,
             recall_prop: SCENE_NODE_RECALL_PROP_ID,
+            remove_prop: SCENE_NODE_REMOVE_PROP_ID,
+            labels_prop: SCENE_NODE_LABELS_PROP_ID,
+            active_scene_prop: SCENE_NODE_ACTIVE_SCENE_PROP_ID,
         }
     }
 
-    pub fn recall(
-        &self,
-        SceneNodeActions::Recall(scene): &SceneNodeActions,
-    ) -> Option<homie5::client::Publish> {
-        if self.config.scenes.contains(scene) {
+    pub fn recall(&self, action: &SceneNodeActions) -> Option<homie5::client::Publish> {
+        let SceneNodeActions::Recall(scene) = action else {
+            return None;
+        };
+        if self.config.contains(scene) {
             Some(
                 self.client
                     .publish_value(self.node.node_id(), &self.recall_prop, scene, false),
@@ -116,6 +344,111 @@ impl SceneNodePublisher {
             None
         }
     }
+
+    /// Publish the last recalled scene. [`SceneNode::apply_set`] includes it
+    /// with the `recall` publish.
+    pub fn active_scene(&self, scene: &str) -> Option<homie5::client::Publish> {
+        if !self.config.active_scene || !self.config.contains(scene) {
+            return None;
+        }
+        Some(
+            self.client
+                .publish_value(self.node.node_id(), &self.active_scene_prop, scene, true),
+        )
+    }
+
+    /// Remove the retained `active-scene` value from the broker, e.g. after the
+    /// active scene was removed.
+    pub fn clear_active_scene(&self) -> Option<homie5::client::Publish> {
+        if !self.config.active_scene {
+            return None;
+        }
+        // an empty string value goes out as a single 0x00 byte, only an empty
+        // payload removes the retained message
+        let mut publish =
+            self.client
+                .publish_value(self.node.node_id(), &self.active_scene_prop, "", true);
+        publish.payload.clear();
+        Some(publish)
+    }
+
+    /// Publish the id -> label mapping of all configured scenes.
+    pub fn labels(&self) -> Option<homie5::client::Publish> {
+        if !self.config.labels {
+            return None;
+        }
+        let labels: BTreeMap<&str, &str> = self
+            .config
+            .scenes
+            .iter()
+            .map(|scene| (scene.id.as_str(), scene.label()))
+            .collect();
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.labels_prop,
+            serde_json::to_string(&labels).ok()?,
+            true,
+        ))
+    }
+
+    /// Remove a scene from the configured scenes, so `recall` and `parse_set`
+    /// reject it from now on. Returns `desc` with the scene dropped from the
+    /// enum formats; republish the device description with it, and `labels`
+    /// if enabled. Returns `None` for unknown scenes and for the last scene,
+    /// as enum formats must not be empty.
+    pub fn remove_scene(
+        &mut self,
+        scene: &str,
+        desc: &HomieNodeDescription,
+    ) -> Option<HomieNodeDescription> {
+        if !self.config.contains(scene) || self.config.scenes.len() == 1 {
+            return None;
+        }
+        self.config.scenes.retain(|s| s.id != scene);
+        let format = HomiePropertyFormat::Enum(self.config.scene_ids().map(String::from).collect());
+        let mut desc = desc.clone();
+        for prop_id in [
+            &self.recall_prop,
+            &self.remove_prop,
+            &self.active_scene_prop,
+        ] {
+            if let Some(prop) = desc.properties.get_mut(prop_id) {
+                prop.format = format.clone();
+            }
+        }
+        Some(desc)
+    }
+
+    pub fn scene_label(&self, id: &str) -> Option<&str> {
+        self.config
+            .scenes
+            .iter()
+            .find(|scene| scene.id == id)
+            .map(|scene| scene.label())
+    }
+}
+
+impl SmarthomePublisher for SceneNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::Scene
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![
+            self.recall_prop.clone(),
+            self.remove_prop.clone(),
+            self.labels_prop.clone(),
+            self.active_scene_prop.clone(),
+        ]
+    }
 }
 
 impl SetCommandParser for SceneNodePublisher {
@@ -127,6 +460,10 @@ impl SetCommandParser for SceneNodePublisher {
         desc: &HomieDeviceDescription,
         set_value: &str,
     ) -> ParseOutcome<Self::Event> {
+        if let Some(outcome) = reject_readonly(&self.node, property, desc, set_value) {
+            return outcome;
+        }
+
         if property.match_with_node(&self.node, &self.recall_prop) {
             let Some(parsed) = desc.with_property(property, |prop_desc| {
                 HomieValue::parse(set_value, prop_desc)
@@ -139,9 +476,40 @@ impl SetCommandParser for SceneNodePublisher {
             };
 
             match parsed {
-                Ok(HomieValue::Enum(value)) => {
+                Ok(HomieValue::Enum(value)) if self.config.contains(&value) => {
                     ParseOutcome::Parsed(SceneNodeActions::Recall(value))
                 }
+                Ok(HomieValue::Enum(_)) => ParseOutcome::Invalid(ParseError::new(
+                    property.prop_id().to_string(),
+                    set_value,
+                    ParseErrorKind::InvalidVariant,
+                )),
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property.prop_id().to_string(),
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else if property.match_with_node(&self.node, &self.remove_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property.prop_id().to_string(),
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Enum(value)) if self.config.contains(&value) => {
+                    ParseOutcome::Parsed(SceneNodeActions::Remove(value))
+                }
+                Ok(HomieValue::Enum(_)) => ParseOutcome::Invalid(ParseError::new(
+                    property.prop_id().to_string(),
+                    set_value,
+                    ParseErrorKind::InvalidVariant,
+                )),
                 _ => ParseOutcome::Invalid(ParseError::new(
                     property.prop_id().to_string(),
                     set_value,
@@ -171,3 +539,168 @@ impl SetCommandParser for SceneNodePublisher {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scenes_deserialize_from_ids_and_labeled_entries() {
+        let config: SceneNodeConfig =
+            serde_json::from_str(r#"{"scenes":["evening",{"id":"movie","label":"Movie night"}]}"#)
+                .expect("scene config must deserialize");
+
+        assert_eq!(
+            config.scenes,
+            vec![
+                SceneNodeScene::from("evening"),
+                SceneNodeScene::new("movie", "Movie night"),
+            ]
+        );
+        assert_eq!(config.scenes[0].label(), "evening");
+        assert_eq!(config.scenes[1].label(), "Movie night");
+    }
+
+    #[test]
+    fn recall_format_uses_scene_ids() {
+        let config = SceneNodeConfig {
+            scenes: vec![SceneNodeScene::new("movie", "Movie night")],
+            settable: true,
+            ..SceneNodeConfig::default()
+        };
+        let node = SceneNodeBuilder::new(&config).build();
+
+        assert_eq!(
+            node.properties
+                .get(&SCENE_NODE_RECALL_PROP_ID)
+                .expect("recall property must exist")
+                .format,
+            homie5::device_description::HomiePropertyFormat::Enum(vec!["movie".to_string()])
+        );
+    }
+
+    #[test]
+    fn active_scene_is_gated_and_retained() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), homie5::HomieDomain::Default);
+        let mut config = SceneNodeConfig {
+            scenes: vec!["evening".into()],
+            ..SceneNodeConfig::default()
+        };
+        let (_, publisher) =
+            SceneNodeBuilder::new(&config).build_with_publisher(SCENE_NODE_DEFAULT_ID, &client);
+        assert!(publisher.active_scene("evening").is_none());
+
+        config.active_scene = true;
+        let (_, publisher) =
+            SceneNodeBuilder::new(&config).build_with_publisher(SCENE_NODE_DEFAULT_ID, &client);
+        let publish = publisher
+            .active_scene("evening")
+            .expect("active scene must publish");
+        assert!(publish.retain);
+        assert_eq!(publish.payload, b"evening".to_vec());
+        assert!(publisher.active_scene("unknown").is_none());
+    }
+
+    #[test]
+    fn recall_records_and_publishes_active_scene() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), homie5::HomieDomain::Default);
+        let config = SceneNodeConfig {
+            scenes: vec!["evening".into(), "movie".into()],
+            active_scene: true,
+            ..SceneNodeConfig::default()
+        };
+        let (_, publisher) =
+            SceneNodeBuilder::new(&config).build_with_publisher(SCENE_NODE_DEFAULT_ID, &client);
+        let mut node = SceneNode {
+            publisher,
+            state: SceneNodeState::default(),
+        };
+
+        let publishes = node.apply_set(SceneNodeActions::Recall("movie".to_owned()));
+        assert_eq!(publishes.len(), 2);
+        assert!(!publishes[0].retain);
+        assert!(publishes[1].retain);
+        assert_eq!(publishes[1].payload, b"movie".to_vec());
+        assert_eq!(node.state.active_scene.as_deref(), Some("movie"));
+
+        assert!(
+            node.apply_set(SceneNodeActions::Recall("unknown".to_owned()))
+                .is_empty()
+        );
+        assert_eq!(node.state.active_scene.as_deref(), Some("movie"));
+    }
+
+    #[test]
+    fn removed_scenes_are_no_longer_accepted() {
+        use crate::StandaloneSetParser;
+
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), homie5::HomieDomain::Default);
+        let config = SceneNodeConfig {
+            scenes: vec!["evening".into(), "movie".into()],
+            settable: true,
+            remove: true,
+            active_scene: true,
+            ..SceneNodeConfig::default()
+        };
+        let (desc, publisher) =
+            SceneNodeBuilder::new(&config).build_with_publisher(SCENE_NODE_DEFAULT_ID, &client);
+        let mut node = SceneNode {
+            publisher,
+            state: SceneNodeState {
+                active_scene: Some("movie".to_owned()),
+            },
+        };
+
+        let (updated, cleared) = node
+            .remove_scene("movie", &desc)
+            .expect("configured scene must be removable");
+        let cleared = cleared.expect("removing the active scene must clear it");
+        assert!(cleared.topic.ends_with("/active-scene"));
+        assert!(cleared.retain);
+        assert!(cleared.payload.is_empty());
+        for prop_id in [
+            SCENE_NODE_RECALL_PROP_ID,
+            SCENE_NODE_REMOVE_PROP_ID,
+            SCENE_NODE_ACTIVE_SCENE_PROP_ID,
+        ] {
+            assert_eq!(
+                updated.properties[&prop_id].format,
+                HomiePropertyFormat::Enum(vec!["evening".to_owned()])
+            );
+        }
+        assert_eq!(node.state.active_scene, None);
+        assert!(
+            node.apply_set(SceneNodeActions::Recall("movie".to_owned()))
+                .is_empty()
+        );
+        assert!(node.remove_scene("movie", &updated).is_none());
+        assert!(node.remove_scene("evening", &updated).is_none());
+
+        // the parser keeps the original description, which still lists the scene
+        let mut parser = StandaloneSetParser::new(desc.clone(), node.publisher);
+        let recall = PropertyRef::from_node(
+            parser.publisher().node_ref().clone(),
+            SCENE_NODE_RECALL_PROP_ID,
+        );
+        assert!(matches!(
+            parser.parse_set(&recall, "movie"),
+            ParseOutcome::Invalid(ParseError {
+                kind: ParseErrorKind::InvalidVariant,
+                ..
+            })
+        ));
+        assert!(matches!(
+            parser.parse_set(&recall, "evening"),
+            ParseOutcome::Parsed(SceneNodeActions::Recall(_))
+        ));
+        assert!(
+            parser
+                .publisher_mut()
+                .remove_scene("evening", &desc)
+                .is_none()
+        );
+    }
+}
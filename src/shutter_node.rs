@@ -1,34 +1,174 @@
-use std::{fmt::Display, str::FromStr};
+use std::{collections::BTreeMap, fmt::Display, str::FromStr};
 
 use homie5::{
-    HOMIE_UNIT_PERCENT, Homie5DeviceProtocol, Homie5Message, Homie5ProtocolError, HomieID,
-    HomieValue, NodeRef, PropertyRef,
+    HOMIE_UNIT_PERCENT, HOMIE_UNIT_SECONDS, Homie5DeviceProtocol, Homie5Message,
+    Homie5ProtocolError, HomieID, HomieValue, NodeRef, PropertyRef,
     device_description::{
-        HomieDeviceDescription, HomieNodeDescription, IntegerRange, NodeDescriptionBuilder,
-        PropertyDescriptionBuilder,
+        FloatRange, HomieDeviceDescription, HomieNodeDescription, IntegerRange,
+        NodeDescriptionBuilder, PropertyDescriptionBuilder,
     },
 };
 use serde::{Deserialize, Serialize};
 
-use crate::{ParseError, ParseErrorKind, ParseOutcome, SMARTHOME_CAP_SHUTTER, SetCommandParser};
+use crate::{
+    ParseError, ParseErrorKind, ParseOutcome, PropertyMetadata, SMARTHOME_CAP_SHUTTER,
+    SetCommandParser, SmarthomePublisher, SmarthomeType, property_metadata, readonly_properties,
+    reject_readonly, smarthome_node_config,
+};
 
 pub const SHUTTER_NODE_DEFAULT_ID: HomieID = HomieID::new_const("shutter");
 pub const SHUTTER_NODE_DEFAULT_NAME: &str = "Shutter control";
 pub const SHUTTER_NODE_POSITION_PROP_ID: HomieID = HomieID::new_const("position");
 pub const SHUTTER_NODE_ACTION_PROP_ID: HomieID = HomieID::new_const("action");
+pub const SHUTTER_NODE_TILT_PROP_ID: HomieID = HomieID::new_const("tilt");
+pub const SHUTTER_NODE_MOVING_PROP_ID: HomieID = HomieID::new_const("moving");
+pub const SHUTTER_NODE_RUNTIME_UP_PROP_ID: HomieID = HomieID::new_const("runtime-up");
+pub const SHUTTER_NODE_RUNTIME_DOWN_PROP_ID: HomieID = HomieID::new_const("runtime-down");
+pub const SHUTTER_NODE_FAVORITE_POSITION_PROP_ID: HomieID =
+    HomieID::new_const("favorite-position");
+pub const SHUTTER_NODE_OBSTRUCTION_PROP_ID: HomieID = HomieID::new_const("obstruction");
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShutterNodeState {
+    pub position: i64,
+    pub position_target: i64,
+    pub tilt: Option<i64>,
+    pub tilt_target: Option<i64>,
+    pub moving: Option<ShutterNodeMovement>,
+    pub runtime_up: Option<f64>,
+    pub runtime_down: Option<f64>,
+    pub favorite_position: Option<i64>,
+    pub obstruction: Option<bool>,
+}
 
 #[derive(Debug)]
 pub struct ShutterNode {
     pub publisher: ShutterNodePublisher,
-    pub position: i64,
-    pub position_target: i64,
+    pub state: ShutterNodeState,
+}
+
+impl ShutterNode {
+    /// Apply a parsed set command and return the resulting publishes. Unlike
+    /// instantly switching actuators, a shutter only takes on a new `$target`
+    /// here; the actual position follows through [`ShutterNode::apply_value`]
+    /// while the cover moves.
+    pub fn apply_set(&mut self, event: ShutterNodeSetEvents) -> Vec<homie5::client::Publish> {
+        match event {
+            ShutterNodeSetEvents::Position(value) => self.apply_position_target(value),
+            ShutterNodeSetEvents::Action(ShutterNodeActions::Up) => {
+                self.apply_position_target(self.publisher.map_position(100))
+            }
+            ShutterNodeSetEvents::Action(ShutterNodeActions::Down) => {
+                self.apply_position_target(self.publisher.map_position(0))
+            }
+            ShutterNodeSetEvents::Action(ShutterNodeActions::Stop) => {
+                self.apply_position_target(self.state.position)
+            }
+            ShutterNodeSetEvents::Action(ShutterNodeActions::Favorite) => self
+                .state
+                .favorite_position
+                .map(|value| self.apply_position_target(value))
+                .unwrap_or_default(),
+            ShutterNodeSetEvents::Action(ShutterNodeActions::Calibrate) => Vec::new(),
+            ShutterNodeSetEvents::Tilt(value) => {
+                self.state.tilt_target = Some(value);
+                self.publisher.tilt_target(value).into_iter().collect()
+            }
+            ShutterNodeSetEvents::RuntimeUp(value) => {
+                self.state.runtime_up = Some(value);
+                self.publisher.runtime_up(value).into_iter().collect()
+            }
+            ShutterNodeSetEvents::RuntimeDown(value) => {
+                self.state.runtime_down = Some(value);
+                self.publisher.runtime_down(value).into_iter().collect()
+            }
+            ShutterNodeSetEvents::FavoritePosition(value) => {
+                self.state.favorite_position = Some(value);
+                self.publisher
+                    .favorite_position(value)
+                    .into_iter()
+                    .collect()
+            }
+        }
+    }
+
+    /// Apply a value reported by the device and return the matching publish.
+    /// Positions are given in application space, like [`ShutterNode::position`].
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Integer(value)) if p == &SHUTTER_NODE_POSITION_PROP_ID => {
+                self.state.position = *value;
+                vec![self.publisher.position(*value)]
+            }
+            (p, HomieValue::Integer(value)) if p == &SHUTTER_NODE_TILT_PROP_ID => {
+                self.state.tilt = Some(*value);
+                self.publisher.tilt(*value).into_iter().collect()
+            }
+            (p, HomieValue::Enum(value)) if p == &SHUTTER_NODE_MOVING_PROP_ID => {
+                let Ok(moving) = value.parse::<ShutterNodeMovement>() else {
+                    return Vec::new();
+                };
+                self.state.moving = Some(moving);
+                self.publisher.moving(moving).into_iter().collect()
+            }
+            (p, HomieValue::Bool(value)) if p == &SHUTTER_NODE_OBSTRUCTION_PROP_ID => {
+                self.state.obstruction = Some(*value);
+                self.publisher.obstruction(*value).into_iter().collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn apply_position_target(&mut self, value: i64) -> Vec<homie5::client::Publish> {
+        self.state.position_target = value;
+        vec![self.publisher.position_target(value)]
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            Some(self.publisher.position(self.state.position)),
+            Some(self.publisher.position_target(self.state.position_target)),
+            self.state.tilt.and_then(|value| self.publisher.tilt(value)),
+            self.state
+                .tilt_target
+                .and_then(|value| self.publisher.tilt_target(value)),
+            self.state
+                .moving
+                .and_then(|value| self.publisher.moving(value)),
+            self.state
+                .runtime_up
+                .and_then(|value| self.publisher.runtime_up(value)),
+            self.state
+                .runtime_down
+                .and_then(|value| self.publisher.runtime_down(value)),
+            self.state
+                .favorite_position
+                .and_then(|value| self.publisher.favorite_position(value)),
+            self.state
+                .obstruction
+                .and_then(|value| self.publisher.obstruction(value)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 pub enum ShutterNodeActions {
     Up,
     Down,
     Stop,
+    Calibrate,
+    Favorite,
 }
 
 impl Display for ShutterNodeActions {
@@ -44,6 +184,8 @@ impl From<&ShutterNodeActions> for &'static str {
             ShutterNodeActions::Up => "up",
             ShutterNodeActions::Down => "down",
             ShutterNodeActions::Stop => "stop",
+            ShutterNodeActions::Calibrate => "calibrate",
+            ShutterNodeActions::Favorite => "favorite",
         }
     }
 }
@@ -54,30 +196,126 @@ impl FromStr for ShutterNodeActions {
             "up" => Ok(ShutterNodeActions::Up),
             "down" => Ok(ShutterNodeActions::Down),
             "stop" => Ok(ShutterNodeActions::Stop),
+            "calibrate" => Ok(ShutterNodeActions::Calibrate),
+            "favorite" => Ok(ShutterNodeActions::Favorite),
+            _ => Err(Homie5ProtocolError::InvalidPayload),
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ShutterNodeMovement {
+    Up,
+    Down,
+    #[default]
+    Idle,
+}
+
+impl ShutterNodeMovement {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            ShutterNodeMovement::Up => "up",
+            ShutterNodeMovement::Down => "down",
+            ShutterNodeMovement::Idle => "idle",
+        }
+    }
+}
+
+impl Display for ShutterNodeMovement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ShutterNodeMovement {
+    type Err = Homie5ProtocolError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "up" => Ok(ShutterNodeMovement::Up),
+            "down" => Ok(ShutterNodeMovement::Down),
+            "idle" => Ok(ShutterNodeMovement::Idle),
             _ => Err(Homie5ProtocolError::InvalidPayload),
         }
     }
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 pub enum ShutterNodeSetEvents {
     Position(i64),
     Action(ShutterNodeActions),
+    Tilt(i64),
+    RuntimeUp(f64),
+    RuntimeDown(f64),
+    FavoritePosition(i64),
 }
 
-#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
-#[serde(default)]
-pub struct ShutterNodeConfig {
-    pub can_stop: bool,
+smarthome_node_config! {
+    #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+    #[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+    #[serde(default)]
+    pub struct ShutterNodeConfig for ShutterNodePublisher {
+        pub can_stop: bool,
+        /// Expose a slat/tilt angle property (venetian blinds).
+        pub tilt: bool,
+        pub tilt_unit: String,
+        #[cfg_attr(
+            feature = "proptest",
+            proptest(strategy = "crate::arbitrary::integer_range()")
+        )]
+        pub tilt_range: IntegerRange,
+        /// Expose the current movement direction (up/down/idle).
+        pub moving: bool,
+        /// Expose a `calibrate` action and settable `runtime-up`/`runtime-down`
+        /// travel times for time-based actuators.
+        pub calibration: bool,
+        /// Expose a `favorite` action and a settable `favorite-position`.
+        pub favorite: bool,
+        /// Treat application positions as 100 % = fully closed. The publisher
+        /// converts to/from the spec semantics (0 = closed, 100 = open).
+        pub invert_position: bool,
+        /// Expose an `obstruction` flag for covers that detect blockage.
+        pub obstruction: bool => SHUTTER_NODE_OBSTRUCTION_PROP_ID, obstruction(bool), |_config| {
+            PropertyDescriptionBuilder::boolean()
+                .name("Obstruction detected")
+                .boolean_labels("clear", "obstructed")
+                .settable(false)
+                .retained(true)
+                .build()
+        },
+        /// Properties to publish as read-only; sets to them are rejected.
+        #[cfg_attr(
+            feature = "proptest",
+            proptest(strategy = "crate::arbitrary::homie_ids()")
+        )]
+        pub readonly: Vec<HomieID>,
+    }
 }
 
 impl Default for ShutterNodeConfig {
     fn default() -> Self {
-        Self { can_stop: true }
+        Self {
+            can_stop: true,
+            tilt: false,
+            tilt_unit: HOMIE_UNIT_PERCENT.to_string(),
+            tilt_range: IntegerRange {
+                min: Some(0),
+                max: Some(100),
+                step: None,
+            },
+            moving: false,
+            calibration: false,
+            favorite: false,
+            invert_position: false,
+            obstruction: false,
+            readonly: Vec::new(),
+        }
     }
 }
 
 pub struct ShutterNodeBuilder {
+    config: ShutterNodeConfig,
     node_builder: NodeDescriptionBuilder,
 }
 
@@ -88,8 +326,12 @@ impl ShutterNodeBuilder {
             config,
         )
         .r#type(SMARTHOME_CAP_SHUTTER);
+        let db = readonly_properties(db, &config.readonly);
 
-        Self { node_builder: db }
+        Self {
+            config: config.clone(),
+            node_builder: db,
+        }
     }
 
     fn build_node(
@@ -101,7 +343,14 @@ impl ShutterNodeBuilder {
         if config.can_stop {
             actions.push(ShutterNodeActions::Stop);
         }
+        if config.calibration {
+            actions.push(ShutterNodeActions::Calibrate);
+        }
+        if config.favorite {
+            actions.push(ShutterNodeActions::Favorite);
+        }
 
+        let db = config.add_optional_properties(db);
         db.add_property(
             SHUTTER_NODE_POSITION_PROP_ID,
             PropertyDescriptionBuilder::integer()
@@ -127,6 +376,78 @@ impl ShutterNodeBuilder {
             .retained(false)
             .build(),
         )
+        .add_property_cond(SHUTTER_NODE_TILT_PROP_ID, config.tilt, || {
+            PropertyDescriptionBuilder::integer()
+                .name("Slat tilt")
+                .integer_range(config.tilt_range.clone())
+                .unit(config.tilt_unit.to_owned())
+                .settable(true)
+                .retained(true)
+                .build()
+        })
+        .add_property_cond(SHUTTER_NODE_MOVING_PROP_ID, config.moving, || {
+            PropertyDescriptionBuilder::enumeration(
+                [
+                    ShutterNodeMovement::Up,
+                    ShutterNodeMovement::Down,
+                    ShutterNodeMovement::Idle,
+                ]
+                .iter()
+                .map(|m| m.as_str()),
+            )
+            .unwrap()
+            .name("Movement state")
+            .settable(false)
+            .retained(true)
+            .build()
+        })
+        .add_property_cond(SHUTTER_NODE_RUNTIME_UP_PROP_ID, config.calibration, || {
+            PropertyDescriptionBuilder::float()
+                .name("Runtime up")
+                .float_range(FloatRange {
+                    min: Some(0.0),
+                    max: None,
+                    step: None,
+                })
+                .unit(HOMIE_UNIT_SECONDS)
+                .settable(true)
+                .retained(true)
+                .build()
+        })
+        .add_property_cond(
+            SHUTTER_NODE_RUNTIME_DOWN_PROP_ID,
+            config.calibration,
+            || {
+                PropertyDescriptionBuilder::float()
+                    .name("Runtime down")
+                    .float_range(FloatRange {
+                        min: Some(0.0),
+                        max: None,
+                        step: None,
+                    })
+                    .unit(HOMIE_UNIT_SECONDS)
+                    .settable(true)
+                    .retained(true)
+                    .build()
+            },
+        )
+        .add_property_cond(
+            SHUTTER_NODE_FAVORITE_POSITION_PROP_ID,
+            config.favorite,
+            || {
+                PropertyDescriptionBuilder::integer()
+                    .name("Favorite position")
+                    .integer_range(IntegerRange {
+                        min: Some(0),
+                        max: Some(100),
+                        step: None,
+                    })
+                    .unit(HOMIE_UNIT_PERCENT)
+                    .settable(true)
+                    .retained(true)
+                    .build()
+            },
+        )
     }
 
     pub fn name<S: Into<String>>(mut self, name: impl Into<Option<S>>) -> Self {
@@ -134,10 +455,23 @@ impl ShutterNodeBuilder {
         self
     }
 
+    /// Append a subtype suffix to the node type (`shutter-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::Shutter.with_subtype(subtype));
+        self
+    }
+
     pub fn build(self) -> HomieNodeDescription {
         self.node_builder.build()
     }
 
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &ShutterNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
     pub fn build_with_publisher(
         self,
         node_id: HomieID,
@@ -149,6 +483,7 @@ impl ShutterNodeBuilder {
             ShutterNodePublisher::new(
                 NodeRef::new(client.homie_domain().to_owned(), did, node_id),
                 client.clone(),
+                self.config,
             ),
         )
     }
@@ -157,18 +492,45 @@ impl ShutterNodeBuilder {
 #[derive(Debug)]
 pub struct ShutterNodePublisher {
     client: Homie5DeviceProtocol,
+    config: ShutterNodeConfig,
     node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
     position_prop: HomieID,
     action_prop: HomieID,
+    tilt_prop: HomieID,
+    moving_prop: HomieID,
+    runtime_up_prop: HomieID,
+    runtime_down_prop: HomieID,
+    favorite_position_prop: HomieID,
+    obstruction_prop: HomieID,
 }
 
 impl ShutterNodePublisher {
-    pub fn new(node: NodeRef, client: Homie5DeviceProtocol) -> Self {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: ShutterNodeConfig) -> Self {
+        let metadata = property_metadata(&ShutterNodeBuilder::new(&config).build());
         Self {
             node,
+            metadata,
             client,
+            config,
             position_prop: SHUTTER_NODE_POSITION_PROP_ID,
             action_prop: SHUTTER_NODE_ACTION_PROP_ID,
+            tilt_prop: SHUTTER_NODE_TILT_PROP_ID,
+            moving_prop: SHUTTER_NODE_MOVING_PROP_ID,
+            runtime_up_prop: SHUTTER_NODE_RUNTIME_UP_PROP_ID,
+            runtime_down_prop: SHUTTER_NODE_RUNTIME_DOWN_PROP_ID,
+            favorite_position_prop: SHUTTER_NODE_FAVORITE_POSITION_PROP_ID,
+            obstruction_prop: SHUTTER_NODE_OBSTRUCTION_PROP_ID,
+        }
+    }
+
+    /// Map between application and spec position semantics according to
+    /// `invert_position`. The mapping is its own inverse.
+    fn map_position(&self, value: i64) -> i64 {
+        if self.config.invert_position {
+            100 - value
+        } else {
+            value
         }
     }
 
@@ -176,7 +538,7 @@ impl ShutterNodePublisher {
         self.client.publish_value(
             self.node.node_id(),
             &self.position_prop,
-            value.to_string(),
+            self.map_position(value).to_string(),
             true,
         )
     }
@@ -185,7 +547,7 @@ impl ShutterNodePublisher {
         self.client.publish_target(
             self.node.node_id(),
             &self.position_prop,
-            value.to_string(),
+            self.map_position(value).to_string(),
             true,
         )
     }
@@ -198,6 +560,141 @@ impl ShutterNodePublisher {
             false,
         )
     }
+
+    pub fn tilt(&self, value: i64) -> Option<homie5::client::Publish> {
+        if !self.config.tilt {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.tilt_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+
+    pub fn tilt_target(&self, value: i64) -> Option<homie5::client::Publish> {
+        if !self.config.tilt {
+            return None;
+        }
+        Some(self.client.publish_target(
+            self.node.node_id(),
+            &self.tilt_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+
+    pub fn moving(&self, value: ShutterNodeMovement) -> Option<homie5::client::Publish> {
+        if !self.config.moving {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.moving_prop,
+            value.as_str(),
+            true,
+        ))
+    }
+
+    pub fn runtime_up(&self, value: f64) -> Option<homie5::client::Publish> {
+        if !self.config.calibration {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.runtime_up_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+
+    pub fn runtime_up_target(&self, value: f64) -> Option<homie5::client::Publish> {
+        if !self.config.calibration {
+            return None;
+        }
+        Some(self.client.publish_target(
+            self.node.node_id(),
+            &self.runtime_up_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+
+    pub fn runtime_down(&self, value: f64) -> Option<homie5::client::Publish> {
+        if !self.config.calibration {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.runtime_down_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+
+    pub fn runtime_down_target(&self, value: f64) -> Option<homie5::client::Publish> {
+        if !self.config.calibration {
+            return None;
+        }
+        Some(self.client.publish_target(
+            self.node.node_id(),
+            &self.runtime_down_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+
+    pub fn favorite_position(&self, value: i64) -> Option<homie5::client::Publish> {
+        if !self.config.favorite {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.favorite_position_prop,
+            self.map_position(value).to_string(),
+            true,
+        ))
+    }
+
+    pub fn favorite_position_target(&self, value: i64) -> Option<homie5::client::Publish> {
+        if !self.config.favorite {
+            return None;
+        }
+        Some(self.client.publish_target(
+            self.node.node_id(),
+            &self.favorite_position_prop,
+            self.map_position(value).to_string(),
+            true,
+        ))
+    }
+}
+
+impl SmarthomePublisher for ShutterNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::Shutter
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![
+            self.position_prop.clone(),
+            self.action_prop.clone(),
+            self.tilt_prop.clone(),
+            self.moving_prop.clone(),
+            self.runtime_up_prop.clone(),
+            self.runtime_down_prop.clone(),
+            self.favorite_position_prop.clone(),
+            self.obstruction_prop.clone(),
+        ]
+    }
 }
 
 impl SetCommandParser for ShutterNodePublisher {
@@ -209,6 +706,10 @@ impl SetCommandParser for ShutterNodePublisher {
         desc: &HomieDeviceDescription,
         set_value: &str,
     ) -> ParseOutcome<Self::Event> {
+        if let Some(outcome) = reject_readonly(&self.node, property, desc, set_value) {
+            return outcome;
+        }
+
         let property_id = property.prop_id().to_string();
 
         if property.match_with_node(&self.node, &self.position_prop) {
@@ -224,7 +725,7 @@ impl SetCommandParser for ShutterNodePublisher {
 
             match parsed {
                 Ok(HomieValue::Integer(value)) => {
-                    ParseOutcome::Parsed(ShutterNodeSetEvents::Position(value))
+                    ParseOutcome::Parsed(ShutterNodeSetEvents::Position(self.map_position(value)))
                 }
                 _ => ParseOutcome::Invalid(ParseError::new(
                     property.prop_id().to_string(),
@@ -258,6 +759,92 @@ impl SetCommandParser for ShutterNodePublisher {
                     ParseErrorKind::InvalidHomieValue,
                 )),
             }
+        } else if property.match_with_node(&self.node, &self.tilt_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Integer(value)) => {
+                    ParseOutcome::Parsed(ShutterNodeSetEvents::Tilt(value))
+                }
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property.prop_id().to_string(),
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else if property.match_with_node(&self.node, &self.runtime_up_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Float(value)) => {
+                    ParseOutcome::Parsed(ShutterNodeSetEvents::RuntimeUp(value))
+                }
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property.prop_id().to_string(),
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else if property.match_with_node(&self.node, &self.runtime_down_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Float(value)) => {
+                    ParseOutcome::Parsed(ShutterNodeSetEvents::RuntimeDown(value))
+                }
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property.prop_id().to_string(),
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else if property.match_with_node(&self.node, &self.favorite_position_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Integer(value)) => {
+                    ParseOutcome::Parsed(ShutterNodeSetEvents::FavoritePosition(
+                        self.map_position(value),
+                    ))
+                }
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property.prop_id().to_string(),
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
         } else {
             ParseOutcome::NoMatch
         }
@@ -281,3 +868,78 @@ impl SetCommandParser for ShutterNodePublisher {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use homie5::{HomieDomain, device_description::DeviceDescriptionBuilder};
+
+    fn publisher_with_desc(
+        config: &ShutterNodeConfig,
+    ) -> (ShutterNodePublisher, HomieDeviceDescription) {
+        let (client, _) = Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let (node, publisher) = ShutterNodeBuilder::new(config)
+            .build_with_publisher(SHUTTER_NODE_DEFAULT_ID, &client);
+        let desc = DeviceDescriptionBuilder::new()
+            .add_node(SHUTTER_NODE_DEFAULT_ID, node)
+            .build();
+        (publisher, desc)
+    }
+
+    #[test]
+    fn invert_position_applies_to_publish_and_parse() {
+        let config = ShutterNodeConfig {
+            invert_position: true,
+            ..ShutterNodeConfig::default()
+        };
+        let (publisher, desc) = publisher_with_desc(&config);
+
+        assert_eq!(publisher.position(30).payload, b"70".to_vec());
+        assert_eq!(publisher.position_target(100).payload, b"0".to_vec());
+
+        let property = PropertyRef::from_node(publisher.node.clone(), SHUTTER_NODE_POSITION_PROP_ID);
+        match publisher.parse_set(&property, &desc, "25") {
+            ParseOutcome::Parsed(ShutterNodeSetEvents::Position(value)) => assert_eq!(value, 75),
+            other => panic!("unexpected parse outcome: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn position_is_passed_through_by_default() {
+        let (publisher, desc) = publisher_with_desc(&ShutterNodeConfig::default());
+
+        assert_eq!(publisher.position(30).payload, b"30".to_vec());
+
+        let property = PropertyRef::from_node(publisher.node.clone(), SHUTTER_NODE_POSITION_PROP_ID);
+        match publisher.parse_set(&property, &desc, "25") {
+            ParseOutcome::Parsed(ShutterNodeSetEvents::Position(value)) => assert_eq!(value, 25),
+            other => panic!("unexpected parse outcome: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn apply_set_only_moves_the_target() {
+        let config = ShutterNodeConfig {
+            invert_position: true,
+            ..ShutterNodeConfig::default()
+        };
+        let (publisher, _) = publisher_with_desc(&config);
+        let mut node = ShutterNode {
+            publisher,
+            state: ShutterNodeState {
+                position: 40,
+                position_target: 40,
+                ..ShutterNodeState::default()
+            },
+        };
+
+        let publishes = node.apply_set(ShutterNodeSetEvents::Action(ShutterNodeActions::Up));
+        assert_eq!((node.state.position, node.state.position_target), (40, 0));
+        assert_eq!(publishes.len(), 1);
+        assert_eq!(publishes[0].payload, b"100".to_vec());
+
+        node.apply_value(&SHUTTER_NODE_POSITION_PROP_ID, &HomieValue::Integer(10));
+        node.apply_set(ShutterNodeSetEvents::Action(ShutterNodeActions::Stop));
+        assert_eq!((node.state.position, node.state.position_target), (10, 10));
+    }
+}
@@ -1,53 +1,224 @@
+use core::fmt;
+use std::{collections::BTreeMap, str::FromStr};
+
 use homie5::{
-    Homie5DeviceProtocol, HomieID, NodeRef,
+    HOMIE_UNIT_PERCENT, Homie5DeviceProtocol, Homie5Message, Homie5ProtocolError, HomieID,
+    HomieValue, NodeRef, PropertyRef,
     device_description::{
-        HomieNodeDescription, NodeDescriptionBuilder, PropertyDescriptionBuilder,
+        HomieDeviceDescription, HomieNodeDescription, IntegerRange, NodeDescriptionBuilder,
+        PropertyDescriptionBuilder,
     },
 };
+use serde::{Deserialize, Serialize};
 
-use crate::SMARTHOME_CAP_SMOKE;
+use crate::{
+    ParseError, ParseErrorKind, ParseOutcome, PropertyMetadata, SMARTHOME_CAP_SMOKE,
+    SetCommandParser, SmarthomePublisher, SmarthomeType, property_metadata, readonly_properties,
+    reject_readonly, smarthome_node_config,
+};
 
 pub const SMOKE_NODE_DEFAULT_ID: HomieID = HomieID::new_const("smoke");
 pub const SMOKE_NODE_DEFAULT_NAME: &str = "Smoke detector";
 pub const SMOKE_NODE_DETECTED_PROP_ID: HomieID = HomieID::new_const("detected");
+pub const SMOKE_NODE_ACTION_PROP_ID: HomieID = HomieID::new_const("action");
+pub const SMOKE_NODE_DEGRADATION_PROP_ID: HomieID = HomieID::new_const("degradation");
 
 // ── Node (state) ────────────────────────────────────────────────────────────
 
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SmokeNodeState {
+    pub detected: bool,
+    pub degradation: Option<i64>,
+}
+
 #[derive(Debug)]
 pub struct SmokeNode {
     pub publisher: SmokeNodePublisher,
-    pub detected: bool,
+    pub state: SmokeNodeState,
+}
+
+impl SmokeNode {
+    /// Apply a parsed set command. Actions are carried out by the device, which
+    /// reports the outcome, so there is nothing to publish yet.
+    pub fn apply_set(&mut self, event: SmokeNodeSetEvents) -> Vec<homie5::client::Publish> {
+        match event {
+            SmokeNodeSetEvents::Action(_) => Vec::new(),
+        }
+    }
+
+    /// Apply a value reported by the device and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Bool(value)) if p == &SMOKE_NODE_DETECTED_PROP_ID => {
+                self.state.detected = *value;
+                vec![self.publisher.detected(*value)]
+            }
+            (p, HomieValue::Integer(value)) if p == &SMOKE_NODE_DEGRADATION_PROP_ID => {
+                self.state.degradation = Some(*value);
+                self.publisher.degradation(*value).into_iter().collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            Some(self.publisher.detected(self.state.detected)),
+            self.state
+                .degradation
+                .and_then(|value| self.publisher.degradation(value)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+// ── Actions / set events ────────────────────────────────────────────────────
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+pub enum SmokeNodeActions {
+    /// Trigger the detector's self-test (alarm sounds briefly).
+    Test,
+    /// Silence an active alarm for the device-defined hush period.
+    Hush,
+}
+
+impl SmokeNodeActions {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SmokeNodeActions::Test => "test",
+            SmokeNodeActions::Hush => "hush",
+        }
+    }
+}
+
+impl fmt::Display for SmokeNodeActions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SmokeNodeActions {
+    type Err = Homie5ProtocolError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "test" => Ok(SmokeNodeActions::Test),
+            "hush" => Ok(SmokeNodeActions::Hush),
+            _ => Err(Homie5ProtocolError::InvalidPayload),
+        }
+    }
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+pub enum SmokeNodeSetEvents {
+    Action(SmokeNodeActions),
+}
+
+// ── Config ──────────────────────────────────────────────────────────────────
+
+smarthome_node_config! {
+    #[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+    #[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+    #[serde(default)]
+    pub struct SmokeNodeConfig for SmokeNodePublisher {
+        /// Expose the `test` action.
+        pub test: bool,
+        /// Expose the `hush` action.
+        pub hush: bool,
+        /// Expose the smoke chamber `degradation` in percent.
+        pub degradation: bool => SMOKE_NODE_DEGRADATION_PROP_ID, degradation(i64), |_config| {
+            PropertyDescriptionBuilder::integer()
+                .name("Smoke chamber degradation")
+                .unit(HOMIE_UNIT_PERCENT)
+                .integer_range(IntegerRange {
+                    min: Some(0),
+                    max: Some(100),
+                    step: None,
+                })
+                .settable(false)
+                .retained(true)
+                .build()
+        },
+        /// Properties to publish as read-only; sets to them are rejected.
+        #[cfg_attr(
+            feature = "proptest",
+            proptest(strategy = "crate::arbitrary::homie_ids()")
+        )]
+        pub readonly: Vec<HomieID>,
+    }
+}
+
+impl SmokeNodeConfig {
+    fn actions(&self) -> Vec<SmokeNodeActions> {
+        let mut actions = Vec::new();
+        if self.test {
+            actions.push(SmokeNodeActions::Test);
+        }
+        if self.hush {
+            actions.push(SmokeNodeActions::Hush);
+        }
+        actions
+    }
 }
 
 // ── Builder ─────────────────────────────────────────────────────────────────
 
 pub struct SmokeNodeBuilder {
     node_builder: NodeDescriptionBuilder,
+    config: SmokeNodeConfig,
 }
 
 impl Default for SmokeNodeBuilder {
     fn default() -> Self {
-        let db = Self::build_node(NodeDescriptionBuilder::new().name(SMOKE_NODE_DEFAULT_NAME))
-            .r#type(SMARTHOME_CAP_SMOKE);
-
-        Self { node_builder: db }
+        Self::new(&SmokeNodeConfig::default())
     }
 }
 
 impl SmokeNodeBuilder {
-    pub fn new() -> Self {
-        Default::default()
+    pub fn new(config: &SmokeNodeConfig) -> Self {
+        let db = Self::build_node(
+            NodeDescriptionBuilder::new().name(SMOKE_NODE_DEFAULT_NAME),
+            config,
+        )
+        .r#type(SMARTHOME_CAP_SMOKE);
+        let db = readonly_properties(db, &config.readonly);
+
+        Self {
+            node_builder: db,
+            config: config.clone(),
+        }
     }
 
-    fn build_node(db: NodeDescriptionBuilder) -> NodeDescriptionBuilder {
-        db.add_property(
-            SMOKE_NODE_DETECTED_PROP_ID,
-            PropertyDescriptionBuilder::boolean()
-                .name("Smoke detected")
-                .boolean_labels("no smoke", "smoke detected")
-                .settable(false)
-                .retained(true)
-                .build(),
+    fn build_node(db: NodeDescriptionBuilder, config: &SmokeNodeConfig) -> NodeDescriptionBuilder {
+        let actions = config.actions();
+        config.add_optional_properties(
+            db.add_property(
+                SMOKE_NODE_DETECTED_PROP_ID,
+                PropertyDescriptionBuilder::boolean()
+                    .name("Smoke detected")
+                    .boolean_labels("no smoke", "smoke detected")
+                    .settable(false)
+                    .retained(true)
+                    .build(),
+            )
+            .add_property_cond(SMOKE_NODE_ACTION_PROP_ID, !actions.is_empty(), || {
+                PropertyDescriptionBuilder::enumeration(actions.iter().map(|a| a.as_str()))
+                    .unwrap()
+                    .name("Smoke detector action")
+                    .settable(true)
+                    .retained(false)
+                    .build()
+            }),
         )
     }
 
@@ -56,10 +227,23 @@ impl SmokeNodeBuilder {
         self
     }
 
+    /// Append a subtype suffix to the node type (`smoke-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::Smoke.with_subtype(subtype));
+        self
+    }
+
     pub fn build(self) -> HomieNodeDescription {
         self.node_builder.build()
     }
 
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &SmokeNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
     pub fn build_with_publisher(
         self,
         node_id: HomieID,
@@ -74,6 +258,7 @@ impl SmokeNodeBuilder {
                     node_id,
                 ),
                 client.clone(),
+                self.config,
             ),
         )
     }
@@ -85,15 +270,24 @@ impl SmokeNodeBuilder {
 pub struct SmokeNodePublisher {
     client: Homie5DeviceProtocol,
     node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
+    config: SmokeNodeConfig,
     detected_prop: HomieID,
+    action_prop: HomieID,
+    degradation_prop: HomieID,
 }
 
 impl SmokeNodePublisher {
-    pub fn new(node: NodeRef, client: Homie5DeviceProtocol) -> Self {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: SmokeNodeConfig) -> Self {
+        let metadata = property_metadata(&SmokeNodeBuilder::new(&config).build());
         Self {
             node,
+            metadata,
             client,
+            config,
             detected_prop: SMOKE_NODE_DETECTED_PROP_ID,
+            action_prop: SMOKE_NODE_ACTION_PROP_ID,
+            degradation_prop: SMOKE_NODE_DEGRADATION_PROP_ID,
         }
     }
 
@@ -106,3 +300,127 @@ impl SmokeNodePublisher {
         )
     }
 }
+
+impl SmarthomePublisher for SmokeNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::Smoke
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![
+            self.detected_prop.clone(),
+            self.action_prop.clone(),
+            self.degradation_prop.clone(),
+        ]
+    }
+}
+
+impl SetCommandParser for SmokeNodePublisher {
+    type Event = SmokeNodeSetEvents;
+
+    fn parse_set(
+        &self,
+        property: &PropertyRef,
+        desc: &HomieDeviceDescription,
+        set_value: &str,
+    ) -> ParseOutcome<Self::Event> {
+        if let Some(outcome) = reject_readonly(&self.node, property, desc, set_value) {
+            return outcome;
+        }
+
+        let property_id = property.prop_id().to_string();
+
+        if property.match_with_node(&self.node, &self.action_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Enum(value)) => match SmokeNodeActions::from_str(&value) {
+                    Ok(action) => ParseOutcome::Parsed(SmokeNodeSetEvents::Action(action)),
+                    Err(_) => ParseOutcome::Invalid(ParseError::new(
+                        property_id,
+                        set_value,
+                        ParseErrorKind::InvalidVariant,
+                    )),
+                },
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else {
+            ParseOutcome::NoMatch
+        }
+    }
+
+    fn parse_set_event(
+        &self,
+        desc: &HomieDeviceDescription,
+        event: &Homie5Message,
+    ) -> ParseOutcome<Self::Event> {
+        match event {
+            Homie5Message::PropertySet {
+                property,
+                set_value,
+            } => self.parse_set(property, desc, set_value),
+            _ => ParseOutcome::Invalid(ParseError::new(
+                self.action_prop.to_string(),
+                "",
+                ParseErrorKind::UnexpectedMessageType,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use homie5::HomieDomain;
+
+    use super::*;
+    use crate::StandaloneSetParser;
+
+    #[test]
+    fn action_property_only_offers_enabled_actions() {
+        let node = SmokeNodeBuilder::default().build();
+        assert!(!node.properties.contains_key(&SMOKE_NODE_ACTION_PROP_ID));
+
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let config = SmokeNodeConfig {
+            hush: true,
+            ..SmokeNodeConfig::default()
+        };
+        let parser: StandaloneSetParser<_> = SmokeNodeBuilder::new(&config)
+            .build_with_publisher(SMOKE_NODE_DEFAULT_ID, &client)
+            .into();
+        let property = PropertyRef::from_node(
+            parser.publisher().node_ref().clone(),
+            SMOKE_NODE_ACTION_PROP_ID,
+        );
+
+        assert!(matches!(
+            parser.parse_set(&property, "hush"),
+            ParseOutcome::Parsed(SmokeNodeSetEvents::Action(SmokeNodeActions::Hush))
+        ));
+        assert!(matches!(
+            parser.parse_set(&property, "test"),
+            ParseOutcome::Invalid(_)
+        ));
+    }
+}
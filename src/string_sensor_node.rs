@@ -0,0 +1,277 @@
+use chrono::prelude::*;
+use std::collections::BTreeMap;
+
+use homie5::{
+    Homie5DeviceProtocol, HomieID, HomieValue, NodeRef,
+    device_description::{
+        HomieNodeDescription, NodeDescriptionBuilder, PropertyDescriptionBuilder,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    PropertyMetadata, SMARTHOME_CAP_STRING_SENSOR, SmarthomePublisher, SmarthomeType,
+    property_metadata,
+};
+
+pub const STRING_SENSOR_NODE_DEFAULT_ID: HomieID = HomieID::new_const("status");
+pub const STRING_SENSOR_NODE_DEFAULT_NAME: &str = "Status";
+pub const STRING_SENSOR_NODE_VALUE_PROP_ID: HomieID = HomieID::new_const("value");
+pub const STRING_SENSOR_NODE_LAST_CHANGED_PROP_ID: HomieID = HomieID::new_const("last-changed");
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StringSensorNodeState {
+    pub value: String,
+    pub last_changed: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug)]
+pub struct StringSensorNode {
+    pub publisher: StringSensorNodePublisher,
+    pub state: StringSensorNodeState,
+}
+
+impl StringSensorNode {
+    /// Apply a value reported by the device and return the matching publishes.
+    /// Readings go through [`Self::update`] at the current system time; call
+    /// that directly to supply the time of the reading.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::String(value)) if p == &STRING_SENSOR_NODE_VALUE_PROP_ID => {
+                self.update(value, Utc::now())
+            }
+            (p, HomieValue::DateTime(value)) if p == &STRING_SENSOR_NODE_LAST_CHANGED_PROP_ID => {
+                self.state.last_changed = Some(*value);
+                self.publisher.last_changed(*value).into_iter().collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Record a value reported at `at`. The `last-changed` timestamp only
+    /// moves when the text differs from the previous value.
+    pub fn update(&mut self, value: &str, at: DateTime<Utc>) -> Vec<homie5::client::Publish> {
+        let mut publishes = vec![self.publisher.value(value)];
+        if self.state.value != value || self.state.last_changed.is_none() {
+            self.state.value = value.to_owned();
+            self.state.last_changed = Some(at);
+            publishes.extend(self.publisher.last_changed(at));
+        }
+        publishes
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            Some(self.publisher.value(&self.state.value)),
+            self.state
+                .last_changed
+                .and_then(|value| self.publisher.last_changed(value)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+#[serde(default)]
+pub struct StringSensorNodeConfig {
+    /// Name of the `value` property, e.g. "Error message" or "Program".
+    pub label: Option<String>,
+    /// Expose a `last-changed` timestamp updated whenever the text changes.
+    pub last_changed: bool,
+}
+
+pub struct StringSensorNodeBuilder {
+    node_builder: NodeDescriptionBuilder,
+    config: StringSensorNodeConfig,
+}
+
+impl Default for StringSensorNodeBuilder {
+    fn default() -> Self {
+        Self::new(&StringSensorNodeConfig::default())
+    }
+}
+
+impl StringSensorNodeBuilder {
+    pub fn new(config: &StringSensorNodeConfig) -> Self {
+        let db = Self::build_node(
+            NodeDescriptionBuilder::new().name(STRING_SENSOR_NODE_DEFAULT_NAME),
+            config,
+        )
+        .r#type(SMARTHOME_CAP_STRING_SENSOR);
+
+        Self {
+            node_builder: db,
+            config: config.clone(),
+        }
+    }
+
+    fn build_node(
+        db: NodeDescriptionBuilder,
+        config: &StringSensorNodeConfig,
+    ) -> NodeDescriptionBuilder {
+        db.add_property(
+            STRING_SENSOR_NODE_VALUE_PROP_ID,
+            PropertyDescriptionBuilder::string()
+                .name(config.label.as_deref().unwrap_or("Value"))
+                .settable(false)
+                .retained(true)
+                .build(),
+        )
+        .add_property_cond(
+            STRING_SENSOR_NODE_LAST_CHANGED_PROP_ID,
+            config.last_changed,
+            || {
+                PropertyDescriptionBuilder::datetime()
+                    .name("Last changed")
+                    .settable(false)
+                    .retained(true)
+                    .build()
+            },
+        )
+    }
+
+    pub fn name<S: Into<String>>(mut self, name: impl Into<Option<S>>) -> Self {
+        self.node_builder = self.node_builder.name(name);
+        self
+    }
+
+    /// Append a subtype suffix to the node type (`string-sensor-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::StringSensor.with_subtype(subtype));
+        self
+    }
+
+    pub fn build(self) -> HomieNodeDescription {
+        self.node_builder.build()
+    }
+
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &StringSensorNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
+    pub fn build_with_publisher(
+        self,
+        node_id: HomieID,
+        client: &Homie5DeviceProtocol,
+    ) -> (HomieNodeDescription, StringSensorNodePublisher) {
+        (
+            self.node_builder.build(),
+            StringSensorNodePublisher::new(
+                NodeRef::new(
+                    client.homie_domain().to_owned(),
+                    client.id().to_owned(),
+                    node_id,
+                ),
+                client.clone(),
+                self.config,
+            ),
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct StringSensorNodePublisher {
+    client: Homie5DeviceProtocol,
+    node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
+    config: StringSensorNodeConfig,
+    value_prop: HomieID,
+    last_changed_prop: HomieID,
+}
+
+impl StringSensorNodePublisher {
+    pub fn new(
+        node: NodeRef,
+        client: Homie5DeviceProtocol,
+        config: StringSensorNodeConfig,
+    ) -> Self {
+        let metadata = property_metadata(&StringSensorNodeBuilder::new(&config).build());
+        Self {
+            node,
+            metadata,
+            client,
+            config,
+            value_prop: STRING_SENSOR_NODE_VALUE_PROP_ID,
+            last_changed_prop: STRING_SENSOR_NODE_LAST_CHANGED_PROP_ID,
+        }
+    }
+
+    pub fn value(&self, value: &str) -> homie5::client::Publish {
+        self.client
+            .publish_value(self.node.node_id(), &self.value_prop, value, true)
+    }
+
+    pub fn last_changed(&self, value: DateTime<Utc>) -> Option<homie5::client::Publish> {
+        if !self.config.last_changed {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.last_changed_prop,
+            value.to_rfc3339_opts(SecondsFormat::Millis, true),
+            true,
+        ))
+    }
+}
+
+impl SmarthomePublisher for StringSensorNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::StringSensor
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![self.value_prop.clone(), self.last_changed_prop.clone()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use homie5::HomieDomain;
+
+    use super::*;
+
+    #[test]
+    fn last_changed_only_moves_on_new_text() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let config = StringSensorNodeConfig {
+            last_changed: true,
+            ..Default::default()
+        };
+        let (_, publisher) = StringSensorNodeBuilder::new(&config)
+            .build_with_publisher(STRING_SENSOR_NODE_DEFAULT_ID, &client);
+        let mut node = StringSensorNode {
+            publisher,
+            state: StringSensorNodeState::default(),
+        };
+        let first = Utc.with_ymd_and_hms(2026, 3, 1, 8, 0, 0).unwrap();
+        let later = Utc.with_ymd_and_hms(2026, 3, 1, 9, 0, 0).unwrap();
+
+        assert_eq!(node.update("E23: drain blocked", first).len(), 2);
+        assert_eq!(node.update("E23: drain blocked", later).len(), 1);
+        assert_eq!(node.state.last_changed, Some(first));
+        assert_eq!(node.update("idle", later).len(), 2);
+        assert_eq!(node.state.last_changed, Some(later));
+    }
+}
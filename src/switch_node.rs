@@ -1,38 +1,142 @@
 use core::fmt;
 
+use std::collections::BTreeMap;
+
 use homie5::{
     Homie5DeviceProtocol, Homie5Message, Homie5ProtocolError, HomieID, HomieValue, NodeRef,
     PropertyRef,
     device_description::{
-        HomieDeviceDescription, HomieNodeDescription, NodeDescriptionBuilder,
-        PropertyDescriptionBuilder,
+        BooleanFormat, HomieDeviceDescription, HomieNodeDescription, IntegerRange,
+        NodeDescriptionBuilder, PropertyDescriptionBuilder,
     },
 };
 use serde::{Deserialize, Serialize};
 
-use crate::{ParseError, ParseErrorKind, ParseOutcome, SMARTHOME_CAP_SWITCH, SetCommandParser};
+use crate::{
+    ConfigError, ConfigErrorKind, ParseError, ParseErrorKind, ParseOutcome, PropertyMetadata,
+    SMARTHOME_CAP_SWITCH, SetCommandParser, SmarthomePublisher, SmarthomeType, property_metadata,
+    readonly_properties, reject_readonly,
+};
 
 pub const SWITCH_NODE_DEFAULT_ID: HomieID = HomieID::new_const("switch");
 pub const SWITCH_NODE_DEFAULT_NAME: &str = "On/Off switch";
 pub const SWITCH_NODE_STATE_PROP_ID: HomieID = HomieID::new_const("state");
 pub const SWITCH_NODE_ACTION_PROP_ID: HomieID = HomieID::new_const("action");
+pub const SWITCH_NODE_IMPULSE_DURATION_PROP_ID: HomieID = HomieID::new_const("impulse-duration");
+pub const SWITCH_NODE_LED_PROP_ID: HomieID = HomieID::new_const("led");
+
+pub const MULTI_RELAY_NODE_DEFAULT_NAME: &str = "Multi-channel relay";
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SwitchNodeState {
+    pub state: bool,
+    pub state_target: bool,
+    pub impulse_duration: Option<i64>,
+    pub led: Option<bool>,
+}
 
 #[derive(Debug)]
 pub struct SwitchNode {
     pub publisher: SwitchNodePublisher,
-    pub state: bool,
-    pub state_target: bool,
+    pub state: SwitchNodeState,
+}
+
+impl SwitchNode {
+    /// Apply a parsed set command and return the `$target` and value publishes
+    /// for the new state. Impulse actions carry no state and publish nothing.
+    pub fn apply_set(&mut self, event: SwitchNodeSetEvents) -> Vec<homie5::client::Publish> {
+        match event {
+            SwitchNodeSetEvents::State(value) => self.apply_state(value),
+            SwitchNodeSetEvents::Action(SwitchNodeActions::Toggle) => {
+                self.apply_state(!self.state.state)
+            }
+            SwitchNodeSetEvents::Action(SwitchNodeActions::Impulse) => Vec::new(),
+            SwitchNodeSetEvents::ImpulseDuration(value) => {
+                self.state.impulse_duration = Some(value);
+                [
+                    self.publisher.impulse_duration_target(value),
+                    self.publisher.impulse_duration(value),
+                ]
+                .into_iter()
+                .flatten()
+                .collect()
+            }
+            SwitchNodeSetEvents::Led(value) => {
+                self.state.led = Some(value);
+                [self.publisher.led_target(value), self.publisher.led(value)]
+                    .into_iter()
+                    .flatten()
+                    .collect()
+            }
+        }
+    }
+
+    /// Apply a value reported by the device and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Bool(value)) if p == &SWITCH_NODE_STATE_PROP_ID => {
+                self.state.state = *value;
+                vec![self.publisher.state(*value)]
+            }
+            (p, HomieValue::Integer(value)) if p == &SWITCH_NODE_IMPULSE_DURATION_PROP_ID => {
+                self.state.impulse_duration = Some(*value);
+                self.publisher
+                    .impulse_duration(*value)
+                    .into_iter()
+                    .collect()
+            }
+            (p, HomieValue::Bool(value)) if p == &SWITCH_NODE_LED_PROP_ID => {
+                self.state.led = Some(*value);
+                self.publisher.led(*value).into_iter().collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn apply_state(&mut self, value: bool) -> Vec<homie5::client::Publish> {
+        self.state.state_target = value;
+        self.state.state = value;
+        vec![
+            self.publisher.state_target(value),
+            self.publisher.state(value),
+        ]
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        let has_state = self.publisher.config.mode.has_state();
+        [
+            has_state.then(|| self.publisher.state(self.state.state)),
+            has_state.then(|| self.publisher.state_target(self.state.state_target)),
+            self.state
+                .impulse_duration
+                .and_then(|value| self.publisher.impulse_duration(value)),
+            self.state.led.and_then(|value| self.publisher.led(value)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 pub enum SwitchNodeActions {
     Toggle,
+    Impulse,
 }
 
 impl fmt::Display for SwitchNodeActions {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             SwitchNodeActions::Toggle => f.write_str("toggle"),
+            SwitchNodeActions::Impulse => f.write_str("impulse"),
         }
     }
 }
@@ -43,30 +147,93 @@ impl TryFrom<String> for SwitchNodeActions {
     fn try_from(value: String) -> Result<Self, Self::Error> {
         match value.as_str() {
             "toggle" => Ok(SwitchNodeActions::Toggle),
+            "impulse" => Ok(SwitchNodeActions::Impulse),
             _ => Err(Homie5ProtocolError::InvalidPayload),
         }
     }
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 pub enum SwitchNodeSetEvents {
     State(bool),
     Action(SwitchNodeActions),
+    ImpulseDuration(i64),
+    Led(bool),
+}
+
+/// Selects whether the switch exposes a retained on/off state, a momentary
+/// impulse action (e.g. garage openers, door bells) or both.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+#[serde(rename_all = "kebab-case")]
+pub enum SwitchNodeMode {
+    #[default]
+    State,
+    Impulse,
+    StateAndImpulse,
+}
+
+impl SwitchNodeMode {
+    pub fn has_state(&self) -> bool {
+        matches!(self, SwitchNodeMode::State | SwitchNodeMode::StateAndImpulse)
+    }
+
+    pub fn has_impulse(&self) -> bool {
+        matches!(
+            self,
+            SwitchNodeMode::Impulse | SwitchNodeMode::StateAndImpulse
+        )
+    }
 }
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 #[serde(default)]
 pub struct SwitchNodeConfig {
     pub settable: bool,
+    pub mode: SwitchNodeMode,
+    /// Pulse duration in milliseconds used for the `impulse` action.
+    #[cfg_attr(
+        feature = "proptest",
+        proptest(strategy = "crate::arbitrary::seconds()")
+    )]
+    pub impulse_duration: i64,
+    /// Display labels for the `state` property, e.g. `closed`/`open` for
+    /// valve-like switches.
+    #[cfg_attr(
+        feature = "proptest",
+        proptest(strategy = "crate::arbitrary::boolean_format()")
+    )]
+    pub state_labels: BooleanFormat,
+    /// Expose a settable status/indicator LED as seen on many smart plugs.
+    pub led: bool,
+    /// Properties to publish as read-only; sets to them are rejected.
+    #[cfg_attr(
+        feature = "proptest",
+        proptest(strategy = "crate::arbitrary::homie_ids()")
+    )]
+    pub readonly: Vec<HomieID>,
 }
 
 impl Default for SwitchNodeConfig {
     fn default() -> Self {
-        Self { settable: true }
+        Self {
+            settable: true,
+            mode: SwitchNodeMode::State,
+            impulse_duration: 500,
+            state_labels: BooleanFormat {
+                false_val: "off".to_string(),
+                true_val: "on".to_string(),
+            },
+            led: false,
+            readonly: Vec::new(),
+        }
     }
 }
 
 pub struct SwitchNodeBuilder {
+    config: SwitchNodeConfig,
     node_builder: NodeDescriptionBuilder,
 }
 
@@ -77,29 +244,68 @@ impl SwitchNodeBuilder {
             config,
         )
         .r#type(SMARTHOME_CAP_SWITCH);
+        let db = readonly_properties(db, &config.readonly);
 
-        Self { node_builder: db }
+        Self {
+            config: config.clone(),
+            node_builder: db,
+        }
     }
 
     fn build_node(db: NodeDescriptionBuilder, config: &SwitchNodeConfig) -> NodeDescriptionBuilder {
-        db.add_property(
-            SWITCH_NODE_STATE_PROP_ID,
+        let mut actions = Vec::new();
+        if config.mode.has_state() {
+            actions.push(SwitchNodeActions::Toggle);
+        }
+        if config.mode.has_impulse() {
+            actions.push(SwitchNodeActions::Impulse);
+        }
+
+        db.add_property_cond(SWITCH_NODE_STATE_PROP_ID, config.mode.has_state(), || {
             PropertyDescriptionBuilder::boolean()
                 .name("On/Off state")
-                .boolean_labels("off", "on")
+                .boolean_labels(
+                    config.state_labels.false_val.to_owned(),
+                    config.state_labels.true_val.to_owned(),
+                )
                 .settable(config.settable)
                 .retained(true)
-                .build(),
-        )
+                .build()
+        })
         .add_property(
             SWITCH_NODE_ACTION_PROP_ID,
-            PropertyDescriptionBuilder::enumeration(["toggle"])
+            PropertyDescriptionBuilder::enumeration(actions.iter().map(|a| a.to_string()))
                 .unwrap()
                 .name("Change state")
                 .settable(config.settable)
                 .retained(false)
                 .build(),
         )
+        .add_property_cond(
+            SWITCH_NODE_IMPULSE_DURATION_PROP_ID,
+            config.mode.has_impulse(),
+            || {
+                PropertyDescriptionBuilder::integer()
+                    .name("Impulse duration")
+                    .integer_range(IntegerRange {
+                        min: Some(0),
+                        max: None,
+                        step: None,
+                    })
+                    .unit("ms")
+                    .settable(config.settable)
+                    .retained(true)
+                    .build()
+            },
+        )
+        .add_property_cond(SWITCH_NODE_LED_PROP_ID, config.led, || {
+            PropertyDescriptionBuilder::boolean()
+                .name("Indicator LED")
+                .boolean_labels("off", "on")
+                .settable(true)
+                .retained(true)
+                .build()
+        })
     }
 
     pub fn name<S: Into<String>>(mut self, name: impl Into<Option<S>>) -> Self {
@@ -107,10 +313,23 @@ impl SwitchNodeBuilder {
         self
     }
 
+    /// Append a subtype suffix to the node type (`switch-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::Switch.with_subtype(subtype));
+        self
+    }
+
     pub fn build(self) -> HomieNodeDescription {
         self.node_builder.build()
     }
 
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &SwitchNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
     pub fn build_with_publisher(
         self,
         node_id: HomieID,
@@ -125,6 +344,7 @@ impl SwitchNodeBuilder {
                     node_id,
                 ),
                 client.clone(),
+                self.config,
             ),
         )
     }
@@ -133,18 +353,27 @@ impl SwitchNodeBuilder {
 #[derive(Debug)]
 pub struct SwitchNodePublisher {
     client: Homie5DeviceProtocol,
+    config: SwitchNodeConfig,
     node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
     state_prop: HomieID,
     action_prop: HomieID,
+    impulse_duration_prop: HomieID,
+    led_prop: HomieID,
 }
 
 impl SwitchNodePublisher {
-    pub fn new(node: NodeRef, client: Homie5DeviceProtocol) -> Self {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: SwitchNodeConfig) -> Self {
+        let metadata = property_metadata(&SwitchNodeBuilder::new(&config).build());
         Self {
             node,
+            metadata,
             client,
+            config,
             state_prop: SWITCH_NODE_STATE_PROP_ID,
             action_prop: SWITCH_NODE_ACTION_PROP_ID,
+            impulse_duration_prop: SWITCH_NODE_IMPULSE_DURATION_PROP_ID,
+            led_prop: SWITCH_NODE_LED_PROP_ID,
         }
     }
 
@@ -166,14 +395,99 @@ impl SwitchNodePublisher {
         )
     }
 
-    pub fn action(&self) -> homie5::client::Publish {
+    pub fn action(&self, kind: &SwitchNodeActions) -> homie5::client::Publish {
         self.client.publish_value(
             self.node.node_id(),
             &self.action_prop,
-            SwitchNodeActions::Toggle.to_string(),
+            kind.to_string(),
             false,
         )
     }
+
+    pub fn impulse(&self) -> Option<homie5::client::Publish> {
+        if !self.config.mode.has_impulse() {
+            return None;
+        }
+        Some(self.action(&SwitchNodeActions::Impulse))
+    }
+
+    /// Publish the pulse duration in milliseconds. Use
+    /// [`SwitchNodePublisher::configured_impulse_duration`] to publish the
+    /// initial value from the node config.
+    pub fn impulse_duration(&self, value: i64) -> Option<homie5::client::Publish> {
+        if !self.config.mode.has_impulse() {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.impulse_duration_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+
+    pub fn impulse_duration_target(&self, value: i64) -> Option<homie5::client::Publish> {
+        if !self.config.mode.has_impulse() {
+            return None;
+        }
+        Some(self.client.publish_target(
+            self.node.node_id(),
+            &self.impulse_duration_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+
+    pub fn configured_impulse_duration(&self) -> Option<homie5::client::Publish> {
+        self.impulse_duration(self.config.impulse_duration)
+    }
+
+    pub fn led(&self, value: bool) -> Option<homie5::client::Publish> {
+        if !self.config.led {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.led_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+
+    pub fn led_target(&self, value: bool) -> Option<homie5::client::Publish> {
+        if !self.config.led {
+            return None;
+        }
+        Some(self.client.publish_target(
+            self.node.node_id(),
+            &self.led_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+}
+
+impl SmarthomePublisher for SwitchNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::Switch
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![
+            self.state_prop.clone(),
+            self.action_prop.clone(),
+            self.impulse_duration_prop.clone(),
+            self.led_prop.clone(),
+        ]
+    }
 }
 
 impl SetCommandParser for SwitchNodePublisher {
@@ -185,6 +499,10 @@ impl SetCommandParser for SwitchNodePublisher {
         desc: &HomieDeviceDescription,
         set_value: &str,
     ) -> ParseOutcome<Self::Event> {
+        if let Some(outcome) = reject_readonly(&self.node, property, desc, set_value) {
+            return outcome;
+        }
+
         let property_id = property.prop_id().to_string();
 
         if property.match_with_device(
@@ -206,6 +524,13 @@ impl SetCommandParser for SwitchNodePublisher {
                 Ok(HomieValue::Bool(value)) => {
                     ParseOutcome::Parsed(SwitchNodeSetEvents::State(value))
                 }
+                // accept the configured labels as well (e.g. "open"/"closed")
+                _ if set_value == self.config.state_labels.true_val => {
+                    ParseOutcome::Parsed(SwitchNodeSetEvents::State(true))
+                }
+                _ if set_value == self.config.state_labels.false_val => {
+                    ParseOutcome::Parsed(SwitchNodeSetEvents::State(false))
+                }
                 _ => ParseOutcome::Invalid(ParseError::new(
                     property.prop_id().to_string(),
                     set_value,
@@ -238,6 +563,48 @@ impl SetCommandParser for SwitchNodePublisher {
                     ParseErrorKind::InvalidHomieValue,
                 )),
             }
+        } else if property.match_with_node(&self.node, &self.impulse_duration_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Integer(value)) => {
+                    ParseOutcome::Parsed(SwitchNodeSetEvents::ImpulseDuration(value))
+                }
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property.prop_id().to_string(),
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else if property.match_with_node(&self.node, &self.led_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Bool(value)) => {
+                    ParseOutcome::Parsed(SwitchNodeSetEvents::Led(value))
+                }
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property.prop_id().to_string(),
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
         } else {
             ParseOutcome::NoMatch
         }
@@ -261,3 +628,663 @@ impl SetCommandParser for SwitchNodePublisher {
         }
     }
 }
+
+// ── Multi-channel relays ────────────────────────────────────────────────────
+
+/// Property ID of the state property for a (1-based) relay channel,
+/// e.g. `state-1`.
+pub fn multi_relay_state_prop_id(channel: u8) -> HomieID {
+    HomieID::try_from(format!("{}-{}", SWITCH_NODE_STATE_PROP_ID, channel))
+        .expect("indexed state id is always a valid HomieID")
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MultiRelayNodeState {
+    /// Relay states by channel, `states[0]` being channel 1.
+    pub states: Vec<bool>,
+}
+
+#[derive(Debug)]
+pub struct MultiRelayNode {
+    pub publisher: MultiRelayNodePublisher,
+    pub state: MultiRelayNodeState,
+}
+
+impl MultiRelayNode {
+    /// Apply a parsed set command and return the `$target` and value publishes
+    /// for the switched channel.
+    pub fn apply_set(&mut self, event: MultiRelayNodeSetEvents) -> Vec<homie5::client::Publish> {
+        match event {
+            MultiRelayNodeSetEvents::State(channel, value) => {
+                let Some(index) = usize::from(channel).checked_sub(1) else {
+                    return Vec::new();
+                };
+                self.store_state(index, value);
+                [
+                    self.publisher.state_target(channel, value),
+                    self.publisher.state(channel, value),
+                ]
+                .into_iter()
+                .flatten()
+                .collect()
+            }
+        }
+    }
+
+    /// Apply a channel state reported by the device and return the matching
+    /// publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        let HomieValue::Bool(value) = value else {
+            return Vec::new();
+        };
+        let Some(index) = self
+            .publisher
+            .state_props
+            .iter()
+            .position(|prop| prop == property)
+        else {
+            return Vec::new();
+        };
+        self.store_state(index, *value);
+        u8::try_from(index + 1)
+            .ok()
+            .and_then(|channel| self.publisher.state(channel, *value))
+            .into_iter()
+            .collect()
+    }
+
+    fn store_state(&mut self, index: usize, value: bool) {
+        if self.state.states.len() <= index {
+            self.state.states.resize(index + 1, false);
+        }
+        self.state.states[index] = value;
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        (1..=self.publisher.config.channels)
+            .zip(self.state.states.iter())
+            .filter_map(|(channel, value)| self.publisher.state(channel, *value))
+            .collect()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+pub enum MultiRelayNodeSetEvents {
+    /// Switch the given 1-based channel on or off.
+    State(u8, bool),
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+#[serde(default)]
+pub struct MultiRelayNodeConfig {
+    /// Number of relays, exposed as `state-1` .. `state-<channels>`.
+    pub channels: u8,
+    /// Display labels for the state properties.
+    #[cfg_attr(
+        feature = "proptest",
+        proptest(strategy = "crate::arbitrary::boolean_format()")
+    )]
+    pub state_labels: BooleanFormat,
+    /// Properties to publish as read-only; sets to them are rejected.
+    #[cfg_attr(
+        feature = "proptest",
+        proptest(strategy = "crate::arbitrary::homie_ids()")
+    )]
+    pub readonly: Vec<HomieID>,
+}
+
+impl MultiRelayNodeConfig {
+    /// Check the channel count before building the node.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.channels == 0 {
+            return Err(ConfigError {
+                property_id: multi_relay_state_prop_id(1),
+                kind: ConfigErrorKind::NoChannels,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl Default for MultiRelayNodeConfig {
+    fn default() -> Self {
+        Self {
+            channels: 2,
+            state_labels: BooleanFormat {
+                false_val: "off".to_string(),
+                true_val: "on".to_string(),
+            },
+            readonly: Vec::new(),
+        }
+    }
+}
+
+/// Builds a single switch capability node for relay modules with several
+/// channels (e.g. DIN-rail relays).
+pub struct MultiRelayNodeBuilder {
+    config: MultiRelayNodeConfig,
+    node_builder: NodeDescriptionBuilder,
+}
+
+impl MultiRelayNodeBuilder {
+    pub fn new(config: &MultiRelayNodeConfig) -> Self {
+        let db = Self::build_node(
+            NodeDescriptionBuilder::new().name(MULTI_RELAY_NODE_DEFAULT_NAME),
+            config,
+        )
+        .r#type(SMARTHOME_CAP_SWITCH);
+        let db = readonly_properties(db, &config.readonly);
+
+        Self {
+            config: config.clone(),
+            node_builder: db,
+        }
+    }
+
+    fn build_node(
+        db: NodeDescriptionBuilder,
+        config: &MultiRelayNodeConfig,
+    ) -> NodeDescriptionBuilder {
+        (1..=config.channels).fold(db, |db, channel| {
+            db.add_property(
+                multi_relay_state_prop_id(channel),
+                PropertyDescriptionBuilder::boolean()
+                    .name(format!("Relay {} state", channel))
+                    .boolean_labels(
+                        config.state_labels.false_val.to_owned(),
+                        config.state_labels.true_val.to_owned(),
+                    )
+                    .settable(true)
+                    .retained(true)
+                    .build(),
+            )
+        })
+    }
+
+    pub fn name<S: Into<String>>(mut self, name: impl Into<Option<S>>) -> Self {
+        self.node_builder = self.node_builder.name(name);
+        self
+    }
+
+    /// Append a subtype suffix to the node type (`switch-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::Switch.with_subtype(subtype));
+        self
+    }
+
+    pub fn build(self) -> HomieNodeDescription {
+        self.node_builder.build()
+    }
+
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &MultiRelayNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
+    pub fn build_with_publisher(
+        self,
+        node_id: HomieID,
+        client: &Homie5DeviceProtocol,
+    ) -> (HomieNodeDescription, MultiRelayNodePublisher) {
+        (
+            self.node_builder.build(),
+            MultiRelayNodePublisher::new(
+                NodeRef::new(client.homie_domain().clone(), client.id().clone(), node_id),
+                client.clone(),
+                self.config,
+            ),
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct MultiRelayNodePublisher {
+    client: Homie5DeviceProtocol,
+    config: MultiRelayNodeConfig,
+    node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
+    state_props: Vec<HomieID>,
+}
+
+impl MultiRelayNodePublisher {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: MultiRelayNodeConfig) -> Self {
+        let metadata = property_metadata(&MultiRelayNodeBuilder::new(&config).build());
+        let state_props = (1..=config.channels)
+            .map(multi_relay_state_prop_id)
+            .collect();
+        Self {
+            node,
+            metadata,
+            client,
+            config,
+            state_props,
+        }
+    }
+
+    fn state_prop(&self, channel: u8) -> Option<&HomieID> {
+        self.state_props.get(usize::from(channel).checked_sub(1)?)
+    }
+
+    /// Publish the state of the given 1-based channel. Returns `None` for
+    /// channels outside of `1..=channels`.
+    pub fn state(&self, channel: u8, value: bool) -> Option<homie5::client::Publish> {
+        let prop = self.state_prop(channel)?;
+        Some(
+            self.client
+                .publish_value(self.node.node_id(), prop, value.to_string(), true),
+        )
+    }
+
+    pub fn state_target(&self, channel: u8, value: bool) -> Option<homie5::client::Publish> {
+        let prop = self.state_prop(channel)?;
+        Some(
+            self.client
+                .publish_target(self.node.node_id(), prop, value.to_string(), true),
+        )
+    }
+}
+
+impl SmarthomePublisher for MultiRelayNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::Switch
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        self.state_props.clone()
+    }
+}
+
+impl SetCommandParser for MultiRelayNodePublisher {
+    type Event = MultiRelayNodeSetEvents;
+
+    fn parse_set(
+        &self,
+        property: &PropertyRef,
+        desc: &HomieDeviceDescription,
+        set_value: &str,
+    ) -> ParseOutcome<Self::Event> {
+        if let Some(outcome) = reject_readonly(&self.node, property, desc, set_value) {
+            return outcome;
+        }
+
+        let Some(index) = self
+            .state_props
+            .iter()
+            .position(|prop| property.match_with_node(&self.node, prop))
+        else {
+            return ParseOutcome::NoMatch;
+        };
+        let property_id = property.prop_id().to_string();
+
+        let Some(parsed) = desc.with_property(property, |prop_desc| {
+            HomieValue::parse(set_value, prop_desc)
+        }) else {
+            return ParseOutcome::Invalid(ParseError::new(
+                property_id,
+                set_value,
+                ParseErrorKind::MissingPropertyDescription,
+            ));
+        };
+
+        let channel = index as u8 + 1;
+        match parsed {
+            Ok(HomieValue::Bool(value)) => {
+                ParseOutcome::Parsed(MultiRelayNodeSetEvents::State(channel, value))
+            }
+            _ if set_value == self.config.state_labels.true_val => {
+                ParseOutcome::Parsed(MultiRelayNodeSetEvents::State(channel, true))
+            }
+            _ if set_value == self.config.state_labels.false_val => {
+                ParseOutcome::Parsed(MultiRelayNodeSetEvents::State(channel, false))
+            }
+            _ => ParseOutcome::Invalid(ParseError::new(
+                property_id,
+                set_value,
+                ParseErrorKind::InvalidHomieValue,
+            )),
+        }
+    }
+
+    fn parse_set_event(
+        &self,
+        desc: &HomieDeviceDescription,
+        event: &Homie5Message,
+    ) -> ParseOutcome<Self::Event> {
+        match event {
+            Homie5Message::PropertySet {
+                property,
+                set_value,
+            } => self.parse_set(property, desc, set_value),
+            _ => ParseOutcome::Invalid(ParseError::new(
+                SWITCH_NODE_STATE_PROP_ID.to_string(),
+                "",
+                ParseErrorKind::UnexpectedMessageType,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use homie5::device_description::HomiePropertyFormat;
+
+    #[test]
+    fn mode_gates_state_and_impulse_properties() {
+        let node = SwitchNodeBuilder::new(&SwitchNodeConfig::default()).build();
+        assert!(node.properties.contains_key(&SWITCH_NODE_STATE_PROP_ID));
+        assert!(!node.properties.contains_key(&SWITCH_NODE_IMPULSE_DURATION_PROP_ID));
+
+        let config = SwitchNodeConfig {
+            mode: SwitchNodeMode::Impulse,
+            ..SwitchNodeConfig::default()
+        };
+        let node = SwitchNodeBuilder::new(&config).build();
+        assert!(!node.properties.contains_key(&SWITCH_NODE_STATE_PROP_ID));
+        assert!(node.properties.contains_key(&SWITCH_NODE_IMPULSE_DURATION_PROP_ID));
+
+        let action = node
+            .properties
+            .get(&SWITCH_NODE_ACTION_PROP_ID)
+            .expect("action property must exist");
+        assert!(!action.retained);
+        assert_eq!(
+            action.format,
+            HomiePropertyFormat::Enum(vec!["impulse".to_string()])
+        );
+    }
+
+    #[test]
+    fn state_property_uses_configured_labels() {
+        let config = SwitchNodeConfig {
+            state_labels: BooleanFormat {
+                false_val: "closed".to_string(),
+                true_val: "open".to_string(),
+            },
+            ..SwitchNodeConfig::default()
+        };
+        let node = SwitchNodeBuilder::new(&config).build();
+        assert_eq!(
+            node.properties
+                .get(&SWITCH_NODE_STATE_PROP_ID)
+                .expect("state property must exist")
+                .format,
+            HomiePropertyFormat::Boolean(BooleanFormat {
+                false_val: "closed".to_string(),
+                true_val: "open".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn parse_set_accepts_configured_state_labels() {
+        let config = SwitchNodeConfig {
+            state_labels: BooleanFormat {
+                false_val: "closed".to_string(),
+                true_val: "open".to_string(),
+            },
+            ..SwitchNodeConfig::default()
+        };
+        let (client, _) = Homie5DeviceProtocol::new(
+            HomieID::new_const("dev"),
+            homie5::HomieDomain::Default,
+        );
+        let (node, publisher) =
+            SwitchNodeBuilder::new(&config).build_with_publisher(SWITCH_NODE_DEFAULT_ID, &client);
+        let desc = homie5::device_description::DeviceDescriptionBuilder::new()
+            .add_node(SWITCH_NODE_DEFAULT_ID, node)
+            .build();
+        let property = PropertyRef::from_node(publisher.node.clone(), SWITCH_NODE_STATE_PROP_ID);
+
+        for (payload, expected) in [("true", true), ("open", true), ("closed", false)] {
+            match publisher.parse_set(&property, &desc, payload) {
+                ParseOutcome::Parsed(SwitchNodeSetEvents::State(value)) => {
+                    assert_eq!(value, expected)
+                }
+                other => panic!("unexpected parse outcome for {payload}: {other:?}"),
+            }
+        }
+        assert!(matches!(
+            publisher.parse_set(&property, &desc, "on"),
+            ParseOutcome::Invalid(_)
+        ));
+    }
+
+    #[test]
+    fn state_and_impulse_mode_exposes_both_actions() {
+        let config = SwitchNodeConfig {
+            mode: SwitchNodeMode::StateAndImpulse,
+            ..SwitchNodeConfig::default()
+        };
+        let node = SwitchNodeBuilder::new(&config).build();
+        assert!(node.properties.contains_key(&SWITCH_NODE_STATE_PROP_ID));
+        assert_eq!(
+            node.properties
+                .get(&SWITCH_NODE_ACTION_PROP_ID)
+                .expect("action property must exist")
+                .format,
+            HomiePropertyFormat::Enum(vec!["toggle".to_string(), "impulse".to_string()])
+        );
+    }
+
+    #[test]
+    fn apply_set_toggles_state_and_publishes_target_and_value() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), homie5::HomieDomain::Default);
+        let (_, publisher) = SwitchNodeBuilder::new(&SwitchNodeConfig::default())
+            .build_with_publisher(SWITCH_NODE_DEFAULT_ID, &client);
+        let mut node = SwitchNode {
+            publisher,
+            state: SwitchNodeState::default(),
+        };
+
+        let publishes = node.apply_set(SwitchNodeSetEvents::Action(SwitchNodeActions::Toggle));
+        assert!(node.state.state && node.state.state_target);
+        assert_eq!(publishes.len(), 2);
+        assert!(publishes[0].topic.ends_with("/switch/state/$target"));
+        assert!(publishes[1].topic.ends_with("/switch/state"));
+        assert_eq!(publishes[1].payload, b"true".to_vec());
+
+        let publishes = node.apply_value(&SWITCH_NODE_STATE_PROP_ID, &HomieValue::Bool(false));
+        assert!(!node.state.state);
+        assert!(node.state.state_target);
+        assert_eq!(publishes.len(), 1);
+
+        assert!(
+            node.apply_value(&SWITCH_NODE_LED_PROP_ID, &HomieValue::Bool(true))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn impulse_actions_and_duration_sets_are_published() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), homie5::HomieDomain::Default);
+        let config = SwitchNodeConfig {
+            mode: SwitchNodeMode::Impulse,
+            ..SwitchNodeConfig::default()
+        };
+        let (_, publisher) =
+            SwitchNodeBuilder::new(&config).build_with_publisher(SWITCH_NODE_DEFAULT_ID, &client);
+        assert_eq!(
+            publisher.action(&SwitchNodeActions::Impulse).payload,
+            b"impulse".to_vec()
+        );
+        let mut node = SwitchNode {
+            publisher,
+            state: SwitchNodeState::default(),
+        };
+
+        let publishes = node.apply_set(SwitchNodeSetEvents::ImpulseDuration(500));
+        assert_eq!(node.state.impulse_duration, Some(500));
+        assert_eq!(publishes.len(), 2);
+        assert!(publishes[0].topic.ends_with("/impulse-duration/$target"));
+        assert!(publishes[1].topic.ends_with("/impulse-duration"));
+    }
+
+    #[test]
+    fn publishes_covers_full_state() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), homie5::HomieDomain::Default);
+        let config = SwitchNodeConfig {
+            led: true,
+            ..SwitchNodeConfig::default()
+        };
+        let (_, publisher) =
+            SwitchNodeBuilder::new(&config).build_with_publisher(SWITCH_NODE_DEFAULT_ID, &client);
+        let node = SwitchNode {
+            publisher,
+            state: SwitchNodeState {
+                state: true,
+                impulse_duration: Some(500),
+                led: Some(true),
+                ..SwitchNodeState::default()
+            },
+        };
+
+        let topics: Vec<_> = node.publishes().into_iter().map(|p| p.topic).collect();
+        assert_eq!(topics.len(), 3);
+        assert!(topics[0].ends_with("/switch/state"));
+        assert!(topics[1].ends_with("/switch/state/$target"));
+        assert!(topics[2].ends_with("/switch/led"));
+    }
+
+    #[test]
+    fn state_round_trips_through_serde() {
+        let state = SwitchNodeState {
+            state: true,
+            led: Some(false),
+            ..SwitchNodeState::default()
+        };
+        let json = serde_json::to_string(&state).unwrap();
+        assert_eq!(
+            serde_json::from_str::<SwitchNodeState>(&json).unwrap(),
+            state
+        );
+        assert_eq!(
+            serde_json::from_str::<SwitchNodeState>(r#"{"state":true}"#).unwrap(),
+            SwitchNodeState {
+                state: true,
+                ..SwitchNodeState::default()
+            }
+        );
+    }
+
+    #[test]
+    fn subtype_is_appended_to_node_type() {
+        let node = SwitchNodeBuilder::new(&SwitchNodeConfig::default())
+            .subtype("relay")
+            .build();
+        assert_eq!(node.r#type.as_deref(), Some("hc-smarthome/v2/cap/switch-relay"));
+    }
+
+    #[test]
+    fn readonly_properties_are_published_and_parsed_as_read_only() {
+        let config = SwitchNodeConfig {
+            led: true,
+            readonly: vec![SWITCH_NODE_LED_PROP_ID],
+            ..SwitchNodeConfig::default()
+        };
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), homie5::HomieDomain::Default);
+        let (node, publisher) =
+            SwitchNodeBuilder::new(&config).build_with_publisher(SWITCH_NODE_DEFAULT_ID, &client);
+        assert!(!node.properties[&SWITCH_NODE_LED_PROP_ID].settable);
+        assert!(node.properties[&SWITCH_NODE_STATE_PROP_ID].settable);
+
+        let desc = homie5::device_description::DeviceDescriptionBuilder::new()
+            .add_node(SWITCH_NODE_DEFAULT_ID, node)
+            .build();
+        let led = PropertyRef::from_node(publisher.node.clone(), SWITCH_NODE_LED_PROP_ID);
+        let state = PropertyRef::from_node(publisher.node.clone(), SWITCH_NODE_STATE_PROP_ID);
+        assert!(matches!(
+            publisher.parse_set(&led, &desc, "true"),
+            ParseOutcome::Invalid(ParseError {
+                kind: ParseErrorKind::NotSettable,
+                ..
+            })
+        ));
+        assert!(matches!(
+            publisher.parse_set(&state, &desc, "true"),
+            ParseOutcome::Parsed(SwitchNodeSetEvents::State(true))
+        ));
+    }
+
+    #[test]
+    fn multi_relay_generates_indexed_state_properties() {
+        let config = MultiRelayNodeConfig {
+            channels: 4,
+            ..MultiRelayNodeConfig::default()
+        };
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), homie5::HomieDomain::Default);
+        let (node, publisher) = MultiRelayNodeBuilder::new(&config)
+            .build_with_publisher(SWITCH_NODE_DEFAULT_ID, &client);
+        assert_eq!(node.properties.len(), 4);
+
+        let publish = publisher.state(3, true).expect("channel 3 must exist");
+        assert!(publish.topic.ends_with("/switch/state-3"));
+        assert!(publisher.state(0, true).is_none());
+        assert!(publisher.state(5, true).is_none());
+
+        let desc = homie5::device_description::DeviceDescriptionBuilder::new()
+            .add_node(SWITCH_NODE_DEFAULT_ID, node)
+            .build();
+        let property = PropertyRef::from_node(publisher.node.clone(), multi_relay_state_prop_id(2));
+        let outcome = publisher.parse_set(&property, &desc, "on");
+        assert_eq!(
+            outcome,
+            ParseOutcome::Parsed(MultiRelayNodeSetEvents::State(2, true))
+        );
+
+        let mut relay = MultiRelayNode {
+            publisher,
+            state: MultiRelayNodeState::default(),
+        };
+        let publishes = relay.apply_set(MultiRelayNodeSetEvents::State(2, true));
+        assert_eq!(publishes.len(), 2);
+        assert_eq!(relay.state.states, vec![false, true]);
+        assert_eq!(relay.publishes().len(), 2);
+
+        let publishes = relay.apply_value(&multi_relay_state_prop_id(3), &HomieValue::Bool(true));
+        assert_eq!(publishes.len(), 1);
+        assert!(publishes[0].topic.ends_with("/switch/state-3"));
+        assert_eq!(relay.state.states, vec![false, true, true]);
+        assert!(
+            relay
+                .apply_value(&SWITCH_NODE_STATE_PROP_ID, &HomieValue::Bool(true))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn multi_relay_without_channels_is_rejected() {
+        let err = MultiRelayNodeConfig {
+            channels: 0,
+            ..Default::default()
+        }
+        .validate()
+        .expect_err("zero channels must be rejected");
+        assert_eq!(err.kind, ConfigErrorKind::NoChannels);
+        assert!(MultiRelayNodeConfig::default().validate().is_ok());
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use homie5::{
     Homie5DeviceProtocol, Homie5Message, HomieID, HomieValue, NodeRef, PropertyRef,
     device_description::{
@@ -5,8 +7,12 @@ use homie5::{
         PropertyDescriptionBuilder,
     },
 };
+use serde::{Deserialize, Serialize};
 
-use crate::{ParseError, ParseErrorKind, ParseOutcome, SMARTHOME_CAP_TEXT, SetCommandParser};
+use crate::{
+    ParseError, ParseErrorKind, ParseOutcome, PropertyMetadata, SMARTHOME_CAP_TEXT,
+    SetCommandParser, SmarthomePublisher, SmarthomeType, property_metadata, reject_readonly,
+};
 
 pub const TEXT_NODE_DEFAULT_ID: HomieID = HomieID::new_const("text");
 pub const TEXT_NODE_DEFAULT_NAME: &str = "Text";
@@ -14,15 +20,62 @@ pub const TEXT_NODE_TEXT_PROP_ID: HomieID = HomieID::new_const("text");
 
 // ── Node (state) ────────────────────────────────────────────────────────────
 
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TextNodeState {
+    pub text: String,
+}
+
 #[derive(Debug)]
 pub struct TextNode {
     pub publisher: TextNodePublisher,
-    pub text: String,
+    pub state: TextNodeState,
+}
+
+impl TextNode {
+    /// Apply a parsed set command and return the `$target` and value publishes.
+    pub fn apply_set(&mut self, event: TextNodeSetEvents) -> Vec<homie5::client::Publish> {
+        match event {
+            TextNodeSetEvents::Text(value) => {
+                let publishes = vec![
+                    self.publisher.text_target(&value),
+                    self.publisher.text(&value),
+                ];
+                self.state.text = value;
+                publishes
+            }
+        }
+    }
+
+    /// Apply a value reported by the device and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::String(value)) if p == &TEXT_NODE_TEXT_PROP_ID => {
+                self.state.text = value.clone();
+                vec![self.publisher.text(value)]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [Some(self.publisher.text(&self.state.text))]
+            .into_iter()
+            .flatten()
+            .collect()
+    }
 }
 
 // ── Set events ──────────────────────────────────────────────────────────────
 
 #[derive(Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 pub enum TextNodeSetEvents {
     Text(String),
 }
@@ -63,10 +116,23 @@ impl TextNodeBuilder {
         self
     }
 
+    /// Append a subtype suffix to the node type (`text-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::Text.with_subtype(subtype));
+        self
+    }
+
     pub fn build(self) -> HomieNodeDescription {
         self.node_builder.build()
     }
 
+    /// Ids of the properties the node contains.
+    pub fn property_ids() -> Vec<HomieID> {
+        Self::new().build().properties.into_keys().collect()
+    }
+
     pub fn build_with_publisher(
         self,
         node_id: HomieID,
@@ -92,13 +158,16 @@ impl TextNodeBuilder {
 pub struct TextNodePublisher {
     client: Homie5DeviceProtocol,
     node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
     text_prop: HomieID,
 }
 
 impl TextNodePublisher {
     pub fn new(node: NodeRef, client: Homie5DeviceProtocol) -> Self {
+        let metadata = property_metadata(&TextNodeBuilder::new().build());
         Self {
             node,
+            metadata,
             client,
             text_prop: TEXT_NODE_TEXT_PROP_ID,
         }
@@ -108,6 +177,29 @@ impl TextNodePublisher {
         self.client
             .publish_value(self.node.node_id(), &self.text_prop, value, true)
     }
+
+    pub fn text_target(&self, value: &str) -> homie5::client::Publish {
+        self.client
+            .publish_target(self.node.node_id(), &self.text_prop, value, true)
+    }
+}
+
+impl SmarthomePublisher for TextNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::Text
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![self.text_prop.clone()]
+    }
 }
 
 impl SetCommandParser for TextNodePublisher {
@@ -119,6 +211,10 @@ impl SetCommandParser for TextNodePublisher {
         desc: &HomieDeviceDescription,
         set_value: &str,
     ) -> ParseOutcome<Self::Event> {
+        if let Some(outcome) = reject_readonly(&self.node, property, desc, set_value) {
+            return outcome;
+        }
+
         let property_id = property.prop_id().to_string();
 
         if property.match_with_node(&self.node, &self.text_prop) {
@@ -1,3 +1,6 @@
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+
 use homie5::{
     HOMIE_UNIT_DEGREE_CELSIUS, HOMIE_UNIT_PERCENT, HOMIE_UNIT_SECONDS, Homie5DeviceProtocol,
     Homie5Message, Homie5ProtocolError, HomieID, HomieValue, NodeRef, PropertyRef,
@@ -8,30 +11,227 @@ use homie5::{
 };
 use serde::{Deserialize, Serialize};
 
-use crate::{ParseError, ParseErrorKind, ParseOutcome, SMARTHOME_CAP_THERMOSTAT, SetCommandParser};
+use crate::{
+    ConfigError, Decimal, ParseError, ParseErrorKind, ParseOutcome, PropertyMetadata,
+    SMARTHOME_CAP_THERMOSTAT, SetCommandParser, SmarthomePublisher, SmarthomeType,
+    property_metadata, readonly_properties, reject_readonly, validate_enum_format,
+};
 
 pub const THERMOSTAT_NODE_DEFAULT_ID: HomieID = HomieID::new_const("thermostat");
 pub const THERMOSTAT_NODE_DEFAULT_NAME: &str = "Thermostat";
 pub const THERMOSTAT_NODE_SET_TEMPERATURE_PROP_ID: HomieID = HomieID::new_const("set-temperature");
+pub const THERMOSTAT_NODE_HEAT_SETPOINT_PROP_ID: HomieID = HomieID::new_const("heat-setpoint");
+pub const THERMOSTAT_NODE_COOL_SETPOINT_PROP_ID: HomieID = HomieID::new_const("cool-setpoint");
 pub const THERMOSTAT_NODE_VALVE_PROP_ID: HomieID = HomieID::new_const("valve");
 pub const THERMOSTAT_NODE_MODE_PROP_ID: HomieID = HomieID::new_const("mode");
 pub const THERMOSTAT_NODE_WINDOWOPEN_PROP_ID: HomieID = HomieID::new_const("window-open");
 pub const THERMOSTAT_NODE_BOOST_STATE_PROP_ID: HomieID = HomieID::new_const("boost-state");
 pub const THERMOSTAT_NODE_BOOST_TIME_PROP_ID: HomieID = HomieID::new_const("boost-time");
+pub const THERMOSTAT_NODE_VACATION_PROP_ID: HomieID = HomieID::new_const("vacation");
+pub const THERMOSTAT_NODE_VACATION_UNTIL_PROP_ID: HomieID = HomieID::new_const("vacation-until");
+pub const THERMOSTAT_NODE_VACATION_TEMPERATURE_PROP_ID: HomieID =
+    HomieID::new_const("vacation-temperature");
 
-#[derive(Debug)]
-pub struct ThermostatNode {
-    pub publisher: ThermostatNodePublisher,
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThermostatNodeState {
     pub set_temperature: f64,
     pub set_temperature_target: f64,
+    pub heat_setpoint: Option<f64>,
+    pub cool_setpoint: Option<f64>,
     pub valve: Option<i64>,
     pub mode: Option<ThermostatNodeModes>,
     pub windowopen: Option<bool>,
     pub boost_state: Option<bool>,
     pub boost_time: Option<i64>,
+    pub vacation: Option<bool>,
+    pub vacation_until: Option<DateTime<Utc>>,
+    pub vacation_temperature: Option<f64>,
+}
+
+#[derive(Debug)]
+pub struct ThermostatNode {
+    pub publisher: ThermostatNodePublisher,
+    pub state: ThermostatNodeState,
+}
+
+impl ThermostatNode {
+    /// Apply a parsed set command and return the `$target` and value publishes.
+    /// Moving one of the dual setpoints into the deadband pushes the other
+    /// one along, so heating and cooling never overlap.
+    pub fn apply_set(&mut self, event: ThermostatNodeSetEvents) -> Vec<homie5::client::Publish> {
+        match event {
+            ThermostatNodeSetEvents::SetTemperature(value) => {
+                self.state.set_temperature_target = value;
+                self.state.set_temperature = value;
+                vec![
+                    self.publisher.set_temperature_target(value),
+                    self.publisher.set_temperature(value),
+                ]
+            }
+            ThermostatNodeSetEvents::HeatSetpoint(value) => {
+                self.state.heat_setpoint = Some(value);
+                let mut publishes = vec![
+                    self.publisher.heat_setpoint_target(value),
+                    self.publisher.heat_setpoint(value),
+                ];
+                let min_cool = value + self.publisher.config.deadband;
+                if self.state.cool_setpoint.is_some_and(|cool| cool < min_cool) {
+                    self.state.cool_setpoint = Some(min_cool);
+                    publishes.push(self.publisher.cool_setpoint_target(min_cool));
+                    publishes.push(self.publisher.cool_setpoint(min_cool));
+                }
+                publishes
+            }
+            ThermostatNodeSetEvents::CoolSetpoint(value) => {
+                self.state.cool_setpoint = Some(value);
+                let mut publishes = vec![
+                    self.publisher.cool_setpoint_target(value),
+                    self.publisher.cool_setpoint(value),
+                ];
+                let max_heat = value - self.publisher.config.deadband;
+                if self.state.heat_setpoint.is_some_and(|heat| heat > max_heat) {
+                    self.state.heat_setpoint = Some(max_heat);
+                    publishes.push(self.publisher.heat_setpoint_target(max_heat));
+                    publishes.push(self.publisher.heat_setpoint(max_heat));
+                }
+                publishes
+            }
+            ThermostatNodeSetEvents::Mode(mode) => {
+                self.state.mode = Some(mode);
+                vec![self.publisher.mode_target(mode), self.publisher.mode(mode)]
+            }
+            ThermostatNodeSetEvents::Boost(value) => {
+                self.state.boost_state = Some(value);
+                vec![self.publisher.boost(value)]
+            }
+            ThermostatNodeSetEvents::Vacation(value) => {
+                self.state.vacation = Some(value);
+                vec![
+                    self.publisher.vacation_target(value),
+                    self.publisher.vacation(value),
+                ]
+            }
+            ThermostatNodeSetEvents::VacationUntil(value) => {
+                self.state.vacation_until = Some(value);
+                vec![
+                    self.publisher.vacation_until_target(value),
+                    self.publisher.vacation_until(value),
+                ]
+            }
+            ThermostatNodeSetEvents::VacationTemperature(value) => {
+                self.state.vacation_temperature = Some(value);
+                vec![
+                    self.publisher.vacation_temperature_target(value),
+                    self.publisher.vacation_temperature(value),
+                ]
+            }
+        }
+    }
+
+    /// Apply a value reported by the device and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Float(value)) if p == &THERMOSTAT_NODE_SET_TEMPERATURE_PROP_ID => {
+                self.state.set_temperature = *value;
+                vec![self.publisher.set_temperature(*value)]
+            }
+            (p, HomieValue::Float(value)) if p == &THERMOSTAT_NODE_HEAT_SETPOINT_PROP_ID => {
+                self.state.heat_setpoint = Some(*value);
+                vec![self.publisher.heat_setpoint(*value)]
+            }
+            (p, HomieValue::Float(value)) if p == &THERMOSTAT_NODE_COOL_SETPOINT_PROP_ID => {
+                self.state.cool_setpoint = Some(*value);
+                vec![self.publisher.cool_setpoint(*value)]
+            }
+            (p, HomieValue::Enum(value)) if p == &THERMOSTAT_NODE_MODE_PROP_ID => {
+                let Ok(mode) = ThermostatNodeModes::try_from(value.as_str()) else {
+                    return Vec::new();
+                };
+                self.state.mode = Some(mode);
+                vec![self.publisher.mode(mode)]
+            }
+            (p, HomieValue::Integer(value)) if p == &THERMOSTAT_NODE_VALVE_PROP_ID => {
+                self.state.valve = Some(*value);
+                vec![self.publisher.valve(*value)]
+            }
+            (p, HomieValue::Bool(value)) if p == &THERMOSTAT_NODE_WINDOWOPEN_PROP_ID => {
+                self.state.windowopen = Some(*value);
+                vec![self.publisher.windowopen(*value)]
+            }
+            (p, HomieValue::Bool(value)) if p == &THERMOSTAT_NODE_BOOST_STATE_PROP_ID => {
+                self.state.boost_state = Some(*value);
+                vec![self.publisher.boost(*value)]
+            }
+            (p, HomieValue::Integer(value)) if p == &THERMOSTAT_NODE_BOOST_TIME_PROP_ID => {
+                self.state.boost_time = Some(*value);
+                vec![self.publisher.boost_time(*value)]
+            }
+            (p, HomieValue::Bool(value)) if p == &THERMOSTAT_NODE_VACATION_PROP_ID => {
+                self.state.vacation = Some(*value);
+                vec![self.publisher.vacation(*value)]
+            }
+            (p, HomieValue::DateTime(value)) if p == &THERMOSTAT_NODE_VACATION_UNTIL_PROP_ID => {
+                self.state.vacation_until = Some(*value);
+                vec![self.publisher.vacation_until(*value)]
+            }
+            (p, HomieValue::Float(value)) if p == &THERMOSTAT_NODE_VACATION_TEMPERATURE_PROP_ID => {
+                self.state.vacation_temperature = Some(*value);
+                vec![self.publisher.vacation_temperature(*value)]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        let single_setpoint = !self.publisher.config.dual_setpoint;
+        [
+            single_setpoint.then(|| self.publisher.set_temperature(self.state.set_temperature)),
+            single_setpoint.then(|| {
+                self.publisher
+                    .set_temperature_target(self.state.set_temperature_target)
+            }),
+            self.state
+                .heat_setpoint
+                .map(|value| self.publisher.heat_setpoint(value)),
+            self.state
+                .cool_setpoint
+                .map(|value| self.publisher.cool_setpoint(value)),
+            self.state.valve.map(|value| self.publisher.valve(value)),
+            self.state.mode.map(|value| self.publisher.mode(value)),
+            self.state
+                .windowopen
+                .map(|value| self.publisher.windowopen(value)),
+            self.state
+                .boost_state
+                .map(|value| self.publisher.boost(value)),
+            self.state
+                .boost_time
+                .map(|value| self.publisher.boost_time(value)),
+            self.state
+                .vacation
+                .map(|value| self.publisher.vacation(value)),
+            self.state
+                .vacation_until
+                .map(|value| self.publisher.vacation_until(value)),
+            self.state
+                .vacation_temperature
+                .map(|value| self.publisher.vacation_temperature(value)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
 }
 
 #[derive(Debug, Default, Copy, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 pub enum ThermostatNodeModes {
     #[default]
     Off,
@@ -110,13 +310,26 @@ impl TryFrom<&str> for ThermostatNodeModes {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 pub enum ThermostatNodeSetEvents {
     Mode(ThermostatNodeModes),
     SetTemperature(f64),
+    HeatSetpoint(f64),
+    CoolSetpoint(f64),
     Boost(bool),
+    Vacation(bool),
+    VacationUntil(
+        #[cfg_attr(
+            feature = "proptest",
+            proptest(strategy = "crate::arbitrary::date_time()")
+        )]
+        DateTime<Utc>,
+    ),
+    VacationTemperature(f64),
 }
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 #[serde(default)]
 pub struct ThermostatNodeConfig {
     pub unit: String,
@@ -125,8 +338,37 @@ pub struct ThermostatNodeConfig {
     pub boost_state: bool,
     pub boost_time: bool,
     pub mode: bool,
+    #[cfg_attr(
+        feature = "proptest",
+        proptest(strategy = "crate::arbitrary::distinct()")
+    )]
     pub modes: Vec<ThermostatNodeModes>,
+    #[cfg_attr(
+        feature = "proptest",
+        proptest(strategy = "crate::arbitrary::float_range()")
+    )]
     pub temp_range: FloatRange,
+    /// Replace `set-temperature` with separate `heat-setpoint` and
+    /// `cool-setpoint` properties for systems that both heat and cool.
+    pub dual_setpoint: bool,
+    /// Minimum distance kept between the heat and cool setpoint.
+    pub deadband: f64,
+    /// Decimal places of published temperatures and setpoints; `None`
+    /// publishes them unrounded.
+    #[cfg_attr(
+        feature = "proptest",
+        proptest(strategy = "crate::arbitrary::precision()")
+    )]
+    pub precision: Option<usize>,
+    /// Expose a holiday program: `vacation` switches it on, `vacation-until`
+    /// ends it and `vacation-temperature` is held while it is active.
+    pub vacation: bool,
+    /// Properties to publish as read-only; sets to them are rejected.
+    #[cfg_attr(
+        feature = "proptest",
+        proptest(strategy = "crate::arbitrary::homie_ids()")
+    )]
+    pub readonly: Vec<HomieID>,
 }
 
 impl Default for ThermostatNodeConfig {
@@ -144,12 +386,31 @@ impl Default for ThermostatNodeConfig {
                 max: Some(32.0),
                 step: Some(0.5),
             },
+            dual_setpoint: false,
+            deadband: 1.0,
+            precision: None,
+            vacation: false,
+            readonly: Vec::new(),
         }
     }
 }
 
+impl ThermostatNodeConfig {
+    /// Check the config-provided enum values before building the node.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.mode {
+            validate_enum_format(
+                &THERMOSTAT_NODE_MODE_PROP_ID,
+                self.modes.iter().map(<&str>::from),
+            )?;
+        }
+        Ok(())
+    }
+}
+
 pub struct ThermostatNodeBuilder {
     node_builder: NodeDescriptionBuilder,
+    config: ThermostatNodeConfig,
 }
 
 impl ThermostatNodeBuilder {
@@ -159,23 +420,41 @@ impl ThermostatNodeBuilder {
             config,
         )
         .r#type(SMARTHOME_CAP_THERMOSTAT);
+        let db = readonly_properties(db, &config.readonly);
 
-        Self { node_builder: db }
+        Self {
+            node_builder: db,
+            config: config.clone(),
+        }
     }
 
     fn build_node(
         db: NodeDescriptionBuilder,
         config: &ThermostatNodeConfig,
     ) -> NodeDescriptionBuilder {
-        db.add_property(
-            THERMOSTAT_NODE_SET_TEMPERATURE_PROP_ID,
+        let setpoint = |name: &str| {
             PropertyDescriptionBuilder::float()
-                .name("Set target temperature")
+                .name(name)
                 .float_range(config.temp_range.clone())
                 .unit(config.unit.to_owned())
                 .settable(true)
                 .retained(true)
-                .build(),
+                .build()
+        };
+        db.add_property_cond(
+            THERMOSTAT_NODE_SET_TEMPERATURE_PROP_ID,
+            !config.dual_setpoint,
+            || setpoint("Set target temperature"),
+        )
+        .add_property_cond(
+            THERMOSTAT_NODE_HEAT_SETPOINT_PROP_ID,
+            config.dual_setpoint,
+            || setpoint("Heating setpoint"),
+        )
+        .add_property_cond(
+            THERMOSTAT_NODE_COOL_SETPOINT_PROP_ID,
+            config.dual_setpoint,
+            || setpoint("Cooling setpoint"),
         )
         .add_property_cond(THERMOSTAT_NODE_VALVE_PROP_ID, config.valve, || {
             PropertyDescriptionBuilder::integer()
@@ -230,9 +509,32 @@ impl ThermostatNodeBuilder {
                     .build()
             },
         )
+        .add_property_cond(THERMOSTAT_NODE_VACATION_PROP_ID, config.vacation, || {
+            PropertyDescriptionBuilder::boolean()
+                .name("Vacation mode active")
+                .settable(true)
+                .retained(true)
+                .build()
+        })
+        .add_property_cond(
+            THERMOSTAT_NODE_VACATION_UNTIL_PROP_ID,
+            config.vacation,
+            || {
+                PropertyDescriptionBuilder::datetime()
+                    .name("Vacation end")
+                    .settable(true)
+                    .retained(true)
+                    .build()
+            },
+        )
+        .add_property_cond(
+            THERMOSTAT_NODE_VACATION_TEMPERATURE_PROP_ID,
+            config.vacation,
+            || setpoint("Vacation temperature"),
+        )
         .add_property_cond(THERMOSTAT_NODE_MODE_PROP_ID, config.mode, || {
             PropertyDescriptionBuilder::enumeration(
-                config.modes.iter().map(|m| <&str>::from(m)),
+                config.modes.iter().map(<&str>::from),
             )
             .unwrap()
             .name("Mode")
@@ -247,10 +549,23 @@ impl ThermostatNodeBuilder {
         self
     }
 
+    /// Append a subtype suffix to the node type (`thermostat-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::Thermostat.with_subtype(subtype));
+        self
+    }
+
     pub fn build(self) -> HomieNodeDescription {
         self.node_builder.build()
     }
 
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &ThermostatNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
     pub fn build_with_publisher(
         self,
         node_id: HomieID,
@@ -262,6 +577,7 @@ impl ThermostatNodeBuilder {
             ThermostatNodePublisher::new(
                 NodeRef::new(client.homie_domain().to_owned(), did, node_id),
                 client.clone(),
+                self.config,
             ),
         )
     }
@@ -286,6 +602,11 @@ mod tests {
                 max: Some(30.0),
                 step: Some(1.0),
             },
+            dual_setpoint: false,
+            deadband: 1.0,
+            precision: None,
+            vacation: false,
+            readonly: Vec::new(),
         }
     }
 
@@ -371,31 +692,145 @@ mod tests {
             HomiePropertyFormat::Enum(vec!["cool".to_string(), "heat".to_string()])
         );
     }
+
+    #[test]
+    fn dual_setpoints_keep_the_deadband() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), homie5::HomieDomain::Default);
+        let config = ThermostatNodeConfig {
+            dual_setpoint: true,
+            deadband: 2.0,
+            ..base_disabled_config()
+        };
+        let (desc, publisher) = ThermostatNodeBuilder::new(&config)
+            .build_with_publisher(THERMOSTAT_NODE_DEFAULT_ID, &client);
+        assert!(
+            !desc
+                .properties
+                .contains_key(&THERMOSTAT_NODE_SET_TEMPERATURE_PROP_ID)
+        );
+        assert!(
+            desc.properties
+                .contains_key(&THERMOSTAT_NODE_COOL_SETPOINT_PROP_ID)
+        );
+
+        let mut node = ThermostatNode {
+            publisher,
+            state: ThermostatNodeState {
+                heat_setpoint: Some(20.0),
+                cool_setpoint: Some(24.0),
+                ..Default::default()
+            },
+        };
+        assert_eq!(node.publishes().len(), 2);
+
+        assert_eq!(
+            node.apply_set(ThermostatNodeSetEvents::HeatSetpoint(21.0))
+                .len(),
+            2
+        );
+        assert_eq!(
+            node.apply_set(ThermostatNodeSetEvents::HeatSetpoint(23.0))
+                .len(),
+            4
+        );
+        assert_eq!(node.state.cool_setpoint, Some(25.0));
+
+        node.apply_set(ThermostatNodeSetEvents::CoolSetpoint(22.0));
+        assert_eq!(node.state.heat_setpoint, Some(20.0));
+    }
+
+    #[test]
+    fn precision_rounds_published_temperatures() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), homie5::HomieDomain::Default);
+        let config = ThermostatNodeConfig {
+            precision: Some(1),
+            ..base_disabled_config()
+        };
+        let (_, publisher) = ThermostatNodeBuilder::new(&config)
+            .build_with_publisher(THERMOSTAT_NODE_DEFAULT_ID, &client);
+
+        assert_eq!(publisher.set_temperature(21.2 + 0.1).payload, b"21.3");
+        assert_eq!(publisher.heat_setpoint_target(19.96).payload, b"20.0");
+    }
+
+    #[test]
+    fn vacation_properties_parse_into_dedicated_events() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), homie5::HomieDomain::Default);
+        let config = ThermostatNodeConfig {
+            vacation: true,
+            ..base_disabled_config()
+        };
+        let parser: crate::StandaloneSetParser<_> = ThermostatNodeBuilder::new(&config)
+            .build_with_publisher(THERMOSTAT_NODE_DEFAULT_ID, &client)
+            .into();
+        let node = parser.publisher().node_ref().clone();
+        let vacation = PropertyRef::from_node(node.clone(), THERMOSTAT_NODE_VACATION_PROP_ID);
+        let until = PropertyRef::from_node(node.clone(), THERMOSTAT_NODE_VACATION_UNTIL_PROP_ID);
+        let temperature =
+            PropertyRef::from_node(node, THERMOSTAT_NODE_VACATION_TEMPERATURE_PROP_ID);
+
+        assert!(matches!(
+            parser.parse_set(&vacation, "true"),
+            ParseOutcome::Parsed(ThermostatNodeSetEvents::Vacation(true))
+        ));
+        let ParseOutcome::Parsed(ThermostatNodeSetEvents::VacationUntil(end)) =
+            parser.parse_set(&until, "2026-08-16T18:00:00Z")
+        else {
+            panic!("vacation-until must parse");
+        };
+        assert_eq!(end.to_rfc3339(), "2026-08-16T18:00:00+00:00");
+        assert!(matches!(
+            parser.parse_set(&temperature, "16"),
+            ParseOutcome::Parsed(ThermostatNodeSetEvents::VacationTemperature(16.0))
+        ));
+        assert!(matches!(
+            parser.parse_set(&temperature, "40"),
+            ParseOutcome::Invalid(_)
+        ));
+    }
 }
 
 #[derive(Debug)]
 pub struct ThermostatNodePublisher {
     client: Homie5DeviceProtocol,
     node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
+    config: ThermostatNodeConfig,
     set_temperature_prop: HomieID,
+    heat_setpoint_prop: HomieID,
+    cool_setpoint_prop: HomieID,
     boost_prop: HomieID,
     boost_time_prop: HomieID,
     mode_prop: HomieID,
     valve_prop: HomieID,
     windowopen_prop: HomieID,
+    vacation_prop: HomieID,
+    vacation_until_prop: HomieID,
+    vacation_temperature_prop: HomieID,
 }
 
 impl ThermostatNodePublisher {
-    pub fn new(node: NodeRef, client: Homie5DeviceProtocol) -> Self {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: ThermostatNodeConfig) -> Self {
+        let metadata = property_metadata(&ThermostatNodeBuilder::new(&config).build());
         Self {
             node,
+            metadata,
             client,
+            config,
             mode_prop: THERMOSTAT_NODE_MODE_PROP_ID,
             boost_prop: THERMOSTAT_NODE_BOOST_STATE_PROP_ID,
             boost_time_prop: THERMOSTAT_NODE_BOOST_TIME_PROP_ID,
             valve_prop: THERMOSTAT_NODE_VALVE_PROP_ID,
             windowopen_prop: THERMOSTAT_NODE_WINDOWOPEN_PROP_ID,
             set_temperature_prop: THERMOSTAT_NODE_SET_TEMPERATURE_PROP_ID,
+            heat_setpoint_prop: THERMOSTAT_NODE_HEAT_SETPOINT_PROP_ID,
+            cool_setpoint_prop: THERMOSTAT_NODE_COOL_SETPOINT_PROP_ID,
+            vacation_prop: THERMOSTAT_NODE_VACATION_PROP_ID,
+            vacation_until_prop: THERMOSTAT_NODE_VACATION_UNTIL_PROP_ID,
+            vacation_temperature_prop: THERMOSTAT_NODE_VACATION_TEMPERATURE_PROP_ID,
         }
     }
 
@@ -403,7 +838,7 @@ impl ThermostatNodePublisher {
         self.client.publish_value(
             self.node.node_id(),
             &self.set_temperature_prop,
-            value.to_string(),
+            self.format_temperature(value),
             true,
         )
     }
@@ -412,7 +847,43 @@ impl ThermostatNodePublisher {
         self.client.publish_target(
             self.node.node_id(),
             &self.set_temperature_prop,
-            value.to_string(),
+            self.format_temperature(value),
+            true,
+        )
+    }
+
+    pub fn heat_setpoint(&self, value: f64) -> homie5::client::Publish {
+        self.client.publish_value(
+            self.node.node_id(),
+            &self.heat_setpoint_prop,
+            self.format_temperature(value),
+            true,
+        )
+    }
+
+    pub fn heat_setpoint_target(&self, value: f64) -> homie5::client::Publish {
+        self.client.publish_target(
+            self.node.node_id(),
+            &self.heat_setpoint_prop,
+            self.format_temperature(value),
+            true,
+        )
+    }
+
+    pub fn cool_setpoint(&self, value: f64) -> homie5::client::Publish {
+        self.client.publish_value(
+            self.node.node_id(),
+            &self.cool_setpoint_prop,
+            self.format_temperature(value),
+            true,
+        )
+    }
+
+    pub fn cool_setpoint_target(&self, value: f64) -> homie5::client::Publish {
+        self.client.publish_target(
+            self.node.node_id(),
+            &self.cool_setpoint_prop,
+            self.format_temperature(value),
             true,
         )
     }
@@ -436,6 +907,15 @@ impl ThermostatNodePublisher {
         )
     }
 
+    pub fn boost_target(&self, value: bool) -> homie5::client::Publish {
+        self.client.publish_target(
+            self.node.node_id(),
+            &self.boost_prop,
+            value.to_string(),
+            true,
+        )
+    }
+
     pub fn boost_time(&self, value: i64) -> homie5::client::Publish {
         self.client.publish_value(
             self.node.node_id(),
@@ -462,6 +942,94 @@ impl ThermostatNodePublisher {
             true,
         )
     }
+
+    pub fn vacation(&self, value: bool) -> homie5::client::Publish {
+        self.client.publish_value(
+            self.node.node_id(),
+            &self.vacation_prop,
+            value.to_string(),
+            true,
+        )
+    }
+
+    pub fn vacation_target(&self, value: bool) -> homie5::client::Publish {
+        self.client.publish_target(
+            self.node.node_id(),
+            &self.vacation_prop,
+            value.to_string(),
+            true,
+        )
+    }
+
+    pub fn vacation_until(&self, value: DateTime<Utc>) -> homie5::client::Publish {
+        self.client.publish_value(
+            self.node.node_id(),
+            &self.vacation_until_prop,
+            HomieValue::DateTime(value),
+            true,
+        )
+    }
+
+    pub fn vacation_until_target(&self, value: DateTime<Utc>) -> homie5::client::Publish {
+        self.client.publish_target(
+            self.node.node_id(),
+            &self.vacation_until_prop,
+            HomieValue::DateTime(value),
+            true,
+        )
+    }
+
+    pub fn vacation_temperature(&self, value: f64) -> homie5::client::Publish {
+        self.client.publish_value(
+            self.node.node_id(),
+            &self.vacation_temperature_prop,
+            self.format_temperature(value),
+            true,
+        )
+    }
+
+    pub fn vacation_temperature_target(&self, value: f64) -> homie5::client::Publish {
+        self.client.publish_target(
+            self.node.node_id(),
+            &self.vacation_temperature_prop,
+            self.format_temperature(value),
+            true,
+        )
+    }
+
+    fn format_temperature(&self, value: f64) -> String {
+        Decimal::new(value, self.config.precision).to_string()
+    }
+}
+
+impl SmarthomePublisher for ThermostatNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::Thermostat
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![
+            self.set_temperature_prop.clone(),
+            self.heat_setpoint_prop.clone(),
+            self.cool_setpoint_prop.clone(),
+            self.boost_prop.clone(),
+            self.boost_time_prop.clone(),
+            self.mode_prop.clone(),
+            self.valve_prop.clone(),
+            self.windowopen_prop.clone(),
+            self.vacation_prop.clone(),
+            self.vacation_until_prop.clone(),
+            self.vacation_temperature_prop.clone(),
+        ]
+    }
 }
 
 impl SetCommandParser for ThermostatNodePublisher {
@@ -473,6 +1041,10 @@ impl SetCommandParser for ThermostatNodePublisher {
         desc: &HomieDeviceDescription,
         set_value: &str,
     ) -> ParseOutcome<Self::Event> {
+        if let Some(outcome) = reject_readonly(&self.node, property, desc, set_value) {
+            return outcome;
+        }
+
         let property_id = property.prop_id().to_string();
 
         if property.match_with_node(&self.node, &self.set_temperature_prop) {
@@ -496,6 +1068,32 @@ impl SetCommandParser for ThermostatNodePublisher {
                     ParseErrorKind::InvalidHomieValue,
                 )),
             }
+        } else if property.match_with_node(&self.node, &self.heat_setpoint_prop)
+            || property.match_with_node(&self.node, &self.cool_setpoint_prop)
+        {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Float(value)) if property.prop_id() == &self.heat_setpoint_prop => {
+                    ParseOutcome::Parsed(ThermostatNodeSetEvents::HeatSetpoint(value))
+                }
+                Ok(HomieValue::Float(value)) => {
+                    ParseOutcome::Parsed(ThermostatNodeSetEvents::CoolSetpoint(value))
+                }
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
         } else if property.match_with_node(&self.node, &self.mode_prop) {
             let Some(parsed) = desc.with_property(property, |prop_desc| {
                 HomieValue::parse(set_value, prop_desc)
@@ -543,6 +1141,36 @@ impl SetCommandParser for ThermostatNodePublisher {
                     ParseErrorKind::InvalidHomieValue,
                 )),
             }
+        } else if property.match_with_node(&self.node, &self.vacation_prop)
+            || property.match_with_node(&self.node, &self.vacation_until_prop)
+            || property.match_with_node(&self.node, &self.vacation_temperature_prop)
+        {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Bool(value)) => {
+                    ParseOutcome::Parsed(ThermostatNodeSetEvents::Vacation(value))
+                }
+                Ok(HomieValue::DateTime(value)) => {
+                    ParseOutcome::Parsed(ThermostatNodeSetEvents::VacationUntil(value))
+                }
+                Ok(HomieValue::Float(value)) => {
+                    ParseOutcome::Parsed(ThermostatNodeSetEvents::VacationTemperature(value))
+                }
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
         } else {
             ParseOutcome::NoMatch
         }
@@ -1,41 +1,153 @@
+use std::collections::BTreeMap;
+
 use homie5::{
-    Homie5DeviceProtocol, HomieID, NodeRef,
+    HOMIE_UNIT_DEGREE, Homie5DeviceProtocol, Homie5Message, HomieID, HomieValue, NodeRef,
+    PropertyRef,
     device_description::{
-        HomieNodeDescription, NodeDescriptionBuilder, PropertyDescriptionBuilder,
+        HomieDeviceDescription, HomieNodeDescription, IntegerRange, NodeDescriptionBuilder,
+        PropertyDescriptionBuilder,
     },
 };
+use serde::{Deserialize, Serialize};
 
-use crate::SMARTHOME_CAP_TILT;
+use crate::{
+    ParseError, ParseErrorKind, ParseOutcome, PropertyMetadata, SMARTHOME_CAP_TILT,
+    SetCommandParser, SmarthomePublisher, SmarthomeType, property_metadata, readonly_properties,
+    reject_readonly,
+};
 
 pub const TILT_NODE_DEFAULT_ID: HomieID = HomieID::new_const("tilt");
 pub const TILT_NODE_DEFAULT_NAME: &str = "Tilt sensor";
 pub const TILT_NODE_STATE_PROP_ID: HomieID = HomieID::new_const("state");
+pub const TILT_NODE_ANGLE_PROP_ID: HomieID = HomieID::new_const("angle");
+pub const TILT_NODE_CALIBRATION_OFFSET_PROP_ID: HomieID = HomieID::new_const("calibration-offset");
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TiltNodeState {
+    pub state: bool,
+    pub angle: Option<i64>,
+    pub calibration_offset: Option<i64>,
+}
 
 #[derive(Debug)]
 pub struct TiltNode {
     pub publisher: TiltNodePublisher,
-    pub state: bool,
+    pub state: TiltNodeState,
+}
+
+impl TiltNode {
+    /// Apply a parsed set command and return the `$target` and value publishes
+    /// for the change.
+    pub fn apply_set(&mut self, event: TiltNodeSetEvents) -> Vec<homie5::client::Publish> {
+        match event {
+            TiltNodeSetEvents::CalibrationOffset(value) => {
+                self.state.calibration_offset = Some(value);
+                [
+                    self.publisher.calibration_offset_target(value),
+                    self.publisher.calibration_offset(value),
+                ]
+                .into_iter()
+                .flatten()
+                .collect()
+            }
+        }
+    }
+
+    /// Apply a value reported by the device and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Bool(value)) if p == &TILT_NODE_STATE_PROP_ID => {
+                self.state.state = *value;
+                vec![self.publisher.state(*value)]
+            }
+            (p, HomieValue::Integer(value)) if p == &TILT_NODE_ANGLE_PROP_ID => {
+                self.state.angle = Some(*value);
+                self.publisher.angle(*value).into_iter().collect()
+            }
+            (p, HomieValue::Integer(value)) if p == &TILT_NODE_CALIBRATION_OFFSET_PROP_ID => {
+                self.state.calibration_offset = Some(*value);
+                self.publisher
+                    .calibration_offset(*value)
+                    .into_iter()
+                    .collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            Some(self.publisher.state(self.state.state)),
+            self.state
+                .angle
+                .and_then(|value| self.publisher.angle(value)),
+            self.state
+                .calibration_offset
+                .and_then(|value| self.publisher.calibration_offset(value)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+pub enum TiltNodeSetEvents {
+    CalibrationOffset(i64),
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+#[serde(default)]
+pub struct TiltNodeConfig {
+    /// Expose the tilt angle in degrees in addition to the binary state.
+    pub angle: bool,
+    /// Expose a settable `calibration-offset` in degrees to compensate the
+    /// mounting orientation.
+    pub calibration_offset: bool,
+    /// Properties to publish as read-only; sets to them are rejected.
+    #[cfg_attr(
+        feature = "proptest",
+        proptest(strategy = "crate::arbitrary::homie_ids()")
+    )]
+    pub readonly: Vec<HomieID>,
 }
 
 pub struct TiltNodeBuilder {
     node_builder: NodeDescriptionBuilder,
+    config: TiltNodeConfig,
 }
 
 impl Default for TiltNodeBuilder {
     fn default() -> Self {
-        let db = Self::build_node(NodeDescriptionBuilder::new().name(TILT_NODE_DEFAULT_NAME))
-            .r#type(SMARTHOME_CAP_TILT);
-
-        Self { node_builder: db }
+        Self::new(&TiltNodeConfig::default())
     }
 }
 
 impl TiltNodeBuilder {
-    pub fn new() -> Self {
-        Default::default()
+    pub fn new(config: &TiltNodeConfig) -> Self {
+        let db = Self::build_node(
+            NodeDescriptionBuilder::new().name(TILT_NODE_DEFAULT_NAME),
+            config,
+        )
+        .r#type(SMARTHOME_CAP_TILT);
+        let db = readonly_properties(db, &config.readonly);
+
+        Self {
+            node_builder: db,
+            config: config.clone(),
+        }
     }
 
-    fn build_node(db: NodeDescriptionBuilder) -> NodeDescriptionBuilder {
+    fn build_node(db: NodeDescriptionBuilder, config: &TiltNodeConfig) -> NodeDescriptionBuilder {
         db.add_property(
             TILT_NODE_STATE_PROP_ID,
             PropertyDescriptionBuilder::boolean()
@@ -45,6 +157,31 @@ impl TiltNodeBuilder {
                 .retained(true)
                 .build(),
         )
+        .add_property_cond(TILT_NODE_ANGLE_PROP_ID, config.angle, || {
+            PropertyDescriptionBuilder::integer()
+                .name("Tilt angle")
+                .unit(HOMIE_UNIT_DEGREE)
+                .settable(false)
+                .retained(true)
+                .build()
+        })
+        .add_property_cond(
+            TILT_NODE_CALIBRATION_OFFSET_PROP_ID,
+            config.calibration_offset,
+            || {
+                PropertyDescriptionBuilder::integer()
+                    .name("Calibration offset")
+                    .unit(HOMIE_UNIT_DEGREE)
+                    .integer_range(IntegerRange {
+                        min: Some(-180),
+                        max: Some(180),
+                        step: None,
+                    })
+                    .settable(true)
+                    .retained(true)
+                    .build()
+            },
+        )
     }
 
     pub fn name<S: Into<String>>(mut self, name: impl Into<Option<S>>) -> Self {
@@ -52,10 +189,23 @@ impl TiltNodeBuilder {
         self
     }
 
+    /// Append a subtype suffix to the node type (`tilt-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::Tilt.with_subtype(subtype));
+        self
+    }
+
     pub fn build(self) -> HomieNodeDescription {
         self.node_builder.build()
     }
 
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &TiltNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
     pub fn build_with_publisher(
         self,
         node_id: HomieID,
@@ -70,6 +220,7 @@ impl TiltNodeBuilder {
                     node_id,
                 ),
                 client.clone(),
+                self.config,
             ),
         )
     }
@@ -79,15 +230,24 @@ impl TiltNodeBuilder {
 pub struct TiltNodePublisher {
     client: Homie5DeviceProtocol,
     node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
+    config: TiltNodeConfig,
     state_prop: HomieID,
+    angle_prop: HomieID,
+    calibration_offset_prop: HomieID,
 }
 
 impl TiltNodePublisher {
-    pub fn new(node: NodeRef, client: Homie5DeviceProtocol) -> Self {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: TiltNodeConfig) -> Self {
+        let metadata = property_metadata(&TiltNodeBuilder::new(&config).build());
         Self {
             node,
+            metadata,
             client,
+            config,
             state_prop: TILT_NODE_STATE_PROP_ID,
+            angle_prop: TILT_NODE_ANGLE_PROP_ID,
+            calibration_offset_prop: TILT_NODE_CALIBRATION_OFFSET_PROP_ID,
         }
     }
 
@@ -99,4 +259,169 @@ impl TiltNodePublisher {
             true,
         )
     }
+
+    pub fn angle(&self, value: i64) -> Option<homie5::client::Publish> {
+        if !self.config.angle {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.angle_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+
+    pub fn calibration_offset(&self, value: i64) -> Option<homie5::client::Publish> {
+        if !self.config.calibration_offset {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.calibration_offset_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+
+    pub fn calibration_offset_target(&self, value: i64) -> Option<homie5::client::Publish> {
+        if !self.config.calibration_offset {
+            return None;
+        }
+        Some(self.client.publish_target(
+            self.node.node_id(),
+            &self.calibration_offset_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+}
+
+impl SmarthomePublisher for TiltNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::Tilt
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![
+            self.state_prop.clone(),
+            self.angle_prop.clone(),
+            self.calibration_offset_prop.clone(),
+        ]
+    }
+}
+
+impl SetCommandParser for TiltNodePublisher {
+    type Event = TiltNodeSetEvents;
+
+    fn parse_set(
+        &self,
+        property: &PropertyRef,
+        desc: &HomieDeviceDescription,
+        set_value: &str,
+    ) -> ParseOutcome<Self::Event> {
+        if let Some(outcome) = reject_readonly(&self.node, property, desc, set_value) {
+            return outcome;
+        }
+
+        let property_id = property.prop_id().to_string();
+
+        if property.match_with_node(&self.node, &self.calibration_offset_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Integer(value)) => {
+                    ParseOutcome::Parsed(TiltNodeSetEvents::CalibrationOffset(value))
+                }
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else {
+            ParseOutcome::NoMatch
+        }
+    }
+
+    fn parse_set_event(
+        &self,
+        desc: &HomieDeviceDescription,
+        event: &Homie5Message,
+    ) -> ParseOutcome<Self::Event> {
+        match event {
+            Homie5Message::PropertySet {
+                property,
+                set_value,
+            } => self.parse_set(property, desc, set_value),
+            _ => ParseOutcome::Invalid(ParseError::new(
+                self.calibration_offset_prop.to_string(),
+                "",
+                ParseErrorKind::UnexpectedMessageType,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use homie5::HomieDomain;
+
+    use super::*;
+    use crate::StandaloneSetParser;
+
+    #[test]
+    fn calibration_offset_parses_within_range() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let config = TiltNodeConfig {
+            angle: true,
+            calibration_offset: true,
+            ..TiltNodeConfig::default()
+        };
+        let parser: StandaloneSetParser<_> = TiltNodeBuilder::new(&config)
+            .build_with_publisher(TILT_NODE_DEFAULT_ID, &client)
+            .into();
+        let node = parser.publisher().node_ref().clone();
+        let offset = PropertyRef::from_node(node.clone(), TILT_NODE_CALIBRATION_OFFSET_PROP_ID);
+        let angle = PropertyRef::from_node(node, TILT_NODE_ANGLE_PROP_ID);
+
+        let ParseOutcome::Parsed(event) = parser.parse_set(&offset, "-12") else {
+            panic!("offset within range must parse");
+        };
+        assert!(matches!(
+            parser.parse_set(&offset, "200"),
+            ParseOutcome::Invalid(_)
+        ));
+        assert!(matches!(
+            parser.parse_set(&angle, "5"),
+            ParseOutcome::Invalid(ParseError {
+                kind: ParseErrorKind::NotSettable,
+                ..
+            })
+        ));
+
+        let mut node = TiltNode {
+            publisher: parser.into_publisher(),
+            state: TiltNodeState::default(),
+        };
+        assert_eq!(node.apply_set(event).len(), 2);
+        assert_eq!(node.state.calibration_offset, Some(-12));
+    }
 }
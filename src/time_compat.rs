@@ -0,0 +1,97 @@
+//! Conversions between the [`time`] crate and the `chrono` types used for
+//! datetime and duration properties, e.g. the maintenance `last-update`, the
+//! thermostat `vacation-until` or the timer durations.
+//!
+//! homie5 represents these values with `chrono`, so node states, set events
+//! and publishers keep using it; projects that have standardized on `time`
+//! convert at the boundary:
+//!
+//! ```
+//! use hc_homie5_smarthome::time_compat::{from_chrono, to_chrono};
+//! use time::macros::datetime;
+//!
+//! let until = datetime!(2026-08-16 18:00 UTC);
+//! let chrono = to_chrono(until).unwrap();
+//! assert_eq!(chrono.to_rfc3339(), "2026-08-16T18:00:00+00:00");
+//! assert_eq!(from_chrono(chrono), Some(until));
+//! ```
+
+use chrono::{DateTime, TimeDelta, Utc};
+use time::OffsetDateTime;
+
+use crate::Clock;
+
+/// Convert a `chrono` timestamp, e.g. from a node state or set event, into an
+/// [`OffsetDateTime`] in UTC. Returns `None` outside the range `time` supports.
+pub fn from_chrono(value: DateTime<Utc>) -> Option<OffsetDateTime> {
+    OffsetDateTime::from_unix_timestamp(value.timestamp())
+        .ok()?
+        .replace_nanosecond(value.timestamp_subsec_nanos())
+        .ok()
+}
+
+/// Convert an [`OffsetDateTime`] into the `chrono` timestamp publishers take.
+/// Returns `None` outside the range `chrono` supports.
+pub fn to_chrono(value: OffsetDateTime) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp(value.unix_timestamp(), value.nanosecond())
+}
+
+/// Convert a `chrono` duration into a [`time::Duration`].
+pub fn duration_from_chrono(value: TimeDelta) -> time::Duration {
+    time::Duration::new(value.num_seconds(), value.subsec_nanos())
+}
+
+/// Convert a [`time::Duration`] into a `chrono` duration. Returns `None` if it
+/// exceeds the range of `chrono`.
+pub fn duration_to_chrono(value: time::Duration) -> Option<TimeDelta> {
+    let (seconds, nanos) = match value.subsec_nanoseconds() {
+        nanos if nanos < 0 => (value.whole_seconds() - 1, nanos + 1_000_000_000),
+        nanos => (value.whole_seconds(), nanos),
+    };
+    TimeDelta::new(seconds, nanos as u32)
+}
+
+/// A [`Clock`] backed by a closure returning [`OffsetDateTime`], e.g.
+/// `TimeClock(OffsetDateTime::now_utc)`. Times `chrono` cannot represent fall
+/// back to the system clock.
+#[derive(Debug, Clone, Copy)]
+pub struct TimeClock<F>(pub F);
+
+impl<F> Clock for TimeClock<F>
+where
+    F: Fn() -> OffsetDateTime + Send + Sync,
+{
+    fn now(&self) -> DateTime<Utc> {
+        to_chrono((self.0)()).unwrap_or_else(Utc::now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use super::*;
+
+    #[test]
+    fn timestamps_and_durations_round_trip() {
+        let value = datetime!(2026-03-01 12:00:00.123_456_789 UTC);
+        let chrono = to_chrono(value).expect("timestamp must fit into chrono");
+        assert_eq!(chrono.to_rfc3339(), "2026-03-01T12:00:00.123456789+00:00");
+        assert_eq!(from_chrono(chrono), Some(value));
+        assert_eq!(from_chrono(DateTime::<Utc>::MAX_UTC), None);
+
+        for duration in [
+            time::Duration::milliseconds(1500),
+            time::Duration::milliseconds(-1500),
+        ] {
+            let chrono = duration_to_chrono(duration).expect("duration must fit into chrono");
+            assert_eq!(
+                chrono.num_milliseconds(),
+                duration.whole_milliseconds() as i64
+            );
+            assert_eq!(duration_from_chrono(chrono), duration);
+        }
+
+        assert_eq!(TimeClock(move || value).now(), chrono);
+    }
+}
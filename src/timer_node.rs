@@ -1,5 +1,5 @@
 use core::fmt;
-use std::str::FromStr;
+use std::{collections::BTreeMap, str::FromStr};
 
 use chrono::prelude::*;
 
@@ -13,7 +13,11 @@ use homie5::{
 };
 use serde::{Deserialize, Serialize};
 
-use crate::{ParseError, ParseErrorKind, ParseOutcome, SMARTHOME_CAP_TIMER, SetCommandParser};
+use crate::{
+    ParseError, ParseErrorKind, ParseOutcome, PropertyMetadata, SMARTHOME_CAP_TIMER,
+    SetCommandParser, SmarthomePublisher, SmarthomeType, property_metadata, readonly_properties,
+    reject_readonly,
+};
 
 pub const TIMER_NODE_DEFAULT_ID: HomieID = HomieID::new_const("timer");
 pub const TIMER_NODE_DEFAULT_NAME: &str = "Timer";
@@ -27,11 +31,13 @@ pub const TIMER_NODE_CREATED_PROP_ID: HomieID = HomieID::new_const("created");
 
 // ── Timer state ─────────────────────────────────────────────────────────────
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum TimerState {
     Running,
     Paused,
     Fired,
+    #[default]
     Cancelled,
 }
 
@@ -59,9 +65,23 @@ impl fmt::Display for TimerState {
     }
 }
 
+impl FromStr for TimerState {
+    type Err = Homie5ProtocolError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "running" => Ok(Self::Running),
+            "paused" => Ok(Self::Paused),
+            "fired" => Ok(Self::Fired),
+            "cancelled" => Ok(Self::Cancelled),
+            _ => Err(Homie5ProtocolError::InvalidPayload),
+        }
+    }
+}
+
 // ── Timer action ────────────────────────────────────────────────────────────
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 pub enum TimerAction {
     Start,
     Pause,
@@ -108,9 +128,9 @@ impl FromStr for TimerAction {
 
 // ── Node (state) ────────────────────────────────────────────────────────────
 
-#[derive(Debug)]
-pub struct TimerNode {
-    pub publisher: TimerNodePublisher,
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimerNodeState {
     pub state: TimerState,
     pub label: Option<String>,
     pub duration: i64,
@@ -119,9 +139,113 @@ pub struct TimerNode {
     pub created: Option<DateTime<Utc>>,
 }
 
+#[derive(Debug)]
+pub struct TimerNode {
+    pub publisher: TimerNodePublisher,
+    pub state: TimerNodeState,
+}
+
+impl TimerNode {
+    /// Apply a parsed set command and return the resulting publishes. Actions
+    /// update the timer state; `remaining` and the timestamps are left to the
+    /// device to report.
+    pub fn apply_set(&mut self, event: TimerNodeSetEvents) -> Vec<homie5::client::Publish> {
+        match event {
+            TimerNodeSetEvents::Action(action) => {
+                let state = match action {
+                    TimerAction::Start | TimerAction::Resume => TimerState::Running,
+                    TimerAction::Pause => TimerState::Paused,
+                    TimerAction::Cancel => TimerState::Cancelled,
+                };
+                self.state.state = state;
+                vec![self.publisher.state(state)]
+            }
+            TimerNodeSetEvents::Label(value) => {
+                let publishes = vec![
+                    self.publisher.label_target(&value),
+                    self.publisher.label(&value),
+                ];
+                self.state.label = Some(value);
+                publishes
+            }
+            TimerNodeSetEvents::Duration(value) => {
+                self.state.duration = value;
+                vec![
+                    self.publisher.duration_target(value),
+                    self.publisher.duration(value),
+                ]
+            }
+        }
+    }
+
+    /// Apply a value reported by the device and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Enum(value)) if p == &TIMER_NODE_STATE_PROP_ID => {
+                let Ok(state) = value.parse::<TimerState>() else {
+                    return Vec::new();
+                };
+                self.state.state = state;
+                vec![self.publisher.state(state)]
+            }
+            (p, HomieValue::String(value)) if p == &TIMER_NODE_LABEL_PROP_ID => {
+                self.state.label = Some(value.clone());
+                vec![self.publisher.label(value)]
+            }
+            (p, HomieValue::Integer(value)) if p == &TIMER_NODE_DURATION_PROP_ID => {
+                self.state.duration = *value;
+                vec![self.publisher.duration(*value)]
+            }
+            (p, HomieValue::Integer(value)) if p == &TIMER_NODE_REMAINING_PROP_ID => {
+                self.state.remaining = Some(*value);
+                vec![self.publisher.remaining(*value)]
+            }
+            (p, HomieValue::DateTime(value)) if p == &TIMER_NODE_TRIGGER_TIME_PROP_ID => {
+                self.state.trigger_time = Some(*value);
+                vec![self.publisher.trigger_time(*value)]
+            }
+            (p, HomieValue::DateTime(value)) if p == &TIMER_NODE_CREATED_PROP_ID => {
+                self.state.created = Some(*value);
+                vec![self.publisher.created(*value)]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            Some(self.publisher.state(self.state.state)),
+            self.state
+                .label
+                .as_deref()
+                .map(|value| self.publisher.label(value)),
+            Some(self.publisher.duration(self.state.duration)),
+            self.state
+                .remaining
+                .map(|value| self.publisher.remaining(value)),
+            self.state
+                .trigger_time
+                .map(|value| self.publisher.trigger_time(value)),
+            self.state
+                .created
+                .map(|value| self.publisher.created(value)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
 // ── Set events ──────────────────────────────────────────────────────────────
 
 #[derive(Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 pub enum TimerNodeSetEvents {
     Action(TimerAction),
     Label(String),
@@ -131,12 +255,19 @@ pub enum TimerNodeSetEvents {
 // ── Config ──────────────────────────────────────────────────────────────────
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 #[serde(default)]
 pub struct TimerNodeConfig {
     pub label: bool,
     pub remaining: bool,
     pub trigger_time: bool,
     pub created: bool,
+    /// Properties to publish as read-only; sets to them are rejected.
+    #[cfg_attr(
+        feature = "proptest",
+        proptest(strategy = "crate::arbitrary::homie_ids()")
+    )]
+    pub readonly: Vec<HomieID>,
 }
 
 impl Default for TimerNodeConfig {
@@ -146,6 +277,7 @@ impl Default for TimerNodeConfig {
             remaining: true,
             trigger_time: true,
             created: true,
+            readonly: Vec::new(),
         }
     }
 }
@@ -154,6 +286,7 @@ impl Default for TimerNodeConfig {
 
 pub struct TimerNodeBuilder {
     node_builder: NodeDescriptionBuilder,
+    config: TimerNodeConfig,
 }
 
 impl TimerNodeBuilder {
@@ -163,8 +296,12 @@ impl TimerNodeBuilder {
             config,
         )
         .r#type(SMARTHOME_CAP_TIMER);
+        let db = readonly_properties(db, &config.readonly);
 
-        Self { node_builder: db }
+        Self {
+            node_builder: db,
+            config: config.clone(),
+        }
     }
 
     fn build_node(db: NodeDescriptionBuilder, config: &TimerNodeConfig) -> NodeDescriptionBuilder {
@@ -241,10 +378,23 @@ impl TimerNodeBuilder {
         self
     }
 
+    /// Append a subtype suffix to the node type (`timer-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::Timer.with_subtype(subtype));
+        self
+    }
+
     pub fn build(self) -> HomieNodeDescription {
         self.node_builder.build()
     }
 
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &TimerNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
     pub fn build_with_publisher(
         self,
         node_id: HomieID,
@@ -259,6 +409,7 @@ impl TimerNodeBuilder {
                     node_id,
                 ),
                 client.clone(),
+                &self.config,
             ),
         )
     }
@@ -270,6 +421,7 @@ impl TimerNodeBuilder {
 pub struct TimerNodePublisher {
     client: Homie5DeviceProtocol,
     node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
     state_prop: HomieID,
     action_prop: HomieID,
     label_prop: HomieID,
@@ -280,9 +432,11 @@ pub struct TimerNodePublisher {
 }
 
 impl TimerNodePublisher {
-    pub fn new(node: NodeRef, client: Homie5DeviceProtocol) -> Self {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: &TimerNodeConfig) -> Self {
+        let metadata = property_metadata(&TimerNodeBuilder::new(config).build());
         Self {
             node,
+            metadata,
             client,
             state_prop: TIMER_NODE_STATE_PROP_ID,
             action_prop: TIMER_NODE_ACTION_PROP_ID,
@@ -304,6 +458,11 @@ impl TimerNodePublisher {
             .publish_value(self.node.node_id(), &self.label_prop, value, true)
     }
 
+    pub fn label_target(&self, value: &str) -> homie5::client::Publish {
+        self.client
+            .publish_target(self.node.node_id(), &self.label_prop, value, true)
+    }
+
     pub fn duration(&self, value: i64) -> homie5::client::Publish {
         self.client.publish_value(
             self.node.node_id(),
@@ -313,6 +472,15 @@ impl TimerNodePublisher {
         )
     }
 
+    pub fn duration_target(&self, value: i64) -> homie5::client::Publish {
+        self.client.publish_target(
+            self.node.node_id(),
+            &self.duration_prop,
+            value.to_string(),
+            true,
+        )
+    }
+
     pub fn remaining(&self, value: i64) -> homie5::client::Publish {
         self.client.publish_value(
             self.node.node_id(),
@@ -341,6 +509,32 @@ impl TimerNodePublisher {
     }
 }
 
+impl SmarthomePublisher for TimerNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::Timer
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![
+            self.state_prop.clone(),
+            self.action_prop.clone(),
+            self.label_prop.clone(),
+            self.duration_prop.clone(),
+            self.remaining_prop.clone(),
+            self.trigger_time_prop.clone(),
+            self.created_prop.clone(),
+        ]
+    }
+}
+
 impl SetCommandParser for TimerNodePublisher {
     type Event = TimerNodeSetEvents;
 
@@ -350,6 +544,10 @@ impl SetCommandParser for TimerNodePublisher {
         desc: &HomieDeviceDescription,
         set_value: &str,
     ) -> ParseOutcome<Self::Event> {
+        if let Some(outcome) = reject_readonly(&self.node, property, desc, set_value) {
+            return outcome;
+        }
+
         let property_id = property.prop_id().to_string();
 
         if property.match_with_node(&self.node, &self.action_prop) {
@@ -0,0 +1,619 @@
+use core::fmt;
+use std::{collections::BTreeMap, str::FromStr};
+
+use homie5::{
+    HOMIE_UNIT_PERCENT, Homie5DeviceProtocol, Homie5Message, Homie5ProtocolError, HomieID,
+    HomieValue, NodeRef, PropertyRef,
+    device_description::{
+        HomieDeviceDescription, HomieNodeDescription, IntegerRange, NodeDescriptionBuilder,
+        PropertyDescriptionBuilder,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ConfigError, ParseError, ParseErrorKind, ParseOutcome, PropertyMetadata, SMARTHOME_CAP_VACUUM,
+    SetCommandParser, SmarthomePublisher, SmarthomeType, property_metadata, readonly_properties,
+    reject_readonly, smarthome_node_config, validate_enum_format,
+};
+
+pub const VACUUM_NODE_DEFAULT_ID: HomieID = HomieID::new_const("vacuum");
+pub const VACUUM_NODE_DEFAULT_NAME: &str = "Robot vacuum";
+pub const VACUUM_NODE_STATE_PROP_ID: HomieID = HomieID::new_const("state");
+pub const VACUUM_NODE_ACTION_PROP_ID: HomieID = HomieID::new_const("action");
+pub const VACUUM_NODE_FAN_SPEED_PROP_ID: HomieID = HomieID::new_const("fan-speed");
+pub const VACUUM_NODE_BATTERY_PROP_ID: HomieID = HomieID::new_const("battery");
+
+// ── Node (state) ────────────────────────────────────────────────────────────
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VacuumNodeState {
+    pub state: VacuumState,
+    pub fan_speed: Option<String>,
+    pub battery: Option<i64>,
+}
+
+#[derive(Debug)]
+pub struct VacuumNode {
+    pub publisher: VacuumNodePublisher,
+    pub state: VacuumNodeState,
+}
+
+impl VacuumNode {
+    /// Apply a parsed set command and return the `$target` and value publishes
+    /// for the change. Actions are commands for the device; the resulting
+    /// `state` is reported back by the device.
+    pub fn apply_set(&mut self, event: VacuumNodeSetEvents) -> Vec<homie5::client::Publish> {
+        match event {
+            VacuumNodeSetEvents::Action(_) => Vec::new(),
+            VacuumNodeSetEvents::FanSpeed(value) => {
+                let publishes = [
+                    self.publisher.fan_speed_target(&value),
+                    self.publisher.fan_speed(&value),
+                ]
+                .into_iter()
+                .flatten()
+                .collect();
+                self.state.fan_speed = Some(value);
+                publishes
+            }
+        }
+    }
+
+    /// Apply a value reported by the device and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Enum(value)) if p == &VACUUM_NODE_STATE_PROP_ID => {
+                let Ok(state) = value.parse::<VacuumState>() else {
+                    return Vec::new();
+                };
+                self.state.state = state;
+                vec![self.publisher.state(state)]
+            }
+            (p, HomieValue::Enum(value)) if p == &VACUUM_NODE_FAN_SPEED_PROP_ID => {
+                self.state.fan_speed = Some(value.clone());
+                self.publisher.fan_speed(value).into_iter().collect()
+            }
+            (p, HomieValue::Integer(value)) if p == &VACUUM_NODE_BATTERY_PROP_ID => {
+                self.state.battery = Some(*value);
+                self.publisher.battery(*value).into_iter().collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            Some(self.publisher.state(self.state.state)),
+            self.state
+                .fan_speed
+                .as_deref()
+                .and_then(|value| self.publisher.fan_speed(value)),
+            self.state
+                .battery
+                .and_then(|value| self.publisher.battery(value)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+// ── State ───────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum VacuumState {
+    Cleaning,
+    Paused,
+    Returning,
+    Docked,
+    #[default]
+    Idle,
+    Error,
+}
+
+impl VacuumState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Cleaning => "cleaning",
+            Self::Paused => "paused",
+            Self::Returning => "returning",
+            Self::Docked => "docked",
+            Self::Idle => "idle",
+            Self::Error => "error",
+        }
+    }
+
+    pub const ALL: [VacuumState; 6] = [
+        VacuumState::Cleaning,
+        VacuumState::Paused,
+        VacuumState::Returning,
+        VacuumState::Docked,
+        VacuumState::Idle,
+        VacuumState::Error,
+    ];
+}
+
+impl fmt::Display for VacuumState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for VacuumState {
+    type Err = Homie5ProtocolError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "cleaning" => Ok(Self::Cleaning),
+            "paused" => Ok(Self::Paused),
+            "returning" => Ok(Self::Returning),
+            "docked" => Ok(Self::Docked),
+            "idle" => Ok(Self::Idle),
+            "error" => Ok(Self::Error),
+            _ => Err(Homie5ProtocolError::InvalidPayload),
+        }
+    }
+}
+
+// ── Actions / set events ────────────────────────────────────────────────────
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+pub enum VacuumNodeActions {
+    Start,
+    Pause,
+    /// Return to the charging dock.
+    Dock,
+    /// Clean a small area around the current position.
+    Spot,
+    /// Play a sound so the robot can be found.
+    Locate,
+}
+
+impl VacuumNodeActions {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Start => "start",
+            Self::Pause => "pause",
+            Self::Dock => "dock",
+            Self::Spot => "spot",
+            Self::Locate => "locate",
+        }
+    }
+}
+
+impl fmt::Display for VacuumNodeActions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for VacuumNodeActions {
+    type Err = Homie5ProtocolError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "start" => Ok(Self::Start),
+            "pause" => Ok(Self::Pause),
+            "dock" => Ok(Self::Dock),
+            "spot" => Ok(Self::Spot),
+            "locate" => Ok(Self::Locate),
+            _ => Err(Homie5ProtocolError::InvalidPayload),
+        }
+    }
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+pub enum VacuumNodeSetEvents {
+    Action(VacuumNodeActions),
+    FanSpeed(String),
+}
+
+// ── Config ──────────────────────────────────────────────────────────────────
+
+smarthome_node_config! {
+    #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+    #[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+    #[serde(default)]
+    pub struct VacuumNodeConfig for VacuumNodePublisher {
+        /// Expose the `spot` action.
+        pub spot: bool,
+        /// Expose the `locate` action.
+        pub locate: bool,
+        /// Suction levels (e.g. `quiet`, `standard`, `max`). The `fan-speed`
+        /// property is only added when this list is not empty.
+        #[cfg_attr(
+            feature = "proptest",
+            proptest(strategy = "crate::arbitrary::enum_values()")
+        )]
+        pub fan_speeds: Vec<String>,
+        /// Expose the `battery` level in percent.
+        pub battery: bool => VACUUM_NODE_BATTERY_PROP_ID, battery(i64), |_config| {
+            PropertyDescriptionBuilder::integer()
+                .name("Battery level")
+                .unit(HOMIE_UNIT_PERCENT)
+                .integer_range(IntegerRange {
+                    min: Some(0),
+                    max: Some(100),
+                    step: None,
+                })
+                .settable(false)
+                .retained(true)
+                .build()
+        },
+        /// Properties to publish as read-only; sets to them are rejected.
+        #[cfg_attr(
+            feature = "proptest",
+            proptest(strategy = "crate::arbitrary::homie_ids()")
+        )]
+        pub readonly: Vec<HomieID>,
+    }
+}
+
+impl Default for VacuumNodeConfig {
+    fn default() -> Self {
+        Self {
+            spot: false,
+            locate: false,
+            fan_speeds: Vec::new(),
+            battery: true,
+            readonly: Vec::new(),
+        }
+    }
+}
+
+impl VacuumNodeConfig {
+    /// Check the config-provided enum values before building the node.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if !self.fan_speeds.is_empty() {
+            validate_enum_format(&VACUUM_NODE_FAN_SPEED_PROP_ID, &self.fan_speeds)?;
+        }
+        Ok(())
+    }
+
+    fn actions(&self) -> Vec<VacuumNodeActions> {
+        let mut actions = vec![
+            VacuumNodeActions::Start,
+            VacuumNodeActions::Pause,
+            VacuumNodeActions::Dock,
+        ];
+        if self.spot {
+            actions.push(VacuumNodeActions::Spot);
+        }
+        if self.locate {
+            actions.push(VacuumNodeActions::Locate);
+        }
+        actions
+    }
+}
+
+// ── Builder ─────────────────────────────────────────────────────────────────
+
+pub struct VacuumNodeBuilder {
+    node_builder: NodeDescriptionBuilder,
+    config: VacuumNodeConfig,
+}
+
+impl Default for VacuumNodeBuilder {
+    fn default() -> Self {
+        Self::new(&VacuumNodeConfig::default())
+    }
+}
+
+impl VacuumNodeBuilder {
+    pub fn new(config: &VacuumNodeConfig) -> Self {
+        let db = Self::build_node(
+            NodeDescriptionBuilder::new().name(VACUUM_NODE_DEFAULT_NAME),
+            config,
+        )
+        .r#type(SMARTHOME_CAP_VACUUM);
+        let db = readonly_properties(db, &config.readonly);
+
+        Self {
+            node_builder: db,
+            config: config.clone(),
+        }
+    }
+
+    fn build_node(db: NodeDescriptionBuilder, config: &VacuumNodeConfig) -> NodeDescriptionBuilder {
+        config.add_optional_properties(
+            db.add_property(
+                VACUUM_NODE_STATE_PROP_ID,
+                PropertyDescriptionBuilder::enumeration(
+                    VacuumState::ALL.iter().map(|s| s.as_str()),
+                )
+                .unwrap()
+                .name("Vacuum state")
+                .settable(false)
+                .retained(true)
+                .build(),
+            )
+            .add_property(
+                VACUUM_NODE_ACTION_PROP_ID,
+                PropertyDescriptionBuilder::enumeration(
+                    config.actions().iter().map(|a| a.as_str()),
+                )
+                .unwrap()
+                .name("Vacuum action")
+                .settable(true)
+                .retained(false)
+                .build(),
+            )
+            .add_property_cond(
+                VACUUM_NODE_FAN_SPEED_PROP_ID,
+                !config.fan_speeds.is_empty(),
+                || {
+                    PropertyDescriptionBuilder::enumeration(
+                        config.fan_speeds.iter().map(|s| s.as_str()),
+                    )
+                    .unwrap()
+                    .name("Fan speed")
+                    .settable(true)
+                    .retained(true)
+                    .build()
+                },
+            ),
+        )
+    }
+
+    pub fn name<S: Into<String>>(mut self, name: impl Into<Option<S>>) -> Self {
+        self.node_builder = self.node_builder.name(name);
+        self
+    }
+
+    /// Append a subtype suffix to the node type (`vacuum-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::Vacuum.with_subtype(subtype));
+        self
+    }
+
+    pub fn build(self) -> HomieNodeDescription {
+        self.node_builder.build()
+    }
+
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &VacuumNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
+    pub fn build_with_publisher(
+        self,
+        node_id: HomieID,
+        client: &Homie5DeviceProtocol,
+    ) -> (HomieNodeDescription, VacuumNodePublisher) {
+        (
+            self.node_builder.build(),
+            VacuumNodePublisher::new(
+                NodeRef::new(
+                    client.homie_domain().to_owned(),
+                    client.id().clone(),
+                    node_id,
+                ),
+                client.clone(),
+                self.config,
+            ),
+        )
+    }
+}
+
+// ── Publisher ────────────────────────────────────────────────────────────────
+
+#[derive(Debug)]
+pub struct VacuumNodePublisher {
+    client: Homie5DeviceProtocol,
+    node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
+    config: VacuumNodeConfig,
+    state_prop: HomieID,
+    action_prop: HomieID,
+    fan_speed_prop: HomieID,
+    battery_prop: HomieID,
+}
+
+impl VacuumNodePublisher {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: VacuumNodeConfig) -> Self {
+        let metadata = property_metadata(&VacuumNodeBuilder::new(&config).build());
+        Self {
+            node,
+            metadata,
+            client,
+            config,
+            state_prop: VACUUM_NODE_STATE_PROP_ID,
+            action_prop: VACUUM_NODE_ACTION_PROP_ID,
+            fan_speed_prop: VACUUM_NODE_FAN_SPEED_PROP_ID,
+            battery_prop: VACUUM_NODE_BATTERY_PROP_ID,
+        }
+    }
+
+    pub fn state(&self, state: VacuumState) -> homie5::client::Publish {
+        self.client
+            .publish_value(self.node.node_id(), &self.state_prop, state.as_str(), true)
+    }
+
+    pub fn action(&self, action: VacuumNodeActions) -> homie5::client::Publish {
+        self.client.publish_value(
+            self.node.node_id(),
+            &self.action_prop,
+            action.as_str(),
+            false,
+        )
+    }
+
+    pub fn fan_speed(&self, value: &str) -> Option<homie5::client::Publish> {
+        if self.config.fan_speeds.is_empty() {
+            return None;
+        }
+        Some(
+            self.client
+                .publish_value(self.node.node_id(), &self.fan_speed_prop, value, true),
+        )
+    }
+
+    pub fn fan_speed_target(&self, value: &str) -> Option<homie5::client::Publish> {
+        if self.config.fan_speeds.is_empty() {
+            return None;
+        }
+        Some(
+            self.client
+                .publish_target(self.node.node_id(), &self.fan_speed_prop, value, true),
+        )
+    }
+}
+
+impl SmarthomePublisher for VacuumNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::Vacuum
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![
+            self.state_prop.clone(),
+            self.action_prop.clone(),
+            self.fan_speed_prop.clone(),
+            self.battery_prop.clone(),
+        ]
+    }
+}
+
+impl SetCommandParser for VacuumNodePublisher {
+    type Event = VacuumNodeSetEvents;
+
+    fn parse_set(
+        &self,
+        property: &PropertyRef,
+        desc: &HomieDeviceDescription,
+        set_value: &str,
+    ) -> ParseOutcome<Self::Event> {
+        if let Some(outcome) = reject_readonly(&self.node, property, desc, set_value) {
+            return outcome;
+        }
+
+        let property_id = property.prop_id().to_string();
+
+        if property.match_with_node(&self.node, &self.action_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Enum(value)) => match VacuumNodeActions::from_str(&value) {
+                    Ok(action) => ParseOutcome::Parsed(VacuumNodeSetEvents::Action(action)),
+                    Err(_) => ParseOutcome::Invalid(ParseError::new(
+                        property_id,
+                        set_value,
+                        ParseErrorKind::InvalidVariant,
+                    )),
+                },
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else if property.match_with_node(&self.node, &self.fan_speed_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Enum(value)) => {
+                    ParseOutcome::Parsed(VacuumNodeSetEvents::FanSpeed(value))
+                }
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else {
+            ParseOutcome::NoMatch
+        }
+    }
+
+    fn parse_set_event(
+        &self,
+        desc: &HomieDeviceDescription,
+        event: &Homie5Message,
+    ) -> ParseOutcome<Self::Event> {
+        match event {
+            Homie5Message::PropertySet {
+                property,
+                set_value,
+            } => self.parse_set(property, desc, set_value),
+            _ => ParseOutcome::Invalid(ParseError::new(
+                self.action_prop.to_string(),
+                "",
+                ParseErrorKind::UnexpectedMessageType,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use homie5::HomieDomain;
+
+    use super::*;
+    use crate::StandaloneSetParser;
+
+    #[test]
+    fn actions_and_fan_speed_parse_against_description() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let config = VacuumNodeConfig {
+            locate: true,
+            fan_speeds: vec!["quiet".to_owned(), "max".to_owned()],
+            ..VacuumNodeConfig::default()
+        };
+        let parser: StandaloneSetParser<_> = VacuumNodeBuilder::new(&config)
+            .build_with_publisher(VACUUM_NODE_DEFAULT_ID, &client)
+            .into();
+        let node = parser.publisher().node_ref().clone();
+        let action = PropertyRef::from_node(node.clone(), VACUUM_NODE_ACTION_PROP_ID);
+        let fan_speed = PropertyRef::from_node(node, VACUUM_NODE_FAN_SPEED_PROP_ID);
+
+        assert!(matches!(
+            parser.parse_set(&action, "locate"),
+            ParseOutcome::Parsed(VacuumNodeSetEvents::Action(VacuumNodeActions::Locate))
+        ));
+        // Spot cleaning is disabled in the config.
+        assert!(matches!(
+            parser.parse_set(&action, "spot"),
+            ParseOutcome::Invalid(_)
+        ));
+        assert!(matches!(
+            parser.parse_set(&fan_speed, "max"),
+            ParseOutcome::Parsed(VacuumNodeSetEvents::FanSpeed(s)) if s == "max"
+        ));
+    }
+}
@@ -1,44 +1,157 @@
+use std::collections::BTreeMap;
+
 use homie5::{
-    Homie5DeviceProtocol, Homie5Message, HomieID, HomieValue, NodeRef, PropertyRef,
+    HOMIE_UNIT_PERCENT, Homie5DeviceProtocol, Homie5Message, HomieID, HomieValue, NodeRef,
+    PropertyRef,
     device_description::{
-        HomieDeviceDescription, HomieNodeDescription, NodeDescriptionBuilder,
+        HomieDeviceDescription, HomieNodeDescription, IntegerRange, NodeDescriptionBuilder,
         PropertyDescriptionBuilder,
     },
 };
 use serde::{Deserialize, Serialize};
 
-use crate::{ParseError, ParseErrorKind, ParseOutcome, SMARTHOME_CAP_VALVE, SetCommandParser};
+use crate::{
+    ParseError, ParseErrorKind, ParseOutcome, PropertyMetadata, SMARTHOME_CAP_VALVE,
+    SetCommandParser, SmarthomePublisher, SmarthomeType, property_metadata, readonly_properties,
+    reject_readonly, smarthome_node_config,
+};
 
 pub const VALVE_NODE_DEFAULT_ID: HomieID = HomieID::new_const("valve");
 pub const VALVE_NODE_DEFAULT_NAME: &str = "Valve control";
 pub const VALVE_NODE_STATE_PROP_ID: HomieID = HomieID::new_const("state");
+pub const VALVE_NODE_POSITION_PROP_ID: HomieID = HomieID::new_const("position");
+pub const VALVE_NODE_FAULT_PROP_ID: HomieID = HomieID::new_const("valve-fault");
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ValveNodeState {
+    pub state: bool,
+    pub state_target: bool,
+    pub position: Option<i64>,
+    pub fault: Option<bool>,
+}
 
 #[derive(Debug)]
 pub struct ValveNode {
     pub publisher: ValveNodePublisher,
-    pub state: bool,
-    pub state_target: bool,
+    pub state: ValveNodeState,
+}
+
+impl ValveNode {
+    /// Apply a parsed set command and return the `$target` and value publishes.
+    pub fn apply_set(&mut self, event: ValveNodeSetEvents) -> Vec<homie5::client::Publish> {
+        match event {
+            ValveNodeSetEvents::State(value) => {
+                self.state.state_target = value;
+                self.state.state = value;
+                vec![
+                    self.publisher.state_target(value),
+                    self.publisher.state(value),
+                ]
+            }
+        }
+    }
+
+    /// Apply a value reported by the device and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Bool(value)) if p == &VALVE_NODE_STATE_PROP_ID => {
+                self.state.state = *value;
+                vec![self.publisher.state(*value)]
+            }
+            (p, HomieValue::Integer(value)) if p == &VALVE_NODE_POSITION_PROP_ID => {
+                self.state.position = Some(*value);
+                self.publisher.position(*value).into_iter().collect()
+            }
+            (p, HomieValue::Bool(value)) if p == &VALVE_NODE_FAULT_PROP_ID => {
+                self.state.fault = Some(*value);
+                self.publisher.fault(*value).into_iter().collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            Some(self.publisher.state(self.state.state)),
+            Some(self.publisher.state_target(self.state.state_target)),
+            self.state
+                .position
+                .and_then(|value| self.publisher.position(value)),
+            self.state
+                .fault
+                .and_then(|value| self.publisher.fault(value)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 pub enum ValveNodeSetEvents {
     State(bool),
 }
 
-#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
-#[serde(default)]
-pub struct ValveNodeConfig {
-    pub settable: bool,
+smarthome_node_config! {
+    #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+    #[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+    #[serde(default)]
+    pub struct ValveNodeConfig for ValveNodePublisher {
+        pub settable: bool,
+        /// Expose the opening `position` in percent (motorized ball valves).
+        pub position: bool => VALVE_NODE_POSITION_PROP_ID, position(i64), |_config| {
+            PropertyDescriptionBuilder::integer()
+                .name("Valve position")
+                .unit(HOMIE_UNIT_PERCENT)
+                .integer_range(IntegerRange {
+                    min: Some(0),
+                    max: Some(100),
+                    step: None,
+                })
+                .settable(false)
+                .retained(true)
+                .build()
+        },
+        /// Expose the `valve-fault` property (stuck or motor failure).
+        pub fault: bool => VALVE_NODE_FAULT_PROP_ID, fault(bool), |_config| {
+            PropertyDescriptionBuilder::boolean()
+                .name("Valve fault")
+                .boolean_labels("ok", "fault")
+                .settable(false)
+                .retained(true)
+                .build()
+        },
+        /// Properties to publish as read-only; sets to them are rejected.
+        #[cfg_attr(
+            feature = "proptest",
+            proptest(strategy = "crate::arbitrary::homie_ids()")
+        )]
+        pub readonly: Vec<HomieID>,
+    }
 }
 
 impl Default for ValveNodeConfig {
     fn default() -> Self {
-        Self { settable: true }
+        Self {
+            settable: true,
+            position: false,
+            fault: false,
+            readonly: Vec::new(),
+        }
     }
 }
 
 pub struct ValveNodeBuilder {
     node_builder: NodeDescriptionBuilder,
+    config: ValveNodeConfig,
 }
 
 impl ValveNodeBuilder {
@@ -48,19 +161,25 @@ impl ValveNodeBuilder {
             config,
         )
         .r#type(SMARTHOME_CAP_VALVE);
+        let db = readonly_properties(db, &config.readonly);
 
-        Self { node_builder: db }
+        Self {
+            node_builder: db,
+            config: config.clone(),
+        }
     }
 
     fn build_node(db: NodeDescriptionBuilder, config: &ValveNodeConfig) -> NodeDescriptionBuilder {
-        db.add_property(
-            VALVE_NODE_STATE_PROP_ID,
-            PropertyDescriptionBuilder::boolean()
-                .name("Valve state")
-                .boolean_labels("closed", "open")
-                .settable(config.settable)
-                .retained(true)
-                .build(),
+        config.add_optional_properties(
+            db.add_property(
+                VALVE_NODE_STATE_PROP_ID,
+                PropertyDescriptionBuilder::boolean()
+                    .name("Valve state")
+                    .boolean_labels("closed", "open")
+                    .settable(config.settable)
+                    .retained(true)
+                    .build(),
+            ),
         )
     }
 
@@ -69,10 +188,23 @@ impl ValveNodeBuilder {
         self
     }
 
+    /// Append a subtype suffix to the node type (`valve-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::Valve.with_subtype(subtype));
+        self
+    }
+
     pub fn build(self) -> HomieNodeDescription {
         self.node_builder.build()
     }
 
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &ValveNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
     pub fn build_with_publisher(
         self,
         node_id: HomieID,
@@ -87,6 +219,7 @@ impl ValveNodeBuilder {
                     node_id,
                 ),
                 client.clone(),
+                self.config,
             ),
         )
     }
@@ -96,15 +229,24 @@ impl ValveNodeBuilder {
 pub struct ValveNodePublisher {
     client: Homie5DeviceProtocol,
     node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
+    config: ValveNodeConfig,
     state_prop: HomieID,
+    position_prop: HomieID,
+    fault_prop: HomieID,
 }
 
 impl ValveNodePublisher {
-    pub fn new(node: NodeRef, client: Homie5DeviceProtocol) -> Self {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: ValveNodeConfig) -> Self {
+        let metadata = property_metadata(&ValveNodeBuilder::new(&config).build());
         Self {
             node,
+            metadata,
             client,
+            config,
             state_prop: VALVE_NODE_STATE_PROP_ID,
+            position_prop: VALVE_NODE_POSITION_PROP_ID,
+            fault_prop: VALVE_NODE_FAULT_PROP_ID,
         }
     }
 
@@ -127,6 +269,28 @@ impl ValveNodePublisher {
     }
 }
 
+impl SmarthomePublisher for ValveNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::Valve
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![
+            self.state_prop.clone(),
+            self.position_prop.clone(),
+            self.fault_prop.clone(),
+        ]
+    }
+}
+
 impl SetCommandParser for ValveNodePublisher {
     type Event = ValveNodeSetEvents;
 
@@ -136,6 +300,10 @@ impl SetCommandParser for ValveNodePublisher {
         desc: &HomieDeviceDescription,
         set_value: &str,
     ) -> ParseOutcome<Self::Event> {
+        if let Some(outcome) = reject_readonly(&self.node, property, desc, set_value) {
+            return outcome;
+        }
+
         let property_id = property.prop_id().to_string();
 
         if property.match_with_node(&self.node, &self.state_prop) {
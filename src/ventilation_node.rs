@@ -0,0 +1,636 @@
+use core::fmt;
+use std::{collections::BTreeMap, str::FromStr};
+
+use homie5::{
+    HOMIE_UNIT_DEGREE_CELSIUS, HOMIE_UNIT_PERCENT, Homie5DeviceProtocol, Homie5Message,
+    Homie5ProtocolError, HomieID, HomieValue, NodeRef, PropertyRef,
+    device_description::{
+        HomieDeviceDescription, HomieNodeDescription, IntegerRange, NodeDescriptionBuilder,
+        PropertyDescriptionBuilder,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ParseError, ParseErrorKind, ParseOutcome, PropertyMetadata, SMARTHOME_CAP_VENTILATION,
+    SetCommandParser, SmarthomePublisher, SmarthomeType, property_metadata, readonly_properties,
+    reject_readonly, smarthome_node_config,
+};
+
+pub const VENTILATION_NODE_DEFAULT_ID: HomieID = HomieID::new_const("ventilation");
+pub const VENTILATION_NODE_DEFAULT_NAME: &str = "Ventilation unit";
+pub const VENTILATION_NODE_LEVEL_PROP_ID: HomieID = HomieID::new_const("level");
+pub const VENTILATION_NODE_ACTION_PROP_ID: HomieID = HomieID::new_const("action");
+pub const VENTILATION_NODE_BYPASS_PROP_ID: HomieID = HomieID::new_const("bypass");
+pub const VENTILATION_NODE_FILTER_PROP_ID: HomieID = HomieID::new_const("filter-remaining");
+pub const VENTILATION_NODE_SUPPLY_TEMP_PROP_ID: HomieID = HomieID::new_const("supply-temperature");
+pub const VENTILATION_NODE_EXTRACT_TEMP_PROP_ID: HomieID =
+    HomieID::new_const("extract-temperature");
+
+// ── Node (state) ────────────────────────────────────────────────────────────
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VentilationNodeState {
+    pub level: VentilationLevel,
+    pub bypass: Option<bool>,
+    pub filter_remaining: Option<i64>,
+    pub supply_temperature: Option<f64>,
+    pub extract_temperature: Option<f64>,
+}
+
+#[derive(Debug)]
+pub struct VentilationNode {
+    pub publisher: VentilationNodePublisher,
+    pub state: VentilationNodeState,
+}
+
+impl VentilationNode {
+    /// Apply a parsed set command and return the `$target` and value publishes
+    /// for the change. A timed boost is a command for the unit; the resulting
+    /// `level` is reported back by the device.
+    pub fn apply_set(&mut self, event: VentilationNodeSetEvents) -> Vec<homie5::client::Publish> {
+        match event {
+            VentilationNodeSetEvents::Level(value) => {
+                self.state.level = value;
+                vec![
+                    self.publisher.level_target(value),
+                    self.publisher.level(value),
+                ]
+            }
+            VentilationNodeSetEvents::Action(_) => Vec::new(),
+        }
+    }
+
+    /// Apply a value reported by the device and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Enum(value)) if p == &VENTILATION_NODE_LEVEL_PROP_ID => {
+                let Ok(level) = value.parse::<VentilationLevel>() else {
+                    return Vec::new();
+                };
+                self.state.level = level;
+                vec![self.publisher.level(level)]
+            }
+            (p, HomieValue::Bool(value)) if p == &VENTILATION_NODE_BYPASS_PROP_ID => {
+                self.state.bypass = Some(*value);
+                self.publisher.bypass(*value).into_iter().collect()
+            }
+            (p, HomieValue::Integer(value)) if p == &VENTILATION_NODE_FILTER_PROP_ID => {
+                self.state.filter_remaining = Some(*value);
+                self.publisher
+                    .filter_remaining(*value)
+                    .into_iter()
+                    .collect()
+            }
+            (p, HomieValue::Float(value)) if p == &VENTILATION_NODE_SUPPLY_TEMP_PROP_ID => {
+                self.state.supply_temperature = Some(*value);
+                self.publisher
+                    .supply_temperature(*value)
+                    .into_iter()
+                    .collect()
+            }
+            (p, HomieValue::Float(value)) if p == &VENTILATION_NODE_EXTRACT_TEMP_PROP_ID => {
+                self.state.extract_temperature = Some(*value);
+                self.publisher
+                    .extract_temperature(*value)
+                    .into_iter()
+                    .collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            Some(self.publisher.level(self.state.level)),
+            self.state
+                .bypass
+                .and_then(|value| self.publisher.bypass(value)),
+            self.state
+                .filter_remaining
+                .and_then(|value| self.publisher.filter_remaining(value)),
+            self.state
+                .supply_temperature
+                .and_then(|value| self.publisher.supply_temperature(value)),
+            self.state
+                .extract_temperature
+                .and_then(|value| self.publisher.extract_temperature(value)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+// ── Level ───────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+#[serde(rename_all = "kebab-case")]
+pub enum VentilationLevel {
+    /// Minimal air exchange while nobody is home.
+    Away,
+    #[default]
+    Low,
+    Medium,
+    High,
+    /// Maximum air exchange (kitchen, bathroom).
+    Boost,
+}
+
+impl VentilationLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Away => "away",
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+            Self::Boost => "boost",
+        }
+    }
+
+    pub const ALL: [VentilationLevel; 5] = [
+        VentilationLevel::Away,
+        VentilationLevel::Low,
+        VentilationLevel::Medium,
+        VentilationLevel::High,
+        VentilationLevel::Boost,
+    ];
+}
+
+impl fmt::Display for VentilationLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for VentilationLevel {
+    type Err = Homie5ProtocolError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "away" => Ok(Self::Away),
+            "low" => Ok(Self::Low),
+            "medium" => Ok(Self::Medium),
+            "high" => Ok(Self::High),
+            "boost" => Ok(Self::Boost),
+            _ => Err(Homie5ProtocolError::InvalidPayload),
+        }
+    }
+}
+
+// ── Actions / set events ────────────────────────────────────────────────────
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+pub enum VentilationNodeActions {
+    /// Run at boost level for the device-defined period, then return to the
+    /// previous level.
+    Boost,
+}
+
+impl VentilationNodeActions {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VentilationNodeActions::Boost => "boost",
+        }
+    }
+}
+
+impl fmt::Display for VentilationNodeActions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for VentilationNodeActions {
+    type Err = Homie5ProtocolError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "boost" => Ok(VentilationNodeActions::Boost),
+            _ => Err(Homie5ProtocolError::InvalidPayload),
+        }
+    }
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+pub enum VentilationNodeSetEvents {
+    Level(VentilationLevel),
+    Action(VentilationNodeActions),
+}
+
+// ── Config ──────────────────────────────────────────────────────────────────
+
+smarthome_node_config! {
+    #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+    #[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+    #[serde(default)]
+    pub struct VentilationNodeConfig for VentilationNodePublisher {
+        /// Expose the timed `boost` action.
+        pub boost_action: bool,
+        /// Expose the summer `bypass` state of the heat exchanger.
+        pub bypass: bool => VENTILATION_NODE_BYPASS_PROP_ID, bypass(bool), |_config| {
+            PropertyDescriptionBuilder::boolean()
+                .name("Bypass")
+                .boolean_labels("closed", "open")
+                .settable(false)
+                .retained(true)
+                .build()
+        },
+        /// Expose the remaining filter life in percent.
+        pub filter: bool => VENTILATION_NODE_FILTER_PROP_ID, filter_remaining(i64), |_config| {
+            PropertyDescriptionBuilder::integer()
+                .name("Filter remaining")
+                .unit(HOMIE_UNIT_PERCENT)
+                .integer_range(IntegerRange {
+                    min: Some(0),
+                    max: Some(100),
+                    step: None,
+                })
+                .settable(false)
+                .retained(true)
+                .build()
+        },
+        /// Expose `supply-temperature` and `extract-temperature`.
+        pub temperatures: bool,
+        pub temp_unit: String,
+        /// Properties to publish as read-only; sets to them are rejected.
+        #[cfg_attr(
+            feature = "proptest",
+            proptest(strategy = "crate::arbitrary::homie_ids()")
+        )]
+        pub readonly: Vec<HomieID>,
+    }
+}
+
+impl Default for VentilationNodeConfig {
+    fn default() -> Self {
+        Self {
+            boost_action: true,
+            bypass: false,
+            filter: true,
+            temperatures: false,
+            temp_unit: HOMIE_UNIT_DEGREE_CELSIUS.to_owned(),
+            readonly: Vec::new(),
+        }
+    }
+}
+
+// ── Builder ─────────────────────────────────────────────────────────────────
+
+pub struct VentilationNodeBuilder {
+    node_builder: NodeDescriptionBuilder,
+    config: VentilationNodeConfig,
+}
+
+impl Default for VentilationNodeBuilder {
+    fn default() -> Self {
+        Self::new(&VentilationNodeConfig::default())
+    }
+}
+
+impl VentilationNodeBuilder {
+    pub fn new(config: &VentilationNodeConfig) -> Self {
+        let db = Self::build_node(
+            NodeDescriptionBuilder::new().name(VENTILATION_NODE_DEFAULT_NAME),
+            config,
+        )
+        .r#type(SMARTHOME_CAP_VENTILATION);
+        let db = readonly_properties(db, &config.readonly);
+
+        Self {
+            node_builder: db,
+            config: config.clone(),
+        }
+    }
+
+    fn build_node(
+        db: NodeDescriptionBuilder,
+        config: &VentilationNodeConfig,
+    ) -> NodeDescriptionBuilder {
+        config.add_optional_properties(
+            db.add_property(
+                VENTILATION_NODE_LEVEL_PROP_ID,
+                PropertyDescriptionBuilder::enumeration(
+                    VentilationLevel::ALL.iter().map(|l| l.as_str()),
+                )
+                .unwrap()
+                .name("Ventilation level")
+                .settable(true)
+                .retained(true)
+                .build(),
+            )
+            .add_property_cond(VENTILATION_NODE_ACTION_PROP_ID, config.boost_action, || {
+                PropertyDescriptionBuilder::enumeration([VentilationNodeActions::Boost.as_str()])
+                    .unwrap()
+                    .name("Ventilation action")
+                    .settable(true)
+                    .retained(false)
+                    .build()
+            })
+            .add_property_cond(
+                VENTILATION_NODE_SUPPLY_TEMP_PROP_ID,
+                config.temperatures,
+                || {
+                    PropertyDescriptionBuilder::float()
+                        .name("Supply air temperature")
+                        .unit(config.temp_unit.to_owned())
+                        .settable(false)
+                        .retained(true)
+                        .build()
+                },
+            )
+            .add_property_cond(
+                VENTILATION_NODE_EXTRACT_TEMP_PROP_ID,
+                config.temperatures,
+                || {
+                    PropertyDescriptionBuilder::float()
+                        .name("Extract air temperature")
+                        .unit(config.temp_unit.to_owned())
+                        .settable(false)
+                        .retained(true)
+                        .build()
+                },
+            ),
+        )
+    }
+
+    pub fn name<S: Into<String>>(mut self, name: impl Into<Option<S>>) -> Self {
+        self.node_builder = self.node_builder.name(name);
+        self
+    }
+
+    /// Append a subtype suffix to the node type (`ventilation-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::Ventilation.with_subtype(subtype));
+        self
+    }
+
+    pub fn build(self) -> HomieNodeDescription {
+        self.node_builder.build()
+    }
+
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &VentilationNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
+    pub fn build_with_publisher(
+        self,
+        node_id: HomieID,
+        client: &Homie5DeviceProtocol,
+    ) -> (HomieNodeDescription, VentilationNodePublisher) {
+        (
+            self.node_builder.build(),
+            VentilationNodePublisher::new(
+                NodeRef::new(
+                    client.homie_domain().to_owned(),
+                    client.id().clone(),
+                    node_id,
+                ),
+                client.clone(),
+                self.config,
+            ),
+        )
+    }
+}
+
+// ── Publisher ────────────────────────────────────────────────────────────────
+
+#[derive(Debug)]
+pub struct VentilationNodePublisher {
+    client: Homie5DeviceProtocol,
+    node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
+    config: VentilationNodeConfig,
+    level_prop: HomieID,
+    action_prop: HomieID,
+    bypass_prop: HomieID,
+    filter_prop: HomieID,
+    supply_temp_prop: HomieID,
+    extract_temp_prop: HomieID,
+}
+
+impl VentilationNodePublisher {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: VentilationNodeConfig) -> Self {
+        let metadata = property_metadata(&VentilationNodeBuilder::new(&config).build());
+        Self {
+            node,
+            metadata,
+            client,
+            config,
+            level_prop: VENTILATION_NODE_LEVEL_PROP_ID,
+            action_prop: VENTILATION_NODE_ACTION_PROP_ID,
+            bypass_prop: VENTILATION_NODE_BYPASS_PROP_ID,
+            filter_prop: VENTILATION_NODE_FILTER_PROP_ID,
+            supply_temp_prop: VENTILATION_NODE_SUPPLY_TEMP_PROP_ID,
+            extract_temp_prop: VENTILATION_NODE_EXTRACT_TEMP_PROP_ID,
+        }
+    }
+
+    pub fn level(&self, level: VentilationLevel) -> homie5::client::Publish {
+        self.client
+            .publish_value(self.node.node_id(), &self.level_prop, level.as_str(), true)
+    }
+
+    pub fn level_target(&self, level: VentilationLevel) -> homie5::client::Publish {
+        self.client
+            .publish_target(self.node.node_id(), &self.level_prop, level.as_str(), true)
+    }
+
+    pub fn action(&self, action: VentilationNodeActions) -> Option<homie5::client::Publish> {
+        if !self.config.boost_action {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.action_prop,
+            action.as_str(),
+            false,
+        ))
+    }
+
+    pub fn supply_temperature(&self, value: f64) -> Option<homie5::client::Publish> {
+        if !self.config.temperatures {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.supply_temp_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+
+    pub fn extract_temperature(&self, value: f64) -> Option<homie5::client::Publish> {
+        if !self.config.temperatures {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.extract_temp_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+}
+
+impl SmarthomePublisher for VentilationNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::Ventilation
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![
+            self.level_prop.clone(),
+            self.action_prop.clone(),
+            self.bypass_prop.clone(),
+            self.filter_prop.clone(),
+            self.supply_temp_prop.clone(),
+            self.extract_temp_prop.clone(),
+        ]
+    }
+}
+
+impl SetCommandParser for VentilationNodePublisher {
+    type Event = VentilationNodeSetEvents;
+
+    fn parse_set(
+        &self,
+        property: &PropertyRef,
+        desc: &HomieDeviceDescription,
+        set_value: &str,
+    ) -> ParseOutcome<Self::Event> {
+        if let Some(outcome) = reject_readonly(&self.node, property, desc, set_value) {
+            return outcome;
+        }
+
+        let property_id = property.prop_id().to_string();
+
+        if property.match_with_node(&self.node, &self.level_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Enum(value)) => match VentilationLevel::from_str(&value) {
+                    Ok(level) => ParseOutcome::Parsed(VentilationNodeSetEvents::Level(level)),
+                    Err(_) => ParseOutcome::Invalid(ParseError::new(
+                        property_id,
+                        set_value,
+                        ParseErrorKind::InvalidVariant,
+                    )),
+                },
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else if property.match_with_node(&self.node, &self.action_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Enum(value)) => match VentilationNodeActions::from_str(&value) {
+                    Ok(action) => ParseOutcome::Parsed(VentilationNodeSetEvents::Action(action)),
+                    Err(_) => ParseOutcome::Invalid(ParseError::new(
+                        property_id,
+                        set_value,
+                        ParseErrorKind::InvalidVariant,
+                    )),
+                },
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
+        } else {
+            ParseOutcome::NoMatch
+        }
+    }
+
+    fn parse_set_event(
+        &self,
+        desc: &HomieDeviceDescription,
+        event: &Homie5Message,
+    ) -> ParseOutcome<Self::Event> {
+        match event {
+            Homie5Message::PropertySet {
+                property,
+                set_value,
+            } => self.parse_set(property, desc, set_value),
+            _ => ParseOutcome::Invalid(ParseError::new(
+                self.level_prop.to_string(),
+                "",
+                ParseErrorKind::UnexpectedMessageType,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use homie5::HomieDomain;
+
+    use super::*;
+    use crate::StandaloneSetParser;
+
+    #[test]
+    fn level_and_boost_action_parse() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let parser: StandaloneSetParser<_> = VentilationNodeBuilder::default()
+            .build_with_publisher(VENTILATION_NODE_DEFAULT_ID, &client)
+            .into();
+        let node = parser.publisher().node_ref().clone();
+        let level = PropertyRef::from_node(node.clone(), VENTILATION_NODE_LEVEL_PROP_ID);
+        let action = PropertyRef::from_node(node, VENTILATION_NODE_ACTION_PROP_ID);
+
+        assert!(matches!(
+            parser.parse_set(&level, "away"),
+            ParseOutcome::Parsed(VentilationNodeSetEvents::Level(VentilationLevel::Away))
+        ));
+        assert!(matches!(
+            parser.parse_set(&level, "turbo"),
+            ParseOutcome::Invalid(_)
+        ));
+        assert!(matches!(
+            parser.parse_set(&action, "boost"),
+            ParseOutcome::Parsed(VentilationNodeSetEvents::Action(
+                VentilationNodeActions::Boost
+            ))
+        ));
+    }
+}
@@ -1,42 +1,194 @@
+use std::collections::BTreeMap;
+
 use homie5::{
-    Homie5DeviceProtocol, HomieID, NodeRef,
+    Homie5DeviceProtocol, Homie5Message, HomieID, HomieValue, NodeRef, PropertyRef,
     device_description::{
-        HomieNodeDescription, NodeDescriptionBuilder, PropertyDescriptionBuilder,
+        HomieDeviceDescription, HomieNodeDescription, IntegerRange, NodeDescriptionBuilder,
+        PropertyDescriptionBuilder,
     },
 };
 use serde::{Deserialize, Serialize};
 
-use crate::SMARTHOME_CAP_VIBRATION;
+use crate::{
+    ParseError, ParseErrorKind, ParseOutcome, PropertyMetadata, SMARTHOME_CAP_VIBRATION,
+    SetCommandParser, SmarthomePublisher, SmarthomeType, property_metadata, readonly_properties,
+    reject_readonly, smarthome_node_config,
+};
 
 pub const VIBRATION_NODE_DEFAULT_ID: HomieID = HomieID::new_const("vibration");
 pub const VIBRATION_NODE_DEFAULT_NAME: &str = "Vibration sensor";
 pub const VIBRATION_NODE_VIBRATION_PROP_ID: HomieID = HomieID::new_const("vibration");
 pub const VIBRATION_NODE_VIBRATION_STRENGTH_PROP_ID: HomieID =
     HomieID::new_const("vibration-strength");
+pub const VIBRATION_NODE_DROP_DETECTED_PROP_ID: HomieID = HomieID::new_const("drop-detected");
+pub const VIBRATION_NODE_TILT_DETECTED_PROP_ID: HomieID = HomieID::new_const("tilt-detected");
+pub const VIBRATION_NODE_VIBRATION_COUNT_PROP_ID: HomieID = HomieID::new_const("vibration-count");
+pub const VIBRATION_NODE_RESET_COUNT_PROP_ID: HomieID = HomieID::new_const("reset-count");
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VibrationNodeState {
+    pub vibration: bool,
+    pub vibration_strength: Option<i64>,
+    pub drop_detected: Option<bool>,
+    pub tilt_detected: Option<bool>,
+    /// Vibration events since the last reset.
+    pub vibration_count: i64,
+}
 
 #[derive(Debug)]
 pub struct VibrationNode {
     pub publisher: VibrationNodePublisher,
-    pub vibration: bool,
-    pub vibration_strength: Option<i64>,
+    pub state: VibrationNodeState,
 }
 
-#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
-#[serde(default)]
-pub struct VibrationNodeConfig {
-    pub vibration_strength: bool,
+impl VibrationNode {
+    /// Publish a vibration reading. Each new vibration (a `false` to `true`
+    /// transition) also increments and publishes `vibration-count`.
+    pub fn vibration(&mut self, value: bool) -> Vec<homie5::client::Publish> {
+        let mut publishes = vec![self.publisher.vibration(value)];
+        if value && !self.state.vibration {
+            self.state.vibration_count += 1;
+            publishes.extend(self.publisher.vibration_count(self.state.vibration_count));
+        }
+        self.state.vibration = value;
+        publishes
+    }
+
+    /// Apply a parsed set command and return the resulting publishes.
+    pub fn apply_set(&mut self, event: VibrationNodeSetEvents) -> Vec<homie5::client::Publish> {
+        match event {
+            VibrationNodeSetEvents::ResetCount => {
+                self.state.vibration_count = 0;
+                self.publisher.vibration_count(0).into_iter().collect()
+            }
+        }
+    }
+
+    /// Apply a value reported by the device and return the matching publishes.
+    /// `vibration` readings are counted like [`Self::vibration`].
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Bool(value)) if p == &VIBRATION_NODE_VIBRATION_PROP_ID => {
+                self.vibration(*value)
+            }
+            (p, HomieValue::Integer(value)) if p == &VIBRATION_NODE_VIBRATION_STRENGTH_PROP_ID => {
+                self.state.vibration_strength = Some(*value);
+                vec![self.publisher.vibration_strength(*value)]
+            }
+            (p, HomieValue::Bool(value)) if p == &VIBRATION_NODE_DROP_DETECTED_PROP_ID => {
+                self.state.drop_detected = Some(*value);
+                self.publisher.drop_detected(*value).into_iter().collect()
+            }
+            (p, HomieValue::Bool(value)) if p == &VIBRATION_NODE_TILT_DETECTED_PROP_ID => {
+                self.state.tilt_detected = Some(*value);
+                self.publisher.tilt_detected(*value).into_iter().collect()
+            }
+            (p, HomieValue::Integer(value)) if p == &VIBRATION_NODE_VIBRATION_COUNT_PROP_ID => {
+                self.state.vibration_count = *value;
+                self.publisher.vibration_count(*value).into_iter().collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            Some(self.publisher.vibration(self.state.vibration)),
+            self.state
+                .vibration_strength
+                .map(|value| self.publisher.vibration_strength(value)),
+            self.state
+                .drop_detected
+                .and_then(|value| self.publisher.drop_detected(value)),
+            self.state
+                .tilt_detected
+                .and_then(|value| self.publisher.tilt_detected(value)),
+            self.publisher.vibration_count(self.state.vibration_count),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+pub enum VibrationNodeSetEvents {
+    /// Reset `vibration-count` to zero.
+    ResetCount,
+}
+
+smarthome_node_config! {
+    #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+    #[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+    #[serde(default)]
+    pub struct VibrationNodeConfig for VibrationNodePublisher {
+        pub vibration_strength: bool,
+        /// Unit of `vibration-strength`; vendor scales are usually unitless.
+        pub strength_unit: Option<String>,
+        #[cfg_attr(
+            feature = "proptest",
+            proptest(strategy = "crate::arbitrary::integer_range()")
+        )]
+        pub strength_range: IntegerRange,
+        /// Expose a `drop-detected` event (e.g. Aqara free-fall detection).
+        pub drop_detected: bool => VIBRATION_NODE_DROP_DETECTED_PROP_ID, drop_detected(bool), |_config| {
+            PropertyDescriptionBuilder::boolean()
+                .name("Drop detected")
+                .boolean_labels("no-drop", "drop")
+                .retained(true)
+                .settable(false)
+                .build()
+        },
+        /// Expose a `tilt-detected` event.
+        pub tilt_detected: bool => VIBRATION_NODE_TILT_DETECTED_PROP_ID, tilt_detected(bool), |_config| {
+            PropertyDescriptionBuilder::boolean()
+                .name("Tilt detected")
+                .boolean_labels("no-tilt", "tilt")
+                .retained(true)
+                .settable(false)
+                .build()
+        },
+        /// Expose a cumulative `vibration-count` and a `reset-count` action, e.g.
+        /// for glass-break or tamper statistics.
+        pub vibration_count: bool,
+        /// Properties to publish as read-only; sets to them are rejected.
+        #[cfg_attr(
+            feature = "proptest",
+            proptest(strategy = "crate::arbitrary::homie_ids()")
+        )]
+        pub readonly: Vec<HomieID>,
+    }
 }
 
 impl Default for VibrationNodeConfig {
     fn default() -> Self {
         Self {
             vibration_strength: true,
+            strength_unit: None,
+            strength_range: IntegerRange {
+                min: None,
+                max: None,
+                step: None,
+            },
+            drop_detected: false,
+            tilt_detected: false,
+            vibration_count: false,
+            readonly: Vec::new(),
         }
     }
 }
 
 pub struct VibrationNodeBuilder {
     node_builder: NodeDescriptionBuilder,
+    config: VibrationNodeConfig,
 }
 
 impl VibrationNodeBuilder {
@@ -46,33 +198,69 @@ impl VibrationNodeBuilder {
             config,
         )
         .r#type(SMARTHOME_CAP_VIBRATION);
+        let db = readonly_properties(db, &config.readonly);
 
-        Self { node_builder: db }
+        Self {
+            node_builder: db,
+            config: config.clone(),
+        }
     }
 
     fn build_node(
         db: NodeDescriptionBuilder,
         config: &VibrationNodeConfig,
     ) -> NodeDescriptionBuilder {
-        db.add_property(
-            VIBRATION_NODE_VIBRATION_PROP_ID,
-            PropertyDescriptionBuilder::boolean()
-                .name("Vibration detected")
-                .boolean_labels("no-vibration", "vibration")
-                .retained(true)
-                .settable(false)
-                .build(),
-        )
-        .add_property_cond(
-            VIBRATION_NODE_VIBRATION_STRENGTH_PROP_ID,
-            config.vibration_strength,
-            || {
-                PropertyDescriptionBuilder::integer()
-                    .name("Vibration strength")
+        config.add_optional_properties(
+            db.add_property(
+                VIBRATION_NODE_VIBRATION_PROP_ID,
+                PropertyDescriptionBuilder::boolean()
+                    .name("Vibration detected")
+                    .boolean_labels("no-vibration", "vibration")
                     .retained(true)
                     .settable(false)
-                    .build()
-            },
+                    .build(),
+            )
+            .add_property_cond(
+                VIBRATION_NODE_VIBRATION_STRENGTH_PROP_ID,
+                config.vibration_strength,
+                || {
+                    PropertyDescriptionBuilder::integer()
+                        .name("Vibration strength")
+                        .integer_range(config.strength_range.clone())
+                        .unit::<&str>(config.strength_unit.as_deref())
+                        .retained(true)
+                        .settable(false)
+                        .build()
+                },
+            )
+            .add_property_cond(
+                VIBRATION_NODE_VIBRATION_COUNT_PROP_ID,
+                config.vibration_count,
+                || {
+                    PropertyDescriptionBuilder::integer()
+                        .name("Vibration count")
+                        .integer_range(IntegerRange {
+                            min: Some(0),
+                            max: None,
+                            step: None,
+                        })
+                        .retained(true)
+                        .settable(false)
+                        .build()
+                },
+            )
+            .add_property_cond(
+                VIBRATION_NODE_RESET_COUNT_PROP_ID,
+                config.vibration_count,
+                || {
+                    PropertyDescriptionBuilder::enumeration(["reset"])
+                        .unwrap()
+                        .name("Reset vibration count")
+                        .retained(false)
+                        .settable(true)
+                        .build()
+                },
+            ),
         )
     }
 
@@ -81,10 +269,23 @@ impl VibrationNodeBuilder {
         self
     }
 
+    /// Append a subtype suffix to the node type (`vibration-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::Vibration.with_subtype(subtype));
+        self
+    }
+
     pub fn build(self) -> HomieNodeDescription {
         self.node_builder.build()
     }
 
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &VibrationNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
     pub fn build_with_publisher(
         self,
         node_id: HomieID,
@@ -99,6 +300,7 @@ impl VibrationNodeBuilder {
                     node_id,
                 ),
                 client.clone(),
+                self.config,
             ),
         )
     }
@@ -108,17 +310,30 @@ impl VibrationNodeBuilder {
 pub struct VibrationNodePublisher {
     client: Homie5DeviceProtocol,
     node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
+    config: VibrationNodeConfig,
     vibr_prop: HomieID,
     vibr_strength: HomieID,
+    drop_detected_prop: HomieID,
+    tilt_detected_prop: HomieID,
+    vibration_count_prop: HomieID,
+    reset_count_prop: HomieID,
 }
 
 impl VibrationNodePublisher {
-    pub fn new(node: NodeRef, client: Homie5DeviceProtocol) -> Self {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: VibrationNodeConfig) -> Self {
+        let metadata = property_metadata(&VibrationNodeBuilder::new(&config).build());
         Self {
             node,
+            metadata,
             client,
+            config,
             vibr_prop: VIBRATION_NODE_VIBRATION_PROP_ID,
             vibr_strength: VIBRATION_NODE_VIBRATION_STRENGTH_PROP_ID,
+            drop_detected_prop: VIBRATION_NODE_DROP_DETECTED_PROP_ID,
+            tilt_detected_prop: VIBRATION_NODE_TILT_DETECTED_PROP_ID,
+            vibration_count_prop: VIBRATION_NODE_VIBRATION_COUNT_PROP_ID,
+            reset_count_prop: VIBRATION_NODE_RESET_COUNT_PROP_ID,
         }
     }
 
@@ -139,4 +354,147 @@ impl VibrationNodePublisher {
             true,
         )
     }
+
+    pub fn vibration_count(&self, value: i64) -> Option<homie5::client::Publish> {
+        if !self.config.vibration_count {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.vibration_count_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+}
+
+impl SmarthomePublisher for VibrationNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::Vibration
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![
+            self.vibr_prop.clone(),
+            self.vibr_strength.clone(),
+            self.drop_detected_prop.clone(),
+            self.tilt_detected_prop.clone(),
+            self.vibration_count_prop.clone(),
+            self.reset_count_prop.clone(),
+        ]
+    }
+}
+
+impl SetCommandParser for VibrationNodePublisher {
+    type Event = VibrationNodeSetEvents;
+
+    fn parse_set(
+        &self,
+        property: &PropertyRef,
+        desc: &HomieDeviceDescription,
+        set_value: &str,
+    ) -> ParseOutcome<Self::Event> {
+        if let Some(outcome) = reject_readonly(&self.node, property, desc, set_value) {
+            return outcome;
+        }
+
+        let property_id = property.prop_id().to_string();
+
+        if property.match_with_node(&self.node, &self.reset_count_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Enum(_)) => ParseOutcome::Parsed(VibrationNodeSetEvents::ResetCount),
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::InvalidVariant,
+                )),
+            }
+        } else {
+            ParseOutcome::NoMatch
+        }
+    }
+
+    fn parse_set_event(
+        &self,
+        desc: &HomieDeviceDescription,
+        event: &Homie5Message,
+    ) -> ParseOutcome<Self::Event> {
+        match event {
+            Homie5Message::PropertySet {
+                property,
+                set_value,
+            } => self.parse_set(property, desc, set_value),
+            _ => ParseOutcome::Invalid(ParseError::new(
+                self.reset_count_prop.to_string(),
+                "",
+                ParseErrorKind::UnexpectedMessageType,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use homie5::HomieDomain;
+
+    use super::*;
+    use crate::StandaloneSetParser;
+
+    #[test]
+    fn vibration_count_increments_per_event_and_resets() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("dev"), HomieDomain::Default);
+        let config = VibrationNodeConfig {
+            vibration_count: true,
+            ..VibrationNodeConfig::default()
+        };
+        let parser: StandaloneSetParser<_> = VibrationNodeBuilder::new(&config)
+            .build_with_publisher(VIBRATION_NODE_DEFAULT_ID, &client)
+            .into();
+        let reset = PropertyRef::from_node(
+            parser.publisher().node_ref().clone(),
+            VIBRATION_NODE_RESET_COUNT_PROP_ID,
+        );
+        assert!(matches!(
+            parser.parse_set(&reset, "clear"),
+            ParseOutcome::Invalid(_)
+        ));
+        let ParseOutcome::Parsed(event) = parser.parse_set(&reset, "reset") else {
+            panic!("reset must parse");
+        };
+
+        let mut node = VibrationNode {
+            publisher: parser.into_publisher(),
+            state: VibrationNodeState::default(),
+        };
+        assert_eq!(node.vibration(true).len(), 2);
+        // still vibrating, not a new event
+        assert_eq!(node.vibration(true).len(), 1);
+        node.vibration(false);
+        node.vibration(true);
+        assert_eq!(node.state.vibration_count, 2);
+
+        let publishes = node.apply_set(event);
+        assert_eq!(publishes.len(), 1);
+        assert_eq!(publishes[0].payload, b"0".to_vec());
+        assert_eq!(node.state.vibration_count, 0);
+    }
 }
@@ -1,8 +1,8 @@
-use std::str::FromStr;
+use std::{collections::BTreeMap, str::FromStr};
 
 use homie5::{
-    HOMIE_UNIT_PERCENT, Homie5DeviceProtocol, Homie5Message, HomieID, HomieValue, NodeRef,
-    PropertyRef,
+    HOMIE_UNIT_PERCENT, Homie5DeviceProtocol, Homie5Message, Homie5ProtocolError, HomieID,
+    HomieValue, NodeRef, PropertyRef,
     device_description::{
         HomieDeviceDescription, HomieNodeDescription, IntegerRange, NodeDescriptionBuilder,
         PropertyDescriptionBuilder,
@@ -11,46 +11,157 @@ use homie5::{
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    ParseError, ParseErrorKind, ParseOutcome, SMARTHOME_CAP_VOLUME, SetCommandParser,
-    mediaplayer_node::ControlState,
+    ParseError, ParseErrorKind, ParseOutcome, PropertyMetadata, SMARTHOME_CAP_VOLUME,
+    SetCommandParser, SmarthomePublisher, SmarthomeType, mediaplayer_node::ControlState,
+    property_metadata, readonly_properties, reject_readonly,
 };
 
 pub const VOLUME_NODE_DEFAULT_ID: HomieID = HomieID::new_const("volume");
 pub const VOLUME_NODE_DEFAULT_NAME: &str = "Volume";
 pub const VOLUME_NODE_LEVEL_PROP_ID: HomieID = HomieID::new_const("level");
 pub const VOLUME_NODE_MUTE_PROP_ID: HomieID = HomieID::new_const("mute");
+pub const VOLUME_NODE_ACTION_PROP_ID: HomieID = HomieID::new_const("action");
 
 const CONTROL_STATE_FORMAT: [&str; 3] = ["on", "off", "disabled"];
 
 // ── Node (state) ────────────────────────────────────────────────────────────
 
-#[derive(Debug)]
-pub struct VolumeNode {
-    pub publisher: VolumeNodePublisher,
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VolumeNodeState {
     pub level: i64,
     pub level_target: i64,
     pub mute: Option<ControlState>,
 }
 
+#[derive(Debug)]
+pub struct VolumeNode {
+    pub publisher: VolumeNodePublisher,
+    pub state: VolumeNodeState,
+}
+
+impl VolumeNode {
+    /// Apply a parsed set command and return the `$target` and value publishes.
+    /// Step actions are relative commands for the device and leave the state
+    /// unchanged.
+    pub fn apply_set(&mut self, event: VolumeNodeSetEvents) -> Vec<homie5::client::Publish> {
+        match event {
+            VolumeNodeSetEvents::Level(value) => {
+                self.state.level_target = value;
+                self.state.level = value;
+                vec![
+                    self.publisher.level_target(value),
+                    self.publisher.level(value),
+                ]
+            }
+            VolumeNodeSetEvents::Mute(value) => {
+                self.state.mute = Some(value);
+                vec![self.publisher.mute(value)]
+            }
+            VolumeNodeSetEvents::Action(_) => Vec::new(),
+        }
+    }
+
+    /// Apply a value reported by the device and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Integer(value)) if p == &VOLUME_NODE_LEVEL_PROP_ID => {
+                self.state.level = *value;
+                vec![self.publisher.level(*value)]
+            }
+            (p, HomieValue::Enum(value)) if p == &VOLUME_NODE_MUTE_PROP_ID => {
+                let Ok(mute) = value.parse::<ControlState>() else {
+                    return Vec::new();
+                };
+                self.state.mute = Some(mute);
+                vec![self.publisher.mute(mute)]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            Some(self.publisher.level(self.state.level)),
+            Some(self.publisher.level_target(self.state.level_target)),
+            self.state.mute.map(|value| self.publisher.mute(value)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+// ── Actions ─────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+pub enum VolumeNodeActions {
+    StepUp,
+    StepDown,
+}
+
+impl VolumeNodeActions {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VolumeNodeActions::StepUp => "step-up",
+            VolumeNodeActions::StepDown => "step-down",
+        }
+    }
+}
+
+impl FromStr for VolumeNodeActions {
+    type Err = Homie5ProtocolError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "step-up" => Ok(VolumeNodeActions::StepUp),
+            "step-down" => Ok(VolumeNodeActions::StepDown),
+            _ => Err(Homie5ProtocolError::InvalidPayload),
+        }
+    }
+}
+
 // ── Set events ──────────────────────────────────────────────────────────────
 
 #[derive(Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 pub enum VolumeNodeSetEvents {
     Level(i64),
     Mute(ControlState),
+    Action(VolumeNodeActions),
 }
 
 // ── Config ──────────────────────────────────────────────────────────────────
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 #[serde(default)]
 pub struct VolumeNodeConfig {
     pub mute: bool,
+    /// Expose `step-up`/`step-down` actions for devices that only support
+    /// relative volume changes (IR-controlled amps, TVs).
+    pub step_action: bool,
+    /// Properties to publish as read-only; sets to them are rejected.
+    #[cfg_attr(
+        feature = "proptest",
+        proptest(strategy = "crate::arbitrary::homie_ids()")
+    )]
+    pub readonly: Vec<HomieID>,
 }
 
 impl Default for VolumeNodeConfig {
     fn default() -> Self {
-        Self { mute: true }
+        Self {
+            mute: true,
+            step_action: false,
+            readonly: Vec::new(),
+        }
     }
 }
 
@@ -58,6 +169,7 @@ impl Default for VolumeNodeConfig {
 
 pub struct VolumeNodeBuilder {
     node_builder: NodeDescriptionBuilder,
+    config: VolumeNodeConfig,
 }
 
 impl VolumeNodeBuilder {
@@ -67,8 +179,12 @@ impl VolumeNodeBuilder {
             config,
         )
         .r#type(SMARTHOME_CAP_VOLUME);
+        let db = readonly_properties(db, &config.readonly);
 
-        Self { node_builder: db }
+        Self {
+            node_builder: db,
+            config: config.clone(),
+        }
     }
 
     fn build_node(db: NodeDescriptionBuilder, config: &VolumeNodeConfig) -> NodeDescriptionBuilder {
@@ -94,6 +210,14 @@ impl VolumeNodeBuilder {
                 .retained(true)
                 .build()
         })
+        .add_property_cond(VOLUME_NODE_ACTION_PROP_ID, config.step_action, || {
+            PropertyDescriptionBuilder::enumeration(["step-up", "step-down"])
+                .unwrap()
+                .name("Step volume")
+                .settable(true)
+                .retained(false)
+                .build()
+        })
     }
 
     pub fn name<S: Into<String>>(mut self, name: impl Into<Option<S>>) -> Self {
@@ -101,10 +225,23 @@ impl VolumeNodeBuilder {
         self
     }
 
+    /// Append a subtype suffix to the node type (`volume-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::Volume.with_subtype(subtype));
+        self
+    }
+
     pub fn build(self) -> HomieNodeDescription {
         self.node_builder.build()
     }
 
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &VolumeNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
     pub fn build_with_publisher(
         self,
         node_id: HomieID,
@@ -119,6 +256,7 @@ impl VolumeNodeBuilder {
                     node_id,
                 ),
                 client.clone(),
+                &self.config,
             ),
         )
     }
@@ -130,17 +268,22 @@ impl VolumeNodeBuilder {
 pub struct VolumeNodePublisher {
     client: Homie5DeviceProtocol,
     node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
     level_prop: HomieID,
     mute_prop: HomieID,
+    action_prop: HomieID,
 }
 
 impl VolumeNodePublisher {
-    pub fn new(node: NodeRef, client: Homie5DeviceProtocol) -> Self {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: &VolumeNodeConfig) -> Self {
+        let metadata = property_metadata(&VolumeNodeBuilder::new(config).build());
         Self {
             node,
+            metadata,
             client,
             level_prop: VOLUME_NODE_LEVEL_PROP_ID,
             mute_prop: VOLUME_NODE_MUTE_PROP_ID,
+            action_prop: VOLUME_NODE_ACTION_PROP_ID,
         }
     }
 
@@ -166,6 +309,42 @@ impl VolumeNodePublisher {
         self.client
             .publish_value(self.node.node_id(), &self.mute_prop, value.as_str(), true)
     }
+
+    pub fn mute_target(&self, value: ControlState) -> homie5::client::Publish {
+        self.client
+            .publish_target(self.node.node_id(), &self.mute_prop, value.as_str(), true)
+    }
+
+    pub fn action(&self, action: VolumeNodeActions) -> homie5::client::Publish {
+        self.client.publish_value(
+            self.node.node_id(),
+            &self.action_prop,
+            action.as_str(),
+            false,
+        )
+    }
+}
+
+impl SmarthomePublisher for VolumeNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::Volume
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![
+            self.level_prop.clone(),
+            self.mute_prop.clone(),
+            self.action_prop.clone(),
+        ]
+    }
 }
 
 impl SetCommandParser for VolumeNodePublisher {
@@ -177,6 +356,10 @@ impl SetCommandParser for VolumeNodePublisher {
         desc: &HomieDeviceDescription,
         set_value: &str,
     ) -> ParseOutcome<Self::Event> {
+        if let Some(outcome) = reject_readonly(&self.node, property, desc, set_value) {
+            return outcome;
+        }
+
         let property_id = property.prop_id().to_string();
 
         if property.match_with_node(&self.node, &self.level_prop) {
@@ -226,6 +409,32 @@ impl SetCommandParser for VolumeNodePublisher {
                     ParseErrorKind::InvalidHomieValue,
                 )),
             }
+        } else if property.match_with_node(&self.node, &self.action_prop) {
+            let Some(parsed) = desc.with_property(property, |prop_desc| {
+                HomieValue::parse(set_value, prop_desc)
+            }) else {
+                return ParseOutcome::Invalid(ParseError::new(
+                    property_id,
+                    set_value,
+                    ParseErrorKind::MissingPropertyDescription,
+                ));
+            };
+
+            match parsed {
+                Ok(HomieValue::Enum(value)) => match VolumeNodeActions::from_str(&value) {
+                    Ok(action) => ParseOutcome::Parsed(VolumeNodeSetEvents::Action(action)),
+                    Err(_) => ParseOutcome::Invalid(ParseError::new(
+                        property.prop_id().to_string(),
+                        set_value,
+                        ParseErrorKind::InvalidVariant,
+                    )),
+                },
+                _ => ParseOutcome::Invalid(ParseError::new(
+                    property.prop_id().to_string(),
+                    set_value,
+                    ParseErrorKind::InvalidHomieValue,
+                )),
+            }
         } else {
             ParseOutcome::NoMatch
         }
@@ -1,42 +1,169 @@
+use core::fmt;
+
+use chrono::prelude::*;
+use std::{collections::BTreeMap, str::FromStr};
+
 use homie5::{
-    Homie5DeviceProtocol, HomieID, NodeRef,
+    Homie5DeviceProtocol, Homie5ProtocolError, HomieID, HomieValue, NodeRef,
     device_description::{
         HomieNodeDescription, NodeDescriptionBuilder, PropertyDescriptionBuilder,
     },
 };
+use serde::{Deserialize, Serialize};
 
-use crate::SMARTHOME_CAP_WATER_SENSOR;
+use crate::{
+    PropertyMetadata, SMARTHOME_CAP_WATER_SENSOR, SmarthomePublisher, SmarthomeType,
+    property_metadata,
+};
 
 pub const WATER_SENSOR_NODE_DEFAULT_ID: HomieID = HomieID::new_const("water");
 pub const WATER_SENSOR_NODE_DEFAULT_NAME: &str = "Water sensor";
 pub const WATER_SENSOR_NODE_DETECTED_PROP_ID: HomieID = HomieID::new_const("detected");
+pub const WATER_SENSOR_NODE_ALARM_LEVEL_PROP_ID: HomieID = HomieID::new_const("alarm-level");
+pub const WATER_SENSOR_NODE_LAST_CHECKED_PROP_ID: HomieID = HomieID::new_const("last-checked");
+
+// ── Alarm level ─────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WaterSensorAlarmLevel {
+    Dry,
+    Leak,
+    Flood,
+}
+
+impl WaterSensorAlarmLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Dry => "dry",
+            Self::Leak => "leak",
+            Self::Flood => "flood",
+        }
+    }
+
+    pub const ALL: [WaterSensorAlarmLevel; 3] = [
+        WaterSensorAlarmLevel::Dry,
+        WaterSensorAlarmLevel::Leak,
+        WaterSensorAlarmLevel::Flood,
+    ];
+}
+
+impl fmt::Display for WaterSensorAlarmLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for WaterSensorAlarmLevel {
+    type Err = Homie5ProtocolError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dry" => Ok(Self::Dry),
+            "leak" => Ok(Self::Leak),
+            "flood" => Ok(Self::Flood),
+            _ => Err(Homie5ProtocolError::InvalidPayload),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WaterSensorNodeState {
+    pub detected: bool,
+    pub alarm_level: Option<WaterSensorAlarmLevel>,
+    pub last_checked: Option<DateTime<Utc>>,
+}
 
 #[derive(Debug)]
 pub struct WaterSensorNode {
     pub publisher: WaterSensorNodePublisher,
-    pub detected: bool,
+    pub state: WaterSensorNodeState,
+}
+
+impl WaterSensorNode {
+    /// Apply a value reported by the device and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Bool(value)) if p == &WATER_SENSOR_NODE_DETECTED_PROP_ID => {
+                self.state.detected = *value;
+                vec![self.publisher.detected(*value)]
+            }
+            (p, HomieValue::Enum(value)) if p == &WATER_SENSOR_NODE_ALARM_LEVEL_PROP_ID => {
+                let Ok(level) = value.parse::<WaterSensorAlarmLevel>() else {
+                    return Vec::new();
+                };
+                self.state.alarm_level = Some(level);
+                self.publisher.alarm_level(level).into_iter().collect()
+            }
+            (p, HomieValue::DateTime(value)) if p == &WATER_SENSOR_NODE_LAST_CHECKED_PROP_ID => {
+                self.state.last_checked = Some(*value);
+                self.publisher.last_checked(*value).into_iter().collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            Some(self.publisher.detected(self.state.detected)),
+            self.state
+                .alarm_level
+                .and_then(|value| self.publisher.alarm_level(value)),
+            self.state
+                .last_checked
+                .and_then(|value| self.publisher.last_checked(value)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+#[serde(default)]
+pub struct WaterSensorNodeConfig {
+    /// Expose a graded `alarm-level` (dry/leak/flood) next to the binary detection.
+    pub alarm_level: bool,
+    /// Expose the time the probe last reported, for battery probes with a heartbeat.
+    pub last_checked: bool,
 }
 
 pub struct WaterSensorNodeBuilder {
     node_builder: NodeDescriptionBuilder,
+    config: WaterSensorNodeConfig,
 }
 
 impl Default for WaterSensorNodeBuilder {
     fn default() -> Self {
-        let db =
-            Self::build_node(NodeDescriptionBuilder::new().name(WATER_SENSOR_NODE_DEFAULT_NAME))
-                .r#type(SMARTHOME_CAP_WATER_SENSOR);
-
-        Self { node_builder: db }
+        Self::new(&WaterSensorNodeConfig::default())
     }
 }
 
 impl WaterSensorNodeBuilder {
-    pub fn new() -> Self {
-        Default::default()
+    pub fn new(config: &WaterSensorNodeConfig) -> Self {
+        let db = Self::build_node(
+            NodeDescriptionBuilder::new().name(WATER_SENSOR_NODE_DEFAULT_NAME),
+            config,
+        )
+        .r#type(SMARTHOME_CAP_WATER_SENSOR);
+
+        Self {
+            node_builder: db,
+            config: config.clone(),
+        }
     }
 
-    fn build_node(db: NodeDescriptionBuilder) -> NodeDescriptionBuilder {
+    fn build_node(
+        db: NodeDescriptionBuilder,
+        config: &WaterSensorNodeConfig,
+    ) -> NodeDescriptionBuilder {
         db.add_property(
             WATER_SENSOR_NODE_DETECTED_PROP_ID,
             PropertyDescriptionBuilder::boolean()
@@ -46,6 +173,31 @@ impl WaterSensorNodeBuilder {
                 .retained(true)
                 .build(),
         )
+        .add_property_cond(
+            WATER_SENSOR_NODE_ALARM_LEVEL_PROP_ID,
+            config.alarm_level,
+            || {
+                PropertyDescriptionBuilder::enumeration(
+                    WaterSensorAlarmLevel::ALL.iter().map(|l| l.as_str()),
+                )
+                .unwrap()
+                .name("Alarm level")
+                .settable(false)
+                .retained(true)
+                .build()
+            },
+        )
+        .add_property_cond(
+            WATER_SENSOR_NODE_LAST_CHECKED_PROP_ID,
+            config.last_checked,
+            || {
+                PropertyDescriptionBuilder::datetime()
+                    .name("Last checked")
+                    .settable(false)
+                    .retained(true)
+                    .build()
+            },
+        )
     }
 
     pub fn name<S: Into<String>>(mut self, name: impl Into<Option<S>>) -> Self {
@@ -53,10 +205,23 @@ impl WaterSensorNodeBuilder {
         self
     }
 
+    /// Append a subtype suffix to the node type (`water-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::WaterSensor.with_subtype(subtype));
+        self
+    }
+
     pub fn build(self) -> HomieNodeDescription {
         self.node_builder.build()
     }
 
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &WaterSensorNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
     pub fn build_with_publisher(
         self,
         node_id: HomieID,
@@ -71,6 +236,7 @@ impl WaterSensorNodeBuilder {
                     node_id,
                 ),
                 client.clone(),
+                self.config,
             ),
         )
     }
@@ -80,15 +246,24 @@ impl WaterSensorNodeBuilder {
 pub struct WaterSensorNodePublisher {
     client: Homie5DeviceProtocol,
     node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
+    config: WaterSensorNodeConfig,
     detected_prop: HomieID,
+    alarm_level_prop: HomieID,
+    last_checked_prop: HomieID,
 }
 
 impl WaterSensorNodePublisher {
-    pub fn new(node: NodeRef, client: Homie5DeviceProtocol) -> Self {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: WaterSensorNodeConfig) -> Self {
+        let metadata = property_metadata(&WaterSensorNodeBuilder::new(&config).build());
         Self {
             node,
+            metadata,
             client,
+            config,
             detected_prop: WATER_SENSOR_NODE_DETECTED_PROP_ID,
+            alarm_level_prop: WATER_SENSOR_NODE_ALARM_LEVEL_PROP_ID,
+            last_checked_prop: WATER_SENSOR_NODE_LAST_CHECKED_PROP_ID,
         }
     }
 
@@ -100,4 +275,50 @@ impl WaterSensorNodePublisher {
             true,
         )
     }
+
+    pub fn alarm_level(&self, value: WaterSensorAlarmLevel) -> Option<homie5::client::Publish> {
+        if !self.config.alarm_level {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.alarm_level_prop,
+            value.as_str(),
+            true,
+        ))
+    }
+
+    pub fn last_checked(&self, value: DateTime<Utc>) -> Option<homie5::client::Publish> {
+        if !self.config.last_checked {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.last_checked_prop,
+            value.to_rfc3339_opts(SecondsFormat::Millis, true),
+            true,
+        ))
+    }
+}
+
+impl SmarthomePublisher for WaterSensorNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::WaterSensor
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![
+            self.detected_prop.clone(),
+            self.alarm_level_prop.clone(),
+            self.last_checked_prop.clone(),
+        ]
+    }
 }
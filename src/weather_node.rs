@@ -0,0 +1,388 @@
+use core::fmt;
+
+use std::{collections::BTreeMap, str::FromStr};
+
+use homie5::{
+    Homie5DeviceProtocol, Homie5ProtocolError, HomieID, HomieValue, NodeRef,
+    device_description::{
+        FloatRange, HomieNodeDescription, NodeDescriptionBuilder, PropertyDescriptionBuilder,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Decimal, PropertyMetadata, SMARTHOME_CAP_WEATHER, SmarthomePublisher, SmarthomeType,
+    property_metadata,
+};
+
+pub const WEATHER_NODE_DEFAULT_ID: HomieID = HomieID::new_const("weather");
+pub const WEATHER_NODE_DEFAULT_NAME: &str = "Weather";
+pub const WEATHER_NODE_RAINING_PROP_ID: HomieID = HomieID::new_const("raining");
+pub const WEATHER_NODE_RAIN_AMOUNT_PROP_ID: HomieID = HomieID::new_const("rain-amount");
+pub const WEATHER_NODE_PRESSURE_TREND_PROP_ID: HomieID = HomieID::new_const("pressure-trend");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PressureTrend {
+    Rising,
+    Steady,
+    Falling,
+}
+
+impl PressureTrend {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Rising => "rising",
+            Self::Steady => "steady",
+            Self::Falling => "falling",
+        }
+    }
+
+    pub const ALL: [PressureTrend; 3] = [
+        PressureTrend::Rising,
+        PressureTrend::Steady,
+        PressureTrend::Falling,
+    ];
+
+    /// Trend of a pressure history, oldest sample first. The pressure is
+    /// `steady` unless the newest sample differs from the oldest by more than
+    /// `threshold`. Returns `None` for fewer than two samples.
+    pub fn from_history(history: &[f64], threshold: f64) -> Option<Self> {
+        let [first, .., last] = history else {
+            return None;
+        };
+        let change = last - first;
+        Some(if change > threshold {
+            Self::Rising
+        } else if change < -threshold {
+            Self::Falling
+        } else {
+            Self::Steady
+        })
+    }
+}
+
+impl fmt::Display for PressureTrend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for PressureTrend {
+    type Err = Homie5ProtocolError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "rising" => Ok(Self::Rising),
+            "steady" => Ok(Self::Steady),
+            "falling" => Ok(Self::Falling),
+            _ => Err(Homie5ProtocolError::InvalidPayload),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WeatherNodeState {
+    pub raining: Option<bool>,
+    pub rain_amount: Option<f64>,
+    pub pressure_trend: Option<PressureTrend>,
+}
+
+#[derive(Debug)]
+pub struct WeatherNode {
+    pub publisher: WeatherNodePublisher,
+    pub state: WeatherNodeState,
+}
+
+impl WeatherNode {
+    /// Apply a value reported by the device and return the matching publish.
+    pub fn apply_value(
+        &mut self,
+        property: &HomieID,
+        value: &HomieValue,
+    ) -> Vec<homie5::client::Publish> {
+        match (property, value) {
+            (p, HomieValue::Bool(value)) if p == &WEATHER_NODE_RAINING_PROP_ID => {
+                self.state.raining = Some(*value);
+                self.publisher.raining(*value).into_iter().collect()
+            }
+            (p, HomieValue::Float(value)) if p == &WEATHER_NODE_RAIN_AMOUNT_PROP_ID => {
+                self.state.rain_amount = Some(*value);
+                self.publisher.rain_amount(*value).into_iter().collect()
+            }
+            (p, HomieValue::Enum(value)) if p == &WEATHER_NODE_PRESSURE_TREND_PROP_ID => {
+                let Ok(trend) = value.parse::<PressureTrend>() else {
+                    return Vec::new();
+                };
+                self.state.pressure_trend = Some(trend);
+                self.publisher.pressure_trend(trend).into_iter().collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publishes for the complete current state, e.g. to republish a node
+    /// after reconnecting.
+    pub fn publishes(&self) -> Vec<homie5::client::Publish> {
+        [
+            self.state
+                .raining
+                .and_then(|value| self.publisher.raining(value)),
+            self.state
+                .rain_amount
+                .and_then(|value| self.publisher.rain_amount(value)),
+            self.state
+                .pressure_trend
+                .and_then(|value| self.publisher.pressure_trend(value)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+#[serde(default)]
+pub struct WeatherNodeConfig {
+    pub raining: bool,
+    pub rain_amount: bool,
+    pub pressure_trend: bool,
+    /// Pressure change (in kPa) across the history buffer above which the
+    /// trend is `rising` or `falling` rather than `steady`.
+    pub pressure_trend_threshold: f64,
+    /// Decimal places of the published rain amount; `None` publishes the
+    /// value as reported.
+    #[cfg_attr(
+        feature = "proptest",
+        proptest(strategy = "crate::arbitrary::precision()")
+    )]
+    pub precision: Option<usize>,
+}
+
+impl Default for WeatherNodeConfig {
+    fn default() -> Self {
+        Self {
+            raining: true,
+            rain_amount: false,
+            pressure_trend: false,
+            pressure_trend_threshold: 0.1,
+            precision: None,
+        }
+    }
+}
+
+pub struct WeatherNodeBuilder {
+    config: WeatherNodeConfig,
+    node_builder: NodeDescriptionBuilder,
+}
+
+impl WeatherNodeBuilder {
+    pub fn new(config: &WeatherNodeConfig) -> Self {
+        let db = Self::build_node(
+            NodeDescriptionBuilder::new().name(WEATHER_NODE_DEFAULT_NAME),
+            config,
+        )
+        .r#type(SMARTHOME_CAP_WEATHER);
+
+        Self {
+            node_builder: db,
+            config: config.clone(),
+        }
+    }
+
+    fn build_node(
+        db: NodeDescriptionBuilder,
+        config: &WeatherNodeConfig,
+    ) -> NodeDescriptionBuilder {
+        db.add_property_cond(WEATHER_NODE_RAINING_PROP_ID, config.raining, || {
+            PropertyDescriptionBuilder::boolean()
+                .name("Raining")
+                .boolean_labels("dry", "raining")
+                .settable(false)
+                .retained(true)
+                .build()
+        })
+        .add_property_cond(WEATHER_NODE_RAIN_AMOUNT_PROP_ID, config.rain_amount, || {
+            PropertyDescriptionBuilder::float()
+                .name("Rain amount")
+                .unit("mm")
+                .float_range(FloatRange {
+                    min: Some(0.0),
+                    max: None,
+                    step: None,
+                })
+                .settable(false)
+                .retained(true)
+                .build()
+        })
+        .add_property_cond(
+            WEATHER_NODE_PRESSURE_TREND_PROP_ID,
+            config.pressure_trend,
+            || {
+                PropertyDescriptionBuilder::enumeration(
+                    PressureTrend::ALL.iter().map(|t| t.as_str()),
+                )
+                .unwrap()
+                .name("Pressure trend")
+                .settable(false)
+                .retained(true)
+                .build()
+            },
+        )
+    }
+
+    pub fn name<S: Into<String>>(mut self, name: impl Into<Option<S>>) -> Self {
+        self.node_builder = self.node_builder.name(name);
+        self
+    }
+
+    /// Append a subtype suffix to the node type (`weather-<subtype>`).
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.node_builder = self
+            .node_builder
+            .r#type(SmarthomeType::Weather.with_subtype(subtype));
+        self
+    }
+
+    pub fn build(self) -> HomieNodeDescription {
+        self.node_builder.build()
+    }
+
+    /// Ids of the properties a node built from `config` contains.
+    pub fn property_ids(config: &WeatherNodeConfig) -> Vec<HomieID> {
+        Self::new(config).build().properties.into_keys().collect()
+    }
+
+    pub fn build_with_publisher(
+        self,
+        node_id: HomieID,
+        client: &Homie5DeviceProtocol,
+    ) -> (HomieNodeDescription, WeatherNodePublisher) {
+        (
+            self.node_builder.build(),
+            WeatherNodePublisher::new(
+                NodeRef::new(
+                    client.homie_domain().to_owned(),
+                    client.id().to_owned(),
+                    node_id,
+                ),
+                client.clone(),
+                self.config,
+            ),
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct WeatherNodePublisher {
+    client: Homie5DeviceProtocol,
+    config: WeatherNodeConfig,
+    node: NodeRef,
+    metadata: BTreeMap<HomieID, PropertyMetadata>,
+    raining_prop: HomieID,
+    rain_amount_prop: HomieID,
+    pressure_trend_prop: HomieID,
+}
+
+impl WeatherNodePublisher {
+    pub fn new(node: NodeRef, client: Homie5DeviceProtocol, config: WeatherNodeConfig) -> Self {
+        let metadata = property_metadata(&WeatherNodeBuilder::new(&config).build());
+        Self {
+            node,
+            metadata,
+            client,
+            config,
+            raining_prop: WEATHER_NODE_RAINING_PROP_ID,
+            rain_amount_prop: WEATHER_NODE_RAIN_AMOUNT_PROP_ID,
+            pressure_trend_prop: WEATHER_NODE_PRESSURE_TREND_PROP_ID,
+        }
+    }
+
+    pub fn raining(&self, value: bool) -> Option<homie5::client::Publish> {
+        if !self.config.raining {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.raining_prop,
+            value.to_string(),
+            true,
+        ))
+    }
+
+    pub fn rain_amount(&self, value: f64) -> Option<homie5::client::Publish> {
+        if !self.config.rain_amount {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.rain_amount_prop,
+            Decimal::new(value, self.config.precision).to_string(),
+            true,
+        ))
+    }
+
+    pub fn pressure_trend(&self, value: PressureTrend) -> Option<homie5::client::Publish> {
+        if !self.config.pressure_trend {
+            return None;
+        }
+        Some(self.client.publish_value(
+            self.node.node_id(),
+            &self.pressure_trend_prop,
+            value.as_str(),
+            true,
+        ))
+    }
+
+    /// Publish the trend of a short pressure history (in kPa, oldest sample
+    /// first), see [`PressureTrend::from_history`].
+    pub fn pressure_trend_from(&self, history: &[f64]) -> Option<homie5::client::Publish> {
+        let trend = PressureTrend::from_history(history, self.config.pressure_trend_threshold)?;
+        self.pressure_trend(trend)
+    }
+}
+
+impl SmarthomePublisher for WeatherNodePublisher {
+    fn smarthome_type(&self) -> SmarthomeType {
+        SmarthomeType::Weather
+    }
+
+    fn node_ref(&self) -> &NodeRef {
+        &self.node
+    }
+
+    fn property_metadata(&self) -> &BTreeMap<HomieID, PropertyMetadata> {
+        &self.metadata
+    }
+
+    fn property_ids(&self) -> Vec<HomieID> {
+        vec![
+            self.raining_prop.clone(),
+            self.rain_amount_prop.clone(),
+            self.pressure_trend_prop.clone(),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pressure_trend_follows_the_history() {
+        assert_eq!(PressureTrend::from_history(&[101.3], 0.1), None);
+        assert_eq!(
+            PressureTrend::from_history(&[101.3, 101.2, 101.35], 0.1),
+            Some(PressureTrend::Steady)
+        );
+        assert_eq!(
+            PressureTrend::from_history(&[101.3, 101.5, 101.6], 0.1),
+            Some(PressureTrend::Rising)
+        );
+        assert_eq!(
+            PressureTrend::from_history(&[101.3, 101.0], 0.1),
+            Some(PressureTrend::Falling)
+        );
+    }
+}
@@ -0,0 +1,177 @@
+//! Composite builder for an outdoor weather station: a `weather` node for the
+//! rain sensor and pressure trend plus `numeric` nodes for wind speed and UV
+//! index, assembled into one device description from a single config.
+
+use homie5::{
+    Homie5DeviceProtocol, HomieID,
+    device_description::{DeviceDescriptionBuilder, HomieDeviceDescription},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    DEVICE_CLASS_WEATHER_STATION,
+    numeric_sensor_node::{
+        NumericSensorNodeBuilder, NumericSensorNodeConfig, NumericSensorNodePublisher,
+        NumericSensorType,
+    },
+    weather_node::{
+        WEATHER_NODE_DEFAULT_ID, WeatherNodeBuilder, WeatherNodeConfig, WeatherNodePublisher,
+    },
+};
+
+pub const WEATHER_STATION_DEFAULT_NAME: &str = "Weather station";
+pub const WEATHER_STATION_WEATHER_NODE_ID: HomieID = WEATHER_NODE_DEFAULT_ID;
+pub const WEATHER_STATION_WIND_NODE_ID: HomieID = HomieID::new_const("wind");
+pub const WEATHER_STATION_UV_NODE_ID: HomieID = HomieID::new_const("uv");
+
+// ── Config ──────────────────────────────────────────────────────────────────
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+#[serde(default)]
+pub struct WeatherStationConfig {
+    /// Rain sensor, rain gauge and pressure trend.
+    pub weather: WeatherNodeConfig,
+    /// Anemometer; `None` for stations without one.
+    pub wind: Option<NumericSensorNodeConfig>,
+    /// UV sensor; `None` for stations without one.
+    pub uv: Option<NumericSensorNodeConfig>,
+}
+
+impl Default for WeatherStationConfig {
+    fn default() -> Self {
+        Self {
+            weather: WeatherNodeConfig {
+                rain_amount: true,
+                ..WeatherNodeConfig::default()
+            },
+            wind: Some(NumericSensorNodeConfig {
+                sensor_type: NumericSensorType::WindSpeed,
+                ..NumericSensorNodeConfig::default()
+            }),
+            uv: Some(NumericSensorNodeConfig {
+                sensor_type: NumericSensorType::UvIndex,
+                ..NumericSensorNodeConfig::default()
+            }),
+        }
+    }
+}
+
+// ── Builder ─────────────────────────────────────────────────────────────────
+
+pub struct WeatherStationBuilder {
+    name: String,
+    config: WeatherStationConfig,
+}
+
+impl Default for WeatherStationBuilder {
+    fn default() -> Self {
+        Self::new(&WeatherStationConfig::default())
+    }
+}
+
+impl WeatherStationBuilder {
+    pub fn new(config: &WeatherStationConfig) -> Self {
+        Self {
+            name: WEATHER_STATION_DEFAULT_NAME.to_owned(),
+            config: config.clone(),
+        }
+    }
+
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    pub fn build(self) -> HomieDeviceDescription {
+        self.device_builder().build()
+    }
+
+    fn device_builder(&self) -> DeviceDescriptionBuilder {
+        let db = DeviceDescriptionBuilder::new()
+            .name(self.name.as_str())
+            .r#type(DEVICE_CLASS_WEATHER_STATION)
+            .add_node(
+                WEATHER_STATION_WEATHER_NODE_ID,
+                WeatherNodeBuilder::new(&self.config.weather).build(),
+            );
+        let db = match &self.config.wind {
+            Some(config) => db.add_node(
+                WEATHER_STATION_WIND_NODE_ID,
+                NumericSensorNodeBuilder::new(config).name("Wind").build(),
+            ),
+            None => db,
+        };
+        match &self.config.uv {
+            Some(config) => db.add_node(
+                WEATHER_STATION_UV_NODE_ID,
+                NumericSensorNodeBuilder::new(config).name("UV").build(),
+            ),
+            None => db,
+        }
+    }
+
+    /// Build the device description together with the publishers of all of
+    /// its nodes.
+    pub fn build_with_publishers(
+        self,
+        client: &Homie5DeviceProtocol,
+    ) -> (HomieDeviceDescription, WeatherStationPublishers) {
+        let (_, weather) = WeatherNodeBuilder::new(&self.config.weather)
+            .build_with_publisher(WEATHER_STATION_WEATHER_NODE_ID, client);
+        let wind = self.config.wind.as_ref().map(|config| {
+            NumericSensorNodeBuilder::new(config)
+                .build_with_publisher(WEATHER_STATION_WIND_NODE_ID, client)
+                .1
+        });
+        let uv = self.config.uv.as_ref().map(|config| {
+            NumericSensorNodeBuilder::new(config)
+                .build_with_publisher(WEATHER_STATION_UV_NODE_ID, client)
+                .1
+        });
+        (self.build(), WeatherStationPublishers { weather, wind, uv })
+    }
+}
+
+// ── Publishers ──────────────────────────────────────────────────────────────
+
+/// Publishers of the nodes of a weather station device.
+#[derive(Debug)]
+pub struct WeatherStationPublishers {
+    pub weather: WeatherNodePublisher,
+    pub wind: Option<NumericSensorNodePublisher>,
+    pub uv: Option<NumericSensorNodePublisher>,
+}
+
+#[cfg(test)]
+mod tests {
+    use homie5::HomieDomain;
+
+    use super::*;
+    use crate::SmarthomePublisher;
+
+    #[test]
+    fn station_contains_the_configured_nodes() {
+        let (client, _) =
+            Homie5DeviceProtocol::new(HomieID::new_const("station"), HomieDomain::Default);
+        let (desc, publishers) = WeatherStationBuilder::default()
+            .name("Garden station")
+            .build_with_publishers(&client);
+
+        assert_eq!(desc.name.as_deref(), Some("Garden station"));
+        assert_eq!(desc.r#type.as_deref(), Some(DEVICE_CLASS_WEATHER_STATION));
+        assert_eq!(desc.nodes.len(), 3);
+        assert_eq!(
+            publishers.wind.unwrap().node_ref().node_id(),
+            &WEATHER_STATION_WIND_NODE_ID
+        );
+
+        let config = WeatherStationConfig {
+            uv: None,
+            ..WeatherStationConfig::default()
+        };
+        let (desc, publishers) = WeatherStationBuilder::new(&config).build_with_publishers(&client);
+        assert!(!desc.nodes.contains_key(&WEATHER_STATION_UV_NODE_ID));
+        assert!(publishers.uv.is_none());
+    }
+}